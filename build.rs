@@ -0,0 +1,25 @@
+// build.rs
+//! Regenerates the C header `include/fast_sde.h` from `src/ffi.rs` when
+//! the `ffi` feature is enabled.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "ffi")]
+    generate_ffi_header();
+}
+
+#[cfg(feature = "ffi")]
+fn generate_ffi_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("set by cargo");
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+        .expect("cbindgen.toml is valid");
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(format!("{}/src/ffi.rs", crate_dir))
+        .generate()
+        .expect("src/ffi.rs is parseable by cbindgen")
+        .write_to_file(format!("{}/include/fast_sde.h", crate_dir));
+}
@@ -8,8 +8,8 @@ use fast_sde::mc::mc_engine::{
 };
 use fast_sde::mc::payoffs::Payoff;
 use fast_sde::output;
+use fast_sde::output::streaming::{stream_paths, CsvPathSink, StreamConfig};
 use fast_sde::rng;
-use rayon::prelude::*;
 use std::f64;
 
 fn main() {
@@ -44,6 +44,7 @@ fn run_canonical_benchmark() {
         payoff: Payoff::EuropeanCall { k },
         greeks: GreeksConfig::NONE,
         epsilon: None,
+        ..Default::default()
     };
 
     let mut timer = Timer::new();
@@ -95,6 +96,7 @@ fn run_demo_mode() {
         payoff: Payoff::EuropeanCall { k },
         greeks: GreeksConfig::DELTA | GreeksConfig::VEGA | GreeksConfig::RHO | GreeksConfig::GAMMA,
         epsilon: Some(0.001 * s0), // 0.1% of spot for finite difference
+        ..Default::default()
     };
 
     let cfg_asian_call = McConfig {
@@ -110,6 +112,7 @@ fn run_demo_mode() {
         payoff: Payoff::AsianCall { k },
         greeks: GreeksConfig::NONE,
         epsilon: None,
+        ..Default::default()
     };
 
     let cfg_barrier_call_up_and_out = McConfig {
@@ -125,6 +128,7 @@ fn run_demo_mode() {
         payoff: Payoff::BarrierCallUpAndOut { k, h },
         greeks: GreeksConfig::NONE,
         epsilon: None,
+        ..Default::default()
     };
 
     let cfg_barrier_put_up_and_out = McConfig {
@@ -140,6 +144,7 @@ fn run_demo_mode() {
         payoff: Payoff::BarrierPutUpAndOut { k, h },
         greeks: GreeksConfig::NONE,
         epsilon: None,
+        ..Default::default()
     };
 
     // --- European Call Pricing ---
@@ -276,7 +281,7 @@ fn run_demo_mode() {
         "MC Price (Asian Call): {} ({} ms)\n",
         mc_price_asian, price_time_asian
     );
-    let elapsed_sec_price_asian = price_time_asian as f64 / 1000.0;
+    let elapsed_sec_price_asian = price_time_asian / 1000.0;
     println!(
         "Throughput: {:.2} paths/sec\n",
         cfg_asian_call.paths as f64 / elapsed_sec_price_asian
@@ -293,7 +298,7 @@ fn run_demo_mode() {
         "MC Price (Barrier Call Up and Out): {} ({} ms)\n",
         mc_price_barrier_call, price_time_barrier_call
     );
-    let elapsed_sec_price_barrier_call = price_time_barrier_call as f64 / 1000.0;
+    let elapsed_sec_price_barrier_call = price_time_barrier_call / 1000.0;
     println!(
         "Throughput: {:.2} paths/sec\n",
         cfg_barrier_call_up_and_out.paths as f64 / elapsed_sec_price_barrier_call
@@ -310,25 +315,25 @@ fn run_demo_mode() {
         "MC Price (Barrier Put Up and Out): {} ({} ms)\n",
         mc_price_barrier_put, price_time_barrier_put
     );
-    let elapsed_sec_price_barrier_put = price_time_barrier_put as f64 / 1000.0;
+    let elapsed_sec_price_barrier_put = price_time_barrier_put / 1000.0;
     println!(
         "Throughput: {:.2} paths/sec\n",
         cfg_barrier_put_up_and_out.paths as f64 / elapsed_sec_price_barrier_put
     );
 
     // --- CSV Output ---
-    // For CSV output, we need to generate paths with the new Payoff structure
-    let path_data_for_csv: Vec<(f64, f64, f64)> = (0..paths)
-        .into_par_iter()
-        .map(|i| {
-            let mut rng = rng::seed_rng_from_u64(cfg_european_call.seed + i as u64);
+    // Paths are streamed to disk chunk by chunk as the workers simulate them,
+    // so the export never holds every path in memory
+    let paths_csv_filename = "results/paths.csv";
+    let dt = t / steps as f64;
+    let sqrt_dt = dt.sqrt();
+    let streamed = CsvPathSink::records(paths_csv_filename).and_then(|mut sink| {
+        stream_paths(paths, &StreamConfig::default(), &mut sink, |i, row| {
+            let mut rng = rng::seed_rng_from_u64(cfg_european_call.seed + i);
             let mut path_prices = Vec::with_capacity(steps + 1);
             path_prices.push(s0);
 
             let mut current_s = s0;
-            let dt = t / steps as f64;
-            let sqrt_dt = dt.sqrt();
-
             for _ in 0..steps {
                 let z = rng::get_normal_draw(&mut rng);
                 current_s *= ((r - 0.5 * sigma * sigma) * dt + sigma * sqrt_dt * z).exp();
@@ -336,24 +341,25 @@ fn run_demo_mode() {
             }
 
             let payoff_european = cfg_european_call.payoff.calculate(&path_prices);
-            let delta_path_european = if *path_prices.last().unwrap() > k {
-                *path_prices.last().unwrap() / s0
-            } else {
-                0.0
-            };
-            (
-                *path_prices.last().unwrap(),
-                payoff_european,
-                delta_path_european,
-            )
+            let delta_path_european = if current_s > k { current_s / s0 } else { 0.0 };
+            row.copy_from_slice(&[current_s, payoff_european, delta_path_european]);
         })
-        .collect();
-
-    let paths_csv_filename = "results/paths.csv";
-    match output::write_paths_to_csv(paths_csv_filename, &path_data_for_csv) {
-        Ok(_) => println!("Path data written to {}", paths_csv_filename),
-        Err(e) => eprintln!("Error writing path data: {}", e),
-    }
+    });
+
+    let run_hash_str = match streamed {
+        Ok(run_hash) => {
+            println!(
+                "Path data written to {} (run hash {})",
+                paths_csv_filename,
+                output::format_hash(run_hash)
+            );
+            output::format_hash(run_hash)
+        }
+        Err(e) => {
+            eprintln!("Error writing path data: {}", e);
+            String::new()
+        }
+    };
 
     // Collect summary data into owned Strings
     let mc_price_european_str = mc_price_european.to_string();
@@ -395,6 +401,7 @@ fn run_demo_mode() {
         ("price_time_ms_barrier_call", &price_time_barrier_call_str),
         ("mc_price_barrier_put", &mc_price_barrier_put_str),
         ("price_time_ms_barrier_put", &price_time_barrier_put_str),
+        ("run_hash", &run_hash_str),
     ];
 
     // Write summary to CSV
@@ -78,6 +78,7 @@ fn main() {
         payoff: Payoff::EuropeanCall { k: 100.0 },
         greeks: fast_sde::mc::mc_engine::GreeksConfig::NONE,
         epsilon: None,
+        ..Default::default()
     };
 
     match mc_price_option_gbm(&invalid_mc_config) {
@@ -101,6 +102,7 @@ fn main() {
         payoff: Payoff::EuropeanCall { k: 100.0 },
         greeks: fast_sde::mc::mc_engine::GreeksConfig::GAMMA,
         epsilon: Some(50.0), // Too large epsilon (50% of spot)
+        ..Default::default()
     };
 
     match mc_price_option_gbm(&invalid_epsilon_config) {
@@ -124,6 +126,7 @@ fn main() {
         payoff: Payoff::EuropeanCall { k: 100.0 },
         greeks: fast_sde::mc::mc_engine::GreeksConfig::NONE,
         epsilon: None,
+        ..Default::default()
     };
 
     match mc_price_option_gbm(&valid_config) {
@@ -1,6 +1,6 @@
 // examples/heston_calibration.rs
 use fast_sde::analytics::bs_analytic;
-use fast_sde::models::heston::{Heston, HestonParams, HestonScheme};
+use fast_sde::models::heston::{FellerPolicy, Heston, HestonParams, HestonScheme};
 use fast_sde::rng::RngFactory;
 use std::f64;
 
@@ -32,7 +32,7 @@ impl HestonCalibrator {
     /// Check if parameters satisfy Feller condition and other constraints
     fn is_valid_params(params: &HestonParams) -> bool {
         // Feller condition: 2κθ > ξ²
-        let feller_condition = 2.0 * params.kappa * params.theta > params.xi * params.xi;
+        let feller_condition = params.feller_satisfied();
 
         // Basic parameter bounds
         let bounds_ok = params.kappa > 0.0
@@ -58,7 +58,11 @@ impl HestonCalibrator {
             return 1e6; // Large error to discourage invalid parameters
         }
 
-        let heston = match Heston::new_with_scheme_quiet(*params, HestonScheme::AndersenQE, true) {
+        let heston = match Heston::new_with_policy(
+            *params,
+            HestonScheme::AndersenQE,
+            FellerPolicy::Ignore,
+        ) {
             Ok(h) => h,
             Err(_) => return 1e6, // Return high error for invalid parameters
         };
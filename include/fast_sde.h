@@ -0,0 +1,202 @@
+/* fast-sde C API. Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#ifndef FAST_SDE_H
+#define FAST_SDE_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Delta bit of the `greeks` mask of [`fsde_greeks_gbm`]
+#define FSDE_GREEK_DELTA (1 << 0)
+
+// Vega bit of the `greeks` mask
+#define FSDE_GREEK_VEGA (1 << 1)
+
+// Rho bit of the `greeks` mask
+#define FSDE_GREEK_RHO (1 << 2)
+
+// Gamma bit of the `greeks` mask
+#define FSDE_GREEK_GAMMA (1 << 3)
+
+// Result code of every FFI function
+typedef enum FsdeStatus {
+  FSDE_STATUS_OK = 0,
+  FSDE_STATUS_NULL_POINTER = 1,
+  FSDE_STATUS_INVALID_PARAMETERS = 2,
+  FSDE_STATUS_NUMERICAL_INSTABILITY = 3,
+  FSDE_STATUS_FELLER_CONDITION_VIOLATION = 4,
+  FSDE_STATUS_INVALID_CONFIGURATION = 5,
+  FSDE_STATUS_MONTE_CARLO_ERROR = 6,
+  FSDE_STATUS_PAYOFF_ERROR = 7,
+  FSDE_STATUS_RANDOM_GENERATION_ERROR = 8,
+  FSDE_STATUS_CALIBRATION_ERROR = 9,
+  FSDE_STATUS_UNSUPPORTED_OPERATION = 10,
+  FSDE_STATUS_CHECK_FAILED = 11,
+  FSDE_STATUS_PANIC = 12,
+} FsdeStatus;
+
+// Payoff families available through the FFI, passed as `uint32_t`
+typedef enum FsdePayoffKind {
+  FSDE_PAYOFF_KIND_EUROPEAN_CALL = 0,
+  FSDE_PAYOFF_KIND_EUROPEAN_PUT = 1,
+  FSDE_PAYOFF_KIND_ASIAN_CALL = 2,
+  FSDE_PAYOFF_KIND_BARRIER_CALL_UP_AND_OUT = 3,
+  FSDE_PAYOFF_KIND_BARRIER_PUT_UP_AND_OUT = 4,
+  FSDE_PAYOFF_KIND_DIGITAL_CALL = 5,
+  FSDE_PAYOFF_KIND_DIGITAL_PUT = 6,
+  FSDE_PAYOFF_KIND_POWER_CALL = 7,
+} FsdePayoffKind;
+
+// Heston discretization scheme, passed as `uint32_t`
+typedef enum FsdeHestonScheme {
+  FSDE_HESTON_SCHEME_FULL_TRUNCATION_EULER = 0,
+  FSDE_HESTON_SCHEME_ANDERSEN_QE = 1,
+  FSDE_HESTON_SCHEME_ALFONSI = 2,
+  FSDE_HESTON_SCHEME_ANDERSEN_QEM = 3,
+} FsdeHestonScheme;
+
+// GBM engine settings (subset of [`McConfig`]; other fields take their
+// defaults)
+typedef struct FsdeGbmConfig {
+  uint64_t paths;
+  uint64_t steps;
+  double s0;
+  double r;
+  double sigma;
+  double t;
+  uint64_t seed;
+  // 0 or 1
+  uint8_t antithetic;
+  // 0 or 1
+  uint8_t control_variate;
+} FsdeGbmConfig;
+
+// Payoff: `strike` for all kinds, `barrier` for the barrier kinds and
+// `exponent` for `PowerCall`; unused fields are ignored
+typedef struct FsdePayoff {
+  // An [`FsdePayoffKind`]
+  uint32_t kind;
+  double strike;
+  double barrier;
+  double exponent;
+} FsdePayoff;
+
+// Price and its Monte Carlo standard error
+typedef struct FsdePriceResult {
+  double price;
+  double std_error;
+} FsdePriceResult;
+
+// GBM Greeks; entries not requested are NaN
+typedef struct FsdeGreeks {
+  double delta;
+  double gamma;
+  double vega;
+  double rho;
+} FsdeGreeks;
+
+// Heston model parameters
+typedef struct FsdeHestonParams {
+  double s0;
+  double v0;
+  double r;
+  double kappa;
+  double theta;
+  double xi;
+  double rho;
+} FsdeHestonParams;
+
+// Heston Monte Carlo settings
+typedef struct FsdeHestonConfig {
+  uint64_t paths;
+  uint64_t steps;
+  double t;
+  uint64_t seed;
+  // 0 or 1
+  uint8_t antithetic;
+  // An [`FsdeHestonScheme`]
+  uint32_t scheme;
+} FsdeHestonConfig;
+
+// Heston price and bump-and-revalue sensitivities
+typedef struct FsdeHestonGreeks {
+  double price;
+  double delta;
+  double gamma;
+  double vega_v0;
+  double dv_dkappa;
+  double dv_dtheta;
+  double dv_dxi;
+  double dv_drho;
+} FsdeHestonGreeks;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Price `payoff` under GBM with the Monte Carlo engine
+//
+// # Safety
+//
+// `config` and `payoff` must point to valid structs and `out` must be
+// valid for writes.
+enum FsdeStatus fsde_price_gbm(const struct FsdeGbmConfig *config,
+                               const struct FsdePayoff *payoff,
+                               struct FsdePriceResult *out);
+
+// GBM Greeks selected by `greeks`, a mask of `FSDE_GREEK_*` bits
+//
+// # Safety
+//
+// `config` and `payoff` must point to valid structs and `out` must be
+// valid for writes.
+enum FsdeStatus fsde_greeks_gbm(const struct FsdeGbmConfig *config,
+                                const struct FsdePayoff *payoff,
+                                uint32_t greeks,
+                                struct FsdeGreeks *out);
+
+// Semi-analytic Heston price of a European call (`call` 1) or put
+// (`call` 0)
+//
+// # Safety
+//
+// `params` must point to a valid struct and `out` must be valid for
+// writes.
+enum FsdeStatus fsde_heston_price(const struct FsdeHestonParams *params,
+                                  double strike,
+                                  double t,
+                                  uint8_t call,
+                                  double *out);
+
+// Heston Monte Carlo price and sensitivities of `payoff` with common
+// random numbers
+//
+// # Safety
+//
+// `params`, `config` and `payoff` must point to valid structs and `out`
+// must be valid for writes.
+enum FsdeStatus fsde_heston_greeks(const struct FsdeHestonParams *params,
+                                   const struct FsdeHestonConfig *config,
+                                   const struct FsdePayoff *payoff,
+                                   struct FsdeHestonGreeks *out);
+
+// Copy the last error message of the calling thread into `buffer`
+//
+// Writes at most `len - 1` bytes plus a NUL terminator and returns the
+// full message length in bytes (0 if no call has failed yet), so a return
+// value `>= len` means the message was truncated.
+//
+// # Safety
+//
+// `buffer` must be null (to query the length) or valid for writes of
+// `len` bytes.
+size_t fsde_last_error_message(char *buffer, size_t len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FAST_SDE_H */
@@ -192,6 +192,7 @@ fn run_monte_carlo_benchmarks() -> Vec<BenchmarkResult> {
             payoff: Payoff::EuropeanCall { k: 100.0 },
             greeks: GreeksConfig::NONE,
             epsilon: None,
+            ..Default::default()
         };
 
         let mut timer = Timer::new();
@@ -0,0 +1,100 @@
+// scripts/fast_sde.rs
+//! `fast-sde` command-line pricer
+//!
+//! ```text
+//! fast-sde <spec.toml | spec.json> [--format table|csv|json] [--output FILE]
+//! ```
+//! Reads a run spec (see `fast_sde::run_spec`), prices it and prints the
+//! result, or writes it to `FILE`.
+use fast_sde::run_spec::{RunSpec, SpecFormat};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str =
+    "usage: fast-sde <spec.toml | spec.json> [--format table|csv|json] [--output FILE]";
+
+#[derive(Clone, Copy)]
+enum OutputFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+struct Args {
+    spec: String,
+    format: OutputFormat,
+    output: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut spec = None;
+    let mut format = OutputFormat::Table;
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("table") => OutputFormat::Table,
+                    Some("csv") => OutputFormat::Csv,
+                    Some("json") => OutputFormat::Json,
+                    other => return Err(format!("unknown format {:?}", other.unwrap_or(""))),
+                }
+            }
+            "--output" => {
+                output = Some(args.next().ok_or("--output needs a file name")?);
+            }
+            "-h" | "--help" => return Err(String::new()),
+            _ if spec.is_none() && !arg.starts_with("--") => spec = Some(arg),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+    Ok(Args {
+        spec: spec.ok_or("missing spec file")?,
+        format,
+        output,
+    })
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let path = Path::new(&args.spec);
+    let format = SpecFormat::from_path(path)
+        .ok_or_else(|| format!("{}: expected a .toml or .json spec", args.spec))?;
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", args.spec, e))?;
+    let spec = RunSpec::parse(&text, format).map_err(|e| format!("{}: {}", args.spec, e))?;
+    let result = spec.run().map_err(|e| e.to_string())?;
+
+    let rendered = match args.format {
+        OutputFormat::Table => result.to_table(),
+        OutputFormat::Csv => result.to_csv(),
+        OutputFormat::Json => result.to_json().map_err(|e| e.to_string())? + "\n",
+    };
+    match &args.output {
+        Some(file) => fs::write(file, rendered).map_err(|e| format!("{}: {}", file, e)),
+        None => {
+            print!("{}", rendered);
+            Ok(())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            if !message.is_empty() {
+                eprintln!("error: {}", message);
+            }
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
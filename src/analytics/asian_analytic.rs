@@ -0,0 +1,250 @@
+// src/analytics/asian_analytic.rs
+//! Analytical approximations for discretely monitored arithmetic Asian calls
+//!
+//! # Averaging Convention
+//!
+//! All functions use the same average as [`Payoff::AsianCall`] on an engine
+//! path with `steps` time steps: the arithmetic mean of the `N = steps + 1`
+//! fixings
+//! ```text
+//! A = (1/N) Σ_{i=0}^{steps} S(t_i),   t_i = i T / steps
+//! ```
+//! which includes the spot `S(0)`.
+//!
+//! # Turnbull–Wakeman
+//!
+//! Matches the first two moments of `A` to a lognormal:
+//! ```text
+//! M₁ = (S/N) Σ_i e^(r t_i)
+//! M₂ = (S²/N²) Σ_i Σ_j e^(r(t_i + t_j) + σ² min(t_i, t_j))
+//! v  = ln(M₂ / M₁²)
+//! C  = e^(-rT) [M₁ Φ(d₁) - K Φ(d₂)],   d₁ = [ln(M₁/K) + v/2] / √v,   d₂ = d₁ - √v
+//! ```
+//! `M₁` is linear in `S` and `v` does not depend on `S`, so Delta and Gamma
+//! have Black-style closed forms; Vega follows from `∂v/∂σ = (∂M₂/∂σ)/M₂`.
+//!
+//! # Curran
+//!
+//! Conditions on the geometric average `G`, which is exactly lognormal, and
+//! replaces the strike by `K̂` so that the conditional expectation of `A`
+//! equals `K` on the boundary. This is typically accurate to a few basis
+//! points of spot for the volatilities seen in practice. Curran Greeks are
+//! central differences of the closed-form price, which carry no Monte Carlo
+//! noise and are accurate to `O(h²)`.
+//!
+//! [`Payoff::AsianCall`]: crate::mc::payoffs::Payoff::AsianCall
+
+use crate::analytics::bs_analytic::norm_pdf;
+use crate::math_utils::norm_cdf;
+
+/// Fixing times `t_i = i T / steps` for `i = 0..=steps`
+fn fixing_times(t: f64, steps: usize) -> Vec<f64> {
+    let steps = steps.max(1);
+    (0..=steps).map(|i| i as f64 * t / steps as f64).collect()
+}
+
+/// Turnbull–Wakeman moments: `(M₁, M₂, ∂M₂/∂σ)`
+fn tw_moments(s: f64, r: f64, sigma: f64, t: f64, steps: usize) -> (f64, f64, f64) {
+    let times = fixing_times(t, steps);
+    let n = times.len() as f64;
+    let m1 = s * times.iter().map(|&ti| (r * ti).exp()).sum::<f64>() / n;
+
+    let (mut m2, mut dm2) = (0.0, 0.0);
+    for &ti in &times {
+        for &tj in &times {
+            let tmin = ti.min(tj);
+            let e = (r * (ti + tj) + sigma * sigma * tmin).exp();
+            m2 += e;
+            dm2 += 2.0 * sigma * tmin * e;
+        }
+    }
+    let scale = s * s / (n * n);
+    (m1, m2 * scale, dm2 * scale)
+}
+
+/// Turnbull–Wakeman `(M₁, √v, d₁, ∂ln M₂/∂σ)` for a given strike
+fn tw_terms(s: f64, k: f64, r: f64, sigma: f64, t: f64, steps: usize) -> (f64, f64, f64, f64) {
+    let (m1, m2, dm2) = tw_moments(s, r, sigma, t, steps);
+    let sd = (m2 / (m1 * m1)).ln().sqrt();
+    let d1 = ((m1 / k).ln() + 0.5 * sd * sd) / sd;
+    (m1, sd, d1, dm2 / m2)
+}
+
+/// Turnbull–Wakeman arithmetic Asian call price
+///
+/// # Parameters
+/// - `s`, `k`, `r`, `sigma`, `t`: as for [`bs_call_price`](super::bs_analytic::bs_call_price)
+/// - `steps`: number of time steps; the average uses `steps + 1` fixings
+pub fn tw_asian_call_price(s: f64, k: f64, r: f64, sigma: f64, t: f64, steps: usize) -> f64 {
+    let (m1, sd, d1, _) = tw_terms(s, k, r, sigma, t, steps);
+    (-r * t).exp() * (m1 * norm_cdf(d1) - k * norm_cdf(d1 - sd))
+}
+
+/// Turnbull–Wakeman arithmetic Asian call Delta
+///
+/// # Formula
+/// ```text
+/// Δ = e^(-rT) (M₁/S) Φ(d₁)
+/// ```
+pub fn tw_asian_call_delta(s: f64, k: f64, r: f64, sigma: f64, t: f64, steps: usize) -> f64 {
+    let (m1, _, d1, _) = tw_terms(s, k, r, sigma, t, steps);
+    (-r * t).exp() * (m1 / s) * norm_cdf(d1)
+}
+
+/// Turnbull–Wakeman arithmetic Asian call Gamma
+///
+/// # Formula
+/// ```text
+/// Γ = e^(-rT) M₁ φ(d₁) / (S² √v)
+/// ```
+pub fn tw_asian_call_gamma(s: f64, k: f64, r: f64, sigma: f64, t: f64, steps: usize) -> f64 {
+    let (m1, sd, d1, _) = tw_terms(s, k, r, sigma, t, steps);
+    (-r * t).exp() * m1 * norm_pdf(d1) / (s * s * sd)
+}
+
+/// Turnbull–Wakeman arithmetic Asian call Vega
+///
+/// # Formula
+/// ```text
+/// ν = e^(-rT) M₁ φ(d₁) (∂M₂/∂σ) / (2 √v M₂)
+/// ```
+pub fn tw_asian_call_vega(s: f64, k: f64, r: f64, sigma: f64, t: f64, steps: usize) -> f64 {
+    let (m1, sd, d1, dlog_m2) = tw_terms(s, k, r, sigma, t, steps);
+    (-r * t).exp() * m1 * norm_pdf(d1) * dlog_m2 / (2.0 * sd)
+}
+
+/// Curran arithmetic Asian call price
+///
+/// # Formula
+/// ```text
+/// μ_i = ln S + (r - σ²/2) t_i,         σ_i² = σ² t_i
+/// μ_G = (1/N) Σ μ_i,                    σ_G² = (σ²/N²) Σ_i Σ_j min(t_i, t_j)
+/// σ_xi = (σ²/N) Σ_j min(t_i, t_j)
+/// K̂ = 2K - (1/N) Σ_i exp(μ_i + σ_xi (ln K - μ_G)/σ_G² + (σ_i² - σ_xi²/σ_G²)/2)
+/// C = e^(-rT) [(1/N) Σ_i e^(μ_i + σ_i²/2) Φ(d + σ_xi/σ_G) - K Φ(d)],   d = (μ_G - ln K̂)/σ_G
+/// ```
+///
+/// When `K̂ ≤ 0` the option is exercised on every path and the price is the
+/// exact `e^(-rT) (E[A] - K)`.
+pub fn curran_asian_call_price(s: f64, k: f64, r: f64, sigma: f64, t: f64, steps: usize) -> f64 {
+    let times = fixing_times(t, steps);
+    let n = times.len() as f64;
+    let discount = (-r * t).exp();
+    let drift = r - 0.5 * sigma * sigma;
+    let ln_s = s.ln();
+
+    let mu: Vec<f64> = times.iter().map(|&ti| ln_s + drift * ti).collect();
+    let cov: Vec<f64> = times
+        .iter()
+        .map(|&ti| sigma * sigma * times.iter().map(|&tj| ti.min(tj)).sum::<f64>() / n)
+        .collect();
+    let mu_g = mu.iter().sum::<f64>() / n;
+    let var_g = cov.iter().sum::<f64>() / n;
+    let sd_g = var_g.sqrt();
+    let forward_mean: f64 = mu
+        .iter()
+        .zip(&times)
+        .map(|(&m, &ti)| (m + 0.5 * sigma * sigma * ti).exp())
+        .sum::<f64>()
+        / n;
+
+    let ln_k = k.ln();
+    let k_hat = 2.0 * k
+        - mu.iter()
+            .zip(&times)
+            .zip(&cov)
+            .map(|((&m, &ti), &c)| {
+                (m + c * (ln_k - mu_g) / var_g + 0.5 * (sigma * sigma * ti - c * c / var_g)).exp()
+            })
+            .sum::<f64>()
+            / n;
+
+    if k_hat <= 0.0 {
+        return discount * (forward_mean - k);
+    }
+
+    let d = (mu_g - k_hat.ln()) / sd_g;
+    let upper: f64 = mu
+        .iter()
+        .zip(&times)
+        .zip(&cov)
+        .map(|((&m, &ti), &c)| (m + 0.5 * sigma * sigma * ti).exp() * norm_cdf(d + c / sd_g))
+        .sum::<f64>()
+        / n;
+    discount * (upper - k * norm_cdf(d))
+}
+
+/// Relative bump for central differences of the Curran price
+const CURRAN_BUMP: f64 = 1e-4;
+
+/// Curran arithmetic Asian call Delta (central difference of the closed form)
+pub fn curran_asian_call_delta(s: f64, k: f64, r: f64, sigma: f64, t: f64, steps: usize) -> f64 {
+    let h = CURRAN_BUMP * s;
+    (curran_asian_call_price(s + h, k, r, sigma, t, steps)
+        - curran_asian_call_price(s - h, k, r, sigma, t, steps))
+        / (2.0 * h)
+}
+
+/// Curran arithmetic Asian call Gamma (central difference of the closed form)
+pub fn curran_asian_call_gamma(s: f64, k: f64, r: f64, sigma: f64, t: f64, steps: usize) -> f64 {
+    // Larger bump: the second difference loses digits to cancellation
+    let h = 1e-2 * s;
+    (curran_asian_call_price(s + h, k, r, sigma, t, steps)
+        - 2.0 * curran_asian_call_price(s, k, r, sigma, t, steps)
+        + curran_asian_call_price(s - h, k, r, sigma, t, steps))
+        / (h * h)
+}
+
+/// Curran arithmetic Asian call Vega (central difference of the closed form)
+pub fn curran_asian_call_vega(s: f64, k: f64, r: f64, sigma: f64, t: f64, steps: usize) -> f64 {
+    let h = CURRAN_BUMP * sigma;
+    (curran_asian_call_price(s, k, r, sigma + h, t, steps)
+        - curran_asian_call_price(s, k, r, sigma - h, t, steps))
+        / (2.0 * h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::bs_analytic;
+
+    #[test]
+    fn test_tw_greeks_match_closed_form_differences() {
+        let (s, k, r, sigma, t, steps) = (100.0, 100.0, 0.05, 0.2, 1.0, 12);
+        let price = |s: f64, sigma: f64| tw_asian_call_price(s, k, r, sigma, t, steps);
+        let h = 1e-3;
+
+        let fd_delta = (price(s + h, sigma) - price(s - h, sigma)) / (2.0 * h);
+        let fd_gamma =
+            (price(s + h, sigma) - 2.0 * price(s, sigma) + price(s - h, sigma)) / (h * h);
+        let fd_vega = (price(s, sigma + 1e-5) - price(s, sigma - 1e-5)) / 2e-5;
+
+        assert!((tw_asian_call_delta(s, k, r, sigma, t, steps) - fd_delta).abs() < 1e-6);
+        assert!((tw_asian_call_gamma(s, k, r, sigma, t, steps) - fd_gamma).abs() < 1e-4);
+        assert!((tw_asian_call_vega(s, k, r, sigma, t, steps) - fd_vega).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_tw_and_curran_agree_and_reduce_to_european() {
+        let (s, k, r, sigma, t, steps) = (100.0, 100.0, 0.05, 0.2, 1.0, 12);
+        let tw = tw_asian_call_price(s, k, r, sigma, t, steps);
+        let curran = curran_asian_call_price(s, k, r, sigma, t, steps);
+        assert!((tw - curran).abs() < 0.05, "TW {} vs Curran {}", tw, curran);
+
+        let tw_delta = tw_asian_call_delta(s, k, r, sigma, t, steps);
+        let curran_delta = curran_asian_call_delta(s, k, r, sigma, t, steps);
+        assert!((tw_delta - curran_delta).abs() < 0.01);
+        let tw_vega = tw_asian_call_vega(s, k, r, sigma, t, steps);
+        let curran_vega = curran_asian_call_vega(s, k, r, sigma, t, steps);
+        assert!((tw_vega - curran_vega).abs() < 0.5);
+
+        // Averaging lowers the effective volatility below the European one
+        assert!(tw < bs_analytic::bs_call_price(s, k, r, sigma, t));
+
+        // Deep in the money, Curran is exact: e^(-rT) (E[A] - K)
+        let deep = curran_asian_call_price(s, 10.0, r, sigma, t, steps);
+        let times = fixing_times(t, steps);
+        let mean = s * times.iter().map(|&ti| (r * ti).exp()).sum::<f64>() / times.len() as f64;
+        assert!((deep - (-r * t).exp() * (mean - 10.0)).abs() < 1e-6);
+    }
+}
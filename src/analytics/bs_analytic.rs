@@ -16,6 +16,7 @@
 //! For European options, this has closed-form solutions involving
 //! the cumulative normal distribution function Φ(x).
 
+use crate::error::{SdeError, SdeResult};
 use crate::math_utils::norm_cdf;
 use std::f64::consts::PI;
 
@@ -25,7 +26,7 @@ use std::f64::consts::PI;
 /// ```text
 /// φ(x) = (1/√(2π)) * exp(-x²/2)
 /// ```
-fn norm_pdf(x: f64) -> f64 {
+pub(crate) fn norm_pdf(x: f64) -> f64 {
     (1.0 / (2.0 * PI).sqrt()) * (-0.5 * x * x).exp()
 }
 
@@ -156,3 +157,229 @@ pub fn bs_call_rho(s: f64, k: f64, r: f64, sigma: f64, t: f64) -> f64 {
     let d2 = d1 - sigma * t.sqrt();
     k * t * (-r * t).exp() * norm_cdf(d2)
 }
+
+/// Cash-or-nothing digital call price (pays 1 if S_T > K)
+///
+/// # Formula
+/// ```text
+/// D(S,K,r,σ,T) = e^(-rT) * Φ(d₂)
+/// ```
+pub fn bs_digital_call_price(s: f64, k: f64, r: f64, sigma: f64, t: f64) -> f64 {
+    let d1 = ((s / k).ln() + (r + 0.5 * sigma * sigma) * t) / (sigma * t.sqrt());
+    let d2 = d1 - sigma * t.sqrt();
+    (-r * t).exp() * norm_cdf(d2)
+}
+
+/// Cash-or-nothing digital call Delta
+///
+/// # Formula
+/// ```text
+/// Δ = e^(-rT) * φ(d₂) / (S * σ * √T)
+/// ```
+pub fn bs_digital_call_delta(s: f64, k: f64, r: f64, sigma: f64, t: f64) -> f64 {
+    let d1 = ((s / k).ln() + (r + 0.5 * sigma * sigma) * t) / (sigma * t.sqrt());
+    let d2 = d1 - sigma * t.sqrt();
+    (-r * t).exp() * norm_pdf(d2) / (s * sigma * t.sqrt())
+}
+
+/// Cash-or-nothing digital call Gamma
+///
+/// # Formula
+/// ```text
+/// Γ = -e^(-rT) * φ(d₂) * d₁ / (S² * σ² * T)
+/// ```
+///
+/// # Interpretation
+/// - Changes sign at d₁ = 0, unlike vanilla Gamma
+/// - Explodes near expiry at the strike, which is what makes finite
+///   difference estimates of digital Gamma so noisy
+pub fn bs_digital_call_gamma(s: f64, k: f64, r: f64, sigma: f64, t: f64) -> f64 {
+    let d1 = ((s / k).ln() + (r + 0.5 * sigma * sigma) * t) / (sigma * t.sqrt());
+    let d2 = d1 - sigma * t.sqrt();
+    -(-r * t).exp() * norm_pdf(d2) * d1 / (s * s * sigma * sigma * t)
+}
+
+/// Continuously monitored up-and-out call price
+///
+/// # Formula
+/// For `H > K` (Hull; no dividends):
+/// ```text
+/// λ  = (r + σ²/2) / σ²
+/// x₁ = ln(S/H)/(σ√T) + λσ√T,   y₁ = ln(H/S)/(σ√T) + λσ√T
+/// y  = ln(H²/(SK))/(σ√T) + λσ√T
+/// C_ui = S Φ(x₁) - K e^(-rT) Φ(x₁ - σ√T)
+///        - S (H/S)^(2λ) [Φ(-y) - Φ(-y₁)]
+///        + K e^(-rT) (H/S)^(2λ-2) [Φ(-y + σ√T) - Φ(-y₁ + σ√T)]
+/// C_uo = C - C_ui
+/// ```
+///
+/// Returns 0 when `H ≤ K` or `S ≥ H`, where the option cannot pay out.
+pub fn bs_up_and_out_call_price(s: f64, k: f64, h: f64, r: f64, sigma: f64, t: f64) -> f64 {
+    if h <= k || s >= h {
+        return 0.0;
+    }
+    let sd = sigma * t.sqrt();
+    let lambda = (r + 0.5 * sigma * sigma) / (sigma * sigma);
+    let discount = (-r * t).exp();
+    let x1 = (s / h).ln() / sd + lambda * sd;
+    let y1 = (h / s).ln() / sd + lambda * sd;
+    let y = (h * h / (s * k)).ln() / sd + lambda * sd;
+
+    let up_and_in = s * norm_cdf(x1)
+        - k * discount * norm_cdf(x1 - sd)
+        - s * (h / s).powf(2.0 * lambda) * (norm_cdf(-y) - norm_cdf(-y1))
+        + k * discount
+            * (h / s).powf(2.0 * lambda - 2.0)
+            * (norm_cdf(-y + sd) - norm_cdf(-y1 + sd));
+    (bs_call_price(s, k, r, sigma, t) - up_and_in).max(0.0)
+}
+
+/// Probability that GBM touches the level `h` before `t`
+///
+/// # Formula
+/// With `x = |ln(H/S)|` and `m = ±(r - σ²/2)` the drift of the log-distance
+/// travelled towards the level (`+` for `H > S`, `-` for `H < S`):
+/// ```text
+/// P(τ ≤ T) = Φ((-x + mT)/(σ√T)) + e^(2mx/σ²) Φ((-x - mT)/(σ√T))
+/// ```
+///
+/// Returns 1 when `S = H`.
+pub fn gbm_hitting_probability(s: f64, h: f64, r: f64, sigma: f64, t: f64) -> f64 {
+    let x = (h / s).ln().abs();
+    if x == 0.0 {
+        return 1.0;
+    }
+    let nu = r - 0.5 * sigma * sigma;
+    let m = if h > s { nu } else { -nu };
+    let sd = sigma * t.sqrt();
+    norm_cdf((-x + m * t) / sd)
+        + (2.0 * m * x / (sigma * sigma)).exp() * norm_cdf((-x - m * t) / sd)
+}
+
+/// Forward-start call price (Rubinstein 1991)
+///
+/// # Formula
+/// The strike `α S_{t₁}` is fixed at `t₁`; by scale invariance of GBM
+/// ```text
+/// FS = S Φ(d₁) - α S e^(-r(T-t₁)) Φ(d₂)
+/// d₁ = [-ln α + (r + σ²/2)(T-t₁)] / (σ√(T-t₁)),   d₂ = d₁ - σ√(T-t₁)
+/// ```
+/// i.e. `bs_call_price(S, αS, r, σ, T - t₁)`.
+pub fn bs_forward_start_call_price(
+    s: f64,
+    alpha: f64,
+    r: f64,
+    sigma: f64,
+    t_start: f64,
+    t: f64,
+) -> f64 {
+    bs_call_price(s, alpha * s, r, sigma, t - t_start)
+}
+
+/// Power call price, payoff `max(S_T^p - K, 0)` with `p > 0`
+///
+/// # Formula
+/// `S_T^p` is lognormal with volatility `pσ` and forward
+/// `F_p = S^p exp(p(r - σ²/2)T + p²σ²T/2)`, so
+/// ```text
+/// PC = e^(-rT) [F_p Φ(d₁) - K Φ(d₂)]
+/// d₁ = [ln(F_p/K) + p²σ²T/2] / (pσ√T),   d₂ = d₁ - pσ√T
+/// ```
+pub fn bs_power_call_price(s: f64, k: f64, p: f64, r: f64, sigma: f64, t: f64) -> f64 {
+    let vol = p * sigma * t.sqrt();
+    let forward = s.powf(p) * (p * (r - 0.5 * sigma * sigma) * t + 0.5 * vol * vol).exp();
+    if k <= 0.0 {
+        return (-r * t).exp() * forward;
+    }
+    let d1 = ((forward / k).ln() + 0.5 * vol * vol) / vol;
+    let d2 = d1 - vol;
+    (-r * t).exp() * (forward * norm_cdf(d1) - k * norm_cdf(d2))
+}
+
+/// Simple chooser price (Rubinstein 1991), choice at `t_choice`
+///
+/// # Formula
+/// By put-call parity at the choice date, the chooser is a call to `T`
+/// plus a put to `t_c` on a discounted strike:
+/// ```text
+/// CH = C(S, K, r, σ, T) + P(S, K e^(-r(T - t_c)), r, σ, t_c)
+/// ```
+pub fn bs_chooser_price(s: f64, k: f64, r: f64, sigma: f64, t_choice: f64, t: f64) -> f64 {
+    bs_call_price(s, k, r, sigma, t)
+        + bs_put_price(s, k * (-r * (t - t_choice)).exp(), r, sigma, t_choice)
+}
+
+const IMPLIED_VOL_ITERATIONS: usize = 200;
+/// Relative tolerance on the price (and on the volatility bracket)
+const IMPLIED_VOL_TOLERANCE: f64 = 1e-12;
+
+/// Black-Scholes implied volatility of a European call price
+///
+/// # Method
+/// Newton iterations on `C(σ) - price` from the Brenner–Subrahmanyam
+/// guess `σ₀ = √(2π/T) · price/S`, safeguarded by bisection on a bracket
+/// that shrinks with every evaluation:
+/// ```text
+/// σ ← σ - (C(σ) - price) / ν(σ),   falling back to the bracket midpoint
+/// ```
+/// Put prices can be converted first by put-call parity,
+/// `C = P + S - K e^(-rT)`.
+///
+/// # Errors
+/// Returns `SdeError::InvalidParameters` unless
+/// `max(S - K e^(-rT), 0) < price < S`, where no volatility reproduces the
+/// price, and `SdeError::NumericalInstability` if the iteration does not
+/// converge.
+pub fn bs_call_implied_vol(price: f64, s: f64, k: f64, r: f64, t: f64) -> SdeResult<f64> {
+    let intrinsic = (s - k * (-r * t).exp()).max(0.0);
+    if !(price > intrinsic && price < s) {
+        return Err(SdeError::InvalidParameters {
+            parameter: "price".to_string(),
+            value: price,
+            constraint: format!("must lie strictly between {} and {}", intrinsic, s),
+        });
+    }
+
+    let (mut lo, mut hi) = (1e-8, 10.0);
+    let mut sigma = ((2.0 * PI / t).sqrt() * price / s).clamp(lo, hi);
+    for _ in 0..IMPLIED_VOL_ITERATIONS {
+        let diff = bs_call_price(s, k, r, sigma, t) - price;
+        if diff.abs() < IMPLIED_VOL_TOLERANCE * price {
+            return Ok(sigma);
+        }
+        if diff > 0.0 {
+            hi = sigma;
+        } else {
+            lo = sigma;
+        }
+        let newton = sigma - diff / bs_call_vega(s, k, r, sigma, t);
+        sigma = if newton > lo && newton < hi {
+            newton
+        } else {
+            0.5 * (lo + hi)
+        };
+        if hi - lo < IMPLIED_VOL_TOLERANCE * hi {
+            return Ok(sigma);
+        }
+    }
+    Err(SdeError::NumericalInstability {
+        method: "Implied volatility".to_string(),
+        reason: format!("no convergence for price {} at strike {}", price, k),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_implied_vol_round_trip() {
+        for &(k, t, sigma) in &[(100.0, 1.0, 0.2), (60.0, 0.1, 0.8), (150.0, 2.0, 0.15)] {
+            let price = bs_call_price(100.0, k, 0.03, sigma, t);
+            let vol = bs_call_implied_vol(price, 100.0, k, 0.03, t).expect("Price in range");
+            assert!((vol - sigma).abs() < 1e-8, "{} vs {}", vol, sigma);
+        }
+        assert!(bs_call_implied_vol(101.0, 100.0, 100.0, 0.03, 1.0).is_err());
+        assert!(bs_call_implied_vol(1.0, 100.0, 50.0, 0.03, 1.0).is_err());
+    }
+}
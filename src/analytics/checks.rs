@@ -0,0 +1,264 @@
+// src/analytics/checks.rs
+//! Greek Parity and Sanity Checks
+//!
+//! Programmatic checks that a set of prices and Greeks is internally
+//! consistent. A failed check usually points at a configuration error (wrong
+//! rate, maturity in days instead of years, swapped call and put) rather
+//! than Monte Carlo noise, provided the tolerance covers the standard error.
+//!
+//! # Checks
+//!
+//! ```text
+//! Put-call parity:  C - P = S - K e^(-rT)
+//! Price bounds:     max(S - K e^(-rT), 0) ≤ C ≤ S
+//! Delta bounds:     0 ≤ Δ_call ≤ 1,   -1 ≤ Δ_put ≤ 0
+//! Gamma:            Γ ≥ 0 for long vanillas
+//! Vega-Gamma:       ν = Γ σ S² T                  (Black-Scholes)
+//! Theta (BS PDE):   Θ = rV - rSΔ - ½σ²S²Γ         (Θ = ∂V/∂t per year)
+//! ```
+//!
+//! Each check returns `SdeError::CheckFailed` on violation; [`run_checks`]
+//! applies every check the available results allow.
+
+use crate::error::{SdeError, SdeResult};
+
+fn check(name: &str, ok: bool, detail: impl FnOnce() -> String) -> SdeResult<()> {
+    if ok {
+        Ok(())
+    } else {
+        Err(SdeError::CheckFailed {
+            check: name.to_string(),
+            detail: detail(),
+        })
+    }
+}
+
+/// `|C - P - (S - K e^(-rT))| ≤ tol`
+pub fn check_put_call_parity(
+    call: f64,
+    put: f64,
+    s: f64,
+    k: f64,
+    r: f64,
+    t: f64,
+    tol: f64,
+) -> SdeResult<()> {
+    let forward = s - k * (-r * t).exp();
+    let gap = call - put - forward;
+    check("put-call parity", gap.abs() <= tol, || {
+        format!("C - P = {} but S - K e^(-rT) = {}", call - put, forward)
+    })
+}
+
+/// `max(S - K e^(-rT), 0) - tol ≤ C ≤ S + tol`
+pub fn check_call_price_bounds(
+    call: f64,
+    s: f64,
+    k: f64,
+    r: f64,
+    t: f64,
+    tol: f64,
+) -> SdeResult<()> {
+    let lower = (s - k * (-r * t).exp()).max(0.0);
+    check(
+        "call price bounds",
+        call >= lower - tol && call <= s + tol,
+        || format!("call {} outside [{}, {}]", call, lower, s),
+    )
+}
+
+/// `-tol ≤ Δ ≤ 1 + tol` for a call
+pub fn check_call_delta_bounds(delta: f64, tol: f64) -> SdeResult<()> {
+    check(
+        "call delta bounds",
+        (-tol..=1.0 + tol).contains(&delta),
+        || format!("delta {} outside [0, 1]", delta),
+    )
+}
+
+/// `-1 - tol ≤ Δ ≤ tol` for a put
+pub fn check_put_delta_bounds(delta: f64, tol: f64) -> SdeResult<()> {
+    check(
+        "put delta bounds",
+        (-1.0 - tol..=tol).contains(&delta),
+        || format!("delta {} outside [-1, 0]", delta),
+    )
+}
+
+/// `Γ ≥ -tol`
+pub fn check_gamma_non_negative(gamma: f64, tol: f64) -> SdeResult<()> {
+    check("gamma positivity", gamma >= -tol, || {
+        format!("gamma {} is negative", gamma)
+    })
+}
+
+/// `|ν - Γ σ S² T| ≤ tol` under Black-Scholes
+pub fn check_vega_gamma_relation(
+    vega: f64,
+    gamma: f64,
+    s: f64,
+    sigma: f64,
+    t: f64,
+    tol: f64,
+) -> SdeResult<()> {
+    let implied = gamma * sigma * s * s * t;
+    check("vega-gamma relation", (vega - implied).abs() <= tol, || {
+        format!("vega {} but Γ σ S² T = {}", vega, implied)
+    })
+}
+
+/// Theta implied by the Black-Scholes PDE: `rV - rSΔ - ½σ²S²Γ`
+pub fn bs_implied_theta(price: f64, delta: f64, gamma: f64, s: f64, r: f64, sigma: f64) -> f64 {
+    r * price - r * s * delta - 0.5 * sigma * sigma * s * s * gamma
+}
+
+/// `|Θ - implied_theta| ≤ tol`, with `implied_theta` from [`bs_implied_theta`]
+pub fn check_bs_theta(theta: f64, implied_theta: f64, tol: f64) -> SdeResult<()> {
+    check(
+        "Black-Scholes theta",
+        (theta - implied_theta).abs() <= tol,
+        || format!("theta {} but the BS PDE implies {}", theta, implied_theta),
+    )
+}
+
+/// Prices and Greeks of a European call/put pair on one underlying
+///
+/// Greeks refer to the call. Leave unknown results as `None`; checks that
+/// need them are skipped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OptionResultSet {
+    pub s: f64,
+    pub k: f64,
+    pub r: f64,
+    pub sigma: f64,
+    pub t: f64,
+    pub call: Option<f64>,
+    pub put: Option<f64>,
+    pub delta: Option<f64>,
+    pub put_delta: Option<f64>,
+    pub gamma: Option<f64>,
+    pub vega: Option<f64>,
+    pub theta: Option<f64>,
+}
+
+/// Outcome of [`run_checks`]
+#[derive(Debug, Clone, Default)]
+pub struct CheckReport {
+    /// Names of the checks that ran and passed
+    pub passed: Vec<&'static str>,
+    /// One error per failed check
+    pub failures: Vec<SdeError>,
+}
+
+impl CheckReport {
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// `Ok` if every check passed, otherwise the first failure
+    pub fn into_result(self) -> SdeResult<()> {
+        match self.failures.into_iter().next() {
+            Some(failure) => Err(failure),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Run every check that the available results allow
+///
+/// `tol` is an absolute tolerance applied to each check; for Monte Carlo
+/// results it should cover a few standard errors.
+pub fn run_checks(set: &OptionResultSet, tol: f64) -> CheckReport {
+    let OptionResultSet {
+        s, k, r, sigma, t, ..
+    } = *set;
+    let mut checks: Vec<(&'static str, SdeResult<()>)> = Vec::new();
+    if let (Some(call), Some(put)) = (set.call, set.put) {
+        checks.push((
+            "put-call parity",
+            check_put_call_parity(call, put, s, k, r, t, tol),
+        ));
+    }
+    if let Some(call) = set.call {
+        checks.push((
+            "call price bounds",
+            check_call_price_bounds(call, s, k, r, t, tol),
+        ));
+    }
+    if let Some(delta) = set.delta {
+        checks.push(("call delta bounds", check_call_delta_bounds(delta, tol)));
+    }
+    if let Some(delta) = set.put_delta {
+        checks.push(("put delta bounds", check_put_delta_bounds(delta, tol)));
+    }
+    if let Some(gamma) = set.gamma {
+        checks.push(("gamma positivity", check_gamma_non_negative(gamma, tol)));
+    }
+    if let (Some(vega), Some(gamma)) = (set.vega, set.gamma) {
+        checks.push((
+            "vega-gamma relation",
+            check_vega_gamma_relation(vega, gamma, s, sigma, t, tol),
+        ));
+    }
+    if let (Some(call), Some(delta), Some(gamma), Some(theta)) =
+        (set.call, set.delta, set.gamma, set.theta)
+    {
+        checks.push((
+            "Black-Scholes theta",
+            check_bs_theta(
+                theta,
+                bs_implied_theta(call, delta, gamma, s, r, sigma),
+                tol,
+            ),
+        ));
+    }
+
+    let mut report = CheckReport::default();
+    for (name, result) in checks {
+        match result {
+            Ok(()) => report.passed.push(name),
+            Err(e) => report.failures.push(e),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::bs_analytic::*;
+
+    #[test]
+    fn test_analytic_results_pass_and_wrong_rate_fails() {
+        let (s, k, r, sigma, t) = (100.0, 95.0, 0.03, 0.25, 0.5);
+        let set = OptionResultSet {
+            s,
+            k,
+            r,
+            sigma,
+            t,
+            call: Some(bs_call_price(s, k, r, sigma, t)),
+            put: Some(bs_put_price(s, k, r, sigma, t)),
+            delta: Some(bs_call_delta(s, k, r, sigma, t)),
+            put_delta: Some(bs_call_delta(s, k, r, sigma, t) - 1.0),
+            gamma: Some(bs_call_gamma(s, k, r, sigma, t)),
+            vega: Some(bs_call_vega(s, k, r, sigma, t)),
+            theta: Some(bs_call_theta(s, k, r, sigma, t)),
+        };
+        let report = run_checks(&set, 1e-8);
+        assert_eq!(report.passed.len(), 7, "{:?}", report.failures);
+        assert!(report.into_result().is_ok());
+
+        // A put priced with the wrong rate breaks parity
+        let wrong = OptionResultSet {
+            put: Some(bs_put_price(s, k, 0.0, sigma, t)),
+            ..set
+        };
+        let report = run_checks(&wrong, 1e-4);
+        assert!(!report.is_ok());
+        assert!(matches!(
+            report.failures[0],
+            SdeError::CheckFailed { ref check, .. } if check == "put-call parity"
+        ));
+    }
+}
@@ -0,0 +1,210 @@
+// src/analytics/fx_analytic.rs
+//! Garman-Kohlhagen FX Option Formulas and Delta Conventions
+//!
+//! # Mathematical Foundation
+//!
+//! The spot `S` (domestic units per unit of foreign currency) earns the
+//! foreign rate like a dividend yield, so under the domestic risk-neutral
+//! measure
+//! ```text
+//! dS_t = (r_d - r_f) S_t dt + σ S_t dW_t,   F = S e^{(r_d - r_f)T}
+//! C = S e^{-r_f T} Φ(d₁) - K e^{-r_d T} Φ(d₂)
+//! P = K e^{-r_d T} Φ(-d₂) - S e^{-r_f T} Φ(-d₁)
+//! d₁ = [ln(F/K) + σ²T/2] / (σ√T),   d₂ = d₁ - σ√T
+//! ```
+//!
+//! # Delta Conventions
+//!
+//! FX markets quote deltas in several conventions. With `ω = +1` for calls
+//! and `-1` for puts:
+//! ```text
+//! Spot:                       ω e^{-r_f T} Φ(ωd₁)
+//! Forward:                    ω Φ(ωd₁)
+//! Spot, premium-adjusted:     ω (K/S) e^{-r_d T} Φ(ωd₂)  = spot delta - V/S
+//! Forward, premium-adjusted:  ω (K/F) Φ(ωd₂)
+//! ```
+//! The premium-adjusted deltas apply when the premium is paid in the
+//! foreign currency, whose value then moves with the spot too.
+
+use crate::analytics::bs_analytic::norm_pdf;
+use crate::error::{validation::*, SdeResult};
+use crate::math_utils::norm_cdf;
+
+/// Spot, rates and volatility of a currency pair
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FxMarket {
+    /// Domestic units per unit of foreign currency
+    pub spot: f64,
+    pub r_domestic: f64,
+    pub r_foreign: f64,
+    pub sigma: f64,
+}
+
+impl FxMarket {
+    /// Validate spot, rates and volatility
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_positive("spot", self.spot)?;
+        validate_finite("r_domestic", self.r_domestic)?;
+        validate_finite("r_foreign", self.r_foreign)?;
+        validate_positive("sigma", self.sigma)?;
+        Ok(())
+    }
+
+    /// Outright forward `S e^{(r_d - r_f)T}`
+    pub fn forward(&self, t: f64) -> f64 {
+        self.spot * ((self.r_domestic - self.r_foreign) * t).exp()
+    }
+}
+
+/// Convention of a reported FX delta
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeltaConvention {
+    /// `∂V/∂S`
+    #[default]
+    Spot,
+    /// `∂V/∂S` in forward terms, `e^{r_f T}` times the spot delta
+    Forward,
+    /// Spot delta less the premium in foreign currency, `V/S`
+    SpotPremiumAdjusted,
+    /// Forward delta less the premium in foreign currency
+    ForwardPremiumAdjusted,
+}
+
+impl DeltaConvention {
+    /// Convert a spot delta of an option worth `price` at `spot` into this
+    /// convention
+    pub fn from_spot_delta(self, spot_delta: f64, price: f64, spot: f64, r_f: f64, t: f64) -> f64 {
+        let forward_factor = (r_f * t).exp();
+        match self {
+            DeltaConvention::Spot => spot_delta,
+            DeltaConvention::Forward => spot_delta * forward_factor,
+            DeltaConvention::SpotPremiumAdjusted => spot_delta - price / spot,
+            DeltaConvention::ForwardPremiumAdjusted => (spot_delta - price / spot) * forward_factor,
+        }
+    }
+}
+
+/// Price and sensitivities of an FX option
+///
+/// Greeks that were not requested (Monte Carlo) are `None`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FxGreeks {
+    pub price: f64,
+    /// Delta in `delta_convention`
+    pub delta: Option<f64>,
+    pub delta_convention: DeltaConvention,
+    pub gamma: Option<f64>,
+    pub vega: Option<f64>,
+    /// `∂V/∂r_d`
+    pub rho_domestic: Option<f64>,
+    /// `∂V/∂r_f`
+    pub rho_foreign: Option<f64>,
+}
+
+fn d1_d2(s: f64, k: f64, r_d: f64, r_f: f64, sigma: f64, t: f64) -> (f64, f64) {
+    let d1 = ((s / k).ln() + (r_d - r_f + 0.5 * sigma * sigma) * t) / (sigma * t.sqrt());
+    (d1, d1 - sigma * t.sqrt())
+}
+
+/// Garman-Kohlhagen call price
+pub fn gk_call_price(s: f64, k: f64, r_d: f64, r_f: f64, sigma: f64, t: f64) -> f64 {
+    let (d1, d2) = d1_d2(s, k, r_d, r_f, sigma, t);
+    s * (-r_f * t).exp() * norm_cdf(d1) - k * (-r_d * t).exp() * norm_cdf(d2)
+}
+
+/// Garman-Kohlhagen put price
+pub fn gk_put_price(s: f64, k: f64, r_d: f64, r_f: f64, sigma: f64, t: f64) -> f64 {
+    let (d1, d2) = d1_d2(s, k, r_d, r_f, sigma, t);
+    k * (-r_d * t).exp() * norm_cdf(-d2) - s * (-r_f * t).exp() * norm_cdf(-d1)
+}
+
+/// Garman-Kohlhagen price and all Greeks of a call (`call = true`) or put,
+/// with the delta in `convention`
+pub fn gk_greeks(
+    market: &FxMarket,
+    call: bool,
+    k: f64,
+    t: f64,
+    convention: DeltaConvention,
+) -> FxGreeks {
+    let FxMarket {
+        spot: s,
+        r_domestic: r_d,
+        r_foreign: r_f,
+        sigma,
+    } = *market;
+    let (d1, d2) = d1_d2(s, k, r_d, r_f, sigma, t);
+    let omega = if call { 1.0 } else { -1.0 };
+    let (foreign, domestic) = ((-r_f * t).exp(), (-r_d * t).exp());
+    let price = omega * (s * foreign * norm_cdf(omega * d1) - k * domestic * norm_cdf(omega * d2));
+    let spot_delta = omega * foreign * norm_cdf(omega * d1);
+    FxGreeks {
+        price,
+        delta: Some(convention.from_spot_delta(spot_delta, price, s, r_f, t)),
+        delta_convention: convention,
+        gamma: Some(foreign * norm_pdf(d1) / (s * sigma * t.sqrt())),
+        vega: Some(s * foreign * norm_pdf(d1) * t.sqrt()),
+        rho_domestic: Some(omega * k * t * domestic * norm_cdf(omega * d2)),
+        rho_foreign: Some(-omega * s * t * foreign * norm_cdf(omega * d1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::bs_analytic::bs_call_price;
+
+    #[test]
+    fn test_garman_kohlhagen_parity_and_deltas() {
+        let (s, k, r_d, r_f, sigma, t) = (1.10, 1.12, 0.04, 0.025, 0.12, 0.75);
+        let call = gk_call_price(s, k, r_d, r_f, sigma, t);
+        let put = gk_put_price(s, k, r_d, r_f, sigma, t);
+        let parity = s * (-r_f * t).exp() - k * (-r_d * t).exp();
+        assert!((call - put - parity).abs() < 1e-14);
+        // No foreign rate: Black-Scholes
+        assert!(
+            (gk_call_price(s, k, r_d, 0.0, sigma, t) - bs_call_price(s, k, r_d, sigma, t)).abs()
+                < 1e-14
+        );
+
+        let market = FxMarket {
+            spot: s,
+            r_domestic: r_d,
+            r_foreign: r_f,
+            sigma,
+        };
+        let forward = market.forward(t);
+        let (d1, d2) = d1_d2(s, k, r_d, r_f, sigma, t);
+        let greeks = |call, convention| gk_greeks(&market, call, k, t, convention);
+        let expected = [
+            (DeltaConvention::Spot, (-r_f * t).exp() * norm_cdf(d1)),
+            (DeltaConvention::Forward, norm_cdf(d1)),
+            (
+                DeltaConvention::SpotPremiumAdjusted,
+                k / s * (-r_d * t).exp() * norm_cdf(d2),
+            ),
+            (
+                DeltaConvention::ForwardPremiumAdjusted,
+                k / forward * norm_cdf(d2),
+            ),
+        ];
+        for (convention, delta) in expected {
+            assert!((greeks(true, convention).delta.unwrap() - delta).abs() < 1e-12);
+        }
+        let put_pa = greeks(false, DeltaConvention::ForwardPremiumAdjusted);
+        assert!((put_pa.delta.unwrap() + k / forward * norm_cdf(-d2)).abs() < 1e-12);
+
+        // Spot delta and the rates by central differences
+        let h = 1e-6;
+        let spot = greeks(true, DeltaConvention::Spot);
+        let bumped =
+            |ds: f64, dd: f64, df: f64| gk_call_price(s + ds, k, r_d + dd, r_f + df, sigma, t);
+        let fd = |f: &dyn Fn(f64) -> f64| (f(h) - f(-h)) / (2.0 * h);
+        assert!((spot.delta.unwrap() - fd(&|e| bumped(e, 0.0, 0.0))).abs() < 1e-7);
+        assert!((spot.rho_domestic.unwrap() - fd(&|e| bumped(0.0, e, 0.0))).abs() < 1e-7);
+        assert!((spot.rho_foreign.unwrap() - fd(&|e| bumped(0.0, 0.0, e))).abs() < 1e-7);
+    }
+}
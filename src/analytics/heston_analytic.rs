@@ -0,0 +1,265 @@
+// src/analytics/heston_analytic.rs
+//! Semi-Analytic Heston Prices (COS Method)
+//!
+//! # Characteristic Function
+//!
+//! The characteristic function of `X_T = ln(S_T/S_0)` is written in the
+//! "little trap" form of Albrecher et al., which avoids the branch cut of
+//! the complex logarithm for long maturities:
+//! ```text
+//! β = κ - ρξiu,   d = √(β² + ξ²(iu + u²)),   g = (β - d)/(β + d)
+//! C = iurT + κθ/ξ² [(β - d)T - 2 ln((1 - g e^(-dT))/(1 - g))]
+//! D = (β - d)/ξ² · (1 - e^(-dT))/(1 - g e^(-dT))
+//! φ(u) = exp(C + D v₀)
+//! ```
+//!
+//! # COS Expansion
+//!
+//! Fang & Oosterlee (2008) expand the density of `y = ln(S_T/K)` in a
+//! cosine series on `[a, b] = x + c₁ ± L√c₂`, from the first two cumulants
+//! of `ln(S_T/S_0)`, and integrate the payoff against each term in closed
+//! form. The log-price has exponential rather than Gaussian tails, so the
+//! range is wide (`L = 24`):
+//! ```text
+//! P = K e^(-rT) Σ'_k Re[φ(u_k) e^(iu_k(x - a))] · 2/(b - a) · (ψ_k - χ_k)
+//! u_k = kπ/(b - a),   x = ln(S_0/K)
+//! ```
+//! where `Σ'` halves the `k = 0` term. Puts are priced by the expansion
+//! (their payoff is bounded, so truncation errors stay small) and calls
+//! by put-call parity. The error decays exponentially in the number of
+//! terms; 512 terms give about `1e-8` for typical parameters.
+//!
+//! # Implied Volatility Surface
+//!
+//! [`heston_implied_vol_surface`] prices calls on a strike × maturity grid
+//! and inverts each with the Black-Scholes implied volatility solver, for
+//! inspecting the smile a parameter set produces or comparing it with
+//! market quotes after a calibration. Far wings whose prices fall below the
+//! COS accuracy have no meaningful implied volatility and are reported as
+//! errors rather than returned as noise.
+
+use crate::analytics::bs_analytic::bs_call_implied_vol;
+use crate::analytics::vol_surface::VolSurface;
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::models::heston::{Heston, HestonParams};
+use nalgebra::Complex;
+use ndarray::Array2;
+use std::f64::consts::PI;
+
+/// Number of cosine terms
+const COS_TERMS: usize = 512;
+/// Half-width of the truncation range in standard deviations
+const TRUNCATION_WIDTH: f64 = 24.0;
+
+/// Characteristic function `E[exp(iu ln(S_T/S_0))]` at maturity `t`
+pub fn heston_char_fn(params: &HestonParams, t: f64, u: f64) -> Complex<f64> {
+    let HestonParams {
+        r,
+        v0,
+        kappa,
+        theta,
+        xi,
+        rho,
+        ..
+    } = *params;
+    let iu = Complex::new(0.0, u);
+    let one = Complex::new(1.0, 0.0);
+    let beta = kappa - rho * xi * iu;
+    let d = (beta * beta + xi * xi * (iu + u * u)).sqrt();
+    let g = (beta - d) / (beta + d);
+    let e = (-d * t).exp();
+    let c = iu * r * t
+        + kappa * theta / (xi * xi) * ((beta - d) * t - 2.0 * ((one - g * e) / (one - g)).ln());
+    let big_d = (beta - d) / (xi * xi) * (one - e) / (one - g * e);
+    (c + big_d * v0).exp()
+}
+
+/// Heston European put price by the COS method
+///
+/// # Errors
+///
+/// Returns `SdeError::InvalidParameters` for invalid model parameters,
+/// strike or maturity, and `SdeError::NumericalInstability` if the
+/// expansion does not produce a finite price.
+pub fn heston_put_price(params: &HestonParams, k: f64, t: f64) -> SdeResult<f64> {
+    Heston::validate_params(params)?;
+    validate_positive("strike", k)?;
+    validate_positive("time_to_expiry", t)?;
+
+    let x = (params.s0 / k).ln();
+    let (c1, c2) = cumulants(params, t);
+    let width = TRUNCATION_WIDTH * c2.abs().max(f64::EPSILON).sqrt();
+    let (a, b) = (x + c1 - width, x + c1 + width);
+    // The put pays K(1 - e^y) on y < 0
+    let upper = b.min(0.0).max(a);
+
+    let mut sum = 0.0;
+    for j in 0..COS_TERMS {
+        let u = j as f64 * PI / (b - a);
+        let coefficient = (heston_char_fn(params, t, u) * Complex::new(0.0, u * (x - a)).exp()).re;
+        let term = coefficient * (psi(u, a, a, upper) - chi(u, a, a, upper));
+        sum += if j == 0 { 0.5 * term } else { term };
+    }
+    let price = k * (-params.r * t).exp() * 2.0 / (b - a) * sum;
+    if !price.is_finite() {
+        return Err(SdeError::NumericalInstability {
+            method: "Heston COS".to_string(),
+            reason: format!("non-finite put price for strike {} and maturity {}", k, t),
+        });
+    }
+    // Truncation can leave a slightly negative price for far OTM puts
+    Ok(price.max(0.0))
+}
+
+/// Heston European call price by the COS method and put-call parity
+///
+/// # Errors
+///
+/// Same as [`heston_put_price`].
+pub fn heston_call_price(params: &HestonParams, k: f64, t: f64) -> SdeResult<f64> {
+    let put = heston_put_price(params, k, t)?;
+    Ok((put + params.s0 - k * (-params.r * t).exp()).max(0.0))
+}
+
+/// Black-Scholes implied volatilities of Heston call prices on the grid of
+/// `strikes` × `maturities`
+///
+/// # Errors
+///
+/// Returns `SdeError::InvalidParameters` for invalid model parameters,
+/// strikes or maturities, `SdeError::NumericalInstability` at the first
+/// grid point whose price has no implied volatility, and the errors of
+/// [`VolSurface::new`] for grids that are not strictly increasing.
+pub fn heston_implied_vol_surface(
+    params: &HestonParams,
+    strikes: Vec<f64>,
+    maturities: Vec<f64>,
+) -> SdeResult<VolSurface> {
+    Heston::validate_params(params)?;
+    let mut vols = Array2::zeros((maturities.len(), strikes.len()));
+    for ((i, j), vol) in vols.indexed_iter_mut() {
+        let (k, t) = (strikes[j], maturities[i]);
+        let call = heston_call_price(params, k, t)?;
+        *vol = bs_call_implied_vol(call, params.s0, k, params.r, t).map_err(|err| {
+            SdeError::NumericalInstability {
+                method: "Heston implied volatility surface".to_string(),
+                reason: format!(
+                    "no implied volatility at strike {} and maturity {}: {}",
+                    k, t, err
+                ),
+            }
+        })?;
+    }
+    VolSurface::new(strikes, maturities, vols)
+}
+
+/// First two cumulants of `ln(S_T/S_0)` (Fang & Oosterlee, appendix A)
+fn cumulants(params: &HestonParams, t: f64) -> (f64, f64) {
+    let HestonParams {
+        r,
+        v0,
+        kappa,
+        theta,
+        xi,
+        rho,
+        ..
+    } = *params;
+    let e1 = (-kappa * t).exp();
+    let c1 = r * t + (1.0 - e1) * (theta - v0) / (2.0 * kappa) - 0.5 * theta * t;
+    let c2 = (xi * t * kappa * e1 * (v0 - theta) * (8.0 * kappa * rho - 4.0 * xi)
+        + kappa * rho * xi * (1.0 - e1) * (16.0 * theta - 8.0 * v0)
+        + 2.0 * theta * kappa * t * (-4.0 * kappa * rho * xi + xi * xi + 4.0 * kappa * kappa)
+        + xi * xi * ((theta - 2.0 * v0) * e1 * e1 + theta * (6.0 * e1 - 7.0) + 2.0 * v0)
+        + 8.0 * kappa * kappa * (v0 - theta) * (1.0 - e1))
+        / (8.0 * kappa.powi(3));
+    (c1, c2)
+}
+
+/// `∫_c^d e^y cos(u(y - a)) dy`
+fn chi(u: f64, a: f64, c: f64, d: f64) -> f64 {
+    let (ud, uc) = (u * (d - a), u * (c - a));
+    ((ud.cos() + u * ud.sin()) * d.exp() - (uc.cos() + u * uc.sin()) * c.exp()) / (1.0 + u * u)
+}
+
+/// `∫_c^d cos(u(y - a)) dy`
+fn psi(u: f64, a: f64, c: f64, d: f64) -> f64 {
+    if u == 0.0 {
+        d - c
+    } else {
+        ((u * (d - a)).sin() - (u * (c - a)).sin()) / u
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cos_matches_reference_prices() {
+        // Fang & Oosterlee (2008), section 5.2: reference call 5.785155450
+        let params = HestonParams {
+            s0: 100.0,
+            v0: 0.0175,
+            r: 0.0,
+            kappa: 1.5768,
+            theta: 0.0398,
+            xi: 0.5751,
+            rho: -0.5711,
+        };
+        let call = heston_call_price(&params, 100.0, 1.0).expect("Valid parameters");
+        assert!((call - 5.785155450).abs() < 1e-7, "{}", call);
+
+        // Put-call parity and φ(0) = 1
+        let with_rate = HestonParams { r: 0.05, ..params };
+        let (k, t) = (110.0, 2.0);
+        let call = heston_call_price(&with_rate, k, t).expect("Valid parameters");
+        let put = heston_put_price(&with_rate, k, t).expect("Valid parameters");
+        assert!((call - put - (100.0 - k * (-0.05 * t).exp())).abs() < 1e-10);
+        assert!((heston_char_fn(&with_rate, t, 0.0) - 1.0).norm() < 1e-14);
+
+        // Vanishing vol-of-vol with v0 = θ is Black-Scholes at σ² = θ
+        let flat = HestonParams {
+            v0: 0.04,
+            theta: 0.04,
+            xi: 1e-4,
+            ..with_rate
+        };
+        let bs = crate::analytics::bs_analytic::bs_call_price(100.0, k, 0.05, 0.2, t);
+        let call = heston_call_price(&flat, k, t).expect("Valid parameters");
+        assert!((call - bs).abs() < 1e-4, "{} vs {}", call, bs);
+    }
+
+    #[test]
+    fn test_implied_vol_surface_reprices_and_skews() {
+        let params = HestonParams {
+            s0: 100.0,
+            v0: 0.04,
+            r: 0.03,
+            kappa: 2.0,
+            theta: 0.06,
+            xi: 0.5,
+            rho: -0.7,
+        };
+        let strikes = vec![70.0, 85.0, 100.0, 115.0, 130.0];
+        let maturities = vec![0.25, 1.0, 3.0];
+        let surface =
+            heston_implied_vol_surface(&params, strikes.clone(), maturities.clone()).unwrap();
+        for &t in &maturities {
+            for &k in &strikes {
+                let call = heston_call_price(&params, k, t).unwrap();
+                assert!((surface.call_price(100.0, 0.03, k, t) - call).abs() < 1e-6);
+            }
+            // Negative spot-vol correlation: downward sloping smile
+            let row: Vec<f64> = strikes.iter().map(|&k| surface.implied_vol(k, t)).collect();
+            assert!(row.windows(2).all(|w| w[0] > w[1]), "{:?}", row);
+        }
+        // ATM variance moves from v0 towards θ with maturity
+        let atm: Vec<f64> = maturities
+            .iter()
+            .map(|&t| surface.implied_vol(100.0, t))
+            .collect();
+        assert!(atm[0] < atm[2] && atm[2] < params.theta.sqrt());
+
+        assert!(heston_implied_vol_surface(&params, vec![100.0, 90.0], vec![1.0]).is_err());
+    }
+}
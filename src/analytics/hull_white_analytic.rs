@@ -0,0 +1,399 @@
+// src/analytics/hull_white_analytic.rs
+//! Closed-Form Hull-White Prices for Caps, Floors and Swaptions
+//!
+//! # Options on Zero-Coupon Bonds
+//!
+//! `ln P(T, S)` is Gaussian, so a call expiring at `T` on the bond maturing
+//! at `S` has a Black-type price (Brigo & Mercurio, 3.40):
+//! ```text
+//! ZBC = P(0,S) Φ(h) - K P(0,T) Φ(h - σ_p)
+//! ZBP = K P(0,T) Φ(σ_p - h) - P(0,S) Φ(-h)
+//! h = ln(P(0,S) / (K P(0,T))) / σ_p + σ_p/2
+//! σ_p = σ √((1 - e^{-2aT}) / 2a) B(T, S)
+//! ```
+//!
+//! # Caps and Floors
+//!
+//! A caplet on the simple rate over `[T, S]` with accrual `τ = S - T` is a
+//! put on the bond `P(T, S)`:
+//! ```text
+//! Caplet   = N (1 + Kτ) ZBP(T, S, 1/(1 + Kτ))
+//! Floorlet = N (1 + Kτ) ZBC(T, S, 1/(1 + Kτ))
+//! ```
+//!
+//! # European Swaptions
+//!
+//! A payer swaption into a swap paying fixed `K` on the dates `t_i` is a
+//! put with strike 1 on the coupon bond with cashflows `c_i = Kτ_i` plus
+//! the notional at `t_n`. Bond prices fall monotonically in the factor, so
+//! Jamshidian's decomposition splits it into zero-coupon bond options:
+//! ```text
+//! Σ_i c_i P(T, t_i; x*) = 1
+//! Payer    = N Σ_i c_i ZBP(T, t_i, P(T, t_i; x*))
+//! Receiver = N Σ_i c_i ZBC(T, t_i, P(T, t_i; x*))
+//! ```
+
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::math_utils::norm_cdf;
+use crate::models::hull_white::HullWhite;
+
+/// Bisection iterations for the Jamshidian critical factor value
+const JAMSHIDIAN_ITERATIONS: usize = 200;
+
+/// Cap or floor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CapFloorType {
+    Cap,
+    Floor,
+}
+
+/// Right to pay (payer) or receive (receiver) the fixed leg of a swap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SwaptionType {
+    Payer,
+    Receiver,
+}
+
+/// Cap or floor on the simple rate, with one caplet per accrual period
+/// from `start` to `maturity`
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CapFloor {
+    pub kind: CapFloorType,
+    pub start: f64,
+    pub maturity: f64,
+    /// Caplets per year (4 for quarterly)
+    pub payments_per_year: usize,
+    pub strike: f64,
+    pub notional: f64,
+}
+
+impl CapFloor {
+    /// Validate the schedule, strike and notional
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_schedule(self.start, self.maturity, self.payments_per_year)?;
+        validate_finite("strike", self.strike)?;
+        validate_positive("notional", self.notional)?;
+        Ok(())
+    }
+
+    /// Payment dates; the caplet paying at `dates[i]` resets one period
+    /// earlier, or at `start` for the first
+    pub fn payment_dates(&self) -> Vec<f64> {
+        payment_dates(self.start, self.maturity, self.payments_per_year)
+    }
+}
+
+/// European swaption exercising at `expiry` into the swap running to
+/// `maturity`
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Swaption {
+    pub kind: SwaptionType,
+    pub expiry: f64,
+    /// End of the underlying swap
+    pub maturity: f64,
+    /// Fixed payments per year (1 for annual)
+    pub payments_per_year: usize,
+    /// Fixed rate of the underlying swap
+    pub strike: f64,
+    pub notional: f64,
+}
+
+impl Swaption {
+    /// Validate the schedule, strike and notional
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_schedule(self.expiry, self.maturity, self.payments_per_year)?;
+        validate_finite("strike", self.strike)?;
+        validate_positive("notional", self.notional)?;
+        Ok(())
+    }
+
+    /// Fixed leg payment dates after the expiry
+    pub fn payment_dates(&self) -> Vec<f64> {
+        payment_dates(self.expiry, self.maturity, self.payments_per_year)
+    }
+
+    /// Value to the fixed-rate payer of the swap still to run at `t`, the
+    /// expiry or a payment date, given the zero-coupon bond prices
+    /// `bond(t_i)` for the payment dates after `t`:
+    /// ```text
+    /// N (1 - P(t, t_n) - K Σ τ_i P(t, t_i))
+    /// ```
+    pub fn payer_swap_value(&self, t: f64, bond: impl Fn(f64) -> f64) -> f64 {
+        let mut start = t;
+        let mut annuity = 0.0;
+        let mut last = 1.0;
+        for end in self.payment_dates().into_iter().filter(|&d| d > t) {
+            last = bond(end);
+            annuity += (end - start) * last;
+            start = end;
+        }
+        self.notional * (1.0 - last - self.strike * annuity)
+    }
+}
+
+/// Price of a European call (`call = true`) or put on the zero-coupon bond
+/// maturing at `maturity`, expiring at `expiry` with strike `strike`
+pub fn hw_zero_bond_option(
+    model: &HullWhite,
+    call: bool,
+    expiry: f64,
+    maturity: f64,
+    strike: f64,
+) -> f64 {
+    let p_expiry = model.curve.discount(expiry);
+    let p_maturity = model.curve.discount(maturity);
+    let sigma_p = model.bond_volatility(expiry.max(0.0), maturity);
+    if sigma_p <= 0.0 {
+        // Deterministic bond price: discounted intrinsic value
+        let forward = p_maturity - strike * p_expiry;
+        return if call {
+            forward.max(0.0)
+        } else {
+            (-forward).max(0.0)
+        };
+    }
+    let h = (p_maturity / (strike * p_expiry)).ln() / sigma_p + 0.5 * sigma_p;
+    if call {
+        p_maturity * norm_cdf(h) - strike * p_expiry * norm_cdf(h - sigma_p)
+    } else {
+        strike * p_expiry * norm_cdf(sigma_p - h) - p_maturity * norm_cdf(-h)
+    }
+}
+
+/// Price of a single caplet or floorlet resetting at `reset` and paying at
+/// `payment`
+pub fn hw_caplet_price(
+    model: &HullWhite,
+    kind: CapFloorType,
+    reset: f64,
+    payment: f64,
+    strike: f64,
+    notional: f64,
+) -> f64 {
+    let growth = 1.0 + strike * (payment - reset);
+    let call = kind == CapFloorType::Floor;
+    notional * growth * hw_zero_bond_option(model, call, reset, payment, 1.0 / growth)
+}
+
+/// Price of a cap or floor as the sum of its caplets or floorlets
+///
+/// # Errors
+///
+/// Returns `SdeError` for an invalid contract.
+pub fn hw_cap_floor_price(model: &HullWhite, cap: &CapFloor) -> SdeResult<f64> {
+    cap.validate()?;
+    let mut reset = cap.start;
+    let mut price = 0.0;
+    for payment in cap.payment_dates() {
+        price += hw_caplet_price(model, cap.kind, reset, payment, cap.strike, cap.notional);
+        reset = payment;
+    }
+    Ok(price)
+}
+
+/// Forward swap rate and annuity `Σ τ_i P(0, t_i)` of the swap underlying
+/// `swaption`
+pub fn hw_forward_swap_rate(model: &HullWhite, swaption: &Swaption) -> (f64, f64) {
+    let mut start = swaption.expiry;
+    let mut annuity = 0.0;
+    for end in swaption.payment_dates() {
+        annuity += (end - start) * model.curve.discount(end);
+        start = end;
+    }
+    let floating = model.curve.discount(swaption.expiry) - model.curve.discount(swaption.maturity);
+    (floating / annuity, annuity)
+}
+
+/// Price of a European swaption by Jamshidian's decomposition
+///
+/// # Errors
+///
+/// Returns `SdeError` for an invalid contract, or if no critical factor
+/// value prices the coupon bond at par (a non-positive strike).
+pub fn hw_swaption_price(model: &HullWhite, swaption: &Swaption) -> SdeResult<f64> {
+    swaption.validate()?;
+    let expiry = swaption.expiry;
+    let dates = swaption.payment_dates();
+    let mut start = expiry;
+    let coupons: Vec<f64> = dates
+        .iter()
+        .enumerate()
+        .map(|(i, &end)| {
+            let coupon = swaption.strike * (end - start);
+            start = end;
+            if i + 1 == dates.len() {
+                coupon + 1.0
+            } else {
+                coupon
+            }
+        })
+        .collect();
+    let coupon_bond = |x: f64| -> f64 {
+        dates
+            .iter()
+            .zip(&coupons)
+            .map(|(&d, c)| c * model.bond_price(expiry, d, x))
+            .sum()
+    };
+
+    // The coupon bond falls in x; bracket its par crossing, then bisect
+    let (mut lo, mut hi) = (-1.0, 1.0);
+    while coupon_bond(lo) < 1.0 || coupon_bond(hi) > 1.0 {
+        lo *= 2.0;
+        hi *= 2.0;
+        if hi > 1e6 {
+            return Err(SdeError::NumericalInstability {
+                method: "Jamshidian decomposition".to_string(),
+                reason: format!(
+                    "no factor value prices the coupon bond at par for strike {}",
+                    swaption.strike
+                ),
+            });
+        }
+    }
+    for _ in 0..JAMSHIDIAN_ITERATIONS {
+        let mid = 0.5 * (lo + hi);
+        if coupon_bond(mid) > 1.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let critical = 0.5 * (lo + hi);
+
+    let call = swaption.kind == SwaptionType::Receiver;
+    let price: f64 = dates
+        .iter()
+        .zip(&coupons)
+        .map(|(&d, c)| {
+            let strike = model.bond_price(expiry, d, critical);
+            c * hw_zero_bond_option(model, call, expiry, d, strike)
+        })
+        .sum();
+    Ok(swaption.notional * price)
+}
+
+fn validate_schedule(start: f64, maturity: f64, payments_per_year: usize) -> SdeResult<()> {
+    validate_non_negative("start", start)?;
+    if maturity <= start {
+        return Err(SdeError::InvalidParameters {
+            parameter: "maturity".to_string(),
+            value: maturity,
+            constraint: format!("must be after the start {}", start),
+        });
+    }
+    if payments_per_year == 0 {
+        return Err(SdeError::InvalidConfiguration {
+            field: "payments_per_year".to_string(),
+            reason: "must be at least 1".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Payment dates after `start`, with a short first period if the schedule
+/// is not a whole number of periods
+fn payment_dates(start: f64, maturity: f64, payments_per_year: usize) -> Vec<f64> {
+    let period = 1.0 / payments_per_year as f64;
+    let count = ((maturity - start) / period - 1e-9).ceil() as usize;
+    (0..count)
+        .rev()
+        .map(|k| maturity - k as f64 * period)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::hull_white::{HullWhiteParams, YieldCurve};
+
+    #[test]
+    fn test_cap_floor_and_swaption_parity() {
+        let curve = YieldCurve::from_zero_rates(&[1.0, 5.0, 10.0], &[0.02, 0.03, 0.035]).unwrap();
+        let model = HullWhite::new(
+            HullWhiteParams {
+                a: 0.05,
+                sigma: 0.01,
+            },
+            curve,
+        )
+        .unwrap();
+        let p = |t: f64| model.curve.discount(t);
+
+        // Cap - floor = payer swap on the same schedule
+        let cap = CapFloor {
+            kind: CapFloorType::Cap,
+            start: 0.5,
+            maturity: 5.0,
+            payments_per_year: 2,
+            strike: 0.03,
+            notional: 100.0,
+        };
+        let floor = CapFloor {
+            kind: CapFloorType::Floor,
+            ..cap
+        };
+        let mut reset = cap.start;
+        let mut swap = 0.0;
+        for pay in cap.payment_dates() {
+            swap += p(reset) - (1.0 + cap.strike * (pay - reset)) * p(pay);
+            reset = pay;
+        }
+        let difference =
+            hw_cap_floor_price(&model, &cap).unwrap() - hw_cap_floor_price(&model, &floor).unwrap();
+        assert!((difference - cap.notional * swap).abs() < 1e-10);
+
+        // Payer - receiver = forward payer swap; at the money they agree
+        let payer = Swaption {
+            kind: SwaptionType::Payer,
+            expiry: 2.0,
+            maturity: 7.0,
+            payments_per_year: 1,
+            strike: 0.035,
+            notional: 100.0,
+        };
+        let receiver = Swaption {
+            kind: SwaptionType::Receiver,
+            ..payer
+        };
+        let forward =
+            payer.payer_swap_value(payer.expiry, |t| p(t) / p(payer.expiry)) * p(payer.expiry);
+        let parity = hw_swaption_price(&model, &payer).unwrap()
+            - hw_swaption_price(&model, &receiver).unwrap();
+        assert!(
+            (parity - forward).abs() < 1e-10,
+            "{} vs {}",
+            parity,
+            forward
+        );
+
+        let (atm, _) = hw_forward_swap_rate(&model, &payer);
+        let atm_payer = hw_swaption_price(
+            &model,
+            &Swaption {
+                strike: atm,
+                ..payer
+            },
+        )
+        .unwrap();
+        let atm_receiver = hw_swaption_price(
+            &model,
+            &Swaption {
+                strike: atm,
+                ..receiver
+            },
+        )
+        .unwrap();
+        assert!(atm_payer > 0.5 && (atm_payer - atm_receiver).abs() < 1e-10);
+
+        let bad = Swaption {
+            maturity: 1.0,
+            ..payer
+        };
+        assert!(hw_swaption_price(&model, &bad).is_err());
+    }
+}
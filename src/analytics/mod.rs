@@ -1,2 +1,11 @@
-// src/analytics/mod.rs
+pub mod asian_analytic;
 pub mod bs_analytic;
+pub mod checks;
+pub mod fx_analytic;
+pub mod heston_analytic;
+pub mod hull_white_analytic;
+pub mod lattice;
+pub mod merton_analytic;
+pub mod sabr_analytic;
+pub mod svi;
+pub mod vol_surface;
@@ -0,0 +1,560 @@
+// src/analytics/svi.rs
+//! SVI and SSVI Volatility Smiles (Gatheral 2004, Gatheral–Jacquier 2014)
+//!
+//! # Raw SVI
+//!
+//! One smile is parameterized in total variance `w = σ_BS² T` against
+//! log-forward-moneyness `k = ln(K/F)`:
+//! ```text
+//! w(k) = a + b (ρ (k - m) + √((k - m)² + σ²)),   b ≥ 0, |ρ| < 1, σ > 0
+//! ```
+//! It needs `a + bσ√(1 - ρ²) ≥ 0` (non-negative variance) and
+//! `b (1 + |ρ|) ≤ 2` (Lee's moment bound on the wings). Butterfly
+//! arbitrage is absent where
+//! ```text
+//! g(k) = (1 - k w'/(2w))² - w'²/4 (1/w + 1/4) + w''/2 ≥ 0
+//! ```
+//! see [`SviParams::butterfly_factor`].
+//!
+//! # SSVI
+//!
+//! The surface version ties every expiry to its at-the-money total variance
+//! `θ_T`:
+//! ```text
+//! w(k, θ) = θ/2 (1 + ρφ(θ)k + √((φ(θ)k + ρ)² + 1 - ρ²)),   φ(θ) = η / (θ^γ (1 + θ)^(1-γ))
+//! ```
+//! With this power-law `φ`, `η (1 + |ρ|) ≤ 2` and `0 < γ ≤ 1/2` rule out
+//! butterfly arbitrage, and a non-decreasing `θ_T` rules out calendar
+//! arbitrage, so three numbers plus the ATM term structure store a whole
+//! arbitrage-free surface.
+//!
+//! # Fitting
+//!
+//! [`SviFitter`] fits total variances by bounded Levenberg–Marquardt. The
+//! raw SVI constraints that are not box bounds enter as penalty residuals
+//! `p · max(b(1 + |ρ|) - 2, 0)` and `p · max(-(a + bσ√(1 - ρ²)), 0)`.
+//! The SSVI fit first reads `θ_T` off each expiry by linear interpolation
+//! of the quotes at `k = 0`, then fits `(ρ, η, γ)` to all quotes at once.
+
+use crate::analytics::vol_surface::VolSurface;
+use crate::calibration::optimizer::{Bounds, LevenbergMarquardt, OptimizationResult};
+use crate::calibration::quotes::{rmse, validate_vol_quotes, VolFit, VolQuote};
+use crate::error::{validation::*, SdeError, SdeResult};
+
+/// Raw SVI parameters of one smile in total variance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SviParams {
+    pub a: f64,
+    pub b: f64,
+    pub rho: f64,
+    pub m: f64,
+    pub sigma: f64,
+}
+
+impl SviParams {
+    /// Total variance at log-forward-moneyness `k`
+    pub fn total_variance(&self, k: f64) -> f64 {
+        let x = k - self.m;
+        self.a + self.b * (self.rho * x + (x * x + self.sigma * self.sigma).sqrt())
+    }
+
+    /// Implied volatility at log-forward-moneyness `k` and expiry `t`
+    pub fn implied_vol(&self, k: f64, t: f64) -> f64 {
+        (self.total_variance(k).max(0.0) / t).sqrt()
+    }
+
+    /// Gatheral's density factor `g(k)`; the smile is free of butterfly
+    /// arbitrage where it is non-negative
+    pub fn butterfly_factor(&self, k: f64) -> f64 {
+        let x = k - self.m;
+        let root = (x * x + self.sigma * self.sigma).sqrt();
+        let w = self.total_variance(k);
+        let w1 = self.b * (self.rho + x / root);
+        let w2 = self.b * self.sigma * self.sigma / root.powi(3);
+        (1.0 - k * w1 / (2.0 * w)).powi(2) - 0.25 * w1 * w1 * (1.0 / w + 0.25) + 0.5 * w2
+    }
+
+    /// Validate the parameter ranges, non-negative variance and Lee's wing
+    /// bound
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidParameters` naming the violated condition.
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_finite("a", self.a)?;
+        validate_non_negative("b", self.b)?;
+        validate_correlation("rho", self.rho)?;
+        validate_finite("m", self.m)?;
+        validate_positive("sigma", self.sigma)?;
+        let min_variance = self.a + self.b * self.sigma * (1.0 - self.rho * self.rho).sqrt();
+        if min_variance < 0.0 {
+            return Err(SdeError::InvalidParameters {
+                parameter: "a".to_string(),
+                value: self.a,
+                constraint: format!("a + bσ√(1 - ρ²) ≥ 0 (got {})", min_variance),
+            });
+        }
+        let wing = self.b * (1.0 + self.rho.abs());
+        if wing > 2.0 {
+            return Err(SdeError::InvalidParameters {
+                parameter: "b".to_string(),
+                value: self.b,
+                constraint: format!("b (1 + |ρ|) ≤ 2 (got {})", wing),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// SSVI parameters with the power-law `φ(θ)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SsviParams {
+    pub rho: f64,
+    pub eta: f64,
+    pub gamma: f64,
+}
+
+impl SsviParams {
+    /// Curvature `φ(θ) = η / (θ^γ (1 + θ)^(1-γ))`
+    pub fn phi(&self, theta: f64) -> f64 {
+        self.eta / (theta.powf(self.gamma) * (1.0 + theta).powf(1.0 - self.gamma))
+    }
+
+    /// Total variance at log-forward-moneyness `k` and ATM total variance
+    /// `theta`
+    pub fn total_variance(&self, k: f64, theta: f64) -> f64 {
+        let phi_k = self.phi(theta) * k;
+        let rho = self.rho;
+        0.5 * theta * (1.0 + rho * phi_k + ((phi_k + rho).powi(2) + 1.0 - rho * rho).sqrt())
+    }
+
+    /// Validate the ranges that make the surface free of static arbitrage
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidParameters` for `|ρ| ≥ 1`, `η ≤ 0`,
+    /// `γ ∉ (0, 1/2]` or `η (1 + |ρ|) > 2`.
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_correlation("rho", self.rho)?;
+        validate_positive("eta", self.eta)?;
+        validate_positive("gamma", self.gamma)?;
+        validate_range("gamma", self.gamma, 0.0, 0.5)?;
+        let wing = self.eta * (1.0 + self.rho.abs());
+        if wing > 2.0 {
+            return Err(SdeError::InvalidParameters {
+                parameter: "eta".to_string(),
+                value: self.eta,
+                constraint: format!("η (1 + |ρ|) ≤ 2 (got {})", wing),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Raw SVI fit of one expiry
+#[derive(Debug, Clone)]
+pub struct SviSlice {
+    pub forward: f64,
+    pub time_to_expiry: f64,
+    pub params: SviParams,
+    /// Root mean squared implied volatility error over the quotes
+    pub rmse: f64,
+    /// Per-quote model volatilities and errors, in quote order
+    pub fits: Vec<VolFit>,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+impl SviSlice {
+    /// Fitted total variance at `strike`
+    pub fn total_variance(&self, strike: f64) -> f64 {
+        self.params.total_variance((strike / self.forward).ln())
+    }
+
+    /// Fitted implied volatility at `strike`
+    pub fn implied_vol(&self, strike: f64) -> f64 {
+        self.params
+            .implied_vol((strike / self.forward).ln(), self.time_to_expiry)
+    }
+}
+
+/// SSVI fit of a set of expiries
+#[derive(Debug, Clone)]
+pub struct SsviSurface {
+    pub spot: f64,
+    pub rate: f64,
+    pub params: SsviParams,
+    /// Fitted expiries, increasing
+    pub maturities: Vec<f64>,
+    /// ATM total variance `θ_T` of each expiry, non-decreasing
+    pub atm_variances: Vec<f64>,
+    /// Root mean squared implied volatility error over the quotes
+    pub rmse: f64,
+    /// Per-quote model volatilities and errors, in quote order
+    pub fits: Vec<VolFit>,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+impl SsviSurface {
+    /// ATM total variance at `t`, linear between fitted expiries and with
+    /// flat ATM volatility outside them
+    pub fn theta(&self, t: f64) -> f64 {
+        let (ts, thetas) = (&self.maturities, &self.atm_variances);
+        let last = ts.len() - 1;
+        if t <= ts[0] {
+            return thetas[0] * t / ts[0];
+        }
+        if t >= ts[last] {
+            return thetas[last] * t / ts[last];
+        }
+        let i = ts.partition_point(|&x| x <= t) - 1;
+        let b = (t - ts[i]) / (ts[i + 1] - ts[i]);
+        (1.0 - b) * thetas[i] + b * thetas[i + 1]
+    }
+
+    /// Total variance at `strike` and `t > 0`
+    pub fn total_variance(&self, strike: f64, t: f64) -> f64 {
+        let k = (strike / (self.spot * (self.rate * t).exp())).ln();
+        self.params.total_variance(k, self.theta(t))
+    }
+
+    /// Implied volatility at `strike` and `t > 0`
+    pub fn implied_vol(&self, strike: f64, t: f64) -> f64 {
+        (self.total_variance(strike, t) / t).sqrt()
+    }
+
+    /// Sample the surface on a grid, e.g. for
+    /// [`VolSurface::local_vol`]
+    ///
+    /// # Errors
+    ///
+    /// Same as [`VolSurface::new`].
+    pub fn to_vol_surface(&self, strikes: Vec<f64>, maturities: Vec<f64>) -> SdeResult<VolSurface> {
+        VolSurface::from_fn(strikes, maturities, |k, t| self.implied_vol(k, t))
+    }
+}
+
+/// Least-squares SVI and SSVI fitter
+#[derive(Debug, Clone)]
+pub struct SviFitter {
+    /// Weight `p` of the raw SVI constraint residuals
+    pub arbitrage_penalty: f64,
+    pub optimizer: LevenbergMarquardt,
+}
+
+impl Default for SviFitter {
+    fn default() -> Self {
+        SviFitter {
+            arbitrage_penalty: 10.0,
+            optimizer: LevenbergMarquardt::default(),
+        }
+    }
+}
+
+impl SviFitter {
+    /// Fit a raw SVI smile to the quotes of one expiry with forward
+    /// `forward`
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` for fewer than 5 quotes or
+    /// quotes with different expiries, and `SdeError` for invalid quotes,
+    /// forward or penalty.
+    pub fn fit_slice(&self, forward: f64, quotes: &[VolQuote]) -> SdeResult<SviSlice> {
+        validate_positive("forward", forward)?;
+        validate_vol_quotes(quotes, 5)?;
+        let t = single_expiry(quotes)?;
+        let ks: Vec<f64> = quotes.iter().map(|q| (q.strike / forward).ln()).collect();
+        let ws: Vec<f64> = quotes.iter().map(|q| q.implied_vol.powi(2) * t).collect();
+        let (params, fit) = self.fit_raw(&ks, &ws)?;
+
+        let fits: Vec<VolFit> = quotes
+            .iter()
+            .zip(&ks)
+            .map(|(&q, &k)| VolFit::new(q, params.implied_vol(k, t)))
+            .collect();
+        Ok(SviSlice {
+            forward,
+            time_to_expiry: t,
+            params,
+            rmse: rmse(fits.iter().map(|f| f.error)),
+            fits,
+            iterations: fit.iterations,
+            converged: fit.converged,
+        })
+    }
+
+    /// Fit an SSVI surface to quotes of one or more expiries, with forwards
+    /// `spot · e^(rate·T)`
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` if the ATM total variance
+    /// decreases between expiries (calendar arbitrage in the quotes), and
+    /// `SdeError` for invalid quotes, spot or rate.
+    pub fn fit_ssvi(&self, spot: f64, rate: f64, quotes: &[VolQuote]) -> SdeResult<SsviSurface> {
+        validate_positive("spot", spot)?;
+        validate_finite("rate", rate)?;
+        validate_vol_quotes(quotes, 3)?;
+        let mut maturities: Vec<f64> = quotes.iter().map(|q| q.time_to_expiry).collect();
+        maturities.sort_by(f64::total_cmp);
+        maturities.dedup();
+
+        let log_moneyness =
+            |q: &VolQuote| (q.strike / (spot * (rate * q.time_to_expiry).exp())).ln();
+        let atm_variances: Vec<f64> = maturities
+            .iter()
+            .map(|&t| {
+                let mut smile: Vec<(f64, f64)> = quotes
+                    .iter()
+                    .filter(|q| q.time_to_expiry == t)
+                    .map(|q| (log_moneyness(q), q.implied_vol.powi(2) * t))
+                    .collect();
+                smile.sort_by(|a, b| a.0.total_cmp(&b.0));
+                atm_value(&smile)
+            })
+            .collect();
+        if let Some(i) = (1..maturities.len()).find(|&i| atm_variances[i] < atm_variances[i - 1]) {
+            return Err(SdeError::InvalidConfiguration {
+                field: "quotes".to_string(),
+                reason: format!(
+                    "ATM total variance decreases from {} at T = {} to {} at T = {}",
+                    atm_variances[i - 1],
+                    maturities[i - 1],
+                    atm_variances[i],
+                    maturities[i]
+                ),
+            });
+        }
+
+        let mut surface = SsviSurface {
+            spot,
+            rate,
+            params: SsviParams {
+                rho: 0.0,
+                eta: 1.0,
+                gamma: 0.25,
+            },
+            maturities,
+            atm_variances,
+            rmse: 0.0,
+            fits: Vec::new(),
+            iterations: 0,
+            converged: false,
+        };
+        let with_vector = |x: &[f64]| SsviParams {
+            rho: x[0],
+            eta: x[1],
+            gamma: x[2],
+        };
+        let residuals = |x: &[f64]| -> SdeResult<Vec<f64>> {
+            let params = with_vector(x);
+            let mut r: Vec<f64> = quotes
+                .iter()
+                .map(|q| {
+                    let theta = surface.theta(q.time_to_expiry);
+                    let w = params.total_variance(log_moneyness(q), theta);
+                    w - q.implied_vol.powi(2) * q.time_to_expiry
+                })
+                .collect();
+            let wing = params.eta * (1.0 + params.rho.abs()) - 2.0;
+            r.push(self.arbitrage_penalty * wing.max(0.0));
+            Ok(r)
+        };
+        let bounds = Bounds::new(vec![-0.999, 1e-4, 1e-3], vec![0.999, 2.0, 0.5])?;
+        let fit = self
+            .optimizer
+            .minimize(residuals, &[0.0, 1.0, 0.25], &bounds)?;
+
+        surface.params = with_vector(&fit.x);
+        surface.fits = quotes
+            .iter()
+            .map(|&q| VolFit::new(q, surface.implied_vol(q.strike, q.time_to_expiry)))
+            .collect();
+        surface.rmse = rmse(surface.fits.iter().map(|f| f.error));
+        surface.iterations = fit.iterations;
+        surface.converged = fit.converged;
+        Ok(surface)
+    }
+
+    /// Fit raw SVI to total variances `ws` at log-moneyness `ks`
+    ///
+    /// `ks` may be shifted by any constant (e.g. `ln K` instead of
+    /// `ln(K/F)`); `m` absorbs the shift.
+    pub(crate) fn fit_raw(
+        &self,
+        ks: &[f64],
+        ws: &[f64],
+    ) -> SdeResult<(SviParams, OptimizationResult)> {
+        validate_non_negative("arbitrage_penalty", self.arbitrage_penalty)?;
+        let (k_min, k_max) = ks
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &k| {
+                (lo.min(k), hi.max(k))
+            });
+        let w_max = ws.iter().copied().fold(0.0, f64::max);
+        let (k_low, w_low) = ks
+            .iter()
+            .zip(ws)
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .map_or((0.0, 0.0), |(&k, &w)| (k, w));
+
+        let with_vector = |x: &[f64]| SviParams {
+            a: x[0],
+            b: x[1],
+            rho: x[2],
+            m: x[3],
+            sigma: x[4],
+        };
+        let residuals = |x: &[f64]| -> SdeResult<Vec<f64>> {
+            let params = with_vector(x);
+            let mut r: Vec<f64> = ks
+                .iter()
+                .zip(ws)
+                .map(|(&k, &w)| params.total_variance(k) - w)
+                .collect();
+            let wing = params.b * (1.0 + params.rho.abs()) - 2.0;
+            let min_variance =
+                params.a + params.b * params.sigma * (1.0 - params.rho * params.rho).sqrt();
+            r.push(self.arbitrage_penalty * wing.max(0.0));
+            r.push(self.arbitrage_penalty * (-min_variance).max(0.0));
+            Ok(r)
+        };
+        let width = (k_max - k_min).max(1e-2);
+        let bounds = Bounds::new(
+            vec![-w_max, 0.0, -0.999, k_min - width, 1e-4],
+            vec![w_max, 2.0, 0.999, k_max + width, 2.0],
+        )?;
+        let x0 = [w_low - 0.01, 0.1, 0.0, k_low, 0.1];
+        let fit = self.optimizer.minimize(residuals, &x0, &bounds)?;
+        Ok((with_vector(&fit.x), fit))
+    }
+}
+
+/// Common expiry of `quotes`
+fn single_expiry(quotes: &[VolQuote]) -> SdeResult<f64> {
+    let t = quotes[0].time_to_expiry;
+    if quotes.iter().any(|q| q.time_to_expiry != t) {
+        return Err(SdeError::InvalidConfiguration {
+            field: "quotes".to_string(),
+            reason: "an SVI slice needs quotes of a single expiry".to_string(),
+        });
+    }
+    Ok(t)
+}
+
+/// Value at `k = 0` of points sorted by `k`, linear between the bracketing
+/// points and flat outside them
+fn atm_value(points: &[(f64, f64)]) -> f64 {
+    let i = points.partition_point(|p| p.0 <= 0.0);
+    if i == 0 {
+        return points[0].1;
+    }
+    if i == points.len() {
+        return points[i - 1].1;
+    }
+    let ((k0, w0), (k1, w1)) = (points[i - 1], points[i]);
+    w0 + (w1 - w0) * (0.0 - k0) / (k1 - k0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svi_fit_recovers_smile() {
+        let truth = SviParams {
+            a: 0.02,
+            b: 0.15,
+            rho: -0.4,
+            m: 0.05,
+            sigma: 0.2,
+        };
+        assert!(truth.validate().is_ok());
+        let (forward, t) = (105.0, 0.75);
+        let quotes: Vec<VolQuote> = (0..11)
+            .map(|i| {
+                let strike = 60.0 + 10.0 * i as f64;
+                VolQuote {
+                    strike,
+                    time_to_expiry: t,
+                    implied_vol: truth.implied_vol((strike / forward).ln(), t),
+                }
+            })
+            .collect();
+
+        let slice = SviFitter::default()
+            .fit_slice(forward, &quotes)
+            .expect("Valid quotes");
+        assert!(slice.rmse < 1e-9, "rmse {}", slice.rmse);
+        let p = slice.params;
+        for (fitted, expected) in [
+            (p.a, truth.a),
+            (p.b, truth.b),
+            (p.rho, truth.rho),
+            (p.m, truth.m),
+            (p.sigma, truth.sigma),
+        ] {
+            assert!((fitted - expected).abs() < 1e-5, "{:?}", p);
+        }
+        assert!((0..=20).all(|i| p.butterfly_factor(-1.0 + 0.1 * i as f64) > 0.0));
+
+        let steep = SviParams { b: 1.8, ..truth };
+        assert!(steep.validate().is_err());
+        assert!(SviFitter::default()
+            .fit_slice(forward, &quotes[..4])
+            .is_err());
+    }
+
+    #[test]
+    fn test_ssvi_fit_recovers_surface() {
+        let truth = SsviParams {
+            rho: -0.5,
+            eta: 1.2,
+            gamma: 0.4,
+        };
+        assert!(truth.validate().is_ok());
+        let spot = 100.0;
+        let terms = [(0.25, 0.2), (1.0, 0.22), (2.0, 0.23)];
+        let quotes: Vec<VolQuote> = terms
+            .iter()
+            .flat_map(|&(t, atm_vol)| {
+                (0..9).map(move |i| {
+                    let strike = 60.0 + 10.0 * i as f64;
+                    let w = truth.total_variance((strike / spot).ln(), atm_vol * atm_vol * t);
+                    VolQuote {
+                        strike,
+                        time_to_expiry: t,
+                        implied_vol: (w / t).sqrt(),
+                    }
+                })
+            })
+            .collect();
+
+        let surface = SviFitter::default()
+            .fit_ssvi(spot, 0.0, &quotes)
+            .expect("Valid quotes");
+        assert!(surface.rmse < 1e-9, "rmse {}", surface.rmse);
+        let p = surface.params;
+        assert!((p.rho - truth.rho).abs() < 1e-6, "{:?}", p);
+        assert!((p.eta - truth.eta).abs() < 1e-6, "{:?}", p);
+        assert!((p.gamma - truth.gamma).abs() < 1e-6, "{:?}", p);
+        assert!((surface.theta(1.0) - 0.22f64.powi(2)).abs() < 1e-15);
+
+        let grid = surface
+            .to_vol_surface(
+                (0..9).map(|i| 70.0 + 7.5 * i as f64).collect(),
+                vec![0.25, 0.5, 1.0, 1.5, 2.0],
+            )
+            .expect("Valid grid");
+        assert!(grid.check_arbitrage(spot, 0.0).is_ok());
+        assert!(grid.local_vol(spot, 0.0, 95.0, 0.8).is_ok());
+
+        let mut inverted = quotes.clone();
+        for q in inverted.iter_mut().filter(|q| q.time_to_expiry == 2.0) {
+            q.implied_vol *= 0.5;
+        }
+        assert!(SviFitter::default().fit_ssvi(spot, 0.0, &inverted).is_err());
+    }
+}
@@ -0,0 +1,487 @@
+// src/analytics/vol_surface.rs
+//! Implied Volatility Surface
+//!
+//! # Grid and Interpolation
+//!
+//! A [`VolSurface`] stores Black-Scholes implied volatilities `σ_ij` on a
+//! grid of maturities `T_i` × strikes `K_j` and interpolates bilinearly in
+//! total variance `w = σ² T`:
+//! ```text
+//! w_i(K) = T_i [(1 - a) σ²_ij + a σ²_i,j+1],       a = (K - K_j)/(K_j+1 - K_j)
+//! w(K, T) = (1 - b) w_i(K) + b w_i+1(K),            b = (T - T_i)/(T_i+1 - T_i)
+//! ```
+//! Linear total variance in maturity keeps a calendar-free grid
+//! calendar-free between its nodes. Outside the grid the volatility is
+//! extrapolated flat: strikes are clamped to `[K_0, K_n]` and maturities
+//! before `T_0` or after `T_m` keep that slice's volatility.
+//!
+//! [`VolSurface::with_svi_interpolation`] replaces the linear strike
+//! interpolation by a raw [SVI](crate::analytics::svi) fit of every
+//! maturity slice, which is smooth in strike (so the local volatility has
+//! no kinks at the grid strikes) at the cost of no longer matching the
+//! grid exactly.
+//!
+//! # Static Arbitrage
+//!
+//! [`VolSurface::arbitrage_violations`] checks the call prices implied by
+//! the grid for
+//! - call spreads: `C` non-increasing in `K` with slope `≥ -e^(-rT)`
+//! - butterflies: `C` convex in `K`
+//! - calendars: total variance non-decreasing in `T` at fixed forward
+//!   moneyness `K / (S e^(rT))`
+//!
+//! # Local Volatility
+//!
+//! [`VolSurface::local_vol`] evaluates Dupire's formula in total variance
+//! and log-forward-moneyness `y = ln(K/F_T)`:
+//! ```text
+//! σ_loc²(K, T) = ∂_T w / [1 - (y/w) ∂_y w + (-1/4 - 1/w + y²/w²)(∂_y w)²/4 + ∂_yy w/2]
+//! ```
+//! with derivatives by central differences on the interpolated surface.
+
+use crate::analytics::bs_analytic::bs_call_price;
+use crate::analytics::svi::{SviFitter, SviParams};
+use crate::error::{validation::*, SdeError, SdeResult};
+use ndarray::Array2;
+
+/// Tolerance of the arbitrage checks, in total variance or fraction of spot
+const ARBITRAGE_TOLERANCE: f64 = 1e-10;
+/// Relative strike step of the local volatility differences
+const LOCAL_VOL_STRIKE_STEP: f64 = 1e-3;
+/// Maturity step of the local volatility differences
+const LOCAL_VOL_TIME_STEP: f64 = 1e-3;
+
+/// Kind of static arbitrage found on a surface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArbitrageKind {
+    /// Call prices increase in strike, or fall faster than discounted strike
+    CallSpread,
+    /// Call prices are not convex in strike
+    Butterfly,
+    /// Total variance decreases in maturity at fixed forward moneyness
+    Calendar,
+}
+
+/// One static arbitrage found by [`VolSurface::arbitrage_violations`]
+#[derive(Debug, Clone, Copy)]
+pub struct ArbitrageViolation {
+    pub kind: ArbitrageKind,
+    /// Grid strike where the violation was detected
+    pub strike: f64,
+    /// Grid maturity where the violation was detected (the earlier one for
+    /// calendars)
+    pub maturity: f64,
+    /// Size of the violation, in price for call spreads and butterflies and
+    /// in total variance for calendars
+    pub amount: f64,
+}
+
+/// Implied volatilities on a maturity × strike grid
+#[derive(Debug, Clone)]
+pub struct VolSurface {
+    strikes: Vec<f64>,
+    maturities: Vec<f64>,
+    vols: Array2<f64>,
+    /// Raw SVI fit of each maturity slice in `ln K`, when interpolating
+    /// with SVI
+    svi_slices: Option<Vec<SviParams>>,
+}
+
+impl VolSurface {
+    /// Surface from `vols[[i, j]]`, the implied volatility at `maturities[i]`
+    /// and `strikes[j]`
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` if the grids are empty, not
+    /// strictly increasing or do not match the shape of `vols`, and
+    /// `SdeError::InvalidParameters` for non-positive strikes, maturities or
+    /// volatilities.
+    pub fn new(strikes: Vec<f64>, maturities: Vec<f64>, vols: Array2<f64>) -> SdeResult<Self> {
+        validate_grid("strikes", &strikes)?;
+        validate_grid("maturities", &maturities)?;
+        if vols.dim() != (maturities.len(), strikes.len()) {
+            return Err(SdeError::InvalidConfiguration {
+                field: "vols".to_string(),
+                reason: format!(
+                    "shape {:?} does not match {} maturities x {} strikes",
+                    vols.dim(),
+                    maturities.len(),
+                    strikes.len()
+                ),
+            });
+        }
+        vols.iter()
+            .try_for_each(|&v| validate_positive("implied_vol", v))?;
+        Ok(VolSurface {
+            strikes,
+            maturities,
+            vols,
+            svi_slices: None,
+        })
+    }
+
+    /// Interpolate in strike with a raw SVI fit of each maturity slice
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` for fewer than 5 strikes,
+    /// and the fitter's error if a slice fit fails.
+    pub fn with_svi_interpolation(mut self, fitter: &SviFitter) -> SdeResult<Self> {
+        if self.strikes.len() < 5 {
+            return Err(SdeError::InvalidConfiguration {
+                field: "strikes".to_string(),
+                reason: format!(
+                    "SVI interpolation needs at least 5 strikes, got {}",
+                    self.strikes.len()
+                ),
+            });
+        }
+        let log_strikes: Vec<f64> = self.strikes.iter().map(|k| k.ln()).collect();
+        let slices = self
+            .maturities
+            .iter()
+            .zip(self.vols.rows())
+            .map(|(&t, row)| {
+                let ws: Vec<f64> = row.iter().map(|v| v * v * t).collect();
+                fitter.fit_raw(&log_strikes, &ws).map(|(params, _)| params)
+            })
+            .collect::<SdeResult<Vec<_>>>()?;
+        self.svi_slices = Some(slices);
+        Ok(self)
+    }
+
+    /// Surface sampling `vol(strike, maturity)` on the grid
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::new`].
+    pub fn from_fn<F>(strikes: Vec<f64>, maturities: Vec<f64>, vol: F) -> SdeResult<Self>
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        let vols = Array2::from_shape_fn((maturities.len(), strikes.len()), |(i, j)| {
+            vol(strikes[j], maturities[i])
+        });
+        Self::new(strikes, maturities, vols)
+    }
+
+    pub fn strikes(&self) -> &[f64] {
+        &self.strikes
+    }
+
+    pub fn maturities(&self) -> &[f64] {
+        &self.maturities
+    }
+
+    /// Grid volatilities, indexed `[[maturity, strike]]`
+    pub fn vols(&self) -> &Array2<f64> {
+        &self.vols
+    }
+
+    /// Interpolated total variance `σ²(K, T) T` (0 for `t ≤ 0`)
+    pub fn total_variance(&self, strike: f64, t: f64) -> f64 {
+        if t <= 0.0 {
+            return 0.0;
+        }
+        let last = self.maturities.len() - 1;
+        if t <= self.maturities[0] {
+            return t * self.slice_variance(0, strike);
+        }
+        if t >= self.maturities[last] {
+            return t * self.slice_variance(last, strike);
+        }
+        let (i, b) = locate(&self.maturities, t);
+        let w0 = self.maturities[i] * self.slice_variance(i, strike);
+        let w1 = self.maturities[i + 1] * self.slice_variance(i + 1, strike);
+        (1.0 - b) * w0 + b * w1
+    }
+
+    /// Interpolated implied volatility (the first slice's for `t ≤ 0`)
+    pub fn implied_vol(&self, strike: f64, t: f64) -> f64 {
+        if t <= 0.0 {
+            return self.slice_variance(0, strike).sqrt();
+        }
+        (self.total_variance(strike, t) / t).sqrt()
+    }
+
+    /// Black-Scholes call price at the interpolated implied volatility
+    pub fn call_price(&self, spot: f64, rate: f64, strike: f64, t: f64) -> f64 {
+        bs_call_price(spot, strike, rate, self.implied_vol(strike, t), t)
+    }
+
+    /// Dupire local volatility at `strike` and `t > 0` for spot `spot` and
+    /// risk-free rate `rate` (no dividends)
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidParameters` for invalid inputs, and
+    /// `SdeError::NumericalInstability` where the surface has butterfly or
+    /// calendar arbitrage, so that the local variance is negative or
+    /// undefined.
+    pub fn local_vol(&self, spot: f64, rate: f64, strike: f64, t: f64) -> SdeResult<f64> {
+        validate_positive("spot", spot)?;
+        validate_finite("rate", rate)?;
+        validate_positive("strike", strike)?;
+        validate_positive("t", t)?;
+
+        let forward = |t: f64| spot * (rate * t).exp();
+        let y = (strike / forward(t)).ln();
+        let w_at = |y: f64, t: f64| self.total_variance(forward(t) * y.exp(), t);
+
+        let dy = LOCAL_VOL_STRIKE_STEP;
+        let dt = LOCAL_VOL_TIME_STEP.min(0.5 * t);
+        let w = w_at(y, t);
+        let (w_up, w_down) = (w_at(y + dy, t), w_at(y - dy, t));
+        let w_y = (w_up - w_down) / (2.0 * dy);
+        let w_yy = (w_up - 2.0 * w + w_down) / (dy * dy);
+        let w_t = (w_at(y, t + dt) - w_at(y, t - dt)) / (2.0 * dt);
+
+        let denominator =
+            1.0 - y / w * w_y + 0.25 * (-0.25 - 1.0 / w + y * y / (w * w)) * w_y * w_y + 0.5 * w_yy;
+        if w_t < 0.0 || denominator <= 0.0 {
+            return Err(SdeError::NumericalInstability {
+                method: "Dupire local volatility".to_string(),
+                reason: format!(
+                    "negative local variance at K = {}, T = {} (dw/dT = {}, strike term = {})",
+                    strike, t, w_t, denominator
+                ),
+            });
+        }
+        Ok((w_t / denominator).sqrt())
+    }
+
+    /// Static arbitrage in the grid's call prices for spot `spot` and
+    /// risk-free rate `rate`
+    ///
+    /// Call spreads and butterflies are checked on consecutive grid strikes
+    /// of every maturity, calendars at every grid strike of each maturity
+    /// against the next maturity at the same forward moneyness.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidParameters` for a non-positive spot or
+    /// non-finite rate.
+    pub fn arbitrage_violations(&self, spot: f64, rate: f64) -> SdeResult<Vec<ArbitrageViolation>> {
+        validate_positive("spot", spot)?;
+        validate_finite("rate", rate)?;
+        let price_tolerance = ARBITRAGE_TOLERANCE * spot;
+        let mut violations = Vec::new();
+
+        for (i, &t) in self.maturities.iter().enumerate() {
+            let prices: Vec<f64> = self
+                .strikes
+                .iter()
+                .zip(self.vols.row(i))
+                .map(|(&k, &v)| bs_call_price(spot, k, rate, v, t))
+                .collect();
+            let slopes: Vec<f64> = (0..prices.len() - 1)
+                .map(|j| (prices[j + 1] - prices[j]) / (self.strikes[j + 1] - self.strikes[j]))
+                .collect();
+            let discount = (-rate * t).exp();
+            for (j, &slope) in slopes.iter().enumerate() {
+                let dk = self.strikes[j + 1] - self.strikes[j];
+                let excess = slope.max(-discount - slope) * dk;
+                if excess > price_tolerance {
+                    violations.push(ArbitrageViolation {
+                        kind: ArbitrageKind::CallSpread,
+                        strike: self.strikes[j],
+                        maturity: t,
+                        amount: excess,
+                    });
+                }
+            }
+            for j in 1..slopes.len() {
+                let dk = 0.5 * (self.strikes[j + 1] - self.strikes[j - 1]);
+                let excess = (slopes[j - 1] - slopes[j]) * dk;
+                if excess > price_tolerance {
+                    violations.push(ArbitrageViolation {
+                        kind: ArbitrageKind::Butterfly,
+                        strike: self.strikes[j],
+                        maturity: t,
+                        amount: excess,
+                    });
+                }
+            }
+        }
+
+        for (i, pair) in self.maturities.windows(2).enumerate() {
+            let growth = (rate * (pair[1] - pair[0])).exp();
+            for (j, &k) in self.strikes.iter().enumerate() {
+                let near = pair[0] * self.vols[[i, j]].powi(2);
+                let far = self.total_variance(k * growth, pair[1]);
+                if near - far > ARBITRAGE_TOLERANCE {
+                    violations.push(ArbitrageViolation {
+                        kind: ArbitrageKind::Calendar,
+                        strike: k,
+                        maturity: pair[0],
+                        amount: near - far,
+                    });
+                }
+            }
+        }
+        Ok(violations)
+    }
+
+    /// Require a surface free of static arbitrage
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` describing the first
+    /// violation found by [`Self::arbitrage_violations`].
+    pub fn check_arbitrage(&self, spot: f64, rate: f64) -> SdeResult<()> {
+        match self.arbitrage_violations(spot, rate)?.first() {
+            None => Ok(()),
+            Some(v) => Err(SdeError::InvalidConfiguration {
+                field: "vols".to_string(),
+                reason: format!(
+                    "{:?} arbitrage of {:e} at K = {}, T = {}",
+                    v.kind, v.amount, v.strike, v.maturity
+                ),
+            }),
+        }
+    }
+
+    /// Implied variance of maturity slice `i`, linear in strike (or SVI)
+    /// and flat beyond the strike grid
+    fn slice_variance(&self, i: usize, strike: f64) -> f64 {
+        let last = self.strikes.len() - 1;
+        if let Some(slices) = &self.svi_slices {
+            let k = strike.clamp(self.strikes[0], self.strikes[last]).ln();
+            return slices[i].total_variance(k).max(0.0) / self.maturities[i];
+        }
+        if strike <= self.strikes[0] {
+            return self.vols[[i, 0]].powi(2);
+        }
+        if strike >= self.strikes[last] {
+            return self.vols[[i, last]].powi(2);
+        }
+        let (j, a) = locate(&self.strikes, strike);
+        (1.0 - a) * self.vols[[i, j]].powi(2) + a * self.vols[[i, j + 1]].powi(2)
+    }
+}
+
+/// Cell `j` with `grid[j] ≤ x < grid[j + 1]` and the fraction of the way
+/// through it, for `x` strictly inside the grid
+fn locate(grid: &[f64], x: f64) -> (usize, f64) {
+    let j = grid.partition_point(|&g| g <= x).clamp(1, grid.len() - 1) - 1;
+    (j, (x - grid[j]) / (grid[j + 1] - grid[j]))
+}
+
+fn validate_grid(field: &str, grid: &[f64]) -> SdeResult<()> {
+    if grid.is_empty() {
+        return Err(SdeError::InvalidConfiguration {
+            field: field.to_string(),
+            reason: "grid is empty".to_string(),
+        });
+    }
+    grid.iter().try_for_each(|&x| validate_positive(field, x))?;
+    if grid.windows(2).any(|pair| pair[1] <= pair[0]) {
+        return Err(SdeError::InvalidConfiguration {
+            field: field.to_string(),
+            reason: "grid must be strictly increasing".to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn test_interpolation_and_local_vol() {
+        let surface = VolSurface::new(
+            vec![80.0, 100.0, 120.0],
+            vec![1.0, 2.0],
+            array![[0.25, 0.2, 0.18], [0.3, 0.3, 0.3]],
+        )
+        .expect("Valid grid");
+
+        // Nodes, strike interpolation in variance, flat extrapolation
+        assert!((surface.implied_vol(100.0, 1.0) - 0.2).abs() < 1e-15);
+        let mid = ((0.25f64.powi(2) + 0.2f64.powi(2)) / 2.0).sqrt();
+        assert!((surface.implied_vol(90.0, 1.0) - mid).abs() < 1e-15);
+        assert!((surface.implied_vol(50.0, 0.5) - 0.25).abs() < 1e-15);
+        assert!((surface.implied_vol(200.0, 5.0) - 0.3).abs() < 1e-15);
+        // Linear total variance between maturities
+        let w = 0.5 * (0.04 + 2.0 * 0.09);
+        assert!((surface.total_variance(100.0, 1.5) - w).abs() < 1e-15);
+        assert!(surface.check_arbitrage(100.0, 0.0).is_ok());
+
+        // A term structure has local variance dw/dT
+        let flat_smile =
+            VolSurface::new(vec![100.0], vec![1.0, 2.0], array![[0.2], [0.3]]).expect("Valid grid");
+        let local = flat_smile
+            .local_vol(100.0, 0.03, 90.0, 1.5)
+            .expect("Arbitrage-free");
+        assert!((local - 0.14f64.sqrt()).abs() < 1e-10, "{}", local);
+        let flat =
+            VolSurface::from_fn(vec![90.0, 110.0], vec![0.5, 1.0], |_, _| 0.2).expect("Valid grid");
+        let local = flat
+            .local_vol(100.0, 0.05, 100.0, 0.7)
+            .expect("Arbitrage-free");
+        assert!((local - 0.2).abs() < 1e-10, "{}", local);
+
+        assert!(VolSurface::new(vec![100.0, 90.0], vec![1.0], array![[0.2, 0.2]]).is_err());
+        assert!(VolSurface::new(vec![100.0], vec![1.0], array![[0.2, 0.2]]).is_err());
+    }
+
+    #[test]
+    fn test_detects_butterfly_and_calendar_arbitrage() {
+        // A volatility spike at one strike makes calls locally concave
+        let spike = VolSurface::new(vec![95.0, 100.0, 105.0], vec![0.1], array![[0.2, 0.6, 0.2]])
+            .expect("Valid grid");
+        let violations = spike
+            .arbitrage_violations(100.0, 0.0)
+            .expect("Valid inputs");
+        assert!(violations
+            .iter()
+            .any(|v| v.kind == ArbitrageKind::Butterfly && v.strike == 100.0));
+        assert!(spike.check_arbitrage(100.0, 0.0).is_err());
+
+        // Total variance falls from 0.09 to 0.08
+        let inverted =
+            VolSurface::new(vec![100.0], vec![1.0, 2.0], array![[0.3], [0.2]]).expect("Valid grid");
+        let violations = inverted
+            .arbitrage_violations(100.0, 0.0)
+            .expect("Valid inputs");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].kind, ArbitrageKind::Calendar);
+        assert!((violations[0].amount - 0.01).abs() < 1e-12);
+        assert!(inverted.local_vol(100.0, 0.0, 100.0, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_svi_interpolation_between_strikes() {
+        let smile = SviParams {
+            a: 0.01,
+            b: 0.1,
+            rho: -0.5,
+            m: 0.0,
+            sigma: 0.3,
+        };
+        let (spot, t) = (100.0, 1.0);
+        let vol = |k: f64| smile.implied_vol((k / spot).ln(), t);
+        let strikes: Vec<f64> = (0..7).map(|i| 70.0 + 10.0 * i as f64).collect();
+        let linear =
+            VolSurface::from_fn(strikes.clone(), vec![t], |k, _| vol(k)).expect("Valid grid");
+        let svi = linear
+            .clone()
+            .with_svi_interpolation(&SviFitter::default())
+            .expect("Enough strikes");
+
+        let (k, exact) = (84.0, vol(84.0));
+        assert!((svi.implied_vol(k, t) - exact).abs() < 1e-8);
+        assert!((linear.implied_vol(k, t) - exact).abs() > 1e-5);
+        // Flat beyond the grid
+        assert!((svi.implied_vol(200.0, t) - vol(130.0)).abs() < 1e-8);
+
+        let narrow = VolSurface::from_fn(vec![90.0, 100.0, 110.0], vec![t], |k, _| vol(k))
+            .expect("Valid grid");
+        assert!(narrow
+            .with_svi_interpolation(&SviFitter::default())
+            .is_err());
+    }
+}
@@ -0,0 +1,214 @@
+// src/bench_api.rs
+//! Micro-Benchmark Kernels
+//!
+//! # Overview
+//!
+//! The hot paths of the pricers, exposed one call at a time so downstream
+//! regression suites (Criterion or any other harness) can time them
+//! without running a full pricing job:
+//! - [`GbmKernel`]: one GBM path from its draws, one block of the engine's
+//!   normal source, and one engine sample (draws, path and payoff)
+//! - [`HestonKernel`]: one Heston path from its draws
+//! - [`fill_normals`]: normal draws from any `rand` generator, e.g.
+//!   [`Philox4x32`](crate::rng::philox::Philox4x32) or
+//!   [`CounterRng`](crate::rng::CounterRng)
+//! - [`payoff_sum`]: payoff evaluation over a batch of stored paths
+//!
+//! Kernels validate their inputs once at construction; the timed calls do
+//! no validation and no allocation once the output
+//! buffers have grown. The kernels run exactly the code of the pricers, so
+//! their timings track the library's.
+//!
+//! # Example
+//!
+//! ```text
+//! let kernel = GbmKernel::new(&cfg)?;
+//! let mut block = 0;
+//! c.bench_function("gbm engine sample", |b| {
+//!     b.iter(|| { block += 1; kernel.sample(black_box(block)) })
+//! });
+//! ```
+
+use crate::error::{validation::*, SdeResult};
+use crate::mc::mc_engine::{
+    engine_normal_source, payoff_and_control, priced_payoff, simulate_gbm_path_from_draws,
+    simulation_increments, with_scratch, McConfig,
+};
+use crate::mc::normal_source::NormalSource;
+use crate::mc::payoffs::Payoff;
+use crate::models::heston::{Heston, HestonParams, HestonScheme};
+use crate::rng;
+use rand::Rng;
+use std::sync::Arc;
+
+/// GBM engine kernels for a validated configuration
+pub struct GbmKernel {
+    cfg: McConfig,
+    grid: Vec<f64>,
+    source: Arc<dyn NormalSource>,
+}
+
+impl GbmKernel {
+    /// Kernels of `cfg`, priced as [`mc_price_option_gbm`] would
+    ///
+    /// [`mc_price_option_gbm`]: crate::mc::mc_engine::mc_price_option_gbm
+    ///
+    /// # Errors
+    ///
+    /// Returns the configuration's validation errors.
+    pub fn new(cfg: &McConfig) -> SdeResult<Self> {
+        cfg.validate()?;
+        let grid = simulation_increments(cfg);
+        let source = engine_normal_source(cfg);
+        source.validate(grid.len())?;
+        let cfg = McConfig {
+            payoff: priced_payoff(cfg),
+            ..cfg.clone()
+        };
+        Ok(GbmKernel { cfg, grid, source })
+    }
+
+    /// Normal draws per path
+    pub fn dimension(&self) -> usize {
+        self.grid.len()
+    }
+
+    /// Paths per block of the normal source (2 with antithetic pairing)
+    pub fn block_size(&self) -> usize {
+        self.source.block_size()
+    }
+
+    /// Simulate `[S_0, ..., S_n]` from `draws` into `path`
+    ///
+    /// `draws` needs [`dimension`](Self::dimension) values.
+    pub fn simulate(&self, draws: &[f64], path: &mut Vec<f64>) {
+        simulate_gbm_path_from_draws(&self.cfg, &self.grid, draws, path);
+    }
+
+    /// Fill `out` (resized to `block_size() * dimension()`) with block
+    /// `block` of the engine's normal source
+    pub fn fill_block(&self, block: usize, out: &mut Vec<f64>) {
+        out.resize(self.block_size() * self.dimension(), 0.0);
+        self.source.fill_block(block, self.dimension(), out);
+    }
+
+    /// Undiscounted payoff of engine sample `block`: its draws, paths and
+    /// payoffs, averaged over the block, as in one step of the engine loop
+    pub fn sample(&self, block: usize) -> f64 {
+        with_scratch(|buffers| {
+            payoff_and_control(&self.cfg, &self.grid, &*self.source, &[], block, buffers).0
+        })
+    }
+}
+
+/// Heston path kernel with a fixed scheme and uniform grid
+pub struct HestonKernel {
+    model: Heston,
+    dt: f64,
+    steps: usize,
+}
+
+impl HestonKernel {
+    /// Kernel for `steps` steps to maturity `t`
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError` for invalid parameters, maturity or step count.
+    pub fn new(
+        params: HestonParams,
+        scheme: HestonScheme,
+        t: f64,
+        steps: usize,
+    ) -> SdeResult<Self> {
+        validate_positive("t", t)?;
+        validate_steps(steps)?;
+        let model = Heston::new_with_scheme(params, scheme)?;
+        Ok(HestonKernel {
+            model,
+            dt: t / steps as f64,
+            steps,
+        })
+    }
+
+    /// Draws per path: `(z1, z2, u)` for each step
+    pub fn dimension(&self) -> usize {
+        3 * self.steps
+    }
+
+    /// Terminal `(S_T, v_T)` of the path driven by `draws`, laid out as
+    /// `[z1, z2, u]` per step with `u` uniform on (0, 1)
+    ///
+    /// # Errors
+    ///
+    /// Returns the scheme's error on a failed step.
+    pub fn simulate(&self, draws: &[f64]) -> SdeResult<(f64, f64)> {
+        let (mut s, mut v) = (self.model.params.s0, self.model.params.v0);
+        for step in draws.chunks_exact(3).take(self.steps) {
+            self.model
+                .step_with_draws(&mut s, &mut v, self.dt, step[0], step[1], step[2])?;
+        }
+        Ok((s, v))
+    }
+}
+
+/// Fill `out` with standard normal draws (ziggurat) from `rng`
+pub fn fill_normals<R: Rng + ?Sized>(rng: &mut R, out: &mut [f64]) {
+    out.iter_mut().for_each(|z| *z = rng::get_normal_draw(rng));
+}
+
+/// Sum of `payoff` over the paths stored row by row in `paths`, each
+/// `width` prices long
+pub fn payoff_sum(payoff: &Payoff, paths: &[f64], width: usize) -> f64 {
+    paths
+        .chunks_exact(width.max(1))
+        .map(|path| payoff.calculate(path))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::CounterRng;
+
+    #[test]
+    fn test_kernels_reproduce_engine_paths() {
+        let cfg = McConfig {
+            paths: 1_000,
+            steps: 8,
+            payoff: Payoff::AsianCall { k: 100.0 },
+            ..Default::default()
+        };
+        let kernel = GbmKernel::new(&cfg).unwrap();
+        assert_eq!((kernel.dimension(), kernel.block_size()), (8, 2));
+
+        // A block's draws through `simulate` and `payoff_sum` give `sample`
+        let mut draws = Vec::new();
+        kernel.fill_block(3, &mut draws);
+        let mut paths = Vec::new();
+        let mut path = Vec::new();
+        for row in draws.chunks(kernel.dimension()) {
+            kernel.simulate(row, &mut path);
+            paths.extend_from_slice(&path);
+        }
+        let mean = payoff_sum(&cfg.payoff, &paths, cfg.steps + 1) / 2.0;
+        assert_eq!(mean, kernel.sample(3));
+
+        let mut normals = vec![0.0; 3 * 50];
+        fill_normals(&mut CounterRng::for_path(1, 0), &mut normals);
+        normals.chunks_mut(3).for_each(|step| step[2] = 0.5);
+        let params = HestonParams {
+            s0: 100.0,
+            v0: 0.04,
+            r: 0.02,
+            kappa: 1.5,
+            theta: 0.04,
+            xi: 0.3,
+            rho: -0.7,
+        };
+        let heston = HestonKernel::new(params, HestonScheme::FullTruncationEuler, 1.0, 50).unwrap();
+        assert_eq!(heston.dimension(), normals.len());
+        let (s, v) = heston.simulate(&normals).unwrap();
+        assert!(s > 0.0 && v >= 0.0);
+        assert!(HestonKernel::new(params, HestonScheme::FullTruncationEuler, 1.0, 0).is_err());
+    }
+}
@@ -0,0 +1,303 @@
+// src/calibration/heston.rs
+//! Heston Calibration
+//!
+//! # Objective
+//!
+//! Fits `x = (v₀, κ, θ, ξ, ρ)` to European quotes by bounded least squares
+//! on prices, with model prices from the COS pricer
+//! ([`heston_analytic`](crate::analytics::heston_analytic)), so the
+//! objective is smooth and free of Monte Carlo noise:
+//! ```text
+//! F(x) = Σ_i (P_model(K_i, T_i; x) - P_mkt,i)² + (w · max(ξ² - 2κθ, 0))²
+//! ```
+//! The spot and rate are market inputs and stay fixed.
+//!
+//! # Feller Condition
+//!
+//! The penalty weight `w` ([`HestonCalibrator::feller_penalty`]) trades fit
+//! quality against `2κθ > ξ²`. With `w = 0` the fit is unconstrained, which
+//! is usual for equity smiles; a large `w` keeps the variance process away
+//! from zero, as some simulation schemes prefer.
+//!
+//! # Global Stage
+//!
+//! Levenberg–Marquardt only refines the basin of the starting point. With
+//! [`HestonCalibrator::global_search`] set, a seeded differential
+//! evolution over the bounds (with the initial guess in its population)
+//! picks the starting point for the local refinement instead.
+
+use crate::analytics::heston_analytic::{heston_call_price, heston_put_price};
+use crate::calibration::optimizer::{Bounds, DifferentialEvolution, LevenbergMarquardt};
+use crate::calibration::quotes::{rmse, validate_quotes, OptionQuote, OptionType, QuoteFit};
+use crate::error::{validation::*, SdeResult};
+use crate::models::heston::HestonParams;
+use crate::trace::span;
+
+/// Search ranges of the calibrated parameters, as `(lower, upper)`
+#[derive(Debug, Clone, Copy)]
+pub struct HestonBounds {
+    pub v0: (f64, f64),
+    pub kappa: (f64, f64),
+    pub theta: (f64, f64),
+    pub xi: (f64, f64),
+    pub rho: (f64, f64),
+}
+
+impl Default for HestonBounds {
+    /// Ranges inside the limits accepted by [`Heston`](crate::models::heston::Heston)
+    fn default() -> Self {
+        HestonBounds {
+            v0: (1e-4, 1.0),
+            kappa: (1e-2, 20.0),
+            theta: (1e-4, 1.0),
+            xi: (1e-2, 5.0),
+            rho: (-0.999, 0.999),
+        }
+    }
+}
+
+impl HestonBounds {
+    fn to_bounds(self) -> SdeResult<Bounds> {
+        let ranges = [self.v0, self.kappa, self.theta, self.xi, self.rho];
+        Bounds::new(
+            ranges.iter().map(|r| r.0).collect(),
+            ranges.iter().map(|r| r.1).collect(),
+        )
+    }
+}
+
+/// Least-squares Heston calibrator
+#[derive(Debug, Clone)]
+pub struct HestonCalibrator {
+    pub quotes: Vec<OptionQuote>,
+    pub bounds: HestonBounds,
+    /// Weight `w` of the Feller residual `max(ξ² - 2κθ, 0)`; 0 disables it
+    pub feller_penalty: f64,
+    /// Optional derivative-free search run before the local refinement
+    pub global_search: Option<DifferentialEvolution>,
+    pub optimizer: LevenbergMarquardt,
+}
+
+/// Outcome of [`HestonCalibrator::calibrate`]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HestonCalibration {
+    pub params: HestonParams,
+    /// Root mean squared price error over the quotes
+    pub rmse: f64,
+    /// Per-quote model prices and errors, in quote order
+    pub fits: Vec<QuoteFit>,
+    /// Levenberg–Marquardt iterations (after any global stage)
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+impl HestonCalibrator {
+    /// Calibrator with default bounds, no Feller penalty, no global stage
+    /// and default optimizer settings
+    pub fn new(quotes: Vec<OptionQuote>) -> Self {
+        HestonCalibrator {
+            quotes,
+            bounds: HestonBounds::default(),
+            feller_penalty: 0.0,
+            global_search: None,
+            optimizer: LevenbergMarquardt::default(),
+        }
+    }
+
+    /// Validate the quotes, bounds, penalty weight and global stage
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_quotes(&self.quotes, 1)?;
+        self.bounds.to_bounds()?;
+        validate_non_negative("feller_penalty", self.feller_penalty)?;
+        self.global_search
+            .as_ref()
+            .map_or(Ok(()), DifferentialEvolution::validate)
+    }
+
+    /// Fit `(v₀, κ, θ, ξ, ρ)` starting from `initial`
+    ///
+    /// `initial.s0` and `initial.r` are the market spot and rate and are
+    /// kept fixed; the other fields are clamped into [`Self::bounds`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError` for invalid quotes, bounds, settings or
+    /// spot/rate, or if the pricer fails at the starting point (or on the
+    /// whole initial population of the global stage).
+    pub fn calibrate(&self, initial: &HestonParams) -> SdeResult<HestonCalibration> {
+        self.validate()?;
+        validate_positive("s0", initial.s0)?;
+        validate_finite("r", initial.r)?;
+        let _span = span!("calibrate_heston", quotes = self.quotes.len());
+
+        let x0 = [
+            initial.v0,
+            initial.kappa,
+            initial.theta,
+            initial.xi,
+            initial.rho,
+        ];
+        let residuals = |x: &[f64]| -> SdeResult<Vec<f64>> {
+            let params = with_vector(initial, x);
+            let mut r = self
+                .quotes
+                .iter()
+                .map(|quote| Ok(model_price(&params, quote)? - quote.market_price))
+                .collect::<SdeResult<Vec<f64>>>()?;
+            if self.feller_penalty > 0.0 {
+                let violation = params.xi * params.xi - 2.0 * params.kappa * params.theta;
+                r.push(self.feller_penalty * violation.max(0.0));
+            }
+            Ok(r)
+        };
+        let bounds = self.bounds.to_bounds()?;
+        let start = match &self.global_search {
+            Some(de) => {
+                let objective =
+                    |x: &[f64]| -> SdeResult<f64> { Ok(residuals(x)?.iter().map(|r| r * r).sum()) };
+                de.minimize(objective, &x0, &bounds)?.x
+            }
+            None => x0.to_vec(),
+        };
+        let fit = self.optimizer.minimize(residuals, &start, &bounds)?;
+
+        let params = with_vector(initial, &fit.x);
+        let fits = self
+            .quotes
+            .iter()
+            .map(|&quote| Ok(QuoteFit::new(quote, model_price(&params, &quote)?)))
+            .collect::<SdeResult<Vec<_>>>()?;
+        Ok(HestonCalibration {
+            params,
+            rmse: rmse(fits.iter().map(|f| f.error)),
+            fits,
+            iterations: fit.iterations,
+            converged: fit.converged,
+        })
+    }
+}
+
+/// `base` with `(v₀, κ, θ, ξ, ρ)` taken from `x`
+fn with_vector(base: &HestonParams, x: &[f64]) -> HestonParams {
+    HestonParams {
+        v0: x[0],
+        kappa: x[1],
+        theta: x[2],
+        xi: x[3],
+        rho: x[4],
+        ..*base
+    }
+}
+
+fn model_price(params: &HestonParams, quote: &OptionQuote) -> SdeResult<f64> {
+    match quote.option_type {
+        OptionType::Call => heston_call_price(params, quote.strike, quote.time_to_expiry),
+        OptionType::Put => heston_put_price(params, quote.strike, quote.time_to_expiry),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quotes_from(params: &HestonParams) -> Vec<OptionQuote> {
+        let mut quotes = Vec::new();
+        for &t in &[0.25, 1.0, 2.0] {
+            for &k in &[80.0, 90.0, 100.0, 110.0, 120.0] {
+                let option_type = if k < 100.0 {
+                    OptionType::Put
+                } else {
+                    OptionType::Call
+                };
+                let quote = OptionQuote {
+                    strike: k,
+                    time_to_expiry: t,
+                    market_price: 0.0,
+                    option_type,
+                };
+                quotes.push(OptionQuote {
+                    market_price: model_price(params, &quote).expect("Valid parameters"),
+                    ..quote
+                });
+            }
+        }
+        quotes
+    }
+
+    #[test]
+    fn test_recovers_parameters_from_own_prices() {
+        let truth = HestonParams {
+            s0: 100.0,
+            v0: 0.05,
+            r: 0.02,
+            kappa: 1.5,
+            theta: 0.06,
+            xi: 0.6,
+            rho: -0.7,
+        };
+        let initial = HestonParams {
+            v0: 0.03,
+            kappa: 3.0,
+            theta: 0.03,
+            xi: 0.3,
+            rho: -0.3,
+            ..truth
+        };
+        let calibrator = HestonCalibrator::new(quotes_from(&truth));
+        let fit = calibrator.calibrate(&initial).expect("Valid quotes");
+        assert!(fit.converged);
+        assert!(fit.rmse < 1e-6, "rmse {}", fit.rmse);
+        assert_eq!(fit.fits.len(), 15);
+        let p = fit.params;
+        assert!((p.v0 - truth.v0).abs() < 1e-4, "{:?}", p);
+        assert!((p.kappa - truth.kappa).abs() < 1e-2, "{:?}", p);
+        assert!((p.theta - truth.theta).abs() < 1e-4, "{:?}", p);
+        assert!((p.xi - truth.xi).abs() < 1e-3, "{:?}", p);
+        assert!((p.rho - truth.rho).abs() < 1e-3, "{:?}", p);
+
+        // The truth violates Feller (2κθ = 0.18 < ξ² = 0.36); a heavy
+        // penalty trades fit quality for the condition
+        let penalized = HestonCalibrator {
+            feller_penalty: 100.0,
+            ..calibrator
+        };
+        let fit = penalized.calibrate(&initial).expect("Valid quotes");
+        let p = fit.params;
+        assert!(p.xi * p.xi - 2.0 * p.kappa * p.theta < 1e-3, "{:?}", p);
+        assert!(fit.rmse > 1e-4);
+    }
+
+    #[test]
+    fn test_global_stage_feeds_local_refinement() {
+        let truth = HestonParams {
+            s0: 100.0,
+            v0: 0.04,
+            r: 0.01,
+            kappa: 2.0,
+            theta: 0.05,
+            xi: 0.4,
+            rho: -0.6,
+        };
+        // A far-off start near the corner of the box
+        let initial = HestonParams {
+            v0: 0.5,
+            kappa: 15.0,
+            theta: 0.5,
+            xi: 3.0,
+            rho: 0.8,
+            ..truth
+        };
+        let calibrator = HestonCalibrator {
+            global_search: Some(DifferentialEvolution {
+                population: 20,
+                generations: 30,
+                ..Default::default()
+            }),
+            ..HestonCalibrator::new(quotes_from(&truth))
+        };
+        let fit = calibrator.calibrate(&initial).expect("Valid quotes");
+        assert!(fit.rmse < 1e-6, "rmse {} at {:?}", fit.rmse, fit.params);
+        assert!((fit.params.rho - truth.rho).abs() < 1e-3);
+    }
+}
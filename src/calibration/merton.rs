@@ -0,0 +1,216 @@
+// src/calibration/merton.rs
+//! Merton Jump-Diffusion Calibration
+//!
+//! # Objective
+//!
+//! Fits `x = (σ, λ, μ_J, σ_J)` to an implied volatility smile (one or more
+//! expiries) by bounded least squares on volatilities:
+//! ```text
+//! F(x) = Σ_i (σ_BS(C_Merton(K_i, T_i; x)) - σ_mkt,i)²
+//! ```
+//! Model prices come from the series expansion
+//! ([`merton_analytic`](crate::analytics::merton_analytic)) and are
+//! inverted with [`bs_call_implied_vol`]. Fitting volatilities rather than
+//! prices weights every strike alike instead of favouring expensive
+//! in-the-money options.
+//!
+//! # Drift
+//!
+//! The fitted parameters carry the risk-neutral drift `mu = r - λk`, so
+//! they can be simulated with [`Merton`](crate::models::merton::Merton)
+//! directly.
+
+use crate::analytics::bs_analytic::bs_call_implied_vol;
+use crate::analytics::merton_analytic::merton_call_price;
+use crate::calibration::optimizer::{Bounds, LevenbergMarquardt};
+use crate::calibration::quotes::{rmse, validate_vol_quotes, VolFit, VolQuote};
+use crate::error::{validation::*, SdeResult};
+use crate::models::merton::MertonParams;
+use crate::trace::span;
+
+/// Search ranges of the calibrated parameters, as `(lower, upper)`
+#[derive(Debug, Clone, Copy)]
+pub struct MertonBounds {
+    pub sigma: (f64, f64),
+    pub lambda: (f64, f64),
+    pub mu_j: (f64, f64),
+    pub sigma_j: (f64, f64),
+}
+
+impl Default for MertonBounds {
+    fn default() -> Self {
+        MertonBounds {
+            sigma: (1e-2, 2.0),
+            lambda: (0.0, 10.0),
+            mu_j: (-1.0, 1.0),
+            sigma_j: (0.0, 1.0),
+        }
+    }
+}
+
+impl MertonBounds {
+    fn to_bounds(self) -> SdeResult<Bounds> {
+        let ranges = [self.sigma, self.lambda, self.mu_j, self.sigma_j];
+        Bounds::new(
+            ranges.iter().map(|r| r.0).collect(),
+            ranges.iter().map(|r| r.1).collect(),
+        )
+    }
+}
+
+/// Least-squares Merton calibrator on implied volatilities
+#[derive(Debug, Clone)]
+pub struct MertonCalibrator {
+    pub quotes: Vec<VolQuote>,
+    /// Risk-free rate
+    pub rate: f64,
+    pub bounds: MertonBounds,
+    pub optimizer: LevenbergMarquardt,
+}
+
+/// Outcome of [`MertonCalibrator::calibrate`]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MertonCalibration {
+    /// Fitted parameters with the risk-neutral drift `mu = r - λk`
+    pub params: MertonParams,
+    /// Root mean squared implied volatility error over the quotes
+    pub rmse: f64,
+    /// Per-quote model volatilities and errors, in quote order
+    pub fits: Vec<VolFit>,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+impl MertonCalibrator {
+    /// Calibrator with default bounds and optimizer settings
+    pub fn new(quotes: Vec<VolQuote>, rate: f64) -> Self {
+        MertonCalibrator {
+            quotes,
+            rate,
+            bounds: MertonBounds::default(),
+            optimizer: LevenbergMarquardt::default(),
+        }
+    }
+
+    /// Validate the quotes, rate and bounds
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_vol_quotes(&self.quotes, 1)?;
+        validate_finite("rate", self.rate)?;
+        self.bounds.to_bounds().map(|_| ())
+    }
+
+    /// Fit `(σ, λ, μ_J, σ_J)` starting from `initial`
+    ///
+    /// `initial.s0` is the market spot and is kept fixed; `initial.mu` is
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError` for invalid quotes, rate, bounds or spot, or if
+    /// a model price at the starting point has no implied volatility.
+    pub fn calibrate(&self, initial: &MertonParams) -> SdeResult<MertonCalibration> {
+        self.validate()?;
+        validate_positive("s0", initial.s0)?;
+        let _span = span!("calibrate_merton", quotes = self.quotes.len());
+
+        let x0 = [initial.sigma, initial.lambda, initial.mu_j, initial.sigma_j];
+        let residuals = |x: &[f64]| -> SdeResult<Vec<f64>> {
+            let params = with_vector(initial, self.rate, x);
+            self.quotes
+                .iter()
+                .map(|quote| Ok(self.model_vol(&params, quote)? - quote.implied_vol))
+                .collect()
+        };
+        let fit = self
+            .optimizer
+            .minimize(residuals, &x0, &self.bounds.to_bounds()?)?;
+
+        let params = with_vector(initial, self.rate, &fit.x);
+        let fits = self
+            .quotes
+            .iter()
+            .map(|&quote| Ok(VolFit::new(quote, self.model_vol(&params, &quote)?)))
+            .collect::<SdeResult<Vec<_>>>()?;
+        Ok(MertonCalibration {
+            params,
+            rmse: rmse(fits.iter().map(|f| f.error)),
+            fits,
+            iterations: fit.iterations,
+            converged: fit.converged,
+        })
+    }
+
+    fn model_vol(&self, params: &MertonParams, quote: &VolQuote) -> SdeResult<f64> {
+        let (k, t) = (quote.strike, quote.time_to_expiry);
+        let price = merton_call_price(params, self.rate, k, t)?;
+        bs_call_implied_vol(price, params.s0, k, self.rate, t)
+    }
+}
+
+/// `base` with `(σ, λ, μ_J, σ_J)` from `x` and the risk-neutral drift
+fn with_vector(base: &MertonParams, rate: f64, x: &[f64]) -> MertonParams {
+    let (lambda, mu_j, sigma_j) = (x[1], x[2], x[3]);
+    let jump_mean = (mu_j + 0.5 * sigma_j * sigma_j).exp() - 1.0;
+    MertonParams {
+        s0: base.s0,
+        mu: rate - lambda * jump_mean,
+        sigma: x[0],
+        lambda,
+        mu_j,
+        sigma_j,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovers_jump_parameters_from_smile() {
+        let rate = 0.02;
+        let truth = MertonParams {
+            s0: 100.0,
+            mu: 0.0,
+            sigma: 0.15,
+            lambda: 0.5,
+            mu_j: -0.15,
+            sigma_j: 0.2,
+        };
+        let quotes: Vec<VolQuote> = [0.25, 1.0]
+            .iter()
+            .flat_map(|&t| {
+                (0..7).map(move |i| {
+                    let k = 70.0 + 10.0 * i as f64;
+                    let price = merton_call_price(&truth, rate, k, t).expect("Valid parameters");
+                    VolQuote {
+                        strike: k,
+                        time_to_expiry: t,
+                        implied_vol: bs_call_implied_vol(price, 100.0, k, rate, t)
+                            .expect("Price in range"),
+                    }
+                })
+            })
+            .collect();
+
+        let initial = MertonParams {
+            sigma: 0.25,
+            lambda: 1.0,
+            mu_j: 0.0,
+            sigma_j: 0.1,
+            ..truth
+        };
+        let fit = MertonCalibrator::new(quotes, rate)
+            .calibrate(&initial)
+            .expect("Valid quotes");
+        assert!(fit.converged);
+        assert!(fit.rmse < 1e-8, "rmse {}", fit.rmse);
+        let p = fit.params;
+        assert!((p.sigma - truth.sigma).abs() < 1e-5, "{:?}", p);
+        assert!((p.lambda - truth.lambda).abs() < 1e-4, "{:?}", p);
+        assert!((p.mu_j - truth.mu_j).abs() < 1e-5, "{:?}", p);
+        assert!((p.sigma_j - truth.sigma_j).abs() < 1e-5, "{:?}", p);
+        let k = (p.mu_j + 0.5 * p.sigma_j * p.sigma_j).exp() - 1.0;
+        assert!((p.mu - (rate - p.lambda * k)).abs() < 1e-15);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod heston;
+pub mod merton;
+pub mod optimizer;
+pub mod quotes;
+pub mod sabr;
@@ -0,0 +1,534 @@
+// src/calibration/optimizer.rs
+//! Bounded Least-Squares Optimization
+//!
+//! # Levenberg–Marquardt
+//!
+//! Minimizes `F(x) = Σ_i r_i(x)²` over a box `l ≤ x ≤ u`. Each iteration
+//! solves the damped normal equations with Marquardt's diagonal scaling
+//! ```text
+//! (JᵀJ + λ diag(JᵀJ)) δ = -Jᵀr
+//! ```
+//! where `J` is a forward-difference Jacobian (columns evaluated in
+//! parallel). A step that lowers `F` is accepted and `λ` shrinks; otherwise
+//! `λ` grows and the step is retried, moving from Gauss–Newton towards
+//! steepest descent.
+//!
+//! # Bounds
+//!
+//! Trial points are projected onto the box. Parameters sitting on a bound
+//! whose gradient points outwards are frozen for the iteration (a simple
+//! active set), so the remaining parameters still get full Gauss–Newton
+//! steps instead of steps that the projection would cut short.
+//!
+//! # Differential Evolution
+//!
+//! Levenberg–Marquardt converges to the nearest local minimum, and
+//! calibration objectives often have several (e.g. the `κ`–`ξ` trade-off
+//! in Heston). [`DifferentialEvolution`] is a derivative-free global search
+//! used to locate the right basin first (Storn & Price, DE/rand/1/bin):
+//! ```text
+//! v = x_a + F (x_b - x_c)                    (a, b, c distinct, ≠ i)
+//! u_j = v_j with probability CR, else x_i,j  (at least one j from v)
+//! x_i ← u  if f(u) ≤ f(x_i)
+//! ```
+//!
+//! # Failures
+//!
+//! The objective or residual function may fail, e.g. when a pricer rejects
+//! a trial point; such a point is treated as worse than any other.
+
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::parallel::prelude::*;
+use crate::rng;
+use crate::trace::{debug_event, span};
+use nalgebra::{DMatrix, DVector};
+use rand::Rng;
+
+/// Relative forward-difference step of the Jacobian
+const JACOBIAN_STEP: f64 = 1e-6;
+const MAX_DAMPING: f64 = 1e12;
+
+/// Box constraints `lower ≤ x ≤ upper`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bounds {
+    pub lower: Vec<f64>,
+    pub upper: Vec<f64>,
+}
+
+impl Bounds {
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` if the lengths differ or
+    /// some `lower[i] > upper[i]` (or is not finite).
+    pub fn new(lower: Vec<f64>, upper: Vec<f64>) -> SdeResult<Self> {
+        let bounds = Bounds { lower, upper };
+        bounds.validate()?;
+        Ok(bounds)
+    }
+
+    pub fn validate(&self) -> SdeResult<()> {
+        if self.lower.len() != self.upper.len() {
+            return Err(SdeError::InvalidConfiguration {
+                field: "bounds".to_string(),
+                reason: format!(
+                    "{} lower but {} upper bounds",
+                    self.lower.len(),
+                    self.upper.len()
+                ),
+            });
+        }
+        for (i, (&lo, &hi)) in self.lower.iter().zip(&self.upper).enumerate() {
+            if !(lo.is_finite() && hi.is_finite() && lo <= hi) {
+                return Err(SdeError::InvalidConfiguration {
+                    field: "bounds".to_string(),
+                    reason: format!("parameter {}: invalid range [{}, {}]", i, lo, hi),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.lower.len()
+    }
+
+    /// Clamp `x` into the box
+    pub fn project(&self, x: &mut [f64]) {
+        for ((v, &lo), &hi) in x.iter_mut().zip(&self.lower).zip(&self.upper) {
+            *v = v.clamp(lo, hi);
+        }
+    }
+}
+
+/// Outcome of a minimization
+#[derive(Debug, Clone)]
+pub struct OptimizationResult {
+    pub x: Vec<f64>,
+    /// `Σ r_i(x)²`
+    pub objective: f64,
+    pub iterations: usize,
+    /// Whether a stopping tolerance was met before `max_iterations`
+    pub converged: bool,
+}
+
+/// Settings of the Levenberg–Marquardt minimizer
+#[derive(Debug, Clone, Copy)]
+pub struct LevenbergMarquardt {
+    pub max_iterations: usize,
+    /// Stop when an accepted step lowers the objective, or moves the
+    /// parameters, by less than this relative amount
+    pub tolerance: f64,
+    /// Initial damping `λ`
+    pub initial_damping: f64,
+}
+
+impl Default for LevenbergMarquardt {
+    fn default() -> Self {
+        LevenbergMarquardt {
+            max_iterations: 100,
+            tolerance: 1e-10,
+            initial_damping: 1e-3,
+        }
+    }
+}
+
+impl LevenbergMarquardt {
+    /// Minimize `Σ residuals(x)²` over `bounds`, starting from `x0`
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` for invalid bounds or a
+    /// starting point of the wrong dimension, and the residual function's
+    /// error if it fails at the (projected) starting point or while
+    /// building a Jacobian.
+    pub fn minimize<F>(
+        &self,
+        residuals: F,
+        x0: &[f64],
+        bounds: &Bounds,
+    ) -> SdeResult<OptimizationResult>
+    where
+        F: Fn(&[f64]) -> SdeResult<Vec<f64>> + Sync,
+    {
+        check_start(x0, bounds)?;
+        let n = x0.len();
+        let _span = span!("levenberg_marquardt", parameters = n);
+        let mut x = x0.to_vec();
+        bounds.project(&mut x);
+        let mut r = residuals(&x)?;
+        let mut objective = sum_of_squares(&r);
+        let mut damping = self.initial_damping;
+
+        for iteration in 1..=self.max_iterations {
+            debug_event!(
+                iteration,
+                objective,
+                damping,
+                "levenberg_marquardt iteration"
+            );
+            let jacobian = forward_jacobian(&residuals, &x, &r, bounds)?;
+            let jtj = jacobian.transpose() * &jacobian;
+            let mut gradient = jacobian.transpose() * DVector::from_column_slice(&r);
+
+            // Freeze parameters pushed against their bound
+            let mut normal = jtj.clone();
+            for j in 0..n {
+                let at_lower = x[j] <= bounds.lower[j] && gradient[j] > 0.0;
+                let at_upper = x[j] >= bounds.upper[j] && gradient[j] < 0.0;
+                if at_lower || at_upper {
+                    normal.row_mut(j).fill(0.0);
+                    normal.column_mut(j).fill(0.0);
+                    normal[(j, j)] = 1.0;
+                    gradient[j] = 0.0;
+                }
+            }
+            if gradient.amax() == 0.0 {
+                return Ok(OptimizationResult {
+                    x,
+                    objective,
+                    iterations: iteration,
+                    converged: true,
+                });
+            }
+
+            loop {
+                let mut damped = normal.clone();
+                for j in 0..n {
+                    damped[(j, j)] += damping * normal[(j, j)].max(f64::EPSILON);
+                }
+                let step = damped.cholesky().map(|c| c.solve(&(-&gradient)));
+                let trial = step.map(|step| {
+                    let mut trial: Vec<f64> =
+                        x.iter().zip(step.iter()).map(|(a, b)| a + b).collect();
+                    bounds.project(&mut trial);
+                    trial
+                });
+                let evaluated = trial.and_then(|trial| {
+                    let r_trial = residuals(&trial).ok()?;
+                    let f_trial = sum_of_squares(&r_trial);
+                    (f_trial < objective).then_some((trial, r_trial, f_trial))
+                });
+
+                match evaluated {
+                    Some((trial, r_trial, f_trial)) => {
+                        let reduction = (objective - f_trial) / objective.max(f64::MIN_POSITIVE);
+                        let moved = x
+                            .iter()
+                            .zip(&trial)
+                            .map(|(a, b)| (a - b).abs() / a.abs().max(1.0))
+                            .fold(0.0, f64::max);
+                        x = trial;
+                        r = r_trial;
+                        objective = f_trial;
+                        damping = (damping / 3.0).max(f64::EPSILON);
+                        if reduction < self.tolerance || moved < self.tolerance {
+                            return Ok(OptimizationResult {
+                                x,
+                                objective,
+                                iterations: iteration,
+                                converged: true,
+                            });
+                        }
+                        break;
+                    }
+                    None if damping < MAX_DAMPING => damping *= 4.0,
+                    // No descent direction left at working precision
+                    None => {
+                        return Ok(OptimizationResult {
+                            x,
+                            objective,
+                            iterations: iteration,
+                            converged: true,
+                        })
+                    }
+                }
+            }
+        }
+
+        Ok(OptimizationResult {
+            x,
+            objective,
+            iterations: self.max_iterations,
+            converged: false,
+        })
+    }
+}
+
+/// Settings of the differential evolution minimizer (DE/rand/1/bin)
+#[derive(Debug, Clone, Copy)]
+pub struct DifferentialEvolution {
+    /// Candidates per generation (at least 4)
+    pub population: usize,
+    pub generations: usize,
+    /// Mutation scale `F`
+    pub differential_weight: f64,
+    /// Crossover probability `CR`
+    pub crossover: f64,
+    /// Stop early once the population's objective values span less than
+    /// this (relative to the best)
+    pub tolerance: f64,
+    pub seed: u64,
+}
+
+impl Default for DifferentialEvolution {
+    fn default() -> Self {
+        DifferentialEvolution {
+            population: 40,
+            generations: 200,
+            differential_weight: 0.7,
+            crossover: 0.9,
+            tolerance: 1e-8,
+            seed: 42,
+        }
+    }
+}
+
+impl DifferentialEvolution {
+    pub fn validate(&self) -> SdeResult<()> {
+        if self.population < 4 {
+            return Err(SdeError::InvalidConfiguration {
+                field: "population".to_string(),
+                reason: format!("must be at least 4, got {}", self.population),
+            });
+        }
+        validate_range("differential_weight", self.differential_weight, 0.0, 2.0)?;
+        validate_range("crossover", self.crossover, 0.0, 1.0)?;
+        validate_non_negative("tolerance", self.tolerance)
+    }
+
+    /// Minimize `objective` over `bounds`
+    ///
+    /// The population is drawn uniformly in the box with `x0` as its first
+    /// member. Trial vectors are generated sequentially from an RNG seeded
+    /// with `seed`, and evaluated in parallel, so results are reproducible
+    /// for any thread count. Points where `objective` fails lose every
+    /// comparison.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` for invalid settings or
+    /// bounds, or a starting point of the wrong dimension, and
+    /// `SdeError::CalibrationError` if `objective` fails on the whole
+    /// initial population.
+    pub fn minimize<F>(
+        &self,
+        objective: F,
+        x0: &[f64],
+        bounds: &Bounds,
+    ) -> SdeResult<OptimizationResult>
+    where
+        F: Fn(&[f64]) -> SdeResult<f64> + Sync,
+    {
+        self.validate()?;
+        check_start(x0, bounds)?;
+        let n = x0.len();
+        let _span = span!(
+            "differential_evolution",
+            population = self.population,
+            generations = self.generations
+        );
+        let value = |x: &Vec<f64>| {
+            objective(x)
+                .ok()
+                .filter(|f| !f.is_nan())
+                .unwrap_or(f64::INFINITY)
+        };
+        let mut rng = rng::seed_rng_from_u64(self.seed);
+
+        let mut members: Vec<Vec<f64>> = Vec::with_capacity(self.population);
+        let mut start = x0.to_vec();
+        bounds.project(&mut start);
+        members.push(start);
+        while members.len() < self.population {
+            members.push(
+                (0..n)
+                    .map(|j| rng.gen_range(bounds.lower[j]..=bounds.upper[j]))
+                    .collect(),
+            );
+        }
+        let mut values: Vec<f64> = members.par_iter().map(value).collect();
+        if values.iter().all(|v| v.is_infinite()) {
+            return Err(SdeError::CalibrationError {
+                reason: "objective failed on the whole initial population".to_string(),
+                current_error: None,
+            });
+        }
+
+        let mut generation = 0;
+        let mut converged = false;
+        while generation < self.generations {
+            generation += 1;
+            let trials: Vec<Vec<f64>> = (0..self.population)
+                .map(|i| {
+                    let [a, b, c] = distinct_others(&mut rng, i, self.population);
+                    let forced = rng.gen_range(0..n);
+                    let mut trial = members[i].clone();
+                    for (j, t) in trial.iter_mut().enumerate() {
+                        if j == forced || rng.gen::<f64>() < self.crossover {
+                            *t = members[a][j]
+                                + self.differential_weight * (members[b][j] - members[c][j]);
+                        }
+                    }
+                    bounds.project(&mut trial);
+                    trial
+                })
+                .collect();
+            let trial_values: Vec<f64> = trials.par_iter().map(value).collect();
+            for (i, (trial, v)) in trials.into_iter().zip(trial_values).enumerate() {
+                if v <= values[i] {
+                    members[i] = trial;
+                    values[i] = v;
+                }
+            }
+
+            let (best, worst) = values
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                    (lo.min(v), hi.max(v))
+                });
+            debug_event!(
+                generation,
+                best,
+                spread = worst - best,
+                "differential_evolution generation"
+            );
+            if worst - best <= self.tolerance * best.abs().max(f64::MIN_POSITIVE) {
+                converged = true;
+                break;
+            }
+        }
+
+        // Ties keep the lowest index, so the result does not depend on order
+        let best =
+            (0..self.population).fold(0, |best, i| if values[i] < values[best] { i } else { best });
+        Ok(OptimizationResult {
+            x: members.swap_remove(best),
+            objective: values[best],
+            iterations: generation,
+            converged,
+        })
+    }
+}
+
+/// Three distinct indices in `0..population`, all different from `i`
+fn distinct_others<R: Rng>(rng: &mut R, i: usize, population: usize) -> [usize; 3] {
+    let mut picked = [i; 3];
+    for k in 0..3 {
+        let mut candidate = rng.gen_range(0..population);
+        while candidate == i || picked[..k].contains(&candidate) {
+            candidate = rng.gen_range(0..population);
+        }
+        picked[k] = candidate;
+    }
+    picked
+}
+
+fn check_start(x0: &[f64], bounds: &Bounds) -> SdeResult<()> {
+    bounds.validate()?;
+    if x0.len() != bounds.dimension() {
+        return Err(SdeError::InvalidConfiguration {
+            field: "x0".to_string(),
+            reason: format!(
+                "expected {} parameters, got {}",
+                bounds.dimension(),
+                x0.len()
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn sum_of_squares(r: &[f64]) -> f64 {
+    r.iter().map(|v| v * v).sum()
+}
+
+/// Forward differences `∂r_i/∂x_j`, stepping backwards at an upper bound
+fn forward_jacobian<F>(
+    residuals: &F,
+    x: &[f64],
+    r: &[f64],
+    bounds: &Bounds,
+) -> SdeResult<DMatrix<f64>>
+where
+    F: Fn(&[f64]) -> SdeResult<Vec<f64>> + Sync,
+{
+    let columns = (0..x.len())
+        .into_par_iter()
+        .map(|j| {
+            let mut h = JACOBIAN_STEP * x[j].abs().max(1.0);
+            if x[j] + h > bounds.upper[j] {
+                h = -h;
+            }
+            let mut shifted = x.to_vec();
+            shifted[j] += h;
+            let r_shifted = residuals(&shifted)?;
+            Ok(r_shifted
+                .iter()
+                .zip(r)
+                .map(|(a, b)| (a - b) / h)
+                .collect::<Vec<f64>>())
+        })
+        .collect::<SdeResult<Vec<_>>>()?;
+    Ok(DMatrix::from_fn(r.len(), x.len(), |i, j| columns[j][i]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenberg_marquardt_with_bounds() {
+        // Rosenbrock as least squares: r = (10(y - x²), 1 - x)
+        let rosenbrock = |p: &[f64]| Ok(vec![10.0 * (p[1] - p[0] * p[0]), 1.0 - p[0]]);
+        let free = Bounds::new(vec![-5.0, -5.0], vec![5.0, 5.0]).expect("Valid bounds");
+        let fit = LevenbergMarquardt::default()
+            .minimize(rosenbrock, &[-1.2, 1.0], &free)
+            .expect("Residuals evaluate");
+        assert!(fit.converged);
+        assert!((fit.x[0] - 1.0).abs() < 1e-6 && (fit.x[1] - 1.0).abs() < 1e-6);
+
+        // With x ≤ 0.5 the minimum sits on the bound: y = x² = 0.25
+        let boxed = Bounds::new(vec![-5.0, -5.0], vec![0.5, 5.0]).expect("Valid bounds");
+        let fit = LevenbergMarquardt::default()
+            .minimize(rosenbrock, &[-1.2, 1.0], &boxed)
+            .expect("Residuals evaluate");
+        assert!((fit.x[0] - 0.5).abs() < 1e-12);
+        assert!((fit.x[1] - 0.25).abs() < 1e-6);
+        assert!((fit.objective - 0.25).abs() < 1e-9);
+
+        assert!(Bounds::new(vec![1.0], vec![0.0]).is_err());
+    }
+
+    #[test]
+    fn test_differential_evolution_finds_global_minimum() {
+        // Rastrigin: a local minimum near every integer point, global at 0
+        let rastrigin = |p: &[f64]| {
+            Ok(p.iter()
+                .map(|x| x * x - 10.0 * (2.0 * std::f64::consts::PI * x).cos() + 10.0)
+                .sum::<f64>())
+        };
+        let bounds = Bounds::new(vec![-5.12; 2], vec![5.12; 2]).expect("Valid bounds");
+        let de = DifferentialEvolution {
+            population: 30,
+            generations: 300,
+            ..Default::default()
+        };
+        let fit = de
+            .minimize(rastrigin, &[4.0, 4.0], &bounds)
+            .expect("Objective evaluates");
+        assert!(fit.x.iter().all(|x| x.abs() < 1e-3), "{:?}", fit.x);
+        assert!(fit.objective < 1e-4);
+
+        // Same seed, same search
+        let again = de
+            .minimize(rastrigin, &[4.0, 4.0], &bounds)
+            .expect("Objective evaluates");
+        assert_eq!(fit.x, again.x);
+
+        let tiny = DifferentialEvolution {
+            population: 3,
+            ..de
+        };
+        assert!(tiny.minimize(rastrigin, &[0.0, 0.0], &bounds).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+// src/calibration/quotes.rs
+//! Option Quotes and Fit Reports
+//!
+//! Calibrators take European option prices as [`OptionQuote`]s, or
+//! Black-Scholes implied volatilities as [`VolQuote`]s, and report the
+//! fitted model value of every quote as a [`QuoteFit`] or [`VolFit`].
+
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::mc::accumulators::Moments;
+pub use crate::mc::compound::OptionType;
+
+/// Market price of a European option
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionQuote {
+    pub strike: f64,
+    pub time_to_expiry: f64,
+    pub market_price: f64,
+    pub option_type: OptionType,
+}
+
+impl OptionQuote {
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_positive("strike", self.strike)?;
+        validate_positive("time_to_expiry", self.time_to_expiry)?;
+        validate_finite("market_price", self.market_price)?;
+        validate_non_negative("market_price", self.market_price)
+    }
+}
+
+/// Model vs market price of one quote
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuoteFit {
+    pub quote: OptionQuote,
+    pub model_price: f64,
+    /// `model_price - market_price`
+    pub error: f64,
+}
+
+impl QuoteFit {
+    pub fn new(quote: OptionQuote, model_price: f64) -> Self {
+        QuoteFit {
+            quote,
+            model_price,
+            error: model_price - quote.market_price,
+        }
+    }
+}
+
+/// Black-Scholes implied volatility of a European option
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VolQuote {
+    pub strike: f64,
+    pub time_to_expiry: f64,
+    pub implied_vol: f64,
+}
+
+impl VolQuote {
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_positive("strike", self.strike)?;
+        validate_positive("time_to_expiry", self.time_to_expiry)?;
+        validate_positive("implied_vol", self.implied_vol)
+    }
+}
+
+/// Model vs market implied volatility of one quote
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VolFit {
+    pub quote: VolQuote,
+    pub model_vol: f64,
+    /// `model_vol - implied_vol`
+    pub error: f64,
+}
+
+impl VolFit {
+    pub fn new(quote: VolQuote, model_vol: f64) -> Self {
+        VolFit {
+            quote,
+            model_vol,
+            error: model_vol - quote.implied_vol,
+        }
+    }
+}
+
+/// Root mean square of `errors` (0 when empty)
+pub fn rmse<I: IntoIterator<Item = f64>>(errors: I) -> f64 {
+    let mut squares = Moments::new();
+    for e in errors {
+        squares.push(e * e);
+    }
+    squares.mean().sqrt()
+}
+
+/// Validate every quote and require at least `min` of them
+pub(crate) fn validate_quotes(quotes: &[OptionQuote], min: usize) -> SdeResult<()> {
+    require_quotes(quotes.len(), min)?;
+    quotes.iter().try_for_each(OptionQuote::validate)
+}
+
+/// Validate every volatility quote and require at least `min` of them
+pub(crate) fn validate_vol_quotes(quotes: &[VolQuote], min: usize) -> SdeResult<()> {
+    require_quotes(quotes.len(), min)?;
+    quotes.iter().try_for_each(VolQuote::validate)
+}
+
+fn require_quotes(count: usize, min: usize) -> SdeResult<()> {
+    if count < min {
+        return Err(SdeError::InvalidConfiguration {
+            field: "quotes".to_string(),
+            reason: format!("at least {} quotes are required, got {}", min, count),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,277 @@
+// src/calibration/sabr.rs
+//! SABR Smile Calibration
+//!
+//! # Per-Expiry Fits
+//!
+//! SABR is a model of one forward, so each expiry is fitted separately:
+//! with `β` fixed (it is poorly identified by a single smile and is usually
+//! set from market convention, e.g. 0.5 for rates or 1 for FX),
+//! `(α, ρ, ν)` minimize
+//! ```text
+//! F(α, ρ, ν) = Σ_i (σ_Hagan(F, K_i, T; α, β, ρ, ν) - σ_mkt,i)²
+//! ```
+//! using [`hagan_implied_vol`]. The starting point is
+//! `α₀ = σ_ATM F^(1-β)`, `ρ₀ = 0`, `ν₀ = 0.5`, with `σ_ATM` the quote
+//! closest to the forward.
+//!
+//! # Smiles
+//!
+//! Each fit is returned as a [`SabrSmile`], which evaluates the calibrated
+//! smile at any strike. For a set of quotes spanning several expiries,
+//! [`SabrCalibrator::calibrate_surface`] uses the forwards `S e^(rT)`, and
+//! [`sabr_vol_surface`] samples the fitted smiles on a strike grid as a
+//! [`VolSurface`].
+
+use crate::analytics::sabr_analytic::hagan_implied_vol;
+use crate::analytics::vol_surface::VolSurface;
+use crate::calibration::optimizer::{Bounds, LevenbergMarquardt};
+use crate::calibration::quotes::{rmse, validate_vol_quotes, VolFit, VolQuote};
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::models::sabr::SabrParams;
+use crate::parallel::prelude::*;
+use crate::trace::span;
+use ndarray::Array2;
+
+/// Search ranges of the calibrated parameters, as `(lower, upper)`
+#[derive(Debug, Clone, Copy)]
+pub struct SabrBounds {
+    pub alpha: (f64, f64),
+    pub rho: (f64, f64),
+    pub nu: (f64, f64),
+}
+
+impl Default for SabrBounds {
+    fn default() -> Self {
+        SabrBounds {
+            alpha: (1e-6, 1e6),
+            rho: (-0.999, 0.999),
+            nu: (1e-4, 5.0),
+        }
+    }
+}
+
+impl SabrBounds {
+    fn to_bounds(self) -> SdeResult<Bounds> {
+        let ranges = [self.alpha, self.rho, self.nu];
+        Bounds::new(
+            ranges.iter().map(|r| r.0).collect(),
+            ranges.iter().map(|r| r.1).collect(),
+        )
+    }
+}
+
+/// Least-squares SABR calibrator with fixed `β`
+#[derive(Debug, Clone)]
+pub struct SabrCalibrator {
+    pub beta: f64,
+    pub bounds: SabrBounds,
+    pub optimizer: LevenbergMarquardt,
+}
+
+/// Calibrated SABR smile of one expiry
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SabrSmile {
+    pub time_to_expiry: f64,
+    /// Fitted parameters, with `f0` the forward and `v0 = 1` (so `alpha`
+    /// is Hagan's initial volatility)
+    pub params: SabrParams,
+    /// Root mean squared implied volatility error over the quotes
+    pub rmse: f64,
+    /// Per-quote model volatilities and errors, in quote order
+    pub fits: Vec<VolFit>,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+impl SabrSmile {
+    /// Calibrated implied volatility at `strike`
+    pub fn implied_vol(&self, strike: f64) -> f64 {
+        let p = &self.params;
+        hagan_implied_vol(
+            p.f0,
+            strike,
+            self.time_to_expiry,
+            p.alpha,
+            p.beta,
+            p.rho,
+            p.nu,
+        )
+    }
+}
+
+impl SabrCalibrator {
+    /// Calibrator with default bounds and optimizer settings
+    pub fn new(beta: f64) -> Self {
+        SabrCalibrator {
+            beta,
+            bounds: SabrBounds::default(),
+            optimizer: LevenbergMarquardt::default(),
+        }
+    }
+
+    /// Validate `β ∈ [0, 1]` and the bounds
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_range("beta", self.beta, 0.0, 1.0)?;
+        self.bounds.to_bounds().map(|_| ())
+    }
+
+    /// Fit `(α, ρ, ν)` to the quotes of one expiry
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` for fewer than 3 quotes or
+    /// quotes with different expiries, and `SdeError` for invalid quotes,
+    /// forward, `β` or bounds.
+    pub fn calibrate_smile(&self, forward: f64, quotes: &[VolQuote]) -> SdeResult<SabrSmile> {
+        self.validate()?;
+        validate_positive("forward", forward)?;
+        validate_vol_quotes(quotes, 3)?;
+        let t = quotes[0].time_to_expiry;
+        if quotes.iter().any(|q| q.time_to_expiry != t) {
+            return Err(SdeError::InvalidConfiguration {
+                field: "quotes".to_string(),
+                reason: "a SABR smile needs quotes of a single expiry".to_string(),
+            });
+        }
+        let _span = span!("calibrate_sabr_smile", quotes = quotes.len(), expiry = t);
+
+        let atm = quotes
+            .iter()
+            .min_by(|a, b| {
+                let distance = |q: &VolQuote| (q.strike / forward).ln().abs();
+                distance(a).total_cmp(&distance(b))
+            })
+            .map_or(0.2, |q| q.implied_vol);
+        let x0 = [atm * forward.powf(1.0 - self.beta), 0.0, 0.5];
+        let smile_vol =
+            |x: &[f64], k: f64| hagan_implied_vol(forward, k, t, x[0], self.beta, x[1], x[2]);
+        let residuals = |x: &[f64]| -> SdeResult<Vec<f64>> {
+            Ok(quotes
+                .iter()
+                .map(|q| smile_vol(x, q.strike) - q.implied_vol)
+                .collect())
+        };
+        let fit = self
+            .optimizer
+            .minimize(residuals, &x0, &self.bounds.to_bounds()?)?;
+
+        let fits: Vec<VolFit> = quotes
+            .iter()
+            .map(|&q| VolFit::new(q, smile_vol(&fit.x, q.strike)))
+            .collect();
+        Ok(SabrSmile {
+            time_to_expiry: t,
+            params: SabrParams {
+                f0: forward,
+                alpha: fit.x[0],
+                beta: self.beta,
+                rho: fit.x[1],
+                nu: fit.x[2],
+                v0: 1.0,
+            },
+            rmse: rmse(fits.iter().map(|f| f.error)),
+            fits,
+            iterations: fit.iterations,
+            converged: fit.converged,
+        })
+    }
+
+    /// Fit one smile per expiry, with forwards `spot · e^(rate·T)`
+    ///
+    /// Returns the smiles in increasing expiry order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError` for invalid spot or rate, or if any expiry fails
+    /// as in [`Self::calibrate_smile`] (e.g. it has fewer than 3 quotes).
+    pub fn calibrate_surface(
+        &self,
+        spot: f64,
+        rate: f64,
+        quotes: &[VolQuote],
+    ) -> SdeResult<Vec<SabrSmile>> {
+        validate_positive("spot", spot)?;
+        validate_finite("rate", rate)?;
+        let mut expiries: Vec<f64> = quotes.iter().map(|q| q.time_to_expiry).collect();
+        expiries.sort_by(f64::total_cmp);
+        expiries.dedup();
+        expiries
+            .par_iter()
+            .map(|&t| {
+                let smile: Vec<VolQuote> = quotes
+                    .iter()
+                    .filter(|q| q.time_to_expiry == t)
+                    .copied()
+                    .collect();
+                self.calibrate_smile(spot * (rate * t).exp(), &smile)
+            })
+            .collect()
+    }
+}
+
+/// Sample calibrated smiles on `strikes` as a [`VolSurface`] with one
+/// maturity per smile
+///
+/// # Errors
+///
+/// Returns `SdeError` if the smiles are not in strictly increasing expiry
+/// order, the strike grid is invalid or a smile has no positive volatility
+/// at a grid strike.
+pub fn sabr_vol_surface(smiles: &[SabrSmile], strikes: Vec<f64>) -> SdeResult<VolSurface> {
+    let maturities: Vec<f64> = smiles.iter().map(|s| s.time_to_expiry).collect();
+    let vols = Array2::from_shape_fn((smiles.len(), strikes.len()), |(i, j)| {
+        smiles[i].implied_vol(strikes[j])
+    });
+    VolSurface::new(strikes, maturities, vols)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovers_smiles_per_expiry() {
+        let (spot, rate, beta) = (100.0, 0.03, 0.5);
+        let truth = [(0.5, 2.0, -0.3, 0.6), (2.0, 1.8, -0.5, 0.4)];
+        let quotes: Vec<VolQuote> = truth
+            .iter()
+            .flat_map(|&(t, alpha, rho, nu)| {
+                let forward = spot * f64::exp(rate * t);
+                (0..9).map(move |i| {
+                    let k = 70.0 + 7.5 * i as f64;
+                    VolQuote {
+                        strike: k,
+                        time_to_expiry: t,
+                        implied_vol: hagan_implied_vol(forward, k, t, alpha, beta, rho, nu),
+                    }
+                })
+            })
+            .collect();
+
+        let smiles = SabrCalibrator::new(beta)
+            .calibrate_surface(spot, rate, &quotes)
+            .expect("Valid quotes");
+        assert_eq!(smiles.len(), 2);
+        for (smile, &(t, alpha, rho, nu)) in smiles.iter().zip(&truth) {
+            assert_eq!(smile.time_to_expiry, t);
+            assert!(smile.rmse < 1e-10, "rmse {}", smile.rmse);
+            let p = smile.params;
+            assert!((p.alpha - alpha).abs() < 1e-6, "{:?}", p);
+            assert!((p.rho - rho).abs() < 1e-6, "{:?}", p);
+            assert!((p.nu - nu).abs() < 1e-6, "{:?}", p);
+            // The smile interpolates between quoted strikes
+            let expected = hagan_implied_vol(p.f0, 101.0, t, alpha, beta, rho, nu);
+            assert!((smile.implied_vol(101.0) - expected).abs() < 1e-8);
+        }
+
+        let surface = sabr_vol_surface(&smiles, vec![80.0, 100.0, 120.0]).expect("Sorted smiles");
+        assert!((surface.implied_vol(100.0, 2.0) - smiles[1].implied_vol(100.0)).abs() < 1e-15);
+        assert!(surface.check_arbitrage(spot, rate).is_ok());
+
+        let too_few = &quotes[..2];
+        assert!(SabrCalibrator::new(beta)
+            .calibrate_smile(spot, too_few)
+            .is_err());
+    }
+}
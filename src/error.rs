@@ -1,5 +1,27 @@
 // src/error.rs
+//! Error and warning types
+//!
+//! # Errors
+//!
+//! Every fallible operation returns [`SdeResult`]. Errors raised while
+//! simulating a path are wrapped in [`SdeError::SimulationFailure`], which
+//! records the path and step at which the scheme failed and chains to the
+//! underlying error through [`std::error::Error::source`]:
+//! ```text
+//! Simulation failed on path 8123 at step 41
+//!   caused by: Numerical instability in Heston Alfonsi: variance became invalid after step: NaN
+//! ```
+//!
+//! # Warnings
+//!
+//! Some schemes silently repair a state instead of failing (truncating a
+//! negative variance at zero, flooring a price). Each repair is counted in
+//! a [`Warnings`] value returned next to the result, so a run that
+//! succeeded but degraded numerically can be monitored rather than
+//! discovered later.
+
 use std::fmt;
+use std::ops::AddAssign;
 
 /// Custom error types for the fast-sde library
 #[derive(Debug, Clone)]
@@ -42,6 +64,76 @@ pub enum SdeError {
 
     /// Unsupported operation
     UnsupportedOperation { operation: String, context: String },
+
+    /// A consistency check on computed results failed
+    CheckFailed { check: String, detail: String },
+
+    /// An error raised while simulating a path, with where it occurred
+    SimulationFailure {
+        /// Index of the failing path, if known
+        path: Option<usize>,
+        /// Time step at which the failure occurred, if known
+        step: Option<usize>,
+        source: Box<SdeError>,
+    },
+}
+
+impl SdeError {
+    /// Record the path on which this error occurred
+    pub fn at_path(self, path: usize) -> Self {
+        match self {
+            SdeError::SimulationFailure { step, source, .. } => SdeError::SimulationFailure {
+                path: Some(path),
+                step,
+                source,
+            },
+            other => SdeError::SimulationFailure {
+                path: Some(path),
+                step: None,
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// Record the time step at which this error occurred
+    pub fn at_step(self, step: usize) -> Self {
+        match self {
+            SdeError::SimulationFailure { path, source, .. } => SdeError::SimulationFailure {
+                path,
+                step: Some(step),
+                source,
+            },
+            other => SdeError::SimulationFailure {
+                path: None,
+                step: Some(step),
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// Index of the path on which the error occurred
+    pub fn path_index(&self) -> Option<usize> {
+        match self {
+            SdeError::SimulationFailure { path, .. } => *path,
+            _ => None,
+        }
+    }
+
+    /// Time step at which the error occurred
+    pub fn step_index(&self) -> Option<usize> {
+        match self {
+            SdeError::SimulationFailure { step, .. } => *step,
+            _ => None,
+        }
+    }
+
+    /// Innermost error, with the simulation context removed
+    pub fn root_cause(&self) -> &SdeError {
+        match self {
+            SdeError::SimulationFailure { source, .. } => source.root_cause(),
+            other => other,
+        }
+    }
 }
 
 impl fmt::Display for SdeError {
@@ -113,11 +205,63 @@ impl fmt::Display for SdeError {
                     operation, context
                 )
             }
+            SdeError::CheckFailed { check, detail } => {
+                write!(f, "Sanity check '{}' failed: {}", check, detail)
+            }
+            SdeError::SimulationFailure { path, step, source } => {
+                write!(f, "Simulation failed")?;
+                if let Some(path) = path {
+                    write!(f, " on path {}", path)?;
+                }
+                if let Some(step) = step {
+                    write!(f, " at step {}", step)?;
+                }
+                write!(f, ": {}", source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SdeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SdeError::SimulationFailure { source, .. } => Some(source.as_ref()),
+            _ => None,
         }
     }
 }
 
-impl std::error::Error for SdeError {}
+/// Counts of numerical repairs made during a simulation
+///
+/// Schemes return one per step; callers add them up with `+=` and report
+/// the total alongside the result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Warnings {
+    /// Steps whose variance update went negative and was truncated at zero
+    pub variance_truncations: u64,
+    /// Steps whose price was floored to stay positive
+    pub price_floors: u64,
+}
+
+impl Warnings {
+    /// Whether no repair was made
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// Total number of repairs
+    pub fn total(&self) -> u64 {
+        self.variance_truncations + self.price_floors
+    }
+}
+
+impl AddAssign for Warnings {
+    fn add_assign(&mut self, other: Warnings) {
+        self.variance_truncations += other.variance_truncations;
+        self.price_floors += other.price_floors;
+    }
+}
 
 /// Result type alias for fast-sde operations
 pub type SdeResult<T> = Result<T, SdeError>;
@@ -276,4 +420,44 @@ mod tests {
         assert!(display.contains("0.08"));
         assert!(display.contains("0.25")); // xi^2
     }
+
+    #[test]
+    fn test_simulation_failure_context() {
+        use std::error::Error;
+
+        let cause = SdeError::NumericalInstability {
+            method: "Heston step".to_string(),
+            reason: "variance is invalid: NaN".to_string(),
+        };
+        let error = cause.clone().at_step(41).at_path(8123);
+        assert_eq!(error.path_index(), Some(8123));
+        assert_eq!(error.step_index(), Some(41));
+        assert_eq!(
+            error.to_string(),
+            format!("Simulation failed on path 8123 at step 41: {}", cause)
+        );
+
+        // One level of context, chained to the original error
+        let source = error.source().expect("Context has a source");
+        assert_eq!(source.to_string(), cause.to_string());
+        assert!(source.source().is_none());
+        assert!(matches!(
+            error.root_cause(),
+            SdeError::NumericalInstability { .. }
+        ));
+        assert_eq!(cause.path_index(), None);
+
+        let mut warnings = Warnings::default();
+        assert!(warnings.is_empty());
+        warnings += Warnings {
+            variance_truncations: 2,
+            price_floors: 1,
+        };
+        warnings += Warnings {
+            variance_truncations: 1,
+            price_floors: 0,
+        };
+        assert_eq!(warnings.variance_truncations, 3);
+        assert_eq!(warnings.total(), 4);
+    }
 }
@@ -0,0 +1,2 @@
+pub mod heston_calibration;
+pub mod vanilla;
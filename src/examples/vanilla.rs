@@ -0,0 +1,269 @@
+// src/examples/vanilla.rs
+//! Monte Carlo vs Analytic Reports
+//!
+//! The workflow of the `demo` example as library functions: price a
+//! European call and its Greeks by Monte Carlo, compare each against the
+//! Black-Scholes closed form, and price the path-dependent payoffs (Asian,
+//! up-and-out barriers) on the same market.
+//!
+//! # Usage
+//!
+//! ```text
+//! let report = run_vanilla_vs_analytic_report(&VanillaReportInputs::default())?;
+//! assert!(report.price.rel_error < 0.01);
+//! ```
+
+use crate::analytics::bs_analytic;
+use crate::error::{validation::*, SdeResult};
+use crate::math_utils::Timer;
+use crate::mc::mc_engine::{
+    mc_delta_european_call_gbm_pathwise, mc_gamma_european_call_gbm_finite_diff_batched,
+    mc_price_option_gbm, mc_rho_european_call_gbm_pathwise, mc_vega_european_call_gbm_pathwise,
+    GreeksConfig, McConfig,
+};
+use crate::mc::payoffs::Payoff;
+
+/// Market and simulation settings shared by the reports
+#[derive(Debug, Clone, Copy)]
+pub struct VanillaReportInputs {
+    pub paths: usize,
+    pub steps: usize,
+    pub s0: f64,
+    pub k: f64,
+    pub r: f64,
+    pub sigma: f64,
+    pub t: f64,
+    pub seed: u64,
+    /// Up-and-out barrier level for [`run_exotic_price_report`]
+    pub barrier: f64,
+}
+
+impl Default for VanillaReportInputs {
+    fn default() -> Self {
+        VanillaReportInputs {
+            paths: 100_000,
+            steps: 252,
+            s0: 100.0,
+            k: 100.0,
+            r: 0.01,
+            sigma: 0.2,
+            t: 1.0,
+            seed: 12345,
+            barrier: 120.0,
+        }
+    }
+}
+
+impl VanillaReportInputs {
+    /// Validate the inputs
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_paths(self.paths)?;
+        validate_steps(self.steps)?;
+        validate_positive("s0", self.s0)?;
+        validate_positive("k", self.k)?;
+        validate_finite("r", self.r)?;
+        validate_positive("sigma", self.sigma)?;
+        validate_positive("t", self.t)?;
+        validate_positive("barrier", self.barrier)?;
+        Ok(())
+    }
+
+    /// Monte Carlo configuration for `payoff` with antithetic variates
+    pub fn mc_config(&self, payoff: Payoff) -> McConfig {
+        McConfig {
+            paths: self.paths,
+            steps: self.steps,
+            s0: self.s0,
+            r: self.r,
+            sigma: self.sigma,
+            t: self.t,
+            seed: self.seed,
+            use_antithetic: true,
+            use_control_variate: false,
+            payoff,
+            greeks: GreeksConfig::DELTA
+                | GreeksConfig::VEGA
+                | GreeksConfig::RHO
+                | GreeksConfig::GAMMA,
+            epsilon: Some(0.001 * self.s0),
+            ..Default::default()
+        }
+    }
+}
+
+/// One Monte Carlo estimate against its closed form
+#[derive(Debug, Clone, Copy)]
+pub struct Comparison {
+    pub mc: f64,
+    pub analytic: f64,
+    pub abs_error: f64,
+    pub rel_error: f64,
+    /// Wall-clock time of the Monte Carlo estimate
+    pub elapsed_ms: f64,
+}
+
+impl Comparison {
+    fn new(mc: f64, analytic: f64, elapsed_ms: f64) -> Self {
+        let abs_error = (mc - analytic).abs();
+        Comparison {
+            mc,
+            analytic,
+            abs_error,
+            rel_error: abs_error / analytic.abs(),
+            elapsed_ms,
+        }
+    }
+}
+
+/// European call price and Greeks, Monte Carlo vs Black-Scholes
+#[derive(Debug, Clone, Copy)]
+pub struct VanillaReport {
+    pub inputs: VanillaReportInputs,
+    pub price: Comparison,
+    /// Pathwise
+    pub delta: Comparison,
+    /// Pathwise
+    pub vega: Comparison,
+    /// Pathwise
+    pub rho: Comparison,
+    /// Finite difference
+    pub gamma: Comparison,
+}
+
+impl VanillaReport {
+    /// `(metric, value)` rows in the layout of `output::write_summary_to_csv`
+    pub fn summary_rows(&self) -> Vec<(String, String)> {
+        let mut rows = Vec::new();
+        for (name, c) in [
+            ("price", &self.price),
+            ("delta", &self.delta),
+            ("vega", &self.vega),
+            ("rho", &self.rho),
+            ("gamma", &self.gamma),
+        ] {
+            rows.push((format!("mc_{}_european", name), c.mc.to_string()));
+            rows.push((
+                format!("analytic_{}_european", name),
+                c.analytic.to_string(),
+            ));
+            rows.push((
+                format!("abs_error_{}_european", name),
+                c.abs_error.to_string(),
+            ));
+            rows.push((
+                format!("rel_error_{}_european", name),
+                c.rel_error.to_string(),
+            ));
+            rows.push((
+                format!("{}_time_ms_european", name),
+                c.elapsed_ms.to_string(),
+            ));
+        }
+        rows
+    }
+}
+
+/// Price a European call struck at `inputs.k` and its Greeks by Monte Carlo
+/// and compare each with the Black-Scholes value
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid inputs or pricing failures.
+pub fn run_vanilla_vs_analytic_report(inputs: &VanillaReportInputs) -> SdeResult<VanillaReport> {
+    inputs.validate()?;
+    let cfg = inputs.mc_config(Payoff::EuropeanCall { k: inputs.k });
+    let VanillaReportInputs {
+        s0, k, r, sigma, t, ..
+    } = *inputs;
+    let mut timer = Timer::new();
+
+    timer.start();
+    let (price, _) = mc_price_option_gbm(&cfg)?;
+    let price = Comparison::new(
+        price,
+        bs_analytic::bs_call_price(s0, k, r, sigma, t),
+        timer.elapsed_ms(),
+    );
+
+    let mut timed = |estimate: fn(&McConfig) -> f64, analytic: f64| {
+        timer.start();
+        let mc = estimate(&cfg);
+        Comparison::new(mc, analytic, timer.elapsed_ms())
+    };
+    let delta = timed(
+        mc_delta_european_call_gbm_pathwise,
+        bs_analytic::bs_call_delta(s0, k, r, sigma, t),
+    );
+    let vega = timed(
+        mc_vega_european_call_gbm_pathwise,
+        bs_analytic::bs_call_vega(s0, k, r, sigma, t),
+    );
+    let rho = timed(
+        mc_rho_european_call_gbm_pathwise,
+        bs_analytic::bs_call_rho(s0, k, r, sigma, t),
+    );
+    let gamma = timed(
+        mc_gamma_european_call_gbm_finite_diff_batched,
+        bs_analytic::bs_call_gamma(s0, k, r, sigma, t),
+    );
+
+    Ok(VanillaReport {
+        inputs: *inputs,
+        price,
+        delta,
+        vega,
+        rho,
+        gamma,
+    })
+}
+
+/// Monte Carlo price of a payoff without a closed form
+#[derive(Debug, Clone, Copy)]
+pub struct ExoticPrice {
+    pub price: f64,
+    pub std_error: f64,
+    pub elapsed_ms: f64,
+    pub paths_per_sec: f64,
+}
+
+/// Asian and up-and-out barrier prices on the vanilla market
+#[derive(Debug, Clone, Copy)]
+pub struct ExoticPriceReport {
+    pub inputs: VanillaReportInputs,
+    pub asian_call: ExoticPrice,
+    pub barrier_call_up_and_out: ExoticPrice,
+    pub barrier_put_up_and_out: ExoticPrice,
+}
+
+/// Price the Asian call and the up-and-out barrier call and put
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid inputs or pricing failures.
+pub fn run_exotic_price_report(inputs: &VanillaReportInputs) -> SdeResult<ExoticPriceReport> {
+    inputs.validate()?;
+    let (k, h) = (inputs.k, inputs.barrier);
+    let price = |payoff: Payoff| -> SdeResult<ExoticPrice> {
+        let cfg = McConfig {
+            greeks: GreeksConfig::NONE,
+            ..inputs.mc_config(payoff)
+        };
+        let mut timer = Timer::new();
+        timer.start();
+        let (price, variance) = mc_price_option_gbm(&cfg)?;
+        let elapsed_ms = timer.elapsed_ms();
+        Ok(ExoticPrice {
+            price,
+            std_error: variance.sqrt(),
+            elapsed_ms,
+            paths_per_sec: cfg.paths as f64 / (elapsed_ms / 1000.0),
+        })
+    };
+
+    Ok(ExoticPriceReport {
+        inputs: *inputs,
+        asian_call: price(Payoff::AsianCall { k })?,
+        barrier_call_up_and_out: price(Payoff::BarrierCallUpAndOut { k, h })?,
+        barrier_put_up_and_out: price(Payoff::BarrierPutUpAndOut { k, h })?,
+    })
+}
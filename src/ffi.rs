@@ -0,0 +1,637 @@
+// src/ffi.rs
+//! C-compatible FFI layer
+//!
+//! `extern "C"` entry points over plain `#[repr(C)]` structs, for C, C++,
+//! C# (P/Invoke) and Excel add-ins. Built into the `cdylib` with the `ffi`
+//! feature, which also regenerates the header `include/fast_sde.h`.
+//!
+//! # Conventions
+//!
+//! Every function returns an [`FsdeStatus`] and writes its result through
+//! an output pointer, which is left untouched on failure. The message of
+//! the last failure on the calling thread is available from
+//! [`fsde_last_error_message`]. Panics are caught at the boundary and
+//! reported as [`FsdeStatus::Panic`].
+//!
+//! Structs carry enums as `uint32_t` and flags as `uint8_t` (0 or 1), so
+//! their layout does not depend on how the caller's language sizes enums
+//! and booleans. Values outside the enum or other than 0 and 1 are
+//! rejected with [`FsdeStatus::InvalidConfiguration`].
+//!
+//! ```text
+//! FsdeGbmConfig cfg = { 100000, 1, 100.0, 0.05, 0.2, 1.0, 42, 1, 0 };
+//! FsdePayoff call = { FSDE_PAYOFF_KIND_EUROPEAN_CALL, 100.0, 0.0, 0.0 };
+//! FsdePriceResult result;
+//! if (fsde_price_gbm(&cfg, &call, &result) != FSDE_STATUS_OK) {
+//!     char message[256];
+//!     fsde_last_error_message(message, sizeof message);
+//! }
+//! ```
+
+use crate::analytics::heston_analytic::{heston_call_price, heston_put_price};
+use crate::error::{SdeError, SdeResult};
+use crate::mc::greeks_plan::mc_greeks_gbm;
+use crate::mc::heston_greeks::{HestonGreeks, HestonGreeksConfig};
+use crate::mc::mc_engine::{mc_price_option_gbm, GreeksConfig, McConfig};
+use crate::mc::payoffs::Payoff;
+use crate::models::heston::{HestonParams, HestonScheme};
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::{self, UnwindSafe};
+
+/// Result code of every FFI function
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsdeStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidParameters = 2,
+    NumericalInstability = 3,
+    FellerConditionViolation = 4,
+    InvalidConfiguration = 5,
+    MonteCarloError = 6,
+    PayoffError = 7,
+    RandomGenerationError = 8,
+    CalibrationError = 9,
+    UnsupportedOperation = 10,
+    CheckFailed = 11,
+    Panic = 12,
+}
+
+impl From<&SdeError> for FsdeStatus {
+    fn from(err: &SdeError) -> Self {
+        match err {
+            SdeError::InvalidParameters { .. } => FsdeStatus::InvalidParameters,
+            SdeError::NumericalInstability { .. } => FsdeStatus::NumericalInstability,
+            SdeError::FellerConditionViolation { .. } => FsdeStatus::FellerConditionViolation,
+            SdeError::InvalidConfiguration { .. } => FsdeStatus::InvalidConfiguration,
+            SdeError::MonteCarloError { .. } => FsdeStatus::MonteCarloError,
+            SdeError::PayoffError { .. } => FsdeStatus::PayoffError,
+            SdeError::RandomGenerationError { .. } => FsdeStatus::RandomGenerationError,
+            SdeError::CalibrationError { .. } => FsdeStatus::CalibrationError,
+            SdeError::UnsupportedOperation { .. } => FsdeStatus::UnsupportedOperation,
+            SdeError::CheckFailed { .. } => FsdeStatus::CheckFailed,
+            SdeError::SimulationFailure { source, .. } => FsdeStatus::from(source.as_ref()),
+        }
+    }
+}
+
+/// Payoff families available through the FFI, passed as `uint32_t`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsdePayoffKind {
+    EuropeanCall = 0,
+    EuropeanPut = 1,
+    AsianCall = 2,
+    BarrierCallUpAndOut = 3,
+    BarrierPutUpAndOut = 4,
+    DigitalCall = 5,
+    DigitalPut = 6,
+    PowerCall = 7,
+}
+
+impl TryFrom<u32> for FsdePayoffKind {
+    type Error = SdeError;
+
+    fn try_from(kind: u32) -> SdeResult<Self> {
+        Ok(match kind {
+            0 => FsdePayoffKind::EuropeanCall,
+            1 => FsdePayoffKind::EuropeanPut,
+            2 => FsdePayoffKind::AsianCall,
+            3 => FsdePayoffKind::BarrierCallUpAndOut,
+            4 => FsdePayoffKind::BarrierPutUpAndOut,
+            5 => FsdePayoffKind::DigitalCall,
+            6 => FsdePayoffKind::DigitalPut,
+            7 => FsdePayoffKind::PowerCall,
+            _ => return Err(unknown_value("kind", kind)),
+        })
+    }
+}
+
+/// Payoff: `strike` for all kinds, `barrier` for the barrier kinds and
+/// `exponent` for `PowerCall`; unused fields are ignored
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FsdePayoff {
+    /// An [`FsdePayoffKind`]
+    pub kind: u32,
+    pub strike: f64,
+    pub barrier: f64,
+    pub exponent: f64,
+}
+
+impl TryFrom<&FsdePayoff> for Payoff {
+    type Error = SdeError;
+
+    fn try_from(p: &FsdePayoff) -> SdeResult<Self> {
+        let k = p.strike;
+        Ok(match FsdePayoffKind::try_from(p.kind)? {
+            FsdePayoffKind::EuropeanCall => Payoff::EuropeanCall { k },
+            FsdePayoffKind::EuropeanPut => Payoff::EuropeanPut { k },
+            FsdePayoffKind::AsianCall => Payoff::AsianCall { k },
+            FsdePayoffKind::BarrierCallUpAndOut => Payoff::BarrierCallUpAndOut { k, h: p.barrier },
+            FsdePayoffKind::BarrierPutUpAndOut => Payoff::BarrierPutUpAndOut { k, h: p.barrier },
+            FsdePayoffKind::DigitalCall => Payoff::DigitalCall { k },
+            FsdePayoffKind::DigitalPut => Payoff::DigitalPut { k },
+            FsdePayoffKind::PowerCall => Payoff::PowerCall { k, p: p.exponent },
+        })
+    }
+}
+
+/// GBM engine settings (subset of [`McConfig`]; other fields take their
+/// defaults)
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FsdeGbmConfig {
+    pub paths: u64,
+    pub steps: u64,
+    pub s0: f64,
+    pub r: f64,
+    pub sigma: f64,
+    pub t: f64,
+    pub seed: u64,
+    /// 0 or 1
+    pub antithetic: u8,
+    /// 0 or 1
+    pub control_variate: u8,
+}
+
+impl FsdeGbmConfig {
+    fn to_config(self, payoff: &FsdePayoff) -> SdeResult<McConfig> {
+        Ok(McConfig {
+            paths: to_usize("paths", self.paths)?,
+            steps: to_usize("steps", self.steps)?,
+            s0: self.s0,
+            r: self.r,
+            sigma: self.sigma,
+            t: self.t,
+            seed: self.seed,
+            use_antithetic: to_bool("antithetic", self.antithetic)?,
+            use_control_variate: to_bool("control_variate", self.control_variate)?,
+            payoff: payoff.try_into()?,
+            ..Default::default()
+        })
+    }
+}
+
+/// Price and its Monte Carlo standard error
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsdePriceResult {
+    pub price: f64,
+    pub std_error: f64,
+}
+
+/// Delta bit of the `greeks` mask of [`fsde_greeks_gbm`]
+pub const FSDE_GREEK_DELTA: u32 = 1 << 0;
+/// Vega bit of the `greeks` mask
+pub const FSDE_GREEK_VEGA: u32 = 1 << 1;
+/// Rho bit of the `greeks` mask
+pub const FSDE_GREEK_RHO: u32 = 1 << 2;
+/// Gamma bit of the `greeks` mask
+pub const FSDE_GREEK_GAMMA: u32 = 1 << 3;
+
+/// GBM Greeks; entries not requested are NaN
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FsdeGreeks {
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub rho: f64,
+}
+
+/// Heston model parameters
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FsdeHestonParams {
+    pub s0: f64,
+    pub v0: f64,
+    pub r: f64,
+    pub kappa: f64,
+    pub theta: f64,
+    pub xi: f64,
+    pub rho: f64,
+}
+
+impl From<&FsdeHestonParams> for HestonParams {
+    fn from(p: &FsdeHestonParams) -> Self {
+        HestonParams {
+            s0: p.s0,
+            v0: p.v0,
+            r: p.r,
+            kappa: p.kappa,
+            theta: p.theta,
+            xi: p.xi,
+            rho: p.rho,
+        }
+    }
+}
+
+/// Heston discretization scheme, passed as `uint32_t`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsdeHestonScheme {
+    FullTruncationEuler = 0,
+    AndersenQe = 1,
+    Alfonsi = 2,
+    AndersenQem = 3,
+}
+
+impl TryFrom<u32> for FsdeHestonScheme {
+    type Error = SdeError;
+
+    fn try_from(scheme: u32) -> SdeResult<Self> {
+        Ok(match scheme {
+            0 => FsdeHestonScheme::FullTruncationEuler,
+            1 => FsdeHestonScheme::AndersenQe,
+            2 => FsdeHestonScheme::Alfonsi,
+            3 => FsdeHestonScheme::AndersenQem,
+            _ => return Err(unknown_value("scheme", scheme)),
+        })
+    }
+}
+
+impl From<FsdeHestonScheme> for HestonScheme {
+    fn from(scheme: FsdeHestonScheme) -> Self {
+        match scheme {
+            FsdeHestonScheme::FullTruncationEuler => HestonScheme::FullTruncationEuler,
+            FsdeHestonScheme::AndersenQe => HestonScheme::AndersenQE,
+            FsdeHestonScheme::Alfonsi => HestonScheme::Alfonsi,
+            FsdeHestonScheme::AndersenQem => HestonScheme::AndersenQEM,
+        }
+    }
+}
+
+/// Heston Monte Carlo settings
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FsdeHestonConfig {
+    pub paths: u64,
+    pub steps: u64,
+    pub t: f64,
+    pub seed: u64,
+    /// 0 or 1
+    pub antithetic: u8,
+    /// An [`FsdeHestonScheme`]
+    pub scheme: u32,
+}
+
+/// Heston price and bump-and-revalue sensitivities
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsdeHestonGreeks {
+    pub price: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega_v0: f64,
+    pub dv_dkappa: f64,
+    pub dv_dtheta: f64,
+    pub dv_dxi: f64,
+    pub dv_drho: f64,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Failure inside an FFI call
+enum FfiError {
+    NullPointer(&'static str),
+    Sde(SdeError),
+}
+
+impl From<SdeError> for FfiError {
+    fn from(err: SdeError) -> Self {
+        FfiError::Sde(err)
+    }
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Run `body`, translating errors and panics into a status code
+fn ffi_call(body: impl FnOnce() -> Result<(), FfiError> + UnwindSafe) -> FsdeStatus {
+    match panic::catch_unwind(body) {
+        Ok(Ok(())) => FsdeStatus::Ok,
+        Ok(Err(FfiError::NullPointer(name))) => {
+            set_last_error(format!("null pointer passed as '{}'", name));
+            FsdeStatus::NullPointer
+        }
+        Ok(Err(FfiError::Sde(err))) => {
+            set_last_error(err.to_string());
+            FsdeStatus::from(&err)
+        }
+        Err(_) => {
+            set_last_error("panic inside fast-sde".to_string());
+            FsdeStatus::Panic
+        }
+    }
+}
+
+/// Shared reference behind `ptr`, or a null-pointer error naming `name`
+///
+/// # Safety
+///
+/// `ptr` must be null or valid for reads of `T` for the duration of the call.
+unsafe fn input<'a, T>(name: &'static str, ptr: *const T) -> Result<&'a T, FfiError> {
+    ptr.as_ref().ok_or(FfiError::NullPointer(name))
+}
+
+/// Exclusive reference behind `ptr`, or a null-pointer error naming `name`
+///
+/// # Safety
+///
+/// `ptr` must be null or valid for writes of `T` for the duration of the
+/// call.
+unsafe fn output<'a, T>(name: &'static str, ptr: *mut T) -> Result<&'a mut T, FfiError> {
+    ptr.as_mut().ok_or(FfiError::NullPointer(name))
+}
+
+fn to_usize(field: &str, value: u64) -> SdeResult<usize> {
+    usize::try_from(value).map_err(|_| SdeError::InvalidConfiguration {
+        field: field.to_string(),
+        reason: format!("{} exceeds the platform's address size", value),
+    })
+}
+
+fn to_bool(field: &str, value: u8) -> SdeResult<bool> {
+    match value {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(unknown_value(field, value)),
+    }
+}
+
+fn unknown_value(field: &str, value: impl std::fmt::Display) -> SdeError {
+    SdeError::InvalidConfiguration {
+        field: field.to_string(),
+        reason: format!("unknown value {}", value),
+    }
+}
+
+/// Price `payoff` under GBM with the Monte Carlo engine
+///
+/// # Safety
+///
+/// `config` and `payoff` must point to valid structs and `out` must be
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn fsde_price_gbm(
+    config: *const FsdeGbmConfig,
+    payoff: *const FsdePayoff,
+    out: *mut FsdePriceResult,
+) -> FsdeStatus {
+    ffi_call(|| {
+        let cfg = input("config", config)?.to_config(input("payoff", payoff)?)?;
+        let out = output("out", out)?;
+        let (price, variance) = mc_price_option_gbm(&cfg)?;
+        *out = FsdePriceResult {
+            price,
+            std_error: variance.sqrt(),
+        };
+        Ok(())
+    })
+}
+
+/// GBM Greeks selected by `greeks`, a mask of `FSDE_GREEK_*` bits
+///
+/// # Safety
+///
+/// `config` and `payoff` must point to valid structs and `out` must be
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn fsde_greeks_gbm(
+    config: *const FsdeGbmConfig,
+    payoff: *const FsdePayoff,
+    greeks: u32,
+    out: *mut FsdeGreeks,
+) -> FsdeStatus {
+    ffi_call(|| {
+        let mut cfg = input("config", config)?.to_config(input("payoff", payoff)?)?;
+        let out = output("out", out)?;
+        cfg.greeks =
+            GreeksConfig::from_bits(greeks).ok_or_else(|| SdeError::InvalidConfiguration {
+                field: "greeks".to_string(),
+                reason: format!("unknown bits in mask {:#x}", greeks),
+            })?;
+        let report = mc_greeks_gbm(&cfg)?;
+        *out = FsdeGreeks {
+            delta: report.delta.unwrap_or(f64::NAN),
+            gamma: report.gamma.unwrap_or(f64::NAN),
+            vega: report.vega.unwrap_or(f64::NAN),
+            rho: report.rho.unwrap_or(f64::NAN),
+        };
+        Ok(())
+    })
+}
+
+/// Semi-analytic Heston price of a European call (`call` 1) or put
+/// (`call` 0)
+///
+/// # Safety
+///
+/// `params` must point to a valid struct and `out` must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn fsde_heston_price(
+    params: *const FsdeHestonParams,
+    strike: f64,
+    t: f64,
+    call: u8,
+    out: *mut f64,
+) -> FsdeStatus {
+    ffi_call(|| {
+        let params = HestonParams::from(input("params", params)?);
+        let call = to_bool("call", call)?;
+        let out = output("out", out)?;
+        *out = if call {
+            heston_call_price(&params, strike, t)?
+        } else {
+            heston_put_price(&params, strike, t)?
+        };
+        Ok(())
+    })
+}
+
+/// Heston Monte Carlo price and sensitivities of `payoff` with common
+/// random numbers
+///
+/// # Safety
+///
+/// `params`, `config` and `payoff` must point to valid structs and `out`
+/// must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn fsde_heston_greeks(
+    params: *const FsdeHestonParams,
+    config: *const FsdeHestonConfig,
+    payoff: *const FsdePayoff,
+    out: *mut FsdeHestonGreeks,
+) -> FsdeStatus {
+    ffi_call(|| {
+        let params = HestonParams::from(input("params", params)?);
+        let config = input("config", config)?;
+        let cfg = HestonGreeksConfig {
+            paths: to_usize("paths", config.paths)?,
+            steps: to_usize("steps", config.steps)?,
+            t: config.t,
+            seed: config.seed,
+            payoff: input("payoff", payoff)?.try_into()?,
+            use_antithetic: to_bool("antithetic", config.antithetic)?,
+            ..Default::default()
+        };
+        let scheme = FsdeHestonScheme::try_from(config.scheme)?;
+        let out = output("out", out)?;
+        let report = HestonGreeks::new(params, scheme.into())?.compute(&cfg)?;
+        *out = FsdeHestonGreeks {
+            price: report.price,
+            delta: report.delta,
+            gamma: report.gamma,
+            vega_v0: report.vega_v0,
+            dv_dkappa: report.dv_dkappa,
+            dv_dtheta: report.dv_dtheta,
+            dv_dxi: report.dv_dxi,
+            dv_drho: report.dv_drho,
+        };
+        Ok(())
+    })
+}
+
+/// Copy the last error message of the calling thread into `buffer`
+///
+/// Writes at most `len - 1` bytes plus a NUL terminator and returns the
+/// full message length in bytes (0 if no call has failed yet), so a return
+/// value `>= len` means the message was truncated.
+///
+/// # Safety
+///
+/// `buffer` must be null (to query the length) or valid for writes of
+/// `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn fsde_last_error_message(buffer: *mut c_char, len: usize) -> usize {
+    LAST_ERROR.with(|last| {
+        let last = last.borrow();
+        let bytes = last.as_ref().map_or(&[][..], |m| m.as_bytes());
+        if !buffer.is_null() && len > 0 {
+            let n = bytes.len().min(len - 1);
+            std::ptr::copy_nonoverlapping(bytes.as_ptr().cast(), buffer, n);
+            *buffer.add(n) = 0;
+        }
+        bytes.len()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::bs_analytic::bs_call_price;
+    use std::ptr;
+
+    fn gbm_config() -> FsdeGbmConfig {
+        FsdeGbmConfig {
+            paths: 50_000,
+            steps: 1,
+            s0: 100.0,
+            r: 0.05,
+            sigma: 0.2,
+            t: 1.0,
+            seed: 42,
+            antithetic: 1,
+            control_variate: 0,
+        }
+    }
+
+    fn call(strike: f64) -> FsdePayoff {
+        FsdePayoff {
+            kind: FsdePayoffKind::EuropeanCall as u32,
+            strike,
+            barrier: 0.0,
+            exponent: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_price_and_greeks_through_ffi() {
+        let cfg = gbm_config();
+        let mut result = FsdePriceResult::default();
+        let status = unsafe { fsde_price_gbm(&cfg, &call(100.0), &mut result) };
+        assert_eq!(status, FsdeStatus::Ok);
+        let analytic = bs_call_price(100.0, 100.0, 0.05, 0.2, 1.0);
+        assert!((result.price - analytic).abs() < 0.1);
+
+        let mut greeks = FsdeGreeks {
+            delta: 0.0,
+            gamma: 0.0,
+            vega: 0.0,
+            rho: 0.0,
+        };
+        let mask = FSDE_GREEK_DELTA | FSDE_GREEK_VEGA;
+        assert_eq!(mask, (GreeksConfig::DELTA | GreeksConfig::VEGA).bits());
+        assert_eq!(FSDE_GREEK_RHO, GreeksConfig::RHO.bits());
+        assert_eq!(FSDE_GREEK_GAMMA, GreeksConfig::GAMMA.bits());
+        let status = unsafe { fsde_greeks_gbm(&cfg, &call(100.0), mask, &mut greeks) };
+        assert_eq!(status, FsdeStatus::Ok);
+        assert!((greeks.delta - 0.6368).abs() < 0.02);
+        assert!(greeks.gamma.is_nan() && greeks.rho.is_nan());
+
+        let params = FsdeHestonParams {
+            s0: 100.0,
+            v0: 0.04,
+            r: 0.02,
+            kappa: 1.5,
+            theta: 0.04,
+            xi: 0.3,
+            rho: -0.7,
+        };
+        let mut price = 0.0;
+        let status = unsafe { fsde_heston_price(&params, 100.0, 1.0, 1, &mut price) };
+        assert_eq!(status, FsdeStatus::Ok);
+        assert!(price > 5.0 && price < 15.0);
+    }
+
+    #[test]
+    fn test_errors_set_status_and_message() {
+        let mut result = FsdePriceResult::default();
+        let status = unsafe { fsde_price_gbm(ptr::null(), &call(100.0), &mut result) };
+        assert_eq!(status, FsdeStatus::NullPointer);
+
+        let cfg = FsdeGbmConfig {
+            paths: 0,
+            ..gbm_config()
+        };
+        let status = unsafe { fsde_price_gbm(&cfg, &call(100.0), &mut result) };
+        assert_eq!(status, FsdeStatus::InvalidConfiguration);
+
+        let len = unsafe { fsde_last_error_message(ptr::null_mut(), 0) };
+        let mut buffer = vec![0 as c_char; len + 1];
+        let written = unsafe { fsde_last_error_message(buffer.as_mut_ptr(), buffer.len()) };
+        assert_eq!(written, len);
+        let message = unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) };
+        assert!(message.to_string_lossy().contains("paths"));
+
+        // Out-of-range enum and flag values are rejected, not transmuted
+        let payoff = FsdePayoff {
+            kind: 8,
+            ..call(100.0)
+        };
+        let status = unsafe { fsde_price_gbm(&gbm_config(), &payoff, &mut result) };
+        assert_eq!(status, FsdeStatus::InvalidConfiguration);
+        let cfg = FsdeGbmConfig {
+            antithetic: 2,
+            ..gbm_config()
+        };
+        let status = unsafe { fsde_price_gbm(&cfg, &call(100.0), &mut result) };
+        assert_eq!(status, FsdeStatus::InvalidConfiguration);
+        assert_eq!(
+            FsdeHestonScheme::try_from(3).unwrap(),
+            FsdeHestonScheme::AndersenQem
+        );
+        assert!(FsdeHestonScheme::try_from(4).is_err());
+
+        let mut short = [1 as c_char; 4];
+        unsafe { fsde_last_error_message(short.as_mut_ptr(), short.len()) };
+        assert_eq!(short[3], 0);
+    }
+}
@@ -6,10 +6,13 @@
 //! ## Key Features
 //!
 //! - **High Performance**: Parallel Monte Carlo with Rayon, optimized for speed
-//! - **Variance Reduction**: Antithetic variates and control variates
-//! - **Multiple SDE Models**: Black-Scholes, Heston, SABR, Merton jump-diffusion
+//! - **Variance Reduction**: Antithetic variates, control variates and importance
+//!   sampling of rare events
+//! - **Multiple SDE Models**: Black-Scholes, Heston, SABR, Merton and Kou jump-diffusions,
+//!   and a GBM + Hull-White equity-rates hybrid
 //! - **Robust Numerics**: Multiple discretization schemes (Euler, Milstein, SRK)
 //! - **Complete Greeks**: Delta, Gamma, Vega, Rho via pathwise and finite difference
+//! - **Calibration**: Heston, Merton and SABR fits to option quotes via semi-analytic pricers
 //! - **Production Ready**: Comprehensive error handling and validation
 //!
 //! ## Quick Start
@@ -18,22 +21,64 @@
 //! use fast_sde::mc::mc_engine::{mc_price_option_gbm, McConfig};
 //! use fast_sde::mc::payoffs::Payoff;
 //!
-//! // Configure European call option
-//! let config = McConfig {
-//!     paths: 100_000,
-//!     s0: 100.0,      // Spot price
-//!     r: 0.05,        // Risk-free rate
-//!     sigma: 0.2,     // Volatility
-//!     t: 1.0,         // Time to expiration
-//!     payoff: Payoff::EuropeanCall { k: 100.0 },
-//!     ..Default::default()
-//! };
+//! // Configure European call option; `build` validates the settings
+//! let config = McConfig::builder()
+//!     .paths(100_000)
+//!     .s0(100.0)      // Spot price
+//!     .r(0.05)        // Risk-free rate
+//!     .sigma(0.2)     // Volatility
+//!     .t(1.0)         // Time to expiration
+//!     .payoff(Payoff::EuropeanCall { k: 100.0 })
+//!     .build()
+//!     .expect("Valid configuration");
 //!
 //! // Price the option
 //! let (price, variance) = mc_price_option_gbm(&config).expect("Valid configuration");
 //! println!("Option price: {:.4} ± {:.4}", price, variance.sqrt());
 //! ```
 //!
+//! ## Example Workflows
+//!
+//! With the `examples` feature enabled, `fast_sde::examples` exposes the end-to-end
+//! workflows behind the bundled example programs (Monte Carlo vs analytic
+//! reports, Heston calibration) as functions with typed inputs and outputs.
+//!
+//! ## Micro-Benchmarks
+//!
+//! With the `bench` feature, `fast_sde::bench_api` exposes the hot paths
+//! (single GBM and Heston paths, engine samples, normal samplers, payoff
+//! evaluation) as standalone calls for Criterion-style regression suites.
+//!
+//! ## Python
+//!
+//! With the `python` feature, `fast_sde::python` builds the `fast_sde`
+//! Python extension module (via maturin): the GBM engine, Greeks, option
+//! chains, Heston/SABR models and calibration, with NumPy arrays for paths
+//! and price grids.
+//!
+//! ## Parallelism and WebAssembly
+//!
+//! Monte Carlo loops run on Rayon with the default `parallel` feature. With
+//! `--no-default-features` they run sequentially (see [`parallel`]), which
+//! together with the `wasm` feature's wasm-bindgen wrappers lets the engine
+//! price in the browser (`wasm32-unknown-unknown`). The pricing path does no
+//! file I/O; the CSV/JSON readers and writers return `io` errors there.
+//!
+//! ## Finite Differences
+//!
+//! [`pde`] solves the Black-Scholes and local volatility PDE by
+//! Crank-Nicolson with Rannacher smoothing for vanillas, knock-out barriers
+//! and American exercise (PSOR), giving high-accuracy references for the
+//! Monte Carlo barrier and early-exercise pricers.
+//!
+//! ## Tracing
+//!
+//! With the `tracing` feature, pricing runs, their variance-reduction
+//! passes and calibrations (down to individual optimizer iterations) emit
+//! spans and events through the `tracing` crate, including a `WARN` event
+//! for Heston parameters that violate the Feller condition. The library
+//! never writes to stderr.
+//!
 //! ## Mathematical Foundation
 //!
 //! The library implements Monte Carlo methods for pricing derivatives under various
@@ -42,13 +87,32 @@
 
 // Module declarations
 pub mod analytics;
+#[cfg(feature = "bench")]
+pub mod bench_api;
+pub mod calibration;
 pub mod error;
+#[cfg(feature = "examples")]
+pub mod examples;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod market_data;
 pub mod math_utils;
 pub mod mc;
 pub mod models;
 pub mod output;
+pub mod parallel;
+pub mod pde;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod risk;
 pub mod rng;
+#[cfg(feature = "cli")]
+pub mod run_spec;
 pub mod solvers;
+pub mod time;
+mod trace;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 // Re-export commonly used types for convenience
 pub use error::{SdeError, SdeResult};
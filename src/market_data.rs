@@ -0,0 +1,500 @@
+// src/market_data.rs
+//! Option chain loading and screening for calibration
+//!
+//! # Input Formats
+//!
+//! Chains are read from CSV with a header row, or from a JSON array of
+//! objects (with the `serde` feature), using the same field names:
+//! - `strike`, `expiry` (`YYYY-MM-DD`) and `type` (`call`/`put`, or `C`/`P`)
+//! - `bid` and `ask`, and/or `mid`; a quote needs `mid` or both sides
+//! - optional `timestamp` of the last update (`YYYY-MM-DD HH:MM:SS`, or
+//!   with a `T` separator)
+//!
+//! Columns are located by header name and unknown columns are ignored, as
+//! in [`output`](crate::output). Empty fields are missing values.
+//!
+//! # Screening
+//!
+//! An [`OptionChain`] adds the spot, rate and valuation time, and
+//! [`OptionChain::option_quotes`] / [`OptionChain::vol_quotes`] turn it into
+//! the [`OptionQuote`]s and [`VolQuote`]s the calibrators take. A
+//! [`QuoteFilter`] drops quotes that are expired, unpriced, crossed
+//! (`bid > ask`), wider than a relative spread, older than a maximum age
+//! or (optionally) in the money; every dropped quote is reported with a
+//! [`RejectReason`]. Maturities are ACT/365 from the valuation date.
+//!
+//! # Implied Volatilities
+//!
+//! Mid prices are inverted with [`bs_call_implied_vol`], puts after
+//! put-call parity `C = P + S - K e^(-rT)`. Prices outside the no-arbitrage
+//! bounds have no implied volatility and are rejected as
+//! [`RejectReason::NoImpliedVol`].
+
+use crate::analytics::bs_analytic::bs_call_implied_vol;
+use crate::calibration::quotes::{OptionQuote, OptionType, VolQuote};
+use crate::error::{validation::*, SdeResult};
+use crate::time::DayCount;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+
+/// One row of an option chain
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChainQuote {
+    pub strike: f64,
+    pub expiry: NaiveDate,
+    pub option_type: OptionType,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    pub mid: Option<f64>,
+    /// Time of the last update, if known
+    pub timestamp: Option<NaiveDateTime>,
+}
+
+impl ChainQuote {
+    /// Quoted `mid`, or the midpoint of `bid` and `ask`
+    pub fn price(&self) -> Option<f64> {
+        match (self.mid, self.bid, self.ask) {
+            (Some(mid), _, _) => Some(mid),
+            (None, Some(bid), Some(ask)) => Some(0.5 * (bid + ask)),
+            _ => None,
+        }
+    }
+}
+
+/// Why a quote was dropped while screening
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+    /// Expiry on or before the valuation date
+    Expired,
+    /// Neither a mid nor both sides, or a non-positive price
+    NoPrice,
+    /// Bid above ask
+    Crossed,
+    /// Relative spread above [`QuoteFilter::max_relative_spread`]
+    WideSpread,
+    /// Older than [`QuoteFilter::max_age`]
+    Stale,
+    /// In the money with [`QuoteFilter::otm_only`] set
+    InTheMoney,
+    /// Price outside the no-arbitrage bounds
+    NoImpliedVol,
+}
+
+/// A dropped quote and the reason
+#[derive(Debug, Clone, Copy)]
+pub struct Rejection {
+    pub quote: ChainQuote,
+    pub reason: RejectReason,
+}
+
+/// Outcome of screening a chain
+#[derive(Debug, Clone)]
+pub struct Screened<T> {
+    /// Converted quotes, in chain order
+    pub accepted: Vec<T>,
+    pub rejected: Vec<Rejection>,
+}
+
+/// Screening rules applied before calibration
+#[derive(Debug, Clone, Copy)]
+pub struct QuoteFilter {
+    /// Drop quotes whose `timestamp` is older than this at valuation time
+    /// (quotes without a timestamp are kept)
+    pub max_age: Option<Duration>,
+    /// Drop quotes with `(ask - bid) / mid` above this
+    pub max_relative_spread: f64,
+    /// Keep only out-of-the-money options: puts below the forward, calls
+    /// at or above it
+    pub otm_only: bool,
+}
+
+impl Default for QuoteFilter {
+    fn default() -> Self {
+        QuoteFilter {
+            max_age: None,
+            max_relative_spread: 0.5,
+            otm_only: false,
+        }
+    }
+}
+
+/// An option chain with the market inputs needed to use it
+#[derive(Debug, Clone)]
+pub struct OptionChain {
+    pub spot: f64,
+    /// Continuously compounded risk-free rate
+    pub rate: f64,
+    pub valuation_time: NaiveDateTime,
+    pub quotes: Vec<ChainQuote>,
+}
+
+impl OptionChain {
+    /// Year fraction from the valuation date to `expiry`
+    pub fn time_to_expiry(&self, expiry: NaiveDate) -> f64 {
+        DayCount::Act365Fixed.year_fraction(self.valuation_time.date(), expiry)
+    }
+
+    /// Screened mid prices as calibration quotes
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidParameters` for a non-positive spot,
+    /// non-finite rate or negative spread limit.
+    pub fn option_quotes(&self, filter: &QuoteFilter) -> SdeResult<Screened<OptionQuote>> {
+        let screened = self.screen_all(filter)?;
+        Ok(Screened {
+            accepted: screened.accepted.into_iter().map(|(_, q)| q).collect(),
+            rejected: screened.rejected,
+        })
+    }
+
+    /// Screened mid prices as Black-Scholes implied volatilities
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::option_quotes`].
+    pub fn vol_quotes(&self, filter: &QuoteFilter) -> SdeResult<Screened<VolQuote>> {
+        let prices = self.screen_all(filter)?;
+        let mut rejected = prices.rejected;
+        let mut accepted = Vec::with_capacity(prices.accepted.len());
+        for (chain_quote, quote) in prices.accepted {
+            let (k, t) = (quote.strike, quote.time_to_expiry);
+            let call_price = match quote.option_type {
+                OptionType::Call => quote.market_price,
+                OptionType::Put => quote.market_price + self.spot - k * (-self.rate * t).exp(),
+            };
+            match bs_call_implied_vol(call_price, self.spot, k, self.rate, t) {
+                Ok(implied_vol) => accepted.push(VolQuote {
+                    strike: k,
+                    time_to_expiry: t,
+                    implied_vol,
+                }),
+                Err(_) => rejected.push(Rejection {
+                    quote: chain_quote,
+                    reason: RejectReason::NoImpliedVol,
+                }),
+            }
+        }
+        Ok(Screened { accepted, rejected })
+    }
+
+    /// Screen every quote, pairing the accepted ones with their conversion
+    fn screen_all(&self, filter: &QuoteFilter) -> SdeResult<Screened<(ChainQuote, OptionQuote)>> {
+        validate_positive("spot", self.spot)?;
+        validate_finite("rate", self.rate)?;
+        validate_non_negative("max_relative_spread", filter.max_relative_spread)?;
+        let mut screened = Screened {
+            accepted: Vec::new(),
+            rejected: Vec::new(),
+        };
+        for quote in &self.quotes {
+            match self.screen(quote, filter) {
+                Ok(converted) => screened.accepted.push((*quote, converted)),
+                Err(reason) => screened.rejected.push(Rejection {
+                    quote: *quote,
+                    reason,
+                }),
+            }
+        }
+        Ok(screened)
+    }
+
+    fn screen(
+        &self,
+        quote: &ChainQuote,
+        filter: &QuoteFilter,
+    ) -> Result<OptionQuote, RejectReason> {
+        let t = self.time_to_expiry(quote.expiry);
+        if t <= 0.0 {
+            return Err(RejectReason::Expired);
+        }
+        if let (Some(bid), Some(ask)) = (quote.bid, quote.ask) {
+            if bid > ask {
+                return Err(RejectReason::Crossed);
+            }
+        }
+        let price = quote
+            .price()
+            .filter(|p| p.is_finite() && *p > 0.0)
+            .ok_or(RejectReason::NoPrice)?;
+        if let (Some(bid), Some(ask)) = (quote.bid, quote.ask) {
+            if (ask - bid) / price > filter.max_relative_spread {
+                return Err(RejectReason::WideSpread);
+            }
+        }
+        if let (Some(max_age), Some(timestamp)) = (filter.max_age, quote.timestamp) {
+            if self.valuation_time - timestamp > max_age {
+                return Err(RejectReason::Stale);
+            }
+        }
+        if filter.otm_only {
+            let forward = self.spot * (self.rate * t).exp();
+            let in_the_money = match quote.option_type {
+                OptionType::Call => quote.strike < forward,
+                OptionType::Put => quote.strike >= forward,
+            };
+            if in_the_money {
+                return Err(RejectReason::InTheMoney);
+            }
+        }
+        Ok(OptionQuote {
+            strike: quote.strike,
+            time_to_expiry: t,
+            market_price: price,
+            option_type: quote.option_type,
+        })
+    }
+}
+
+/// Read an option chain from a CSV file
+pub fn read_chain_csv(filename: &str) -> io::Result<Vec<ChainQuote>> {
+    let lines = BufReader::new(File::open(filename)?).lines();
+    parse_chain_lines(lines)
+}
+
+/// Parse an option chain from CSV text
+pub fn parse_chain_csv(text: &str) -> io::Result<Vec<ChainQuote>> {
+    parse_chain_lines(text.lines().map(|line| Ok(line.to_string())))
+}
+
+fn parse_chain_lines<I>(mut lines: I) -> io::Result<Vec<ChainQuote>>
+where
+    I: Iterator<Item = io::Result<String>>,
+{
+    let header = lines
+        .next()
+        .ok_or_else(|| invalid_data("option chain is empty".to_string()))??;
+    let columns: Vec<String> = header
+        .split(',')
+        .map(|c| c.trim().to_ascii_lowercase())
+        .collect();
+    let index_of = |names: &[&str]| columns.iter().position(|c| names.contains(&c.as_str()));
+    let required = |names: &[&str]| {
+        index_of(names)
+            .ok_or_else(|| invalid_data(format!("option chain has no {} column", names[0])))
+    };
+    let (i_strike, i_expiry, i_type) = (
+        required(&["strike"])?,
+        required(&["expiry"])?,
+        required(&["type", "option_type"])?,
+    );
+    let (i_bid, i_ask, i_mid, i_time) = (
+        index_of(&["bid"]),
+        index_of(&["ask"]),
+        index_of(&["mid"]),
+        index_of(&["timestamp"]),
+    );
+
+    let mut quotes = Vec::new();
+    for (line_no, line) in lines.enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let at_line = |e: String| invalid_data(format!("line {}: {}", line_no + 2, e));
+        let field = |i: Option<usize>| i.and_then(|i| fields.get(i).copied());
+        let optional_float = |i: Option<usize>| -> io::Result<Option<f64>> {
+            match field(i) {
+                None | Some("") => Ok(None),
+                Some(s) => s.parse().map(Some).map_err(|e| at_line(format!("{}", e))),
+            }
+        };
+        let row = RawQuote {
+            strike: optional_float(Some(i_strike))?
+                .ok_or_else(|| at_line("missing strike".to_string()))?,
+            expiry: field(Some(i_expiry)).unwrap_or("").to_string(),
+            option_type: field(Some(i_type)).unwrap_or("").to_string(),
+            bid: optional_float(i_bid)?,
+            ask: optional_float(i_ask)?,
+            mid: optional_float(i_mid)?,
+            timestamp: field(i_time).filter(|s| !s.is_empty()).map(str::to_string),
+        };
+        quotes.push(row.into_quote().map_err(at_line)?);
+    }
+    Ok(quotes)
+}
+
+/// Parse an option chain from a JSON array of quote objects
+#[cfg(feature = "serde")]
+pub fn parse_chain_json(text: &str) -> io::Result<Vec<ChainQuote>> {
+    let rows: Vec<RawQuote> =
+        serde_json::from_str(text).map_err(|e| invalid_data(format!("{}", e)))?;
+    rows.into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            row.into_quote()
+                .map_err(|e| invalid_data(format!("quote {}: {}", i, e)))
+        })
+        .collect()
+}
+
+/// Read an option chain from a JSON file
+#[cfg(feature = "serde")]
+pub fn read_chain_json(filename: &str) -> io::Result<Vec<ChainQuote>> {
+    parse_chain_json(&std::fs::read_to_string(filename)?)
+}
+
+/// A chain row before its text fields are parsed
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+struct RawQuote {
+    strike: f64,
+    expiry: String,
+    #[cfg_attr(feature = "serde", serde(rename = "type", alias = "option_type"))]
+    option_type: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    bid: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    ask: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    mid: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    timestamp: Option<String>,
+}
+
+impl RawQuote {
+    fn into_quote(self) -> Result<ChainQuote, String> {
+        let option_type = match self.option_type.to_ascii_lowercase().as_str() {
+            "c" | "call" => OptionType::Call,
+            "p" | "put" => OptionType::Put,
+            other => return Err(format!("unknown option type '{}'", other)),
+        };
+        let expiry = NaiveDate::parse_from_str(&self.expiry, "%Y-%m-%d")
+            .map_err(|e| format!("expiry '{}': {}", self.expiry, e))?;
+        let timestamp = match &self.timestamp {
+            None => None,
+            Some(s) => Some(
+                NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+                    .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S"))
+                    .map_err(|e| format!("timestamp '{}': {}", s, e))?,
+            ),
+        };
+        Ok(ChainQuote {
+            strike: self.strike,
+            expiry,
+            option_type,
+            bid: self.bid,
+            ask: self.ask,
+            mid: self.mid,
+            timestamp,
+        })
+    }
+}
+
+fn invalid_data(reason: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::bs_analytic::{bs_call_price, bs_put_price};
+
+    const CHAIN: &str = "\
+strike,expiry,type,bid,ask,mid,timestamp,venue
+90,2025-01-01,C,,,CALL90,2024-01-01 15:59:00,X
+100,2025-01-01,call,BID100,ASK100,,2024-01-01 15:59:30,X
+100,2025-01-01,put,,,PUT100,,X
+110,2025-01-01,C,5.0,4.0,,,X
+120,2025-01-01,C,0.5,2.0,,,X
+100,2024-01-01,C,1.0,1.1,,,X
+95,2025-01-01,P,,,PUT95,2023-12-01 10:00:00,X
+80,2025-01-01,C,,,5.0,,X
+";
+
+    fn chain() -> OptionChain {
+        let (s, r, t) = (100.0, 0.02, 366.0 / 365.0);
+        let text = CHAIN
+            .replace("CALL90", &bs_call_price(s, 90.0, r, 0.25, t).to_string())
+            .replace(
+                "BID100",
+                &(bs_call_price(s, 100.0, r, 0.2, t) - 0.05).to_string(),
+            )
+            .replace(
+                "ASK100",
+                &(bs_call_price(s, 100.0, r, 0.2, t) + 0.05).to_string(),
+            )
+            .replace("PUT100", &bs_put_price(s, 100.0, r, 0.2, t).to_string())
+            .replace("PUT95", &bs_put_price(s, 95.0, r, 0.22, t).to_string());
+        OptionChain {
+            spot: s,
+            rate: r,
+            valuation_time: NaiveDate::from_ymd_opt(2024, 1, 1)
+                .and_then(|d| d.and_hms_opt(16, 0, 0))
+                .expect("Valid date"),
+            quotes: parse_chain_csv(&text).expect("Valid chain"),
+        }
+    }
+
+    #[test]
+    fn test_screens_and_converts_chain() {
+        let chain = chain();
+        assert_eq!(chain.quotes.len(), 8);
+        let filter = QuoteFilter {
+            max_age: Some(Duration::days(1)),
+            ..QuoteFilter::default()
+        };
+        let vols = chain.vol_quotes(&filter).expect("Valid chain");
+        let reasons: Vec<(f64, RejectReason)> = vols
+            .rejected
+            .iter()
+            .map(|r| (r.quote.strike, r.reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (110.0, RejectReason::Crossed),
+                (120.0, RejectReason::WideSpread),
+                (100.0, RejectReason::Expired),
+                (95.0, RejectReason::Stale),
+                (80.0, RejectReason::NoImpliedVol),
+            ]
+        );
+        let expected = [(90.0, 0.25), (100.0, 0.2), (100.0, 0.2)];
+        assert_eq!(vols.accepted.len(), expected.len());
+        for (quote, &(k, sigma)) in vols.accepted.iter().zip(&expected) {
+            assert_eq!(quote.strike, k);
+            assert!((quote.time_to_expiry - 366.0 / 365.0).abs() < 1e-15);
+            assert!((quote.implied_vol - sigma).abs() < 1e-8, "{:?}", quote);
+        }
+
+        let otm = QuoteFilter {
+            otm_only: true,
+            ..QuoteFilter::default()
+        };
+        let prices = chain.option_quotes(&otm).expect("Valid chain");
+        assert!(prices
+            .rejected
+            .iter()
+            .any(|r| r.quote.strike == 90.0 && r.reason == RejectReason::InTheMoney));
+        let kept: Vec<(f64, OptionType)> = prices
+            .accepted
+            .iter()
+            .map(|q| (q.strike, q.option_type))
+            .collect();
+        assert_eq!(
+            kept,
+            vec![(100.0, OptionType::Put), (95.0, OptionType::Put)]
+        );
+
+        assert!(parse_chain_csv("strike,expiry\n100,2025-01-01").is_err());
+        assert!(parse_chain_csv("strike,expiry,type,mid\n100,2025-01-01,X,1.0").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_parses_json_chain() {
+        let json = r#"[
+            {"strike": 100.0, "expiry": "2025-01-01", "type": "call", "bid": 1.0, "ask": 1.2},
+            {"strike": 95.0, "expiry": "2025-01-01", "option_type": "P", "mid": 0.7,
+             "timestamp": "2024-01-01T15:00:00"}
+        ]"#;
+        let quotes = parse_chain_json(json).expect("Valid chain");
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes[0].price(), Some(1.1));
+        assert_eq!(quotes[1].option_type, OptionType::Put);
+        assert!(quotes[1].timestamp.is_some());
+        assert!(parse_chain_json(r#"[{"strike": 1.0}]"#).is_err());
+    }
+}
@@ -1,24 +1,102 @@
 // src/math_utils.rs
 use statrs::function::erf;
 use std::f64::consts::SQRT_2;
+// Monotonic clock: `std::time::Instant` panics on `wasm32-unknown-unknown`,
+// where `web_time` reads the browser's `performance.now()` instead
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub use web_time::Instant;
+
+pub mod correlation;
 
 pub fn norm_cdf(x: f64) -> f64 {
     0.5 * (1.0 + erf::erf(x / SQRT_2))
 }
 
+/// Standard normal quantile `Φ⁻¹(p)` for `p` in (0, 1)
+///
+/// Acklam's rational approximation (relative error 1.2e-9) refined by one
+/// Halley step on `Φ(x) - p`, which brings it to near machine precision.
+/// Returns `∓∞` at `p = 0` and `p = 1`.
+pub fn norm_inv_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    let x = if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    };
+
+    // Halley refinement with Φ(x) = erfc(-x/√2)/2, accurate in both tails
+    let e = 0.5 * erf::erfc(-x / SQRT_2) - p;
+    let u = e * (2.0 * std::f64::consts::PI).sqrt() * (0.5 * x * x).exp();
+    x - u / (1.0 + 0.5 * x * u)
+}
+
 pub struct Timer {
-    start_time: std::time::Instant,
+    start_time: Instant,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Timer {
     pub fn new() -> Timer {
         Timer {
-            start_time: std::time::Instant::now(),
+            start_time: Instant::now(),
         }
     }
 
     pub fn start(&mut self) {
-        self.start_time = std::time::Instant::now();
+        self.start_time = Instant::now();
     }
 
     pub fn elapsed_ms(&self) -> f64 {
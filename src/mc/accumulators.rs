@@ -0,0 +1,366 @@
+// src/mc/accumulators.rs
+//! Numerically Stable Path Statistics
+//!
+//! # Motivation
+//!
+//! Accumulating `Σ Y` and `Σ Y²` and forming `E[Y²] - E[Y]²` loses most
+//! significant digits when the variance is small relative to the squared
+//! mean, and the plain running sums drift once `n` reaches millions of paths.
+//!
+//! # Welford / Chan Updates
+//!
+//! Each accumulator keeps the count, the mean and the centered sums
+//! `M₂ = Σ (Y - Ȳ)²` (and `C = Σ (X - X̄)(Y - Ȳ)` for pairs, or the whole
+//! matrix of them for vectors in [`MultiMoments`]). Two disjoint
+//! samples `a` and `b` merge exactly (Chan et al.), which is what the Rayon
+//! `reduce` uses:
+//! ```text
+//! δ = Ȳ_b - Ȳ_a,   n = n_a + n_b
+//! Ȳ  = Ȳ_a + δ n_b / n
+//! M₂ = M₂_a + M₂_b + δ² n_a n_b / n
+//! C  = C_a + C_b + δ_x δ_y n_a n_b / n
+//! ```
+//!
+//! # Compensated Means
+//!
+//! The mean increments `δ n_b / n` shrink as `n` grows and are added with
+//! Kahan-Babuška (Neumaier) compensation, so rounding of the running mean
+//! does not accumulate over the merge tree.
+//!
+//! # Variance of the Mean
+//!
+//! Every pricer reports the variance of its estimate `Ȳ`, the unbiased
+//! sample variance over the number of samples:
+//! ```text
+//! Var(Ȳ) ≈ s² / n = M₂ / (n (n - 1))
+//! ```
+//! so `sqrt` of it is the standard error.
+
+/// Neumaier-compensated running sum
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KahanSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl KahanSum {
+    pub fn new(value: f64) -> Self {
+        KahanSum {
+            sum: value,
+            compensation: 0.0,
+        }
+    }
+
+    pub fn add(&mut self, x: f64) {
+        let t = self.sum + x;
+        if self.sum.abs() >= x.abs() {
+            self.compensation += (self.sum - t) + x;
+        } else {
+            self.compensation += (x - t) + self.sum;
+        }
+        self.sum = t;
+    }
+
+    /// Compensated total
+    pub fn value(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+/// Count, mean and centered second moment of one variable
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Moments {
+    count: u64,
+    mean: KahanSum,
+    m2: f64,
+}
+
+impl Moments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one observation
+    pub fn push(&mut self, y: f64) {
+        self.merge(Moments {
+            count: 1,
+            mean: KahanSum::new(y),
+            m2: 0.0,
+        });
+    }
+
+    /// Combine with the moments of a disjoint sample
+    pub fn merge(&mut self, other: Moments) {
+        if other.count == 0 {
+            return;
+        }
+        let (na, nb) = (self.count as f64, other.count as f64);
+        let n = na + nb;
+        let delta = other.mean() - self.mean();
+        self.m2 += other.m2 + delta * delta * na * nb / n;
+        self.mean.add(delta * nb / n);
+        self.count += other.count;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean.value()
+    }
+
+    /// `Σ (Y - Ȳ)² / n`
+    pub fn population_variance(&self) -> f64 {
+        if self.count > 0 {
+            self.m2 / self.count as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// `Σ (Y - Ȳ)² / (n - 1)`
+    pub fn sample_variance(&self) -> f64 {
+        if self.count > 1 {
+            self.m2 / (self.count - 1) as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Variance of the mean, `s² / n`
+    pub fn variance_of_mean(&self) -> f64 {
+        variance_of_mean(self.population_variance(), self.count)
+    }
+}
+
+/// Variance of the mean of `count` samples with population variance
+/// `population_variance`, `s² / n = σ̂² / (n - 1)`; zero below two samples
+pub fn variance_of_mean(population_variance: f64, count: u64) -> f64 {
+    if count > 1 {
+        population_variance.max(0.0) / (count - 1) as f64
+    } else {
+        0.0
+    }
+}
+
+/// Joint moments of a pair `(X, Y)`, for control variate regressions
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CoMoments {
+    pub x: Moments,
+    pub y: Moments,
+    c: f64,
+}
+
+impl CoMoments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one observation of the pair
+    pub fn push(&mut self, x: f64, y: f64) {
+        let mut single = CoMoments::new();
+        single.x.push(x);
+        single.y.push(y);
+        self.merge(single);
+    }
+
+    /// Combine with the moments of a disjoint sample
+    pub fn merge(&mut self, other: CoMoments) {
+        if other.x.count == 0 {
+            return;
+        }
+        let (na, nb) = (self.x.count as f64, other.x.count as f64);
+        let n = na + nb;
+        let dx = other.x.mean() - self.x.mean();
+        let dy = other.y.mean() - self.y.mean();
+        self.c += other.c + dx * dy * na * nb / n;
+        self.x.merge(other.x);
+        self.y.merge(other.y);
+    }
+
+    /// `Σ (X - X̄)(Y - Ȳ) / n`
+    pub fn population_covariance(&self) -> f64 {
+        if self.x.count > 0 {
+            self.c / self.x.count as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Joint moments of a vector of up to `D` variables, for regressions on
+/// several control variates
+#[derive(Debug, Clone, Copy)]
+pub struct MultiMoments<const D: usize> {
+    dim: usize,
+    count: u64,
+    mean: [KahanSum; D],
+    c: [[f64; D]; D],
+}
+
+impl<const D: usize> MultiMoments<D> {
+    /// Empty moments of the first `dim` variables (at most `D`)
+    pub fn new(dim: usize) -> Self {
+        assert!(dim <= D, "dimension {} exceeds capacity {}", dim, D);
+        MultiMoments {
+            dim,
+            count: 0,
+            mean: [KahanSum::default(); D],
+            c: [[0.0; D]; D],
+        }
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Add one observation; `values` holds at least `dim` entries
+    pub fn push(&mut self, values: &[f64]) {
+        // Merge with a single observation, without building one
+        self.count += 1;
+        let n = self.count as f64;
+        let mut delta = [0.0; D];
+        for (i, d) in delta.iter_mut().enumerate().take(self.dim) {
+            *d = values[i] - self.mean(i);
+        }
+        for i in 0..self.dim {
+            for j in 0..self.dim {
+                self.c[i][j] += delta[i] * delta[j] * (n - 1.0) / n;
+            }
+            self.mean[i].add(delta[i] / n);
+        }
+    }
+
+    /// Combine with the moments of a disjoint sample of the same dimension
+    pub fn merge(&mut self, other: Self) {
+        if other.count == 0 {
+            return;
+        }
+        let (na, nb) = (self.count as f64, other.count as f64);
+        let n = na + nb;
+        let mut delta = [0.0; D];
+        for (i, d) in delta.iter_mut().enumerate().take(self.dim) {
+            *d = other.mean(i) - self.mean(i);
+        }
+        for i in 0..self.dim {
+            for j in 0..self.dim {
+                self.c[i][j] += other.c[i][j] + delta[i] * delta[j] * na * nb / n;
+            }
+            self.mean[i].add(delta[i] * nb / n);
+        }
+        self.count += other.count;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Mean of variable `i`
+    pub fn mean(&self, i: usize) -> f64 {
+        self.mean[i].value()
+    }
+
+    /// `Σ (X_i - X̄_i)(X_j - X̄_j) / n`
+    pub fn population_covariance(&self, i: usize, j: usize) -> f64 {
+        if self.count > 0 {
+            self.c[i][j] / self.count as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Exact state as words: the count, then the bit patterns of each
+    /// compensated mean (sum, compensation) and of the `dim x dim` centered
+    /// sums, row by row
+    pub(crate) fn words(&self) -> Vec<u64> {
+        let mut words = vec![self.count];
+        for mean in &self.mean[..self.dim] {
+            words.push(mean.sum.to_bits());
+            words.push(mean.compensation.to_bits());
+        }
+        for row in &self.c[..self.dim] {
+            words.extend(row[..self.dim].iter().map(|c| c.to_bits()));
+        }
+        words
+    }
+
+    /// Inverse of [`words`](Self::words); `None` if `words` does not
+    /// hold a state of dimension `dim`
+    pub(crate) fn from_words(dim: usize, words: &[u64]) -> Option<Self> {
+        if dim > D || words.len() != 1 + 2 * dim + dim * dim {
+            return None;
+        }
+        let mut moments = Self::new(dim);
+        moments.count = words[0];
+        for (i, pair) in words[1..1 + 2 * dim].chunks(2).enumerate() {
+            moments.mean[i] = KahanSum {
+                sum: f64::from_bits(pair[0]),
+                compensation: f64::from_bits(pair[1]),
+            };
+        }
+        for (i, row) in words[1 + 2 * dim..].chunks(dim.max(1)).enumerate() {
+            for (c, &word) in moments.c[i].iter_mut().zip(row) {
+                *c = f64::from_bits(word);
+            }
+        }
+        Some(moments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moments_survive_large_offsets() {
+        // Naive E[Y²] - E[Y]² returns noise for a variance of 1 around 1e9
+        let values: Vec<f64> = (0..99_999).map(|i| 1e9 + (i % 3) as f64 - 1.0).collect();
+        let mut whole = Moments::new();
+        values.iter().for_each(|&v| whole.push(v));
+        assert!((whole.mean() - 1e9).abs() < 1e-6);
+        assert!((whole.population_variance() - 2.0 / 3.0).abs() < 1e-9);
+
+        // Merging chunks gives the same moments as a single pass
+        let merged = values
+            .chunks(777)
+            .map(|chunk| {
+                let mut m = CoMoments::new();
+                chunk.iter().for_each(|&v| m.push(v, -2.0 * v));
+                m
+            })
+            .fold(CoMoments::new(), |mut acc, m| {
+                acc.merge(m);
+                acc
+            });
+        assert_eq!(merged.x.count(), 99_999);
+        assert!((merged.x.mean() - whole.mean()).abs() < 1e-6);
+        assert!((merged.x.population_variance() - 2.0 / 3.0).abs() < 1e-9);
+        assert!((merged.population_covariance() + 4.0 / 3.0).abs() < 1e-8);
+
+        // The vector accumulator agrees with the pair accumulator
+        let multi = values
+            .chunks(777)
+            .map(|chunk| {
+                let mut m = MultiMoments::<3>::new(2);
+                chunk.iter().for_each(|&v| m.push(&[v, -2.0 * v]));
+                m
+            })
+            .fold(MultiMoments::<3>::new(2), |mut acc, m| {
+                acc.merge(m);
+                acc
+            });
+        assert_eq!(multi.count(), 99_999);
+        assert!((multi.mean(0) - whole.mean()).abs() < 1e-6);
+        assert!((multi.population_covariance(1, 1) - 8.0 / 3.0).abs() < 1e-8);
+        assert!((multi.population_covariance(0, 1) + 4.0 / 3.0).abs() < 1e-8);
+
+        let mut sum = KahanSum::default();
+        for _ in 0..10 {
+            sum.add(1e16);
+            sum.add(1.0);
+            sum.add(-1e16);
+        }
+        assert_eq!(sum.value(), 10.0);
+    }
+}
@@ -0,0 +1,502 @@
+// src/mc/bermudan_swaption.rs
+//! Monte Carlo Swaptions under Hull-White with Longstaff-Schwartz Exercise
+//!
+//! # Simulation
+//!
+//! The Hull-White factor is stepped exactly between the exercise dates
+//! together with the bank-account deflator `D(t) = exp(-∫₀ᵗ r du)`, and the
+//! underlying swap at an exercise date `T_j` is valued from the affine bond
+//! prices `P(T_j, t_i; x)`, so the only error is statistical (and, for
+//! Bermudans, the regression).
+//!
+//! # Longstaff-Schwartz
+//!
+//! A Bermudan swaption may be exercised at the expiry and at every later
+//! fixed payment date before maturity, entering the remaining swap.
+//! Working backwards from the last exercise date, the continuation value
+//! `C_j(x)` is estimated by regressing the realised deflated values of the
+//! in-the-money paths on polynomials of the factor (see
+//! [`RegressionProxy`]); a path exercises when its exercise value beats the
+//! estimate:
+//! ```text
+//! V = D(T_j) E_j   if E_j > 0 and E_j ≥ C_j(x_{T_j}),   else V unchanged
+//! price = mean(V)
+//! ```
+//! The exercise policy is estimated on the pricing paths, which biases the
+//! price slightly upwards; a suboptimal policy biases it downwards.
+//!
+//! # Exercise Policy
+//!
+//! [`mc_swaption_lsm`] returns the fitted continuation values with the
+//! price. Since the exercise value is monotone in the factor, the policy
+//! at each date reduces to a boundary `x*_j`: a payer exercises above it
+//! and a receiver below it. Applying the policy to an independent path set
+//! gives a low-biased price, which brackets the true value together with
+//! the in-sample one.
+
+use crate::analytics::hull_white_analytic::{Swaption, SwaptionType};
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::mc::regression_proxy::RegressionProxy;
+use crate::models::hull_white::HullWhite;
+use crate::parallel::prelude::*;
+use crate::rng::SeedStrategy;
+
+/// When the swaption may be exercised
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SwaptionExercise {
+    /// Only at the expiry
+    European,
+    /// At the expiry and every later payment date before maturity
+    Bermudan,
+}
+
+/// Simulation settings of the Longstaff-Schwartz pricer
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LsmConfig {
+    pub paths: usize,
+    pub seed: u64,
+    /// Derivation of the per-path streams from `seed`
+    pub seed_strategy: SeedStrategy,
+    /// Total degree of the regression polynomials in the factor
+    pub basis_degree: usize,
+}
+
+impl Default for LsmConfig {
+    fn default() -> Self {
+        LsmConfig {
+            paths: 20_000,
+            seed: 12345,
+            seed_strategy: SeedStrategy::Hashed,
+            basis_degree: 3,
+        }
+    }
+}
+
+impl LsmConfig {
+    /// Validate path count and basis degree
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_paths(self.paths)?;
+        if self.basis_degree == 0 {
+            return Err(SdeError::InvalidConfiguration {
+                field: "basis_degree".to_string(),
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Exercise dates of `swaption` for the given style
+pub fn exercise_dates(swaption: &Swaption, exercise: SwaptionExercise) -> Vec<f64> {
+    let mut dates = vec![swaption.expiry];
+    if exercise == SwaptionExercise::Bermudan {
+        let payments = swaption.payment_dates();
+        dates.extend(&payments[..payments.len() - 1]);
+    }
+    dates
+}
+
+/// Estimated exercise rule at one exercise date
+#[derive(Debug, Clone)]
+pub struct ExerciseDate {
+    pub time: f64,
+    /// Regression of the deflated continuation value on the factor, `None`
+    /// at the last date or where too few paths were in the money to fit
+    /// one (nobody exercises there before the last date)
+    pub continuation: Option<RegressionProxy>,
+    /// Factor level where the decision switches between holding and
+    /// exercising, searched on the simulated factor range; `None` if the
+    /// decision does not change there
+    pub boundary: Option<f64>,
+}
+
+/// Exercise policy estimated by Longstaff-Schwartz, one rule per date
+#[derive(Debug, Clone)]
+pub struct ExercisePolicy {
+    pub dates: Vec<ExerciseDate>,
+}
+
+impl ExercisePolicy {
+    /// Whether to exercise at date `j` with factor `x` and exercise value
+    /// `exercise`
+    pub fn exercises(&self, j: usize, x: f64, exercise: f64) -> bool {
+        if exercise <= 0.0 {
+            return false;
+        }
+        match &self.dates[j].continuation {
+            Some(proxy) => exercise >= proxy.value(&[x]),
+            None => j + 1 == self.dates.len(),
+        }
+    }
+}
+
+/// Longstaff-Schwartz price with the exercise policy behind it
+#[derive(Debug, Clone)]
+pub struct LsmResult {
+    pub price: f64,
+    /// Variance of the price estimate
+    pub variance: f64,
+    pub policy: ExercisePolicy,
+}
+
+/// Monte Carlo price of a European or Bermudan swaption under Hull-White
+///
+/// Path `i` draws from its `seed_strategy` stream. Returns `(price,
+/// variance of the estimate)`; see [`mc_swaption_lsm`] for the exercise policy.
+///
+/// # Errors
+///
+/// Returns `SdeError` for an invalid contract or configuration, a failed
+/// regression, or a non-finite price.
+pub fn mc_price_swaption(
+    model: &HullWhite,
+    swaption: &Swaption,
+    exercise: SwaptionExercise,
+    cfg: &LsmConfig,
+) -> SdeResult<(f64, f64)> {
+    let result = mc_swaption_lsm(model, swaption, exercise, cfg)?;
+    Ok((result.price, result.variance))
+}
+
+/// Longstaff-Schwartz swaption price together with the estimated
+/// continuation values and exercise boundary at every exercise date
+///
+/// Pricing a second, independent path set with the returned policy (see
+/// [`mc_price_swaption_with_policy`]) removes the upward bias of
+/// estimating the policy on the pricing paths.
+///
+/// # Errors
+///
+/// As [`mc_price_swaption`].
+pub fn mc_swaption_lsm(
+    model: &HullWhite,
+    swaption: &Swaption,
+    exercise: SwaptionExercise,
+    cfg: &LsmConfig,
+) -> SdeResult<LsmResult> {
+    swaption.validate()?;
+    cfg.validate()?;
+    let dates = exercise_dates(swaption, exercise);
+    let states = simulate(model, swaption, &dates, cfg);
+
+    let last = dates.len() - 1;
+    let mut values: Vec<f64> = states
+        .iter()
+        .map(|path| {
+            let (_, deflator, exercise) = path[last];
+            deflator * exercise.max(0.0)
+        })
+        .collect();
+    let mut proxies = vec![None; dates.len()];
+    for j in (0..last).rev() {
+        let (mut factors, mut targets) = (Vec::new(), Vec::new());
+        for (path, &value) in states.iter().zip(&values) {
+            let (x, deflator, exercise) = path[j];
+            if exercise > 0.0 {
+                factors.push(x);
+                targets.push(value / deflator);
+            }
+        }
+        // Too few paths in the money to regress: nobody exercises here
+        if targets.len() <= cfg.basis_degree + 1 {
+            continue;
+        }
+        let proxy = RegressionProxy::fit(&factors, &targets, 1, cfg.basis_degree)?;
+        for (path, value) in states.iter().zip(values.iter_mut()) {
+            let (x, deflator, exercise) = path[j];
+            if exercise > 0.0 && exercise >= proxy.value(&[x]) {
+                *value = deflator * exercise;
+            }
+        }
+        proxies[j] = Some(proxy);
+    }
+
+    let mut policy = ExercisePolicy {
+        dates: dates
+            .iter()
+            .zip(proxies)
+            .map(|(&time, continuation)| ExerciseDate {
+                time,
+                continuation,
+                boundary: None,
+            })
+            .collect(),
+    };
+    for j in 0..dates.len() {
+        let (lo, hi) = states
+            .iter()
+            .map(|path| path[j].0)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), x| {
+                (lo.min(x), hi.max(x))
+            });
+        let exercises = |x: f64| {
+            let date = dates[j];
+            let value = swaption.payer_swap_value(date, |d| model.bond_price(date, d, x));
+            policy.exercises(j, x, sign(swaption) * value)
+        };
+        policy.dates[j].boundary = boundary(exercises, lo, hi);
+    }
+
+    let (price, variance) = mean_and_variance(&values)?;
+    Ok(LsmResult {
+        price,
+        variance,
+        policy,
+    })
+}
+
+/// Monte Carlo price of a swaption exercised by a given `policy`
+///
+/// Path `i` draws from its `seed_strategy` stream; with a seed independent
+/// of the one that estimated the policy this is a low-biased estimate, since any fixed
+/// policy is at best optimal. `cfg.basis_degree` is not used.
+///
+/// # Errors
+///
+/// Returns `SdeError::InvalidConfiguration` if the policy's dates are not
+/// the swaption's exercise dates for any style, and otherwise as
+/// [`mc_price_swaption`].
+pub fn mc_price_swaption_with_policy(
+    model: &HullWhite,
+    swaption: &Swaption,
+    policy: &ExercisePolicy,
+    cfg: &LsmConfig,
+) -> SdeResult<(f64, f64)> {
+    swaption.validate()?;
+    cfg.validate()?;
+    let dates: Vec<f64> = policy.dates.iter().map(|d| d.time).collect();
+    let matches = [SwaptionExercise::European, SwaptionExercise::Bermudan]
+        .into_iter()
+        .any(|style| exercise_dates(swaption, style) == dates);
+    if !matches {
+        return Err(SdeError::InvalidConfiguration {
+            field: "policy".to_string(),
+            reason: format!("dates {:?} are not exercise dates of the swaption", dates),
+        });
+    }
+
+    let values: Vec<f64> = simulate(model, swaption, &dates, cfg)
+        .iter()
+        .map(|path| {
+            path.iter()
+                .enumerate()
+                .find(|&(j, &(x, _, exercise))| policy.exercises(j, x, exercise))
+                .map_or(0.0, |(_, &(_, deflator, exercise))| deflator * exercise)
+        })
+        .collect();
+    mean_and_variance(&values)
+}
+
+fn sign(swaption: &Swaption) -> f64 {
+    match swaption.kind {
+        SwaptionType::Payer => 1.0,
+        SwaptionType::Receiver => -1.0,
+    }
+}
+
+/// Factor, deflator and exercise value at each exercise date of each path
+fn simulate(
+    model: &HullWhite,
+    swaption: &Swaption,
+    dates: &[f64],
+    cfg: &LsmConfig,
+) -> Vec<Vec<(f64, f64, f64)>> {
+    let sign = sign(swaption);
+    (0..cfg.paths)
+        .into_par_iter()
+        .map(|i| {
+            let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+            let (mut x, mut deflator, mut t) = (0.0, 1.0, 0.0);
+            dates
+                .iter()
+                .map(|&date| {
+                    deflator *= model.step(&mut x, t, date - t, &mut rng);
+                    t = date;
+                    let swap = swaption.payer_swap_value(date, |d| model.bond_price(date, d, x));
+                    (x, deflator, sign * swap)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// First switch of `exercises` on `[lo, hi]`, located on a grid and
+/// refined by bisection
+fn boundary(exercises: impl Fn(f64) -> bool, lo: f64, hi: f64) -> Option<f64> {
+    const GRID: usize = 256;
+    if hi <= lo {
+        return None;
+    }
+    let at = |k: usize| lo + (hi - lo) * k as f64 / GRID as f64;
+    let first = exercises(lo);
+    let k = (1..=GRID).find(|&k| exercises(at(k)) != first)?;
+    let (mut a, mut b) = (at(k - 1), at(k));
+    for _ in 0..50 {
+        let mid = 0.5 * (a + b);
+        if exercises(mid) == first {
+            a = mid;
+        } else {
+            b = mid;
+        }
+    }
+    Some(0.5 * (a + b))
+}
+
+fn mean_and_variance(values: &[f64]) -> SdeResult<(f64, f64)> {
+    let n = values.len() as f64;
+    let price = values.iter().sum::<f64>() / n;
+    let variance = if values.len() > 1 {
+        values.iter().map(|v| (v - price).powi(2)).sum::<f64>() / (n - 1.0) / n
+    } else {
+        0.0
+    };
+    if !price.is_finite() {
+        return Err(SdeError::NumericalInstability {
+            method: "Swaption pricing".to_string(),
+            reason: format!("non-finite price {}", price),
+        });
+    }
+    Ok((price, variance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::hull_white_analytic::{hw_forward_swap_rate, hw_swaption_price};
+    use crate::models::hull_white::{HullWhiteParams, YieldCurve};
+
+    #[test]
+    fn test_european_matches_jamshidian_and_bermudan_dominates() {
+        let curve = YieldCurve::from_zero_rates(&[1.0, 5.0, 10.0], &[0.02, 0.03, 0.035]).unwrap();
+        let model = HullWhite::new(
+            HullWhiteParams {
+                a: 0.05,
+                sigma: 0.01,
+            },
+            curve,
+        )
+        .unwrap();
+        let mut swaption = Swaption {
+            kind: SwaptionType::Payer,
+            expiry: 1.0,
+            maturity: 6.0,
+            payments_per_year: 1,
+            strike: 0.0,
+            notional: 100.0,
+        };
+        swaption.strike = hw_forward_swap_rate(&model, &swaption).0;
+        let cfg = LsmConfig {
+            paths: 10_000,
+            ..Default::default()
+        };
+        assert_eq!(
+            exercise_dates(&swaption, SwaptionExercise::Bermudan),
+            vec![1.0, 2.0, 3.0, 4.0, 5.0]
+        );
+
+        for kind in [SwaptionType::Payer, SwaptionType::Receiver] {
+            let swaption = Swaption { kind, ..swaption };
+            let exact = hw_swaption_price(&model, &swaption).unwrap();
+            let (european, var) =
+                mc_price_swaption(&model, &swaption, SwaptionExercise::European, &cfg).unwrap();
+            assert!(
+                (european - exact).abs() < 4.0 * var.sqrt(),
+                "{:?}: MC {} vs Jamshidian {}",
+                kind,
+                european,
+                exact
+            );
+
+            // The Bermudan is worth at least every co-terminal European
+            let (bermudan, _) =
+                mc_price_swaption(&model, &swaption, SwaptionExercise::Bermudan, &cfg).unwrap();
+            let coterminal = (1..5)
+                .map(|k| {
+                    let later = Swaption {
+                        expiry: k as f64,
+                        ..swaption
+                    };
+                    hw_swaption_price(&model, &later).unwrap()
+                })
+                .fold(0.0, f64::max);
+            assert!(
+                bermudan > coterminal.max(exact),
+                "{:?}: Bermudan {} vs Europeans {} / {}",
+                kind,
+                bermudan,
+                coterminal,
+                exact
+            );
+        }
+
+        let bad = LsmConfig {
+            basis_degree: 0,
+            ..cfg
+        };
+        assert!(mc_price_swaption(&model, &swaption, SwaptionExercise::Bermudan, &bad).is_err());
+    }
+
+    #[test]
+    fn test_exercise_policy_reuse_and_boundary() {
+        let model = HullWhite::new(
+            HullWhiteParams {
+                a: 0.05,
+                sigma: 0.01,
+            },
+            YieldCurve::flat(0.03),
+        )
+        .unwrap();
+        let mut swaption = Swaption {
+            kind: SwaptionType::Receiver,
+            expiry: 1.0,
+            maturity: 6.0,
+            payments_per_year: 1,
+            strike: 0.0,
+            notional: 100.0,
+        };
+        swaption.strike = hw_forward_swap_rate(&model, &swaption).0;
+        let cfg = LsmConfig {
+            paths: 10_000,
+            ..Default::default()
+        };
+        let lsm = mc_swaption_lsm(&model, &swaption, SwaptionExercise::Bermudan, &cfg).unwrap();
+        let dates = &lsm.policy.dates;
+        assert_eq!(dates.len(), 5);
+        assert!(dates[..4].iter().all(|d| d.continuation.is_some()));
+        assert!(dates[4].continuation.is_none());
+
+        // A receiver exercises below the boundary and holds above it
+        for (j, date) in dates.iter().enumerate() {
+            let x = date.boundary.unwrap();
+            let exercise = |x: f64| {
+                -swaption.payer_swap_value(date.time, |d| model.bond_price(date.time, d, x))
+            };
+            assert!(lsm.policy.exercises(j, x - 1e-3, exercise(x - 1e-3)));
+            assert!(!lsm.policy.exercises(j, x + 1e-3, exercise(x + 1e-3)));
+        }
+        // Waiting is worth more early on, so the boundary is lower
+        assert!(dates[0].boundary.unwrap() < dates[4].boundary.unwrap());
+
+        // Same paths reproduce the in-sample price; fresh paths give a
+        // low-biased price within noise of it
+        let same = mc_price_swaption_with_policy(&model, &swaption, &lsm.policy, &cfg).unwrap();
+        assert!((same.0 - lsm.price).abs() < 1e-10 * lsm.price);
+        let fresh = LsmConfig { seed: 999, ..cfg };
+        let (low, var) =
+            mc_price_swaption_with_policy(&model, &swaption, &lsm.policy, &fresh).unwrap();
+        assert!(
+            (low - lsm.price).abs() < 4.0 * (var + lsm.variance).sqrt(),
+            "{} vs {}",
+            low,
+            lsm.price
+        );
+
+        let european = Swaption {
+            maturity: 5.0,
+            ..swaption
+        };
+        assert!(mc_price_swaption_with_policy(&model, &european, &lsm.policy, &cfg).is_err());
+    }
+}
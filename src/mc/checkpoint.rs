@@ -0,0 +1,274 @@
+// src/mc/checkpoint.rs
+//! Checkpoint and Resume for Long Simulations
+//!
+//! # Overview
+//!
+//! [`mc_price_resumable`] runs the chunked estimator of
+//! [`mc_price_with_progress`](crate::mc::progress::mc_price_with_progress)
+//! and writes a [`Checkpoint`] after every chunk: the running statistics of
+//! (payoff, controls) and the index of the next path. When the file exists
+//! at start-up the run continues from it, so a billion-path run on a spot
+//! instance loses at most one chunk to an interruption.
+//!
+//! # Bit-Identical Resumption
+//!
+//! Path `i` always draws from its own stream, each chunk is reduced in a
+//! fixed order (the fixed tree of `McConfig::deterministic`), chunks are
+//! merged in path order, and the checkpoint stores the exact bit patterns
+//! of the statistics. A run interrupted and resumed any number of times
+//! therefore returns exactly the result of an uninterrupted run with the
+//! same configuration and `chunk_paths`.
+//!
+//! # File Format
+//!
+//! A small text file, written to a temporary file and renamed into place
+//! so an interruption during the write leaves the previous checkpoint:
+//! ```text
+//! fast-sde checkpoint v1
+//! run paths=... steps=... s0=... payoff=... chunk_paths=...
+//! next_path 1048576
+//! dim 2
+//! moments <count and IEEE-754 bit patterns in hex>
+//! ```
+//! The `run` line describes the configuration; resuming with a different
+//! one is an error. Custom `normal_source`s and `controls` are only
+//! recorded by count, so they must be recreated identically by the caller.
+
+use crate::error::{SdeError, SdeResult};
+use crate::mc::control_variates::PathMoments;
+use crate::mc::mc_engine::McConfig;
+use crate::mc::progress::{chunked_config, run_chunks, ProgressResult, RunControl};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const HEADER: &str = "fast-sde checkpoint v1";
+
+/// Saved state of a chunked run
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    run: String,
+    next_path: usize,
+    moments: PathMoments,
+}
+
+impl Checkpoint {
+    /// Index of the first path not yet simulated
+    pub fn next_path(&self) -> usize {
+        self.next_path
+    }
+
+    /// Description of the run that wrote the checkpoint
+    pub fn run(&self) -> &str {
+        &self.run
+    }
+
+    /// Read a checkpoint written by [`Checkpoint::write`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error, or `InvalidData` for a malformed file.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Write the checkpoint to `path` atomically (via `path.tmp`)
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error of writing or renaming the file.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let words: Vec<String> = self
+            .moments
+            .words()
+            .iter()
+            .map(|w| format!("{:016x}", w))
+            .collect();
+        let text = format!(
+            "{}\nrun {}\nnext_path {}\ndim {}\nmoments {}\n",
+            HEADER,
+            self.run,
+            self.next_path,
+            self.moments.dim(),
+            words.join(" ")
+        );
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, text)?;
+        fs::rename(&tmp, path)
+    }
+
+    fn parse(text: &str) -> io::Result<Self> {
+        let invalid = |what: &str| io::Error::new(io::ErrorKind::InvalidData, what.to_string());
+        let mut lines = text.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(invalid("not a fast-sde checkpoint"));
+        }
+        let mut field = |name: &str| {
+            lines
+                .next()
+                .and_then(|line| line.strip_prefix(name))
+                .and_then(|rest| rest.strip_prefix(' '))
+                .ok_or_else(|| invalid(&format!("missing {}", name)))
+        };
+        let run = field("run")?.to_string();
+        let next_path = field("next_path")?
+            .parse()
+            .map_err(|_| invalid("bad next_path"))?;
+        let dim = field("dim")?.parse().map_err(|_| invalid("bad dim"))?;
+        let words = field("moments")?
+            .split_whitespace()
+            .map(|w| u64::from_str_radix(w, 16))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid("bad moments"))?;
+        let moments = PathMoments::from_words(dim, &words).ok_or_else(|| invalid("bad moments"))?;
+        Ok(Checkpoint {
+            run,
+            next_path,
+            moments,
+        })
+    }
+}
+
+/// Price `cfg` in chunks, checkpointing to `checkpoint` after every chunk
+/// and resuming from it if it exists
+///
+/// `control.chunk_paths` is the checkpoint interval; progress reports and
+/// cancellation work as in
+/// [`mc_price_with_progress`](crate::mc::progress::mc_price_with_progress).
+/// A cancelled run keeps its checkpoint and can be resumed later. The file
+/// is left in place when the run completes.
+///
+/// # Errors
+///
+/// Returns `SdeError::InvalidConfiguration` if the checkpoint cannot be
+/// read or written or belongs to a different run, and the errors of the
+/// chunked estimator.
+pub fn mc_price_resumable(
+    cfg: &McConfig,
+    control: &RunControl,
+    checkpoint: &Path,
+) -> SdeResult<ProgressResult> {
+    let cfg = &chunked_config(cfg, control)?;
+    let run = describe_run(cfg, control);
+    let io_error = |e: io::Error| SdeError::InvalidConfiguration {
+        field: "checkpoint".to_string(),
+        reason: format!("{}: {}", checkpoint.display(), e),
+    };
+
+    let resume = match Checkpoint::read(checkpoint) {
+        Ok(saved) if saved.run == run => Some((saved.moments, saved.next_path)),
+        Ok(_) => {
+            return Err(SdeError::InvalidConfiguration {
+                field: "checkpoint".to_string(),
+                reason: format!("{} was written by a different run", checkpoint.display()),
+            })
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(io_error(e)),
+    };
+
+    run_chunks(cfg, control, resume, true, |next_path, moments| {
+        Checkpoint {
+            run: run.clone(),
+            next_path,
+            moments: *moments,
+        }
+        .write(checkpoint)
+        .map_err(io_error)
+    })
+}
+
+/// Everything that determines the sample and its reduction order
+fn describe_run(cfg: &McConfig, control: &RunControl) -> String {
+    format!(
+        "paths={} steps={} s0={:?} r={:?} sigma={:?} t={:?} antithetic={} \
+         control_variate={} controls={} seed={} seed_strategy={:?} deterministic={} \
+         scheme={:?} observation_times={:?} early_termination={} chunk_size={:?} \
+         normal_source={} path_construction={:?} payoff={:?} chunk_paths={}",
+        cfg.paths,
+        cfg.steps,
+        cfg.s0,
+        cfg.r,
+        cfg.sigma,
+        cfg.t,
+        cfg.use_antithetic,
+        cfg.use_control_variate,
+        cfg.controls.len(),
+        cfg.seed,
+        cfg.seed_strategy,
+        cfg.deterministic,
+        cfg.scheme,
+        cfg.observation_times,
+        cfg.early_termination,
+        cfg.chunk_size,
+        cfg.normal_source.is_some(),
+        cfg.path_construction,
+        cfg.payoff,
+        control.chunk_paths,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mc::payoffs::Payoff;
+    use crate::mc::progress::{CancellationToken, Progress};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_resumed_run_is_bit_identical() {
+        let dir = std::env::temp_dir().join(format!("fast_sde_checkpoint_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (whole, parts) = (dir.join("whole.ckpt"), dir.join("parts.ckpt"));
+        let _ = fs::remove_file(&whole);
+        let _ = fs::remove_file(&parts);
+
+        let cfg = McConfig {
+            paths: 40_000,
+            steps: 4,
+            payoff: Payoff::AsianCall { k: 100.0 },
+            ..Default::default()
+        };
+        let control = RunControl {
+            chunk_paths: 5_000,
+            ..Default::default()
+        };
+        let uninterrupted = mc_price_resumable(&cfg, &control, &whole).unwrap();
+
+        // Stop after every second chunk and resume until done
+        let mut result;
+        loop {
+            let token = CancellationToken::new();
+            let handle = token.clone();
+            let stops_at = Checkpoint::read(&parts).map_or(0, |c| c.next_path()) + 10_000;
+            let interrupted = RunControl {
+                on_progress: Some(Arc::new(move |p: &Progress| {
+                    if p.paths_completed >= stops_at {
+                        handle.cancel();
+                    }
+                })),
+                cancel: Some(token),
+                ..control.clone()
+            };
+            result = mc_price_resumable(&cfg, &interrupted, &parts).unwrap();
+            if !result.cancelled {
+                break;
+            }
+        }
+        assert_eq!(result.paths_completed, 40_000);
+        assert_eq!(result.price.to_bits(), uninterrupted.price.to_bits());
+        assert_eq!(
+            result.std_error.to_bits(),
+            uninterrupted.std_error.to_bits()
+        );
+
+        // A checkpoint of another run is rejected
+        let other = McConfig {
+            seed: 1,
+            ..cfg.clone()
+        };
+        assert!(mc_price_resumable(&other, &control, &parts).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,329 @@
+// src/mc/commodity.rs
+//! Commodity Asian and Calendar Spread Options under Schwartz-Smith
+//!
+//! # Contracts
+//!
+//! With a flat discount rate `r`:
+//! ```text
+//! Asian:            e^{-r t_n} max(ω(Σ_i S_{t_i}/n - K), 0)
+//! Calendar spread:  e^{-r T} max(ω(F(T, T₁) - F(T, T₂) - K), 0)
+//! ```
+//! with `ω = ±1` for calls and puts. The Asian averages the spot on its
+//! fixing dates and pays at the last one; the spread option pays the
+//! difference of two futures prices observed at its expiry.
+//!
+//! # Simulation
+//!
+//! The factors are stepped exactly between observation dates, and futures
+//! at the expiry come from the affine formula, so there is no
+//! discretisation bias.
+//!
+//! # Parity
+//!
+//! Spot expectations and futures prices are martingale quantities, so
+//! ```text
+//! Asian call - put  = e^{-r t_n} (Σ_i F(0, t_i)/n - K)
+//! Spread call - put = e^{-r T} (F(0, T₁) - F(0, T₂) - K)
+//! ```
+
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::mc::accumulators::Moments;
+use crate::mc::compound::OptionType;
+use crate::models::schwartz_smith::SchwartzSmith;
+use crate::parallel::prelude::*;
+use crate::rng::SeedStrategy;
+use rand::rngs::StdRng;
+
+/// Arithmetic-average option on the spot at the fixing dates
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommodityAsian {
+    pub option_type: OptionType,
+    pub strike: f64,
+    /// Increasing fixing dates; the option pays at the last one
+    pub fixings: Vec<f64>,
+}
+
+impl CommodityAsian {
+    /// Validate the strike and fixing dates
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_finite("strike", self.strike)?;
+        if self.fixings.is_empty() {
+            return Err(SdeError::InvalidConfiguration {
+                field: "fixings".to_string(),
+                reason: "needs at least one fixing date".to_string(),
+            });
+        }
+        let mut prev = 0.0;
+        for &t in &self.fixings {
+            validate_finite("fixing", t)?;
+            if t <= prev {
+                return Err(SdeError::InvalidParameters {
+                    parameter: "fixing".to_string(),
+                    value: t,
+                    constraint: "fixing dates must be positive and strictly increasing".to_string(),
+                });
+            }
+            prev = t;
+        }
+        Ok(())
+    }
+}
+
+/// Option on the spread between the futures maturing at `near` and `far`
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalendarSpread {
+    pub option_type: OptionType,
+    pub strike: f64,
+    pub expiry: f64,
+    /// Maturity of the long futures, at or after the expiry
+    pub near: f64,
+    /// Maturity of the short futures, at or after the expiry
+    pub far: f64,
+}
+
+impl CalendarSpread {
+    /// Validate the strike, expiry and futures maturities
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_finite("strike", self.strike)?;
+        validate_positive("expiry", self.expiry)?;
+        for (name, maturity) in [("near", self.near), ("far", self.far)] {
+            validate_finite(name, maturity)?;
+            if maturity < self.expiry {
+                return Err(SdeError::InvalidParameters {
+                    parameter: name.to_string(),
+                    value: maturity,
+                    constraint: format!("must not precede the expiry {}", self.expiry),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Monte Carlo price of a commodity Asian option
+///
+/// Path `i` steps both factors from fixing to fixing on stream `i` of
+/// `seed` under `seed_strategy`.
+/// Returns `(price, variance of the estimate)`.
+///
+/// # Errors
+///
+/// Returns `SdeError` for an invalid contract, rate or path count, or a
+/// non-finite price.
+pub fn mc_price_commodity_asian(
+    model: &SchwartzSmith,
+    asian: &CommodityAsian,
+    r: f64,
+    paths: usize,
+    seed: u64,
+    seed_strategy: SeedStrategy,
+) -> SdeResult<(f64, f64)> {
+    asian.validate()?;
+    validate_finite("r", r)?;
+    let expiry = *asian.fixings.last().unwrap();
+    mc_price(
+        paths,
+        seed,
+        seed_strategy,
+        "Commodity Asian pricing",
+        |rng| {
+            let mut state = model.initial_state();
+            let mut t = 0.0;
+            let mut total = 0.0;
+            for &fixing in &asian.fixings {
+                model.step(&mut state, fixing - t, rng);
+                t = fixing;
+                total += model.spot(t, &state);
+            }
+            let average = total / asian.fixings.len() as f64;
+            (-r * expiry).exp() * intrinsic(asian.option_type, average - asian.strike)
+        },
+    )
+}
+
+/// Monte Carlo price of a calendar spread option on futures
+///
+/// Each path takes one exact step of both factors to the expiry, drawn
+/// from its `seed_strategy` stream of `seed`; both futures are read off
+/// that one state.
+/// Returns `(price, variance of the estimate)`.
+///
+/// # Errors
+///
+/// Returns `SdeError` for an invalid contract, rate or path count, or a
+/// non-finite price.
+pub fn mc_price_calendar_spread(
+    model: &SchwartzSmith,
+    spread: &CalendarSpread,
+    r: f64,
+    paths: usize,
+    seed: u64,
+    seed_strategy: SeedStrategy,
+) -> SdeResult<(f64, f64)> {
+    spread.validate()?;
+    validate_finite("r", r)?;
+    mc_price(
+        paths,
+        seed,
+        seed_strategy,
+        "Calendar spread pricing",
+        |rng| {
+            let mut state = model.initial_state();
+            model.step(&mut state, spread.expiry, rng);
+            let near = model.futures(spread.expiry, spread.near, &state);
+            let far = model.futures(spread.expiry, spread.far, &state);
+            (-r * spread.expiry).exp() * intrinsic(spread.option_type, near - far - spread.strike)
+        },
+    )
+}
+
+fn intrinsic(option_type: OptionType, moneyness: f64) -> f64 {
+    match option_type {
+        OptionType::Call => moneyness.max(0.0),
+        OptionType::Put => (-moneyness).max(0.0),
+    }
+}
+
+fn mc_price(
+    paths: usize,
+    seed: u64,
+    seed_strategy: SeedStrategy,
+    method: &str,
+    payoff: impl Fn(&mut StdRng) -> f64 + Sync,
+) -> SdeResult<(f64, f64)> {
+    validate_paths(paths)?;
+    let moments = (0..paths)
+        .into_par_iter()
+        .map(|i| {
+            let mut rng = seed_strategy.path_rng(seed, i as u64);
+            payoff(&mut rng)
+        })
+        .fold(Moments::new, |mut acc, y| {
+            acc.push(y);
+            acc
+        })
+        .reduce(Moments::new, |mut a, b| {
+            a.merge(b);
+            a
+        });
+
+    let (price, variance) = (moments.mean(), moments.variance_of_mean());
+    if !price.is_finite() {
+        return Err(SdeError::NumericalInstability {
+            method: method.to_string(),
+            reason: format!("non-finite price {}", price),
+        });
+    }
+    Ok((price, variance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::schwartz_smith::{SchwartzSmithParams, Seasonality};
+    use crate::rng;
+
+    #[test]
+    fn test_futures_and_option_parities() {
+        let model = SchwartzSmith::new(
+            SchwartzSmithParams {
+                chi0: 0.1,
+                xi0: 4.0,
+                kappa: 1.5,
+                sigma_chi: 0.3,
+                lambda_chi: 0.02,
+                mu_xi: -0.01,
+                sigma_xi: 0.15,
+                rho: 0.3,
+            },
+            Seasonality {
+                cos: vec![0.1],
+                sin: vec![0.05, -0.02],
+            },
+        )
+        .unwrap();
+        let (r, paths) = (0.03, 20_000);
+
+        // Simulated spot is unbiased for the futures price
+        for maturity in [0.25, 1.0, 3.0] {
+            let mut moments = Moments::new();
+            for i in 0..paths {
+                let mut rng = rng::seed_rng_from_u64(40 + i as u64);
+                let mut state = model.initial_state();
+                model.step(&mut state, maturity, &mut rng);
+                moments.push(model.spot(maturity, &state));
+            }
+            let (mean, se) = (moments.mean(), moments.variance_of_mean().sqrt());
+            let futures = model.futures_price(maturity);
+            assert!(
+                (mean - futures).abs() < 4.0 * se,
+                "E[S({})] = {} vs F = {}",
+                maturity,
+                mean,
+                futures
+            );
+        }
+
+        // Call - put parity on common paths
+        let asian = CommodityAsian {
+            option_type: OptionType::Call,
+            strike: 60.0,
+            fixings: (1..=12).map(|m| m as f64 / 12.0).collect(),
+        };
+        let put = CommodityAsian {
+            option_type: OptionType::Put,
+            ..asian.clone()
+        };
+        let (call_price, call_var) =
+            mc_price_commodity_asian(&model, &asian, r, paths, 1, SeedStrategy::Hashed).unwrap();
+        let (put_price, put_var) =
+            mc_price_commodity_asian(&model, &put, r, paths, 1, SeedStrategy::Hashed).unwrap();
+        let average = asian
+            .fixings
+            .iter()
+            .map(|&t| model.futures_price(t))
+            .sum::<f64>()
+            / 12.0;
+        let forward = (-r).exp() * (average - asian.strike);
+        assert!(
+            (call_price - put_price - forward).abs() < 4.0 * (call_var + put_var).sqrt(),
+            "Asian parity {} vs {}",
+            call_price - put_price,
+            forward
+        );
+
+        let spread = CalendarSpread {
+            option_type: OptionType::Call,
+            strike: 1.0,
+            expiry: 0.5,
+            near: 0.75,
+            far: 1.25,
+        };
+        let spread_put = CalendarSpread {
+            option_type: OptionType::Put,
+            ..spread
+        };
+        let (call_price, call_var) =
+            mc_price_calendar_spread(&model, &spread, r, paths, 2, SeedStrategy::Hashed).unwrap();
+        let (put_price, put_var) =
+            mc_price_calendar_spread(&model, &spread_put, r, paths, 2, SeedStrategy::Hashed)
+                .unwrap();
+        let forward = (-r * 0.5f64).exp()
+            * (model.futures_price(0.75) - model.futures_price(1.25) - spread.strike);
+        assert!(call_price > 0.0 && put_price > 0.0);
+        assert!(
+            (call_price - put_price - forward).abs() < 4.0 * (call_var + put_var).sqrt(),
+            "Spread parity {} vs {}",
+            call_price - put_price,
+            forward
+        );
+
+        let bad = CalendarSpread {
+            near: 0.25,
+            ..spread
+        };
+        assert!(mc_price_calendar_spread(&model, &bad, r, 100, 1, SeedStrategy::Hashed).is_err());
+    }
+}
@@ -0,0 +1,247 @@
+// src/mc/compound.rs
+//! Compound Options (Options on Options)
+//!
+//! # Contract
+//!
+//! At the outer expiry `t₁` the holder may buy (call) or sell (put) the
+//! inner option, which expires at `T = cfg.t`, for the outer strike `K₁`:
+//! ```text
+//! Call on V:  max(V(S_{t₁}, t₁) - K₁, 0)
+//! Put on V:   max(K₁ - V(S_{t₁}, t₁), 0)
+//! price = e^(-r t₁) E[outer payoff]
+//! ```
+//!
+//! # Inner Valuation
+//!
+//! `S_{t₁}` is simulated exactly, and the inner option value at `t₁` is
+//! either the Black-Scholes price ([`InnerValuation::Analytic`]) or a nested
+//! Monte Carlo estimate from each outer path ([`InnerValuation::Nested`]).
+//! The nested estimator is biased upwards by the convexity of the outer
+//! payoff in the inner noise; the bias decays like `1/inner_paths`.
+//!
+//! # Parity
+//!
+//! With the analytic inner value, on every path
+//! ```text
+//! CallOn - PutOn = e^(-r t₁) (V(S_{t₁}) - K₁)
+//! ```
+//! whose expectation is `V(S₀, 0) - K₁ e^(-r t₁)`.
+
+use crate::analytics::bs_analytic;
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::mc::accumulators::Moments;
+use crate::mc::mc_engine::McConfig;
+use crate::parallel::prelude::*;
+use crate::rng;
+use rand::rngs::StdRng;
+
+/// Call or put
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+/// Compound option on a European option expiring at `cfg.t`
+#[derive(Debug, Clone, Copy)]
+pub struct CompoundOption {
+    /// Right on the inner option
+    pub outer: OptionType,
+    pub outer_strike: f64,
+    /// Outer expiry `t₁`, strictly before `cfg.t`
+    pub outer_expiry: f64,
+    pub inner: OptionType,
+    pub inner_strike: f64,
+}
+
+impl CompoundOption {
+    /// Validate strikes and the outer expiry against the inner maturity `t`
+    pub fn validate(&self, t: f64) -> SdeResult<()> {
+        validate_non_negative("outer_strike", self.outer_strike)?;
+        validate_positive("inner_strike", self.inner_strike)?;
+        validate_positive("outer_expiry", self.outer_expiry)?;
+        if self.outer_expiry >= t {
+            return Err(SdeError::InvalidParameters {
+                parameter: "outer_expiry".to_string(),
+                value: self.outer_expiry,
+                constraint: format!("must be before the inner maturity t = {}", t),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// How the inner option is valued at the outer expiry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InnerValuation {
+    /// Black-Scholes closed form
+    Analytic,
+    /// Nested Monte Carlo with this many inner paths per outer path
+    Nested { inner_paths: usize },
+}
+
+/// Price a compound option under GBM
+///
+/// Paths, seed and antithetic pairing come from `cfg`, as do the market
+/// inputs and the inner maturity `cfg.t`; `cfg.payoff`, `steps` and control
+/// variates are not used. Returns `(price, variance of the estimate)`.
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid configurations or contracts, or
+/// non-finite estimates.
+pub fn mc_compound_option_gbm(
+    cfg: &McConfig,
+    option: &CompoundOption,
+    inner: InnerValuation,
+) -> SdeResult<(f64, f64)> {
+    cfg.validate()?;
+    cfg.parallelism.install(|| {
+        option.validate(cfg.t)?;
+        if let InnerValuation::Nested { inner_paths } = inner {
+            validate_paths(inner_paths)?;
+        }
+
+        let t1 = option.outer_expiry;
+        let tau = cfg.t - t1;
+        let drift = (cfg.r - 0.5 * cfg.sigma * cfg.sigma) * t1;
+        let vol = cfg.sigma * t1.sqrt();
+        let discount = (-cfg.r * t1).exp();
+
+        let outer_payoff = |s1: f64, rng: &mut StdRng| {
+            let value = match inner {
+                InnerValuation::Analytic => inner_value_analytic(cfg, option, s1, tau),
+                InnerValuation::Nested { inner_paths } => {
+                    inner_value_nested(cfg, option, s1, tau, inner_paths, rng)
+                }
+            };
+            match option.outer {
+                OptionType::Call => (value - option.outer_strike).max(0.0),
+                OptionType::Put => (option.outer_strike - value).max(0.0),
+            }
+        };
+
+        let moments = (0..cfg.paths)
+            .into_par_iter()
+            .map(|i| {
+                let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+                let z = rng::get_normal_draw(&mut rng);
+                let mut y = outer_payoff(cfg.s0 * (drift + vol * z).exp(), &mut rng);
+                if cfg.use_antithetic {
+                    y = 0.5 * (y + outer_payoff(cfg.s0 * (drift - vol * z).exp(), &mut rng));
+                }
+                discount * y
+            })
+            .fold(Moments::new, |mut acc, y| {
+                acc.push(y);
+                acc
+            })
+            .reduce(Moments::new, |mut a, b| {
+                a.merge(b);
+                a
+            });
+
+        let (price, variance) = (moments.mean(), moments.variance_of_mean());
+        if !price.is_finite() {
+            return Err(SdeError::NumericalInstability {
+                method: "Compound option pricing".to_string(),
+                reason: format!("non-finite price {}", price),
+            });
+        }
+        Ok((price, variance))
+    })
+}
+
+fn inner_value_analytic(cfg: &McConfig, option: &CompoundOption, s1: f64, tau: f64) -> f64 {
+    let k = option.inner_strike;
+    match option.inner {
+        OptionType::Call => bs_analytic::bs_call_price(s1, k, cfg.r, cfg.sigma, tau),
+        OptionType::Put => bs_analytic::bs_put_price(s1, k, cfg.r, cfg.sigma, tau),
+    }
+}
+
+fn inner_value_nested(
+    cfg: &McConfig,
+    option: &CompoundOption,
+    s1: f64,
+    tau: f64,
+    inner_paths: usize,
+    rng: &mut StdRng,
+) -> f64 {
+    let drift = (cfg.r - 0.5 * cfg.sigma * cfg.sigma) * tau;
+    let vol = cfg.sigma * tau.sqrt();
+    let k = option.inner_strike;
+    let total: f64 = (0..inner_paths)
+        .map(|_| {
+            let s_t = s1 * (drift + vol * rng::get_normal_draw(rng)).exp();
+            match option.inner {
+                OptionType::Call => (s_t - k).max(0.0),
+                OptionType::Put => (k - s_t).max(0.0),
+            }
+        })
+        .sum();
+    (-cfg.r * tau).exp() * total / inner_paths as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compound_parity_and_nested_inner_value() {
+        let cfg = McConfig {
+            paths: 20_000,
+            ..Default::default()
+        };
+        let call_on_call = CompoundOption {
+            outer: OptionType::Call,
+            outer_strike: 5.0,
+            outer_expiry: 0.5,
+            inner: OptionType::Call,
+            inner_strike: 100.0,
+        };
+        let put_on_call = CompoundOption {
+            outer: OptionType::Put,
+            ..call_on_call
+        };
+
+        let (call, var) = mc_compound_option_gbm(&cfg, &call_on_call, InnerValuation::Analytic)
+            .expect("Valid contract");
+        let (put, _) = mc_compound_option_gbm(&cfg, &put_on_call, InnerValuation::Analytic)
+            .expect("Valid contract");
+        let parity = bs_analytic::bs_call_price(cfg.s0, 100.0, cfg.r, cfg.sigma, cfg.t)
+            - 5.0 * (-cfg.r * 0.5).exp();
+        assert!(
+            (call - put - parity).abs() < 0.05,
+            "{} vs {}",
+            call - put,
+            parity
+        );
+        // The right to buy the call for K₁ is worth less than the call itself
+        assert!(call > 0.0 && call < parity + 5.0 * (-cfg.r * 0.5).exp());
+
+        let nested_cfg = McConfig {
+            paths: 2_000,
+            ..cfg.clone()
+        };
+        let (nested, _) = mc_compound_option_gbm(
+            &nested_cfg,
+            &call_on_call,
+            InnerValuation::Nested { inner_paths: 2_000 },
+        )
+        .expect("Valid contract");
+        assert!(
+            (nested - call).abs() < 0.1 + 4.0 * var.sqrt(),
+            "{} vs {}",
+            nested,
+            call
+        );
+
+        let late = CompoundOption {
+            outer_expiry: 1.0,
+            ..call_on_call
+        };
+        assert!(mc_compound_option_gbm(&cfg, &late, InnerValuation::Analytic).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+// src/mc/config_builder.rs
+//! Builder for [`McConfig`]
+//!
+//! # Motivation
+//!
+//! [`McConfig`] is a plain struct with public fields, so every new field
+//! breaks callers that spell out a struct literal. [`McConfigBuilder`]
+//! starts from defaults and sets only what the caller names, so fields can
+//! be added without touching existing code, and [`McConfigBuilder::build`]
+//! validates the result once instead of at every pricing call.
+//!
+//! # Presets
+//!
+//! ```text
+//! preset        paths       steps   antithetic   control variate   deterministic
+//! new()         1 000 000   1       yes          yes               no
+//! quick()       10 000      1       yes          yes               no
+//! production()  1 000 000   1       yes          yes               yes
+//! ```
+//! `production()` uses the bitwise-reproducible Philox streams, so a price
+//! can be recomputed exactly on another machine or thread count.
+
+use crate::error::SdeResult;
+use crate::mc::control_variates::ControlVariate;
+use crate::mc::mc_engine::{GreekMethod, GreeksConfig, McConfig, Scheme};
+use crate::mc::normal_source::NormalSource;
+use crate::mc::path_construction::PathConstruction;
+use crate::mc::payoffs::{BarrierShift, Payoff, PayoffSmoothing};
+use crate::parallel::Parallelism;
+use crate::rng::SeedStrategy;
+use crate::time::{Date, DayCount};
+use std::sync::Arc;
+
+/// Fluent, validating constructor of [`McConfig`]
+#[derive(Clone, Default)]
+pub struct McConfigBuilder {
+    config: McConfig,
+}
+
+impl McConfig {
+    /// Builder starting from [`McConfig::default`]
+    pub fn builder() -> McConfigBuilder {
+        McConfigBuilder::new()
+    }
+}
+
+impl McConfigBuilder {
+    /// Builder starting from [`McConfig::default`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 10 000 paths, fast enough for interactive checks
+    pub fn quick() -> Self {
+        Self::new().paths(10_000)
+    }
+
+    /// 1 000 000 paths with reproducible path streams
+    pub fn production() -> Self {
+        Self::new().paths(1_000_000).deterministic(true)
+    }
+
+    pub fn paths(mut self, paths: usize) -> Self {
+        self.config.paths = paths;
+        self
+    }
+
+    pub fn steps(mut self, steps: usize) -> Self {
+        self.config.steps = steps;
+        self
+    }
+
+    pub fn s0(mut self, s0: f64) -> Self {
+        self.config.s0 = s0;
+        self
+    }
+
+    pub fn r(mut self, r: f64) -> Self {
+        self.config.r = r;
+        self
+    }
+
+    pub fn sigma(mut self, sigma: f64) -> Self {
+        self.config.sigma = sigma;
+        self
+    }
+
+    /// Maturity in years
+    pub fn t(mut self, t: f64) -> Self {
+        self.config.t = t;
+        self
+    }
+
+    /// Maturity as the year fraction from `valuation` to `expiry`
+    pub fn expiry(self, valuation: Date, expiry: Date, day_count: DayCount) -> Self {
+        self.t(day_count.year_fraction(valuation, expiry))
+    }
+
+    /// Observe at these dates only, maturing at the last one; see
+    /// [`McConfig::observation_times`]
+    pub fn fixing_dates(self, valuation: Date, dates: &[Date], day_count: DayCount) -> Self {
+        let times = day_count.year_fractions(valuation, dates);
+        let t = times.last().copied().unwrap_or(0.0);
+        self.t(t).observation_times(times)
+    }
+
+    pub fn antithetic(mut self, enabled: bool) -> Self {
+        self.config.use_antithetic = enabled;
+        self
+    }
+
+    pub fn control_variate(mut self, enabled: bool) -> Self {
+        self.config.use_control_variate = enabled;
+        self
+    }
+
+    /// Add a control variate and enable control variates; see
+    /// [`McConfig::controls`]
+    pub fn control_variate_with(mut self, control: Arc<dyn ControlVariate>) -> Self {
+        self.config.controls.push(control);
+        self.config.use_control_variate = true;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.config.seed = seed;
+        self
+    }
+
+    /// See [`McConfig::seed_strategy`]
+    pub fn seed_strategy(mut self, strategy: SeedStrategy) -> Self {
+        self.config.seed_strategy = strategy;
+        self
+    }
+
+    pub fn payoff(mut self, payoff: Payoff) -> Self {
+        self.config.payoff = payoff;
+        self
+    }
+
+    pub fn greeks(mut self, greeks: GreeksConfig) -> Self {
+        self.config.greeks = greeks;
+        self
+    }
+
+    /// Bump size of finite-difference Greeks
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.config.epsilon = Some(epsilon);
+        self
+    }
+
+    pub fn greek_method(mut self, method: GreekMethod) -> Self {
+        self.config.greek_method = method;
+        self
+    }
+
+    pub fn smoothing(mut self, smoothing: PayoffSmoothing) -> Self {
+        self.config.smoothing = smoothing;
+        self
+    }
+
+    /// Simulate at these times only; see [`McConfig::observation_times`]
+    pub fn observation_times(mut self, times: Vec<f64>) -> Self {
+        self.config.observation_times = Some(times);
+        self
+    }
+
+    pub fn barrier_shift(mut self, shift: BarrierShift) -> Self {
+        self.config.barrier_shift = Some(shift);
+        self
+    }
+
+    /// Minimum paths per Rayon task; see [`McConfig::chunk_size`]
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.config.chunk_size = Some(chunk_size);
+        self
+    }
+
+    /// Thread pool of the engine; see [`McConfig::parallelism`]
+    pub fn parallelism(mut self, parallelism: Parallelism) -> Self {
+        self.config.parallelism = parallelism;
+        self
+    }
+
+    pub fn early_termination(mut self, enabled: bool) -> Self {
+        self.config.early_termination = enabled;
+        self
+    }
+
+    /// See [`McConfig::deterministic`]
+    pub fn deterministic(mut self, enabled: bool) -> Self {
+        self.config.deterministic = enabled;
+        self
+    }
+
+    /// Time-stepping scheme; see [`McConfig::scheme`]
+    pub fn scheme(mut self, scheme: Scheme) -> Self {
+        self.config.scheme = scheme;
+        self
+    }
+
+    /// Normal draws of the engine; see [`McConfig::normal_source`]
+    pub fn normal_source(mut self, source: Arc<dyn NormalSource>) -> Self {
+        self.config.normal_source = Some(source);
+        self
+    }
+
+    /// Construction order of the Brownian paths; see
+    /// [`McConfig::path_construction`]
+    pub fn path_construction(mut self, construction: PathConstruction) -> Self {
+        self.config.path_construction = construction;
+        self
+    }
+
+    /// Validate and return the configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of [`McConfig::validate`].
+    pub fn build(self) -> SdeResult<McConfig> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SdeError;
+    use crate::mc::mc_engine::mc_price_option_gbm;
+
+    #[test]
+    fn test_builder_matches_struct_literal_and_validates() {
+        let built = McConfig::builder()
+            .paths(20_000)
+            .steps(12)
+            .sigma(0.3)
+            .payoff(Payoff::EuropeanPut { k: 95.0 })
+            .control_variate(false)
+            .build()
+            .expect("Valid configuration");
+        let literal = McConfig {
+            paths: 20_000,
+            steps: 12,
+            sigma: 0.3,
+            payoff: Payoff::EuropeanPut { k: 95.0 },
+            use_control_variate: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            mc_price_option_gbm(&built).unwrap(),
+            mc_price_option_gbm(&literal).unwrap()
+        );
+
+        let quick = McConfigBuilder::quick().build().unwrap();
+        assert_eq!(quick.paths, 10_000);
+        let production = McConfigBuilder::production().build().unwrap();
+        assert!(production.deterministic && production.paths == 1_000_000);
+
+        // Validation happens at build time
+        let err = McConfig::builder().sigma(-0.2).build().err();
+        assert!(matches!(
+            err,
+            Some(SdeError::InvalidParameters { ref parameter, .. }) if parameter == "sigma"
+        ));
+        assert!(McConfig::builder().chunk_size(0).build().is_err());
+    }
+}
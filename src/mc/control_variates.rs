@@ -0,0 +1,444 @@
+// src/mc/control_variates.rs
+//! Control Variates of the GBM Engine
+//!
+//! # Estimator
+//!
+//! A control is a path functional `X` with known expectation `E[X]`. The
+//! engine prices
+//! ```text
+//! Y - b (X - E[X]),    b = Cov(Y, X) / Var(X)
+//! ```
+//! which is unbiased for `E[Y]` and removes the share `ρ²(X, Y)` of the
+//! variance of `Y`. Controls implement [`ControlVariate`] and are selected
+//! with [`McConfig::controls`]; without one the engine picks
+//! [`default_control`] for the payoff.
+//!
+//! # Several Controls
+//!
+//! With `m ≤ MAX_CONTROLS` controls `X = (X_1, ..., X_m)` the coefficient
+//! vector is the least-squares regression of `Y` on `X`, i.e. the solution
+//! of the normal equations
+//! ```text
+//! Σ_XX b = Σ_XY,    price = e^{-rT} (Ȳ - bᵀ(X̄ - E[X]))
+//! ```
+//! which removes the share `R²` of the variance of `Y`. Controls that
+//! capture different features of the payoff add up: terminal spot and the
+//! geometric Asian call together track an arithmetic Asian far better
+//! than either alone. The `m × m` system is solved once per run by SVD,
+//! so collinear controls (e.g. a call, a put and the spot, which are
+//! linearly dependent by parity) share the coefficient rather than fail.
+//!
+//! # Built-in Controls
+//!
+//! ```text
+//! control           X (undiscounted)                             E[X]
+//! TerminalSpot      S_T                                          S_0 e^{rT}
+//! EuropeanCall      (S_T - K)⁺                                   e^{rT} C_BS(K)
+//! EuropeanPut       (K - S_T)⁺                                   e^{rT} (C_BS(K) - S_0 + K e^{-rT})
+//! DeltaHedge        Σ Δ(t_i, S_i)(S_{i+1} - S_i e^{rΔt_i}) e^{r(T - t_{i+1})}   0
+//! GeometricAsian    (G - K)⁺,  G = (Π_{i=0}^n S(t_i))^{1/(n+1)}  e^{μ + v/2} Φ(d₁) - K Φ(d₂)
+//! ```
+//! For the geometric average on the simulation grid `ln G` is normal with
+//! ```text
+//! μ = ln S_0 + (r - σ²/2) t̄,   v = σ²/(n+1)² Σ_i Σ_j min(t_i, t_j)
+//! d₁ = (μ - ln K + v) / √v,   d₂ = d₁ - √v
+//! ```
+//! where `t̄` is the mean fixing time.
+//! The put expectation comes from put-call parity. The delta hedge holds
+//! the Black–Scholes delta of a call struck at `K`, rebalanced on every
+//! `rebalance_every`-th grid point; each term is a martingale increment of
+//! the discounted spot, so `E[X] = 0` for any hedge ratios, and the closer
+//! the ratios are to the payoff's own delta the closer `X` tracks `Y`.
+//!
+//! # Validity
+//!
+//! The expectations hold for the exact GBM transition on full paths. The
+//! engine therefore drops the control under a discretization
+//! [`Scheme`] other than `Exact`, and
+//! [`McConfig::validate`] rejects explicit controls together with
+//! `early_termination`, which truncates paths.
+//!
+//! Samples and expectations are both undiscounted, like the payoff; the
+//! engine discounts the controlled estimate once.
+
+use crate::analytics::bs_analytic;
+use crate::math_utils::norm_cdf;
+use crate::mc::accumulators::MultiMoments;
+use crate::mc::mc_engine::{simulation_increments, McConfig, Scheme};
+use crate::mc::payoffs::Payoff;
+use nalgebra::{DMatrix, DVector};
+use std::sync::Arc;
+
+/// Most controls of one run
+pub const MAX_CONTROLS: usize = 8;
+
+/// Control values of one path, in the order of the engine's controls
+pub(crate) type ControlValues = [f64; MAX_CONTROLS];
+
+/// Moments of `(Y, X_1, ..., X_m)`
+pub(crate) type PathMoments = MultiMoments<{ MAX_CONTROLS + 1 }>;
+
+/// Path functional with a known expectation under the engine's GBM
+pub trait ControlVariate: Send + Sync {
+    /// Undiscounted value of the control on `path = [S_0, ..., S_n]`,
+    /// simulated on the time increments `dts`
+    fn sample(&self, cfg: &McConfig, dts: &[f64], path: &[f64]) -> f64;
+
+    /// Undiscounted expectation `E[X]` under the risk-neutral GBM of `cfg`
+    fn expectation(&self, cfg: &McConfig) -> f64;
+}
+
+/// Terminal spot `S_T`: the martingale control
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TerminalSpot;
+
+impl ControlVariate for TerminalSpot {
+    fn sample(&self, _cfg: &McConfig, _dts: &[f64], path: &[f64]) -> f64 {
+        path[path.len() - 1]
+    }
+
+    fn expectation(&self, cfg: &McConfig) -> f64 {
+        cfg.s0 * (cfg.r * cfg.t).exp()
+    }
+}
+
+/// European call on the terminal price
+#[derive(Debug, Clone, Copy)]
+pub struct EuropeanCallControl {
+    pub k: f64,
+}
+
+impl ControlVariate for EuropeanCallControl {
+    fn sample(&self, _cfg: &McConfig, _dts: &[f64], path: &[f64]) -> f64 {
+        Payoff::EuropeanCall { k: self.k }.calculate(&[path[path.len() - 1]])
+    }
+
+    fn expectation(&self, cfg: &McConfig) -> f64 {
+        bs_analytic::bs_call_price(cfg.s0, self.k, cfg.r, cfg.sigma, cfg.t) * (cfg.r * cfg.t).exp()
+    }
+}
+
+/// European put on the terminal price, with its expectation from put-call
+/// parity
+#[derive(Debug, Clone, Copy)]
+pub struct EuropeanPutControl {
+    pub k: f64,
+}
+
+impl ControlVariate for EuropeanPutControl {
+    fn sample(&self, _cfg: &McConfig, _dts: &[f64], path: &[f64]) -> f64 {
+        Payoff::EuropeanPut { k: self.k }.calculate(&[path[path.len() - 1]])
+    }
+
+    fn expectation(&self, cfg: &McConfig) -> f64 {
+        // P = C - S_0 + K e^{-rT}
+        let call = bs_analytic::bs_call_price(cfg.s0, self.k, cfg.r, cfg.sigma, cfg.t);
+        (call - cfg.s0 + self.k * (-cfg.r * cfg.t).exp()) * (cfg.r * cfg.t).exp()
+    }
+}
+
+/// Gains of a discretely rebalanced Black–Scholes delta hedge of a call
+/// struck at `k`, carried to maturity
+#[derive(Debug, Clone, Copy)]
+pub struct DeltaHedge {
+    pub k: f64,
+    /// Rebalance on every `rebalance_every`-th grid point (0 counts as 1)
+    pub rebalance_every: usize,
+}
+
+impl ControlVariate for DeltaHedge {
+    fn sample(&self, cfg: &McConfig, dts: &[f64], path: &[f64]) -> f64 {
+        let every = self.rebalance_every.max(1);
+        let mut gains = 0.0;
+        let mut t = 0.0;
+        let mut delta = 0.0;
+        for (i, (&dt, pair)) in dts.iter().zip(path.windows(2)).enumerate() {
+            if i % every == 0 {
+                delta = bs_analytic::bs_call_delta(pair[0], self.k, cfg.r, cfg.sigma, cfg.t - t);
+            }
+            t += dt;
+            let carry = (cfg.r * (cfg.t - t)).exp();
+            gains += delta * (pair[1] - pair[0] * (cfg.r * dt).exp()) * carry;
+        }
+        gains
+    }
+
+    fn expectation(&self, _cfg: &McConfig) -> f64 {
+        0.0
+    }
+}
+
+/// Call on the geometric average of all path points, `S_0` included, as
+/// for [`Payoff::AsianCall`]
+#[derive(Debug, Clone, Copy)]
+pub struct GeometricAsianCall {
+    pub k: f64,
+}
+
+impl ControlVariate for GeometricAsianCall {
+    fn sample(&self, _cfg: &McConfig, _dts: &[f64], path: &[f64]) -> f64 {
+        let log_mean = path.iter().map(|s| s.ln()).sum::<f64>() / path.len() as f64;
+        (log_mean.exp() - self.k).max(0.0)
+    }
+
+    fn expectation(&self, cfg: &McConfig) -> f64 {
+        let mut times = vec![0.0];
+        for dt in simulation_increments(cfg) {
+            times.push(times[times.len() - 1] + dt);
+        }
+        let n = times.len() as f64;
+        let mean_time = times.iter().sum::<f64>() / n;
+        // Σ_i Σ_j min(t_i, t_j) over increasing times
+        let min_sum: f64 = times
+            .iter()
+            .enumerate()
+            .map(|(i, &t)| t * (2.0 * (times.len() - i) as f64 - 1.0))
+            .sum();
+        let mu = cfg.s0.ln() + (cfg.r - 0.5 * cfg.sigma * cfg.sigma) * mean_time;
+        let v = cfg.sigma * cfg.sigma * min_sum / (n * n);
+        if v <= 0.0 {
+            return (mu.exp() - self.k).max(0.0);
+        }
+        let d1 = (mu - self.k.ln() + v) / v.sqrt();
+        let d2 = d1 - v.sqrt();
+        (mu + 0.5 * v).exp() * norm_cdf(d1) - self.k * norm_cdf(d2)
+    }
+}
+
+/// Control used when [`McConfig::controls`] is empty: the European call for
+/// calls and Asian calls, the European put for puts, none otherwise
+pub fn default_control(payoff: &Payoff) -> Option<Arc<dyn ControlVariate>> {
+    match *payoff {
+        // For European calls, the control is itself (perfect control)
+        // For Asian calls, the terminal call is strongly correlated
+        Payoff::EuropeanCall { k } | Payoff::AsianCall { k } => {
+            Some(Arc::new(EuropeanCallControl { k }))
+        }
+        Payoff::EuropeanPut { k } => Some(Arc::new(EuropeanPutControl { k })),
+        _ => None,
+    }
+}
+
+/// Controls of the engine for `cfg`, empty if it prices without any
+pub(crate) fn engine_controls(cfg: &McConfig) -> Vec<Arc<dyn ControlVariate>> {
+    // Under a biased scheme the analytic expectation would cancel the very
+    // bias being measured
+    if !cfg.use_control_variate || cfg.scheme != Scheme::Exact {
+        return Vec::new();
+    }
+    if cfg.controls.is_empty() {
+        default_control(&cfg.payoff).into_iter().collect()
+    } else {
+        cfg.controls.clone()
+    }
+}
+
+/// Values of `controls` on one path
+pub(crate) fn sample_controls(
+    controls: &[Arc<dyn ControlVariate>],
+    cfg: &McConfig,
+    dts: &[f64],
+    path: &[f64],
+) -> ControlValues {
+    let mut values = [0.0; MAX_CONTROLS];
+    for (value, control) in values.iter_mut().zip(controls) {
+        *value = control.sample(cfg, dts, path);
+    }
+    values
+}
+
+/// Add the payoff and control values of one sample to `moments`
+pub(crate) fn push_sample(moments: &mut PathMoments, payoff: f64, values: &ControlValues) {
+    let mut row = [0.0; MAX_CONTROLS + 1];
+    row[0] = payoff;
+    row[1..].copy_from_slice(values);
+    moments.push(&row);
+}
+
+/// Regression coefficients and expectations of the controls of a run
+pub(crate) struct ControlFit {
+    m: usize,
+    b: ControlValues,
+    expectations: ControlValues,
+}
+
+impl ControlFit {
+    /// Least-squares coefficients from the moments of `(Y, X_1, ..., X_m)`
+    ///
+    /// Controls with (numerically) no variance get a zero coefficient, as
+    /// does the null space of collinear controls.
+    pub(crate) fn new(
+        cfg: &McConfig,
+        controls: &[Arc<dyn ControlVariate>],
+        moments: &PathMoments,
+    ) -> Self {
+        let m = controls.len();
+        let mut expectations = [0.0; MAX_CONTROLS];
+        for (e, control) in expectations.iter_mut().zip(controls) {
+            *e = control.expectation(cfg);
+        }
+        let mut b = [0.0; MAX_CONTROLS];
+        let active: Vec<usize> = (1..=m)
+            .filter(|&i| moments.population_covariance(i, i) > 1e-10)
+            .collect();
+        if !active.is_empty() {
+            let k = active.len();
+            let sxx = DMatrix::from_fn(k, k, |i, j| {
+                moments.population_covariance(active[i], active[j])
+            });
+            let sxy = DVector::from_fn(k, |i, _| moments.population_covariance(active[i], 0));
+            let svd = sxx.svd(true, true);
+            let eps = 1e-12 * svd.singular_values.max();
+            if let Ok(solution) = svd.solve(&sxy, eps) {
+                for (i, &index) in active.iter().enumerate() {
+                    b[index - 1] = solution[i];
+                }
+            }
+        }
+        ControlFit { m, b, expectations }
+    }
+
+    /// `Y - bᵀ(X - E[X])` for one path
+    pub(crate) fn adjust(&self, payoff: f64, controls: &ControlValues) -> f64 {
+        let terms = self.b.iter().zip(controls).zip(&self.expectations);
+        payoff
+            - terms
+                .take(self.m)
+                .map(|((b, x), e)| b * (x - e))
+                .sum::<f64>()
+    }
+
+    /// Controlled mean and population variance from the moments of a run
+    pub(crate) fn moments(&self, moments: &PathMoments) -> (f64, f64) {
+        let mut mean = moments.mean(0);
+        let mut variance = moments.population_covariance(0, 0);
+        for i in 0..self.m {
+            mean -= self.b[i] * (moments.mean(i + 1) - self.expectations[i]);
+            variance -= 2.0 * self.b[i] * moments.population_covariance(0, i + 1);
+            for j in 0..self.m {
+                variance += self.b[i] * self.b[j] * moments.population_covariance(i + 1, j + 1);
+            }
+        }
+        (mean, variance.max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mc::mc_engine::mc_price_option_gbm;
+
+    #[test]
+    fn test_controls_are_unbiased_and_reduce_variance() {
+        let base = McConfig::builder()
+            .paths(40_000)
+            .steps(12)
+            .sigma(0.3)
+            .payoff(Payoff::AsianCall { k: 100.0 })
+            .antithetic(false);
+        let (_, plain_var) =
+            mc_price_option_gbm(&base.clone().control_variate(false).build().unwrap()).unwrap();
+        let exact = mc_price_option_gbm(
+            &base
+                .clone()
+                .paths(400_000)
+                .antithetic(true)
+                .control_variate(false)
+                .build()
+                .unwrap(),
+        )
+        .unwrap()
+        .0;
+
+        let controls: [Arc<dyn ControlVariate>; 4] = [
+            Arc::new(TerminalSpot),
+            Arc::new(EuropeanCallControl { k: 100.0 }),
+            Arc::new(EuropeanPutControl { k: 100.0 }),
+            Arc::new(DeltaHedge {
+                k: 100.0,
+                rebalance_every: 1,
+            }),
+        ];
+        for control in controls {
+            let cfg = base.clone().control_variate_with(control).build().unwrap();
+            let (price, variance) = mc_price_option_gbm(&cfg).unwrap();
+            assert!(variance < 0.9 * plain_var, "{} vs {}", variance, plain_var);
+            // About three standard errors of the weakest control
+            assert!((price - exact).abs() < 0.15, "{} vs {}", price, exact);
+        }
+
+        // Puts now have a parity control by default
+        let put = base.payoff(Payoff::EuropeanPut { k: 100.0 });
+        let (price, variance) = mc_price_option_gbm(&put.clone().build().unwrap()).unwrap();
+        let (_, plain_var) =
+            mc_price_option_gbm(&put.control_variate(false).build().unwrap()).unwrap();
+        let analytic = bs_analytic::bs_put_price(100.0, 100.0, 0.01, 0.3, 1.0);
+        assert!(variance < 1e-3 * plain_var);
+        assert!((price - analytic).abs() < 1e-6);
+    }
+    #[test]
+    fn test_regression_on_several_controls() {
+        let base = McConfig::builder()
+            .paths(40_000)
+            .steps(12)
+            .sigma(0.3)
+            .payoff(Payoff::AsianCall { k: 100.0 })
+            .antithetic(false);
+        let spot: Arc<dyn ControlVariate> = Arc::new(TerminalSpot);
+        let geometric: Arc<dyn ControlVariate> = Arc::new(GeometricAsianCall { k: 100.0 });
+        let price = |controls: &[&Arc<dyn ControlVariate>]| {
+            let cfg = controls
+                .iter()
+                .fold(base.clone(), |b, &c| b.control_variate_with(c.clone()))
+                .build()
+                .unwrap();
+            mc_price_option_gbm(&cfg).unwrap()
+        };
+        let (_, spot_var) = price(&[&spot]);
+        let (_, geometric_var) = price(&[&geometric]);
+        let (both, both_var) = price(&[&spot, &geometric]);
+        // The regression never does worse in-sample than either control
+        assert!(both_var < 0.01 * spot_var);
+        assert!(both_var < geometric_var);
+
+        // Collinear controls share a coefficient instead of failing
+        let call: Arc<dyn ControlVariate> = Arc::new(EuropeanCallControl { k: 100.0 });
+        let put: Arc<dyn ControlVariate> = Arc::new(EuropeanPutControl { k: 100.0 });
+        let (parity, _) = price(&[&call, &put, &spot, &geometric]);
+        assert!((parity - both).abs() < 0.01, "{} vs {}", parity, both);
+
+        // The geometric Asian expectation matches its simulated mean
+        let cfg = base.build().unwrap();
+        let dts = simulation_increments(&cfg);
+        let mut rng = crate::rng::seed_rng_from_u64(7);
+        let n = 50_000;
+        let mut path = Vec::new();
+        let mean = (0..n)
+            .map(|_| {
+                path.clear();
+                path.push(cfg.s0);
+                for &dt in &dts {
+                    let z = crate::rng::get_normal_draw(&mut rng);
+                    let s = path[path.len() - 1]
+                        * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * dt + cfg.sigma * dt.sqrt() * z)
+                            .exp();
+                    path.push(s);
+                }
+                geometric.sample(&cfg, &dts, &path)
+            })
+            .sum::<f64>()
+            / n as f64;
+        let expectation = geometric.expectation(&cfg);
+        assert!(
+            (mean - expectation).abs() < 0.1,
+            "{} vs {}",
+            mean,
+            expectation
+        );
+
+        let too_many = (0..=MAX_CONTROLS).fold(McConfig::builder(), |b, _| {
+            b.control_variate_with(spot.clone())
+        });
+        assert!(too_many.build().is_err());
+    }
+}
@@ -0,0 +1,384 @@
+// src/mc/convergence.rs
+//! Convergence-Controlled Pricing
+//!
+//! # Sequential Stopping Rule
+//!
+//! Instead of fixing the number of paths up front, paths are simulated in
+//! batches and the running standard error `s_n / √n` is checked after each
+//! batch. After `n` paths with sample standard deviation `s_n`, the number
+//! of paths needed to reach the target `ε` is estimated as
+//! ```text
+//! n* = (s_n / ε)²
+//! ```
+//! and the next batch aims for `n*` plus a 10% margin, so that typically one
+//! or two batches after the pilot suffice. The run stops when
+//! `s_n / √n ≤ ε` or when `max_paths` is exhausted. Each batch is
+//! accumulated with Welford updates and merged into the running moments
+//! ([`crate::mc::accumulators`]), so `s_n` stays accurate however many
+//! batches are added.
+//!
+//! # Pilot Batch
+//!
+//! The first batch has `cfg.paths` paths. It must be large enough for
+//! `s_n` to be a reliable estimate: stopping as soon as a noisy `s_n` dips
+//! below target biases the reported error downwards.
+//!
+//! # Reproducibility
+//!
+//! Path `i` draws block `i` of the engine's normal source whichever batch
+//! it falls in, so a run that stops after `n` paths gives the same estimate
+//! as a fixed run of `n` paths without control variates.
+//!
+//! # Batch Means
+//!
+//! [`mc_price_with_diagnostics`] splits the paths into `B` consecutive
+//! batches with means `m_b`. If the payoff has finite variance the batch
+//! means are approximately normal and
+//! ```text
+//! Var(m̄) ≈ s_m² / B,    s_m² = Σ (m_b - m̄)² / (B - 1)
+//! ```
+//! should agree with the path-level `s_n² / n`. A ratio of the two far from
+//! one, or strongly skewed batch means, flags an estimator whose CLT
+//! confidence interval should not be trusted yet (heavy tails, rare large
+//! payoffs, or too few paths).
+
+use crate::error::{SdeError, SdeResult};
+use crate::mc::accumulators::Moments;
+use crate::mc::mc_engine::{
+    engine_normal_source, for_each_block_path, priced_payoff, simulation_increments, with_scratch,
+    BlockBuffers, McConfig,
+};
+use crate::mc::payoff_stats::StreamingStats;
+use crate::parallel::prelude::*;
+
+/// Headroom on the projected number of paths
+const BATCH_MARGIN: f64 = 1.1;
+/// Two-sided 95% and 99% standard normal quantiles
+const Z_95: f64 = 1.959_963_984_540_054;
+const Z_99: f64 = 2.575_829_303_548_900_4;
+
+/// Outcome of [`mc_price_to_tolerance`]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ToleranceResult {
+    pub price: f64,
+    pub std_error: f64,
+    /// Paths simulated (blocks of the normal source, so antithetic pairs
+    /// count once)
+    pub paths_used: usize,
+    pub batches: usize,
+    /// Whether `std_error` reached the target within `max_paths`
+    pub converged: bool,
+}
+
+/// Price `cfg.payoff` by adding paths until the standard error is at most
+/// `target_stderr` or `max_paths` paths have been used
+///
+/// Draws follow the engine (`cfg.normal_source`, `cfg.use_antithetic`);
+/// control variates are not applied. A run that hits `max_paths` first is
+/// returned with `converged = false`.
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid configurations, a non-positive target,
+/// `max_paths < cfg.paths`, or non-finite estimates.
+pub fn mc_price_to_tolerance(
+    cfg: &McConfig,
+    target_stderr: f64,
+    max_paths: usize,
+) -> SdeResult<ToleranceResult> {
+    cfg.validate()?;
+    cfg.parallelism.install(|| {
+        if !(target_stderr.is_finite() && target_stderr > 0.0) {
+            return Err(SdeError::InvalidParameters {
+                parameter: "target_stderr".to_string(),
+                value: target_stderr,
+                constraint: "must be positive and finite".to_string(),
+            });
+        }
+        if max_paths < cfg.paths {
+            return Err(SdeError::InvalidConfiguration {
+                field: "max_paths".to_string(),
+                reason: format!("must be at least the pilot batch cfg.paths = {}", cfg.paths),
+            });
+        }
+
+        let sample = path_sampler(cfg);
+
+        let mut moments = Moments::new();
+        let mut batches = 0;
+        let mut batch = cfg.paths;
+        loop {
+            let n = moments.count() as usize;
+            moments.merge(batch_moments(cfg, &sample, n..n + batch));
+            let n = n + batch;
+            batches += 1;
+
+            let (price, variance) = (moments.mean(), moments.sample_variance());
+            let std_error = moments.variance_of_mean().sqrt();
+            if !price.is_finite() || !std_error.is_finite() {
+                return Err(SdeError::NumericalInstability {
+                    method: "Convergence-controlled Monte Carlo".to_string(),
+                    reason: format!("non-finite estimate after {} paths", n),
+                });
+            }
+            let converged = std_error <= target_stderr;
+            if converged || n >= max_paths {
+                return Ok(ToleranceResult {
+                    price,
+                    std_error,
+                    paths_used: n,
+                    batches,
+                    converged,
+                });
+            }
+
+            let needed =
+                (BATCH_MARGIN * variance / (target_stderr * target_stderr)).ceil() as usize;
+            batch = needed.saturating_sub(n).clamp(1, max_paths - n);
+        }
+    })
+}
+
+/// Batch layout of [`mc_price_with_diagnostics`]
+#[derive(Debug, Clone, Copy)]
+pub struct BatchMeansConfig {
+    /// Number of consecutive path batches, at least 2
+    pub batches: usize,
+}
+
+impl Default for BatchMeansConfig {
+    fn default() -> Self {
+        BatchMeansConfig { batches: 20 }
+    }
+}
+
+/// Two-sided confidence interval
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl ConfidenceInterval {
+    fn around(center: f64, half_width: f64) -> Self {
+        ConfidenceInterval {
+            lower: center - half_width,
+            upper: center + half_width,
+        }
+    }
+
+    pub fn half_width(&self) -> f64 {
+        0.5 * (self.upper - self.lower)
+    }
+
+    pub fn contains(&self, x: f64) -> bool {
+        self.lower <= x && x <= self.upper
+    }
+}
+
+/// Running estimate after a batch
+#[derive(Debug, Clone, Copy)]
+pub struct ConvergencePoint {
+    pub paths: usize,
+    pub mean: f64,
+    pub std_error: f64,
+}
+
+/// Price with batch-means diagnostics and CLT confidence intervals
+#[derive(Debug, Clone)]
+pub struct ConvergenceDiagnostics {
+    pub price: f64,
+    /// Path-level standard error `s_n / √n`
+    pub std_error: f64,
+    /// 95% and 99% CLT intervals from `std_error`
+    pub ci_95: ConfidenceInterval,
+    pub ci_99: ConfidenceInterval,
+    /// Mean of each batch, in path order
+    pub batch_means: Vec<f64>,
+    /// Sample variance of the batch means
+    pub batch_means_variance: f64,
+    /// Standard error implied by the batch means, `s_m / √B`
+    pub batch_std_error: f64,
+    /// `(batch_std_error / std_error)²`, close to 1 when the CLT applies
+    pub variance_ratio: f64,
+    /// Skewness of the batch means, close to 0 when the CLT applies
+    pub batch_skewness: f64,
+    /// Running estimate at the end of every batch
+    pub trace: Vec<ConvergencePoint>,
+}
+
+/// Price `cfg.payoff` over `cfg.paths` paths split into batches, reporting
+/// batch-means diagnostics, a convergence trace and confidence intervals
+///
+/// Draws match [`mc_price_to_tolerance`];
+/// control variates are not applied.
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid configurations, fewer than 2 batches,
+/// fewer paths than batches, or non-finite estimates.
+pub fn mc_price_with_diagnostics(
+    cfg: &McConfig,
+    batching: &BatchMeansConfig,
+) -> SdeResult<ConvergenceDiagnostics> {
+    cfg.validate()?;
+    cfg.parallelism.install(|| {
+        let b = batching.batches;
+        if b < 2 || b > cfg.paths {
+            return Err(SdeError::InvalidConfiguration {
+                field: "batches".to_string(),
+                reason: format!("must be between 2 and cfg.paths = {}", cfg.paths),
+            });
+        }
+
+        let sample = path_sampler(cfg);
+        let mut moments = Moments::new();
+        let mut batch_means = Vec::with_capacity(b);
+        let mut trace = Vec::with_capacity(b);
+        for batch in 0..b {
+            // Spread the remainder over the first batches
+            let size = cfg.paths / b + usize::from(batch < cfg.paths % b);
+            let n = moments.count() as usize;
+            let batch_stats = batch_moments(cfg, &sample, n..n + size);
+            batch_means.push(batch_stats.mean());
+            moments.merge(batch_stats);
+            trace.push(ConvergencePoint {
+                paths: n + size,
+                mean: moments.mean(),
+                std_error: moments.variance_of_mean().sqrt(),
+            });
+        }
+
+        let last = trace[b - 1];
+        let (price, std_error) = (last.mean, last.std_error);
+        if !price.is_finite() || !std_error.is_finite() {
+            return Err(SdeError::NumericalInstability {
+                method: "Batch-means Monte Carlo".to_string(),
+                reason: format!("non-finite estimate after {} paths", cfg.paths),
+            });
+        }
+
+        let mut stats = StreamingStats::new();
+        for &m in &batch_means {
+            stats.push(m);
+        }
+        let batch_means_variance = stats.std_dev().powi(2);
+        let batch_std_error = (batch_means_variance / b as f64).sqrt();
+        let variance_ratio = if std_error > 0.0 {
+            (batch_std_error / std_error).powi(2)
+        } else {
+            1.0
+        };
+
+        Ok(ConvergenceDiagnostics {
+            price,
+            std_error,
+            ci_95: ConfidenceInterval::around(price, Z_95 * std_error),
+            ci_99: ConfidenceInterval::around(price, Z_99 * std_error),
+            batch_means,
+            batch_means_variance,
+            batch_std_error,
+            variance_ratio,
+            batch_skewness: stats.skewness(),
+            trace,
+        })
+    })
+}
+
+/// Discounted payoff of block `i` of the engine's normal source, averaged
+/// over its samples (e.g. an antithetic pair)
+fn path_sampler(cfg: &McConfig) -> impl Fn(usize, &mut BlockBuffers) -> f64 + Sync + '_ {
+    let grid = simulation_increments(cfg);
+    let payoff = priced_payoff(cfg);
+    let discount = (-cfg.r * cfg.t).exp();
+    let source = engine_normal_source(cfg);
+    move |i, buffers| {
+        let mut sum = 0.0;
+        for_each_block_path(cfg, &grid, &source, i, buffers, |path| {
+            sum += payoff.calculate(path);
+        });
+        discount * sum / source.block_size() as f64
+    }
+}
+
+/// Welford moments of `sample` over the paths in `range`
+fn batch_moments(
+    cfg: &McConfig,
+    sample: &(impl Fn(usize, &mut BlockBuffers) -> f64 + Sync),
+    range: std::ops::Range<usize>,
+) -> Moments {
+    range
+        .into_par_iter()
+        .with_min_len(cfg.chunk_size.unwrap_or(1))
+        .map(|i| with_scratch(|buffers| sample(i, buffers)))
+        .fold(Moments::new, |mut acc, y| {
+            acc.push(y);
+            acc
+        })
+        .reduce(Moments::new, |mut a, b| {
+            a.merge(b);
+            a
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::bs_analytic;
+    use crate::mc::payoffs::Payoff;
+
+    #[test]
+    fn test_stops_at_target_standard_error() {
+        let cfg = McConfig {
+            paths: 5_000,
+            payoff: Payoff::EuropeanCall { k: 100.0 },
+            ..Default::default()
+        };
+        let result = mc_price_to_tolerance(&cfg, 0.02, 1_000_000).expect("Valid configuration");
+        assert!(result.converged);
+        assert!(result.std_error <= 0.02);
+        assert!(result.batches >= 2 && result.paths_used < 1_000_000);
+        let analytic = bs_analytic::bs_call_price(100.0, 100.0, 0.01, 0.2, 1.0);
+        assert!((result.price - analytic).abs() < 4.0 * result.std_error);
+
+        // Same paths as a fixed run of that size
+        let fixed = McConfig {
+            paths: result.paths_used,
+            use_control_variate: false,
+            ..cfg.clone()
+        };
+        let (price, _) = crate::mc::mc_engine::mc_price_option_gbm(&fixed).expect("Valid");
+        assert!((price - result.price).abs() < 1e-9);
+
+        // An unreachable target runs out of paths
+        let capped = mc_price_to_tolerance(&cfg, 1e-6, 20_000).expect("Valid configuration");
+        assert!(!capped.converged);
+        assert_eq!(capped.paths_used, 20_000);
+    }
+
+    #[test]
+    fn test_batch_means_diagnostics() {
+        let cfg = McConfig {
+            paths: 40_003,
+            payoff: Payoff::EuropeanCall { k: 100.0 },
+            ..Default::default()
+        };
+        let diag = mc_price_with_diagnostics(&cfg, &BatchMeansConfig::default())
+            .expect("Valid configuration");
+        assert_eq!(diag.batch_means.len(), 20);
+        assert_eq!(diag.trace.last().unwrap().paths, 40_003);
+        assert!(diag.trace.windows(2).all(|w| w[1].paths > w[0].paths));
+
+        // Batch means agree with the path-level error and the analytic price
+        assert!(diag.variance_ratio > 0.3 && diag.variance_ratio < 3.0);
+        let analytic = bs_analytic::bs_call_price(100.0, 100.0, 0.01, 0.2, 1.0);
+        assert!(diag.ci_99.contains(analytic));
+        assert!(diag.ci_99.half_width() > diag.ci_95.half_width());
+        let mean_of_batches: f64 = diag.batch_means.iter().sum::<f64>() / 20.0;
+        assert!((mean_of_batches - diag.price).abs() < 1e-3);
+
+        let one = BatchMeansConfig { batches: 1 };
+        assert!(mc_price_with_diagnostics(&cfg, &one).is_err());
+    }
+}
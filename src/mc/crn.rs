@@ -0,0 +1,233 @@
+// src/mc/crn.rs
+//! Common Random Numbers for Bump-and-Reprice
+//!
+//! # Motivation
+//!
+//! A sensitivity estimated by repricing with a bumped input,
+//! ```text
+//! ∂V/∂p ≈ [V(p + h) - V(p - h)] / (2h)
+//! ```
+//! is only usable if both prices see the same random inputs: with
+//! independent draws the variance of the difference is `O(1/h²)` larger
+//! than with common ones.
+//!
+//! # Design
+//!
+//! Every path draws from its own stream keyed by `(seed, path)` (see
+//! [`SeedStrategy`]), and a path consumes its stream in an order fixed by
+//! the step count alone. A [`CrnContext`] pins the plan — path count, step
+//! count, seed, seed strategy and antithetic pairing — so every pricing
+//! call made through it replays the same draws, whatever the market or
+//! model parameters:
+//! - [`CrnContext::price_gbm`] runs the GBM engine on a configuration with
+//!   the plan applied, so spot, rate, volatility and payoff may change
+//! - [`CrnContext::price_heston`] runs the Heston simulation behind
+//!   [`HestonGreeks`](crate::mc::heston_greeks::HestonGreeks), so the
+//!   parameters and the payoff may change
+//!
+//! Unlike [`PricingSession`](crate::mc::session::PricingSession), which
+//! caches the GBM draws in memory, the context regenerates them on each
+//! call and holds no state beyond the plan.
+
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::mc::heston_greeks::{crn_scenario_sums, HestonGreeksConfig};
+use crate::mc::mc_engine::{mc_price_option_gbm, McConfig};
+use crate::mc::path_failures::PathFailurePolicy;
+use crate::mc::payoffs::Payoff;
+use crate::models::heston::{FellerPolicy, Heston, HestonParams, HestonScheme};
+use crate::rng::SeedStrategy;
+
+/// Fixed simulation plan shared by repeated pricing calls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CrnContext {
+    pub paths: usize,
+    pub steps: usize,
+    pub seed: u64,
+    pub seed_strategy: SeedStrategy,
+    pub use_antithetic: bool,
+}
+
+impl CrnContext {
+    /// Plan with hash-keyed streams and antithetic pairing
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError` for zero paths or steps.
+    pub fn new(paths: usize, steps: usize, seed: u64) -> SdeResult<Self> {
+        let context = CrnContext {
+            paths,
+            steps,
+            seed,
+            seed_strategy: SeedStrategy::Hashed,
+            use_antithetic: true,
+        };
+        context.validate()?;
+        Ok(context)
+    }
+
+    /// The plan of an engine configuration
+    pub fn from_config(cfg: &McConfig) -> Self {
+        CrnContext {
+            paths: cfg.paths,
+            steps: cfg.steps,
+            seed: cfg.seed,
+            seed_strategy: cfg.seed_strategy,
+            use_antithetic: cfg.use_antithetic,
+        }
+    }
+
+    /// Validate path and step counts
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_paths(self.paths)?;
+        validate_steps(self.steps)?;
+        Ok(())
+    }
+
+    /// `cfg` with this plan
+    pub fn apply(&self, cfg: &McConfig) -> McConfig {
+        McConfig {
+            paths: self.paths,
+            steps: self.steps,
+            seed: self.seed,
+            seed_strategy: self.seed_strategy,
+            use_antithetic: self.use_antithetic,
+            ..cfg.clone()
+        }
+    }
+
+    /// GBM engine price of `cfg` on this plan's draws
+    ///
+    /// Returns `(price, variance)` like [`mc_price_option_gbm`]. A custom
+    /// `normal_source` or `deterministic` streams are kept: both are fixed
+    /// by the plan too.
+    ///
+    /// # Errors
+    ///
+    /// Returns the engine's errors.
+    pub fn price_gbm(&self, cfg: &McConfig) -> SdeResult<(f64, f64)> {
+        self.validate()?;
+        mc_price_option_gbm(&self.apply(cfg))
+    }
+
+    /// Heston settings with this plan, for pricing `payoff` at maturity `t`
+    ///
+    /// Passing them to
+    /// [`HestonGreeks::compute`](crate::mc::heston_greeks::HestonGreeks::compute)
+    /// gives Greeks on the same draws as [`price_heston`](Self::price_heston).
+    pub fn heston_config(&self, payoff: &Payoff, t: f64) -> HestonGreeksConfig {
+        HestonGreeksConfig {
+            paths: self.paths,
+            steps: self.steps,
+            t,
+            seed: self.seed,
+            seed_strategy: self.seed_strategy,
+            payoff: payoff.clone(),
+            use_antithetic: self.use_antithetic,
+            failure_policy: PathFailurePolicy::Fail,
+            ..Default::default()
+        }
+    }
+
+    /// Heston price of `payoff` at maturity `t` on this plan's draws
+    ///
+    /// The Feller condition is not enforced, so bumps may cross it. Returns
+    /// `(price, variance of the estimate)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError` for invalid parameters or settings, and for the
+    /// first path on which the scheme fails.
+    pub fn price_heston(
+        &self,
+        params: HestonParams,
+        scheme: HestonScheme,
+        payoff: &Payoff,
+        t: f64,
+    ) -> SdeResult<(f64, f64)> {
+        let cfg = self.heston_config(payoff, t);
+        cfg.validate()?;
+        let model = Heston::new_with_policy(params, scheme, FellerPolicy::Ignore)?;
+        let (sums, _) = crn_scenario_sums(
+            std::slice::from_ref(&model),
+            &cfg,
+            std::slice::from_ref(payoff),
+        )?;
+
+        let n = self.paths as f64;
+        let discount = (-params.r * t).exp();
+        let mean = sums[0] / n;
+        let price = discount * mean;
+        let variance = if self.paths > 1 {
+            discount * discount * ((sums[1] - n * mean * mean) / (n - 1.0)).max(0.0) / n
+        } else {
+            0.0
+        };
+        if !price.is_finite() {
+            return Err(SdeError::NumericalInstability {
+                method: "Heston CRN pricing".to_string(),
+                reason: format!("non-finite price {}", price),
+            });
+        }
+        Ok((price, variance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::bs_analytic::bs_call_delta;
+    use crate::mc::heston_greeks::HestonGreeks;
+
+    #[test]
+    fn test_bumped_reprices_share_draws() {
+        let ctx = CrnContext::new(20_000, 1, 11).unwrap();
+        let cfg = McConfig {
+            paths: 1,
+            use_control_variate: false,
+            payoff: Payoff::EuropeanCall { k: 100.0 },
+            ..Default::default()
+        };
+        let h = 0.5;
+        let bumped = |s0: f64| ctx.price_gbm(&McConfig { s0, ..cfg.clone() }).unwrap().0;
+        let delta = (bumped(100.0 + h) - bumped(100.0 - h)) / (2.0 * h);
+        let exact = bs_call_delta(100.0, 100.0, cfg.r, cfg.sigma, cfg.t);
+        assert!((delta - exact).abs() < 5e-3, "{} vs {}", delta, exact);
+        assert_eq!(CrnContext::from_config(&ctx.apply(&cfg)), ctx);
+
+        // Heston: the context reproduces the Greeks' central differences
+        let params = HestonParams {
+            s0: 100.0,
+            v0: 0.04,
+            r: 0.02,
+            kappa: 1.5,
+            theta: 0.04,
+            xi: 0.5,
+            rho: -0.7,
+        };
+        let ctx = CrnContext::new(5_000, 20, 3).unwrap();
+        let payoff = Payoff::EuropeanCall { k: 100.0 };
+        let scheme = HestonScheme::FullTruncationEuler;
+        let price = |params| ctx.price_heston(params, scheme, &payoff, 1.0).unwrap();
+        let (base, variance) = price(params);
+        assert_eq!(price(params).0, base);
+        assert!(variance > 0.0);
+
+        let report = HestonGreeks::new(params, scheme)
+            .unwrap()
+            .compute(&ctx.heston_config(&payoff, 1.0))
+            .unwrap();
+        assert!((report.price - base).abs() < 1e-10);
+        let h = 0.01 * params.v0;
+        let up = price(HestonParams {
+            v0: params.v0 + h,
+            ..params
+        });
+        let down = price(HestonParams {
+            v0: params.v0 - h,
+            ..params
+        });
+        let vega_v0 = (up.0 - down.0) / (2.0 * h);
+        assert!((vega_v0 - report.vega_v0).abs() < 1e-6 * report.vega_v0.abs());
+    }
+}
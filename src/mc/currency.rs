@@ -0,0 +1,172 @@
+// src/mc/currency.rs
+//! Payoff Currencies and FX Conversion
+//!
+//! # Currencies
+//!
+//! Every instrument pays in a [`Currency`], identified by its ISO 4217
+//! code. A book mixing currencies reports in one currency of its own, so
+//! each value and Greek is converted before it is aggregated:
+//! ```text
+//! V_book = Σ_j X(ccy_j → ccy_book) V_j
+//! ```
+//!
+//! # Conversion Hook
+//!
+//! [`FxConverter`] supplies the rate `X(from → to)`, the amount of `to`
+//! paid for one unit of `from`. [`FxRates`] quotes every currency against
+//! one base and triangulates through it:
+//! ```text
+//! X(from → to) = X(from → base) / X(to → base)
+//! ```
+//! Conversion happens at today's rates; the FX rate itself is not
+//! simulated, so a foreign position carries no FX risk in the Greeks.
+
+use crate::error::{validation::*, SdeError, SdeResult};
+use std::fmt;
+
+/// ISO 4217 currency code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub const USD: Currency = Currency(*b"USD");
+    pub const EUR: Currency = Currency(*b"EUR");
+    pub const GBP: Currency = Currency(*b"GBP");
+    pub const JPY: Currency = Currency(*b"JPY");
+    pub const CHF: Currency = Currency(*b"CHF");
+
+    /// Currency from a three-letter upper-case code such as `"USD"`
+    pub fn new(code: &str) -> SdeResult<Self> {
+        match code.as_bytes() {
+            &[a, b, c] if [a, b, c].iter().all(u8::is_ascii_uppercase) => Ok(Currency([a, b, c])),
+            _ => Err(SdeError::InvalidConfiguration {
+                field: "currency".to_string(),
+                reason: format!("'{}' is not a three-letter upper-case code", code),
+            }),
+        }
+    }
+
+    /// The three-letter code
+    pub fn code(&self) -> &str {
+        std::str::from_utf8(&self.0).expect("codes are ASCII")
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Currency::USD
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// An amount tagged with its currency
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CurrencyAmount {
+    pub currency: Currency,
+    pub amount: f64,
+}
+
+/// Source of FX rates for converting results between currencies
+pub trait FxConverter: Send + Sync {
+    /// Units of `to` per unit of `from`
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError` if the pair cannot be quoted.
+    fn fx_rate(&self, from: Currency, to: Currency) -> SdeResult<f64>;
+
+    /// `amount` of `from` expressed in `to`
+    ///
+    /// # Errors
+    ///
+    /// Same as [`fx_rate`](Self::fx_rate).
+    fn convert(&self, amount: f64, from: Currency, to: Currency) -> SdeResult<f64> {
+        if from == to {
+            return Ok(amount);
+        }
+        Ok(amount * self.fx_rate(from, to)?)
+    }
+}
+
+/// Spot FX rates against a single base currency
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FxRates {
+    pub base: Currency,
+    /// Units of `base` per unit of each quoted currency
+    pub quotes: Vec<(Currency, f64)>,
+}
+
+impl FxRates {
+    /// Rates with no quotes besides the base itself
+    pub fn new(base: Currency) -> Self {
+        FxRates {
+            base,
+            quotes: Vec::new(),
+        }
+    }
+
+    /// Add or replace the quote of `currency` in units of the base
+    pub fn with_quote(mut self, currency: Currency, rate: f64) -> SdeResult<Self> {
+        validate_positive("fx_rate", rate)?;
+        self.quotes.retain(|&(c, _)| c != currency);
+        self.quotes.push((currency, rate));
+        Ok(self)
+    }
+
+    /// Units of the base per unit of `currency`
+    fn in_base(&self, currency: Currency) -> SdeResult<f64> {
+        if currency == self.base {
+            return Ok(1.0);
+        }
+        self.quotes
+            .iter()
+            .find(|&&(c, _)| c == currency)
+            .map(|&(_, rate)| rate)
+            .ok_or_else(|| SdeError::InvalidConfiguration {
+                field: "fx_rates".to_string(),
+                reason: format!("no quote for {} against {}", currency, self.base),
+            })
+    }
+}
+
+impl FxConverter for FxRates {
+    fn fx_rate(&self, from: Currency, to: Currency) -> SdeResult<f64> {
+        Ok(self.in_base(from)? / self.in_base(to)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_and_triangulated_rates() {
+        assert_eq!(Currency::new("EUR").unwrap(), Currency::EUR);
+        assert_eq!(Currency::GBP.to_string(), "GBP");
+        assert!(Currency::new("eur").is_err());
+        assert!(Currency::new("EURO").is_err());
+
+        let fx = FxRates::new(Currency::USD)
+            .with_quote(Currency::EUR, 1.10)
+            .unwrap()
+            .with_quote(Currency::GBP, 1.25)
+            .unwrap();
+        assert!((fx.fx_rate(Currency::EUR, Currency::USD).unwrap() - 1.10).abs() < 1e-15);
+        assert!((fx.fx_rate(Currency::USD, Currency::EUR).unwrap() - 1.0 / 1.10).abs() < 1e-15);
+        let eur_gbp = fx.fx_rate(Currency::EUR, Currency::GBP).unwrap();
+        assert!((eur_gbp - 1.10 / 1.25).abs() < 1e-15);
+        assert_eq!(fx.convert(5.0, Currency::JPY, Currency::JPY).unwrap(), 5.0);
+        assert!(fx.fx_rate(Currency::JPY, Currency::USD).is_err());
+        assert!(FxRates::new(Currency::USD)
+            .with_quote(Currency::EUR, 0.0)
+            .is_err());
+    }
+}
@@ -0,0 +1,372 @@
+// src/mc/equity_rates.rs
+//! Equity-Rates Hybrid Monte Carlo with Pathwise Discounting
+//!
+//! # Pathwise Discounting
+//!
+//! With the bank account as numeraire, a claim paying `X` at `T` is worth
+//! ```text
+//! V = E[D(T) X],   D(T) = exp(-∫₀ᵀ r_u du)
+//! ```
+//! Each path of a [`GbmHullWhite`] model carries its own deflator, so a
+//! payoff correlated with the rates is discounted consistently. Pricing
+//! the same claim with `e^{-R(T)T}` at today's zero rate `R(T)` ignores
+//! `Cov(D(T), X)`, which grows with the maturity, the rate volatility and
+//! the equity-rate correlation.
+//!
+//! # Numeraires
+//!
+//! Under another [`Numeraire`] `N` the same value is
+//! `V = E^N[(N_0 / N_T) X]`: the paths are simulated with the drift
+//! adjustment of that measure and the deflator becomes `N_0 / N_t`, which
+//! is `D(t)` for the bank account. Payoffs written as `deflator × cashflow`
+//! are therefore priced unchanged under every numeraire.
+//!
+//! # Observations
+//!
+//! [`mc_price_hybrid`] steps the model between the observation dates and
+//! hands the payoff every [`HybridObservation`], so the payoff can
+//! discount cashflows at any of them (a payoff on several dates sums its
+//! deflated cashflows). [`mc_price_equity_option`] is the European case,
+//! with [`GbmHullWhite::call_price`] as its closed form.
+
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::mc::accumulators::Moments;
+use crate::mc::compound::OptionType;
+use crate::mc::numeraire::{Numeraire, MEASURE_CHANGE_MAX_STEP};
+use crate::models::gbm_hull_white::GbmHullWhite;
+use crate::parallel::prelude::*;
+use crate::rng::SeedStrategy;
+
+/// Simulation settings of the hybrid pricers
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HybridConfig {
+    pub paths: usize,
+    pub seed: u64,
+    /// Derivation of the per-path streams from `seed`
+    pub seed_strategy: SeedStrategy,
+}
+
+impl Default for HybridConfig {
+    fn default() -> Self {
+        HybridConfig {
+            paths: 20_000,
+            seed: 12345,
+            seed_strategy: SeedStrategy::Hashed,
+        }
+    }
+}
+
+/// State of a hybrid path at an observation date
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HybridObservation {
+    pub time: f64,
+    pub spot: f64,
+    pub short_rate: f64,
+    /// Hull-White factor `x_t = r_t - α(t)`
+    pub factor: f64,
+    /// `N_0 / N_t`; `D(t) = exp(-∫₀ᵗ r du)` under the bank account
+    pub deflator: f64,
+}
+
+/// Monte Carlo value of a hybrid claim observed on the increasing `dates`,
+/// simulated under the measure of `numeraire`
+///
+/// `payoff` maps a path's observations to its deflated value, e.g.
+/// `deflator × max(S_T - K, 0)`. Path `i` draws from its `seed_strategy`
+/// stream. Returns `(price, variance of the estimate)`.
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid dates, numeraire or path count, a date
+/// past the numeraire's horizon, or a non-finite price.
+pub fn mc_price_hybrid(
+    model: &GbmHullWhite,
+    numeraire: &Numeraire,
+    dates: &[f64],
+    cfg: &HybridConfig,
+    payoff: impl Fn(&[HybridObservation]) -> f64 + Sync,
+) -> SdeResult<(f64, f64)> {
+    validate_paths(cfg.paths)?;
+    if dates.is_empty() {
+        return Err(SdeError::InvalidConfiguration {
+            field: "dates".to_string(),
+            reason: "needs at least one observation date".to_string(),
+        });
+    }
+    let mut prev = 0.0;
+    for &t in dates {
+        validate_finite("date", t)?;
+        if t <= prev {
+            return Err(SdeError::InvalidParameters {
+                parameter: "date".to_string(),
+                value: t,
+                constraint: "observation dates must be positive and strictly increasing"
+                    .to_string(),
+            });
+        }
+        prev = t;
+    }
+    numeraire.validate()?;
+    if prev > numeraire.horizon() {
+        return Err(SdeError::InvalidParameters {
+            parameter: "date".to_string(),
+            value: prev,
+            constraint: format!(
+                "observation dates must not exceed the numeraire horizon {}",
+                numeraire.horizon()
+            ),
+        });
+    }
+
+    let rates = &model.rates;
+    let initial_numeraire = numeraire.initial_value(rates);
+    let max_step = match numeraire {
+        Numeraire::BankAccount => f64::INFINITY,
+        _ => MEASURE_CHANGE_MAX_STEP,
+    };
+    let moments = (0..cfg.paths)
+        .into_par_iter()
+        .map(|i| {
+            let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+            let (mut state, mut bank_deflator, mut t) = (model.initial_state(), 1.0, 0.0);
+            let observations: Vec<HybridObservation> = dates
+                .iter()
+                .map(|&date| {
+                    let substeps = ((date - t) / max_step).ceil().max(1.0) as usize;
+                    let dt = (date - t) / substeps as f64;
+                    for _ in 0..substeps {
+                        let vol = numeraire.volatility(rates, t + 0.5 * dt, state[1]);
+                        bank_deflator *= model.step_under(&mut state, t, dt, vol, &mut rng);
+                        t += dt;
+                    }
+                    t = date;
+                    HybridObservation {
+                        time: t,
+                        spot: state[0],
+                        short_rate: rates.short_rate(state[1], t),
+                        factor: state[1],
+                        deflator: initial_numeraire
+                            / numeraire.value(rates, t, state[1], bank_deflator),
+                    }
+                })
+                .collect();
+            payoff(&observations)
+        })
+        .fold(Moments::new, |mut acc, y| {
+            acc.push(y);
+            acc
+        })
+        .reduce(Moments::new, |mut a, b| {
+            a.merge(b);
+            a
+        });
+
+    let (price, variance) = (moments.mean(), moments.variance_of_mean());
+    if !price.is_finite() {
+        return Err(SdeError::NumericalInstability {
+            method: "Equity-rates hybrid pricing".to_string(),
+            reason: format!("non-finite price {}", price),
+        });
+    }
+    Ok((price, variance))
+}
+
+/// Monte Carlo price of a European option on the stock, discounted
+/// pathwise in units of `numeraire`
+///
+/// Returns `(price, variance of the estimate)`.
+///
+/// # Errors
+///
+/// Returns `SdeError` for an invalid strike, expiry, numeraire or path
+/// count, or a non-finite price.
+pub fn mc_price_equity_option(
+    model: &GbmHullWhite,
+    numeraire: &Numeraire,
+    option_type: OptionType,
+    strike: f64,
+    expiry: f64,
+    cfg: &HybridConfig,
+) -> SdeResult<(f64, f64)> {
+    validate_positive("strike", strike)?;
+    validate_positive("expiry", expiry)?;
+    mc_price_hybrid(model, numeraire, &[expiry], cfg, |obs| {
+        let moneyness = obs[0].spot - strike;
+        obs[0].deflator
+            * match option_type {
+                OptionType::Call => moneyness.max(0.0),
+                OptionType::Put => (-moneyness).max(0.0),
+            }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::bs_analytic::bs_call_price;
+    use crate::analytics::hull_white_analytic::{hw_swaption_price, Swaption, SwaptionType};
+    use crate::models::gbm_hull_white::GbmHullWhiteParams;
+    use crate::models::hull_white::{HullWhite, HullWhiteParams, YieldCurve};
+
+    #[test]
+    fn test_pathwise_discounting_matches_hybrid_closed_form() {
+        let rates = HullWhite::new(
+            HullWhiteParams {
+                a: 0.05,
+                sigma: 0.015,
+            },
+            YieldCurve::from_zero_rates(&[1.0, 5.0, 20.0], &[0.02, 0.03, 0.035]).unwrap(),
+        )
+        .unwrap();
+        let model = GbmHullWhite::new(
+            GbmHullWhiteParams {
+                s0: 100.0,
+                q: 0.01,
+                sigma: 0.2,
+                rho: 0.5,
+            },
+            rates,
+        )
+        .unwrap();
+        let expiry: f64 = 15.0;
+        let bank = Numeraire::BankAccount;
+        let cfg = HybridConfig {
+            paths: 40_000,
+            seed: 5,
+            ..Default::default()
+        };
+        let option_cfg = HybridConfig { seed: 7, ..cfg };
+
+        // Deflated stock and bond are martingales over several steps
+        let dates: Vec<f64> = (1..=5).map(|j| 3.0 * j as f64).collect();
+        let (stock, var) = mc_price_hybrid(&model, &bank, &dates, &cfg, |obs| {
+            obs[4].deflator * obs[4].spot
+        })
+        .unwrap();
+        let forward_value = 100.0 * (-0.01 * expiry).exp();
+        assert!((stock - forward_value).abs() < 4.0 * var.sqrt());
+        let (bond, var) =
+            mc_price_hybrid(&model, &bank, &dates, &cfg, |obs| obs[4].deflator).unwrap();
+        assert!((bond - model.rates.curve.discount(expiry)).abs() < 4.0 * var.sqrt());
+
+        for strike in [60.0, 100.0, 160.0] {
+            let (call, var) = mc_price_equity_option(
+                &model,
+                &bank,
+                OptionType::Call,
+                strike,
+                expiry,
+                &option_cfg,
+            )
+            .unwrap();
+            let exact = model.call_price(strike, expiry);
+            assert!(
+                (call - exact).abs() < 4.0 * var.sqrt(),
+                "{} vs {}",
+                call,
+                exact
+            );
+        }
+        let (put, var) =
+            mc_price_equity_option(&model, &bank, OptionType::Put, 100.0, expiry, &option_cfg)
+                .unwrap();
+        assert!((put - model.put_price(100.0, expiry)).abs() < 4.0 * var.sqrt());
+
+        // A deterministic rate at the zero rate misprices the long-dated call
+        let (call, var) =
+            mc_price_equity_option(&model, &bank, OptionType::Call, 100.0, expiry, &option_cfg)
+                .unwrap();
+        let flat = bs_call_price(
+            forward_value,
+            100.0,
+            model.rates.curve.zero_rate(expiry),
+            0.2,
+            expiry,
+        );
+        assert!(call - flat > 10.0 * var.sqrt(), "{} vs {}", call, flat);
+
+        assert!(mc_price_hybrid(&model, &bank, &[2.0, 1.0], &cfg, |_| 0.0).is_err());
+    }
+
+    #[test]
+    fn test_prices_agree_across_numeraires() {
+        let rates = HullWhite::new(
+            HullWhiteParams {
+                a: 0.08,
+                sigma: 0.012,
+            },
+            YieldCurve::from_zero_rates(&[1.0, 5.0, 10.0], &[0.025, 0.03, 0.032]).unwrap(),
+        )
+        .unwrap();
+        let model = GbmHullWhite::new(
+            GbmHullWhiteParams {
+                s0: 100.0,
+                q: 0.0,
+                sigma: 0.25,
+                rho: -0.4,
+            },
+            rates,
+        )
+        .unwrap();
+        let swaption = Swaption {
+            kind: SwaptionType::Payer,
+            expiry: 5.0,
+            maturity: 10.0,
+            payments_per_year: 1,
+            strike: 0.033,
+            notional: 1.0,
+        };
+        let numeraires = [
+            Numeraire::BankAccount,
+            Numeraire::ForwardBond { maturity: 10.0 },
+            Numeraire::swap_annuity(&swaption),
+        ];
+        let exact_call = model.call_price(100.0, 5.0);
+        let cfg = HybridConfig {
+            seed: 3,
+            ..Default::default()
+        };
+        let exact_swaption = hw_swaption_price(&model.rates, &swaption).unwrap();
+        for numeraire in &numeraires {
+            let (call, var) =
+                mc_price_equity_option(&model, numeraire, OptionType::Call, 100.0, 5.0, &cfg)
+                    .unwrap();
+            assert!(
+                (call - exact_call).abs() < 4.0 * var.sqrt(),
+                "{:?}: {} vs {}",
+                numeraire,
+                call,
+                exact_call
+            );
+
+            let (price, var) = mc_price_hybrid(
+                &model,
+                numeraire,
+                &[5.0],
+                &HybridConfig { seed: 11, ..cfg },
+                |obs| {
+                    let bond = |d| model.rates.bond_price(5.0, d, obs[0].factor);
+                    obs[0].deflator * swaption.payer_swap_value(5.0, bond).max(0.0)
+                },
+            )
+            .unwrap();
+            assert!(
+                (price - exact_swaption).abs() < 4.0 * var.sqrt(),
+                "{:?}: {} vs {}",
+                numeraire,
+                price,
+                exact_swaption
+            );
+        }
+
+        // The annuity cannot serve past its first payment date
+        let annuity = &numeraires[2];
+        assert!(mc_price_hybrid(&model, annuity, &[7.0], &cfg, |_| 0.0).is_err());
+        let broken = Numeraire::Annuity {
+            payment_dates: vec![1.0, 2.0],
+            accruals: vec![1.0],
+        };
+        assert!(broken.validate().is_err());
+    }
+}
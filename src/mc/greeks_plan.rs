@@ -0,0 +1,317 @@
+// src/mc/greeks_plan.rs
+//! Greeks Execution Planning
+//!
+//! # Overview
+//!
+//! [`GreeksConfig`] flags select which Greeks to compute. A [`GreeksPlan`]
+//! turns the selection into groups of Greeks that can share one simulation
+//! (common random numbers, CRN), then runs the groups concurrently on the
+//! Rayon pool and reports per-group timing.
+//!
+//! # CRN Groups
+//!
+//! - **Pathwise**: Δ, ν and ρ of a European call all derive from the same
+//!   terminal price `S_T`, so they are accumulated in one pass:
+//!   ```text
+//!   δ_path = 1_{S_T > K} S_T/S₀
+//!   ν_path = 1_{S_T > K} S_T (W_T - σT)
+//!   ρ_path = 1_{S_T > K} S_T T - T (S_T - K)⁺
+//!   ```
+//! - **Spot bump**: Γ by central difference of pathwise Δ at `S₀ ± ε`, with
+//!   both bumps driven by the same draw.
+//! - **Vibrato**: Δ and Γ together from one vibrato pass (used when
+//!   `greek_method` is [`GreekMethod::Vibrato`]).
+//!
+//! Groups are independent and are executed with `par_iter`, so a Γ bump
+//! group runs alongside the pathwise group instead of after it.
+
+use crate::error::{SdeError, SdeResult};
+use crate::math_utils::Timer;
+use crate::mc::mc_engine::{
+    mc_gamma_european_call_gbm_finite_diff_batched, GreekMethod, GreeksConfig, McConfig,
+};
+use crate::mc::payoffs::Payoff;
+use crate::mc::vibrato;
+use crate::parallel::prelude::*;
+use crate::rng;
+
+/// A set of Greeks computed from one shared simulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrnGroup {
+    /// Pathwise Δ/ν/ρ from a single pass over terminal prices
+    Pathwise(GreeksConfig),
+    /// Γ by spot bump with common random numbers
+    SpotBump,
+    /// Vibrato Δ and/or Γ from one pass
+    Vibrato(GreeksConfig),
+}
+
+impl CrnGroup {
+    /// Greeks produced by this group
+    pub fn greeks(&self) -> GreeksConfig {
+        match self {
+            CrnGroup::Pathwise(g) | CrnGroup::Vibrato(g) => *g,
+            CrnGroup::SpotBump => GreeksConfig::GAMMA,
+        }
+    }
+}
+
+/// Execution plan derived from `McConfig::greeks`
+#[derive(Debug, Clone)]
+pub struct GreeksPlan {
+    groups: Vec<CrnGroup>,
+}
+
+/// Wall-clock time spent on one CRN group
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GreekTiming {
+    pub greeks: GreeksConfig,
+    pub elapsed_ms: f64,
+}
+
+/// Greeks requested by the plan, with timing per group
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GreeksReport {
+    pub delta: Option<f64>,
+    pub gamma: Option<f64>,
+    pub vega: Option<f64>,
+    pub rho: Option<f64>,
+    pub timings: Vec<GreekTiming>,
+}
+
+impl GreeksReport {
+    /// Time spent on the group that produced `greek`
+    ///
+    /// Greeks sharing a CRN group share its timing.
+    pub fn elapsed_ms_for(&self, greek: GreeksConfig) -> Option<f64> {
+        self.timings
+            .iter()
+            .find(|t| t.greeks.contains(greek))
+            .map(|t| t.elapsed_ms)
+    }
+}
+
+impl GreeksPlan {
+    /// Plan the CRN groups for the Greeks selected in `cfg`
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::UnsupportedOperation` if a requested Greek has no
+    /// estimator for the configured payoff and method.
+    pub fn from_config(cfg: &McConfig) -> SdeResult<Self> {
+        let requested = cfg.greeks;
+        let is_call = matches!(cfg.payoff, Payoff::EuropeanCall { .. });
+        let mut groups = Vec::new();
+
+        let spot_greeks = requested & (GreeksConfig::DELTA | GreeksConfig::GAMMA);
+        let mut pathwise = requested & (GreeksConfig::VEGA | GreeksConfig::RHO);
+        match cfg.greek_method {
+            GreekMethod::Vibrato if !spot_greeks.is_empty() => {
+                groups.push(CrnGroup::Vibrato(spot_greeks));
+            }
+            _ => {
+                pathwise |= requested & GreeksConfig::DELTA;
+                if requested.contains(GreeksConfig::GAMMA) {
+                    groups.push(CrnGroup::SpotBump);
+                }
+            }
+        }
+        if !pathwise.is_empty() {
+            groups.insert(0, CrnGroup::Pathwise(pathwise));
+        }
+
+        let needs_call = groups
+            .iter()
+            .any(|g| matches!(g, CrnGroup::Pathwise(_) | CrnGroup::SpotBump));
+        if needs_call && !is_call {
+            return Err(SdeError::UnsupportedOperation {
+                operation: "Greeks plan".to_string(),
+                context: "pathwise and spot-bump Greeks are only implemented for European \
+                          calls; use GreekMethod::Vibrato for Delta/Gamma of other payoffs"
+                    .to_string(),
+            });
+        }
+
+        Ok(GreeksPlan { groups })
+    }
+
+    /// Planned groups in execution order
+    pub fn groups(&self) -> &[CrnGroup] {
+        &self.groups
+    }
+
+    /// Run all groups concurrently and assemble the report
+    pub fn execute(&self, cfg: &McConfig) -> SdeResult<GreeksReport> {
+        cfg.validate()?;
+        let results = cfg.parallelism.install(|| {
+            self.groups
+                .par_iter()
+                .map(|group| {
+                    let timer = Timer::new();
+                    let values = run_group(group, cfg)?;
+                    Ok((
+                        values,
+                        GreekTiming {
+                            greeks: group.greeks(),
+                            elapsed_ms: timer.elapsed_ms(),
+                        },
+                    ))
+                })
+                .collect::<SdeResult<Vec<_>>>()
+        })?;
+
+        let mut report = GreeksReport::default();
+        for (values, timing) in results {
+            report.delta = values.delta.or(report.delta);
+            report.gamma = values.gamma.or(report.gamma);
+            report.vega = values.vega.or(report.vega);
+            report.rho = values.rho.or(report.rho);
+            report.timings.push(timing);
+        }
+        Ok(report)
+    }
+}
+
+/// Plan and compute the Greeks selected by `cfg.greeks`
+pub fn mc_greeks_gbm(cfg: &McConfig) -> SdeResult<GreeksReport> {
+    GreeksPlan::from_config(cfg)?.execute(cfg)
+}
+
+fn run_group(group: &CrnGroup, cfg: &McConfig) -> SdeResult<GreeksReport> {
+    let mut out = GreeksReport::default();
+    match group {
+        CrnGroup::Pathwise(greeks) => {
+            let (delta, vega, rho) = pathwise_call_greeks(cfg);
+            out.delta = greeks.contains(GreeksConfig::DELTA).then_some(delta);
+            out.vega = greeks.contains(GreeksConfig::VEGA).then_some(vega);
+            out.rho = greeks.contains(GreeksConfig::RHO).then_some(rho);
+        }
+        CrnGroup::SpotBump => {
+            out.gamma = Some(mc_gamma_european_call_gbm_finite_diff_batched(cfg));
+        }
+        CrnGroup::Vibrato(greeks) => {
+            let v = vibrato::mc_vibrato_greeks_gbm(cfg)?;
+            out.delta = greeks.contains(GreeksConfig::DELTA).then_some(v.delta);
+            out.gamma = greeks.contains(GreeksConfig::GAMMA).then_some(v.gamma);
+        }
+    }
+    Ok(out)
+}
+
+/// Fused pathwise Δ, ν, ρ for a European call from one set of draws
+///
+/// Uses the same seeding and antithetic scheme as the individual
+/// `mc_*_european_call_gbm_pathwise` functions.
+fn pathwise_call_greeks(cfg: &McConfig) -> (f64, f64, f64) {
+    let k = match cfg.payoff {
+        Payoff::EuropeanCall { k } => k,
+        _ => return (0.0, 0.0, 0.0),
+    };
+    let n = cfg.paths;
+    let discount = (-cfg.r * cfg.t).exp();
+    let sqrt_t = cfg.t.sqrt();
+
+    let per_draw = |z: f64| {
+        let w_t = sqrt_t * z;
+        let st = cfg.s0 * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * w_t).exp();
+        if st > k {
+            (
+                st / cfg.s0,
+                st * (-cfg.sigma * cfg.t + w_t),
+                st * cfg.t - cfg.t * (st - k),
+            )
+        } else {
+            (0.0, 0.0, 0.0)
+        }
+    };
+
+    let (sum_delta, sum_vega, sum_rho) = (0..n)
+        .into_par_iter()
+        .map(|i| {
+            let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+            let z = rng::get_normal_draw(&mut rng);
+            let a = per_draw(z);
+            if cfg.use_antithetic {
+                let b = per_draw(-z);
+                (0.5 * (a.0 + b.0), 0.5 * (a.1 + b.1), 0.5 * (a.2 + b.2))
+            } else {
+                a
+            }
+        })
+        .reduce(|| (0.0, 0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2));
+
+    let scale = discount / n as f64;
+    (sum_delta * scale, sum_vega * scale, sum_rho * scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mc::mc_engine::{
+        mc_delta_european_call_gbm_pathwise, mc_rho_european_call_gbm_pathwise,
+        mc_vega_european_call_gbm_pathwise,
+    };
+
+    #[test]
+    fn test_plan_groups_and_fused_pathwise_agree() {
+        let cfg = McConfig {
+            paths: 20_000,
+            greeks: GreeksConfig::DELTA
+                | GreeksConfig::VEGA
+                | GreeksConfig::RHO
+                | GreeksConfig::GAMMA,
+            use_control_variate: false,
+            ..Default::default()
+        };
+        let plan = GreeksPlan::from_config(&cfg).expect("Supported Greeks");
+        assert_eq!(
+            plan.groups(),
+            &[
+                CrnGroup::Pathwise(GreeksConfig::DELTA | GreeksConfig::VEGA | GreeksConfig::RHO),
+                CrnGroup::SpotBump
+            ]
+        );
+
+        let report = plan.execute(&cfg).expect("Valid configuration");
+        assert_eq!(report.timings.len(), 2);
+        assert_eq!(
+            report.elapsed_ms_for(GreeksConfig::VEGA),
+            report.elapsed_ms_for(GreeksConfig::DELTA)
+        );
+        assert!(report.elapsed_ms_for(GreeksConfig::GAMMA).is_some());
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9 * (1.0 + b.abs());
+        assert!(close(
+            report.delta.unwrap(),
+            mc_delta_european_call_gbm_pathwise(&cfg)
+        ));
+        assert!(close(
+            report.vega.unwrap(),
+            mc_vega_european_call_gbm_pathwise(&cfg)
+        ));
+        assert!(close(
+            report.rho.unwrap(),
+            mc_rho_european_call_gbm_pathwise(&cfg)
+        ));
+        assert!(report.gamma.unwrap() > 0.0);
+
+        // Vibrato handles a digital's Delta/Gamma, but not its vega
+        let digital = McConfig {
+            payoff: Payoff::DigitalCall { k: 100.0 },
+            greek_method: GreekMethod::Vibrato,
+            greeks: GreeksConfig::DELTA | GreeksConfig::GAMMA,
+            ..cfg.clone()
+        };
+        let plan = GreeksPlan::from_config(&digital).expect("Vibrato supports digitals");
+        assert_eq!(
+            plan.groups(),
+            &[CrnGroup::Vibrato(GreeksConfig::DELTA | GreeksConfig::GAMMA)]
+        );
+        let with_vega = McConfig {
+            greeks: GreeksConfig::VEGA,
+            ..digital
+        };
+        assert!(GreeksPlan::from_config(&with_vega).is_err());
+    }
+}
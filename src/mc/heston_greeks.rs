@@ -0,0 +1,380 @@
+// src/mc/heston_greeks.rs
+//! Heston Model Greeks via Common-Random-Number Bumping
+//!
+//! # Method
+//!
+//! Each sensitivity is a central finite difference of the Monte Carlo price:
+//! ```text
+//! ∂V/∂p ≈ [V(p + h) - V(p - h)] / (2h)
+//! ```
+//! All bumped scenarios are priced inside a single parallel loop. For each
+//! path the random draws are generated once and fed to every scenario via
+//! [`Heston::step_with_draws`], so the difference of two scenario payoffs
+//! reflects only the parameter change, not sampling noise:
+//! ```text
+//! Var[V(p+h) - V(p-h)] << Var[V(p+h)] + Var[V(p-h)]
+//! ```
+//!
+//! # Bumps
+//!
+//! Bumps are relative (`relative_bump * |p|`) except for ρ, which is bumped
+//! by `relative_bump` in absolute terms. Bumped values are clamped to the
+//! parameter domain (v₀ ≥ 0, ρ ∈ [-1, 1]) and the difference quotient uses
+//! the actual bumped distance, so a parameter at its boundary automatically
+//! falls back to a one-sided difference.
+
+use crate::error::{validation::*, SdeError, SdeResult, Warnings};
+use crate::mc::path_failures::{
+    refine_normal, sum_paths_with_policy, PathDiagnostics, PathFailurePolicy,
+};
+use crate::mc::payoffs::Payoff;
+use crate::models::heston::{FellerPolicy, Heston, HestonParams, HestonScheme};
+use crate::rng::{self, SeedStrategy};
+use rand::Rng;
+
+/// Simulation settings for Heston Greeks
+#[derive(Clone)]
+pub struct HestonGreeksConfig {
+    pub paths: usize,
+    pub steps: usize,
+    pub t: f64,
+    pub seed: u64,
+    /// Derivation of the per-path streams from `seed`
+    pub seed_strategy: SeedStrategy,
+    pub payoff: Payoff,
+    pub use_antithetic: bool,
+    /// Relative bump size for finite differences (absolute for ρ)
+    pub relative_bump: f64,
+    /// Handling of paths on which the scheme fails
+    pub failure_policy: PathFailurePolicy,
+}
+
+impl HestonGreeksConfig {
+    /// Validate the simulation settings
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_paths(self.paths)?;
+        validate_steps(self.steps)?;
+        validate_positive("t", self.t)?;
+        validate_range("relative_bump", self.relative_bump, 1e-6, 0.1)?;
+        self.failure_policy.validate()?;
+        Ok(())
+    }
+}
+
+impl Default for HestonGreeksConfig {
+    fn default() -> Self {
+        HestonGreeksConfig {
+            paths: 100_000,
+            steps: 100,
+            t: 1.0,
+            seed: 12345,
+            seed_strategy: SeedStrategy::Hashed,
+            payoff: Payoff::EuropeanCall { k: 100.0 },
+            use_antithetic: true,
+            relative_bump: 0.01,
+            failure_policy: PathFailurePolicy::Fail,
+        }
+    }
+}
+
+/// Structured sensitivity report for a Heston price
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HestonSensitivityReport {
+    pub price: f64,
+    /// ∂V/∂S₀
+    pub delta: f64,
+    /// ∂²V/∂S₀²
+    pub gamma: f64,
+    /// ∂V/∂v₀ (sensitivity to initial variance)
+    pub vega_v0: f64,
+    /// ∂V/∂κ
+    pub dv_dkappa: f64,
+    /// ∂V/∂θ
+    pub dv_dtheta: f64,
+    /// ∂V/∂ξ
+    pub dv_dxi: f64,
+    /// ∂V/∂ρ
+    pub dv_drho: f64,
+}
+
+/// Scenario layout: base, then (up, down) pairs per parameter
+const BASE: usize = 0;
+const S0: usize = 1;
+const V0: usize = 3;
+const KAPPA: usize = 5;
+const THETA: usize = 7;
+const XI: usize = 9;
+const RHO: usize = 11;
+const N_SCENARIOS: usize = 13;
+
+/// Heston Greeks calculator
+pub struct HestonGreeks {
+    pub params: HestonParams,
+    pub scheme: HestonScheme,
+}
+
+impl HestonGreeks {
+    pub fn new(params: HestonParams, scheme: HestonScheme) -> SdeResult<Self> {
+        // Validate once up front so errors refer to the caller's parameters
+        Heston::new_with_policy(params, scheme, FellerPolicy::Ignore)?;
+        Ok(HestonGreeks { params, scheme })
+    }
+
+    /// Compute price and all sensitivities with common random numbers
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError` if the configuration or a bumped parameter set is
+    /// invalid, or if any scenario path becomes numerically unstable and
+    /// `cfg.failure_policy` is [`PathFailurePolicy::Fail`].
+    pub fn compute(&self, cfg: &HestonGreeksConfig) -> SdeResult<HestonSensitivityReport> {
+        self.compute_with_diagnostics(cfg).map(|(report, _)| report)
+    }
+
+    /// [`compute`](Self::compute), also returning per-path failure diagnostics
+    ///
+    /// # Errors
+    ///
+    /// Same as [`compute`](Self::compute), and if every path is quarantined.
+    pub fn compute_with_diagnostics(
+        &self,
+        cfg: &HestonGreeksConfig,
+    ) -> SdeResult<(HestonSensitivityReport, PathDiagnostics)> {
+        cfg.validate()?;
+        let (models, bumps) = self.build_scenarios(cfg.relative_bump)?;
+        let discount = (-self.params.r * cfg.t).exp();
+
+        let (sums, diagnostics) =
+            crn_scenario_sums(&models, cfg, std::slice::from_ref(&cfg.payoff))?;
+
+        let accepted = diagnostics.accepted_paths as f64;
+        let price = |scenario: usize| discount * sums[scenario] / accepted;
+        let central = |idx: usize| (price(idx) - price(idx + 1)) / bumps[idx];
+        let h_s0 = 0.5 * bumps[S0];
+
+        let report = HestonSensitivityReport {
+            price: price(BASE),
+            delta: central(S0),
+            gamma: (price(S0) - 2.0 * price(BASE) + price(S0 + 1)) / (h_s0 * h_s0),
+            vega_v0: central(V0),
+            dv_dkappa: central(KAPPA),
+            dv_dtheta: central(THETA),
+            dv_dxi: central(XI),
+            dv_drho: central(RHO),
+        };
+
+        if !report.price.is_finite() || !report.delta.is_finite() || !report.gamma.is_finite() {
+            return Err(SdeError::NumericalInstability {
+                method: "Heston CRN Greeks".to_string(),
+                reason: format!("non-finite estimate in report: {:?}", report),
+            });
+        }
+
+        Ok((report, diagnostics))
+    }
+
+    /// Build the base and bumped models
+    ///
+    /// Returns the models in scenario order together with the distance
+    /// between each (up, down) pair, indexed by the up scenario.
+    fn build_scenarios(&self, rel: f64) -> SdeResult<(Vec<Heston>, [f64; N_SCENARIOS])> {
+        let p = self.params;
+        let mut params = vec![p; N_SCENARIOS];
+        let mut bumps = [0.0; N_SCENARIOS];
+
+        let mut bump =
+            |idx: usize, value: f64, h: f64, lo: f64, hi: f64, set: fn(&mut HestonParams, f64)| {
+                let up = (value + h).min(hi);
+                let down = (value - h).max(lo);
+                set(&mut params[idx], up);
+                set(&mut params[idx + 1], down);
+                bumps[idx] = up - down;
+            };
+
+        let rel_h = |x: f64| rel * x.abs().max(1e-4);
+        bump(S0, p.s0, rel_h(p.s0), 0.0, f64::INFINITY, |q, x| q.s0 = x);
+        bump(V0, p.v0, rel_h(p.v0), 0.0, f64::INFINITY, |q, x| q.v0 = x);
+        bump(
+            KAPPA,
+            p.kappa,
+            rel_h(p.kappa),
+            0.0,
+            f64::INFINITY,
+            |q, x| q.kappa = x,
+        );
+        bump(
+            THETA,
+            p.theta,
+            rel_h(p.theta),
+            0.0,
+            f64::INFINITY,
+            |q, x| q.theta = x,
+        );
+        bump(XI, p.xi, rel_h(p.xi), 0.0, f64::INFINITY, |q, x| q.xi = x);
+        bump(RHO, p.rho, rel, -1.0, 1.0, |q, x| q.rho = x);
+
+        let models = params
+            .into_iter()
+            .map(|q| Heston::new_with_policy(q, self.scheme, FellerPolicy::Ignore))
+            .collect::<SdeResult<Vec<_>>>()?;
+        Ok((models, bumps))
+    }
+}
+
+/// Payoff sums for every (scenario, payoff) pair over common random numbers
+///
+/// For each path the draws are generated once and fed to every scenario
+/// model; the result is laid out `[scenario * payoffs.len() + payoff]`,
+/// undiscounted and summed over accepted paths (antithetic pairs averaged),
+/// followed by the sums of squares in the same layout.
+/// A path that fails in any scenario is handled by `cfg.failure_policy`
+/// for all scenarios together, so the scenarios keep sharing paths.
+pub(crate) fn crn_scenario_sums(
+    models: &[Heston],
+    cfg: &HestonGreeksConfig,
+    payoffs: &[Payoff],
+) -> SdeResult<(Vec<f64>, PathDiagnostics)> {
+    let dt = cfg.t / cfg.steps as f64;
+    let width = models.len() * payoffs.len();
+    let simulate = |i: usize, refinement: usize| -> SdeResult<(Vec<f64>, Warnings)> {
+        let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+        let mut draws: Vec<(f64, f64, f64)> = (0..cfg.steps)
+            .map(|_| {
+                let z1 = rng::get_normal_draw(&mut rng);
+                let z2 = rng::get_normal_draw(&mut rng);
+                (z1, z2, rng.gen())
+            })
+            .collect();
+        if refinement > 1 {
+            // Same Brownian path on the finer grid; sub-steps after the
+            // first draw fresh uniforms
+            let mut fine = Vec::with_capacity(cfg.steps * refinement);
+            for &(z1, z2, u) in &draws {
+                let y1: Vec<f64> = (0..refinement)
+                    .map(|_| rng::get_normal_draw(&mut rng))
+                    .collect();
+                let y2: Vec<f64> = (0..refinement)
+                    .map(|_| rng::get_normal_draw(&mut rng))
+                    .collect();
+                for (j, (a, b)) in refine_normal(z1, &y1)
+                    .zip(refine_normal(z2, &y2))
+                    .enumerate()
+                {
+                    fine.push((a, b, if j == 0 { u } else { rng.gen() }));
+                }
+            }
+            draws = fine;
+        }
+        let sub_dt = dt / refinement as f64;
+
+        let mut path = Vec::with_capacity(cfg.steps + 1);
+        let mut values = vec![0.0; 2 * width];
+        let mut warnings = Warnings::default();
+        let signs: &[f64] = if cfg.use_antithetic {
+            &[1.0, -1.0]
+        } else {
+            &[1.0]
+        };
+        for &sign in signs {
+            for (scenario, model) in models.iter().enumerate() {
+                let mut s = model.params.s0;
+                let mut v = model.params.v0;
+                path.clear();
+                path.push(s);
+                for (n, &(z1, z2, u)) in draws.iter().enumerate() {
+                    // Antithetic: negate normals, reflect the uniform
+                    let u = if sign > 0.0 { u } else { 1.0 - u };
+                    warnings += model
+                        .step_with_draws(&mut s, &mut v, sub_dt, sign * z1, sign * z2, u)
+                        .map_err(|e| e.at_step(n / refinement))?;
+                    if (n + 1) % refinement == 0 {
+                        path.push(s);
+                    }
+                }
+                for (j, payoff) in payoffs.iter().enumerate() {
+                    values[scenario * payoffs.len() + j] +=
+                        payoff.calculate(&path) / signs.len() as f64;
+                }
+            }
+        }
+        let (sums, squares) = values.split_at_mut(width);
+        for (square, sum) in squares.iter_mut().zip(sums.iter()) {
+            *square = sum * sum;
+        }
+        Ok((values, warnings))
+    };
+    sum_paths_with_policy(cfg.paths, 2 * width, cfg.failure_policy, simulate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heston_greeks_signs_and_reproducibility() {
+        let params = HestonParams {
+            s0: 100.0,
+            v0: 0.04,
+            r: 0.05,
+            kappa: 2.0,
+            theta: 0.04,
+            xi: 0.3,
+            rho: -0.5,
+        };
+        let greeks =
+            HestonGreeks::new(params, HestonScheme::FullTruncationEuler).expect("Valid parameters");
+        let cfg = HestonGreeksConfig {
+            paths: 20_000,
+            steps: 20,
+            ..Default::default()
+        };
+
+        let report = greeks.compute(&cfg).expect("Greeks should compute");
+        assert!(
+            report.delta > 0.0 && report.delta < 1.0,
+            "Call delta {} outside (0, 1)",
+            report.delta
+        );
+        assert!(report.gamma > 0.0, "Call gamma must be positive");
+        assert!(report.vega_v0 > 0.0, "Call value must increase with v0");
+        assert!(
+            report.dv_dtheta > 0.0,
+            "Call value must increase with theta"
+        );
+
+        // Common random numbers make the report exactly reproducible
+        let again = greeks.compute(&cfg).expect("Greeks should compute");
+        assert_eq!(report.delta, again.delta);
+        assert_eq!(report.dv_drho, again.dv_drho);
+    }
+
+    #[test]
+    fn test_failure_policy_is_transparent_without_failures() {
+        let params = HestonParams {
+            s0: 100.0,
+            v0: 0.04,
+            r: 0.05,
+            kappa: 2.0,
+            theta: 0.04,
+            xi: 0.3,
+            rho: -0.5,
+        };
+        let greeks = HestonGreeks::new(params, HestonScheme::AndersenQE).expect("Valid parameters");
+        let cfg = HestonGreeksConfig {
+            paths: 2_000,
+            steps: 10,
+            ..Default::default()
+        };
+        let base = greeks.compute(&cfg).expect("Greeks should compute");
+        let (report, diagnostics) = greeks
+            .compute_with_diagnostics(&HestonGreeksConfig {
+                failure_policy: PathFailurePolicy::Resimulate { refinement: 4 },
+                ..cfg.clone()
+            })
+            .expect("Greeks should compute");
+        assert_eq!(diagnostics.accepted_paths, cfg.paths);
+        assert_eq!(diagnostics.quarantined + diagnostics.resimulated, 0);
+        assert_eq!(report.price, base.price);
+    }
+}
@@ -0,0 +1,257 @@
+// src/mc/heston_stress.rs
+//! Heston Model-Parameter Stress Testing
+//!
+//! # Motivation
+//!
+//! Exotic positions carry risk to the shape of the volatility surface that
+//! spot and vega bumps do not show. In Heston, vol-of-vol `ξ` controls the
+//! smile curvature, correlation `ρ` the skew, and long-run variance `θ` the
+//! level of the term structure. Stressing these parameters directly gives a
+//! model-parameter stress report.
+//!
+//! # Method
+//!
+//! Every stress is a modified parameter set. The base and all stressed
+//! models are priced in one pass over the CRN scenario loop shared with
+//! [`HestonGreeks`](crate::mc::heston_greeks::HestonGreeks), so each path's
+//! draws drive every scenario and the stress P&L
+//! ```text
+//! ΔV = Σ_j q_j [V_j(p_stressed) - V_j(p)]
+//! ```
+//! is free of most sampling noise.
+//!
+//! # Standard Scenarios
+//!
+//! [`HestonStress::standard_set`] returns ξ ×1.5 and ×0.5, ρ to ±0.9, and
+//! θ shifted by ±0.02 (floored at a small positive variance).
+
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::mc::heston_greeks::{crn_scenario_sums, HestonGreeksConfig};
+use crate::mc::path_failures::PathDiagnostics;
+use crate::mc::payoffs::Payoff;
+use crate::models::heston::{FellerPolicy, Heston, HestonParams, HestonScheme};
+
+/// Smallest long-run variance a θ shift can produce
+const MIN_THETA: f64 = 1e-4;
+
+/// A stress applied to Heston parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HestonStress {
+    /// Multiply vol-of-vol ξ by a factor
+    VolOfVolScale(f64),
+    /// Set correlation ρ to a value in [-1, 1]
+    Correlation(f64),
+    /// Add an absolute shift to long-run variance θ
+    LongRunVarianceShift(f64),
+}
+
+impl HestonStress {
+    /// Predefined vol-of-vol, skew and long-run variance stresses
+    pub fn standard_set() -> Vec<HestonStress> {
+        vec![
+            HestonStress::VolOfVolScale(1.5),
+            HestonStress::VolOfVolScale(0.5),
+            HestonStress::Correlation(-0.9),
+            HestonStress::Correlation(0.9),
+            HestonStress::LongRunVarianceShift(0.02),
+            HestonStress::LongRunVarianceShift(-0.02),
+        ]
+    }
+
+    /// Stressed copy of `params`
+    pub fn apply(&self, params: &HestonParams) -> HestonParams {
+        let mut p = *params;
+        match *self {
+            HestonStress::VolOfVolScale(factor) => p.xi *= factor,
+            HestonStress::Correlation(rho) => p.rho = rho,
+            HestonStress::LongRunVarianceShift(shift) => p.theta = (p.theta + shift).max(MIN_THETA),
+        }
+        p
+    }
+
+    /// Short label for reports, e.g. `"xi x1.50"`
+    pub fn label(&self) -> String {
+        match *self {
+            HestonStress::VolOfVolScale(factor) => format!("xi x{:.2}", factor),
+            HestonStress::Correlation(rho) => format!("rho -> {:+.2}", rho),
+            HestonStress::LongRunVarianceShift(shift) => format!("theta {:+.4}", shift),
+        }
+    }
+}
+
+/// Position values under one stress
+#[derive(Debug, Clone)]
+pub struct StressResult {
+    pub stress: HestonStress,
+    pub params: HestonParams,
+    /// Value of each position, in input order
+    pub values: Vec<f64>,
+    pub total: f64,
+    /// `total - base_total`
+    pub pnl: f64,
+}
+
+/// Model-parameter stress report for a set of positions
+#[derive(Debug, Clone)]
+pub struct HestonStressReport {
+    /// Value of each position under the base parameters
+    pub base_values: Vec<f64>,
+    pub base_total: f64,
+    /// One entry per stress, in input order
+    pub results: Vec<StressResult>,
+    /// Failed-path handling under `cfg.failure_policy`
+    pub diagnostics: PathDiagnostics,
+}
+
+impl HestonStressReport {
+    /// The stress with the largest loss, if any
+    pub fn worst(&self) -> Option<&StressResult> {
+        self.results.iter().min_by(|a, b| a.pnl.total_cmp(&b.pnl))
+    }
+}
+
+/// Value `(payoff, quantity)` positions under base and stressed parameters
+///
+/// All positions mature at `cfg.t`; `cfg.payoff` and `cfg.relative_bump`
+/// are ignored.
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid settings or positions, a stressed
+/// parameter set that fails Heston validation, or numerical instability.
+pub fn run_heston_stress(
+    params: HestonParams,
+    scheme: HestonScheme,
+    cfg: &HestonGreeksConfig,
+    positions: &[(Payoff, f64)],
+    stresses: &[HestonStress],
+) -> SdeResult<HestonStressReport> {
+    cfg.validate()?;
+    if positions.is_empty() {
+        return Err(SdeError::InvalidConfiguration {
+            field: "positions".to_string(),
+            reason: "must contain at least one position".to_string(),
+        });
+    }
+    for (_, quantity) in positions {
+        validate_finite("quantity", *quantity)?;
+    }
+
+    let models = std::iter::once(params)
+        .chain(stresses.iter().map(|s| s.apply(&params)))
+        .map(|p| Heston::new_with_policy(p, scheme, FellerPolicy::Ignore))
+        .collect::<SdeResult<Vec<_>>>()?;
+    let payoffs: Vec<Payoff> = positions.iter().map(|(payoff, _)| payoff.clone()).collect();
+    let (sums, diagnostics) = crn_scenario_sums(&models, cfg, &payoffs)?;
+
+    let scale = (-params.r * cfg.t).exp() / diagnostics.accepted_paths as f64;
+    let values_for = |scenario: usize| -> Vec<f64> {
+        positions
+            .iter()
+            .enumerate()
+            .map(|(j, (_, quantity))| quantity * scale * sums[scenario * positions.len() + j])
+            .collect()
+    };
+
+    let base_values = values_for(0);
+    let base_total: f64 = base_values.iter().sum();
+    if !base_total.is_finite() {
+        return Err(SdeError::NumericalInstability {
+            method: "Heston stress testing".to_string(),
+            reason: "non-finite base portfolio value".to_string(),
+        });
+    }
+
+    let results = stresses
+        .iter()
+        .enumerate()
+        .map(|(i, stress)| {
+            let values = values_for(i + 1);
+            let total: f64 = values.iter().sum();
+            StressResult {
+                stress: *stress,
+                params: models[i + 1].params,
+                values,
+                total,
+                pnl: total - base_total,
+            }
+        })
+        .collect();
+
+    Ok(HestonStressReport {
+        base_values,
+        base_total,
+        results,
+        diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_stresses_move_skew_and_level() {
+        let params = HestonParams {
+            s0: 100.0,
+            v0: 0.04,
+            r: 0.02,
+            kappa: 2.0,
+            theta: 0.04,
+            xi: 0.5,
+            rho: -0.5,
+        };
+        let cfg = HestonGreeksConfig {
+            paths: 20_000,
+            steps: 20,
+            ..Default::default()
+        };
+        let positions = [
+            (Payoff::EuropeanPut { k: 80.0 }, 1.0),
+            (Payoff::EuropeanCall { k: 100.0 }, 1.0),
+            (Payoff::BarrierCallUpAndOut { k: 100.0, h: 130.0 }, -2.0),
+        ];
+        let stresses = HestonStress::standard_set();
+        let report = run_heston_stress(
+            params,
+            HestonScheme::FullTruncationEuler,
+            &cfg,
+            &positions,
+            &stresses,
+        )
+        .expect("Valid stress run");
+
+        assert_eq!(report.results.len(), stresses.len());
+        let sum: f64 = report.base_values.iter().sum();
+        assert!((report.base_total - sum).abs() < 1e-12);
+
+        // Steeper negative skew fattens the left tail: OTM put gains
+        let put = |label: &str| {
+            report
+                .results
+                .iter()
+                .find(|r| r.stress.label() == label)
+                .expect("Scenario present")
+                .values[0]
+        };
+        assert!(put("rho -> -0.90") > report.base_values[0]);
+        assert!(put("rho -> +0.90") < report.base_values[0]);
+
+        // Higher long-run variance lifts the ATM call
+        let theta_up = &report.results[4];
+        assert_eq!(theta_up.stress, HestonStress::LongRunVarianceShift(0.02));
+        assert!(theta_up.values[1] > report.base_values[1]);
+
+        // θ never goes non-positive
+        let theta_down = HestonStress::LongRunVarianceShift(-1.0).apply(&params);
+        assert!(theta_down.theta > 0.0);
+
+        let worst = report.worst().expect("Stresses present").pnl;
+        assert!(report.results.iter().all(|r| r.pnl >= worst));
+
+        // A NaN P&L never ranks as the worst stress, nor panics
+        let mut poisoned = report.clone();
+        poisoned.results[0].pnl = f64::NAN;
+        assert!(!poisoned.worst().expect("Stresses present").pnl.is_nan());
+    }
+}
@@ -0,0 +1,341 @@
+// src/mc/histogram.rs
+//! Terminal Distribution Histograms
+//!
+//! # Motivation
+//!
+//! The simulated terminal prices are a sample of the risk-neutral density
+//! of `S_T`. Binning them shows whether the simulation matches the model
+//! (lognormal under GBM), and binning the discounted payoffs shows where the
+//! price comes from, without keeping every path in memory.
+//!
+//! # Density
+//!
+//! With `N` observations in total (including those outside the range) and
+//! bin width `w_i`, the density estimate of bin `i` is
+//! ```text
+//! f_i = count_i / (N w_i)
+//! ```
+//! so it integrates to the fraction of observations inside the range.
+//!
+//! # Range
+//!
+//! Without an explicit range, a first pass over the same paths finds the
+//! sample minimum and maximum, so no observation falls outside the bins at
+//! the cost of simulating every path twice.
+
+use crate::error::{SdeError, SdeResult};
+use crate::mc::mc_engine::{
+    engine_normal_source, for_each_block_path, mc_price_option_gbm, priced_payoff,
+    simulation_increments, with_scratch, BlockBuffers, McConfig,
+};
+use crate::parallel::prelude::*;
+
+/// Bins and ranges of the histograms
+#[derive(Debug, Clone, Copy)]
+pub struct HistogramConfig {
+    /// Number of equal-width bins
+    pub bins: usize,
+    /// `(lower, upper)` for terminal prices; sample range if `None`
+    pub range: Option<(f64, f64)>,
+    /// Also bin the discounted payoffs
+    pub payoffs: bool,
+    /// `(lower, upper)` for discounted payoffs; sample range if `None`
+    pub payoff_range: Option<(f64, f64)>,
+}
+
+impl Default for HistogramConfig {
+    fn default() -> Self {
+        HistogramConfig {
+            bins: 100,
+            range: None,
+            payoffs: false,
+            payoff_range: None,
+        }
+    }
+}
+
+impl HistogramConfig {
+    /// Validate the bin count and ranges
+    pub fn validate(&self) -> SdeResult<()> {
+        if self.bins == 0 {
+            return Err(SdeError::InvalidConfiguration {
+                field: "bins".to_string(),
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        for (field, range) in [("range", self.range), ("payoff_range", self.payoff_range)] {
+            if let Some((lower, upper)) = range {
+                if !(lower.is_finite() && upper.is_finite() && lower < upper) {
+                    return Err(SdeError::InvalidConfiguration {
+                        field: field.to_string(),
+                        reason: format!(
+                            "[{}, {}] is not a finite, non-empty interval",
+                            lower, upper
+                        ),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Equal-width histogram with underflow and overflow counts
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// `bins + 1` bin edges; the last bin includes its upper edge
+    pub edges: Vec<f64>,
+    pub counts: Vec<u64>,
+    /// Observations below `edges[0]`
+    pub underflow: u64,
+    /// Observations above the last edge
+    pub overflow: u64,
+}
+
+impl Histogram {
+    /// Empty histogram of `bins` bins spanning `[lower, upper]`
+    pub fn new(lower: f64, upper: f64, bins: usize) -> Self {
+        let width = (upper - lower) / bins as f64;
+        Histogram {
+            edges: (0..=bins).map(|i| lower + i as f64 * width).collect(),
+            counts: vec![0; bins],
+            underflow: 0,
+            overflow: 0,
+        }
+    }
+
+    /// Count one observation
+    pub fn push(&mut self, x: f64) {
+        let bins = self.counts.len();
+        let (lower, upper) = (self.edges[0], self.edges[bins]);
+        if x < lower {
+            self.underflow += 1;
+        } else if x > upper {
+            self.overflow += 1;
+        } else {
+            let i = ((x - lower) / (upper - lower) * bins as f64) as usize;
+            self.counts[i.min(bins - 1)] += 1;
+        }
+    }
+
+    /// Add the counts of a histogram with the same edges
+    pub fn merge(&mut self, other: &Histogram) {
+        for (a, b) in self.counts.iter_mut().zip(&other.counts) {
+            *a += b;
+        }
+        self.underflow += other.underflow;
+        self.overflow += other.overflow;
+    }
+
+    /// All observations, including underflow and overflow
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum::<u64>() + self.underflow + self.overflow
+    }
+
+    /// Bin midpoints
+    pub fn centers(&self) -> Vec<f64> {
+        self.edges.windows(2).map(|e| 0.5 * (e[0] + e[1])).collect()
+    }
+
+    /// Density estimate per bin, `count / (total · width)`
+    pub fn density(&self) -> Vec<f64> {
+        let total = self.total().max(1) as f64;
+        self.edges
+            .windows(2)
+            .zip(&self.counts)
+            .map(|(e, &c)| c as f64 / (total * (e[1] - e[0])))
+            .collect()
+    }
+}
+
+/// Engine price together with the terminal (and payoff) histograms
+#[derive(Debug, Clone)]
+pub struct PriceWithHistogram {
+    /// As returned by [`mc_price_option_gbm`]
+    pub price: f64,
+    pub variance: f64,
+    /// Terminal prices `S_T` of every simulated path
+    pub terminal: Histogram,
+    /// Discounted payoffs, if requested
+    pub payoffs: Option<Histogram>,
+}
+
+/// Price with [`mc_price_option_gbm`] and bin the simulated paths
+///
+/// Every sample path of the engine's normal source is binned (both members
+/// of an antithetic pair), on the same draws as the engine. Paths are
+/// always simulated to maturity here, even with `cfg.early_termination`,
+/// so that `S_T` is the true terminal price.
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid configurations or pricing failures.
+pub fn mc_price_with_histogram(
+    cfg: &McConfig,
+    hist: &HistogramConfig,
+) -> SdeResult<PriceWithHistogram> {
+    cfg.parallelism.install(|| {
+        hist.validate()?;
+        let (price, variance) = mc_price_option_gbm(cfg)?;
+
+        let sim = McConfig {
+            early_termination: false,
+            ..cfg.clone()
+        };
+        let grid = simulation_increments(&sim);
+        let payoff = priced_payoff(&sim);
+        let discount = (-sim.r * sim.t).exp();
+        let source = engine_normal_source(&sim);
+        let outcomes = |buffers: &mut BlockBuffers, i: usize| {
+            let mut outcomes = Vec::with_capacity(source.block_size());
+            for_each_block_path(&sim, &grid, &source, i, buffers, |path| {
+                outcomes.push((path[path.len() - 1], discount * payoff.calculate(path)));
+            });
+            outcomes
+        };
+
+        let sampled =
+            (hist.range.is_none() || (hist.payoffs && hist.payoff_range.is_none())).then(|| {
+                (0..sim.paths)
+                    .into_par_iter()
+                    .map(|i| with_scratch(|buffers| outcomes(buffers, i)))
+                    .flat_map_iter(|block| block)
+                    .fold(sample_range_identity, |(s, p), (s_t, value)| {
+                        (
+                            (s.0.min(s_t), s.1.max(s_t)),
+                            (p.0.min(value), p.1.max(value)),
+                        )
+                    })
+                    .reduce(sample_range_identity, |(s, p), (t, q)| {
+                        ((s.0.min(t.0), s.1.max(t.1)), (p.0.min(q.0), p.1.max(q.1)))
+                    })
+            });
+        let range = hist
+            .range
+            .or_else(|| sampled.map(|(s, _)| widen(s)))
+            .expect("range given or sampled");
+        let payoff_range = hist
+            .payoffs
+            .then(|| hist.payoff_range.or_else(|| sampled.map(|(_, p)| widen(p))))
+            .flatten();
+
+        let empty = || {
+            (
+                Histogram::new(range.0, range.1, hist.bins),
+                payoff_range.map(|(lower, upper)| Histogram::new(lower, upper, hist.bins)),
+            )
+        };
+        let (terminal, payoffs) = (0..sim.paths)
+            .into_par_iter()
+            .map(|i| with_scratch(|buffers| outcomes(buffers, i)))
+            .flat_map_iter(|block| block)
+            .fold(empty, |(mut terminal, mut payoffs), (s_t, value)| {
+                terminal.push(s_t);
+                if let Some(payoffs) = payoffs.as_mut() {
+                    payoffs.push(value);
+                }
+                (terminal, payoffs)
+            })
+            .reduce(empty, |(mut terminal, mut payoffs), (t, p)| {
+                terminal.merge(&t);
+                if let (Some(payoffs), Some(p)) = (payoffs.as_mut(), p) {
+                    payoffs.merge(&p);
+                }
+                (terminal, payoffs)
+            });
+
+        Ok(PriceWithHistogram {
+            price,
+            variance,
+            terminal,
+            payoffs,
+        })
+    })
+}
+
+fn sample_range_identity() -> ((f64, f64), (f64, f64)) {
+    (
+        (f64::INFINITY, f64::NEG_INFINITY),
+        (f64::INFINITY, f64::NEG_INFINITY),
+    )
+}
+
+/// Sample range as a non-empty interval (all-equal samples get a unit width)
+fn widen((lower, upper): (f64, f64)) -> (f64, f64) {
+    if upper > lower {
+        (lower, upper)
+    } else {
+        (lower, lower + 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mc::payoffs::Payoff;
+
+    #[test]
+    fn test_histogram_binning() {
+        let mut hist = Histogram::new(0.0, 1.0, 4);
+        for x in [-0.5, 0.0, 0.1, 0.3, 0.5, 0.99, 1.0, 1.5] {
+            hist.push(x);
+        }
+        assert_eq!(hist.counts, vec![2, 1, 1, 2]);
+        assert_eq!((hist.underflow, hist.overflow, hist.total()), (1, 1, 8));
+        assert_eq!(hist.centers()[0], 0.125);
+        let mass: f64 = hist.density().iter().map(|d| d * 0.25).sum();
+        assert!((mass - 0.75).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_terminal_density_is_lognormal() {
+        let cfg = McConfig {
+            paths: 50_000,
+            steps: 1,
+            payoff: Payoff::EuropeanCall { k: 100.0 },
+            ..Default::default()
+        };
+        let hist = HistogramConfig {
+            bins: 40,
+            range: Some((60.0, 160.0)),
+            payoffs: true,
+            ..Default::default()
+        };
+        let result = mc_price_with_histogram(&cfg, &hist).expect("Valid configuration");
+        assert_eq!(result.terminal.total(), 100_000);
+
+        // Lognormal density of S_T under GBM
+        let (s0, r, sigma, t) = (cfg.s0, cfg.r, cfg.sigma, cfg.t);
+        let lognormal = |s: f64| {
+            let z = ((s / s0).ln() - (r - 0.5 * sigma * sigma) * t) / (sigma * t.sqrt());
+            (-0.5 * z * z).exp() / (s * sigma * (2.0 * std::f64::consts::PI * t).sqrt())
+        };
+        for (center, density) in result
+            .terminal
+            .centers()
+            .iter()
+            .zip(result.terminal.density())
+        {
+            let expected = lognormal(*center);
+            assert!(
+                (density - expected).abs() < 0.1 * expected + 5e-4,
+                "S = {}: {} vs {}",
+                center,
+                density,
+                expected
+            );
+        }
+
+        // Sample range: every payoff is binned, about half in the zero bin
+        let payoffs = result.payoffs.expect("Payoffs requested");
+        assert_eq!(payoffs.underflow + payoffs.overflow, 0);
+        assert_eq!(payoffs.edges[0], 0.0);
+        assert!(payoffs.counts[0] > 40_000);
+
+        let bad = HistogramConfig {
+            range: Some((1.0, 1.0)),
+            ..hist
+        };
+        assert!(mc_price_with_histogram(&cfg, &bad).is_err());
+    }
+}
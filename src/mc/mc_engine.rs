@@ -1,14 +1,33 @@
 // src/mc/mc_engine.rs
-use crate::analytics::bs_analytic;
 use crate::error::{validation::*, SdeError, SdeResult};
-use crate::mc::payoffs::Payoff;
-use crate::rng;
+use crate::mc::accumulators::{self, Moments};
+use crate::mc::barrier_smoothing;
+use crate::mc::control_variates::{
+    engine_controls, push_sample, sample_controls, ControlFit, ControlValues, ControlVariate,
+    PathMoments, MAX_CONTROLS,
+};
+use crate::mc::normal_source::{Antithetic, NormalSource, PseudoRandom};
+use crate::mc::path_construction::{PathBuilder, PathConstructed, PathConstruction};
+use crate::mc::payoffs::{BarrierShift, Payoff, PayoffSmoothing};
+use crate::mc::vibrato;
+use crate::models::gbm::Gbm;
+use crate::parallel::prelude::*;
+use crate::parallel::Parallelism;
+use crate::rng::{self, SeedStrategy};
+use crate::solvers::euler_maruyama::EulerMaruyama;
+use crate::solvers::milstein::Milstein;
+use crate::solvers::ninomiya_victoir::NinomiyaVictoir;
+use crate::solvers::srk::Srk;
+use crate::trace::{debug_event, span};
 use bitflags::bitflags;
-use rayon::prelude::*;
+use std::cell::RefCell;
 use std::f64;
+use std::sync::Arc;
 
 bitflags! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serde", serde(transparent))]
     pub struct GreeksConfig: u32 {
         const NONE  = 0;
         const DELTA = 1 << 0;
@@ -18,7 +37,47 @@ bitflags! {
     }
 }
 
+/// Estimation method for Monte Carlo Greeks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GreekMethod {
+    /// Pathwise derivatives; Gamma by finite difference on pathwise Delta.
+    /// Requires a Lipschitz payoff (European call).
+    #[default]
+    Pathwise,
+    /// Vibrato Monte Carlo (pathwise + likelihood ratio on one step).
+    /// Valid for discontinuous payoffs such as digitals and barriers.
+    Vibrato,
+}
+
+/// Time-stepping scheme of the GBM engine
+///
+/// Only [`Scheme::Exact`] is free of discretization bias; the others apply
+/// the generic solvers to `dS = rS dt + σS dW` so that their bias can be
+/// studied on a model with a known answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Scheme {
+    /// Exact log-normal transition
+    #[default]
+    Exact,
+    /// [`EulerMaruyama`]: weak order 1
+    EulerMaruyama,
+    /// [`Milstein`]: strong order 1
+    Milstein,
+    /// [`Srk`] predictor-corrector, with the Stratonovich drift
+    Srk,
+    /// [`NinomiyaVictoir`]: weak order 2
+    NinomiyaVictoir,
+}
+
+/// Settings of the GBM Monte Carlo engine
+///
+/// Prefer [`McConfig::builder`] to struct literals: it validates at build
+/// time and keeps compiling when fields are added.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct McConfig {
     pub paths: usize,
     pub steps: usize,
@@ -32,6 +91,55 @@ pub struct McConfig {
     pub payoff: Payoff,
     pub greeks: GreeksConfig,
     pub epsilon: Option<f64>, // For finite difference Greeks (default: 1e-3 * s0)
+    pub greek_method: GreekMethod,
+    /// Payoff smoothing for pathwise Greeks of barrier options
+    pub smoothing: PayoffSmoothing,
+    /// Sparse observation mode: simulate exactly at these times only
+    /// (fixings, barrier dates) instead of on `steps` uniform steps. Must be
+    /// strictly increasing in `(0, t]` and end at `t`. Used by
+    /// [`mc_price_option_gbm`].
+    pub observation_times: Option<Vec<f64>>,
+    /// Shift applied to the barrier of barrier payoffs before pricing in
+    /// [`mc_price_option_gbm`]
+    pub barrier_shift: Option<BarrierShift>,
+    /// Minimum number of paths per Rayon task in [`mc_price_option_gbm`];
+    /// `None` lets Rayon split freely. See
+    /// [`tune_batch_size`](crate::mc::tuning::tune_batch_size).
+    pub chunk_size: Option<usize>,
+    /// Thread pool of every entry point taking this config: the global
+    /// pool, a dedicated pool of `n` threads, or one thread. See
+    /// [`Parallelism`].
+    pub parallelism: Parallelism,
+    /// Stop stepping a path once its payoff is determined (e.g. a knocked-out
+    /// barrier) in [`mc_price_option_gbm`]
+    pub early_termination: bool,
+    /// Bitwise-reproducible results in [`mc_price_option_gbm`] regardless
+    /// of thread count or machine: Philox path streams and a fixed
+    /// reduction tree. Draws differ from the default `StdRng` streams.
+    pub deterministic: bool,
+    /// Derivation of the per-path `StdRng` streams from `seed`; ignored by
+    /// the Philox streams of `deterministic`
+    pub seed_strategy: SeedStrategy,
+    /// Normal draws of [`mc_price_option_gbm`]; `None` for independent
+    /// draws seeded by `seed`. Wrapped in [`Antithetic`] when
+    /// `use_antithetic` is set. Not serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub normal_source: Option<Arc<dyn NormalSource>>,
+    /// Order in which the draws of a path build its Brownian motion; the
+    /// bridge and PCA orders concentrate variance in the leading
+    /// dimensions of a quasi-random `normal_source`. See
+    /// [`crate::mc::path_construction`].
+    pub path_construction: PathConstruction,
+    /// Time-stepping scheme of [`mc_price_option_gbm`]. The control variate
+    /// is only used with [`Scheme::Exact`], the one scheme under which its
+    /// expectation is known.
+    pub scheme: Scheme,
+    /// Controls of `use_control_variate`, at most
+    /// [`MAX_CONTROLS`], combined by regression; empty for the
+    /// [`default_control`](crate::mc::control_variates::default_control)
+    /// of the payoff. Not serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub controls: Vec<Arc<dyn ControlVariate>>,
 }
 
 impl McConfig {
@@ -55,6 +163,140 @@ impl McConfig {
             }
         }
 
+        if self.parallelism == Parallelism::Threads(0) {
+            return Err(SdeError::InvalidConfiguration {
+                field: "parallelism".to_string(),
+                reason: "needs at least one thread".to_string(),
+            });
+        }
+
+        match self.barrier_shift {
+            Some(BarrierShift::Absolute(shift)) => validate_finite("barrier_shift", shift)?,
+            Some(BarrierShift::Relative(shift)) => {
+                validate_finite("barrier_shift", shift)?;
+                if shift <= -1.0 {
+                    return Err(SdeError::InvalidParameters {
+                        parameter: "barrier_shift".to_string(),
+                        value: shift,
+                        constraint: "relative shift must be greater than -1".to_string(),
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        if let Payoff::ForwardStartCall { start_index, alpha }
+        | Payoff::ForwardStartPut { start_index, alpha } = self.payoff
+        {
+            validate_positive("alpha", alpha)?;
+            let last_index = simulation_increments(self).len();
+            if start_index >= last_index {
+                return Err(SdeError::InvalidConfiguration {
+                    field: "start_index".to_string(),
+                    reason: format!("strike date must precede maturity (index < {})", last_index),
+                });
+            }
+        }
+
+        if let Payoff::PowerCall { k, p } = self.payoff {
+            validate_non_negative("k", k)?;
+            validate_positive("p", p)?;
+        }
+
+        if let Payoff::Chooser {
+            k,
+            choice_index,
+            choice_discount,
+        } = self.payoff
+        {
+            validate_positive("k", k)?;
+            validate_positive("choice_discount", choice_discount)?;
+            let last_index = simulation_increments(self).len();
+            if choice_index >= last_index {
+                return Err(SdeError::InvalidConfiguration {
+                    field: "choice_index".to_string(),
+                    reason: format!("choice date must precede maturity (index < {})", last_index),
+                });
+            }
+        }
+
+        if let Payoff::Autocallable {
+            observations,
+            notional,
+            knock_in,
+            put_strike,
+        } = &self.payoff
+        {
+            validate_positive("notional", *notional)?;
+            validate_non_negative("knock_in", *knock_in)?;
+            validate_positive("put_strike", *put_strike)?;
+            let last_index = simulation_increments(self).len();
+            let mut prev = None;
+            for obs in observations {
+                validate_positive("autocall_barrier", obs.autocall_barrier)?;
+                validate_finite("coupon", obs.coupon)?;
+                validate_positive("carry_to_maturity", obs.carry_to_maturity)?;
+                if obs.index == 0 || obs.index > last_index || prev >= Some(obs.index) {
+                    return Err(SdeError::InvalidConfiguration {
+                        field: "observations".to_string(),
+                        reason: format!(
+                            "observation indices must be strictly increasing in 1..={}",
+                            last_index
+                        ),
+                    });
+                }
+                prev = Some(obs.index);
+            }
+        }
+
+        if self.chunk_size == Some(0) {
+            return Err(SdeError::InvalidConfiguration {
+                field: "chunk_size".to_string(),
+                reason: "must be at least 1".to_string(),
+            });
+        }
+
+        if let Some(times) = &self.observation_times {
+            let increasing = times
+                .iter()
+                .try_fold(0.0, |prev, &t| (t > prev && t.is_finite()).then_some(t))
+                .is_some();
+            if times.is_empty() || !increasing {
+                return Err(SdeError::InvalidConfiguration {
+                    field: "observation_times".to_string(),
+                    reason: "must be non-empty, finite, positive and strictly increasing"
+                        .to_string(),
+                });
+            }
+            let last = *times.last().unwrap();
+            if (last - self.t).abs() > 1e-12 * self.t.max(1.0) {
+                return Err(SdeError::InvalidConfiguration {
+                    field: "observation_times".to_string(),
+                    reason: format!(
+                        "last observation {} must equal maturity t = {}",
+                        last, self.t
+                    ),
+                });
+            }
+        }
+
+        if self.controls.len() > MAX_CONTROLS {
+            return Err(SdeError::InvalidConfiguration {
+                field: "controls".to_string(),
+                reason: format!("at most {} control variates", MAX_CONTROLS),
+            });
+        }
+        if !self.controls.is_empty() && self.early_termination {
+            return Err(SdeError::InvalidConfiguration {
+                field: "control".to_string(),
+                reason: "control variates need full paths; disable early_termination".to_string(),
+            });
+        }
+
+        if let Some(source) = &self.normal_source {
+            source.validate(simulation_increments(self).len())?;
+        }
+
         Ok(())
     }
 }
@@ -74,6 +316,19 @@ impl Default for McConfig {
             payoff: Payoff::EuropeanCall { k: 100.0 },
             greeks: GreeksConfig::NONE,
             epsilon: None,
+            greek_method: GreekMethod::Pathwise,
+            smoothing: PayoffSmoothing::None,
+            observation_times: None,
+            barrier_shift: None,
+            chunk_size: None,
+            parallelism: Parallelism::Auto,
+            early_termination: false,
+            deterministic: false,
+            seed_strategy: SeedStrategy::Hashed,
+            normal_source: None,
+            path_construction: PathConstruction::Incremental,
+            scheme: Scheme::Exact,
+            controls: Vec::new(),
         }
     }
 }
@@ -93,378 +348,484 @@ impl Default for McConfig {
 /// ```
 /// where Z ~ N(0,1).
 ///
+/// # Sparse Observation Mode
+///
+/// Because each step uses the exact transition, the path only needs to be
+/// sampled where the payoff looks at it. With `observation_times` set, the
+/// path is `[S_0, S(t_1), ..., S(t_n)]` and `steps` is ignored: a monthly
+/// Asian needs 12 draws per path rather than a daily grid of 252.
+///
+/// # Barrier Shifts
+///
+/// With `barrier_shift` set, barrier payoffs are priced with the shifted
+/// barrier (see [`BarrierShift`]). The continuity correction uses the mean
+/// monitoring interval of the simulation grid.
+///
+/// # Early Termination
+///
+/// With `early_termination` set, a path stops at the first price at which
+/// its payoff is fixed ([`Payoff::is_determined_at`]), e.g. an up-and-out
+/// barrier touching `H`. The truncated path still ends at the knock-out
+/// point, so the payoff evaluates to the same value and every estimator
+/// sees the same per-path contribution, and the price is identical to the
+/// full-path run.
+///
 /// # Variance Reduction Techniques
 ///
 /// 1. **Antithetic Variates**: For each path with normal draw Z, also simulate
 ///    path with -Z and average the payoffs. Reduces variance for smooth payoffs.
+///    Stratified, quasi-random and moment-matched draws plug in through
+///    `normal_source` (see [`crate::mc::normal_source`]) and compose with it.
 ///
-/// 2. **Control Variates**: Uses European call as control with known expectation.
-///    Estimator: Y - b(X - E\[X\]) where:
+/// 2. **Control Variates**: `cfg.controls`, or the default control of the
+///    payoff (e.g. the European call), with known expectations.
+///    Estimator: Y - bᵀ(X - E\[X\]) where:
 ///    - Y = target payoff (e.g., Asian call)  
-///    - X = control payoff (European call)
-///    - b = Cov(Y,X)/Var(X) (optimal coefficient)
+///    - X = control payoffs (e.g. European call)
+///    - b = Σ_XX⁻¹ Σ_XY (optimal coefficients; Cov(Y,X)/Var(X) for one)
+///
+///    See [`crate::mc::control_variates`] for the built-in controls.
+///
+/// # Reproducibility
+///
+/// Block `i` of the normal source always draws from its own stream keyed
+/// by `(seed, i)` through `seed_strategy`, so the sample does not depend
+/// on scheduling. By default the Rayon reduction order does, which moves
+/// results in the last bits. With `deterministic`, paths are grouped into
+/// fixed chunks whose statistics are merged in a fixed pairwise tree, and
+/// path `i` draws from Philox4x32-10 stream `i` ([`rng::philox::Philox4x32`]),
+/// whose output is defined by the algorithm rather than by the `rand`
+/// version, so the same configuration gives bitwise-identical results
+/// everywhere.
+///
+/// # Accumulation
+///
+/// Means, variances and the payoff/control covariance are accumulated with
+/// Welford updates per Rayon task and merged with Chan's formulas
+/// ([`crate::mc::accumulators`]), so they stay accurate at millions of paths
+/// where `E[Y²] - E[Y]²` would cancel catastrophically.
 ///
 /// # Returns
 ///
 /// Returns `(price, variance_estimate)` where:
 /// - `price`: Discounted expected payoff
-/// - `variance_estimate`: Sample variance of the estimator (for confidence intervals)
+/// - `variance_estimate`: Variance of the price estimate, `s² / n` (see
+///   [`crate::mc::accumulators`]); its square root is the standard error
 ///
 /// # Errors
 ///
 /// Returns `SdeError` for:
 /// - Invalid configuration parameters
-/// - Numerical instability (negative variance, non-finite results)
+/// - Numerical instability (non-finite results)
 pub fn mc_price_option_gbm(cfg: &McConfig) -> SdeResult<(f64, f64)> {
     // Validate configuration
     cfg.validate()?;
-    let n = cfg.paths;
-    let dt = cfg.t / cfg.steps as f64;
-    let sqrt_dt = dt.sqrt();
-    let discount = (-cfg.r * cfg.t).exp();
-
-    let (
-        sum_payoff_path,
-        sum_control_path,
-        sum_payoff_times_control_path,
-        sum_control_sq_path,
-        sum_european_analytic_price_path,
-        sum_payoff_sq_path,
-    ) = (0..n)
-        .into_par_iter()
-        .map(|i| {
-            let mut rng = rng::seed_rng_from_u64(cfg.seed + i as u64);
+    cfg.parallelism.install(|| price_option_gbm(cfg))
+}
 
-            // Generate asset price path using exact GBM solution
-            // S_{t+dt} = S_t * exp((r - σ²/2)dt + σ√dt * Z_t)
-            // where Z_t ~ N(0,1) are independent normal draws
-            let mut path_prices = Vec::with_capacity(cfg.steps + 1);
-            path_prices.push(cfg.s0);
+/// [`mc_price_option_gbm`] on the pool of `cfg.parallelism`
+fn price_option_gbm(cfg: &McConfig) -> SdeResult<(f64, f64)> {
+    let _span = span!(
+        "mc_price_option_gbm",
+        paths = cfg.paths,
+        steps = cfg.steps,
+        antithetic = cfg.use_antithetic,
+        control_variate = cfg.use_control_variate
+    );
+
+    // Price the shifted contract: a barrier shift is just a different barrier
+    if let Some(shift) = cfg.barrier_shift {
+        let monitoring_dt = cfg.t / simulation_increments(cfg).len() as f64;
+        let shifted = McConfig {
+            payoff: cfg
+                .payoff
+                .with_barrier_shift(shift, cfg.sigma, monitoring_dt),
+            barrier_shift: None,
+            ..cfg.clone()
+        };
+        return mc_price_option_gbm(&shifted);
+    }
+    let grid = simulation_increments(cfg);
+    let discount = (-cfg.r * cfg.t).exp();
 
-            let mut current_s = cfg.s0;
-            for _ in 0..cfg.steps {
-                let z = rng::get_normal_draw(&mut rng);
-                // Apply exact GBM step: drift + diffusion
-                current_s *=
-                    ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * dt + cfg.sigma * sqrt_dt * z).exp();
-                path_prices.push(current_s);
-            }
-            
-            // Calculate the payoff for this path
-            let payoff_raw = cfg.payoff.calculate(&path_prices);
-            
-            // Control Variate Setup
-            // For variance reduction, we use a control variate with known expectation
-            let mut control_var_raw = 0.0;
-            let mut european_analytic_price = 0.0;
-
-            match cfg.payoff {
-                Payoff::EuropeanCall { k } => {
-                    // For European calls, the control is itself (perfect control)
-                    control_var_raw = Payoff::EuropeanCall { k }.calculate(&path_prices);
-                    european_analytic_price =
-                        bs_analytic::bs_call_price(cfg.s0, k, cfg.r, cfg.sigma, cfg.t);
-                }
-                Payoff::AsianCall { k } => {
-                    // For Asian calls, use European call on terminal price as control
-                    // Theory: Both depend on final price, providing positive correlation
-                    let st_final = *path_prices.last().unwrap();
-                    control_var_raw = Payoff::EuropeanCall { k }.calculate(&vec![st_final]);
-                    european_analytic_price =
-                        bs_analytic::bs_call_price(cfg.s0, k, cfg.r, cfg.sigma, cfg.t);
-                }
-                _ => {
-                    // For barrier and other exotic options, control variates are more complex
-                    // Future enhancement: implement specific controls for each payoff type
-                }
-            }
+    // Controls with known expectations E[X]
+    let controls = engine_controls(cfg);
+
+    // Welford/Chan statistics of (payoff, controls), merged across Rayon tasks
+    let source = engine_normal_source(cfg);
+
+    let moments = {
+        let _span = span!("simulate");
+        reduce_paths(
+            cfg,
+            |i, buffers| payoff_and_control(cfg, &grid, &source, &controls, i, buffers),
+            || PathMoments::new(controls.len() + 1),
+            |acc, (payoff, values)| push_sample(acc, payoff, &values),
+            PathMoments::merge,
+        )
+    };
 
-            let mut payoff_path = payoff_raw;
-            let mut control_var_path = control_var_raw;
-
-            // Antithetic Variates Implementation
-            // Generate second path with negated normal draws for variance reduction
-            if cfg.use_antithetic {
-                let mut path_prices2 = Vec::with_capacity(cfg.steps + 1);
-                path_prices2.push(cfg.s0);
-
-                let mut current_s2 = cfg.s0;
-                for _ in 0..cfg.steps {
-                    // Use -Z instead of Z for antithetic path
-                    // Theory: E[f(Z) + f(-Z)]/2 has lower variance than E[f(Z)] for symmetric f
-                    let z2 = -rng::get_normal_draw(&mut rng);
-                    current_s2 *= ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * dt
-                        + cfg.sigma * sqrt_dt * z2)
-                        .exp();
-                    path_prices2.push(current_s2);
-                }
-                
-                let payoff2_raw = cfg.payoff.calculate(&path_prices2);
-                
-                let mut control_var2_raw = 0.0;
-                match cfg.payoff {
-                    Payoff::EuropeanCall { k } => {
-                        control_var2_raw = Payoff::EuropeanCall { k }.calculate(&path_prices2);
-                    }
-                    Payoff::AsianCall { k } => {
-                        let st2_final = *path_prices2.last().unwrap();
-                        control_var2_raw = Payoff::EuropeanCall { k }.calculate(&vec![st2_final]);
-                    }
-                    _ => {}
-                }
-                
-                // Average the original and antithetic payoffs
-                // This is the antithetic variate estimator: (Y₁ + Y₂)/2
-                payoff_path = 0.5 * (payoff_raw + payoff2_raw);
-                control_var_path = 0.5 * (control_var_raw + control_var2_raw);
-            }
+    let estimated_price;
+    let variance_of_estimate;
 
-            (
-                payoff_path,
-                control_var_path,
-                payoff_path * control_var_path,
-                control_var_path * control_var_path,
-                european_analytic_price,
-                payoff_path * payoff_path,
-            )
-        })
-        .reduce(
-            || (0.0, 0.0, 0.0, 0.0, 0.0, 0.0),
-            |a, b| {
-                (
-                    a.0 + b.0,
-                    a.1 + b.1,
-                    a.2 + b.2,
-                    a.3 + b.3,
-                    a.4 + b.4,
-                    a.5 + b.5,
-                )
+    // Control Variate Method Implementation
+    // Estimator: Y - bᵀ(X - E[X]) where b minimizes variance
+    if !controls.is_empty() {
+        // Optimal coefficients: b* = Σ_XX⁻¹ Σ_XY, which minimizes
+        // Var(Y - bᵀ(X - E[X])); Cov(Y,X) / Var(X) for a single control
+        let fit = ControlFit::new(cfg, &controls, &moments);
+
+        let _span = span!("control_variate", controls = controls.len());
+        let controlled = reduce_paths(
+            cfg,
+            |i, buffers| {
+                let (payoff_path, control_values) =
+                    payoff_and_control(cfg, &grid, &source, &controls, i, buffers);
+                discount * fit.adjust(payoff_path, &control_values)
             },
+            Moments::new,
+            Moments::push,
+            Moments::merge,
         );
 
-    // Compute sample statistics for control variate method
-    let mean_payoff = sum_payoff_path / n as f64;
-    let mean_control = sum_control_path / n as f64;
-    let mean_payoff_times_control = sum_payoff_times_control_path / n as f64;
-    let mean_control_sq = sum_control_sq_path / n as f64;
-    let mean_european_analytic_price = sum_european_analytic_price_path / n as f64;
-    let mean_payoff_sq = sum_payoff_sq_path / n as f64;
-
-    let estimated_price;
-    let mut variance_of_estimate;
+        estimated_price = controlled.mean();
+        variance_of_estimate = controlled.variance_of_mean();
+    } else {
+        estimated_price = discount * moments.mean(0);
+        variance_of_estimate = discount.powi(2)
+            * accumulators::variance_of_mean(moments.population_covariance(0, 0), moments.count());
+    }
 
-    // Control Variate Method Implementation
-    // Estimator: Y - b(X - E[X]) where b minimizes variance
-    if cfg.use_control_variate {
-        // Optimal control variate coefficient: b* = Cov(Y,X) / Var(X)
-        // This minimizes Var(Y - b(X - E[X]))
-        let cov_payoff_control = mean_payoff_times_control - mean_payoff * mean_control;
-        let var_control = mean_control_sq - mean_control * mean_control;
-
-        // Avoid division by zero if control has no variance
-        let b = if var_control > 1e-10 {
-            cov_payoff_control / var_control
-        } else {
-            0.0
-        };
+    // Final validation of results
+    if !estimated_price.is_finite() {
+        return Err(SdeError::NumericalInstability {
+            method: "Monte Carlo".to_string(),
+            reason: format!("Price estimate is not finite: {}", estimated_price),
+        });
+    }
 
-        let controlled_payoffs_sum = (0..n)
-            .into_par_iter()
-            .map(|i| {
-                let mut rng = rng::seed_rng_from_u64(cfg.seed + i as u64);
-                let mut path_prices = Vec::with_capacity(cfg.steps + 1);
-                path_prices.push(cfg.s0);
-
-                let mut current_s = cfg.s0;
-                for _ in 0..cfg.steps {
-                    let z = rng::get_normal_draw(&mut rng);
-                    current_s *= ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * dt
-                        + cfg.sigma * sqrt_dt * z)
-                        .exp();
-                    path_prices.push(current_s);
-                }
+    if !variance_of_estimate.is_finite() {
+        return Err(SdeError::NumericalInstability {
+            method: "Monte Carlo".to_string(),
+            reason: format!("Variance estimate is not finite: {}", variance_of_estimate),
+        });
+    }
 
-                let payoff_raw = cfg.payoff.calculate(&path_prices);
-                
-                let mut control_var_raw = 0.0;
-                match cfg.payoff {
-                    Payoff::EuropeanCall { k } => {
-                        control_var_raw = Payoff::EuropeanCall { k }.calculate(&path_prices);
-                    }
-                    Payoff::AsianCall { k } => {
-                        let st_final = *path_prices.last().unwrap();
-                        control_var_raw = Payoff::EuropeanCall { k }.calculate(&vec![st_final]);
-                    }
-                    _ => {}
-                }
+    debug_event!(
+        price = estimated_price,
+        variance = variance_of_estimate,
+        "priced"
+    );
+    Ok((estimated_price, variance_of_estimate))
+}
 
-                let mut payoff_path = payoff_raw;
-                let mut control_var_path = control_var_raw;
+/// Paths per chunk of the fixed reduction tree when `chunk_size` is unset
+const DETERMINISTIC_CHUNK: usize = 1024;
+
+/// Normal source of the engine: `cfg.normal_source`, or
+/// [`PseudoRandom`] draws from `cfg.seed`, built into paths by
+/// `cfg.path_construction` and made antithetic with `cfg.use_antithetic`
+pub(crate) fn engine_normal_source(cfg: &McConfig) -> Arc<dyn NormalSource> {
+    let mut base = cfg.normal_source.clone().unwrap_or_else(|| {
+        Arc::new(PseudoRandom {
+            seed: cfg.seed,
+            counter_based: cfg.deterministic,
+            strategy: cfg.seed_strategy,
+        })
+    });
+    if cfg.path_construction != PathConstruction::Incremental {
+        let builder = PathBuilder::new(cfg.path_construction, &simulation_increments(cfg));
+        base = Arc::new(PathConstructed {
+            inner: base,
+            builder: Arc::new(builder),
+        });
+    }
+    if cfg.use_antithetic {
+        Arc::new(Antithetic { inner: base })
+    } else {
+        base
+    }
+}
 
-                if cfg.use_antithetic {
-                    let mut path_prices2 = Vec::with_capacity(cfg.steps + 1);
-                    path_prices2.push(cfg.s0);
-
-                    let mut current_s2 = cfg.s0;
-                    for _ in 0..cfg.steps {
-                        let z2 = -rng::get_normal_draw(&mut rng);
-                        current_s2 *= ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * dt
-                            + cfg.sigma * sqrt_dt * z2)
-                            .exp();
-                        path_prices2.push(current_s2);
-                    }
+/// Reusable storage for one block of draws and one path
+#[derive(Default)]
+pub(crate) struct BlockBuffers {
+    draws: Vec<f64>,
+    path: Vec<f64>,
+}
 
-                    let payoff2_raw = cfg.payoff.calculate(&path_prices2);
-                    
-                    let mut control_var2_raw = 0.0;
-                    match cfg.payoff {
-                        Payoff::EuropeanCall { k } => {
-                            control_var2_raw = Payoff::EuropeanCall { k }.calculate(&path_prices2);
-                        }
-                        Payoff::AsianCall { k } => {
-                            let st2_final = *path_prices2.last().unwrap();
-                            control_var2_raw =
-                                Payoff::EuropeanCall { k }.calculate(&vec![st2_final]);
-                        }
-                        _ => {}
-                    }
-                    payoff_path = 0.5 * (payoff_raw + payoff2_raw);
-                    control_var_path = 0.5 * (control_var_raw + control_var2_raw);
-                }
+thread_local! {
+    static SCRATCH: RefCell<BlockBuffers> = RefCell::default();
+}
 
-                discount * (payoff_path - b * (control_var_path - mean_european_analytic_price))
-            })
-            .sum::<f64>();
-        
-        let mean_controlled_payoff = controlled_payoffs_sum / n as f64;
-        let sum_controlled_payoff_sq = (0..n)
-            .into_par_iter()
-            .map(|i| {
-                let mut rng = rng::seed_rng_from_u64(cfg.seed + i as u64);
-                let mut path_prices = Vec::with_capacity(cfg.steps + 1);
-                path_prices.push(cfg.s0);
-
-                let mut current_s = cfg.s0;
-                for _ in 0..cfg.steps {
-                    let z = rng::get_normal_draw(&mut rng);
-                    current_s *= ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * dt
-                        + cfg.sigma * sqrt_dt * z)
-                        .exp();
-                    path_prices.push(current_s);
-                }
+/// Run `f` with this thread's scratch buffers, which persist across calls
+/// so a worker allocates them once however many tasks it runs; a nested
+/// call on the same thread gets fresh buffers
+pub(crate) fn with_scratch<R>(f: impl FnOnce(&mut BlockBuffers) -> R) -> R {
+    SCRATCH.with(|scratch| match scratch.try_borrow_mut() {
+        Ok(mut buffers) => f(&mut buffers),
+        Err(_) => f(&mut BlockBuffers::default()),
+    })
+}
 
-                let payoff_raw = cfg.payoff.calculate(&path_prices);
-                
-                let mut control_var_raw = 0.0;
-                match cfg.payoff {
-                    Payoff::EuropeanCall { k } => {
-                        control_var_raw = Payoff::EuropeanCall { k }.calculate(&path_prices);
-                    }
-                    Payoff::AsianCall { k } => {
-                        let st_final = *path_prices.last().unwrap();
-                        control_var_raw = Payoff::EuropeanCall { k }.calculate(&vec![st_final]);
-                    }
-                    _ => {}
-                }
+/// Simulate every sample path of block `block` of `source` and pass each
+/// to `visit`
+pub(crate) fn for_each_block_path(
+    cfg: &McConfig,
+    grid: &[f64],
+    source: &dyn NormalSource,
+    block: usize,
+    buffers: &mut BlockBuffers,
+    mut visit: impl FnMut(&[f64]),
+) {
+    let dim = grid.len();
+    buffers.draws.resize(source.block_size() * dim, 0.0);
+    source.fill_block(block, dim, &mut buffers.draws);
+    for draws in buffers.draws.chunks(dim) {
+        simulate_gbm_path_from_draws(cfg, grid, draws, &mut buffers.path);
+        visit(&buffers.path);
+    }
+}
 
-                let mut payoff_path = payoff_raw;
-                let mut control_var_path = control_var_raw;
+/// Evaluate `sample` on every path and combine the results into one
+/// accumulator
+///
+/// Each worker thread reuses its scratch buffers ([`with_scratch`]). With
+/// `cfg.deterministic` the paths are split into fixed chunks, accumulated
+/// in order within each chunk and merged pairwise in a fixed tree, so the
+/// floating-point result does not depend on how Rayon schedules the work.
+pub(crate) fn reduce_paths<T, A>(
+    cfg: &McConfig,
+    sample: impl Fn(usize, &mut BlockBuffers) -> T + Sync,
+    identity: impl Fn() -> A + Sync + Send,
+    push: impl Fn(&mut A, T) + Sync + Send,
+    merge: impl Fn(&mut A, A) + Sync + Send,
+) -> A
+where
+    T: Send,
+    A: Send,
+{
+    let paths = 0..cfg.paths;
+    reduce_path_range(cfg, paths, cfg.deterministic, sample, identity, push, merge)
+}
 
-                if cfg.use_antithetic {
-                    let mut path_prices2 = Vec::with_capacity(cfg.steps + 1);
-                    path_prices2.push(cfg.s0);
-
-                    let mut current_s2 = cfg.s0;
-                    for _ in 0..cfg.steps {
-                        let z2 = -rng::get_normal_draw(&mut rng);
-                        current_s2 *= ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * dt
-                            + cfg.sigma * sqrt_dt * z2)
-                            .exp();
-                        path_prices2.push(current_s2);
-                    }
+/// [`reduce_paths`] over the paths `range` only, with the fixed reduction
+/// tree whenever `fixed_tree` is set (its chunks start at `range.start`)
+pub(crate) fn reduce_path_range<T, A>(
+    cfg: &McConfig,
+    range: std::ops::Range<usize>,
+    fixed_tree: bool,
+    sample: impl Fn(usize, &mut BlockBuffers) -> T + Sync,
+    identity: impl Fn() -> A + Sync + Send,
+    push: impl Fn(&mut A, T) + Sync + Send,
+    merge: impl Fn(&mut A, A) + Sync + Send,
+) -> A
+where
+    T: Send,
+    A: Send,
+{
+    if !fixed_tree {
+        return range
+            .into_par_iter()
+            .with_min_len(cfg.chunk_size.unwrap_or(1))
+            .map(|i| with_scratch(|buffers| sample(i, buffers)))
+            .fold(&identity, |mut acc, y| {
+                push(&mut acc, y);
+                acc
+            })
+            .reduce(&identity, |mut a, b| {
+                merge(&mut a, b);
+                a
+            });
+    }
 
-                    let payoff2_raw = cfg.payoff.calculate(&path_prices2);
-                    
-                    let mut control_var2_raw = 0.0;
-                    match cfg.payoff {
-                        Payoff::EuropeanCall { k } => {
-                            control_var2_raw = Payoff::EuropeanCall { k }.calculate(&path_prices2);
-                        }
-                        Payoff::AsianCall { k } => {
-                            let st2_final = *path_prices2.last().unwrap();
-                            control_var2_raw =
-                                Payoff::EuropeanCall { k }.calculate(&vec![st2_final]);
-                        }
-                        _ => {}
-                    }
-                    payoff_path = 0.5 * (payoff_raw + payoff2_raw);
-                    control_var_path = 0.5 * (control_var_raw + control_var2_raw);
+    let chunk = cfg.chunk_size.unwrap_or(DETERMINISTIC_CHUNK).max(1);
+    let (start, n) = (range.start, range.len());
+    let mut level: Vec<A> = (0..(n + chunk - 1) / chunk)
+        .into_par_iter()
+        .map(|c| {
+            with_scratch(|buffers| {
+                let mut acc = identity();
+                for i in start + c * chunk..start + ((c + 1) * chunk).min(n) {
+                    push(&mut acc, sample(i, buffers));
                 }
-
-                let controlled_payoff = discount
-                    * (payoff_path - b * (control_var_path - mean_european_analytic_price));
-                controlled_payoff * controlled_payoff
+                acc
             })
-            .sum::<f64>()
-            / n as f64;
-
-        estimated_price = mean_controlled_payoff;
-        variance_of_estimate = (sum_controlled_payoff_sq
-            - mean_controlled_payoff * mean_controlled_payoff)
-            / (n as f64 * (n as f64 - 1.0));
-
-        // Handle numerical precision issues that can cause negative variance
-        if variance_of_estimate < 0.0 {
-            if variance_of_estimate > -1e-10 {
-                // Small negative due to floating point precision - set to zero
-                variance_of_estimate = 0.0;
-            } else {
-                return Err(SdeError::NumericalInstability {
-                    method: "Control Variate Monte Carlo".to_string(),
-                    reason: format!(
-                        "Variance estimate became significantly negative: {}",
-                        variance_of_estimate
-                    ),
-                });
+        })
+        .collect();
+    while level.len() > 1 {
+        let mut next = Vec::with_capacity((level.len() + 1) / 2);
+        let mut items = level.into_iter();
+        while let Some(mut a) = items.next() {
+            if let Some(b) = items.next() {
+                merge(&mut a, b);
             }
+            next.push(a);
         }
-    } else {
-        estimated_price = discount * mean_payoff;
-        variance_of_estimate = (mean_payoff_sq - mean_payoff * mean_payoff) * discount.powi(2)
-            / (n as f64 * (n as f64 - 1.0));
-
-        // Handle numerical precision issues for regular MC too
-        if variance_of_estimate < 0.0 {
-            if variance_of_estimate > -1e-10 {
-                variance_of_estimate = 0.0;
-            } else {
-                return Err(SdeError::NumericalInstability {
-                    method: "Monte Carlo".to_string(),
-                    reason: format!(
-                        "Variance estimate became significantly negative: {}",
-                        variance_of_estimate
-                    ),
-                });
-            }
+        level = next;
+    }
+    level.pop().unwrap_or_else(identity)
+}
+
+/// Undiscounted payoff and control variates of one block of the normal
+/// source (e.g. an antithetic pair), averaged over its sample paths
+///
+/// Control values beyond `controls.len()` are zero.
+pub(crate) fn payoff_and_control(
+    cfg: &McConfig,
+    grid: &[f64],
+    source: &dyn NormalSource,
+    controls: &[Arc<dyn ControlVariate>],
+    block: usize,
+    buffers: &mut BlockBuffers,
+) -> (f64, ControlValues) {
+    let mut payoff_sum = 0.0;
+    let mut control_sums = [0.0; MAX_CONTROLS];
+    let m = source.block_size() as f64;
+    if controls.is_empty() {
+        // Without controls the path itself is never needed
+        let dim = grid.len();
+        buffers.draws.resize(source.block_size() * dim, 0.0);
+        source.fill_block(block, dim, &mut buffers.draws);
+        for draws in buffers.draws.chunks(dim) {
+            payoff_sum += gbm_payoff_from_draws(cfg, grid, draws);
         }
+        return (payoff_sum / m, control_sums);
     }
+    for_each_block_path(cfg, grid, source, block, buffers, |path| {
+        // Calculate the payoff for this path
+        payoff_sum += cfg.payoff.calculate(path);
+
+        // Control Variate Setup
+        // For variance reduction, we use controls with known expectations
+        let values = sample_controls(controls, cfg, grid, path);
+        for (sum, value) in control_sums.iter_mut().zip(values) {
+            *sum += value;
+        }
+    });
 
-    // Final validation of results
-    if !estimated_price.is_finite() {
-        return Err(SdeError::NumericalInstability {
-            method: "Monte Carlo".to_string(),
-            reason: format!("Price estimate is not finite: {}", estimated_price),
-        });
+    // Averaging the samples of a block gives one i.i.d. observation, e.g.
+    // the antithetic variate estimator (Y₁ + Y₂)/2
+    control_sums.iter_mut().for_each(|sum| *sum /= m);
+    (payoff_sum / m, control_sums)
+}
+
+/// The payoff actually priced: `cfg.payoff` with `cfg.barrier_shift` applied
+pub(crate) fn priced_payoff(cfg: &McConfig) -> Payoff {
+    match cfg.barrier_shift {
+        Some(shift) => {
+            let monitoring_dt = cfg.t / simulation_increments(cfg).len() as f64;
+            cfg.payoff
+                .with_barrier_shift(shift, cfg.sigma, monitoring_dt)
+        }
+        None => cfg.payoff.clone(),
     }
+}
 
-    if !variance_of_estimate.is_finite() {
-        return Err(SdeError::NumericalInstability {
-            method: "Monte Carlo".to_string(),
-            reason: format!("Variance estimate is not finite: {}", variance_of_estimate),
-        });
+/// Time increments of the simulation grid
+///
+/// A uniform grid of `steps` increments, or the gaps between consecutive
+/// `observation_times` (starting from 0) in sparse observation mode.
+pub(crate) fn simulation_increments(cfg: &McConfig) -> Vec<f64> {
+    match &cfg.observation_times {
+        Some(times) => {
+            let mut prev = 0.0;
+            times
+                .iter()
+                .map(|&t| {
+                    let dt = t - prev;
+                    prev = t;
+                    dt
+                })
+                .collect()
+        }
+        None => vec![cfg.t / cfg.steps as f64; cfg.steps],
+    }
+}
+
+/// Simulate `[S_0, S_1, ..., S_n]` on the grid of increments `dts` from
+/// the normal draws `draws`, into `path` (cleared first)
+///
+/// With [`Scheme::Exact`] this is the exact GBM transition, so any grid
+/// spacing is free of discretization error:
+/// ```text
+/// S_{t+dt} = S_t * exp((r - σ²/2)dt + σ√dt * Z_t)
+/// ```
+/// The other schemes step `dS = rS dt + σS dW` with `dW = √dt * Z_t`;
+/// [`Scheme::Srk`], which converges to the Stratonovich solution, steps the
+/// equivalent `dS = (r - σ²/2)S dt + σS ∘ dW`.
+/// With `cfg.early_termination`, the path ends at the first price that
+/// determines the payoff.
+pub(crate) fn simulate_gbm_path_from_draws(
+    cfg: &McConfig,
+    dts: &[f64],
+    draws: &[f64],
+    path: &mut Vec<f64>,
+) {
+    path.clear();
+    path.push(cfg.s0);
+    let mut current_s = cfg.s0;
+    let mut t = 0.0;
+    let step = gbm_stepper(cfg);
+    for (&dt, &z) in dts.iter().zip(draws) {
+        step(&mut current_s, t, dt, z);
+        t += dt;
+        path.push(current_s);
+        if cfg.early_termination && cfg.payoff.is_determined_at(current_s) {
+            break;
+        }
     }
+}
 
-    Ok((estimated_price, variance_of_estimate))
+/// Payoff of the path of [`simulate_gbm_path_from_draws`], without
+/// storing the path
+///
+/// Every payoff takes all the steps of `dts`. Terminal-only payoffs carry
+/// only the spot and are priced once at `S_T`
+/// ([`Payoff::calculate_terminal`]); path-dependent ones update a
+/// [`PayoffState`](crate::mc::payoffs::PayoffState) at every step, so the
+/// memory used is independent of the number of steps.
+pub(crate) fn gbm_payoff_from_draws(cfg: &McConfig, dts: &[f64], draws: &[f64]) -> f64 {
+    let mut current_s = cfg.s0;
+    let mut t = 0.0;
+    let step = gbm_stepper(cfg);
+    if !cfg.payoff.requires_full_path() {
+        for (&dt, &z) in dts.iter().zip(draws) {
+            step(&mut current_s, t, dt, z);
+            t += dt;
+        }
+        return cfg.payoff.calculate_terminal(current_s).unwrap_or_default();
+    }
+    let mut state = cfg.payoff.start();
+    state.observe(current_s);
+    for (&dt, &z) in dts.iter().zip(draws) {
+        step(&mut current_s, t, dt, z);
+        t += dt;
+        state.observe(current_s);
+        if cfg.early_termination && state.is_determined() {
+            break;
+        }
+    }
+    state.value()
+}
+
+/// One step of `cfg.scheme`: `step(s, t, dt, z)` advances `s` from `t` to
+/// `t + dt` with the standard normal draw `z`
+fn gbm_stepper(cfg: &McConfig) -> impl Fn(&mut f64, f64, f64, f64) {
+    let drift = cfg.r - 0.5 * cfg.sigma * cfg.sigma;
+    let model = Gbm::new(cfg.s0, cfg.r, cfg.sigma);
+    let stratonovich = Gbm::new(cfg.s0, drift, cfg.sigma);
+    let (sigma, scheme) = (cfg.sigma, cfg.scheme);
+    move |current_s: &mut f64, t: f64, dt: f64, z: f64| {
+        let dw = dt.sqrt() * z;
+        match scheme {
+            Scheme::Exact => *current_s *= (drift * dt + sigma * dt.sqrt() * z).exp(),
+            Scheme::EulerMaruyama => EulerMaruyama::step_with_dw(&model, current_s, t, dt, dw),
+            Scheme::Milstein => Milstein::step_with_dw(&model, current_s, t, dt, dw),
+            Scheme::Srk => Srk::step_with_dw(&stratonovich, current_s, t, dt, dw),
+            Scheme::NinomiyaVictoir => NinomiyaVictoir::step_with_dw(&model, current_s, t, dt, dw),
+        }
+    }
 }
 
 /// Monte Carlo Delta calculation using pathwise derivative method
@@ -501,37 +862,39 @@ pub fn mc_delta_european_call_gbm_pathwise(cfg: &McConfig) -> f64 {
         }
     };
 
-    (0..n)
-        .into_par_iter()
-        .map(|i| {
-            let mut rng = rng::seed_rng_from_u64(cfg.seed + i as u64);
-            let z = rng::get_normal_draw(&mut rng);
-
-            let st = cfg.s0
-                * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * cfg.t.sqrt() * z)
-                    .exp();
-            
-            let mut delta_path = 0.0;
-            if st > k {
-                delta_path = st / cfg.s0;
-            }
+    cfg.parallelism.install(|| {
+        (0..n)
+            .into_par_iter()
+            .map(|i| {
+                let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+                let z = rng::get_normal_draw(&mut rng);
 
-            if cfg.use_antithetic {
-                let z2 = -z;
-                let st2 = cfg.s0
+                let st = cfg.s0
                     * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t
-                        + cfg.sigma * cfg.t.sqrt() * z2)
+                        + cfg.sigma * cfg.t.sqrt() * z)
                         .exp();
-                let mut delta_path2 = 0.0;
-                if st2 > k {
-                    delta_path2 = st2 / cfg.s0;
+
+                let mut delta_path = 0.0;
+                if st > k {
+                    delta_path = st / cfg.s0;
                 }
-                delta_path = 0.5 * (delta_path + delta_path2);
-            }
-            delta_path
-        })
-        .reduce(|| 0.0, |a, b| a + b)
-        / n as f64
+
+                if cfg.use_antithetic {
+                    let z2 = -z;
+                    let st2 = cfg.s0
+                        * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t
+                            + cfg.sigma * cfg.t.sqrt() * z2)
+                            .exp();
+                    let mut delta_path2 = 0.0;
+                    if st2 > k {
+                        delta_path2 = st2 / cfg.s0;
+                    }
+                    delta_path = 0.5 * (delta_path + delta_path2);
+                }
+                delta_path
+            })
+            .reduce(|| 0.0, |a, b| a + b)
+    }) / n as f64
         * discount
 }
 
@@ -573,40 +936,41 @@ pub fn mc_vega_european_call_gbm_pathwise(cfg: &McConfig) -> f64 {
     };
 
     // For single-step European option, we accumulate W_T directly
-    (0..n)
-        .into_par_iter()
-        .map(|i| {
-            let mut rng = rng::seed_rng_from_u64(cfg.seed + i as u64);
-            let z = rng::get_normal_draw(&mut rng);
-            let w_t = sqrt_t * z; // W_T = sqrt(T) * Z where Z ~ N(0,1)
-
-            let st =
-                cfg.s0 * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * w_t).exp();
-
-            let mut vega_path = 0.0;
-            if st > k {
-                // dS_T/dsigma = S_T * (-sigma * T + W_T)
-                let ds_dsigma = st * (-cfg.sigma * cfg.t + w_t);
-                vega_path = ds_dsigma;
-            }
+    cfg.parallelism.install(|| {
+        (0..n)
+            .into_par_iter()
+            .map(|i| {
+                let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+                let z = rng::get_normal_draw(&mut rng);
+                let w_t = sqrt_t * z; // W_T = sqrt(T) * Z where Z ~ N(0,1)
 
-            if cfg.use_antithetic {
-                let z2 = -z;
-                let w_t2 = sqrt_t * z2;
-                let st2 = cfg.s0
-                    * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * w_t2).exp();
+                let st = cfg.s0
+                    * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * w_t).exp();
 
-                let mut vega_path2 = 0.0;
-                if st2 > k {
-                    let ds_dsigma2 = st2 * (-cfg.sigma * cfg.t + w_t2);
-                    vega_path2 = ds_dsigma2;
+                let mut vega_path = 0.0;
+                if st > k {
+                    // dS_T/dsigma = S_T * (-sigma * T + W_T)
+                    let ds_dsigma = st * (-cfg.sigma * cfg.t + w_t);
+                    vega_path = ds_dsigma;
                 }
-                vega_path = 0.5 * (vega_path + vega_path2);
-            }
-            vega_path
-        })
-        .reduce(|| 0.0, |a, b| a + b)
-        / n as f64
+
+                if cfg.use_antithetic {
+                    let z2 = -z;
+                    let w_t2 = sqrt_t * z2;
+                    let st2 = cfg.s0
+                        * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * w_t2).exp();
+
+                    let mut vega_path2 = 0.0;
+                    if st2 > k {
+                        let ds_dsigma2 = st2 * (-cfg.sigma * cfg.t + w_t2);
+                        vega_path2 = ds_dsigma2;
+                    }
+                    vega_path = 0.5 * (vega_path + vega_path2);
+                }
+                vega_path
+            })
+            .reduce(|| 0.0, |a, b| a + b)
+    }) / n as f64
         * discount
 }
 
@@ -647,40 +1011,42 @@ pub fn mc_rho_european_call_gbm_pathwise(cfg: &McConfig) -> f64 {
         }
     };
 
-    (0..n)
-        .into_par_iter()
-        .map(|i| {
-            let mut rng = rng::seed_rng_from_u64(cfg.seed + i as u64);
-            let z = rng::get_normal_draw(&mut rng);
+    cfg.parallelism.install(|| {
+        (0..n)
+            .into_par_iter()
+            .map(|i| {
+                let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+                let z = rng::get_normal_draw(&mut rng);
 
-            let st = cfg.s0
-                * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * sqrt_t * z).exp();
+                let st = cfg.s0
+                    * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * sqrt_t * z)
+                        .exp();
 
-            let payoff = (st - k).max(0.0);
-            let indicator = if st > k { 1.0 } else { 0.0 };
+                let payoff = (st - k).max(0.0);
+                let indicator = if st > k { 1.0 } else { 0.0 };
 
-            // Rho = -T * e^(-rT) * payoff + e^(-rT) * indicator * dS_T/dr
-            // where dS_T/dr = S_T * T
-            let ds_dr = st * cfg.t;
-            let mut rho_path = -cfg.t * payoff + indicator * ds_dr;
+                // Rho = -T * e^(-rT) * payoff + e^(-rT) * indicator * dS_T/dr
+                // where dS_T/dr = S_T * T
+                let ds_dr = st * cfg.t;
+                let mut rho_path = -cfg.t * payoff + indicator * ds_dr;
 
-            if cfg.use_antithetic {
-                let z2 = -z;
-                let st2 = cfg.s0
-                    * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * sqrt_t * z2)
-                        .exp();
+                if cfg.use_antithetic {
+                    let z2 = -z;
+                    let st2 = cfg.s0
+                        * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * sqrt_t * z2)
+                            .exp();
 
-                let payoff2 = (st2 - k).max(0.0);
-                let indicator2 = if st2 > k { 1.0 } else { 0.0 };
-                let ds_dr2 = st2 * cfg.t;
-                let rho_path2 = -cfg.t * payoff2 + indicator2 * ds_dr2;
+                    let payoff2 = (st2 - k).max(0.0);
+                    let indicator2 = if st2 > k { 1.0 } else { 0.0 };
+                    let ds_dr2 = st2 * cfg.t;
+                    let rho_path2 = -cfg.t * payoff2 + indicator2 * ds_dr2;
 
-                rho_path = 0.5 * (rho_path + rho_path2);
-            }
-            rho_path
-        })
-        .reduce(|| 0.0, |a, b| a + b)
-        / n as f64
+                    rho_path = 0.5 * (rho_path + rho_path2);
+                }
+                rho_path
+            })
+            .reduce(|| 0.0, |a, b| a + b)
+    }) / n as f64
         * discount
 }
 
@@ -781,51 +1147,55 @@ pub fn mc_gamma_european_call_gbm_finite_diff_batched(cfg: &McConfig) -> f64 {
     let s0_down = cfg.s0 - epsilon;
 
     // Compute deltas for both spot scenarios using common random numbers
-    let (sum_delta_up, sum_delta_down) = (0..n)
-        .into_par_iter()
-        .map(|i| {
-            // Use the same RNG seed for both scenarios to ensure common random numbers
-            let mut rng = rng::seed_rng_from_u64(cfg.seed + i as u64);
-            let z = rng::get_normal_draw(&mut rng);
+    let (sum_delta_up, sum_delta_down) = cfg.parallelism.install(|| {
+        (0..n)
+            .into_par_iter()
+            .map(|i| {
+                // Use the same RNG seed for both scenarios to ensure common random numbers
+                let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+                let z = rng::get_normal_draw(&mut rng);
 
-            // Compute terminal stock prices for both spot scenarios
-            let st_up = s0_up
-                * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * sqrt_t * z).exp();
-            let st_down = s0_down
-                * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * sqrt_t * z).exp();
+                // Compute terminal stock prices for both spot scenarios
+                let st_up = s0_up
+                    * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * sqrt_t * z)
+                        .exp();
+                let st_down = s0_down
+                    * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * sqrt_t * z)
+                        .exp();
 
-            // Pathwise delta for spot up
-            let delta_up = if st_up > k { st_up / s0_up } else { 0.0 };
+                // Pathwise delta for spot up
+                let delta_up = if st_up > k { st_up / s0_up } else { 0.0 };
 
-            // Pathwise delta for spot down
-            let delta_down = if st_down > k { st_down / s0_down } else { 0.0 };
+                // Pathwise delta for spot down
+                let delta_down = if st_down > k { st_down / s0_down } else { 0.0 };
 
-            if cfg.use_antithetic {
-                let z2 = -z;
+                if cfg.use_antithetic {
+                    let z2 = -z;
 
-                let st_up2 = s0_up
-                    * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * sqrt_t * z2)
-                        .exp();
-                let st_down2 = s0_down
-                    * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * sqrt_t * z2)
-                        .exp();
+                    let st_up2 = s0_up
+                        * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * sqrt_t * z2)
+                            .exp();
+                    let st_down2 = s0_down
+                        * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * cfg.t + cfg.sigma * sqrt_t * z2)
+                            .exp();
 
-                let delta_up2 = if st_up2 > k { st_up2 / s0_up } else { 0.0 };
-                let delta_down2 = if st_down2 > k {
-                    st_down2 / s0_down
+                    let delta_up2 = if st_up2 > k { st_up2 / s0_up } else { 0.0 };
+                    let delta_down2 = if st_down2 > k {
+                        st_down2 / s0_down
+                    } else {
+                        0.0
+                    };
+
+                    (
+                        0.5 * (delta_up + delta_up2),
+                        0.5 * (delta_down + delta_down2),
+                    )
                 } else {
-                    0.0
-                };
-
-                (
-                    0.5 * (delta_up + delta_up2),
-                    0.5 * (delta_down + delta_down2),
-                )
-            } else {
-                (delta_up, delta_down)
-            }
-        })
-        .reduce(|| (0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1));
+                    (delta_up, delta_down)
+                }
+            })
+            .reduce(|| (0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1))
+    });
 
     let mean_delta_up = sum_delta_up / n as f64 * discount;
     let mean_delta_down = sum_delta_down / n as f64 * discount;
@@ -833,3 +1203,56 @@ pub fn mc_gamma_european_call_gbm_finite_diff_batched(cfg: &McConfig) -> f64 {
     // Central finite difference for Gamma
     (mean_delta_up - mean_delta_down) / (2.0 * epsilon)
 }
+
+/// Monte Carlo Delta using the configured [`GreekMethod`]
+///
+/// # Errors
+///
+/// Returns `SdeError::UnsupportedOperation` when the pathwise method is
+/// requested for a payoff it cannot differentiate (anything other than a
+/// European call, or a barrier with [`PayoffSmoothing::BrownianBridge`]);
+/// use [`GreekMethod::Vibrato`] for those.
+pub fn mc_delta_gbm(cfg: &McConfig) -> SdeResult<f64> {
+    match cfg.greek_method {
+        GreekMethod::Pathwise if barrier_smoothing::is_smoothed_barrier(cfg) => {
+            Ok(barrier_smoothing::mc_smoothed_barrier_greeks_gbm(cfg)?.delta)
+        }
+        GreekMethod::Pathwise => {
+            cfg.validate()?;
+            require_pathwise_payoff(cfg, "Pathwise Delta")?;
+            Ok(mc_delta_european_call_gbm_pathwise(cfg))
+        }
+        GreekMethod::Vibrato => Ok(vibrato::mc_vibrato_greeks_gbm(cfg)?.delta),
+    }
+}
+
+/// Monte Carlo Gamma using the configured [`GreekMethod`]
+///
+/// The pathwise method uses the batched central finite difference on
+/// pathwise Delta; vibrato estimates Gamma directly without a bump.
+///
+/// # Errors
+///
+/// Same as [`mc_delta_gbm`].
+pub fn mc_gamma_gbm(cfg: &McConfig) -> SdeResult<f64> {
+    match cfg.greek_method {
+        GreekMethod::Pathwise => {
+            cfg.validate()?;
+            require_pathwise_payoff(cfg, "Pathwise Gamma")?;
+            Ok(mc_gamma_european_call_gbm_finite_diff_batched(cfg))
+        }
+        GreekMethod::Vibrato => Ok(vibrato::mc_vibrato_greeks_gbm(cfg)?.gamma),
+    }
+}
+
+fn require_pathwise_payoff(cfg: &McConfig, operation: &str) -> SdeResult<()> {
+    match cfg.payoff {
+        Payoff::EuropeanCall { .. } => Ok(()),
+        _ => Err(SdeError::UnsupportedOperation {
+            operation: operation.to_string(),
+            context: "pathwise Greeks are only implemented for European calls; \
+                      use GreekMethod::Vibrato for discontinuous payoffs"
+                .to_string(),
+        }),
+    }
+}
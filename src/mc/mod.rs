@@ -1,2 +1,43 @@
+pub mod accumulators;
+pub mod barrier_smoothing;
+pub mod bermudan_swaption;
+pub mod bonds;
+pub mod chain;
+pub mod checkpoint;
+pub mod commodity;
+pub mod compound;
+pub mod config_builder;
+pub mod control_variates;
+pub mod convergence;
+pub mod credit_basket;
+pub mod crn;
+pub mod currency;
+pub mod equity_rates;
+pub mod extrapolation;
+pub mod fd_greeks;
+pub mod first_passage;
+pub mod fx;
+pub mod greeks_plan;
+pub mod heston_bias;
+pub mod heston_greeks;
+pub mod heston_stress;
+pub mod histogram;
+pub mod importance_sampling;
 pub mod mc_engine;
+pub mod nested;
+pub mod normal_source;
+pub mod numeraire;
+pub mod path_construction;
+pub mod path_failures;
+pub(crate) mod path_generator;
+pub mod payoff_stats;
 pub mod payoffs;
+pub mod portfolio;
+pub mod progress;
+pub mod regression_proxy;
+pub mod session;
+pub mod single_precision;
+pub mod tuning;
+pub mod vega_buckets;
+pub mod vibrato;
+pub mod what_if;
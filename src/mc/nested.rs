@@ -0,0 +1,379 @@
+// src/mc/nested.rs
+//! Nested Simulation of Future Values
+//!
+//! # Estimator
+//!
+//! Many risk quantities are an expectation of a nonlinear function of a
+//! conditional value at a horizon `h`:
+//! ```text
+//! θ = E[g(V(X_h))],   V(x) = E[Y | X_h = x]
+//! ```
+//! e.g. `g(v) = max(v - K₁, 0)` for a compound option or `g(v) = max(v, 0)`
+//! for the expected exposure. When `V` has no closed form, each of `N`
+//! outer scenarios `X_h` is repriced with `M` inner samples:
+//! ```text
+//! V̂ᵢ = (1/M) Σⱼ Yᵢⱼ,   θ̂ = (1/N) Σᵢ g(V̂ᵢ)
+//! ```
+//!
+//! # Bias Correction
+//!
+//! The inner noise makes `θ̂` biased: for smooth `g` the bias is
+//! `½ E[g''(V) Var(Y | X_h)] / M = β/M`, and it keeps the `1/M` rate for
+//! kinks such as `max`. The jackknife over the two halves `V̂ᵃ`, `V̂ᵇ` of the
+//! inner samples cancels the leading term:
+//! ```text
+//! g̃ᵢ = 2 g(V̂ᵢ) - ½ (g(V̂ᵢᵃ) + g(V̂ᵢᵇ))
+//! ```
+//! and the difference between the plain and the corrected averages
+//! estimates the bias `β/M` of the plain estimator.
+//!
+//! # Budgeting
+//!
+//! With `Γ = N M` inner samples in total the mean squared error of the
+//! plain estimator is `β²/M² + σ²/N`, where `σ²` is the variance of
+//! `g(V̂ᵢ)` across scenarios. It is smallest for
+//! ```text
+//! M* = (2 β² Γ / σ²)^{1/3},   N* = Γ / M*
+//! ```
+//! (Gordy & Juneja, 2010). [`NestedConfig::with_budget`] plugs in `β` and
+//! `σ²` estimated by a pilot run.
+
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::mc::mc_engine::McConfig;
+use crate::parallel::prelude::*;
+use crate::risk::var::{var_and_expected_shortfall, RiskMeasure};
+use crate::rng::{self, SeedStrategy};
+use rand::rngs::StdRng;
+
+/// Bias correction of the outer average
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BiasCorrection {
+    /// Plain average of `g(V̂ᵢ)`
+    None,
+    /// Jackknife over the two halves of the inner samples
+    #[default]
+    Jackknife,
+}
+
+/// Settings of a nested simulation
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NestedConfig {
+    /// Horizon `h` of the outer scenarios
+    pub horizon: f64,
+    /// Outer scenarios `N`
+    pub outer_paths: usize,
+    /// Inner samples `M` per scenario, even so that it splits in halves
+    pub inner_paths: usize,
+    /// Scenario `i` draws from its stream of `seed`, outer draws first
+    pub seed: u64,
+    /// Derivation of the per-scenario streams from `seed`
+    pub seed_strategy: SeedStrategy,
+    pub bias_correction: BiasCorrection,
+}
+
+impl Default for NestedConfig {
+    fn default() -> Self {
+        NestedConfig {
+            horizon: 0.5,
+            outer_paths: 1_000,
+            inner_paths: 100,
+            seed: 12345,
+            seed_strategy: SeedStrategy::Hashed,
+            bias_correction: BiasCorrection::Jackknife,
+        }
+    }
+}
+
+impl NestedConfig {
+    /// Validate the horizon and the workload
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_positive("horizon", self.horizon)?;
+        validate_paths(self.outer_paths)?;
+        validate_paths(self.inner_paths)?;
+        if self.inner_paths % 2 != 0 {
+            return Err(SdeError::InvalidConfiguration {
+                field: "inner_paths".to_string(),
+                reason: format!("{} must be even", self.inner_paths),
+            });
+        }
+        Ok(())
+    }
+
+    /// Configuration spending `total` inner samples with the MSE-optimal
+    /// split, from the bias and variance measured by `pilot`
+    ///
+    /// The inner count is rounded to an even number of at least 2.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError` if `total` is below 2 or `pilot` has no variance
+    /// estimate.
+    pub fn with_budget(&self, total: usize, pilot: &NestedResult) -> SdeResult<NestedConfig> {
+        validate_range("total", total as f64, 2.0, f64::INFINITY)?;
+        validate_positive("outer_variance", pilot.outer_variance)?;
+        let beta = pilot.bias * pilot.inner_paths as f64;
+        let optimal = (2.0 * beta * beta * total as f64 / pilot.outer_variance).cbrt();
+        let inner = ((optimal / 2.0).round() as usize).clamp(1, total / 2) * 2;
+        Ok(NestedConfig {
+            outer_paths: (total / inner).max(1),
+            inner_paths: inner,
+            ..*self
+        })
+    }
+}
+
+/// Outcome of [`nested_simulate`]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NestedResult {
+    /// Estimate of `E[g(V)]`, bias-corrected as configured
+    pub estimate: f64,
+    /// Variance of the estimate
+    pub variance: f64,
+    /// Estimated bias `β/M` of the uncorrected estimator
+    pub bias: f64,
+    /// Variance `σ²` of the per-scenario terms
+    pub outer_variance: f64,
+    /// Inner estimate `V̂ᵢ` of every scenario, in scenario order
+    pub values: Vec<f64>,
+    pub outer_paths: usize,
+    pub inner_paths: usize,
+}
+
+impl NestedResult {
+    /// VaR and ES of the P&L `V̂ᵢ - base_value` at `confidence`
+    ///
+    /// Inner noise widens the simulated P&L distribution, so tail measures
+    /// from few inner samples are conservative.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`var_and_expected_shortfall`].
+    pub fn risk_measure(&self, base_value: f64, confidence: f64) -> SdeResult<RiskMeasure> {
+        let pnl: Vec<f64> = self.values.iter().map(|v| v - base_value).collect();
+        let (var, expected_shortfall) = var_and_expected_shortfall(&pnl, confidence)?;
+        Ok(RiskMeasure {
+            confidence,
+            var,
+            expected_shortfall,
+        })
+    }
+}
+
+/// Nested Monte Carlo estimate of `E[g(V(X_h))]`
+///
+/// `outer(rng)` simulates a scenario at the horizon, `inner(scenario, rng)`
+/// draws one sample `Y` whose conditional mean is the value `V` at the
+/// horizon, and `functional` is `g`. Scenarios run in parallel, each on
+/// its own seeded stream.
+///
+/// # Errors
+///
+/// Returns `SdeError` for an invalid configuration, the first error of
+/// `outer` or `inner` tagged with its scenario, or a non-finite estimate.
+pub fn nested_simulate<S, O, I, G>(
+    cfg: &NestedConfig,
+    outer: O,
+    inner: I,
+    functional: G,
+) -> SdeResult<NestedResult>
+where
+    O: Fn(&mut StdRng) -> SdeResult<S> + Sync,
+    I: Fn(&S, &mut StdRng) -> SdeResult<f64> + Sync,
+    G: Fn(f64) -> f64 + Sync,
+{
+    cfg.validate()?;
+    let half = cfg.inner_paths / 2;
+    // (V̂, plain term, jackknife term) per scenario
+    let terms = (0..cfg.outer_paths)
+        .into_par_iter()
+        .map(|i| -> SdeResult<(f64, f64, f64)> {
+            let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+            let scenario = outer(&mut rng).map_err(|e| e.at_path(i))?;
+            let mut halves = [0.0; 2];
+            for k in 0..cfg.inner_paths {
+                halves[k / half] += inner(&scenario, &mut rng).map_err(|e| e.at_path(i))?;
+            }
+            let value = (halves[0] + halves[1]) / cfg.inner_paths as f64;
+            let plain = functional(value);
+            let halves_mean =
+                0.5 * (functional(halves[0] / half as f64) + functional(halves[1] / half as f64));
+            Ok((value, plain, 2.0 * plain - halves_mean))
+        })
+        .collect::<SdeResult<Vec<_>>>()?;
+
+    let n = cfg.outer_paths as f64;
+    let (plain, corrected) = terms
+        .iter()
+        .fold((0.0, 0.0), |acc, t| (acc.0 + t.1 / n, acc.1 + t.2 / n));
+    let term = |t: &(f64, f64, f64)| match cfg.bias_correction {
+        BiasCorrection::None => t.1,
+        BiasCorrection::Jackknife => t.2,
+    };
+    let estimate = match cfg.bias_correction {
+        BiasCorrection::None => plain,
+        BiasCorrection::Jackknife => corrected,
+    };
+    let outer_variance = if cfg.outer_paths > 1 {
+        terms
+            .iter()
+            .map(|t| (term(t) - estimate).powi(2))
+            .sum::<f64>()
+            / (n - 1.0)
+    } else {
+        0.0
+    };
+    if !estimate.is_finite() {
+        return Err(SdeError::NumericalInstability {
+            method: "Nested simulation".to_string(),
+            reason: format!("non-finite estimate {}", estimate),
+        });
+    }
+    Ok(NestedResult {
+        estimate,
+        variance: outer_variance / n,
+        bias: plain - corrected,
+        outer_variance,
+        values: terms.iter().map(|t| t.0).collect(),
+        outer_paths: cfg.outer_paths,
+        inner_paths: cfg.inner_paths,
+    })
+}
+
+/// Nested estimate of `E[g(V(S_h))]` for `cfg.payoff` under GBM
+///
+/// Outer scenarios are exact risk-neutral GBM spots at `nested.horizon`;
+/// each inner sample is the payoff of a path from `S_h` over the remaining
+/// `cfg.t - h` on `cfg.steps` steps, discounted to the horizon. Fixings of
+/// path-dependent payoffs before the horizon are not carried over. Uses
+/// `cfg.s0`, `r`, `sigma`, `t`, `steps` and `payoff`; the workload and the
+/// seed come from `nested`.
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid configurations, a horizon at or beyond
+/// `cfg.t`, or a non-finite estimate.
+pub fn mc_nested_gbm<G>(
+    cfg: &McConfig,
+    nested: &NestedConfig,
+    functional: G,
+) -> SdeResult<NestedResult>
+where
+    G: Fn(f64) -> f64 + Sync,
+{
+    cfg.validate()?;
+    cfg.parallelism.install(|| {
+        nested.validate()?;
+        let h = nested.horizon;
+        if h >= cfg.t {
+            return Err(SdeError::InvalidParameters {
+                parameter: "horizon".to_string(),
+                value: h,
+                constraint: format!("must be before the maturity t = {}", cfg.t),
+            });
+        }
+        let tau = cfg.t - h;
+        let dt = tau / cfg.steps as f64;
+        let half_var = 0.5 * cfg.sigma * cfg.sigma;
+        let discount = (-cfg.r * tau).exp();
+        let outer = |rng: &mut StdRng| {
+            let z = rng::get_normal_draw(rng);
+            Ok(cfg.s0 * ((cfg.r - half_var) * h + cfg.sigma * h.sqrt() * z).exp())
+        };
+        let inner = |&s_h: &f64, rng: &mut StdRng| {
+            let mut path = Vec::with_capacity(cfg.steps + 1);
+            let mut s = s_h;
+            path.push(s);
+            for _ in 0..cfg.steps {
+                s *= ((cfg.r - half_var) * dt + cfg.sigma * dt.sqrt() * rng::get_normal_draw(rng))
+                    .exp();
+                path.push(s);
+            }
+            Ok(discount * cfg.payoff.calculate(&path))
+        };
+        nested_simulate(nested, outer, inner, &functional)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::bs_analytic::bs_call_price;
+    use crate::mc::payoffs::Payoff;
+
+    #[test]
+    fn test_jackknife_removes_nested_bias() {
+        // Compound call: g(V) = max(V - K₁, 0) on the call value at h
+        let cfg = McConfig {
+            payoff: Payoff::EuropeanCall { k: 100.0 },
+            steps: 1,
+            ..Default::default()
+        };
+        let nested = NestedConfig {
+            outer_paths: 20_000,
+            inner_paths: 8,
+            bias_correction: BiasCorrection::None,
+            ..Default::default()
+        };
+        let g = |v: f64| (v - 8.0).max(0.0);
+        let plain = mc_nested_gbm(&cfg, &nested, g).unwrap();
+        let jackknife = mc_nested_gbm(
+            &cfg,
+            &NestedConfig {
+                bias_correction: BiasCorrection::Jackknife,
+                ..nested
+            },
+            g,
+        )
+        .unwrap();
+
+        // Same outer scenarios revalued exactly
+        let h = nested.horizon;
+        let exact = (0..nested.outer_paths)
+            .map(|i| {
+                let mut rng = nested.seed_strategy.path_rng(nested.seed, i as u64);
+                let z = rng::get_normal_draw(&mut rng);
+                let s_h = cfg.s0
+                    * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * h + cfg.sigma * h.sqrt() * z).exp();
+                g(bs_call_price(s_h, 100.0, cfg.r, cfg.sigma, cfg.t - h))
+            })
+            .sum::<f64>()
+            / nested.outer_paths as f64;
+
+        let plain_bias = plain.estimate - exact;
+        let jackknife_bias = jackknife.estimate - exact;
+        assert!(plain_bias > 0.1, "plain bias {}", plain_bias);
+        assert!(
+            jackknife_bias.abs() < 0.3 * plain_bias,
+            "jackknife bias {} vs plain {}",
+            jackknife_bias,
+            plain_bias
+        );
+        assert!((plain.bias - plain_bias).abs() < 0.3 * plain_bias);
+        assert_eq!(plain.values, jackknife.values);
+
+        // Budgeting: inner samples grow like Γ^{1/3}
+        let small = nested.with_budget(100_000, &plain).unwrap();
+        let large = nested.with_budget(800_000, &plain).unwrap();
+        assert_eq!(small.inner_paths % 2, 0);
+        assert!(small.outer_paths * small.inner_paths <= 100_000);
+        let growth = large.inner_paths as f64 / small.inner_paths as f64;
+        assert!((1.6..2.5).contains(&growth), "inner growth {}", growth);
+
+        // Call value losses over the horizon
+        let base = bs_call_price(cfg.s0, 100.0, cfg.r, cfg.sigma, cfg.t);
+        let measure = plain.risk_measure(base, 0.99).unwrap();
+        assert!(measure.expected_shortfall >= measure.var && measure.var > 0.0);
+        assert!(mc_nested_gbm(
+            &cfg,
+            &NestedConfig {
+                horizon: 1.0,
+                ..nested
+            },
+            g
+        )
+        .is_err());
+    }
+}
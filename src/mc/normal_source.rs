@@ -0,0 +1,447 @@
+// src/mc/normal_source.rs
+//! Pluggable Normal Draws for the GBM Engine
+//!
+//! # Blocks
+//!
+//! A [`NormalSource`] produces standard normal draws in *blocks*: block `b`
+//! holds `block_size()` samples of `dim` draws each (one per time step).
+//! Samples inside a block may be dependent (antithetic partners, strata,
+//! a QMC point set), but distinct blocks are independent and identically
+//! distributed, so the engine averages each block into one observation and
+//! its variance estimate stays valid. `cfg.paths` counts blocks.
+//!
+//! # Composition
+//!
+//! Variance reduction is expressed by wrapping sources rather than by flags
+//! in the engine:
+//! ```text
+//! PseudoRandom                 i.i.d. draws, one sample per block
+//! Stratified / SobolSource     Latin hypercube / shifted Sobol point sets
+//! HaltonSource / FaureSource   scrambled Halton / generalized Faure sets
+//! Antithetic(S)                S's block followed by its negation
+//! MomentMatched(S, k)          k blocks of S, standardized per dimension
+//! ```
+//! The engine wraps the configured source (or [`PseudoRandom`] with the
+//! configuration's seed) in [`Antithetic`] when `use_antithetic` is set.
+//! [`SamplerKind`] names the plain and quasi-random constructions, to
+//! compare them on one payoff.
+
+use crate::error::{SdeError, SdeResult};
+use crate::math_utils::norm_inv_cdf;
+use crate::rng::faure::Faure;
+use crate::rng::halton::Halton;
+use crate::rng::philox::Philox4x32;
+use crate::rng::sobol::Sobol;
+use crate::rng::{self, SeedStrategy};
+use rand::{Rng, RngCore};
+use std::sync::Arc;
+
+/// Source of standard normal draws, generated in independent blocks
+pub trait NormalSource: Send + Sync {
+    /// Number of samples per block
+    fn block_size(&self) -> usize;
+
+    /// Fill `out` (`block_size() * dim` values, sample-major) with the draws
+    /// of block `block`
+    fn fill_block(&self, block: usize, dim: usize, out: &mut [f64]);
+
+    /// Check that the source can produce `dim` draws per sample
+    fn validate(&self, _dim: usize) -> SdeResult<()> {
+        Ok(())
+    }
+}
+
+impl<S: NormalSource + ?Sized> NormalSource for Arc<S> {
+    fn block_size(&self) -> usize {
+        (**self).block_size()
+    }
+
+    fn fill_block(&self, block: usize, dim: usize, out: &mut [f64]) {
+        (**self).fill_block(block, dim, out)
+    }
+
+    fn validate(&self, dim: usize) -> SdeResult<()> {
+        (**self).validate(dim)
+    }
+}
+
+/// Run `f` with the generator of block `block`: the `StdRng` stream of
+/// `strategy`, or Philox stream `block` when `counter_based`
+fn with_block_rng<T>(
+    seed: u64,
+    block: usize,
+    counter_based: bool,
+    strategy: SeedStrategy,
+    f: impl FnOnce(&mut dyn RngCore) -> T,
+) -> T {
+    if counter_based {
+        f(&mut Philox4x32::new(seed, block as u64))
+    } else {
+        f(&mut strategy.path_rng(seed, block as u64))
+    }
+}
+
+/// Independent pseudo-random draws, one sample per block
+#[derive(Debug, Clone, Copy)]
+pub struct PseudoRandom {
+    pub seed: u64,
+    /// Philox streams instead of `StdRng`, see `McConfig::deterministic`
+    pub counter_based: bool,
+    /// Derivation of the `StdRng` block streams from `seed`
+    pub strategy: SeedStrategy,
+}
+
+impl NormalSource for PseudoRandom {
+    fn block_size(&self) -> usize {
+        1
+    }
+
+    fn fill_block(&self, block: usize, _dim: usize, out: &mut [f64]) {
+        with_block_rng(self.seed, block, self.counter_based, self.strategy, |rng| {
+            for z in out.iter_mut() {
+                *z = rng::get_normal_draw(rng);
+            }
+        });
+    }
+}
+
+/// Each block of `inner` followed by the same draws negated
+#[derive(Debug, Clone, Copy)]
+pub struct Antithetic<S> {
+    pub inner: S,
+}
+
+impl<S: NormalSource> NormalSource for Antithetic<S> {
+    fn block_size(&self) -> usize {
+        2 * self.inner.block_size()
+    }
+
+    fn fill_block(&self, block: usize, dim: usize, out: &mut [f64]) {
+        let (first, second) = out.split_at_mut(out.len() / 2);
+        self.inner.fill_block(block, dim, first);
+        for (negated, &z) in second.iter_mut().zip(first.iter()) {
+            *negated = -z;
+        }
+    }
+
+    fn validate(&self, dim: usize) -> SdeResult<()> {
+        self.inner.validate(dim)
+    }
+}
+
+/// Latin hypercube blocks: in every dimension, each of the `samples` equal
+/// probability strata holds exactly one sample, in random order
+#[derive(Debug, Clone, Copy)]
+pub struct Stratified {
+    pub seed: u64,
+    pub samples: usize,
+    /// Derivation of the per-block permutations and jitter from `seed`
+    pub strategy: SeedStrategy,
+}
+
+impl NormalSource for Stratified {
+    fn block_size(&self) -> usize {
+        self.samples
+    }
+
+    fn fill_block(&self, block: usize, dim: usize, out: &mut [f64]) {
+        let m = self.samples;
+        let mut rng = self.strategy.path_rng(self.seed, block as u64);
+        let mut strata: Vec<usize> = (0..m).collect();
+        for d in 0..dim {
+            for j in (1..m).rev() {
+                strata.swap(j, rng.gen_range(0..=j));
+            }
+            for (j, &stratum) in strata.iter().enumerate() {
+                let u = (stratum as f64 + rng.gen::<f64>()) / m as f64;
+                out[j * dim + d] = norm_inv_cdf(u.max(f64::MIN_POSITIVE));
+            }
+        }
+    }
+
+    fn validate(&self, _dim: usize) -> SdeResult<()> {
+        validate_block("samples", self.samples, 1)
+    }
+}
+
+/// Randomized quasi-Monte Carlo: every block is the first `points` Sobol
+/// points with an independent random digital shift, mapped through `Φ⁻¹`
+///
+/// Use a power of two for `points` so each block is a complete net.
+#[derive(Debug, Clone)]
+pub struct SobolSource {
+    pub seed: u64,
+    pub points: usize,
+    /// Derivation of the per-block digital shifts from `seed`
+    pub strategy: SeedStrategy,
+}
+
+impl NormalSource for SobolSource {
+    fn block_size(&self) -> usize {
+        self.points
+    }
+
+    fn fill_block(&self, block: usize, dim: usize, out: &mut [f64]) {
+        let sobol = Sobol::new(dim).expect("dimension checked by validate");
+        let mut rng = self.strategy.path_rng(self.seed, block as u64);
+        let shift: Vec<u32> = (0..dim).map(|_| rng.gen()).collect();
+        let mut point = vec![0u32; dim];
+        for (j, sample) in out.chunks_mut(dim).enumerate() {
+            sobol.point(j as u32, &mut point);
+            for ((z, &x), &s) in sample.iter_mut().zip(&point).zip(&shift) {
+                // Midpoint of the 2^-32 cell keeps u strictly inside (0, 1)
+                *z = norm_inv_cdf(((x ^ s) as f64 + 0.5) / 4_294_967_296.0);
+            }
+        }
+    }
+
+    fn validate(&self, dim: usize) -> SdeResult<()> {
+        validate_block("points", self.points, 1)?;
+        Sobol::new(dim).map(|_| ())
+    }
+}
+
+/// Randomized quasi-Monte Carlo: every block is the first `points` Halton
+/// points (every `leap`-th with a leap above 1), independently scrambled,
+/// mapped through `Φ⁻¹`
+///
+/// Scrambling keeps high dimensions usable; see [`crate::rng::halton`].
+#[derive(Debug, Clone)]
+pub struct HaltonSource {
+    pub seed: u64,
+    pub points: usize,
+    /// Leap between the points used, coprime to every base (1 for none)
+    pub leap: u64,
+    /// Derivation of the per-block scrambles from `seed`
+    pub strategy: SeedStrategy,
+}
+
+impl NormalSource for HaltonSource {
+    fn block_size(&self) -> usize {
+        self.points
+    }
+
+    fn fill_block(&self, block: usize, dim: usize, out: &mut [f64]) {
+        let mut halton = Halton::new(dim)
+            .and_then(|h| h.with_leap(self.leap))
+            .expect("dimension and leap checked by validate");
+        halton.scramble(&mut self.strategy.path_rng(self.seed, block as u64));
+        for (j, sample) in out.chunks_mut(dim).enumerate() {
+            halton.point(j as u64, sample);
+            to_normals(sample);
+        }
+    }
+
+    fn validate(&self, dim: usize) -> SdeResult<()> {
+        validate_block("points", self.points, 1)?;
+        Halton::new(dim)?.with_leap(self.leap).map(|_| ())
+    }
+}
+
+/// Randomized quasi-Monte Carlo: every block is the first `points` points
+/// of an independently scrambled generalized Faure sequence, mapped through
+/// `Φ⁻¹`
+///
+/// Use a power of the base (the smallest prime at least the dimension) for
+/// `points` so each block is a complete net.
+#[derive(Debug, Clone)]
+pub struct FaureSource {
+    pub seed: u64,
+    pub points: usize,
+    /// Derivation of the per-block scrambles from `seed`
+    pub strategy: SeedStrategy,
+}
+
+impl NormalSource for FaureSource {
+    fn block_size(&self) -> usize {
+        self.points
+    }
+
+    fn fill_block(&self, block: usize, dim: usize, out: &mut [f64]) {
+        let mut faure = Faure::new(dim).expect("dimension checked by validate");
+        faure.scramble(&mut self.strategy.path_rng(self.seed, block as u64));
+        for (j, sample) in out.chunks_mut(dim).enumerate() {
+            faure.point(j as u64, sample);
+            to_normals(sample);
+        }
+    }
+
+    fn validate(&self, dim: usize) -> SdeResult<()> {
+        validate_block("points", self.points, 1)?;
+        Faure::new(dim).map(|_| ())
+    }
+}
+
+/// Map uniforms in `[0, 1)` to standard normals in place
+fn to_normals(sample: &mut [f64]) {
+    for u in sample.iter_mut() {
+        *u = norm_inv_cdf(u.max(f64::MIN_POSITIVE));
+    }
+}
+
+/// Construction of the draws of a [`NormalSource`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SamplerKind {
+    /// [`PseudoRandom`] draws, one sample per block
+    #[default]
+    PseudoRandom,
+    /// [`SobolSource`]: digitally shifted Sobol points (up to
+    /// [`MAX_DIMENSION`](crate::rng::sobol::MAX_DIMENSION) dimensions)
+    Sobol,
+    /// [`HaltonSource`]: scrambled Halton points, any dimension
+    Halton,
+    /// [`FaureSource`]: scrambled generalized Faure points, any dimension
+    Faure,
+}
+
+impl SamplerKind {
+    /// Source of this kind whose blocks draw from the `strategy` streams of
+    /// `seed`; the quasi-random kinds draw blocks of `points` points, so
+    /// `cfg.paths` blocks give `cfg.paths * points` paths
+    pub fn source(self, seed: u64, strategy: SeedStrategy, points: usize) -> Arc<dyn NormalSource> {
+        match self {
+            SamplerKind::PseudoRandom => Arc::new(PseudoRandom {
+                seed,
+                counter_based: false,
+                strategy,
+            }),
+            SamplerKind::Sobol => Arc::new(SobolSource {
+                seed,
+                points,
+                strategy,
+            }),
+            SamplerKind::Halton => Arc::new(HaltonSource {
+                seed,
+                points,
+                leap: 1,
+                strategy,
+            }),
+            SamplerKind::Faure => Arc::new(FaureSource {
+                seed,
+                points,
+                strategy,
+            }),
+        }
+    }
+}
+
+/// `blocks` consecutive blocks of `inner` as one block, shifted and scaled
+/// so that every dimension has sample mean 0 and variance 1
+///
+/// Matching moments removes the first-order sampling error of the drift
+/// and volatility terms, at the cost of an `O(1/n)` bias in the block size
+/// `n` (with two samples the draws are exactly `±1`), so use a few dozen
+/// samples per block.
+#[derive(Debug, Clone, Copy)]
+pub struct MomentMatched<S> {
+    pub inner: S,
+    pub blocks: usize,
+}
+
+impl<S: NormalSource> NormalSource for MomentMatched<S> {
+    fn block_size(&self) -> usize {
+        self.blocks * self.inner.block_size()
+    }
+
+    fn fill_block(&self, block: usize, dim: usize, out: &mut [f64]) {
+        let inner_len = self.inner.block_size() * dim;
+        for (k, chunk) in out.chunks_mut(inner_len).enumerate() {
+            self.inner.fill_block(block * self.blocks + k, dim, chunk);
+        }
+        let n = self.block_size() as f64;
+        for d in 0..dim {
+            let column = || out.iter().skip(d).step_by(dim);
+            let mean = column().sum::<f64>() / n;
+            let variance = column().map(|z| (z - mean).powi(2)).sum::<f64>() / n;
+            let scale = if variance > 0.0 {
+                variance.sqrt().recip()
+            } else {
+                1.0
+            };
+            for z in out.iter_mut().skip(d).step_by(dim) {
+                *z = (*z - mean) * scale;
+            }
+        }
+    }
+
+    fn validate(&self, dim: usize) -> SdeResult<()> {
+        validate_block("blocks", self.blocks, 1)?;
+        if self.block_size() < 2 {
+            return Err(SdeError::InvalidConfiguration {
+                field: "blocks".to_string(),
+                reason: "moment matching needs at least 2 samples per block".to_string(),
+            });
+        }
+        self.inner.validate(dim)
+    }
+}
+
+fn validate_block(field: &str, value: usize, min: usize) -> SdeResult<()> {
+    if value < min {
+        return Err(SdeError::InvalidConfiguration {
+            field: field.to_string(),
+            reason: format!("must be at least {}, got {}", min, value),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sources_compose() {
+        let dim = 3;
+        let base = PseudoRandom {
+            seed: 5,
+            counter_based: false,
+            strategy: SeedStrategy::Hashed,
+        };
+        let anti = Antithetic { inner: base };
+        let mut pair = vec![0.0; anti.block_size() * dim];
+        anti.fill_block(7, dim, &mut pair);
+        assert_eq!(pair.len(), 6);
+        assert!(pair[..3].iter().zip(&pair[3..]).all(|(a, b)| *a == -*b));
+
+        // Moment matching standardizes each dimension of the block
+        let matched = MomentMatched {
+            inner: anti,
+            blocks: 50,
+        };
+        let mut block = vec![0.0; matched.block_size() * dim];
+        matched.fill_block(0, dim, &mut block);
+        for d in 0..dim {
+            let column: Vec<f64> = block.iter().skip(d).step_by(dim).copied().collect();
+            let mean = column.iter().sum::<f64>() / 100.0;
+            let variance = column.iter().map(|z| z * z).sum::<f64>() / 100.0;
+            assert!(mean.abs() < 1e-12 && (variance - 1.0).abs() < 1e-12);
+        }
+
+        // One sample per stratum in every dimension
+        let strata = Stratified {
+            seed: 1,
+            samples: 16,
+            strategy: SeedStrategy::Hashed,
+        };
+        let mut block = vec![0.0; 16 * dim];
+        strata.fill_block(2, dim, &mut block);
+        for d in 0..dim {
+            let mut hit = [false; 16];
+            for z in block.iter().skip(d).step_by(dim) {
+                let u = crate::math_utils::norm_cdf(*z);
+                hit[(u * 16.0) as usize] = true;
+            }
+            assert!(hit.iter().all(|&h| h));
+        }
+
+        let sobol = SobolSource {
+            seed: 3,
+            points: 64,
+            strategy: SeedStrategy::Hashed,
+        };
+        assert!(sobol.validate(dim).is_ok());
+        assert!(sobol.validate(100).is_err());
+    }
+}
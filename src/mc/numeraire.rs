@@ -0,0 +1,162 @@
+// src/mc/numeraire.rs
+//! Numeraires and Measure Changes for Stochastic-Rate Simulation
+//!
+//! # Change of Numeraire
+//!
+//! Any strictly positive traded asset `N` defines a pricing measure under
+//! which every deflated price `V_t / N_t` is a martingale:
+//! ```text
+//! V_0 = N_0 E^N[X / N_T]
+//! ```
+//! In a Hull-White economy each numeraire below has dynamics
+//! `dN/N = r dt + σ_N dW_r`, and Girsanov shifts the rate Brownian motion
+//! by `σ_N dt`. The factor then drifts by `σ_r σ_N` and an asset loading
+//! `ρσ_S` on the rate driver gains `ρσ_S σ_N`:
+//! ```text
+//! Bank account   N = exp(∫₀ᵗ r du)    σ_N = 0
+//! T-forward bond N = P(t, T)          σ_N = -σ_r B(t, T)
+//! Annuity        N = Σ τ_i P(t, t_i)  σ_N = -σ_r Σ w_i B(t, t_i),  w_i = τ_i P(t, t_i) / N
+//! ```
+//!
+//! # Simulation
+//!
+//! The simulation freezes `σ_N` over a step at the mid-step time and the
+//! start-of-step factor, so measures other than the bank account are
+//! stepped at most [`MEASURE_CHANGE_MAX_STEP`] apart. A payoff that
+//! divides by its numeraire has a smaller variance when the numeraire
+//! matches the claim: a bond for a single cashflow, an annuity for a swap
+//! rate.
+
+use crate::analytics::hull_white_analytic::Swaption;
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::models::hull_white::HullWhite;
+
+/// Largest step over which the numeraire volatility is held constant
+pub const MEASURE_CHANGE_MAX_STEP: f64 = 0.05;
+
+/// Asset in whose units deflated prices are martingales
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Numeraire {
+    /// Continuously rolled money-market account; the risk-neutral measure
+    BankAccount,
+    /// Zero-coupon bond maturing at `maturity`; the `T`-forward measure
+    ForwardBond { maturity: f64 },
+    /// Fixed-leg annuity `Σ τ_i P(t, t_i)`; the swap measure
+    Annuity {
+        payment_dates: Vec<f64>,
+        accruals: Vec<f64>,
+    },
+}
+
+impl Numeraire {
+    /// Annuity of the fixed leg underlying `swaption`, per unit notional
+    pub fn swap_annuity(swaption: &Swaption) -> Self {
+        let payment_dates = swaption.payment_dates();
+        let mut start = swaption.expiry;
+        let accruals = payment_dates
+            .iter()
+            .map(|&end| {
+                let accrual = end - start;
+                start = end;
+                accrual
+            })
+            .collect();
+        Numeraire::Annuity {
+            payment_dates,
+            accruals,
+        }
+    }
+
+    /// Validate the bond maturity or the annuity schedule
+    pub fn validate(&self) -> SdeResult<()> {
+        match self {
+            Numeraire::BankAccount => Ok(()),
+            Numeraire::ForwardBond { maturity } => validate_positive("maturity", *maturity),
+            Numeraire::Annuity {
+                payment_dates,
+                accruals,
+            } => {
+                if payment_dates.is_empty() || payment_dates.len() != accruals.len() {
+                    return Err(SdeError::InvalidConfiguration {
+                        field: "accruals".to_string(),
+                        reason: format!(
+                            "need one accrual per payment date, got {} dates and {} accruals",
+                            payment_dates.len(),
+                            accruals.len()
+                        ),
+                    });
+                }
+                let mut prev = 0.0;
+                for (&date, &accrual) in payment_dates.iter().zip(accruals) {
+                    validate_finite("payment_date", date)?;
+                    if date <= prev {
+                        return Err(SdeError::InvalidParameters {
+                            parameter: "payment_date".to_string(),
+                            value: date,
+                            constraint: "payment dates must be positive and strictly increasing"
+                                .to_string(),
+                        });
+                    }
+                    validate_positive("accrual", accrual)?;
+                    prev = date;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Last time at which the numeraire is a traded asset: the bond's
+    /// maturity or the annuity's first payment date
+    pub fn horizon(&self) -> f64 {
+        match self {
+            Numeraire::BankAccount => f64::INFINITY,
+            Numeraire::ForwardBond { maturity } => *maturity,
+            Numeraire::Annuity { payment_dates, .. } => payment_dates[0],
+        }
+    }
+
+    /// Value `N_t` given the Hull-White factor `x` and the bank-account
+    /// deflator `D(t) = exp(-∫₀ᵗ r du)` of the path
+    pub fn value(&self, rates: &HullWhite, t: f64, x: f64, deflator: f64) -> f64 {
+        match self {
+            Numeraire::BankAccount => 1.0 / deflator,
+            Numeraire::ForwardBond { maturity } => rates.bond_price(t, *maturity, x),
+            Numeraire::Annuity {
+                payment_dates,
+                accruals,
+            } => payment_dates
+                .iter()
+                .zip(accruals)
+                .map(|(&date, &accrual)| accrual * rates.bond_price(t, date, x))
+                .sum(),
+        }
+    }
+
+    /// Today's value `N_0`
+    pub fn initial_value(&self, rates: &HullWhite) -> f64 {
+        self.value(rates, 0.0, 0.0, 1.0)
+    }
+
+    /// Volatility `σ_N` of the numeraire on the rate Brownian motion
+    pub fn volatility(&self, rates: &HullWhite, t: f64, x: f64) -> f64 {
+        let sigma_r = rates.params.sigma;
+        match self {
+            Numeraire::BankAccount => 0.0,
+            Numeraire::ForwardBond { maturity } => -sigma_r * rates.b(t, *maturity),
+            Numeraire::Annuity {
+                payment_dates,
+                accruals,
+            } => {
+                let (annuity, weighted) = payment_dates.iter().zip(accruals).fold(
+                    (0.0, 0.0),
+                    |(annuity, weighted), (&date, &accrual)| {
+                        let leg = accrual * rates.bond_price(t, date, x);
+                        (annuity + leg, weighted + leg * rates.b(t, date))
+                    },
+                );
+                -sigma_r * weighted / annuity
+            }
+        }
+    }
+}
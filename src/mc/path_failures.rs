@@ -0,0 +1,229 @@
+// src/mc/path_failures.rs
+//! Per-Path Failure Handling
+//!
+//! # Motivation
+//!
+//! Discretisation schemes for two-factor models can fail on rare paths
+//! (a non-finite variance, a price underflowing to zero). By default the
+//! first such path aborts the whole run, which throws away a million good
+//! paths because of one bad one.
+//!
+//! # Policies
+//!
+//! - [`PathFailurePolicy::Fail`]: abort on the first failing path (default)
+//! - [`PathFailurePolicy::Quarantine`]: drop failing paths and average over
+//!   the accepted ones
+//! - [`PathFailurePolicy::Resimulate`]: rerun a failing path on a grid
+//!   `refinement` times finer, driven by the *same* Brownian path, and
+//!   quarantine it only if that fails too
+//!
+//! Resimulating the same Brownian path, rather than a fresh one, avoids
+//! selecting against the paths that stress the scheme. Each coarse normal
+//! `Z` is split into `m` sub-step normals conditional on their sum:
+//! ```text
+//! z_j = Z/√m + (y_j - ȳ),   y_j ~ N(0, 1) i.i.d.
+//! Σ_j z_j √(Δt/m) = Z √Δt,   Var(z_j) = 1,   Cov(z_j, z_k) = 0
+//! ```
+//!
+//! Errors carry the index of the failing path
+//! ([`SdeError::path_index`]), and quarantined paths are reported by index
+//! in [`PathDiagnostics`] so they can be replayed from the path's stream
+//! (see [`SeedStrategy`](crate::rng::SeedStrategy)). The diagnostics also
+//! sum the [`Warnings`] of the accepted paths.
+//!
+//! Dropping paths biases the estimate if failures are correlated with the
+//! payoff, so a non-zero quarantine count should be investigated rather
+//! than ignored.
+
+use crate::error::{SdeError, SdeResult, Warnings};
+use crate::parallel::prelude::*;
+
+/// What to do with a path whose simulation fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathFailurePolicy {
+    /// Abort the run with the path's error
+    #[default]
+    Fail,
+    /// Exclude the path from the estimate
+    Quarantine,
+    /// Rerun the path with `dt / refinement`, then quarantine on failure
+    Resimulate { refinement: usize },
+}
+
+impl PathFailurePolicy {
+    /// Validate the policy
+    pub fn validate(&self) -> SdeResult<()> {
+        if let PathFailurePolicy::Resimulate { refinement } = *self {
+            if refinement < 2 {
+                return Err(SdeError::InvalidParameters {
+                    parameter: "refinement".to_string(),
+                    value: refinement as f64,
+                    constraint: "must be at least 2".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Outcome counts of a run under a [`PathFailurePolicy`]
+#[derive(Debug, Clone, Default)]
+pub struct PathDiagnostics {
+    /// Paths included in the estimate
+    pub accepted_paths: usize,
+    /// Paths that failed and were rescued on a finer grid
+    pub resimulated: usize,
+    /// Paths excluded from the estimate
+    pub quarantined: usize,
+    /// Indices of the quarantined paths, in path order
+    pub quarantined_paths: Vec<usize>,
+    /// Error of the first quarantined path
+    pub first_error: Option<SdeError>,
+    /// Numerical repairs made on the accepted paths
+    pub warnings: Warnings,
+}
+
+impl PathDiagnostics {
+    fn merge(mut self, other: PathDiagnostics) -> Self {
+        self.accepted_paths += other.accepted_paths;
+        self.resimulated += other.resimulated;
+        self.quarantined += other.quarantined;
+        self.quarantined_paths.extend(other.quarantined_paths);
+        if self.first_error.is_none() {
+            self.first_error = other.first_error;
+        }
+        self.warnings += other.warnings;
+        self
+    }
+}
+
+/// Sum per-path values over `paths` paths under `policy`
+///
+/// `simulate(i, refinement)` returns the `width` values of path `i` on a
+/// grid `refinement` times finer than the
+/// base grid, and the warnings raised on it. Returns the sums over accepted
+/// paths and the diagnostics; path errors are tagged with the path index.
+pub(crate) fn sum_paths_with_policy<F>(
+    paths: usize,
+    width: usize,
+    policy: PathFailurePolicy,
+    simulate: F,
+) -> SdeResult<(Vec<f64>, PathDiagnostics)>
+where
+    F: Fn(usize, usize) -> SdeResult<(Vec<f64>, Warnings)> + Sync,
+{
+    policy.validate()?;
+    let (sums, diagnostics) = (0..paths)
+        .into_par_iter()
+        .map(|i| {
+            let mut diagnostics = PathDiagnostics::default();
+            let error = match simulate(i, 1) {
+                Ok((values, warnings)) => {
+                    diagnostics.accepted_paths = 1;
+                    diagnostics.warnings = warnings;
+                    return Ok((values, diagnostics));
+                }
+                Err(e) => e.at_path(i),
+            };
+            let error = match policy {
+                PathFailurePolicy::Fail => return Err(error),
+                PathFailurePolicy::Quarantine => error,
+                PathFailurePolicy::Resimulate { refinement } => match simulate(i, refinement) {
+                    Ok((values, warnings)) => {
+                        diagnostics.accepted_paths = 1;
+                        diagnostics.resimulated = 1;
+                        diagnostics.warnings = warnings;
+                        return Ok((values, diagnostics));
+                    }
+                    Err(e) => e.at_path(i),
+                },
+            };
+            diagnostics.quarantined = 1;
+            diagnostics.quarantined_paths.push(i);
+            diagnostics.first_error = Some(error);
+            Ok((vec![0.0; width], diagnostics))
+        })
+        .try_reduce(
+            || (vec![0.0; width], PathDiagnostics::default()),
+            |(mut a, da), (b, db)| {
+                for (x, y) in a.iter_mut().zip(b.iter()) {
+                    *x += y;
+                }
+                Ok((a, da.merge(db)))
+            },
+        )?;
+
+    if diagnostics.accepted_paths == 0 {
+        return Err(SdeError::NumericalInstability {
+            method: "Path failure policy".to_string(),
+            reason: format!(
+                "all {} paths failed; first error: {}",
+                paths,
+                diagnostics
+                    .first_error
+                    .as_ref()
+                    .map_or_else(String::new, |e| e.to_string())
+            ),
+        });
+    }
+
+    Ok((sums, diagnostics))
+}
+
+/// Split one coarse standard normal into `refinement` sub-step normals
+/// whose scaled sum reproduces it, using the i.i.d. normals `y`
+pub(crate) fn refine_normal(z: f64, y: &[f64]) -> impl Iterator<Item = f64> + '_ {
+    let m = y.len() as f64;
+    let mean = y.iter().sum::<f64>() / m;
+    y.iter().map(move |&y_j| z / m.sqrt() + (y_j - mean))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flaky(i: usize, refinement: usize) -> SdeResult<(Vec<f64>, Warnings)> {
+        // Every tenth path fails on the base grid; path 5 always fails
+        if i == 5 || (i % 10 == 0 && refinement == 1) {
+            Err(SdeError::NumericalInstability {
+                method: "test".to_string(),
+                reason: format!("path {}", i),
+            })
+        } else {
+            let warnings = Warnings {
+                variance_truncations: u64::from(i % 3 == 0),
+                ..Warnings::default()
+            };
+            Ok((vec![1.0, i as f64], warnings))
+        }
+    }
+
+    #[test]
+    fn test_policies_quarantine_and_resimulate() {
+        let err = sum_paths_with_policy(100, 2, PathFailurePolicy::Fail, flaky).unwrap_err();
+        let path = err.path_index().expect("Error tagged with its path");
+        assert!(path == 5 || path % 10 == 0);
+
+        let (sums, diag) =
+            sum_paths_with_policy(100, 2, PathFailurePolicy::Quarantine, flaky).unwrap();
+        assert_eq!(diag.quarantined, 11);
+        assert_eq!(diag.accepted_paths, 89);
+        assert_eq!(sums[0], 89.0);
+        assert_eq!(diag.quarantined_paths[..3], [0, 5, 10]);
+        // Multiples of 3 that are not multiples of 10
+        assert_eq!(diag.warnings.variance_truncations, 30);
+
+        let policy = PathFailurePolicy::Resimulate { refinement: 4 };
+        let (sums, diag) = sum_paths_with_policy(100, 2, policy, flaky).unwrap();
+        assert_eq!((diag.resimulated, diag.quarantined), (10, 1));
+        assert_eq!(diag.quarantined_paths, vec![5]);
+        assert_eq!(sums[0], 99.0);
+        assert_eq!(diag.first_error.and_then(|e| e.path_index()), Some(5));
+
+        // Refined normals add back up to the coarse one
+        let z = 0.7;
+        let y = [0.3, -1.2, 0.5, 2.0];
+        let total: f64 = refine_normal(z, &y).sum::<f64>() / 2.0;
+        assert!((total - z).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,119 @@
+// src/mc/path_generator.rs
+//! Shared GBM Paths on a Union of Observation Dates
+//!
+//! # Motivation
+//!
+//! Books, chains and exposure profiles price many payoffs on the same
+//! paths. Each payoff reads its own dates (a maturity, or the fixings
+//! `i T / steps` of an Asian), so the paths are simulated once on the
+//! sorted union of all of them and each payoff reads its indices.
+//!
+//! # Exact Simulation
+//!
+//! Draw `z_j` moves the Brownian motion across `[t_{j-1}, t_j]`, and the
+//! spot is a function of `W_t` alone:
+//! ```text
+//! W(t_j) = W(t_{j-1}) + √(t_j - t_{j-1}) z_j
+//! S(t)   = S₀ exp((r - σ²/2) t + σ W(t))
+//! ```
+//! Negating the draws gives the antithetic path. Keeping `W` rather than
+//! `S` lets scenarios with a bumped `S₀` or `σ` reuse the same path.
+
+/// Relative tolerance under which two dates are the same grid point
+const DATE_TOLERANCE: f64 = 1e-12;
+
+/// Dates a payoff maturing at `t` reads: `steps` equally spaced fixings if
+/// it is path dependent, otherwise the maturity alone
+pub(crate) fn observation_dates(t: f64, steps: usize, path_dependent: bool) -> Vec<f64> {
+    if path_dependent {
+        (1..=steps).map(|i| i as f64 * t / steps as f64).collect()
+    } else {
+        vec![t]
+    }
+}
+
+/// Index of `date` on `grid`, if it is one of its points
+pub(crate) fn grid_index(grid: &[f64], date: f64) -> Option<usize> {
+    grid.iter()
+        .position(|&g| (g - date).abs() <= DATE_TOLERANCE * date.abs().max(1.0))
+}
+
+/// Sorted union of `date_sets` and `extra_dates` and, per set, the grid
+/// indices of its dates
+///
+/// Grid index `i` refers to the date `grid[i]`; `S₀` is not part of the grid.
+pub(crate) fn observation_grid(
+    date_sets: &[Vec<f64>],
+    extra_dates: &[f64],
+) -> (Vec<f64>, Vec<Vec<usize>>) {
+    let mut grid: Vec<f64> = date_sets
+        .iter()
+        .flatten()
+        .chain(extra_dates)
+        .copied()
+        .collect();
+    grid.sort_by(f64::total_cmp);
+    grid.dedup_by(|a, b| (*a - *b).abs() <= DATE_TOLERANCE * b.abs().max(1.0));
+
+    let observed = date_sets
+        .iter()
+        .map(|dates| {
+            dates
+                .iter()
+                .map(|&d| grid_index(&grid, d).expect("every date is on the grid"))
+                .collect()
+        })
+        .collect();
+    (grid, observed)
+}
+
+/// Brownian motion on `grid` from standard normal `draws`, negated when
+/// `sign` is -1
+pub(crate) fn brownian_path(grid: &[f64], draws: &[f64], sign: f64) -> Vec<f64> {
+    let (mut prev_t, mut w) = (0.0, 0.0);
+    grid.iter()
+        .zip(draws)
+        .map(|(&t, &z)| {
+            w += (t - prev_t).sqrt() * sign * z;
+            prev_t = t;
+            w
+        })
+        .collect()
+}
+
+/// Exact GBM spot at `t` given the Brownian value `w`
+pub(crate) fn gbm_spot(s0: f64, r: f64, sigma: f64, t: f64, w: f64) -> f64 {
+    s0 * ((r - 0.5 * sigma * sigma) * t + sigma * w).exp()
+}
+
+/// Exact GBM spots on `grid` along the Brownian path `brownian`
+pub(crate) fn gbm_path(s0: f64, r: f64, sigma: f64, grid: &[f64], brownian: &[f64]) -> Vec<f64> {
+    grid.iter()
+        .zip(brownian)
+        .map(|(&t, &w)| gbm_spot(s0, r, sigma, t, w))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_merges_shared_dates() {
+        let sets = vec![
+            observation_dates(1.0, 4, true),
+            observation_dates(0.5, 4, false),
+        ];
+        let (grid, observed) = observation_grid(&sets, &[0.1, 0.5]);
+        assert_eq!(grid, [0.1, 0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(observed, [vec![1, 2, 3, 4], vec![2]]);
+        assert_eq!(grid_index(&grid, 0.75), Some(3));
+        assert_eq!(grid_index(&grid, 0.3), None);
+
+        let brownian = brownian_path(&grid, &[1.0, -1.0, 0.5, 0.0, 2.0], -1.0);
+        assert!((brownian[0] + 0.1f64.sqrt()).abs() < 1e-15);
+        assert!((brownian[4] - brownian[3] + 2.0 * 0.25f64.sqrt()).abs() < 1e-15);
+        let spots = gbm_path(100.0, 0.02, 0.2, &grid, &brownian);
+        assert!((spots[2] - gbm_spot(100.0, 0.02, 0.2, 0.5, brownian[2])).abs() < 1e-12);
+    }
+}
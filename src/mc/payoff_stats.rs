@@ -0,0 +1,407 @@
+// src/mc/payoff_stats.rs
+//! Streaming Payoff Distribution Statistics
+//!
+//! # Motivation
+//!
+//! The price is only the mean of the discounted payoff. Its spread, skew,
+//! tails and quantiles tell whether the estimator is trustworthy (heavy
+//! tails converge slowly) and what the payoff actually looks like, but
+//! storing a million payoffs to compute them is wasteful.
+//!
+//! # Moments
+//!
+//! Central moment sums `M_p = Σ (x - x̄)^p` for `p = 2, 3, 4` are
+//! accumulated per Rayon task and merged pairwise (Pébay's update), which is
+//! exact and numerically stable:
+//! ```text
+//! δ = x̄_b - x̄_a,   n = n_a + n_b
+//! M₂ = M₂a + M₂b + δ² n_a n_b / n
+//! M₃ = M₃a + M₃b + δ³ n_a n_b (n_a - n_b) / n² + 3δ (n_a M₂b - n_b M₂a) / n
+//! M₄ = M₄a + M₄b + δ⁴ n_a n_b (n_a² - n_a n_b + n_b²) / n³
+//!      + 6δ² (n_a² M₂b + n_b² M₂a) / n² + 4δ (n_a M₃b - n_b M₃a) / n
+//! ```
+//!
+//! # Quantiles
+//!
+//! Quantiles come from a merging t-digest: values are clustered into
+//! centroids whose weight is capped at `4 W q (1 - q) / δ`, so clusters are
+//! small in the tails and large in the body. Memory is `O(δ)` regardless of
+//! the number of paths, digests merge across Rayon tasks, and tail
+//! quantiles are accurate to a fraction of a percent in rank.
+
+use crate::error::{validation::*, SdeResult};
+use crate::mc::mc_engine::{
+    engine_normal_source, for_each_block_path, mc_price_option_gbm, priced_payoff,
+    simulation_increments, BlockBuffers, McConfig,
+};
+use crate::parallel::prelude::*;
+
+/// Default t-digest compression `δ`
+const COMPRESSION: f64 = 200.0;
+
+/// Mergeable quantile sketch (t-digest)
+#[derive(Debug, Clone)]
+struct TDigest {
+    /// `(mean, weight)` sorted by mean after each flush
+    centroids: Vec<(f64, f64)>,
+    buffer: Vec<f64>,
+}
+
+impl TDigest {
+    fn new() -> Self {
+        TDigest {
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, x: f64) {
+        self.buffer.push(x);
+        if self.buffer.len() >= 10 * COMPRESSION as usize {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        self.centroids
+            .extend(self.buffer.drain(..).map(|x| (x, 1.0)));
+        self.compress();
+    }
+
+    fn merge(&mut self, mut other: TDigest) {
+        other.flush();
+        self.flush();
+        self.centroids.extend(other.centroids);
+        self.compress();
+    }
+
+    fn compress(&mut self) {
+        self.centroids.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let total: f64 = self.centroids.iter().map(|c| c.1).sum();
+        let mut merged: Vec<(f64, f64)> = Vec::with_capacity(self.centroids.len());
+        let mut cumulative = 0.0;
+        for &(mean, weight) in &self.centroids {
+            if let Some(last) = merged.last_mut() {
+                let combined = last.1 + weight;
+                let q = (cumulative + 0.5 * combined) / total;
+                if combined <= 4.0 * total * q * (1.0 - q) / COMPRESSION {
+                    last.0 += (mean - last.0) * weight / combined;
+                    last.1 = combined;
+                    continue;
+                }
+                cumulative += last.1;
+            }
+            merged.push((mean, weight));
+        }
+        self.centroids = merged;
+    }
+
+    /// Quantile by interpolation between centroid centres
+    fn quantile(&self, q: f64, min: f64, max: f64) -> f64 {
+        let mut digest = self.clone();
+        digest.flush();
+        let c = &digest.centroids;
+        if c.is_empty() {
+            return f64::NAN;
+        }
+        let total: f64 = c.iter().map(|c| c.1).sum();
+        let target = q * total;
+
+        // Centre of centroid i sits at cumulative weight before it + w_i/2
+        let mut prev = (0.0, min);
+        let mut cumulative = 0.0;
+        for &(mean, weight) in c {
+            let centre = cumulative + 0.5 * weight;
+            if target <= centre {
+                let span = centre - prev.0;
+                return if span > 0.0 {
+                    prev.1 + (mean - prev.1) * (target - prev.0) / span
+                } else {
+                    mean
+                };
+            }
+            prev = (centre, mean);
+            cumulative += weight;
+        }
+        let span = total - prev.0;
+        if span > 0.0 {
+            prev.1 + (max - prev.1) * (target - prev.0) / span
+        } else {
+            max
+        }
+    }
+}
+
+/// Streaming mean, higher moments, extremes and quantiles
+#[derive(Debug, Clone)]
+pub struct StreamingStats {
+    count: u64,
+    mean: f64,
+    m2: f64,
+    m3: f64,
+    m4: f64,
+    min: f64,
+    max: f64,
+    digest: TDigest,
+}
+
+impl Default for StreamingStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamingStats {
+    pub fn new() -> Self {
+        StreamingStats {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            m3: 0.0,
+            m4: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            digest: TDigest::new(),
+        }
+    }
+
+    /// Add one observation
+    pub fn push(&mut self, x: f64) {
+        self.merge_moments(1, x, 0.0, 0.0, 0.0);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.digest.push(x);
+    }
+
+    /// Combine with statistics of a disjoint sample
+    pub fn merge(&mut self, other: StreamingStats) {
+        self.merge_moments(other.count, other.mean, other.m2, other.m3, other.m4);
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.digest.merge(other.digest);
+    }
+
+    fn merge_moments(&mut self, nb: u64, mean_b: f64, m2b: f64, m3b: f64, m4b: f64) {
+        if nb == 0 {
+            return;
+        }
+        let (na, nb_f) = (self.count as f64, nb as f64);
+        let n = na + nb_f;
+        let d = mean_b - self.mean;
+        let (d2, d3, d4) = (d * d, d * d * d, d * d * d * d);
+        let (m2a, m3a) = (self.m2, self.m3);
+
+        self.m4 += m4b
+            + d4 * na * nb_f * (na * na - na * nb_f + nb_f * nb_f) / (n * n * n)
+            + 6.0 * d2 * (na * na * m2b + nb_f * nb_f * m2a) / (n * n)
+            + 4.0 * d * (na * m3b - nb_f * m3a) / n;
+        self.m3 +=
+            m3b + d3 * na * nb_f * (na - nb_f) / (n * n) + 3.0 * d * (na * m2b - nb_f * m2a) / n;
+        self.m2 += m2b + d2 * na * nb_f / n;
+        self.mean += d * nb_f / n;
+        self.count += nb;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Sample standard deviation
+    pub fn std_dev(&self) -> f64 {
+        if self.count > 1 {
+            (self.m2 / (self.count - 1) as f64).sqrt()
+        } else {
+            0.0
+        }
+    }
+
+    /// Sample skewness `√n M₃ / M₂^(3/2)`
+    pub fn skewness(&self) -> f64 {
+        if self.m2 > 0.0 {
+            (self.count as f64).sqrt() * self.m3 / self.m2.powf(1.5)
+        } else {
+            0.0
+        }
+    }
+
+    /// Excess kurtosis `n M₄ / M₂² - 3`
+    pub fn excess_kurtosis(&self) -> f64 {
+        if self.m2 > 0.0 {
+            self.count as f64 * self.m4 / (self.m2 * self.m2) - 3.0
+        } else {
+            0.0
+        }
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// Approximate `q`-quantile, `q` in [0, 1]
+    pub fn quantile(&self, q: f64) -> f64 {
+        self.digest.quantile(q.clamp(0.0, 1.0), self.min, self.max)
+    }
+}
+
+/// Summary of the discounted payoff distribution
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PayoffSummary {
+    pub count: u64,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub skewness: f64,
+    pub excess_kurtosis: f64,
+    pub min: f64,
+    pub max: f64,
+    /// `(level, quantile)` for each requested level
+    pub quantiles: Vec<(f64, f64)>,
+}
+
+/// Engine price together with the payoff distribution summary
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PriceWithStats {
+    /// As returned by [`mc_price_option_gbm`]
+    pub price: f64,
+    pub variance: f64,
+    pub summary: PayoffSummary,
+}
+
+/// Price with [`mc_price_option_gbm`] and summarise the discounted payoffs
+///
+/// The summary covers every simulated path individually (both members of
+/// an antithetic pair), on the same draws as the engine, before any
+/// control variate adjustment.
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid configurations, quantile levels outside
+/// [0, 1], or pricing failures.
+pub fn mc_price_with_payoff_stats(cfg: &McConfig, quantiles: &[f64]) -> SdeResult<PriceWithStats> {
+    cfg.parallelism.install(|| {
+        for &q in quantiles {
+            validate_range("quantile", q, 0.0, 1.0)?;
+        }
+        let (price, variance) = mc_price_option_gbm(cfg)?;
+
+        let grid = simulation_increments(cfg);
+        let payoff = priced_payoff(cfg);
+        let discount = (-cfg.r * cfg.t).exp();
+
+        let source = engine_normal_source(cfg);
+        let stats = (0..cfg.paths)
+            .into_par_iter()
+            .fold(
+                || (StreamingStats::new(), BlockBuffers::default()),
+                |(mut stats, mut buffers), i| {
+                    for_each_block_path(cfg, &grid, &source, i, &mut buffers, |path| {
+                        stats.push(discount * payoff.calculate(path));
+                    });
+                    (stats, buffers)
+                },
+            )
+            .map(|(stats, _)| stats)
+            .reduce(StreamingStats::new, |mut a, b| {
+                a.merge(b);
+                a
+            });
+
+        Ok(PriceWithStats {
+            price,
+            variance,
+            summary: PayoffSummary {
+                count: stats.count(),
+                mean: stats.mean(),
+                std_dev: stats.std_dev(),
+                skewness: stats.skewness(),
+                excess_kurtosis: stats.excess_kurtosis(),
+                min: stats.min(),
+                max: stats.max(),
+                quantiles: quantiles.iter().map(|&q| (q, stats.quantile(q))).collect(),
+            },
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mc::payoffs::Payoff;
+
+    #[test]
+    fn test_streaming_stats_match_exact_values() {
+        // Uniform grid on [0, 1): skewness 0, excess kurtosis -1.2
+        let n = 100_000;
+        let xs: Vec<f64> = (0..n).map(|i| i as f64 / n as f64).collect();
+        let mut sequential = StreamingStats::new();
+        xs.iter().for_each(|&x| sequential.push(x));
+        let merged = xs
+            .chunks(7_919)
+            .map(|chunk| {
+                let mut s = StreamingStats::new();
+                chunk.iter().for_each(|&x| s.push(x));
+                s
+            })
+            .fold(StreamingStats::new(), |mut a, b| {
+                a.merge(b);
+                a
+            });
+
+        for stats in [&sequential, &merged] {
+            assert_eq!(stats.count(), n as u64);
+            assert!((stats.mean() - 0.499995).abs() < 1e-9);
+            assert!(stats.skewness().abs() < 1e-6);
+            assert!((stats.excess_kurtosis() + 1.2).abs() < 1e-3);
+            for q in [0.01, 0.25, 0.5, 0.9, 0.999] {
+                assert!(
+                    (stats.quantile(q) - q).abs() < 2e-3,
+                    "q = {}: {}",
+                    q,
+                    stats.quantile(q)
+                );
+            }
+        }
+        assert!((sequential.std_dev() - merged.std_dev()).abs() < 1e-12);
+
+        // A NaN from an overflowed path sorts last instead of panicking
+        let mut poisoned = StreamingStats::new();
+        for x in [1.0, f64::NAN, 2.0] {
+            poisoned.push(x);
+        }
+        poisoned.digest.flush();
+        assert_eq!(poisoned.digest.centroids[..2], [(1.0, 1.0), (2.0, 1.0)]);
+        assert!(poisoned.mean().is_nan() && poisoned.digest.centroids[2].0.is_nan());
+    }
+
+    #[test]
+    fn test_call_payoff_distribution() {
+        let cfg = McConfig {
+            paths: 20_000,
+            payoff: Payoff::EuropeanCall { k: 100.0 },
+            use_control_variate: false,
+            ..Default::default()
+        };
+        let result = mc_price_with_payoff_stats(&cfg, &[0.3, 0.99]).expect("Valid config");
+        let summary = &result.summary;
+        assert_eq!(summary.count, 40_000);
+        // Without control variates the engine price is the payoff mean
+        assert!((summary.mean - result.price).abs() < 1e-9);
+        assert_eq!(summary.min, 0.0);
+        // About half the paths finish out of the money
+        assert_eq!(summary.quantiles[0].1, 0.0);
+        assert!(summary.quantiles[1].1 > summary.mean + 2.0 * summary.std_dev);
+        assert!(summary.skewness > 1.0);
+    }
+}
@@ -9,22 +9,128 @@
 //! - **Call**: max(S_T - K, 0) - right to buy at strike K
 //! - **Put**: max(K - S_T, 0) - right to sell at strike K
 //!
+//! ## Digital Options
+//! - **Cash-or-nothing**: pays 1 if the option finishes in-the-money
+//!
+//! ## Power Options
+//! - **Power call**: max(S_T^p - K, 0)
+//!
 //! ## Path-Dependent Options
 //! - **Asian**: Based on average price over the path
 //! - **Barrier**: Knocked out if price crosses barrier level
+//! - **Forward-start**: Strike fixed as a fraction `α` of the price on a
+//!   future date: max(S_T - α S_{t₁}, 0)
+//! - **Chooser**: Call or put, whichever is worth more at a choice date
+//! - **Autocallable**: Redeems early with a coupon when the price is at or
+//!   above the autocall barrier on an observation date; otherwise pays the
+//!   notional at maturity, less a put loss if a knock-in level was touched
 //!
 //! # Implementation Notes
 //!
 //! All payoffs operate on the full price path `&[f64]` to support
 //! both European (terminal price only) and exotic (full path) options.
+//! [`Payoff::start`] gives the same value from prices fed one at a time,
+//! keeping O(1) running statistics (sum, barrier flags, fixings)
+//! instead of the path, so long simulations need not store it.
 
 use std::f64;
 
+/// How discontinuous payoffs are evaluated on a simulated path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PayoffSmoothing {
+    /// Evaluate the payoff exactly as written on the discrete path
+    #[default]
+    None,
+    /// Replace the barrier indicator by the Brownian bridge probability of
+    /// not crossing between grid points (continuous monitoring). The
+    /// weighted payoff is smooth in the inputs, so barrier Greeks can be
+    /// taken pathwise; see [`crate::mc::barrier_smoothing`].
+    BrownianBridge,
+}
+
+/// Broadie–Glasserman–Kou continuity correction constant `β = -ζ(1/2)/√(2π)`
+pub const BGK_BETA: f64 = 0.5826;
+
+/// Barrier shift applied before pricing a barrier payoff
+///
+/// Desks price barrier options with the barrier moved against themselves
+/// (an "overhedge") so that the hedge stays robust near the barrier, and
+/// discretely monitored simulations shift the barrier to approximate
+/// continuous monitoring.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BarrierShift {
+    /// Move the barrier by a fixed amount: `H' = H + shift`
+    Absolute(f64),
+    /// Move the barrier by a fraction of its level: `H' = H (1 + shift)`
+    Relative(f64),
+    /// Broadie–Glasserman–Kou correction so that monitoring every `Δt`
+    /// approximates continuous monitoring: `H' = H exp(-β σ √Δt)` for an
+    /// up barrier
+    ContinuityCorrection,
+}
+
+impl BarrierShift {
+    /// Shifted barrier level for an up barrier `h`
+    ///
+    /// `sigma` and `dt` (monitoring interval) are only used by
+    /// [`BarrierShift::ContinuityCorrection`].
+    pub fn apply(&self, h: f64, sigma: f64, dt: f64) -> f64 {
+        match *self {
+            BarrierShift::Absolute(shift) => h + shift,
+            BarrierShift::Relative(shift) => h * (1.0 + shift),
+            BarrierShift::ContinuityCorrection => h * (-BGK_BETA * sigma * dt.sqrt()).exp(),
+        }
+    }
+}
+
+/// One observation date of an autocallable
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AutocallObservation {
+    /// Index into the simulated path (`0` is `S_0`, `i` the `i`-th grid time)
+    pub index: usize,
+    /// Early redemption if `S ≥ autocall_barrier` on this date
+    pub autocall_barrier: f64,
+    /// Coupon paid with the notional on redemption, as a fraction of the
+    /// notional; a snowball accumulates, e.g. `i · c` on the `i`-th date
+    pub coupon: f64,
+    /// Factor carrying a payment on this date to maturity, `e^(r(T - t))`,
+    /// so that discounting the payoff from `T` values it at its payment date
+    pub carry_to_maturity: f64,
+}
+
+impl AutocallObservation {
+    /// Observation at `time` on a uniform grid of `steps` steps over
+    /// `maturity`, carried to maturity at rate `r`
+    ///
+    /// `time` is rounded to the nearest grid point.
+    pub fn on_uniform_grid(
+        time: f64,
+        maturity: f64,
+        steps: usize,
+        r: f64,
+        autocall_barrier: f64,
+        coupon: f64,
+    ) -> Self {
+        let index = (time / maturity * steps as f64).round() as usize;
+        let grid_time = index as f64 * maturity / steps as f64;
+        AutocallObservation {
+            index,
+            autocall_barrier,
+            coupon,
+            carry_to_maturity: (r * (maturity - grid_time)).exp(),
+        }
+    }
+}
+
 /// Enumeration of supported option payoff types
 ///
 /// Each variant contains the parameters needed to compute the payoff
 /// from a simulated asset price path.
-#[derive(Clone)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Payoff {
     /// European call option: max(S_T - K, 0)
     EuropeanCall { k: f64 },
@@ -40,9 +146,63 @@ pub enum Payoff {
 
     /// Up-and-out barrier put: max(K - S_T, 0) if max(S_t) < H, else 0
     BarrierPutUpAndOut { k: f64, h: f64 },
+
+    /// Cash-or-nothing digital call: 1 if S_T > K, else 0
+    DigitalCall { k: f64 },
+
+    /// Cash-or-nothing digital put: 1 if S_T < K, else 0
+    DigitalPut { k: f64 },
+
+    /// Forward-start call: max(S_T - α S_{t₁}, 0), with `t₁` the grid time
+    /// at path index `start_index`
+    ForwardStartCall { start_index: usize, alpha: f64 },
+
+    /// Forward-start put: max(α S_{t₁} - S_T, 0)
+    ForwardStartPut { start_index: usize, alpha: f64 },
+
+    /// Power call: max(S_T^p - K, 0)
+    PowerCall { k: f64, p: f64 },
+
+    /// Simple chooser: at the grid time of `choice_index` the holder takes
+    /// the call or the put struck at `K` with the larger Black-Scholes
+    /// value. By put-call parity the call is chosen iff
+    /// `S_{t_c} ≥ K e^(-r(T - t_c))`, with `choice_discount = e^(-r(T - t_c))`.
+    Chooser {
+        k: f64,
+        choice_index: usize,
+        choice_discount: f64,
+    },
+
+    /// Autocallable (snowball) note with a down-and-in put at maturity:
+    /// - first date `i` with `S ≥ B_i`: `N (1 + c_i)`, carried to maturity
+    /// - never called, `min(S_t) > KI`: `N`
+    /// - never called, `min(S_t) ≤ KI`: `N min(S_T / K, 1)`
+    Autocallable {
+        /// Observation dates in increasing path order
+        observations: Vec<AutocallObservation>,
+        notional: f64,
+        /// Knock-in level of the put, monitored on every path point
+        knock_in: f64,
+        /// Strike `K` of the knock-in put
+        put_strike: f64,
+    },
 }
 
 impl Payoff {
+    /// Chooser struck at `k` deciding at `choice_time` on a uniform grid of
+    /// `steps` steps over `maturity`, at rate `r`
+    ///
+    /// `choice_time` is rounded to the nearest grid point.
+    pub fn chooser(k: f64, choice_time: f64, maturity: f64, steps: usize, r: f64) -> Payoff {
+        let choice_index = (choice_time / maturity * steps as f64).round() as usize;
+        let grid_time = choice_index as f64 * maturity / steps as f64;
+        Payoff::Chooser {
+            k,
+            choice_index,
+            choice_discount: (-r * (maturity - grid_time)).exp(),
+        }
+    }
+
     /// Calculate payoff value from a simulated asset price path
     ///
     /// # Parameters
@@ -56,13 +216,15 @@ impl Payoff {
     /// Each payoff type implements its specific mathematical definition:
     pub fn calculate(&self, path: &[f64]) -> f64 {
         match self {
-            // European Call: max(S_T - K, 0)
-            // Uses only terminal price (last element of path)
-            Payoff::EuropeanCall { k } => (path.last().unwrap() - k).max(0.0),
-
-            // European Put: max(K - S_T, 0)
-            // Uses only terminal price (last element of path)
-            Payoff::EuropeanPut { k } => (k - path.last().unwrap()).max(0.0),
+            // Terminal-only payoffs: max(S_T - K, 0), max(K - S_T, 0),
+            // digitals and the power call
+            Payoff::EuropeanCall { .. }
+            | Payoff::EuropeanPut { .. }
+            | Payoff::DigitalCall { .. }
+            | Payoff::DigitalPut { .. }
+            | Payoff::PowerCall { .. } => self
+                .calculate_terminal(*path.last().unwrap())
+                .expect("terminal-only payoff"),
 
             // Asian Call: max(A - K, 0) where A = (1/n)∑S_i
             // Arithmetic average of all prices in the path
@@ -104,6 +266,345 @@ impl Payoff {
                     (k - path.last().unwrap()).max(0.0)
                 }
             }
+
+            // Forward-start: strike set at path[start_index]
+            Payoff::ForwardStartCall { start_index, alpha } => {
+                (path.last().unwrap() - alpha * path[*start_index]).max(0.0)
+            }
+            Payoff::ForwardStartPut { start_index, alpha } => {
+                (alpha * path[*start_index] - path.last().unwrap()).max(0.0)
+            }
+
+            // Chooser: the branch is fixed at the choice date
+            Payoff::Chooser {
+                k,
+                choice_index,
+                choice_discount,
+            } => {
+                let s_t = *path.last().unwrap();
+                if path[*choice_index] >= k * choice_discount {
+                    (s_t - k).max(0.0)
+                } else {
+                    (k - s_t).max(0.0)
+                }
+            }
+
+            // Autocallable: first observation at or above its barrier redeems
+            Payoff::Autocallable {
+                observations,
+                notional,
+                knock_in,
+                put_strike,
+            } => {
+                for obs in observations {
+                    if path[obs.index] >= obs.autocall_barrier {
+                        return notional * (1.0 + obs.coupon) * obs.carry_to_maturity;
+                    }
+                }
+                let s_t = *path.last().unwrap();
+                if path.iter().any(|&price| price <= *knock_in) {
+                    notional * (s_t / put_strike).min(1.0)
+                } else {
+                    *notional
+                }
+            }
+        }
+    }
+
+    /// Payoff of a terminal-only payoff from the terminal price `s_t` alone
+    ///
+    /// Lets a pricer skip storing the path: `calculate(path)` equals
+    /// `calculate_terminal(S_T)` for these payoffs. Returns `None` when the
+    /// payoff [`requires_full_path`](Self::requires_full_path).
+    pub fn calculate_terminal(&self, s_t: f64) -> Option<f64> {
+        match *self {
+            // European Call: max(S_T - K, 0)
+            Payoff::EuropeanCall { k } => Some((s_t - k).max(0.0)),
+
+            // European Put: max(K - S_T, 0)
+            Payoff::EuropeanPut { k } => Some((k - s_t).max(0.0)),
+
+            // Digital Call: 1_{S_T > K}
+            // Discontinuous at the strike, so pathwise Greeks do not apply
+            Payoff::DigitalCall { k } => Some(if s_t > k { 1.0 } else { 0.0 }),
+
+            // Digital Put: 1_{S_T < K}
+            Payoff::DigitalPut { k } => Some(if s_t < k { 1.0 } else { 0.0 }),
+
+            // Power Call: max(S_T^p - K, 0)
+            Payoff::PowerCall { k, p } => Some((s_t.powf(p) - k).max(0.0)),
+
+            _ => None,
+        }
+    }
+
+    /// Running state for evaluating the payoff one price at a time, from
+    /// `S_0` onwards; see [`PayoffState`]
+    pub fn start(&self) -> PayoffState<'_> {
+        PayoffState {
+            payoff: self,
+            observed: 0,
+            sum: 0.0,
+            last: f64::NAN,
+            knocked: false,
+            fixing: f64::NAN,
+            called: None,
+            next_observation: 0,
+        }
+    }
+
+    /// Gradient of the payoff with respect to each price on the path
+    ///
+    /// Returns `∂payoff/∂S_i` for every point of `path`, defined almost
+    /// everywhere (indicator kinks and barrier crossings contribute zero).
+    /// This is the building block for pathwise sensitivities: under GBM,
+    /// `∂S_i/∂S_0 = S_i/S_0`, so `Δ_path = Σ_i ∂payoff/∂S_i * S_i/S_0`.
+    pub fn path_gradient(&self, path: &[f64]) -> Vec<f64> {
+        let n = path.len();
+        let mut grad = vec![0.0; n];
+        let s_t = *path.last().unwrap();
+        let knocked_out = |h: f64| path.iter().any(|&price| price >= h);
+        match self {
+            Payoff::EuropeanCall { k } => {
+                if s_t > *k {
+                    grad[n - 1] = 1.0;
+                }
+            }
+            Payoff::EuropeanPut { k } => {
+                if s_t < *k {
+                    grad[n - 1] = -1.0;
+                }
+            }
+            Payoff::AsianCall { k } => {
+                let average_price: f64 = path.iter().sum::<f64>() / n as f64;
+                if average_price > *k {
+                    grad.iter_mut().for_each(|g| *g = 1.0 / n as f64);
+                }
+            }
+            Payoff::BarrierCallUpAndOut { k, h } => {
+                if !knocked_out(*h) && s_t > *k {
+                    grad[n - 1] = 1.0;
+                }
+            }
+            Payoff::BarrierPutUpAndOut { k, h } => {
+                if !knocked_out(*h) && s_t < *k {
+                    grad[n - 1] = -1.0;
+                }
+            }
+            // Piecewise constant: zero gradient almost everywhere
+            Payoff::DigitalCall { .. } | Payoff::DigitalPut { .. } => {}
+            Payoff::ForwardStartCall { start_index, alpha } => {
+                if s_t > alpha * path[*start_index] {
+                    grad[n - 1] += 1.0;
+                    grad[*start_index] -= alpha;
+                }
+            }
+            Payoff::ForwardStartPut { start_index, alpha } => {
+                if s_t < alpha * path[*start_index] {
+                    grad[n - 1] -= 1.0;
+                    grad[*start_index] += alpha;
+                }
+            }
+            Payoff::PowerCall { k, p } => {
+                if s_t.powf(*p) > *k {
+                    grad[n - 1] = p * s_t.powf(p - 1.0);
+                }
+            }
+            Payoff::Chooser {
+                k,
+                choice_index,
+                choice_discount,
+            } => {
+                let call = path[*choice_index] >= k * choice_discount;
+                if call && s_t > *k {
+                    grad[n - 1] = 1.0;
+                } else if !call && s_t < *k {
+                    grad[n - 1] = -1.0;
+                }
+            }
+            Payoff::Autocallable {
+                observations,
+                notional,
+                knock_in,
+                put_strike,
+            } => {
+                let called = observations
+                    .iter()
+                    .any(|obs| path[obs.index] >= obs.autocall_barrier);
+                let knocked_in = path.iter().any(|&price| price <= *knock_in);
+                if !called && knocked_in && s_t < *put_strike {
+                    grad[n - 1] = notional / put_strike;
+                }
+            }
+        }
+        grad
+    }
+
+    /// Copy of this payoff with its barrier moved by `shift`
+    ///
+    /// Payoffs without a barrier are returned unchanged.
+    pub fn with_barrier_shift(&self, shift: BarrierShift, sigma: f64, dt: f64) -> Payoff {
+        match *self {
+            Payoff::BarrierCallUpAndOut { k, h } => Payoff::BarrierCallUpAndOut {
+                k,
+                h: shift.apply(h, sigma, dt),
+            },
+            Payoff::BarrierPutUpAndOut { k, h } => Payoff::BarrierPutUpAndOut {
+                k,
+                h: shift.apply(h, sigma, dt),
+            },
+            _ => self.clone(),
+        }
+    }
+
+    /// Whether the payoff is fixed once the path reaches `price`
+    ///
+    /// True when an up-and-out barrier is touched: the payoff is zero
+    /// whatever happens afterwards, so the path need not be simulated further.
+    pub fn is_determined_at(&self, price: f64) -> bool {
+        match *self {
+            Payoff::BarrierCallUpAndOut { h, .. } | Payoff::BarrierPutUpAndOut { h, .. } => {
+                price >= h
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether [`calculate`](Self::calculate) needs more than the terminal
+    /// price; when false, [`calculate_terminal`](Self::calculate_terminal)
+    /// gives the payoff without a stored path
+    pub fn requires_full_path(&self) -> bool {
+        matches!(
+            self,
+            Payoff::AsianCall { .. }
+                | Payoff::BarrierCallUpAndOut { .. }
+                | Payoff::BarrierPutUpAndOut { .. }
+                | Payoff::ForwardStartCall { .. }
+                | Payoff::ForwardStartPut { .. }
+                | Payoff::Chooser { .. }
+                | Payoff::Autocallable { .. }
+        )
+    }
+}
+
+/// Payoff evaluated incrementally over a path
+///
+/// Feeding the prices `S_0, S_1, ..., S_n` to [`observe`](Self::observe)
+/// and calling [`value`](Self::value) gives `calculate(&[S_0, ..., S_n])`
+/// (bit for bit) with O(1) state:
+/// - Asian: the running sum
+/// - barriers: whether the barrier has been touched
+/// - forward-start and chooser: the fixing at the start or choice date
+/// - autocallable: the redemption amount once called, and whether the
+///   knock-in level has been touched
+#[derive(Debug, Clone)]
+pub struct PayoffState<'a> {
+    payoff: &'a Payoff,
+    observed: usize,
+    sum: f64,
+    last: f64,
+    knocked: bool,
+    fixing: f64,
+    called: Option<f64>,
+    next_observation: usize,
+}
+
+impl PayoffState<'_> {
+    /// Consume the next price of the path
+    pub fn observe(&mut self, price: f64) {
+        let index = self.observed;
+        self.observed += 1;
+        self.last = price;
+        match self.payoff {
+            Payoff::AsianCall { .. } => self.sum += price,
+            Payoff::BarrierCallUpAndOut { h, .. } | Payoff::BarrierPutUpAndOut { h, .. } => {
+                self.knocked |= price >= *h
+            }
+            Payoff::ForwardStartCall { start_index, .. }
+            | Payoff::ForwardStartPut { start_index, .. }
+            | Payoff::Chooser {
+                choice_index: start_index,
+                ..
+            } if index == *start_index => self.fixing = price,
+            Payoff::Autocallable {
+                observations,
+                notional,
+                knock_in,
+                ..
+            } => {
+                self.knocked |= price <= *knock_in;
+                while let Some(obs) = observations.get(self.next_observation) {
+                    if obs.index > index {
+                        break;
+                    }
+                    self.next_observation += 1;
+                    if self.called.is_none() && obs.index == index && price >= obs.autocall_barrier
+                    {
+                        self.called = Some(notional * (1.0 + obs.coupon) * obs.carry_to_maturity);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Number of prices observed so far
+    pub fn observed(&self) -> usize {
+        self.observed
+    }
+
+    /// Whether the payoff can no longer change, e.g. after an up-and-out
+    /// barrier is touched, so the rest of the path need not be simulated
+    pub fn is_determined(&self) -> bool {
+        match self.payoff {
+            Payoff::BarrierCallUpAndOut { .. } | Payoff::BarrierPutUpAndOut { .. } => self.knocked,
+            Payoff::Autocallable { .. } => self.called.is_some(),
+            _ => false,
+        }
+    }
+
+    /// Payoff of the prices observed so far, taken as the whole path
+    ///
+    /// At least one price must have been observed.
+    pub fn value(&self) -> f64 {
+        let s_t = self.last;
+        match self.payoff {
+            Payoff::AsianCall { k } => (self.sum / self.observed as f64 - k).max(0.0),
+            Payoff::BarrierCallUpAndOut { k, .. } => {
+                if self.knocked {
+                    0.0
+                } else {
+                    (s_t - k).max(0.0)
+                }
+            }
+            Payoff::BarrierPutUpAndOut { k, .. } => {
+                if self.knocked {
+                    0.0
+                } else {
+                    (k - s_t).max(0.0)
+                }
+            }
+            Payoff::ForwardStartCall { alpha, .. } => (s_t - alpha * self.fixing).max(0.0),
+            Payoff::ForwardStartPut { alpha, .. } => (alpha * self.fixing - s_t).max(0.0),
+            Payoff::Chooser {
+                k, choice_discount, ..
+            } => {
+                if self.fixing >= k * choice_discount {
+                    (s_t - k).max(0.0)
+                } else {
+                    (k - s_t).max(0.0)
+                }
+            }
+            Payoff::Autocallable {
+                notional,
+                put_strike,
+                ..
+            } => match self.called {
+                Some(redemption) => redemption,
+                None if self.knocked => notional * (s_t / put_strike).min(1.0),
+                None => *notional,
+            },
+            payoff => payoff.calculate_terminal(s_t).unwrap_or(f64::NAN),
         }
     }
 }
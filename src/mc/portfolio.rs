@@ -0,0 +1,500 @@
+// src/mc/portfolio.rs
+//! Portfolio Pricing on a Shared Scenario Set
+//!
+//! # Motivation
+//!
+//! Risk is aggregated at the portfolio level. Pricing each position with its
+//! own simulation gives each one independent noise, so netted quantities
+//! (a hedged book's value or Delta) inherit the noise of every leg. Pricing
+//! all positions on the *same* paths lets offsetting positions cancel path by
+//! path, e.g. a long call and a short put netting exactly to a forward.
+//!
+//! # Simulation
+//!
+//! One Brownian path is drawn on the union of all observation dates of the
+//! book (each maturity, plus `steps` uniform fixings up to each maturity for
+//! path-dependent payoffs). Each instrument reads its own dates from the
+//! shared path:
+//! ```text
+//! S(t) = S₀ exp((r - σ²/2) t + σ W_t)
+//! ```
+//!
+//! # Greeks
+//!
+//! Greeks are central differences with common random numbers: the bumped
+//! scenarios reuse the same `W_t`, so
+//! ```text
+//! Δ = [V(S₀ + h) - V(S₀ - h)] / 2h
+//! Γ = [V(S₀ + h) - 2V(S₀) + V(S₀ - h)] / h²
+//! ν = [V(σ + h_σ) - V(σ - h_σ)] / 2h_σ
+//! ```
+//! This works for every payoff type, and the netted Greeks are exactly the
+//! sums of the per-position Greeks.
+//!
+//! # Currencies
+//!
+//! Each instrument pays in its own [`Currency`]; values and Greeks are
+//! converted into the portfolio's reporting currency with its
+//! [`FxConverter`] before they are netted (see [`crate::mc::currency`]).
+
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::mc::currency::{Currency, CurrencyAmount, FxConverter};
+use crate::mc::mc_engine::McConfig;
+use crate::mc::path_generator;
+use crate::mc::payoffs::Payoff;
+use crate::parallel::prelude::*;
+use crate::rng;
+use std::sync::Arc;
+
+/// Absolute volatility bump for portfolio Vega
+const VOL_BUMP: f64 = 1e-3;
+
+/// One position in a portfolio
+#[derive(Clone)]
+pub struct Instrument {
+    /// Contract payoff; its strike and barrier live in the payoff
+    pub payoff: Payoff,
+    /// Signed number of contracts (negative for short positions)
+    pub quantity: f64,
+    /// Time to expiry in years
+    pub maturity: f64,
+    /// Currency the payoff is paid in
+    pub currency: Currency,
+}
+
+/// Collection of positions priced together
+#[derive(Clone, Default)]
+pub struct Portfolio {
+    pub instruments: Vec<Instrument>,
+    /// Currency in which values and Greeks are reported
+    pub currency: Currency,
+    /// Conversion of foreign positions into `currency`; required once the
+    /// book holds one
+    pub fx: Option<Arc<dyn FxConverter>>,
+}
+
+impl Portfolio {
+    pub fn new(instruments: Vec<Instrument>) -> Self {
+        Portfolio {
+            instruments,
+            ..Default::default()
+        }
+    }
+
+    /// Report in `currency`
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    /// Convert foreign positions with `fx`
+    pub fn with_fx(mut self, fx: impl FxConverter + 'static) -> Self {
+        self.fx = Some(Arc::new(fx));
+        self
+    }
+
+    /// Add a position paying in the reporting currency
+    pub fn push(&mut self, payoff: Payoff, quantity: f64, maturity: f64) {
+        self.push_in(payoff, quantity, maturity, self.currency);
+    }
+
+    /// Add a position paying in `currency`
+    pub fn push_in(&mut self, payoff: Payoff, quantity: f64, maturity: f64, currency: Currency) {
+        self.instruments.push(Instrument {
+            payoff,
+            quantity,
+            maturity,
+            currency,
+        });
+    }
+
+    /// Per instrument, units of the reporting currency per unit of its
+    /// payoff currency
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError` for a foreign position without an FX converter,
+    /// or a pair the converter cannot quote.
+    pub fn conversion_rates(&self) -> SdeResult<Vec<f64>> {
+        self.instruments
+            .iter()
+            .map(|inst| {
+                if inst.currency == self.currency {
+                    return Ok(1.0);
+                }
+                let fx = self
+                    .fx
+                    .as_ref()
+                    .ok_or_else(|| SdeError::InvalidConfiguration {
+                        field: "fx".to_string(),
+                        reason: format!(
+                            "a {} position in a {} portfolio needs an FX converter",
+                            inst.currency, self.currency
+                        ),
+                    })?;
+                let rate = fx.fx_rate(inst.currency, self.currency)?;
+                validate_positive("fx_rate", rate)?;
+                Ok(rate)
+            })
+            .collect()
+    }
+
+    /// Validate quantities and maturities
+    pub fn validate(&self) -> SdeResult<()> {
+        if self.instruments.is_empty() {
+            return Err(SdeError::InvalidConfiguration {
+                field: "portfolio".to_string(),
+                reason: "must contain at least one instrument".to_string(),
+            });
+        }
+        for instrument in &self.instruments {
+            validate_finite("quantity", instrument.quantity)?;
+            validate_finite("maturity", instrument.maturity)?;
+            validate_positive("maturity", instrument.maturity)?;
+        }
+        self.conversion_rates()?;
+        Ok(())
+    }
+}
+
+/// Value, standard error and Greeks of a position or of the whole book
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PositionValuation {
+    pub value: f64,
+    pub std_error: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+}
+
+/// Portfolio total and per-instrument contributions, in the reporting
+/// currency
+#[derive(Debug, Clone)]
+pub struct PortfolioValuation {
+    pub currency: Currency,
+    pub total: PositionValuation,
+    /// One entry per instrument, in portfolio order
+    pub positions: Vec<PositionValuation>,
+    /// Value of the positions paying in each currency, in that currency,
+    /// in order of first appearance
+    pub by_currency: Vec<CurrencyAmount>,
+}
+
+/// Scenario layout: base, spot up/down, vol up/down
+const N_SCENARIOS: usize = 5;
+
+/// Price every position of `portfolio` on one shared set of GBM paths
+///
+/// Market inputs (`s0`, `r`, `sigma`), `paths`, `steps`, `seed` and
+/// `use_antithetic` come from `cfg`; `cfg.t` and `cfg.payoff` are ignored.
+/// The spot bump is `cfg.epsilon`, or 1% of spot if unset.
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid configurations or portfolios, or
+/// non-finite results.
+pub fn mc_price_portfolio(cfg: &McConfig, portfolio: &Portfolio) -> SdeResult<PortfolioValuation> {
+    cfg.validate()?;
+    cfg.parallelism.install(|| {
+        portfolio.validate()?;
+
+        let h_s = cfg.epsilon.unwrap_or(0.01 * cfg.s0);
+        let scenarios = [
+            (cfg.s0, cfg.sigma),
+            (cfg.s0 + h_s, cfg.sigma),
+            (cfg.s0 - h_s, cfg.sigma),
+            (cfg.s0, cfg.sigma + VOL_BUMP),
+            (cfg.s0, cfg.sigma - VOL_BUMP),
+        ];
+        let n_inst = portfolio.instruments.len();
+        let width = N_SCENARIOS + 1;
+        let rates = portfolio.conversion_rates()?;
+        let sums = scenario_sums(cfg, portfolio, &rates, &scenarios);
+
+        let nf = cfg.paths as f64;
+        let summarize = |j: usize| {
+            let s = &sums[width * j..width * (j + 1)];
+            let mean = |sc: usize| s[sc] / nf;
+            let variance = if cfg.paths > 1 {
+                (s[N_SCENARIOS] / nf - mean(0) * mean(0)).max(0.0) / (nf - 1.0)
+            } else {
+                0.0
+            };
+            PositionValuation {
+                value: mean(0),
+                std_error: variance.sqrt(),
+                delta: (mean(1) - mean(2)) / (2.0 * h_s),
+                gamma: (mean(1) - 2.0 * mean(0) + mean(2)) / (h_s * h_s),
+                vega: (mean(3) - mean(4)) / (2.0 * VOL_BUMP),
+            }
+        };
+
+        let positions: Vec<PositionValuation> = (0..n_inst).map(summarize).collect();
+        let mut by_currency: Vec<CurrencyAmount> = Vec::new();
+        for ((inst, position), rate) in portfolio.instruments.iter().zip(&positions).zip(&rates) {
+            let amount = position.value / rate;
+            match by_currency.iter_mut().find(|c| c.currency == inst.currency) {
+                Some(entry) => entry.amount += amount,
+                None => by_currency.push(CurrencyAmount {
+                    currency: inst.currency,
+                    amount,
+                }),
+            }
+        }
+        let valuation = PortfolioValuation {
+            currency: portfolio.currency,
+            total: summarize(n_inst),
+            positions,
+            by_currency,
+        };
+
+        if !valuation.total.value.is_finite() || !valuation.total.delta.is_finite() {
+            return Err(SdeError::NumericalInstability {
+                method: "Portfolio pricing".to_string(),
+                reason: format!("non-finite portfolio valuation: {:?}", valuation.total),
+            });
+        }
+
+        Ok(valuation)
+    })
+}
+
+/// Monte Carlo value of the whole book (no Greeks)
+///
+/// Used for full revaluation, where only the total is needed per scenario.
+pub(crate) fn mc_portfolio_value(cfg: &McConfig, portfolio: &Portfolio) -> SdeResult<f64> {
+    Ok(mc_portfolio_scenario_values(cfg, portfolio, &[(cfg.s0, cfg.sigma)])?[0])
+}
+
+/// Monte Carlo value of the whole book in each `(s0, sigma)` scenario
+///
+/// All scenarios share the same paths (common random numbers).
+pub(crate) fn mc_portfolio_scenario_values(
+    cfg: &McConfig,
+    portfolio: &Portfolio,
+    scenarios: &[(f64, f64)],
+) -> SdeResult<Vec<f64>> {
+    cfg.validate()?;
+    portfolio.validate()?;
+    for &(s0, sigma) in scenarios {
+        validate_positive("s0", s0)?;
+        validate_positive("sigma", sigma)?;
+    }
+    let rates = portfolio.conversion_rates()?;
+    let sums = scenario_sums(cfg, portfolio, &rates, scenarios);
+    let total = (scenarios.len() + 1) * portfolio.instruments.len();
+    let values: Vec<f64> = sums[total..total + scenarios.len()]
+        .iter()
+        .map(|s| s / cfg.paths as f64)
+        .collect();
+    if values.iter().any(|v| !v.is_finite()) {
+        return Err(SdeError::NumericalInstability {
+            method: "Portfolio pricing".to_string(),
+            reason: "non-finite scenario value".to_string(),
+        });
+    }
+    Ok(values)
+}
+
+/// Discounted payoff sums on shared paths for each `(s0, sigma)` scenario,
+/// converted into the reporting currency at `rates`
+///
+/// Layout: for each instrument, then for the book total, one sum per
+/// scenario followed by the sum of squared base-scenario values.
+fn scenario_sums(
+    cfg: &McConfig,
+    portfolio: &Portfolio,
+    rates: &[f64],
+    scenarios: &[(f64, f64)],
+) -> Vec<f64> {
+    let (grid, observed) = observation_grid(portfolio, cfg.steps, &[]);
+    let discounts: Vec<f64> = portfolio
+        .instruments
+        .iter()
+        .zip(rates)
+        .map(|(inst, rate)| rate * (-cfg.r * inst.maturity).exp())
+        .collect();
+
+    let n_inst = portfolio.instruments.len();
+    // Per instrument: one sum per scenario plus the base value squared;
+    // then the same for the book total
+    let n_scenarios = scenarios.len();
+    let width = n_scenarios + 1;
+    let len = width * (n_inst + 1);
+
+    (0..cfg.paths)
+        .into_par_iter()
+        .fold(
+            || vec![0.0; len],
+            |mut acc, i| {
+                let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+                let draws: Vec<f64> = grid
+                    .iter()
+                    .map(|_| rng::get_normal_draw(&mut rng))
+                    .collect();
+                let signs: &[f64] = if cfg.use_antithetic {
+                    &[1.0, -1.0]
+                } else {
+                    &[1.0]
+                };
+                let weight = 1.0 / signs.len() as f64;
+
+                let mut values = vec![0.0; n_scenarios * (n_inst + 1)];
+                let mut path = Vec::new();
+                for &sign in signs {
+                    let brownian = path_generator::brownian_path(&grid, &draws, sign);
+                    for (sc, &(s0, sigma)) in scenarios.iter().enumerate() {
+                        for (j, inst) in portfolio.instruments.iter().enumerate() {
+                            path.clear();
+                            path.push(s0);
+                            path.extend(observed[j].iter().map(|&g| {
+                                path_generator::gbm_spot(s0, cfg.r, sigma, grid[g], brownian[g])
+                            }));
+                            let v = weight
+                                * inst.quantity
+                                * discounts[j]
+                                * inst.payoff.calculate(&path);
+                            values[sc * (n_inst + 1) + j] += v;
+                            values[sc * (n_inst + 1) + n_inst] += v;
+                        }
+                    }
+                }
+
+                for j in 0..=n_inst {
+                    for sc in 0..n_scenarios {
+                        acc[width * j + sc] += values[sc * (n_inst + 1) + j];
+                    }
+                    acc[width * j + n_scenarios] += values[j] * values[j];
+                }
+                acc
+            },
+        )
+        .reduce(
+            || vec![0.0; len],
+            |mut a, b| {
+                for (x, y) in a.iter_mut().zip(b.iter()) {
+                    *x += y;
+                }
+                a
+            },
+        )
+}
+
+/// Observation grid of `portfolio` joined with `extra_dates` and, per
+/// instrument, the grid indices it reads
+pub(crate) fn observation_grid(
+    portfolio: &Portfolio,
+    steps: usize,
+    extra_dates: &[f64],
+) -> (Vec<f64>, Vec<Vec<usize>>) {
+    let date_sets: Vec<Vec<f64>> = portfolio
+        .instruments
+        .iter()
+        .map(|inst| {
+            path_generator::observation_dates(
+                inst.maturity,
+                steps,
+                inst.payoff.requires_full_path(),
+            )
+        })
+        .collect();
+    path_generator::observation_grid(&date_sets, extra_dates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::bs_analytic;
+
+    #[test]
+    fn test_call_put_parity_nets_to_forward() {
+        let cfg = McConfig {
+            paths: 20_000,
+            steps: 12,
+            seed: 9,
+            r: 0.03,
+            ..Default::default()
+        };
+        let (k, t) = (95.0, 0.75);
+        let mut book = Portfolio::default();
+        book.push(Payoff::EuropeanCall { k }, 2.0, t);
+        book.push(Payoff::EuropeanPut { k }, -2.0, t);
+        book.push(Payoff::AsianCall { k: 100.0 }, 1.0, 1.0);
+
+        let valuation = mc_price_portfolio(&cfg, &book).expect("Valid portfolio");
+        assert_eq!(valuation.positions.len(), 3);
+
+        // Contributions and Greeks add up to the book
+        let sum: f64 = valuation.positions.iter().map(|p| p.value).sum();
+        let delta: f64 = valuation.positions.iter().map(|p| p.delta).sum();
+        assert!((sum - valuation.total.value).abs() < 1e-9);
+        assert!((delta - valuation.total.delta).abs() < 1e-9);
+
+        // Long call + short put is a forward on every path
+        let forward = 2.0 * (cfg.s0 - k * (-cfg.r * t).exp());
+        let call_put = valuation.positions[0].value + valuation.positions[1].value;
+        let call_put_delta = valuation.positions[0].delta + valuation.positions[1].delta;
+        assert!(
+            (call_put - forward).abs() < 0.15,
+            "{} vs {}",
+            call_put,
+            forward
+        );
+        assert!((call_put_delta - 2.0).abs() < 2e-3, "{}", call_put_delta);
+
+        // Call Vega matches Black-Scholes (two contracts)
+        let bs_vega = 2.0 * bs_analytic::bs_call_vega(cfg.s0, k, cfg.r, cfg.sigma, t);
+        assert!(
+            (valuation.positions[0].vega - bs_vega).abs() < 0.03 * bs_vega,
+            "{} vs {}",
+            valuation.positions[0].vega,
+            bs_vega
+        );
+
+        let mut empty = Portfolio::default();
+        assert!(mc_price_portfolio(&cfg, &empty).is_err());
+        empty.push(Payoff::EuropeanCall { k }, 1.0, -1.0);
+        assert!(mc_price_portfolio(&cfg, &empty).is_err());
+    }
+
+    #[test]
+    fn test_mixed_currency_book_aggregates_in_reporting_currency() {
+        use crate::mc::currency::FxRates;
+
+        let cfg = McConfig {
+            paths: 5_000,
+            seed: 4,
+            ..Default::default()
+        };
+        let mut usd = Portfolio::default();
+        usd.push(Payoff::EuropeanCall { k: 100.0 }, 1.0, 1.0);
+        usd.push(Payoff::EuropeanPut { k: 100.0 }, 1.0, 1.0);
+        let usd_only = mc_price_portfolio(&cfg, &usd).unwrap();
+
+        let fx = FxRates::new(Currency::USD)
+            .with_quote(Currency::EUR, 1.1)
+            .unwrap();
+        let mut book = Portfolio::default().with_fx(fx);
+        book.push(Payoff::EuropeanCall { k: 100.0 }, 1.0, 1.0);
+        book.push_in(Payoff::EuropeanPut { k: 100.0 }, 1.0, 1.0, Currency::EUR);
+        let mixed = mc_price_portfolio(&cfg, &book).unwrap();
+
+        // Same paths, with the EUR put converted into USD before netting
+        let (call, put) = (usd_only.positions[0], usd_only.positions[1]);
+        assert_eq!(mixed.currency, Currency::USD);
+        assert!((mixed.positions[1].value - 1.1 * put.value).abs() < 1e-9);
+        assert!((mixed.total.value - (call.value + 1.1 * put.value)).abs() < 1e-9);
+        assert!((mixed.total.delta - (call.delta + 1.1 * put.delta)).abs() < 1e-9);
+        assert_eq!(mixed.by_currency.len(), 2);
+        assert_eq!(mixed.by_currency[1].currency, Currency::EUR);
+        assert!((mixed.by_currency[1].amount - put.value).abs() < 1e-9);
+
+        // Reporting in EUR rescales the whole book
+        let in_eur = mc_price_portfolio(&cfg, &book.clone().with_currency(Currency::EUR)).unwrap();
+        assert!((in_eur.total.value - mixed.total.value / 1.1).abs() < 1e-9);
+
+        // A foreign position cannot be netted without a converter
+        book.fx = None;
+        assert!(mc_price_portfolio(&cfg, &book).is_err());
+    }
+}
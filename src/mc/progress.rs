@@ -0,0 +1,320 @@
+// src/mc/progress.rs
+//! Progress Reporting and Cancellation
+//!
+//! # Chunked Runs
+//!
+//! [`mc_price_with_progress`] simulates `cfg.paths` paths in consecutive
+//! chunks of [`RunControl::chunk_paths`]. After every chunk it calls the
+//! progress callback with the running estimate, and before every chunk it
+//! checks the [`CancellationToken`]. A cancelled run returns the estimate
+//! over the paths actually completed, so a long simulation can be stopped
+//! as soon as its error bar is good enough.
+//!
+//! # Estimator
+//!
+//! Draws follow the engine (block `i` of `cfg.normal_source`, antithetic
+//! pairs as one block), so a run that completes uses the same sample as
+//! [`mc_price_option_gbm`]. With control variates the coefficients are
+//! re-estimated from the running co-moments of payoff `Y` and controls `X`:
+//! ```text
+//! b = Σ_XX⁻¹ Σ_XY,    price = e^{-rT} (Ȳ - bᵀ(X̄ - E[X]))
+//! s² = Var(Y) - 2bᵀΣ_XY + bᵀΣ_XX b,    std_error = e^{-rT} s / √n
+//! ```
+//! which gives the engine's price in a single pass.
+//!
+//! [`mc_price_option_gbm`]: crate::mc::mc_engine::mc_price_option_gbm
+
+use crate::error::{SdeError, SdeResult};
+use crate::math_utils::Timer;
+use crate::mc::accumulators;
+use crate::mc::control_variates::{
+    engine_controls, push_sample, ControlFit, ControlVariate, PathMoments,
+};
+use crate::mc::mc_engine::{
+    engine_normal_source, payoff_and_control, priced_payoff, reduce_path_range,
+    simulation_increments, McConfig,
+};
+use crate::trace::{debug_event, span};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Shared flag that stops a running simulation at the next chunk boundary
+///
+/// Clones share the flag, so one clone can be handed to another thread
+/// (a UI, a request handler) and cancelled from there.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Running estimate reported after each chunk
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    pub paths_completed: usize,
+    pub paths_total: usize,
+    pub price: f64,
+    pub std_error: f64,
+    pub elapsed_ms: f64,
+}
+
+impl Progress {
+    /// Completed share of the run in [0, 1]
+    pub fn fraction(&self) -> f64 {
+        self.paths_completed as f64 / self.paths_total as f64
+    }
+}
+
+/// Callback receiving [`Progress`] after each chunk
+pub type ProgressCallback = Arc<dyn Fn(&Progress) + Send + Sync>;
+
+/// Progress reporting and cancellation of [`mc_price_with_progress`]
+#[derive(Clone)]
+pub struct RunControl {
+    /// Paths between progress reports and cancellation checks
+    /// (default 65 536)
+    pub chunk_paths: usize,
+    pub on_progress: Option<ProgressCallback>,
+    pub cancel: Option<CancellationToken>,
+}
+
+impl Default for RunControl {
+    fn default() -> Self {
+        RunControl {
+            chunk_paths: 65_536,
+            on_progress: None,
+            cancel: None,
+        }
+    }
+}
+
+/// Outcome of [`mc_price_with_progress`]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProgressResult {
+    pub price: f64,
+    pub std_error: f64,
+    /// Paths simulated (blocks of the normal source, so antithetic pairs
+    /// count once)
+    pub paths_completed: usize,
+    /// Whether the run stopped early on cancellation
+    pub cancelled: bool,
+}
+
+/// Price `cfg.payoff` in chunks, reporting progress and honouring
+/// cancellation between chunks
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid configurations, a zero `chunk_paths`,
+/// cancellation before the first chunk completed, or non-finite estimates.
+pub fn mc_price_with_progress(cfg: &McConfig, control: &RunControl) -> SdeResult<ProgressResult> {
+    let cfg = &chunked_config(cfg, control)?;
+    run_chunks(cfg, control, None, cfg.deterministic, |_, _| Ok(()))
+}
+
+/// Validate `cfg` and `control` and return `cfg` with its barrier shift
+/// applied to the payoff
+pub(crate) fn chunked_config(cfg: &McConfig, control: &RunControl) -> SdeResult<McConfig> {
+    cfg.validate()?;
+    if control.chunk_paths == 0 {
+        return Err(SdeError::InvalidConfiguration {
+            field: "chunk_paths".to_string(),
+            reason: "must be at least 1".to_string(),
+        });
+    }
+    Ok(McConfig {
+        payoff: priced_payoff(cfg),
+        barrier_shift: None,
+        ..cfg.clone()
+    })
+}
+
+/// Simulate the paths of `cfg` (prepared by [`chunked_config`]) in chunks,
+/// starting from `resume` (moments and next path) if given, and call
+/// `after_chunk` with the paths completed and the moments after each chunk
+///
+/// With `fixed_tree` each chunk is reduced in a fixed order, so the
+/// moments after every chunk do not depend on scheduling.
+pub(crate) fn run_chunks(
+    cfg: &McConfig,
+    control: &RunControl,
+    resume: Option<(PathMoments, usize)>,
+    fixed_tree: bool,
+    mut after_chunk: impl FnMut(usize, &PathMoments) -> SdeResult<()>,
+) -> SdeResult<ProgressResult> {
+    let _span = span!(
+        "mc_price_with_progress",
+        paths = cfg.paths,
+        chunk_paths = control.chunk_paths
+    );
+    let grid = simulation_increments(cfg);
+    let source = engine_normal_source(cfg);
+    let controls = engine_controls(cfg);
+    let new_moments = || PathMoments::new(controls.len() + 1);
+    let timer = Timer::new();
+
+    let (mut moments, mut n) = resume.unwrap_or_else(|| (new_moments(), 0));
+    if moments.dim() != controls.len() + 1 || n > cfg.paths {
+        return Err(SdeError::InvalidConfiguration {
+            field: "resume".to_string(),
+            reason: "state does not belong to this configuration".to_string(),
+        });
+    }
+    let mut cancelled = false;
+    while n < cfg.paths {
+        if control.cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+            cancelled = true;
+            break;
+        }
+        let size = control.chunk_paths.min(cfg.paths - n);
+        let chunk = cfg.parallelism.install(|| {
+            reduce_path_range(
+                cfg,
+                n..n + size,
+                fixed_tree,
+                |i, buffers| payoff_and_control(cfg, &grid, &source, &controls, i, buffers),
+                new_moments,
+                |acc, (payoff, values)| push_sample(acc, payoff, &values),
+                PathMoments::merge,
+            )
+        });
+        moments.merge(chunk);
+        n += size;
+        debug_event!(paths_completed = n, "chunk completed");
+        after_chunk(n, &moments)?;
+
+        if let Some(on_progress) = &control.on_progress {
+            let (price, std_error) = estimate(cfg, &controls, &moments);
+            on_progress(&Progress {
+                paths_completed: n,
+                paths_total: cfg.paths,
+                price,
+                std_error,
+                elapsed_ms: timer.elapsed_ms(),
+            });
+        }
+    }
+
+    if n == 0 {
+        return Err(SdeError::MonteCarloError {
+            paths: 0,
+            reason: "cancelled before any path completed".to_string(),
+        });
+    }
+    let (price, std_error) = estimate(cfg, &controls, &moments);
+    if !price.is_finite() || !std_error.is_finite() {
+        return Err(SdeError::NumericalInstability {
+            method: "Chunked Monte Carlo".to_string(),
+            reason: format!("non-finite estimate after {} paths", n),
+        });
+    }
+    Ok(ProgressResult {
+        price,
+        std_error,
+        paths_completed: n,
+        cancelled,
+    })
+}
+
+/// Discounted price and standard error from the running co-moments of
+/// (payoff, controls)
+fn estimate(
+    cfg: &McConfig,
+    controls: &[Arc<dyn ControlVariate>],
+    moments: &PathMoments,
+) -> (f64, f64) {
+    let discount = (-cfg.r * cfg.t).exp();
+    // Same regression as the engine; without controls it is the plain mean
+    let (mean, population) = ControlFit::new(cfg, controls, moments).moments(moments);
+    let variance = accumulators::variance_of_mean(population, moments.count());
+    (discount * mean, discount * variance.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mc::mc_engine::mc_price_option_gbm;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_progress_reports_and_matches_engine() {
+        let cfg = McConfig {
+            paths: 50_000,
+            ..Default::default()
+        };
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let control = RunControl {
+            chunk_paths: 12_000,
+            on_progress: Some(Arc::new(move |p: &Progress| sink.lock().unwrap().push(*p))),
+            cancel: None,
+        };
+        let result = mc_price_with_progress(&cfg, &control).expect("Valid configuration");
+        let reports = reports.lock().unwrap();
+
+        let completed: Vec<usize> = reports.iter().map(|p| p.paths_completed).collect();
+        assert_eq!(completed, [12_000, 24_000, 36_000, 48_000, 50_000]);
+        assert_eq!(reports.last().unwrap().fraction(), 1.0);
+        assert!(!result.cancelled);
+        assert_eq!(result.price, reports.last().unwrap().price);
+
+        // Same sample and control variate estimator as the engine
+        let (engine_price, _) = mc_price_option_gbm(&cfg).expect("Valid configuration");
+        assert!((result.price - engine_price).abs() < 1e-9);
+        let plain = McConfig {
+            use_control_variate: false,
+            ..cfg.clone()
+        };
+        let (plain_price, _) = mc_price_option_gbm(&plain).expect("Valid configuration");
+        let plain_result = mc_price_with_progress(&plain, &RunControl::default()).unwrap();
+        assert!((plain_result.price - plain_price).abs() < 1e-9);
+        assert!(plain_result.std_error > result.std_error);
+    }
+
+    #[test]
+    fn test_cancellation_returns_partial_estimate() {
+        let cfg = McConfig {
+            paths: 100_000,
+            use_control_variate: false,
+            ..Default::default()
+        };
+        let token = CancellationToken::new();
+        let handle = token.clone();
+        let control = RunControl {
+            chunk_paths: 10_000,
+            on_progress: Some(Arc::new(move |p: &Progress| {
+                if p.paths_completed >= 20_000 {
+                    handle.cancel();
+                }
+            })),
+            cancel: Some(token.clone()),
+        };
+        let result = mc_price_with_progress(&cfg, &control).expect("Valid configuration");
+        assert!(result.cancelled);
+        assert_eq!(result.paths_completed, 20_000);
+
+        // The partial estimate is the fixed run over the completed paths
+        let partial = McConfig {
+            paths: 20_000,
+            ..cfg.clone()
+        };
+        let (price, _) = mc_price_option_gbm(&partial).expect("Valid configuration");
+        assert!((result.price - price).abs() < 1e-9);
+
+        // Already cancelled: nothing to report
+        assert!(mc_price_with_progress(&cfg, &control).is_err());
+    }
+}
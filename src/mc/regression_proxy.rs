@@ -0,0 +1,239 @@
+// src/mc/regression_proxy.rs
+//! Least-Squares Regression Proxies for Future Values
+//!
+//! # Proxy
+//!
+//! The value at a horizon `t` is a conditional expectation of the
+//! discounted future cashflows `Y` given the state `X_t`. Instead of
+//! repricing every scenario with an inner simulation (see
+//! [`nested`](crate::mc::nested)), the American Monte Carlo approach
+//! (Longstaff & Schwartz, 2001) regresses the realised cashflows of the
+//! outer paths on a basis of the state:
+//! ```text
+//! V(x) ≈ Σ_k β_k φ_k(x),   β = argmin Σᵢ (Yᵢ - Σ_k β_k φ_k(Xᵢ))²
+//! ```
+//! Since `E[Y | X_t] = V(X_t)`, the fit converges to the best polynomial
+//! approximation of `V` as the number of paths grows, at the cost of a
+//! single simulation.
+//!
+//! # Basis
+//!
+//! The basis holds every monomial of total degree at most `degree` in the
+//! standardised state `(x_d - mean_d) / std_d`, e.g. for two state
+//! variables and degree 2
+//! ```text
+//! 1, x₁, x₂, x₁², x₁x₂, x₂²
+//! ```
+//! Standardising keeps the normal equations well conditioned; they are
+//! solved by SVD, so collinear terms are dropped rather than amplified.
+//!
+//! # Limitations
+//!
+//! The proxy is only as good as the state: a path-dependent value that is
+//! regressed on spot alone is approximated by its average over the
+//! histories leading to that spot.
+
+use crate::error::{SdeError, SdeResult};
+use nalgebra::{DMatrix, DVector};
+
+/// Polynomial least-squares approximation of a conditional value
+#[derive(Debug, Clone)]
+pub struct RegressionProxy {
+    dim: usize,
+    /// Exponents of each basis monomial, one per state variable
+    exponents: Vec<Vec<u32>>,
+    center: Vec<f64>,
+    scale: Vec<f64>,
+    coefficients: Vec<f64>,
+    /// Share of the target variance explained by the fit
+    pub r_squared: f64,
+}
+
+impl RegressionProxy {
+    /// Fit the proxy of `targets` on `states` with polynomials of total
+    /// degree at most `degree`
+    ///
+    /// `states` is row-major with `dim` variables per sample, so it holds
+    /// `dim * targets.len()` values.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError` if `dim` is zero, the lengths disagree, there are
+    /// no more samples than basis terms, or an input is not finite.
+    pub fn fit(states: &[f64], targets: &[f64], dim: usize, degree: usize) -> SdeResult<Self> {
+        if dim == 0 || states.len() != dim * targets.len() {
+            return Err(SdeError::InvalidConfiguration {
+                field: "states".to_string(),
+                reason: format!(
+                    "{} values do not hold {} samples of dimension {}",
+                    states.len(),
+                    targets.len(),
+                    dim
+                ),
+            });
+        }
+        let exponents = monomials(dim, degree);
+        let n = targets.len();
+        if n <= exponents.len() {
+            return Err(SdeError::InvalidConfiguration {
+                field: "targets".to_string(),
+                reason: format!("{} samples cannot fit {} basis terms", n, exponents.len()),
+            });
+        }
+        if states.iter().chain(targets).any(|x| !x.is_finite()) {
+            return Err(SdeError::NumericalInstability {
+                method: "Regression proxy".to_string(),
+                reason: "non-finite state or target".to_string(),
+            });
+        }
+
+        let mut center = vec![0.0; dim];
+        let mut scale = vec![0.0; dim];
+        for row in states.chunks(dim) {
+            for (c, x) in center.iter_mut().zip(row) {
+                *c += x / n as f64;
+            }
+        }
+        for row in states.chunks(dim) {
+            for ((s, c), x) in scale.iter_mut().zip(&center).zip(row) {
+                *s += (x - c).powi(2) / n as f64;
+            }
+        }
+        // A constant state variable contributes nothing beyond the intercept
+        let scale: Vec<f64> = scale
+            .into_iter()
+            .map(|v| if v > 0.0 { v.sqrt() } else { 1.0 })
+            .collect();
+
+        let mut proxy = RegressionProxy {
+            dim,
+            exponents,
+            center,
+            scale,
+            coefficients: Vec::new(),
+            r_squared: 0.0,
+        };
+        let p = proxy.exponents.len();
+        let mut xtx = DMatrix::<f64>::zeros(p, p);
+        let mut xty = DVector::<f64>::zeros(p);
+        let mut features = vec![0.0; p];
+        for (row, &y) in states.chunks(dim).zip(targets) {
+            proxy.features(row, &mut features);
+            for (a, &fa) in features.iter().enumerate() {
+                xty[a] += fa * y;
+                for (b, &fb) in features.iter().enumerate().take(a + 1) {
+                    xtx[(a, b)] += fa * fb;
+                }
+            }
+        }
+        xtx.fill_upper_triangle_with_lower_triangle();
+        let svd = xtx.svd(true, true);
+        let eps = 1e-12 * svd.singular_values.max();
+        let beta = svd
+            .solve(&xty, eps)
+            .map_err(|reason| SdeError::NumericalInstability {
+                method: "Regression proxy".to_string(),
+                reason: reason.to_string(),
+            })?;
+        proxy.coefficients = beta.iter().copied().collect();
+
+        let mean = targets.iter().sum::<f64>() / n as f64;
+        let (mut residual, mut total) = (0.0, 0.0);
+        for (row, &y) in states.chunks(dim).zip(targets) {
+            residual += (y - proxy.value(row)).powi(2);
+            total += (y - mean).powi(2);
+        }
+        proxy.r_squared = if total > 0.0 {
+            1.0 - residual / total
+        } else {
+            1.0
+        };
+        Ok(proxy)
+    }
+
+    /// Proxy value at `state`, which holds one value per state variable
+    pub fn value(&self, state: &[f64]) -> f64 {
+        let mut features = vec![0.0; self.exponents.len()];
+        self.features(state, &mut features);
+        features
+            .iter()
+            .zip(&self.coefficients)
+            .map(|(f, b)| f * b)
+            .sum()
+    }
+
+    /// Number of state variables
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Number of basis monomials
+    pub fn terms(&self) -> usize {
+        self.exponents.len()
+    }
+
+    fn features(&self, state: &[f64], out: &mut [f64]) {
+        for (f, powers) in out.iter_mut().zip(&self.exponents) {
+            *f = powers
+                .iter()
+                .zip(state)
+                .zip(self.center.iter().zip(&self.scale))
+                .map(|((&k, &x), (c, s))| ((x - c) / s).powi(k as i32))
+                .product();
+        }
+    }
+}
+
+/// Exponents of all monomials in `dim` variables of total degree at most
+/// `degree`, by increasing degree
+fn monomials(dim: usize, degree: usize) -> Vec<Vec<u32>> {
+    let mut all = vec![vec![0; dim]];
+    let mut last = vec![vec![0; dim]];
+    for _ in 0..degree {
+        let mut next: Vec<Vec<u32>> = Vec::new();
+        for powers in &last {
+            // Raise only variables at or after the last raised one, so each
+            // monomial is generated once
+            let first = powers.iter().rposition(|&k| k > 0).unwrap_or(0);
+            for d in first..dim {
+                let mut raised = powers.clone();
+                raised[d] += 1;
+                next.push(raised);
+            }
+        }
+        all.extend(next.iter().cloned());
+        last = next;
+    }
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+
+    #[test]
+    fn test_proxy_recovers_polynomial_value() {
+        assert_eq!(monomials(2, 2).len(), 6);
+        assert_eq!(monomials(3, 3).len(), 20);
+
+        // Noisy targets around a quadratic in two state variables
+        let value = |x: f64, y: f64| 1.0 + 2.0 * x - y + 0.5 * x * y + 0.25 * y * y;
+        let mut rng = rng::seed_rng_from_u64(5);
+        let (mut states, mut targets) = (Vec::new(), Vec::new());
+        for i in 0..2_000 {
+            let (x, y) = ((i % 40) as f64 / 10.0, (i / 40) as f64 / 25.0);
+            states.extend([x, y]);
+            targets.push(value(x, y) + 0.3 * rng::get_normal_draw(&mut rng));
+        }
+        let proxy = RegressionProxy::fit(&states, &targets, 2, 2).unwrap();
+        assert_eq!((proxy.dim(), proxy.terms()), (2, 6));
+        for (x, y) in [(0.5, 1.0), (3.0, 0.2), (2.0, 1.8)] {
+            assert!((proxy.value(&[x, y]) - value(x, y)).abs() < 0.05);
+        }
+        assert!(proxy.r_squared > 0.9 && proxy.r_squared < 1.0);
+
+        assert!(RegressionProxy::fit(&states[1..], &targets, 2, 2).is_err());
+        assert!(RegressionProxy::fit(&states[..12], &targets[..6], 2, 2).is_err());
+    }
+}
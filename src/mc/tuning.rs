@@ -0,0 +1,128 @@
+// src/mc/tuning.rs
+//! Warm-Up Timing and Batch Size Selection
+//!
+//! # Motivation
+//!
+//! Rayon splits a path range adaptively, but with very cheap paths the
+//! per-task overhead dominates, and with heterogeneous paths (a barrier that
+//! knocks out early is cheap, one that survives is not) large fixed chunks
+//! leave threads idle at the end of the run.
+//!
+//! # Heuristic
+//!
+//! [`tune_batch_size`] times a warm-up batch path by path and picks the
+//! chunk size from the mean cost `c̄` and its coefficient of variation `CV`:
+//! ```text
+//! chunk = TARGET_TASK_NS / (c̄ (1 + CV))
+//! ```
+//! capped so that every thread still gets at least `TASKS_PER_THREAD`
+//! chunks. The result is used by setting `McConfig::chunk_size`; the price
+//! is the same up to floating-point summation order.
+
+use crate::error::SdeResult;
+use crate::math_utils::Instant;
+use crate::mc::mc_engine::{
+    engine_normal_source, for_each_block_path, simulation_increments, BlockBuffers, McConfig,
+};
+
+/// Wall-clock work per Rayon task the tuner aims for
+const TARGET_TASK_NS: f64 = 200_000.0;
+/// Minimum number of chunks per worker thread, for load balance
+const TASKS_PER_THREAD: usize = 8;
+/// Upper bound on warm-up paths
+const MAX_WARMUP_PATHS: usize = 2_000;
+
+/// Measured path cost and the chosen chunk size
+#[derive(Debug, Clone, Copy)]
+pub struct BatchTuning {
+    /// Paths timed in the warm-up batch
+    pub warmup_paths: usize,
+    /// Mean cost of one path in nanoseconds
+    pub mean_path_ns: f64,
+    /// Coefficient of variation of the per-path cost
+    pub cost_cv: f64,
+    /// Recommended `McConfig::chunk_size`
+    pub chunk_size: usize,
+}
+
+impl BatchTuning {
+    /// Copy of `cfg` using the tuned chunk size
+    pub fn apply(&self, cfg: &McConfig) -> McConfig {
+        McConfig {
+            chunk_size: Some(self.chunk_size),
+            ..cfg.clone()
+        }
+    }
+}
+
+/// Time a warm-up batch of `cfg` and choose a chunk size for the full run
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid configurations.
+pub fn tune_batch_size(cfg: &McConfig) -> SdeResult<BatchTuning> {
+    cfg.validate()?;
+    let grid = simulation_increments(cfg);
+    let warmup_paths = cfg.paths.min(MAX_WARMUP_PATHS);
+
+    let source = engine_normal_source(cfg);
+    let mut buffers = BlockBuffers::default();
+    let costs: Vec<f64> = (0..warmup_paths)
+        .map(|i| {
+            let start = Instant::now();
+            let mut payoff = 0.0;
+            for_each_block_path(cfg, &grid, &source, i, &mut buffers, |path| {
+                payoff += cfg.payoff.calculate(path);
+            });
+            std::hint::black_box(payoff);
+            start.elapsed().as_nanos() as f64
+        })
+        .collect();
+
+    let n = costs.len() as f64;
+    let mean = costs.iter().sum::<f64>() / n;
+    let variance = costs.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / n;
+    let cost_cv = if mean > 0.0 {
+        variance.sqrt() / mean
+    } else {
+        0.0
+    };
+
+    let balanced_max = (cfg.paths / (TASKS_PER_THREAD * cfg.parallelism.threads())).max(1);
+    let ideal = TARGET_TASK_NS / (mean.max(1.0) * (1.0 + cost_cv));
+    let chunk_size = (ideal as usize).clamp(1, balanced_max);
+
+    Ok(BatchTuning {
+        warmup_paths,
+        mean_path_ns: mean,
+        cost_cv,
+        chunk_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mc::mc_engine::mc_price_option_gbm;
+
+    #[test]
+    fn test_tuned_chunks_keep_price() {
+        let cfg = McConfig {
+            paths: 20_000,
+            steps: 20,
+            use_control_variate: false,
+            ..Default::default()
+        };
+        let tuning = tune_batch_size(&cfg).expect("Valid configuration");
+        assert!(tuning.mean_path_ns > 0.0);
+        assert!(tuning.chunk_size >= 1);
+        assert!(
+            tuning.chunk_size
+                <= cfg.paths / (TASKS_PER_THREAD * crate::parallel::current_num_threads())
+        );
+
+        let (base, _) = mc_price_option_gbm(&cfg).expect("Valid configuration");
+        let (tuned, _) = mc_price_option_gbm(&tuning.apply(&cfg)).expect("Valid configuration");
+        assert!((base - tuned).abs() < 1e-9 * base);
+    }
+}
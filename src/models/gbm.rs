@@ -33,6 +33,18 @@ impl SDEModel for Gbm {
         self.sigma
     }
 
+    fn drift_derivative(&self, _s: f64, _t: f64) -> f64 {
+        self.mu
+    }
+
+    fn drift_second_derivative(&self, _s: f64, _t: f64) -> f64 {
+        0.0
+    }
+
+    fn diffusion_second_derivative(&self, _s: f64, _t: f64) -> f64 {
+        0.0
+    }
+
     fn step_with_dw(&self, s_current: &mut f64, t_current: f64, dt: f64, dw: f64) {
         // This is an Euler-Maruyama step, not the exact solution.
         *s_current +=
@@ -25,28 +25,66 @@
 //! ```
 //!
 //! When violated, variance can hit zero, requiring careful numerical treatment.
+//! [`FellerPolicy`] decides whether [`Heston::new_with_policy`] rejects such
+//! parameters, reports them, or accepts them silently;
+//! [`HestonParams::feller_satisfied`] checks the condition directly.
 //!
 //! # Discretization Schemes
 //!
-//! Three schemes are implemented with different stability/accuracy tradeoffs:
+//! Four schemes are implemented with different stability/accuracy tradeoffs:
 //! 1. **Andersen QE**: Most robust, handles Feller violations gracefully
-//! 2. **Alfonsi**: High-order weak convergence, good for smooth payoffs  
+//!    (**QE-M** adds Andersen's martingale correction, so the discounted
+//!    price is an exact martingale of the discrete scheme)
+//! 2. **Alfonsi**: Second-order weak convergence with a non-negative
+//!    variance, good for smooth payoffs (see [`super::alfonsi`])
 //! 3. **Full Truncation Euler**: Fastest but can be unstable
+//!
+//! Each step returns the [`Warnings`] it raised: a variance update truncated
+//! at zero (Full Truncation Euler) or a price floored to stay
+//! positive (QE). Frequent truncations mean the grid is too coarse for the
+//! parameters.
+//!
+//! # Antithetic Sampling
+//!
+//! [`Heston::step_antithetic`] advances a pair of states on `(z1, z2)` and
+//! `(-z1, -z2)`, which negates both correlated increments `ΔW_s` and `ΔW_v`.
+//! The QE uniform `u` of the first state is reflected to `1 - u` for the
+//! second, so the exponential branch is antithetic too.
 
+use super::alfonsi::AlfonsiCir;
 use super::model::SDEModel;
-use crate::error::{validation::*, SdeError, SdeResult};
+use crate::error::{validation::*, SdeError, SdeResult, Warnings};
 use crate::rng;
+use crate::trace::warn_event;
 use rand::Rng;
 use std::f64;
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HestonScheme {
     FullTruncationEuler,
     AndersenQE,
+    /// Andersen QE with the martingale-corrected drift of the price
+    AndersenQEM,
     Alfonsi,
 }
 
+/// Reaction to parameters that violate the Feller condition `2κθ > ξ²`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FellerPolicy {
+    /// Reject with `SdeError::FellerConditionViolation`
+    Error,
+    /// Accept and report the violation as a `tracing` WARN event (silent
+    /// without the `tracing` feature)
+    #[default]
+    Warn,
+    /// Accept silently
+    Ignore,
+}
+
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HestonParams {
     pub s0: f64,    // Initial stock price
     pub v0: f64,    // Initial variance
@@ -57,6 +95,14 @@ pub struct HestonParams {
     pub rho: f64,   // Correlation between stock and variance
 }
 
+impl HestonParams {
+    /// Feller condition `2κθ > ξ²`, under which the variance stays strictly
+    /// positive
+    pub fn feller_satisfied(&self) -> bool {
+        2.0 * self.kappa * self.theta > self.xi * self.xi
+    }
+}
+
 pub struct Heston {
     pub params: HestonParams,
     pub scheme: HestonScheme,
@@ -68,32 +114,70 @@ impl Heston {
     }
 
     pub fn new_with_scheme(params: HestonParams, scheme: HestonScheme) -> SdeResult<Self> {
-        Self::new_with_scheme_quiet(params, scheme, false)
+        Self::new_with_policy(params, scheme, FellerPolicy::default())
     }
 
-    pub fn new_with_scheme_quiet(
+    /// Validate `params` and handle a Feller violation according to `policy`
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidParameters` for invalid parameters, and
+    /// `SdeError::FellerConditionViolation` for a violation under
+    /// [`FellerPolicy::Error`].
+    pub fn new_with_policy(
         params: HestonParams,
         scheme: HestonScheme,
-        suppress_warnings: bool,
+        policy: FellerPolicy,
     ) -> SdeResult<Self> {
         // Validate all parameters
         Self::validate_params(&params)?;
 
-        // Check Feller condition
-        let feller = 2.0 * params.kappa * params.theta;
-        if feller <= params.xi * params.xi {
-            if !suppress_warnings {
-                eprintln!("WARNING!: Feller condition violated (2κθ ≤ ξ²). Variance may hit zero.");
+        if !params.feller_satisfied() {
+            match policy {
+                FellerPolicy::Error => {
+                    return Err(SdeError::FellerConditionViolation {
+                        kappa: params.kappa,
+                        theta: params.theta,
+                        xi: params.xi,
+                        feller_value: 2.0 * params.kappa * params.theta,
+                    })
+                }
+                FellerPolicy::Warn => {
+                    warn_event!(
+                        kappa = params.kappa,
+                        theta = params.theta,
+                        xi = params.xi,
+                        "Feller condition violated (2κθ ≤ ξ²); variance may hit zero"
+                    );
+                }
+                FellerPolicy::Ignore => {}
             }
-            // For strict validation, uncomment the next line:
-            // return Err(SdeError::FellerConditionViolation { kappa: params.kappa, theta: params.theta, xi: params.xi, feller_value: feller });
         }
 
         Ok(Heston { params, scheme })
     }
 
+    /// Validate `params`, suppressing the Feller warning if
+    /// `suppress_warnings` is set
+    #[deprecated(
+        since = "0.1.1",
+        note = "use `new_with_policy` with `FellerPolicy::Warn` or `FellerPolicy::Ignore`"
+    )]
+    pub fn new_with_scheme_quiet(
+        params: HestonParams,
+        scheme: HestonScheme,
+        suppress_warnings: bool,
+    ) -> SdeResult<Self> {
+        let policy = if suppress_warnings {
+            FellerPolicy::Ignore
+        } else {
+            FellerPolicy::Warn
+        };
+        Self::new_with_policy(params, scheme, policy)
+    }
+
     /// Validate Heston parameters
-    fn validate_params(params: &HestonParams) -> SdeResult<()> {
+    pub(crate) fn validate_params(params: &HestonParams) -> SdeResult<()> {
         validate_positive("s0", params.s0)?;
         validate_non_negative("v0", params.v0)?;
         validate_finite("r", params.r)?;
@@ -132,16 +216,78 @@ impl Heston {
     }
 
     /// Two-factor step: updates both stock price and variance
+    ///
+    /// Returns the numerical repairs made during the step.
     pub fn step<R: Rng + ?Sized>(
         &self,
         s: &mut f64,
         v: &mut f64,
         dt: f64,
         rng: &mut R,
-    ) -> SdeResult<()> {
+    ) -> SdeResult<Warnings> {
+        let z1 = rng::get_normal_draw(rng);
+        let z2 = rng::get_normal_draw(rng);
+        self.step_impl(s, v, dt, z1, z2, || rng.gen())
+    }
+
+    /// Two-factor step driven by caller-supplied random draws
+    ///
+    /// `z1`, `z2` are independent standard normals and `u` is a Uniform(0,1)
+    /// draw (used by the QE exponential branch and the Alfonsi variance
+    /// step). Supplying the draws
+    /// explicitly guarantees that bumped models consume exactly the same
+    /// random numbers, which is what common-random-number Greeks require:
+    /// with [`Heston::step`] the QE branch decides whether a uniform is drawn,
+    /// so two parameter sets could fall out of sync.
+    pub fn step_with_draws(
+        &self,
+        s: &mut f64,
+        v: &mut f64,
+        dt: f64,
+        z1: f64,
+        z2: f64,
+        u: f64,
+    ) -> SdeResult<Warnings> {
+        self.step_impl(s, v, dt, z1, z2, || u)
+    }
+
+    /// Two-factor step of an antithetic pair of states
+    ///
+    /// State 0 is driven by `(z1, z2, u)` and state 1 by `(-z1, -z2, 1 - u)`,
+    /// so both correlated increments `ΔW_s`, `ΔW_v` are negated together.
+    /// The QE uniform is drawn only when a state reaches the exponential
+    /// branch: if state 0 drew it, state 1 reflects it, otherwise state 1
+    /// draws its own. Each state is an exact sample of the plain scheme.
+    ///
+    /// Returns the numerical repairs made on both states.
+    pub fn step_antithetic<R: Rng + ?Sized>(
+        &self,
+        s: &mut [f64; 2],
+        v: &mut [f64; 2],
+        dt: f64,
+        rng: &mut R,
+    ) -> SdeResult<Warnings> {
         let z1 = rng::get_normal_draw(rng);
         let z2 = rng::get_normal_draw(rng);
+        let mut u: Option<f64> = None;
+        let mut warnings =
+            self.step_impl(&mut s[0], &mut v[0], dt, z1, z2, || *u.insert(rng.gen()))?;
+        warnings += self.step_impl(&mut s[1], &mut v[1], dt, -z1, -z2, || match u {
+            Some(u) => 1.0 - u,
+            None => rng.gen(),
+        })?;
+        Ok(warnings)
+    }
 
+    fn step_impl(
+        &self,
+        s: &mut f64,
+        v: &mut f64,
+        dt: f64,
+        z1: f64,
+        z2: f64,
+        uniform: impl FnOnce() -> f64,
+    ) -> SdeResult<Warnings> {
         // Generate correlated Brownian increments
         let dw_s = z1;
         let dw_v = self.params.rho * z1 + (1.0 - self.params.rho * self.params.rho).sqrt() * z2;
@@ -169,17 +315,16 @@ impl Heston {
             });
         }
 
-        match self.scheme {
+        let warnings = match self.scheme {
             HestonScheme::FullTruncationEuler => {
-                self.step_full_truncation_euler(s, v, dt, dw_s, dw_v)?;
+                self.step_full_truncation_euler(s, v, dt, dw_s, dw_v)?
             }
-            HestonScheme::AndersenQE => {
-                self.step_andersen_qe(s, v, dt, dw_s, dw_v, rng)?;
+            // QE correlates through the variance increment: independent drivers
+            HestonScheme::AndersenQE | HestonScheme::AndersenQEM => {
+                self.step_andersen_qe(s, v, dt, z2, z1, uniform)?
             }
-            HestonScheme::Alfonsi => {
-                self.step_alfonsi(s, v, dt, dw_s, dw_v)?;
-            }
-        }
+            HestonScheme::Alfonsi => self.step_alfonsi(s, v, dt, z1, uniform())?,
+        };
 
         // Validate outputs
         if !s.is_finite() || *s <= 0.0 {
@@ -196,7 +341,7 @@ impl Heston {
             });
         }
 
-        Ok(())
+        Ok(warnings)
     }
 
     /// Full Truncation Euler (FTE) scheme
@@ -205,8 +350,8 @@ impl Heston {
     ///
     /// Simple Euler-Maruyama discretization with variance truncation:
     /// ```text
-    /// V_{n+1} = max(0, V_n + κ(θ - V_n)Δt + ξ√V_n ΔW_v)
-    /// S_{n+1} = S_n * exp(r*Δt + √V_n ΔW_s)
+    /// V_{n+1} = max(0, V_n + κ(θ - V_n)Δt + ξ√V_n⁺ ΔW_v)
+    /// S_{n+1} = S_n * exp((r - ½V_n⁺)Δt + √V_n⁺ ΔW_s)
     /// ```
     ///
     /// # Characteristics
@@ -221,20 +366,24 @@ impl Heston {
         dt: f64,
         dw_s: f64,
         dw_v: f64,
-    ) -> SdeResult<()> {
+    ) -> SdeResult<Warnings> {
         let sqrt_dt = dt.sqrt();
         let sqrt_v = if *v > 0.0 { v.sqrt() } else { 0.0 };
 
         // Update variance first
         let dv = self.params.kappa * (self.params.theta - *v) * dt
             + self.params.xi * sqrt_v * sqrt_dt * dw_v;
+        let warnings = Warnings {
+            variance_truncations: u64::from(*v + dv < 0.0),
+            ..Warnings::default()
+        };
         *v = (*v + dv).max(0.0); // Full truncation
 
         // Update stock price using current variance
-        let ds_over_s = self.params.r * dt + sqrt_v * sqrt_dt * dw_s;
+        let ds_over_s = (self.params.r - 0.5 * sqrt_v * sqrt_v) * dt + sqrt_v * sqrt_dt * dw_s;
         *s *= ds_over_s.exp();
 
-        Ok(())
+        Ok(warnings)
     }
 
     /// Andersen's Quadratic Exponential (QE) scheme
@@ -252,135 +401,157 @@ impl Heston {
     /// ```
     ///
     /// ## Conditional Distribution
-    /// - If ψ ≤ ψ_c: Use quadratic approximation (chi-squared-like)
-    /// - If ψ > ψ_c: Use exponential approximation (prevents explosion)
+    /// - If ψ ≤ ψ_c: Use quadratic approximation `V_{n+1} = a(b + Z_v)²`
+    /// - If ψ > ψ_c: Use exponential approximation (prevents explosion):
+    ///   `V_{n+1} = 0` with probability `p`, else exponential with rate `β`
     ///
     /// ## Stock Price Update
-    /// Includes martingale correction terms to ensure risk-neutral drift.
+    /// Central discretization of `∫V dt` with the correlation carried by the
+    /// variance increment (γ₁ = γ₂ = ½):
+    /// ```text
+    /// ln S_{n+1} = ln S_n + rΔt + K₀ + K₁V_n + K₂V_{n+1} + √(K₃V_n + K₄V_{n+1}) Z_s
+    /// K₀ = -ρκθΔt/ξ,  K₁ = γ₁Δt(κρ/ξ - ½) - ρ/ξ,  K₂ = γ₂Δt(κρ/ξ - ½) + ρ/ξ
+    /// K₃ = γ₁Δt(1 - ρ²),  K₄ = γ₂Δt(1 - ρ²)
+    /// ```
+    /// Under [`HestonScheme::AndersenQEM`], `K₀` is replaced by the value
+    /// that makes `E[S_{n+1} | S_n, V_n] = S_n e^{rΔt}` exactly under the
+    /// discrete variance law, with `A = K₂ + ½K₄`:
+    /// ```text
+    /// quadratic:    K₀* = -A b² a/(1 - 2Aa) + ½ ln(1 - 2Aa) - (K₁ + ½K₃)V_n
+    /// exponential:  K₀* = -ln(p + β(1 - p)/(β - A)) - (K₁ + ½K₃)V_n
+    /// ```
+    /// (kept at `K₀` where the moment `E[e^{A V_{n+1}}]` does not exist).
     ///
     /// # Characteristics
     /// - **Robustness**: Handles Feller violations without instability
     /// - **Accuracy**: Preserves key distributional properties
     /// - **Industry standard**: Widely used in practice
     /// - **Speed**: Moderate (requires moment calculations)
-    fn step_andersen_qe<R: Rng + ?Sized>(
+    fn step_andersen_qe(
         &self,
         s: &mut f64,
         v: &mut f64,
         dt: f64,
-        dw_s: f64,
-        dw_v: f64,
-        rng: &mut R,
-    ) -> SdeResult<()> {
-        let _sqrt_dt = dt.sqrt();
+        z_v: f64,
+        z_s: f64,
+        uniform: impl FnOnce() -> f64,
+    ) -> SdeResult<Warnings> {
+        let martingale_correction = matches!(self.scheme, HestonScheme::AndersenQEM);
+        let HestonParams {
+            kappa, theta, xi, ..
+        } = self.params;
+        let decay = (-kappa * dt).exp();
 
         // QE scheme for variance
-        let m = self.params.theta + (*v - self.params.theta) * (-self.params.kappa * dt).exp();
-        let s2 = *v * self.params.xi * self.params.xi * (-self.params.kappa * dt).exp()
-            / self.params.kappa
-            * (1.0 - (-self.params.kappa * dt).exp())
-            + self.params.theta * self.params.xi * self.params.xi / (2.0 * self.params.kappa)
-                * (1.0 - (-self.params.kappa * dt).exp()).powi(2);
+        let m = theta + (*v - theta) * decay;
+        let s2 = *v * xi * xi * decay / kappa * (1.0 - decay)
+            + theta * xi * xi / (2.0 * kappa) * (1.0 - decay).powi(2);
 
         let psi = s2 / (m * m);
         let psi_c = 1.5; // Critical value
 
+        let [mut k0, k1, k2, k3, k4] = self.central_coefficients(dt);
+        let a_mgf = k2 + 0.5 * k4;
+
         let v_next = if psi <= psi_c {
             // Use quadratic approximation
             let b2 = 2.0 / psi - 1.0 + (2.0 / psi * (2.0 / psi - 1.0)).sqrt();
             let a = m / (1.0 + b2);
-            a * (dw_v.abs().sqrt() + b2.sqrt()).powi(2)
+            if martingale_correction && 2.0 * a_mgf * a < 1.0 {
+                k0 = -a_mgf * b2 * a / (1.0 - 2.0 * a_mgf * a) + 0.5 * (1.0 - 2.0 * a_mgf * a).ln()
+                    - (k1 + 0.5 * k3) * *v;
+            }
+            a * (b2.sqrt() + z_v).powi(2)
         } else {
             // Use exponential approximation
             let p = (psi - 1.0) / (psi + 1.0);
             let beta = (1.0 - p) / m;
+            if martingale_correction && a_mgf < beta {
+                k0 = -(p + beta * (1.0 - p) / (beta - a_mgf)).ln() - (k1 + 0.5 * k3) * *v;
+            }
 
-            let u: f64 = rng.gen(); // Uniform random variable
+            let u = uniform(); // Uniform random variable
             if u <= p {
                 0.0
             } else {
-                (1.0 - p) / beta * (u - p) / (1.0 - p)
+                ((1.0 - p) / (1.0 - u)).ln() / beta
             }
-        }
-        .max(0.0); // Ensure non-negative
-
-        // Update stock price with martingale correction
-        let k0 = -self.params.rho * self.params.kappa * self.params.theta / self.params.xi * dt;
-        let k1 = 0.5 * dt * (self.params.kappa * self.params.rho / self.params.xi - 0.5)
-            - self.params.rho / self.params.xi;
-        let k2 = 0.5 * dt * (self.params.kappa * self.params.rho / self.params.xi - 0.5)
-            + self.params.rho / self.params.xi;
-        let k3 = 0.5 * dt * (1.0 - self.params.rho * self.params.rho);
+        };
 
         let ds_over_s =
-            self.params.r * dt + k0 + k1 * *v + k2 * v_next + v.max(0.0).sqrt() * k3.sqrt() * dw_s;
+            self.params.r * dt + k0 + k1 * *v + k2 * v_next + (k3 * *v + k4 * v_next).sqrt() * z_s;
 
-        *s = (*s * ds_over_s.exp()).max(1e-10); // Ensure positive stock price
+        let s_next = *s * ds_over_s.exp();
+        let s_floored = s_next.max(1e-10); // Ensure positive stock price
+        let warnings = Warnings {
+            price_floors: u64::from(s_floored != s_next),
+            ..Warnings::default()
+        };
+        *s = s_floored;
         *v = v_next;
 
-        Ok(())
+        Ok(warnings)
     }
 
-    /// Alfonsi scheme for high-order weak convergence
+    /// Alfonsi's second-order scheme
     ///
     /// # Mathematical Description
     ///
-    /// Enhanced Euler scheme with higher-order correction terms:
+    /// The variance takes one step of [`AlfonsiCir`] (Alfonsi 2010): a
+    /// Strang splitting of the CIR generator driven by a discrete
+    /// moment-matching variable, switching to a two-point law matching the
+    /// exact first two moments below a threshold near zero when `ξ² > 4κθ`.
+    /// The log-price uses the same central discretization of `∫V dt` as QE:
     /// ```text
-    /// V_aux = V_n + κ(θ - V_n)Δt + ξ√V_n ΔW_v
-    /// V_{n+1} = max(0, V_aux + correction_term)
-    /// ```
-    ///
-    /// Where the correction term involves:
-    /// ```text
-    /// correction = γ*ξ²*Δt*(1/√V_aux - 1/√V_n)*(ΔW_v² - Δt)/(2√Δt)
+    /// V_{n+1}    = AlfonsiCir(V_n, Δt, U)
+    /// ln S_{n+1} = ln S_n + rΔt + K₀ + K₁V_n + K₂V_{n+1} + √(K₃V_n + K₄V_{n+1}) Z_s
     /// ```
     ///
     /// # Characteristics
-    /// - **Accuracy**: Higher-order weak convergence than Euler
-    /// - **Stability**: Better than FTE, not as robust as QE
-    /// - **Performance**: Slightly slower than FTE due to correction terms
-    /// - **Use case**: When accuracy is critical and Feller condition holds
+    /// - **Accuracy**: Weak order 2 for smooth payoffs
+    /// - **Stability**: Variance non-negative by construction, no truncation
+    /// - **Performance**: One uniform and one normal per step
+    /// - **Use case**: Smooth payoffs where few, large steps are wanted
     fn step_alfonsi(
         &self,
         s: &mut f64,
         v: &mut f64,
         dt: f64,
-        dw_s: f64,
-        dw_v: f64,
-    ) -> SdeResult<()> {
-        let sqrt_dt = dt.sqrt();
-
-        // Alfonsi's improved scheme for variance
-        let gamma = 0.5; // Alfonsi parameter
-        let k = self.params.kappa;
-        let theta = self.params.theta;
-        let xi = self.params.xi;
-
-        // First, compute auxiliary process
-        let v_aux = *v + k * (theta - *v) * dt + xi * v.sqrt() * sqrt_dt * dw_v;
-        let v_aux_pos = v_aux.max(0.0);
-
-        // Alfonsi correction
-        let correction = if *v > 0.0 && v_aux > 0.0 {
-            gamma * xi * xi * dt * (1.0 / v_aux_pos.sqrt() - 1.0 / v.sqrt()) * (dw_v * dw_v - dt)
-                / (2.0 * sqrt_dt)
-        } else {
-            0.0
-        };
-
-        *v = (v_aux + correction).max(0.0);
-
-        // Update stock price
-        let sqrt_v_avg = if *v > 0.0 {
-            (v.sqrt() + (*v).sqrt()) / 2.0
-        } else {
-            0.0
-        };
-        let ds_over_s = self.params.r * dt + sqrt_v_avg * sqrt_dt * dw_s;
-        *s *= ds_over_s.exp();
+        z_s: f64,
+        u: f64,
+    ) -> SdeResult<Warnings> {
+        let HestonParams {
+            kappa, theta, xi, ..
+        } = self.params;
+        let v_next = AlfonsiCir::new(kappa, theta, xi).step(*v, dt, u);
+        let [k0, k1, k2, k3, k4] = self.central_coefficients(dt);
+        *s *= (self.params.r * dt
+            + k0
+            + k1 * *v
+            + k2 * v_next
+            + (k3 * *v + k4 * v_next).sqrt() * z_s)
+            .exp();
+        *v = v_next;
+        Ok(Warnings::default())
+    }
 
-        Ok(())
+    /// `[K₀, K₁, K₂, K₃, K₄]` of the central log-price step (γ₁ = γ₂ = ½)
+    fn central_coefficients(&self, dt: f64) -> [f64; 5] {
+        let HestonParams {
+            kappa,
+            theta,
+            xi,
+            rho,
+            ..
+        } = self.params;
+        let k3 = 0.5 * dt * (1.0 - rho * rho);
+        [
+            -rho * kappa * theta / xi * dt,
+            0.5 * dt * (kappa * rho / xi - 0.5) - rho / xi,
+            0.5 * dt * (kappa * rho / xi - 0.5) + rho / xi,
+            k3,
+            k3,
+        ]
     }
 
     /// Get current scheme name for reporting
@@ -388,6 +559,7 @@ impl Heston {
         match self.scheme {
             HestonScheme::FullTruncationEuler => "Full Truncation Euler",
             HestonScheme::AndersenQE => "Andersen QE",
+            HestonScheme::AndersenQEM => "Andersen QE-M",
             HestonScheme::Alfonsi => "Alfonsi",
         }
     }
@@ -479,6 +651,24 @@ mod tests {
 
         // Should create without panic but with warning
         let _heston = Heston::new(params).expect("Should create despite Feller violation");
+        assert!(!params.feller_satisfied());
+
+        let quiet = Heston::new_with_policy(params, HestonScheme::AndersenQE, FellerPolicy::Ignore)
+            .expect("Ignore accepts the violation");
+        assert!(!quiet.params.feller_satisfied());
+        match Heston::new_with_policy(params, HestonScheme::AndersenQE, FellerPolicy::Error) {
+            Err(SdeError::FellerConditionViolation { feller_value, .. }) => {
+                assert!((feller_value - 0.08).abs() < 1e-12)
+            }
+            other => panic!("expected a Feller violation, got {:?}", other.map(|_| ())),
+        }
+
+        // Parameters satisfying the condition pass even the strict policy
+        let good = HestonParams { xi: 0.2, ..params };
+        assert!(good.feller_satisfied());
+        assert!(
+            Heston::new_with_policy(good, HestonScheme::AndersenQE, FellerPolicy::Error).is_ok()
+        );
     }
 
     #[test]
@@ -522,4 +712,126 @@ mod tests {
 
         assert!(Heston::new(bad_params3).is_err());
     }
+
+    #[test]
+    fn test_step_warnings_count_truncations() {
+        // Strong Feller violation: 2κθ = 0.04 < ξ² = 1
+        let params = HestonParams {
+            s0: 100.0,
+            v0: 0.01,
+            r: 0.0,
+            kappa: 1.0,
+            theta: 0.02,
+            xi: 1.0,
+            rho: -0.7,
+        };
+        let mut totals = Vec::new();
+        for scheme in [HestonScheme::FullTruncationEuler, HestonScheme::Alfonsi] {
+            let heston = Heston::new_with_policy(params, scheme, FellerPolicy::Ignore).unwrap();
+            let mut rng = StdRng::seed_from_u64(7);
+            let mut warnings = Warnings::default();
+            let (mut s, mut v) = (params.s0, params.v0);
+            for _ in 0..1_000 {
+                warnings += heston.step(&mut s, &mut v, 0.01, &mut rng).unwrap();
+            }
+            assert_eq!(warnings.price_floors, 0);
+            totals.push(warnings.variance_truncations);
+        }
+        // Euler truncates often; Alfonsi stays non-negative by construction
+        assert!(
+            totals[0] > 10,
+            "Truncations {:?} should be frequent",
+            totals
+        );
+        assert_eq!(totals[1], 0);
+
+        // A positive state far from zero needs no repair
+        let heston = Heston::new_with_policy(
+            HestonParams { xi: 0.1, ..params },
+            HestonScheme::FullTruncationEuler,
+            FellerPolicy::Ignore,
+        )
+        .unwrap();
+        let (mut s, mut v) = (100.0, 0.04);
+        let warnings = heston
+            .step_with_draws(&mut s, &mut v, 0.01, 0.5, 0.5, 0.5)
+            .unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_antithetic_pairs_negate_both_drivers() {
+        let params = HestonParams {
+            s0: 100.0,
+            v0: 0.04,
+            r: 0.03,
+            kappa: 1.5,
+            theta: 0.04,
+            xi: 0.6,
+            rho: -0.7,
+        };
+        let (steps, dt, pairs) = (20, 0.05, 20_000);
+        for scheme in [HestonScheme::FullTruncationEuler, HestonScheme::AndersenQE] {
+            let heston = Heston::new_with_policy(params, scheme, FellerPolicy::Ignore).unwrap();
+
+            // Each leg replays the plain step on (z1, z2) and (-z1, -z2)
+            let mut rng = StdRng::seed_from_u64(3);
+            let (mut s, mut v) = ([params.s0; 2], [params.v0; 2]);
+            heston
+                .step_antithetic(&mut s, &mut v, dt, &mut rng)
+                .unwrap();
+            let mut rng = StdRng::seed_from_u64(3);
+            let z1 = rng::get_normal_draw(&mut rng);
+            let z2 = rng::get_normal_draw(&mut rng);
+            if let HestonScheme::FullTruncationEuler = scheme {
+                for (k, sign) in [1.0, -1.0].into_iter().enumerate() {
+                    let (mut s1, mut v1) = (params.s0, params.v0);
+                    heston
+                        .step_with_draws(&mut s1, &mut v1, dt, sign * z1, sign * z2, 0.5)
+                        .unwrap();
+                    assert_eq!((s[k], v[k]), (s1, v1));
+                }
+            }
+
+            // Pair averages of S_T have the plain scheme's mean with a much
+            // smaller variance
+            let mut plain = Vec::with_capacity(pairs);
+            let mut paired = Vec::with_capacity(pairs);
+            for i in 0..pairs {
+                let mut rng = StdRng::seed_from_u64(1_000 + i as u64);
+                let (mut s, mut v) = ([params.s0; 2], [params.v0; 2]);
+                let (mut s1, mut v1) = (params.s0, params.v0);
+                for _ in 0..steps {
+                    heston
+                        .step_antithetic(&mut s, &mut v, dt, &mut rng)
+                        .unwrap();
+                    heston.step(&mut s1, &mut v1, dt, &mut rng).unwrap();
+                }
+                paired.push(0.5 * (s[0] + s[1]));
+                plain.push(s1);
+            }
+            let moments = |x: &[f64]| {
+                let mean = x.iter().sum::<f64>() / x.len() as f64;
+                let var = x.iter().map(|y| (y - mean).powi(2)).sum::<f64>() / x.len() as f64;
+                (mean, var)
+            };
+            let (mean, var) = moments(&paired);
+            let (plain_mean, plain_var) = moments(&plain);
+            assert!(
+                (mean - plain_mean).abs() < 4.0 * ((var + plain_var) / pairs as f64).sqrt(),
+                "{}: antithetic mean {} vs plain {}",
+                heston.scheme_name(),
+                mean,
+                plain_mean
+            );
+            // A pair costs two plain paths, whose average has half the variance
+            assert!(
+                var < 0.5 * plain_var,
+                "{}: pair variance {} vs plain {}",
+                heston.scheme_name(),
+                var,
+                plain_var
+            );
+        }
+    }
 }
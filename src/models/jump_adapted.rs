@@ -0,0 +1,485 @@
+// src/models/jump_adapted.rs
+//! Jump-Adapted Simulation of Jump-Diffusions
+//!
+//! # Jump-Adapted Grid
+//!
+//! Bucketing the jumps of each `Δt` (as [`Merton::step`] does) places them
+//! at the ends of the steps, so a barrier or extremum sees the path
+//! neither just before nor just after a jump. Here the jump times are drawn
+//! exactly, with exponential inter-arrival times of mean `1/λ`, and merged
+//! into the requested grid. Between two consecutive event times the
+//! log-price is a Brownian motion with drift, simulated exactly:
+//! ```text
+//! ln S(τ_{k+1}-) = ln S(τ_k) + (μ - ½σ²)(τ_{k+1} - τ_k) + σ √(τ_{k+1} - τ_k) Z
+//! S(τ_{k+1})     = S(τ_{k+1}-) e^{Y}                  at a jump time
+//! ```
+//!
+//! # Continuous Monitoring
+//!
+//! Conditional on the event values, each interval is a Brownian bridge in
+//! log space, whose extremes are known in law. With `x`, `y` the log
+//! prices at its ends and `h = σ²Δτ`:
+//! ```text
+//! P(no crossing of ln B) = 1 - exp(-2 (x - ln B)(y - ln B) / h)   (same side)
+//! max = (x + y + √((y - x)² - 2h ln U)) / 2,   U ~ U(0, 1)
+//! ```
+//! [`JumpAdaptedPath::survival_probability`] and
+//! [`JumpAdaptedPath::sample_extremes`] apply these on every interval and
+//! check both sides of every jump, so continuously monitored barrier and
+//! lookback payoffs carry no discretization bias.
+//!
+//! # Poisson Bridge
+//!
+//! Given `N` jumps on `(0, T]`, the counts of the grid intervals are
+//! multinomial with probabilities `Δt_i / T` and the jump times within an
+//! interval are uniform order statistics. [`simulate_given_jumps`] and
+//! [`simulate_grid_given_jumps`] sample a path conditionally on its total
+//! count, e.g. to stratify on `N ~ Poisson(λT)`:
+//! ```text
+//! E[f] = Σ_n P(N = n) E[f | N = n]
+//! ```
+//! On the grid alone no jump time is needed: over an interval with `n_i`
+//! jumps the log-return is the diffusion increment plus `n_i` log-jumps,
+//! `N(n_i μ_J, n_i σ_J²)` under Merton, so discretely monitored barrier and
+//! Asian payoffs are sampled exactly on grids as coarse as their dates.
+//!
+//! [`Merton::step`]: super::merton::Merton::step
+
+use super::kou::Kou;
+use super::merton::Merton;
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::rng;
+use rand::Rng;
+use rand_distr::{Binomial, Distribution};
+
+/// A jump-diffusion `dS/S = μ dt + σ dW + (e^Y - 1) dN` with i.i.d.
+/// log-jumps `Y`
+pub trait JumpDiffusion {
+    fn s0(&self) -> f64;
+    fn mu(&self) -> f64;
+    fn sigma(&self) -> f64;
+    /// Intensity `λ` of the jump times
+    fn intensity(&self) -> f64;
+    /// One log-jump `Y`
+    fn log_jump<R: Rng + ?Sized>(&self, rng: &mut R) -> f64;
+
+    /// Sum of `count` independent log-jumps
+    fn compound_log_jump<R: Rng + ?Sized>(&self, count: u64, rng: &mut R) -> f64 {
+        (0..count).map(|_| self.log_jump(rng)).sum()
+    }
+}
+
+impl JumpDiffusion for Merton {
+    fn s0(&self) -> f64 {
+        self.params.s0
+    }
+
+    fn mu(&self) -> f64 {
+        self.params.mu
+    }
+
+    fn sigma(&self) -> f64 {
+        self.params.sigma
+    }
+
+    fn intensity(&self) -> f64 {
+        self.params.lambda
+    }
+
+    fn log_jump<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.params.mu_j + self.params.sigma_j * rng::get_normal_draw(rng)
+    }
+
+    // A sum of n Gaussian log-jumps is N(n μ_J, n σ_J²)
+    fn compound_log_jump<R: Rng + ?Sized>(&self, count: u64, rng: &mut R) -> f64 {
+        let n = count as f64;
+        n * self.params.mu_j + (n.sqrt() * self.params.sigma_j) * rng::get_normal_draw(rng)
+    }
+}
+
+impl JumpDiffusion for Kou {
+    fn s0(&self) -> f64 {
+        self.params.s0
+    }
+
+    fn mu(&self) -> f64 {
+        self.params.mu
+    }
+
+    fn sigma(&self) -> f64 {
+        self.params.sigma
+    }
+
+    fn intensity(&self) -> f64 {
+        self.params.lambda
+    }
+
+    fn log_jump<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.sample_log_jump(rng)
+    }
+}
+
+/// One path on the union of a grid and its jump times
+#[derive(Debug, Clone)]
+pub struct JumpAdaptedPath {
+    /// Event times, increasing from 0: grid times and jump times
+    pub times: Vec<f64>,
+    /// `S(τ_k-)`, the price just before each event
+    pub left_limits: Vec<f64>,
+    /// `S(τ_k)`, after the jump at a jump time
+    pub values: Vec<f64>,
+    /// Position of each grid time in `times`
+    pub grid_indices: Vec<usize>,
+    sigma: f64,
+}
+
+impl JumpAdaptedPath {
+    /// `[S_0, S(t_1), ..., S(t_n)]` on the grid, as the engine's payoffs
+    /// expect
+    pub fn grid_prices(&self) -> Vec<f64> {
+        std::iter::once(self.values[0])
+            .chain(self.grid_indices.iter().map(|&i| self.values[i]))
+            .collect()
+    }
+
+    /// Number of jumps on the path
+    pub fn jumps(&self) -> usize {
+        self.times.len() - 1 - self.grid_indices.len()
+    }
+
+    /// Probability, given the event values, that the continuously
+    /// monitored path never touches `barrier`, from below when `up`
+    pub fn survival_probability(&self, barrier: f64, up: bool) -> f64 {
+        let log_barrier = barrier.ln();
+        // Log distance to the barrier, positive on the surviving side
+        let distance = |s: f64| {
+            let d = s.ln() - log_barrier;
+            if up {
+                -d
+            } else {
+                d
+            }
+        };
+        if distance(self.values[0]) <= 0.0 {
+            return 0.0;
+        }
+        let mut survival = 1.0;
+        for k in 1..self.times.len() {
+            let (a, b) = (distance(self.values[k - 1]), distance(self.left_limits[k]));
+            if b <= 0.0 || distance(self.values[k]) <= 0.0 {
+                return 0.0;
+            }
+            let h = self.sigma * self.sigma * (self.times[k] - self.times[k - 1]);
+            survival *= 1.0 - (-2.0 * a * b / h).exp();
+        }
+        survival
+    }
+
+    /// `(min, max)` of the continuous path, sampled from the bridge law of
+    /// every interval
+    pub fn sample_extremes<R: Rng + ?Sized>(&self, rng: &mut R) -> (f64, f64) {
+        let mut lo = self.values[0].ln();
+        let mut hi = lo;
+        for k in 1..self.times.len() {
+            let (x, y) = (self.values[k - 1].ln(), self.left_limits[k].ln());
+            let h = self.sigma * self.sigma * (self.times[k] - self.times[k - 1]);
+            let mut spread = || {
+                let u = 1.0 - rng.gen::<f64>();
+                ((y - x).powi(2) - 2.0 * h * u.ln()).sqrt()
+            };
+            hi = hi.max(0.5 * (x + y + spread()));
+            lo = lo.min(0.5 * (x + y - spread()));
+            let after = self.values[k].ln();
+            hi = hi.max(after);
+            lo = lo.min(after);
+        }
+        (lo.exp(), hi.exp())
+    }
+}
+
+/// Simulate `model` on `grid` (increasing positive times) with its jump
+/// times inserted
+///
+/// # Errors
+///
+/// Returns `SdeError::InvalidConfiguration` for an empty, non-increasing
+/// or non-positive grid, and the parameter errors of a non-positive `s0`
+/// or `sigma` or a negative intensity.
+pub fn simulate_jump_adapted<M: JumpDiffusion, R: Rng + ?Sized>(
+    model: &M,
+    grid: &[f64],
+    rng: &mut R,
+) -> SdeResult<JumpAdaptedPath> {
+    validate_inputs(model, grid)?;
+    let (horizon, lambda) = (grid[grid.len() - 1], model.intensity());
+    let mut jump_times = Vec::new();
+    if lambda > 0.0 {
+        let mut t = -(1.0 - rng.gen::<f64>()).ln() / lambda;
+        while t < horizon {
+            jump_times.push(t);
+            t -= (1.0 - rng.gen::<f64>()).ln() / lambda;
+        }
+    }
+    Ok(build_path(model, grid, &jump_times, rng))
+}
+
+/// Jumps per grid interval given `total` jumps up to the last grid time:
+/// successive binomial splits, so the counts are multinomial with
+/// probabilities proportional to the interval lengths
+///
+/// `grid` holds increasing positive times, as for
+/// [`simulate_jump_adapted`].
+pub fn poisson_bridge_counts<R: Rng + ?Sized>(total: u64, grid: &[f64], rng: &mut R) -> Vec<u64> {
+    let horizon = grid[grid.len() - 1];
+    let mut remaining = total;
+    let mut prev = 0.0;
+    grid.iter()
+        .map(|&t| {
+            let share = ((t - prev) / (horizon - prev)).min(1.0);
+            let count = Binomial::new(remaining, share).unwrap().sample(rng);
+            remaining -= count;
+            prev = t;
+            count
+        })
+        .collect()
+}
+
+/// Jump-adapted path conditioned on `total` jumps up to the last grid
+/// time: counts per interval from [`poisson_bridge_counts`], placed
+/// uniformly within their intervals
+///
+/// # Errors
+///
+/// As [`simulate_jump_adapted`].
+pub fn simulate_given_jumps<M: JumpDiffusion, R: Rng + ?Sized>(
+    model: &M,
+    grid: &[f64],
+    total: u64,
+    rng: &mut R,
+) -> SdeResult<JumpAdaptedPath> {
+    validate_inputs(model, grid)?;
+    let counts = poisson_bridge_counts(total, grid, rng);
+    let mut jump_times = Vec::with_capacity(total as usize);
+    let mut prev = 0.0;
+    for (&t, &count) in grid.iter().zip(&counts) {
+        let first = jump_times.len();
+        jump_times.extend((0..count).map(|_| prev + (t - prev) * rng.gen::<f64>()));
+        jump_times[first..].sort_by(f64::total_cmp);
+        prev = t;
+    }
+    Ok(build_path(model, grid, &jump_times, rng))
+}
+
+/// `[S_0, S(t_1), ..., S(t_n)]` conditioned on `total` jumps up to the
+/// last grid time, without the jump times: each interval adds its
+/// Gaussian diffusion and the compound of its bridge count of log-jumps
+///
+/// Exact on any grid, so discretely monitored payoffs need no steps
+/// between their dates; `O(n)` for Merton whatever the number of jumps.
+///
+/// # Errors
+///
+/// As [`simulate_jump_adapted`].
+pub fn simulate_grid_given_jumps<M: JumpDiffusion, R: Rng + ?Sized>(
+    model: &M,
+    grid: &[f64],
+    total: u64,
+    rng: &mut R,
+) -> SdeResult<Vec<f64>> {
+    validate_inputs(model, grid)?;
+    let counts = poisson_bridge_counts(total, grid, rng);
+    let sigma = model.sigma();
+    let drift = model.mu() - 0.5 * sigma * sigma;
+    let mut prices = Vec::with_capacity(grid.len() + 1);
+    let mut s = model.s0();
+    prices.push(s);
+    let mut prev = 0.0;
+    for (&t, &count) in grid.iter().zip(&counts) {
+        let dt = t - prev;
+        s *= (drift * dt
+            + sigma * dt.sqrt() * rng::get_normal_draw(rng)
+            + model.compound_log_jump(count, rng))
+        .exp();
+        prices.push(s);
+        prev = t;
+    }
+    Ok(prices)
+}
+
+fn validate_inputs<M: JumpDiffusion>(model: &M, grid: &[f64]) -> SdeResult<()> {
+    validate_positive("s0", model.s0())?;
+    validate_positive("sigma", model.sigma())?;
+    validate_non_negative("lambda", model.intensity())?;
+    if grid.is_empty() || grid[0] <= 0.0 || grid.windows(2).any(|w| w[1] <= w[0]) {
+        return Err(SdeError::InvalidConfiguration {
+            field: "grid".to_string(),
+            reason: "needs increasing positive times".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Exact path through the sorted `jump_times` (all before the last grid
+/// time) and the grid
+fn build_path<M: JumpDiffusion, R: Rng + ?Sized>(
+    model: &M,
+    grid: &[f64],
+    jump_times: &[f64],
+    rng: &mut R,
+) -> JumpAdaptedPath {
+    let sigma = model.sigma();
+    let drift = model.mu() - 0.5 * sigma * sigma;
+    let mut path = JumpAdaptedPath {
+        times: vec![0.0],
+        left_limits: vec![model.s0()],
+        values: vec![model.s0()],
+        grid_indices: Vec::with_capacity(grid.len()),
+        sigma,
+    };
+    let (mut t, mut s) = (0.0, model.s0());
+    let mut jumps = jump_times.iter().peekable();
+    for &grid_time in grid {
+        while let Some(&jump_time) = jumps.next_if(|&&jump_time| jump_time < grid_time) {
+            let dt = jump_time - t;
+            s *= (drift * dt + sigma * dt.sqrt() * rng::get_normal_draw(rng)).exp();
+            path.times.push(jump_time);
+            path.left_limits.push(s);
+            s *= model.log_jump(rng).exp();
+            path.values.push(s);
+            t = jump_time;
+        }
+        let dt = grid_time - t;
+        s *= (drift * dt + sigma * dt.sqrt() * rng::get_normal_draw(rng)).exp();
+        path.grid_indices.push(path.times.len());
+        path.times.push(grid_time);
+        path.left_limits.push(s);
+        path.values.push(s);
+        t = grid_time;
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math_utils::norm_cdf;
+    use crate::models::kou::KouParams;
+    use crate::models::merton::MertonParams;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_jump_adapted_paths_match_closed_forms() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let grid = [0.25, 0.5, 0.75, 1.0];
+        let n = 40_000;
+
+        // Kou: E[S_T] = S_0 exp((μ + λ E[e^Y - 1]) T), jumps ~ Poisson(λT)
+        let kou = Kou::new(KouParams {
+            s0: 100.0,
+            mu: 0.05,
+            sigma: 0.2,
+            lambda: 3.0,
+            p: 0.4,
+            eta_up: 10.0,
+            eta_down: 5.0,
+        })
+        .unwrap();
+        let (mut mean, mut jumps) = (0.0, 0);
+        for _ in 0..n {
+            let path = simulate_jump_adapted(&kou, &grid, &mut rng).unwrap();
+            assert_eq!(path.grid_indices.len(), grid.len());
+            assert!(path.times.windows(2).all(|w| w[0] < w[1]));
+            mean += path.grid_prices()[grid.len()] / n as f64;
+            jumps += path.jumps();
+        }
+        let expected = 100.0 * (0.05 + 3.0 * kou.mean_jump()).exp();
+        assert!(
+            (mean - expected).abs() < 0.01 * expected,
+            "{} vs {}",
+            mean,
+            expected
+        );
+        assert!((jumps as f64 / n as f64 - 3.0).abs() < 0.05);
+
+        // Without jumps, one grid point: the down-and-out survival of GBM
+        let gbm = Merton::new(MertonParams {
+            s0: 100.0,
+            mu: 0.03,
+            sigma: 0.25,
+            lambda: 0.0,
+            mu_j: 0.0,
+            sigma_j: 0.0,
+        });
+        let (barrier, nu, sigma): (f64, f64, f64) = (85.0, 0.03 - 0.5 * 0.0625, 0.25);
+        let b = (barrier / 100.0).ln();
+        let exact = norm_cdf((nu - b) / sigma)
+            - (2.0 * nu * b / (sigma * sigma)).exp() * norm_cdf((b + nu) / sigma);
+        let (mut survival, mut below) = (0.0, 0);
+        for _ in 0..n {
+            let path = simulate_jump_adapted(&gbm, &[1.0], &mut rng).unwrap();
+            survival += path.survival_probability(barrier, false) / n as f64;
+            below += usize::from(path.sample_extremes(&mut rng).0 <= barrier);
+        }
+        assert!(
+            (survival - exact).abs() < 0.005,
+            "{} vs {}",
+            survival,
+            exact
+        );
+        let sampled = 1.0 - below as f64 / n as f64;
+        assert!((sampled - exact).abs() < 0.01, "{} vs {}", sampled, exact);
+
+        assert!(simulate_jump_adapted(&gbm, &[0.5, 0.5], &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_poisson_bridge_stratifies_jump_counts() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        let grid = [0.1, 0.4, 0.5, 1.0];
+        let mut totals = [0u64; 4];
+        for _ in 0..10_000 {
+            let counts = poisson_bridge_counts(6, &grid, &mut rng);
+            assert_eq!(counts.iter().sum::<u64>(), 6);
+            totals.iter_mut().zip(&counts).for_each(|(t, c)| *t += c);
+        }
+        for (total, share) in totals.iter().zip([0.1, 0.3, 0.1, 0.5]) {
+            assert!((*total as f64 / 60_000.0 - share).abs() < 0.01);
+        }
+
+        // Stratified on N, E[S(t_i)] = S_0 exp((μ + λ(e^{μ_J + σ_J²/2} - 1)) t_i)
+        let params = MertonParams {
+            s0: 100.0,
+            mu: 0.04,
+            sigma: 0.2,
+            lambda: 2.0,
+            mu_j: -0.1,
+            sigma_j: 0.15,
+        };
+        let merton = Merton::new(params);
+        let mut means = [0.0; 4];
+        let mut probability = (-2.0f64).exp();
+        for n in 0..16u64 {
+            if n > 0 {
+                probability *= 2.0 / n as f64;
+            }
+            for _ in 0..4_000 {
+                let prices = simulate_grid_given_jumps(&merton, &grid, n, &mut rng).unwrap();
+                for (mean, price) in means.iter_mut().zip(&prices[1..]) {
+                    *mean += probability * price / 4_000.0;
+                }
+            }
+            let path = simulate_given_jumps(&merton, &grid, n, &mut rng).unwrap();
+            assert_eq!(path.jumps(), n as usize);
+        }
+        let k = (-0.1f64 + 0.5 * 0.15 * 0.15).exp() - 1.0;
+        for (mean, t) in means.iter().zip(grid) {
+            let expected = 100.0 * ((0.04 + 2.0 * k) * t).exp();
+            assert!(
+                (mean - expected).abs() < 0.005 * expected,
+                "{} vs {}",
+                mean,
+                expected
+            );
+        }
+    }
+}
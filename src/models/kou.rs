@@ -0,0 +1,126 @@
+// src/models/kou.rs
+//! Kou Double-Exponential Jump-Diffusion
+//!
+//! # Mathematical Framework
+//!
+//! ```text
+//! dS_t / S_{t-} = μ dt + σ dW_t + d(Σ_{k ≤ N_t} (e^{Y_k} - 1))
+//! ```
+//! with `N_t` a Poisson process of intensity `λ` and log-jumps `Y_k`
+//! drawn from the asymmetric double-exponential density
+//! ```text
+//! f(y) = p η₁ e^{-η₁ y} 1{y ≥ 0} + (1 - p) η₂ e^{η₂ y} 1{y < 0}
+//! E[e^Y] = p η₁/(η₁ - 1) + (1 - p) η₂/(η₂ + 1)        (η₁ > 1)
+//! ```
+//! Upward jumps have mean `1/η₁` and downward jumps mean `1/η₂`; the heavy
+//! right tail of `e^Y` needs `η₁ > 1` for a finite mean price.
+//!
+//! # Simulation
+//!
+//! [`Kou::step`] buckets the jumps of each step like
+//! [`Merton::step`](super::merton::Merton::step);
+//! [`jump_adapted`](super::jump_adapted) simulates them at their exact
+//! times.
+
+use super::model::SDEModel;
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::rng;
+use rand::Rng;
+use rand_distr::{Distribution, Poisson};
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KouParams {
+    pub s0: f64,
+    pub mu: f64,
+    pub sigma: f64,
+    pub lambda: f64,   // Jump intensity
+    pub p: f64,        // Probability of an upward jump
+    pub eta_up: f64,   // Rate of upward log-jumps
+    pub eta_down: f64, // Rate of downward log-jumps
+}
+
+pub struct Kou {
+    pub params: KouParams,
+}
+
+impl Kou {
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidParameters` unless `s0`, `sigma` and
+    /// `eta_down` are positive, `lambda` non-negative, `p` in `[0, 1]` and
+    /// `eta_up > 1`.
+    pub fn new(params: KouParams) -> SdeResult<Self> {
+        validate_positive("s0", params.s0)?;
+        validate_finite("mu", params.mu)?;
+        validate_positive("sigma", params.sigma)?;
+        validate_non_negative("lambda", params.lambda)?;
+        validate_range("p", params.p, 0.0, 1.0)?;
+        validate_finite("eta_up", params.eta_up)?;
+        if params.eta_up <= 1.0 {
+            return Err(SdeError::InvalidParameters {
+                parameter: "eta_up".to_string(),
+                value: params.eta_up,
+                constraint: "must exceed 1 for a finite mean price".to_string(),
+            });
+        }
+        validate_positive("eta_down", params.eta_down)?;
+        Ok(Kou { params })
+    }
+
+    /// `E[e^Y] - 1`, the mean relative jump
+    pub fn mean_jump(&self) -> f64 {
+        let KouParams {
+            p,
+            eta_up,
+            eta_down,
+            ..
+        } = self.params;
+        p * eta_up / (eta_up - 1.0) + (1.0 - p) * eta_down / (eta_down + 1.0) - 1.0
+    }
+
+    /// One log-jump `Y`
+    pub fn sample_log_jump<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        // 1 - U in (0, 1] keeps the logarithm finite
+        let size = -(1.0 - rng.gen::<f64>()).ln();
+        if rng.gen::<f64>() < self.params.p {
+            size / self.params.eta_up
+        } else {
+            -size / self.params.eta_down
+        }
+    }
+
+    pub fn step<R: Rng + ?Sized>(&self, s: &mut f64, dt: f64, rng: &mut R) {
+        let z = rng::get_normal_draw(rng);
+        *s *= ((self.params.mu - 0.5 * self.params.sigma * self.params.sigma) * dt
+            + self.params.sigma * dt.sqrt() * z)
+            .exp();
+
+        if self.params.lambda > 0.0 {
+            let num_jumps = Poisson::new(self.params.lambda * dt).unwrap().sample(rng) as usize;
+            for _ in 0..num_jumps {
+                *s *= self.sample_log_jump(rng).exp();
+            }
+        }
+    }
+}
+
+impl SDEModel for Kou {
+    // Continuous part, as for Merton
+    fn drift(&self, s: f64, _t: f64) -> f64 {
+        self.params.mu * s
+    }
+
+    fn diffusion(&self, s: f64, _t: f64) -> f64 {
+        self.params.sigma * s
+    }
+
+    fn diffusion_derivative(&self, _s: f64, _t: f64) -> f64 {
+        self.params.sigma
+    }
+
+    fn step_with_dw(&self, s_current: &mut f64, t_current: f64, dt: f64, dw: f64) {
+        *s_current +=
+            self.drift(*s_current, t_current) * dt + self.diffusion(*s_current, t_current) * dw;
+    }
+}
@@ -5,6 +5,8 @@ use rand::Rng;
 use rand_distr::{Distribution, Poisson};
 use std::f64;
 
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MertonParams {
     pub s0: f64,
     pub mu: f64,
@@ -1,7 +1,16 @@
 // src/models/mod.rs
+pub mod alfonsi;
+pub mod cir_intensity;
 pub mod gbm;
+pub mod gbm_hull_white;
 pub mod heston;
+pub mod heston_exact;
+pub mod hull_white;
+pub mod jump_adapted;
+pub mod kou;
 pub mod merton;
 pub mod model;
 pub mod ou_process;
 pub mod sabr;
+pub mod schwartz_smith;
+pub mod time_dependent_gbm;
@@ -4,4 +4,83 @@ pub trait SDEModel {
     fn diffusion(&self, s: f64, t: f64) -> f64;
     fn diffusion_derivative(&self, s: f64, t: f64) -> f64;
     fn step_with_dw(&self, s_current: &mut f64, t_current: f64, dt: f64, dw: f64);
+
+    /// `∂a/∂s`; central finite difference unless overridden
+    fn drift_derivative(&self, s: f64, t: f64) -> f64 {
+        let h = fd_bump(s);
+        (self.drift(s + h, t) - self.drift(s - h, t)) / (2.0 * h)
+    }
+
+    /// `∂²a/∂s²`; three-point central difference of
+    /// [`drift`](Self::drift) unless overridden
+    fn drift_second_derivative(&self, s: f64, t: f64) -> f64 {
+        let h = fd_bump_second(s);
+        (self.drift(s + h, t) - 2.0 * self.drift(s, t) + self.drift(s - h, t)) / (h * h)
+    }
+
+    /// `∂²b/∂s²`; three-point central difference of
+    /// [`diffusion`](Self::diffusion) unless overridden
+    fn diffusion_second_derivative(&self, s: f64, t: f64) -> f64 {
+        let h = fd_bump_second(s);
+        (self.diffusion(s + h, t) - 2.0 * self.diffusion(s, t) + self.diffusion(s - h, t)) / (h * h)
+    }
+}
+
+/// Finite-difference bump for the default first derivatives: about the cube
+/// root of machine epsilon relative to `s`, the optimum for central
+/// differences
+fn fd_bump(s: f64) -> f64 {
+    6e-6 * s.abs().max(1.0)
+}
+
+/// Finite-difference bump for the default second derivatives: the fourth
+/// root of machine epsilon relative to `s`, which balances the `O(h²)`
+/// truncation error of the three-point formula against its `O(ε/h²)`
+/// rounding error
+fn fd_bump_second(s: f64) -> f64 {
+    f64::EPSILON.powf(0.25) * s.abs().max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `a(s) = sin s`, `b(s) = 0.3 s³`, relying on the default derivatives
+    struct Smooth;
+
+    impl SDEModel for Smooth {
+        fn drift(&self, s: f64, _t: f64) -> f64 {
+            s.sin()
+        }
+
+        fn diffusion(&self, s: f64, _t: f64) -> f64 {
+            0.3 * s.powi(3)
+        }
+
+        fn diffusion_derivative(&self, s: f64, _t: f64) -> f64 {
+            0.9 * s * s
+        }
+
+        fn step_with_dw(&self, s_current: &mut f64, t_current: f64, dt: f64, dw: f64) {
+            *s_current +=
+                self.drift(*s_current, t_current) * dt + self.diffusion(*s_current, t_current) * dw;
+        }
+    }
+
+    #[test]
+    fn test_default_derivatives_match_closed_forms() {
+        for s in [0.3f64, 2.0, 50.0] {
+            let scale = s.max(1.0);
+            assert!((Smooth.drift_derivative(s, 0.0) - s.cos()).abs() < 1e-7);
+            let a_xx = Smooth.drift_second_derivative(s, 0.0);
+            assert!((a_xx + s.sin()).abs() < 1e-6, "a''({}) = {}", s, a_xx);
+            let b_xx = Smooth.diffusion_second_derivative(s, 0.0);
+            assert!(
+                (b_xx - 1.8 * s).abs() < 1e-6 * scale * scale,
+                "b''({}) = {}",
+                s,
+                b_xx
+            );
+        }
+    }
 }
@@ -27,6 +27,18 @@ impl SDEModel for OuProcess {
         0.0 // Derivative of a constant diffusion w.r.t. s is 0
     }
 
+    fn drift_derivative(&self, _s: f64, _t: f64) -> f64 {
+        -self.theta
+    }
+
+    fn drift_second_derivative(&self, _s: f64, _t: f64) -> f64 {
+        0.0
+    }
+
+    fn diffusion_second_derivative(&self, _s: f64, _t: f64) -> f64 {
+        0.0
+    }
+
     fn step_with_dw(&self, s_current: &mut f64, t_current: f64, dt: f64, dw: f64) {
         *s_current +=
             self.drift(*s_current, t_current) * dt + self.diffusion(*s_current, t_current) * dw;
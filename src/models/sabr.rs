@@ -1,13 +1,44 @@
 // src/models/sabr.rs
+//! SABR Stochastic Volatility Model
+//!
+//! # Dynamics
+//!
+//! ```text
+//! dF_t = α V_t F_t^β dW_1,   dV_t = ν V_t dW_2,   dW_1 dW_2 = ρ dt,   V_0 = v0
+//! ```
+//! with `0 ≤ β ≤ 1`. For `β = 1` the forward is lognormal and stays
+//! positive. For `β < 1` it can reach zero, and zero is made absorbing,
+//! which keeps `F` a martingale and matches the prices of
+//! [`sabr_call_price`](crate::analytics::sabr_analytic::sabr_call_price);
+//! a forward at zero stays there.
+//!
+//! # Simulation
+//!
+//! The volatility is lognormal given its driver and is stepped exactly.
+//! The forward takes a log-Euler step for `β = 1` and an Euler step with
+//! the start-of-step local volatility `σ = α V_n F_n^β` otherwise. An
+//! Euler step that lands at or below zero is absorbed, and a path that
+//! ends a step above zero is absorbed with the Brownian bridge probability
+//! of having crossed zero in between:
+//! ```text
+//! P(hit 0 | F_n, F_{n+1}) = exp(-2 F_n F_{n+1} / (σ² Δt))
+//! ```
+//! Without the bridge check, paths that dip through zero between grid
+//! points survive, which biases low-strike prices and the absorption
+//! probability by `O(√Δt)`.
+
 use super::model::SDEModel;
+use crate::error::{validation::*, SdeResult};
 use crate::rng;
 use rand::Rng;
 use std::f64;
 
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SabrParams {
     pub f0: f64, // Initial forward rate/price
     pub alpha: f64,
-    pub beta: f64, // Fixed to 1.0 for lognormal case
+    pub beta: f64, // CEV exponent in [0, 1]
     pub rho: f64,
     pub nu: f64,
     pub v0: f64, // Initial volatility for simplification in SDEModel trait
@@ -18,36 +49,81 @@ pub struct Sabr {
 }
 
 impl Sabr {
-    pub fn new(params: SabrParams) -> Self {
-        // Ensure beta is 1.0 for this implementation
-        assert!(
-            params.beta == 1.0,
-            "SABR implementation assumes beta = 1.0 (lognormal)"
-        );
-        Sabr { params }
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidParameters` unless `f0`, `alpha` and `v0`
+    /// are positive, `beta` in `[0, 1]`, `rho` in `[-1, 1]` and `nu`
+    /// non-negative.
+    pub fn new(params: SabrParams) -> SdeResult<Self> {
+        validate_positive("f0", params.f0)?;
+        validate_positive("alpha", params.alpha)?;
+        validate_range("beta", params.beta, 0.0, 1.0)?;
+        validate_correlation("rho", params.rho)?;
+        validate_non_negative("nu", params.nu)?;
+        validate_positive("v0", params.v0)?;
+        Ok(Sabr { params })
     }
 
-    // Implement the step for the SABR model (for beta=1.0, lognormal)
-    // dF_t = alpha * F_t * V_t dW_1
-    // dV_t = nu * V_t dW_2
-    // dW_1, dW_2 correlated with rho
     pub fn step<R: Rng + ?Sized>(&self, f: &mut f64, v: &mut f64, dt: f64, rng: &mut R) {
         let z1: f64 = rng::get_normal_draw(rng);
         let z2: f64 = rng::get_normal_draw(rng);
+        let u = self.bridge_uniform(rng);
+        self.step_with_draws(f, v, dt, z1, z2, u);
+    }
 
-        let z2corr = self.params.rho * z1 + (1.0 - self.params.rho * self.params.rho).sqrt() * z2;
+    /// Step driven by caller-supplied independent standard normals `z1`
+    /// (forward) and `z2` (volatility, before correlation), and the uniform
+    /// `u` of the zero-crossing test (unused for `β = 1`)
+    pub fn step_with_draws(&self, f: &mut f64, v: &mut f64, dt: f64, z1: f64, z2: f64, u: f64) {
+        let SabrParams {
+            alpha,
+            beta,
+            rho,
+            nu,
+            ..
+        } = self.params;
+        let z2corr = rho * z1 + (1.0 - rho * rho).sqrt() * z2;
+        let sqrt_dt = dt.sqrt();
 
-        // Update volatility (V_t)
-        let dv = self.params.nu * *v * dt.sqrt() * z2corr; // Simplified Euler for V
-        *v = *v + dv;
-        if *v < 0.0 {
-            *v = 0.0;
-        } // Full truncation for volatility
+        // Update forward rate/price (F_t) with the start-of-step volatility
+        if beta == 1.0 {
+            let sigma = alpha * *v;
+            *f *= (-0.5 * sigma * sigma * dt + sigma * sqrt_dt * z1).exp();
+        } else if *f > 0.0 {
+            let sigma = alpha * *v * f.powf(beta);
+            let next = *f + sigma * sqrt_dt * z1;
+            let crossed = next <= 0.0 || u < (-2.0 * *f * next / (sigma * sigma * dt)).exp();
+            *f = if crossed { 0.0 } else { next };
+        }
+
+        // Update volatility (V_t), exactly lognormal
+        *v *= (nu * sqrt_dt * z2corr - 0.5 * nu * nu * dt).exp();
+    }
+
+    /// Step of an antithetic pair of states: state 1 sees the negated
+    /// draws `(-z1, -z2)` and the reflected uniform `1 - u` of state 0, so
+    /// both correlated increments flip
+    pub fn step_antithetic<R: Rng + ?Sized>(
+        &self,
+        f: &mut [f64; 2],
+        v: &mut [f64; 2],
+        dt: f64,
+        rng: &mut R,
+    ) {
+        let z1: f64 = rng::get_normal_draw(rng);
+        let z2: f64 = rng::get_normal_draw(rng);
+        let u = self.bridge_uniform(rng);
+        self.step_with_draws(&mut f[0], &mut v[0], dt, z1, z2, u);
+        self.step_with_draws(&mut f[1], &mut v[1], dt, -z1, -z2, 1.0 - u);
+    }
 
-        // Update forward rate/price (F_t)
-        let df_log = (-0.5 * self.params.alpha * self.params.alpha * *v * *v) * dt
-            + self.params.alpha * *v * dt.sqrt() * z1;
-        *f = *f * df_log.exp();
+    /// The zero-crossing uniform, drawn only when the forward can reach zero
+    fn bridge_uniform<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        if self.params.beta < 1.0 {
+            rng.gen()
+        } else {
+            1.0
+        }
     }
 }
 
@@ -60,16 +136,92 @@ impl SDEModel for Sabr {
     }
 
     fn diffusion(&self, f: f64, _t: f64) -> f64 {
-        self.params.alpha * self.params.v0 * f // Using initial volatility as a constant for this simplified trait implementation
+        // Using initial volatility as a constant for this simplified trait implementation
+        self.params.alpha * self.params.v0 * f.max(0.0).powf(self.params.beta)
     }
 
-    fn diffusion_derivative(&self, _f: f64, _t: f64) -> f64 {
-        self.params.alpha * self.params.v0 // Derivative of alpha * v0 * f with respect to f is alpha * v0
+    fn diffusion_derivative(&self, f: f64, _t: f64) -> f64 {
+        // Derivative of alpha * v0 * f^beta with respect to f, zero at the absorbing boundary
+        if f > 0.0 {
+            self.params.alpha * self.params.v0 * self.params.beta * f.powf(self.params.beta - 1.0)
+        } else {
+            0.0
+        }
     }
 
     fn step_with_dw(&self, s_current: &mut f64, t_current: f64, dt: f64, dw: f64) {
         // Simplified 1D step, consistent with the 1D drift and diffusion implementations
         *s_current +=
             self.drift(*s_current, t_current) * dt + self.diffusion(*s_current, t_current) * dw;
+        if self.params.beta < 1.0 {
+            *s_current = s_current.max(0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::sabr_analytic::sabr_call_price;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_absorbing_forward_matches_zero_correlation_prices() {
+        let params = SabrParams {
+            f0: 0.02,
+            alpha: 0.025,
+            beta: 0.5,
+            rho: 0.0,
+            nu: 0.3,
+            v0: 1.0,
+        };
+        let model = Sabr::new(params).unwrap();
+        let (t, steps, n) = (10.0, 100, 20_000);
+        let dt = t / steps as f64;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let finals: Vec<[f64; 2]> = (0..n)
+            .map(|_| {
+                let (mut f, mut v) = ([params.f0; 2], [params.v0; 2]);
+                for _ in 0..steps {
+                    model.step_antithetic(&mut f, &mut v, dt, &mut rng);
+                }
+                assert!(f.iter().all(|&f| f >= 0.0));
+                f
+            })
+            .collect();
+        // Mean and standard error of a payoff over antithetic pairs
+        let pair_mean = |payoff: &dyn Fn(f64) -> f64| {
+            let values: Vec<f64> = finals
+                .iter()
+                .map(|f| 0.5 * (payoff(f[0]) + payoff(f[1])))
+                .collect();
+            let mean = values.iter().sum::<f64>() / n as f64;
+            let sd = (values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n as f64).sqrt();
+            (mean, sd / (n as f64).sqrt())
+        };
+        // Absorption keeps the forward a martingale
+        let (mean, std_error) = pair_mean(&|f| f);
+        assert!((mean - params.f0).abs() < 4.0 * std_error, "{}", mean);
+        let (absorbed, _) = pair_mean(&|f| if f == 0.0 { 1.0 } else { 0.0 });
+        assert!(absorbed > 0.01);
+
+        for k in [0.002, 0.01, 0.02, 0.04] {
+            let (price, std_error) = pair_mean(&|f| (f - k).max(0.0));
+            let exact = sabr_call_price(params.f0, k, t, params.alpha, params.beta, 0.0, params.nu)
+                .unwrap();
+            assert!(
+                (price - exact).abs() < 4.0 * std_error,
+                "K = {}: {} vs {}",
+                k,
+                price,
+                exact
+            );
+        }
+
+        assert!(Sabr::new(SabrParams {
+            beta: 1.5,
+            ..params
+        })
+        .is_err());
     }
 }
@@ -1,20 +1,475 @@
 // src/output.rs
+//! CSV export of simulated paths, run summaries and histograms
+//!
+//! # Path Hashing
+//!
+//! Every exported path row carries a 64-bit content hash, and the file as a
+//! whole is summarised by a run hash folded over the path hashes in `path_id`
+//! order. Both use FNV-1a over the IEEE-754 bit patterns of the values, which
+//! is fully specified and therefore stable across machines, compilers, and
+//! crate versions (unlike `std::collections::hash_map::DefaultHasher`).
+//!
+//! Because Rayon's indexed `collect` preserves input order, the run hash does
+//! not depend on how paths were scheduled across threads: two runs produce
+//! the same run hash if and only if (up to hash collisions) every exported
+//! value is bit-for-bit identical.
+//!
+//! [`read_paths_from_csv`] recomputes the hash of every row that stores one
+//! and rejects the file on a mismatch, so a corrupted or hand-edited value
+//! is reported rather than read back silently.
+//!
+//! # Schema Versions
+//!
+//! Exported artifacts are tagged with a [`SchemaVersion`]:
+//! - **v1**: `path_id,s_t,payoff,delta` (original format, no hashes)
+//! - **v2**: v1 columns plus `path_hash`; summaries carry a `schema_version` row
+//!
+//! Readers locate columns by header name rather than position and ignore
+//! columns they do not know, so files written by newer versions that only
+//! add fields remain readable. Older files are upgraded with
+//! [`migrate_path_records`].
+//!
+//! # Columnar Output
+//!
+//! With the `arrow` feature, the `arrow` module builds Arrow RecordBatches and
+//! writes Parquet files for path matrices, per-path results and option
+//! chains, with the same path and run hashes.
+//!
+//! # Streaming
+//!
+//! [`streaming`] writes paths chunk by chunk as the workers produce them,
+//! so exports of any size run in bounded memory.
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod streaming;
+
+use crate::mc::histogram::Histogram;
 use std::fs::File;
-use std::io::{self, Write};
+use std::io::{self, BufRead, BufReader, Write};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+fn fnv1a_update(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// Content hash of a single exported path record
+///
+/// Hashes the `path_id` together with the exact bit patterns of `values`,
+/// so any change in a simulated value (even in the last ulp) changes the hash.
+pub fn hash_path_record(path_id: u64, values: &[f64]) -> u64 {
+    let mut hash = fnv1a_update(FNV_OFFSET_BASIS, &path_id.to_le_bytes());
+    for value in values {
+        hash = fnv1a_update(hash, &value.to_bits().to_le_bytes());
+    }
+    hash
+}
+
+/// Global run hash folded over per-path hashes in `path_id` order
+pub fn hash_run(path_hashes: &[u64]) -> u64 {
+    let mut hasher = RunHasher::new(path_hashes.len() as u64);
+    path_hashes.iter().for_each(|&h| hasher.push(h));
+    hasher.finish()
+}
+
+/// Incremental [`hash_run`] for writers that see the path hashes in
+/// `path_id` order but never all at once
+pub(crate) struct RunHasher {
+    hash: u64,
+}
+
+impl RunHasher {
+    pub(crate) fn new(paths: u64) -> Self {
+        RunHasher {
+            hash: fnv1a_update(FNV_OFFSET_BASIS, &paths.to_le_bytes()),
+        }
+    }
+
+    pub(crate) fn push(&mut self, path_hash: u64) {
+        self.hash = fnv1a_update(self.hash, &path_hash.to_le_bytes());
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Format a hash the way it appears in exported files (16 hex digits)
+pub fn format_hash(hash: u64) -> String {
+    format!("{:016x}", hash)
+}
+
+/// Version of the exported result schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SchemaVersion {
+    /// `path_id,s_t,payoff,delta`
+    V1,
+    /// v1 columns plus `path_hash`, summaries tagged with `schema_version`
+    V2,
+}
+
+impl SchemaVersion {
+    /// Version written by this release
+    pub const CURRENT: SchemaVersion = SchemaVersion::V2;
+
+    /// Tag used in summary files (`v1`, `v2`, ...)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchemaVersion::V1 => "v1",
+            SchemaVersion::V2 => "v2",
+        }
+    }
+
+    /// Parse a version tag as written by [`SchemaVersion::as_str`]
+    pub fn parse(tag: &str) -> Option<Self> {
+        match tag.trim() {
+            "v1" => Some(SchemaVersion::V1),
+            "v2" => Some(SchemaVersion::V2),
+            _ => None,
+        }
+    }
+
+    /// Path file columns in the order this version writes them
+    pub fn path_columns(&self) -> &'static [&'static str] {
+        match self {
+            SchemaVersion::V1 => &["path_id", "s_t", "payoff", "delta"],
+            SchemaVersion::V2 => &["path_id", "s_t", "payoff", "delta", "path_hash"],
+        }
+    }
+
+    /// Detect the version of a path file from its header columns
+    ///
+    /// Unknown extra columns are tolerated; the newest version whose
+    /// columns are all present is returned.
+    pub fn detect_from_header(columns: &[&str]) -> Option<Self> {
+        [SchemaVersion::V2, SchemaVersion::V1]
+            .into_iter()
+            .find(|v| v.path_columns().iter().all(|c| columns.contains(c)))
+    }
+}
+
+/// A single row of an exported path file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathRecord {
+    pub path_id: u64,
+    pub s_t: f64,
+    pub payoff: f64,
+    pub delta: f64,
+    /// Present from v2 onwards
+    pub path_hash: Option<u64>,
+}
+
+impl PathRecord {
+    /// Recompute the content hash from the record's values
+    pub fn compute_hash(&self) -> u64 {
+        hash_path_record(self.path_id, &[self.s_t, self.payoff, self.delta])
+    }
+}
+
+fn invalid_data(reason: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
 
+/// Write per-path results to CSV with a content hash per row
+///
+/// Use [`write_paths_to_csv_versioned`] with [`SchemaVersion::CURRENT`] to
+/// also get the run hash to record alongside the run summary.
 pub fn write_paths_to_csv(filename: &str, paths: &[(f64, f64, f64)]) -> io::Result<()> {
+    write_paths_to_csv_versioned(filename, paths, SchemaVersion::CURRENT).map(|_| ())
+}
+
+/// Write per-path results in a specific schema version, returning the run
+/// hash
+///
+/// Lets producers keep emitting an older layout for consumers that have not
+/// migrated yet. The run hash is returned regardless of whether the chosen
+/// version stores per-path hashes; callers should record it alongside the
+/// run summary (e.g. as a `run_hash` entry).
+pub fn write_paths_to_csv_versioned(
+    filename: &str,
+    paths: &[(f64, f64, f64)],
+    version: SchemaVersion,
+) -> io::Result<u64> {
     let mut file = File::create(filename)?;
-    writeln!(file, "path_id,s_t,payoff,delta")?;
+    writeln!(file, "{}", version.path_columns().join(","))?;
+    let mut path_hashes = Vec::with_capacity(paths.len());
     for (i, (s_t, payoff, delta)) in paths.iter().enumerate() {
-        writeln!(file, "{},{},{},{}", i, s_t, payoff, delta)?;
+        let path_hash = hash_path_record(i as u64, &[*s_t, *payoff, *delta]);
+        path_hashes.push(path_hash);
+        match version {
+            SchemaVersion::V1 => writeln!(file, "{},{},{},{}", i, s_t, payoff, delta)?,
+            SchemaVersion::V2 => writeln!(
+                file,
+                "{},{},{},{},{}",
+                i,
+                s_t,
+                payoff,
+                delta,
+                format_hash(path_hash)
+            )?,
+        }
+    }
+    Ok(hash_run(&path_hashes))
+}
+
+/// Read a path file written by any supported schema version
+///
+/// Returns the detected version and the records as stored (v1 records have
+/// no `path_hash`; use [`migrate_path_records`] to upgrade them).
+///
+/// # Errors
+///
+/// Returns `io::ErrorKind::InvalidData` for a malformed file or a row whose
+/// stored `path_hash` differs from the hash of its values.
+pub fn read_paths_from_csv(filename: &str) -> io::Result<(SchemaVersion, Vec<PathRecord>)> {
+    let mut lines = BufReader::new(File::open(filename)?).lines();
+    let header = lines
+        .next()
+        .ok_or_else(|| invalid_data("path file is empty".to_string()))??;
+    let columns: Vec<&str> = header.split(',').map(str::trim).collect();
+    let version = SchemaVersion::detect_from_header(&columns)
+        .ok_or_else(|| invalid_data(format!("unrecognised path file header: {}", header)))?;
+    let index_of = |name: &str| columns.iter().position(|c| *c == name).unwrap();
+    let (i_id, i_st, i_payoff, i_delta) = (
+        index_of("path_id"),
+        index_of("s_t"),
+        index_of("payoff"),
+        index_of("delta"),
+    );
+    let i_hash = columns.iter().position(|c| *c == "path_hash");
+
+    let mut records = Vec::new();
+    for (line_no, line) in lines.enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let field = |i: usize| {
+            fields.get(i).copied().ok_or_else(|| {
+                invalid_data(format!(
+                    "line {}: missing column {}",
+                    line_no + 2,
+                    columns[i]
+                ))
+            })
+        };
+        let float = |i: usize| -> io::Result<f64> {
+            field(i)?
+                .parse()
+                .map_err(|e| invalid_data(format!("line {}: {}", line_no + 2, e)))
+        };
+        let record = PathRecord {
+            path_id: field(i_id)?
+                .parse()
+                .map_err(|e| invalid_data(format!("line {}: {}", line_no + 2, e)))?,
+            s_t: float(i_st)?,
+            payoff: float(i_payoff)?,
+            delta: float(i_delta)?,
+            path_hash: match i_hash {
+                Some(i) => Some(
+                    u64::from_str_radix(field(i)?, 16)
+                        .map_err(|e| invalid_data(format!("line {}: {}", line_no + 2, e)))?,
+                ),
+                None => None,
+            },
+        };
+        if let Some(stored) = record.path_hash {
+            let computed = record.compute_hash();
+            if stored != computed {
+                return Err(invalid_data(format!(
+                    "line {}: path_hash {} does not match the row's values (hash {})",
+                    line_no + 2,
+                    format_hash(stored),
+                    format_hash(computed)
+                )));
+            }
+        }
+        records.push(record);
+    }
+    Ok((version, records))
+}
+
+/// Upgrade records read from an older schema to [`SchemaVersion::CURRENT`]
+///
+/// v1 → v2 fills in the missing `path_hash` from the stored values. Records
+/// already at the current version are returned unchanged.
+pub fn migrate_path_records(records: &[PathRecord], from: SchemaVersion) -> Vec<PathRecord> {
+    match from {
+        SchemaVersion::V1 => records
+            .iter()
+            .map(|r| PathRecord {
+                path_hash: Some(r.compute_hash()),
+                ..*r
+            })
+            .collect(),
+        SchemaVersion::V2 => records.to_vec(),
     }
-    Ok(())
 }
 
+/// Write a key/value summary, tagged with the current schema version
+///
+/// A `schema_version` row is appended unless the caller already supplied one.
 pub fn write_summary_to_csv(filename: &str, summary_data: &[(&str, &str)]) -> io::Result<()> {
     let mut file = File::create(filename)?;
     for (key, value) in summary_data {
         writeln!(file, "{},{}", key, value)?;
     }
+    if !summary_data.iter().any(|(key, _)| *key == "schema_version") {
+        writeln!(file, "schema_version,{}", SchemaVersion::CURRENT.as_str())?;
+    }
+    Ok(())
+}
+
+/// Read a key/value summary and its schema version
+///
+/// Summaries without a `schema_version` row predate versioning and are
+/// reported as [`SchemaVersion::V1`].
+pub fn read_summary_from_csv(filename: &str) -> io::Result<(SchemaVersion, Vec<(String, String)>)> {
+    let mut version = SchemaVersion::V1;
+    let mut entries = Vec::new();
+    for line in BufReader::new(File::open(filename)?).lines() {
+        let line = line?;
+        let Some((key, value)) = line.split_once(',') else {
+            continue;
+        };
+        if key == "schema_version" {
+            version = SchemaVersion::parse(value)
+                .ok_or_else(|| invalid_data(format!("unknown schema version: {}", value)))?;
+        } else {
+            entries.push((key.to_string(), value.to_string()));
+        }
+    }
+    Ok((version, entries))
+}
+
+/// Write a histogram as `bin_lower,bin_upper,count,density` rows
+///
+/// Underflow and overflow are written as the first and last rows, with
+/// infinite outer edges and zero density.
+pub fn write_histogram_to_csv(filename: &str, histogram: &Histogram) -> io::Result<()> {
+    let mut file = File::create(filename)?;
+    let edges = &histogram.edges;
+    writeln!(file, "bin_lower,bin_upper,count,density")?;
+    writeln!(file, "-inf,{},{},0", edges[0], histogram.underflow)?;
+    for ((edge, count), density) in edges
+        .windows(2)
+        .zip(&histogram.counts)
+        .zip(histogram.density())
+    {
+        writeln!(file, "{},{},{},{}", edge[0], edge[1], count, density)?;
+    }
+    writeln!(
+        file,
+        "{},inf,{},0",
+        edges[edges.len() - 1],
+        histogram.overflow
+    )?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_hash_is_deterministic_and_sensitive() {
+        let a = hash_path_record(7, &[101.5, 1.5, 1.015]);
+        let b = hash_path_record(7, &[101.5, 1.5, 1.015]);
+        assert_eq!(a, b);
+
+        // One ulp change in any value must change the hash
+        let bumped = f64::from_bits(101.5f64.to_bits() + 1);
+        assert_ne!(a, hash_path_record(7, &[bumped, 1.5, 1.015]));
+
+        // Same values under a different path id hash differently
+        assert_ne!(a, hash_path_record(8, &[101.5, 1.5, 1.015]));
+    }
+
+    #[test]
+    fn test_run_hash_depends_on_order() {
+        let h1 = hash_path_record(0, &[1.0]);
+        let h2 = hash_path_record(1, &[2.0]);
+        assert_eq!(hash_run(&[h1, h2]), hash_run(&[h1, h2]));
+        assert_ne!(hash_run(&[h1, h2]), hash_run(&[h2, h1]));
+        assert_eq!(format_hash(0xab).len(), 16);
+    }
+
+    #[test]
+    fn test_v1_file_round_trips_through_migration() {
+        let filename = std::env::temp_dir().join("fast_sde_output_v1_migration.csv");
+        let filename = filename.to_str().unwrap();
+        let paths = [(105.0, 5.0, 1.05), (95.0, 0.0, 0.0)];
+
+        let run_hash = write_paths_to_csv_versioned(filename, &paths, SchemaVersion::V1).unwrap();
+        let (version, records) = read_paths_from_csv(filename).unwrap();
+        assert_eq!(version, SchemaVersion::V1);
+        assert!(records.iter().all(|r| r.path_hash.is_none()));
+
+        let migrated = migrate_path_records(&records, version);
+        let hashes: Vec<u64> = migrated.iter().map(|r| r.path_hash.unwrap()).collect();
+        assert_eq!(hash_run(&hashes), run_hash);
+
+        write_paths_to_csv(filename, &paths).unwrap();
+        let (version, records) = read_paths_from_csv(filename).unwrap();
+        assert_eq!(version, SchemaVersion::CURRENT);
+        assert_eq!(records, migrated);
+        std::fs::remove_file(filename).ok();
+    }
+
+    #[test]
+    fn test_read_rejects_rows_that_do_not_match_their_hash() {
+        let filename = std::env::temp_dir().join("fast_sde_output_corrupted.csv");
+        let filename = filename.to_str().unwrap();
+        write_paths_to_csv(filename, &[(105.0, 5.0, 1.05), (95.0, 0.0, 0.0)]).unwrap();
+        assert!(read_paths_from_csv(filename).is_ok());
+
+        let contents = std::fs::read_to_string(filename).unwrap();
+        std::fs::write(filename, contents.replacen("105,5,", "105,6,", 1)).unwrap();
+        let error = read_paths_from_csv(filename).unwrap_err();
+        std::fs::remove_file(filename).ok();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().starts_with("line 2:"), "{}", error);
+    }
+
+    #[test]
+    fn test_header_detection_ignores_unknown_columns() {
+        let columns = ["path_id", "s_t", "payoff", "delta", "path_hash", "vega"];
+        assert_eq!(
+            SchemaVersion::detect_from_header(&columns),
+            Some(SchemaVersion::V2)
+        );
+        assert_eq!(SchemaVersion::detect_from_header(&["path_id", "s_t"]), None);
+    }
+
+    #[test]
+    fn test_histogram_csv_has_one_row_per_bin() {
+        let filename = std::env::temp_dir().join("fast_sde_output_histogram.csv");
+        let filename = filename.to_str().unwrap();
+        let mut histogram = Histogram::new(0.0, 2.0, 2);
+        for x in [-1.0, 0.5, 1.5, 1.5, 3.0] {
+            histogram.push(x);
+        }
+        write_histogram_to_csv(filename, &histogram).unwrap();
+
+        let contents = std::fs::read_to_string(filename).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "bin_lower,bin_upper,count,density",
+                "-inf,0,1,0",
+                "0,1,1,0.2",
+                "1,2,2,0.4",
+                "2,inf,1,0",
+            ]
+        );
+        std::fs::remove_file(filename).ok();
+    }
+}
@@ -0,0 +1,472 @@
+// src/output/arrow.rs
+//! Arrow RecordBatches and Parquet files for paths and results
+//!
+//! Columnar counterparts of the CSV writers in [`crate::output`]: values are
+//! stored as raw IEEE-754 doubles (no decimal round trip), and Parquet files
+//! are Snappy-compressed, which keeps million-path exports fast and small.
+//!
+//! # Layouts
+//!
+//! ```text
+//! path records:  path_id u64, s_t f64, payoff f64, delta f64, path_hash u64
+//! path matrix:   path_id u64, values fixed_size_list<f64>[steps + 1], path_hash u64
+//! option chain:  maturity f64, strike f64, price f64, std_error f64, delta f64, vega f64
+//! ```
+//!
+//! Path hashes are the same FNV-1a hashes as in the CSV files. Path batches
+//! carry `schema_version` and `run_hash` in their schema metadata, which
+//! Parquet stores alongside the data. [`ParquetPathSink`] streams the same
+//! layouts chunk by chunk and adds `run_hash` when the export finishes.
+
+use super::streaming::{PathChunk, PathSink};
+use super::{format_hash, hash_path_record, hash_run, PathRecord, SchemaVersion};
+use crate::mc::chain::ChainResult;
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, UInt64Type};
+use arrow_array::{ArrayRef, FixedSizeListArray, Float64Array, RecordBatch, UInt64Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use ndarray::ArrayView2;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::sync::Arc;
+
+fn io_error<E: std::error::Error + Send + Sync + 'static>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error)
+}
+
+fn path_metadata(run_hash: Option<u64>) -> HashMap<String, String> {
+    let mut metadata = HashMap::from([(
+        "schema_version".to_string(),
+        SchemaVersion::CURRENT.as_str().to_string(),
+    )]);
+    if let Some(run_hash) = run_hash {
+        metadata.insert("run_hash".to_string(), format_hash(run_hash));
+    }
+    metadata
+}
+
+fn float_column(name: &str) -> Field {
+    Field::new(name, DataType::Float64, false)
+}
+
+/// Column layout of exported paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PathLayout {
+    /// `(s_t, payoff, delta)` per path
+    Records,
+    /// Fixed-size list of values per path
+    Matrix(i32),
+}
+
+impl PathLayout {
+    fn matrix(width: usize) -> Result<Self, ArrowError> {
+        i32::try_from(width)
+            .ok()
+            .filter(|&n| n > 0)
+            .map(PathLayout::Matrix)
+            .ok_or_else(|| {
+                ArrowError::InvalidArgumentError(format!("path length {} is not supported", width))
+            })
+    }
+
+    fn width(&self) -> usize {
+        match self {
+            PathLayout::Records => 3,
+            PathLayout::Matrix(width) => *width as usize,
+        }
+    }
+
+    fn schema(&self, run_hash: Option<u64>) -> Schema {
+        let values = match self {
+            PathLayout::Records => vec![
+                float_column("s_t"),
+                float_column("payoff"),
+                float_column("delta"),
+            ],
+            PathLayout::Matrix(width) => vec![Field::new(
+                "values",
+                DataType::FixedSizeList(Arc::new(float_column("item")), *width),
+                false,
+            )],
+        };
+        let mut fields = vec![Field::new("path_id", DataType::UInt64, false)];
+        fields.extend(values);
+        fields.push(Field::new("path_hash", DataType::UInt64, false));
+        Schema::new_with_metadata(fields, path_metadata(run_hash))
+    }
+
+    /// Batch of the rows in `values` (row-major), numbered from `first_path_id`
+    fn batch(
+        &self,
+        schema: SchemaRef,
+        first_path_id: u64,
+        values: Vec<f64>,
+        path_hashes: Vec<u64>,
+    ) -> Result<RecordBatch, ArrowError> {
+        let rows = path_hashes.len() as u64;
+        let mut columns: Vec<ArrayRef> = vec![Arc::new(UInt64Array::from_iter_values(
+            first_path_id..first_path_id + rows,
+        ))];
+        match self {
+            PathLayout::Records => {
+                for field in 0..3 {
+                    columns.push(Arc::new(Float64Array::from_iter_values(
+                        values.iter().skip(field).step_by(3).copied(),
+                    )));
+                }
+            }
+            PathLayout::Matrix(width) => columns.push(Arc::new(FixedSizeListArray::try_new(
+                Arc::new(float_column("item")),
+                *width,
+                Arc::new(Float64Array::from(values)),
+                None,
+            )?)),
+        }
+        columns.push(Arc::new(UInt64Array::from(path_hashes)));
+        RecordBatch::try_new(schema, columns)
+    }
+
+    /// Batch of every path, with the run hash in the schema metadata
+    fn full_batch(&self, values: Vec<f64>) -> Result<RecordBatch, ArrowError> {
+        let path_hashes: Vec<u64> = values
+            .chunks(self.width())
+            .enumerate()
+            .map(|(i, row)| hash_path_record(i as u64, row))
+            .collect();
+        let schema = Arc::new(self.schema(Some(hash_run(&path_hashes))));
+        self.batch(schema, 0, values, path_hashes)
+    }
+}
+
+/// Per-path results as a RecordBatch in the current path schema
+///
+/// Rows are `(s_t, payoff, delta)` as for
+/// [`write_paths_to_csv`](super::write_paths_to_csv); the run hash is
+/// recorded in the schema metadata.
+pub fn path_records_batch(paths: &[(f64, f64, f64)]) -> Result<RecordBatch, ArrowError> {
+    let values = paths
+        .iter()
+        .flat_map(|&(s_t, payoff, delta)| [s_t, payoff, delta])
+        .collect();
+    PathLayout::Records.full_batch(values)
+}
+
+/// Full simulated paths (one row per path, `steps + 1` columns) as a
+/// RecordBatch with one fixed-size list of values per path
+///
+/// # Errors
+///
+/// Returns `ArrowError::InvalidArgumentError` for a matrix without columns.
+pub fn path_matrix_batch(paths: ArrayView2<f64>) -> Result<RecordBatch, ArrowError> {
+    PathLayout::matrix(paths.ncols())?.full_batch(paths.iter().copied().collect())
+}
+
+/// Option chain as a RecordBatch with one row per (maturity, strike)
+///
+/// Rows are ordered by maturity, then strike, as given to
+/// [`mc_price_chain`](crate::mc::chain::mc_price_chain).
+pub fn chain_batch(chain: &ChainResult) -> Result<RecordBatch, ArrowError> {
+    let cells = || {
+        chain.maturities.iter().enumerate().flat_map(|(i, &t)| {
+            chain
+                .strikes
+                .iter()
+                .enumerate()
+                .map(move |(j, &k)| (i, j, t, k))
+        })
+    };
+    let grid = |matrix: &ndarray::Array2<f64>| -> ArrayRef {
+        Arc::new(Float64Array::from_iter_values(
+            cells().map(|(i, j, _, _)| matrix[[i, j]]),
+        ))
+    };
+    let schema = Schema::new(vec![
+        float_column("maturity"),
+        float_column("strike"),
+        float_column("price"),
+        float_column("std_error"),
+        float_column("delta"),
+        float_column("vega"),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Float64Array::from_iter_values(cells().map(|c| c.2))),
+        Arc::new(Float64Array::from_iter_values(cells().map(|c| c.3))),
+        grid(&chain.prices),
+        grid(&chain.std_errors),
+        grid(&chain.deltas),
+        grid(&chain.vegas),
+    ];
+    RecordBatch::try_new(Arc::new(schema), columns)
+}
+
+/// Write a RecordBatch to a Snappy-compressed Parquet file
+pub fn write_batch_to_parquet(filename: &str, batch: &RecordBatch) -> io::Result<()> {
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer =
+        ArrowWriter::try_new(File::create(filename)?, batch.schema(), Some(properties))
+            .map_err(io_error)?;
+    writer.write(batch).map_err(io_error)?;
+    writer.close().map_err(io_error)?;
+    Ok(())
+}
+
+/// Read every RecordBatch of a Parquet file
+///
+/// Batches carry the file's Arrow schema, including its metadata.
+pub fn read_batches_from_parquet(filename: &str) -> io::Result<Vec<RecordBatch>> {
+    let builder =
+        ParquetRecordBatchReaderBuilder::try_new(File::open(filename)?).map_err(io_error)?;
+    let schema = builder.schema().clone();
+    builder
+        .build()
+        .map_err(io_error)?
+        .map(|batch| batch.and_then(|b| b.with_schema(schema.clone())))
+        .collect::<Result<_, _>>()
+        .map_err(io_error)
+}
+
+/// Write per-path results to Parquet, returning the run hash
+///
+/// Parquet counterpart of
+/// [`write_paths_to_csv_versioned`](super::write_paths_to_csv_versioned)
+/// at the current schema version.
+pub fn write_paths_to_parquet(filename: &str, paths: &[(f64, f64, f64)]) -> io::Result<u64> {
+    let batch = path_records_batch(paths).map_err(io_error)?;
+    let path_hashes = batch.column(4).as_primitive::<UInt64Type>().values();
+    let run_hash = hash_run(path_hashes);
+    write_batch_to_parquet(filename, &batch)?;
+    Ok(run_hash)
+}
+
+/// Write full simulated paths to Parquet, returning the run hash
+pub fn write_path_matrix_to_parquet(filename: &str, paths: ArrayView2<f64>) -> io::Result<u64> {
+    let batch = path_matrix_batch(paths).map_err(io_error)?;
+    let path_hashes = batch.column(2).as_primitive::<UInt64Type>().values();
+    let run_hash = hash_run(path_hashes);
+    write_batch_to_parquet(filename, &batch)?;
+    Ok(run_hash)
+}
+
+/// Write an option chain to Parquet
+pub fn write_chain_to_parquet(filename: &str, chain: &ChainResult) -> io::Result<()> {
+    write_batch_to_parquet(filename, &chain_batch(chain).map_err(io_error)?)
+}
+
+/// Read per-path results written by [`write_paths_to_parquet`]
+///
+/// Columns are located by name, as for the CSV reader.
+pub fn read_paths_from_parquet(filename: &str) -> io::Result<Vec<PathRecord>> {
+    let mut records = Vec::new();
+    for batch in read_batches_from_parquet(filename)? {
+        let column = |name: &str| {
+            batch.column_by_name(name).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("path file has no {} column", name),
+                )
+            })
+        };
+        let path_id = column("path_id")?.as_primitive::<UInt64Type>();
+        let s_t = column("s_t")?.as_primitive::<Float64Type>();
+        let payoff = column("payoff")?.as_primitive::<Float64Type>();
+        let delta = column("delta")?.as_primitive::<Float64Type>();
+        let path_hash = column("path_hash")?.as_primitive::<UInt64Type>();
+        records.extend((0..batch.num_rows()).map(|i| PathRecord {
+            path_id: path_id.value(i),
+            s_t: s_t.value(i),
+            payoff: payoff.value(i),
+            delta: delta.value(i),
+            path_hash: Some(path_hash.value(i)),
+        }));
+    }
+    Ok(records)
+}
+
+/// Parquet sink for [`stream_paths`](super::streaming::stream_paths)
+///
+/// Each chunk becomes one RecordBatch in the layout of
+/// [`path_records_batch`] or [`path_matrix_batch`]; the run hash is
+/// appended to the file's key/value metadata once the export finishes.
+pub struct ParquetPathSink {
+    writer: ArrowWriter<File>,
+    schema: SchemaRef,
+    layout: PathLayout,
+}
+
+impl ParquetPathSink {
+    fn new(filename: &str, layout: PathLayout) -> io::Result<Self> {
+        let schema = Arc::new(layout.schema(None));
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let writer =
+            ArrowWriter::try_new(File::create(filename)?, schema.clone(), Some(properties))
+                .map_err(io_error)?;
+        Ok(ParquetPathSink {
+            writer,
+            schema,
+            layout,
+        })
+    }
+
+    /// Per-path `(s_t, payoff, delta)` rows
+    pub fn records(filename: &str) -> io::Result<Self> {
+        Self::new(filename, PathLayout::Records)
+    }
+
+    /// Full paths `s_0, ..., s_steps`
+    pub fn paths(filename: &str, steps: usize) -> io::Result<Self> {
+        Self::new(filename, PathLayout::matrix(steps + 1).map_err(io_error)?)
+    }
+}
+
+impl PathSink for ParquetPathSink {
+    fn width(&self) -> usize {
+        self.layout.width()
+    }
+
+    fn write_chunk(&mut self, chunk: &PathChunk) -> io::Result<()> {
+        let batch = self
+            .layout
+            .batch(
+                self.schema.clone(),
+                chunk.first_path_id,
+                chunk.values.clone(),
+                chunk.path_hashes.clone(),
+            )
+            .map_err(io_error)?;
+        self.writer.write(&batch).map_err(io_error)
+    }
+
+    fn finish(&mut self, run_hash: u64) -> io::Result<()> {
+        self.writer.append_key_value_metadata(KeyValue::new(
+            "run_hash".to_string(),
+            format_hash(run_hash),
+        ));
+        self.writer.finish().map_err(io_error)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    fn temp_file(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("fast_sde_{}_{}.parquet", name, std::process::id()))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_path_records_round_trip_bit_for_bit() {
+        let paths = vec![
+            (101.5, 1.5, 1.015),
+            (0.1 + 0.2, 0.0, -0.0),
+            (99.0, 0.0, 0.0),
+        ];
+        let filename = temp_file("paths");
+        let run_hash = write_paths_to_parquet(&filename, &paths).unwrap();
+        let records = read_paths_from_parquet(&filename).unwrap();
+        let batches = read_batches_from_parquet(&filename).unwrap();
+        std::fs::remove_file(&filename).unwrap();
+
+        assert_eq!(records.len(), paths.len());
+        for (record, &(s_t, payoff, delta)) in records.iter().zip(&paths) {
+            assert_eq!(record.s_t.to_bits(), s_t.to_bits());
+            assert_eq!(record.payoff.to_bits(), payoff.to_bits());
+            assert_eq!(record.delta.to_bits(), delta.to_bits());
+            assert_eq!(record.path_hash, Some(record.compute_hash()));
+        }
+        let metadata = batches[0].schema().metadata().clone();
+        assert_eq!(metadata["run_hash"], format_hash(run_hash));
+        assert_eq!(metadata["schema_version"], SchemaVersion::CURRENT.as_str());
+    }
+
+    #[test]
+    fn test_path_matrix_and_chain_batches() {
+        let paths = array![[100.0, 101.0, 99.5], [100.0, 98.0, 97.25]];
+        let filename = temp_file("matrix");
+        write_path_matrix_to_parquet(&filename, paths.view()).unwrap();
+        let batches = read_batches_from_parquet(&filename).unwrap();
+        std::fs::remove_file(&filename).unwrap();
+
+        let values = batches[0].column(1).as_fixed_size_list();
+        assert_eq!(values.value_length(), 3);
+        let second = values.value(1);
+        assert_eq!(
+            second.as_primitive::<Float64Type>().values(),
+            &[100.0, 98.0, 97.25]
+        );
+        let hashes = batches[0].column(2).as_primitive::<UInt64Type>();
+        assert_eq!(hashes.value(1), hash_path_record(1, &[100.0, 98.0, 97.25]));
+        assert!(path_matrix_batch(ndarray::Array2::zeros((2, 0)).view()).is_err());
+
+        let chain = ChainResult {
+            strikes: vec![90.0, 110.0],
+            maturities: vec![0.5],
+            prices: array![[12.0, 3.0]],
+            std_errors: array![[0.1, 0.05]],
+            deltas: array![[0.8, 0.3]],
+            vegas: array![[20.0, 25.0]],
+        };
+        let batch = chain_batch(&chain).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        let strike = batch.column_by_name("strike").unwrap();
+        let price = batch.column_by_name("price").unwrap();
+        assert_eq!(strike.as_primitive::<Float64Type>().value(1), 110.0);
+        assert_eq!(price.as_primitive::<Float64Type>().value(1), 3.0);
+    }
+
+    #[test]
+    fn test_streamed_parquet_matches_collected_export() {
+        use crate::output::streaming::{stream_paths, StreamConfig};
+
+        let paths = ndarray::Array2::from_shape_fn((50, 4), |(i, j)| 100.0 + (i * j) as f64 / 7.0);
+        let (collected, streamed) = (temp_file("collected"), temp_file("streamed"));
+        let expected_hash = write_path_matrix_to_parquet(&collected, paths.view()).unwrap();
+        let config = StreamConfig {
+            chunk_paths: 8,
+            max_chunks_in_flight: 2,
+        };
+        let mut sink = ParquetPathSink::paths(&streamed, 3).unwrap();
+        let run_hash = stream_paths(50, &config, &mut sink, |i, row| {
+            row.iter_mut()
+                .zip(paths.row(i as usize))
+                .for_each(|(v, p)| *v = *p)
+        })
+        .unwrap();
+        drop(sink);
+        let batches = read_batches_from_parquet(&streamed).unwrap();
+        let expected = read_batches_from_parquet(&collected).unwrap();
+        std::fs::remove_file(&collected).unwrap();
+        std::fs::remove_file(&streamed).unwrap();
+
+        assert_eq!(run_hash, expected_hash);
+        assert_eq!(
+            batches[0].schema().metadata()["run_hash"],
+            format_hash(run_hash)
+        );
+        let columns = |batches: &[RecordBatch], i: usize| -> Vec<ArrayRef> {
+            batches.iter().map(|b| b.column(i).clone()).collect()
+        };
+        let concat = |arrays: Vec<ArrayRef>| -> Vec<u64> {
+            arrays
+                .iter()
+                .flat_map(|a| a.as_primitive::<UInt64Type>().values().to_vec())
+                .collect()
+        };
+        assert_eq!(concat(columns(&batches, 2)), concat(columns(&expected, 2)));
+        assert_eq!(concat(columns(&batches, 0)), (0..50).collect::<Vec<u64>>());
+    }
+}
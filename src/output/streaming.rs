@@ -0,0 +1,316 @@
+// src/output/streaming.rs
+//! Streaming path export with bounded memory
+//!
+//! # Pipeline
+//!
+//! [`stream_paths`] splits the paths into chunks of
+//! [`StreamConfig::chunk_paths`] rows. Rayon workers fill the chunks and
+//! hash their rows, then hand them to a single writer thread through a
+//! bounded channel; the writer puts them back in `path_id` order and passes
+//! them to a [`PathSink`]:
+//! ```text
+//! workers ──chunk──▶ sync_channel(max_chunks_in_flight) ──▶ writer ──▶ sink
+//! ```
+//! Workers run at most `max_chunks_in_flight` chunks ahead of the writer,
+//! so at most about twice that many chunks are held in memory however many
+//! paths are exported.
+//!
+//! # Output
+//!
+//! Rows reach the sink in `path_id` order with the same path hashes as the
+//! collect-then-write exporters, so [`CsvPathSink::records`] writes exactly
+//! the file [`write_paths_to_csv`](super::write_paths_to_csv) would and the
+//! returned run hash is the one
+//! [`write_paths_to_csv_versioned`](super::write_paths_to_csv_versioned)
+//! returns.
+
+use super::{format_hash, hash_path_record, RunHasher, SchemaVersion};
+use crate::parallel::prelude::*;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::sync::mpsc;
+use std::thread;
+
+/// Chunking of a streamed export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamConfig {
+    /// Paths per chunk (default 4096)
+    pub chunk_paths: usize,
+    /// Chunks the workers may run ahead of the writer (default 16)
+    pub max_chunks_in_flight: usize,
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        StreamConfig {
+            chunk_paths: 4096,
+            max_chunks_in_flight: 16,
+        }
+    }
+}
+
+/// Consecutive rows of a streamed export
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathChunk {
+    /// `path_id` of the first row
+    pub first_path_id: u64,
+    /// Row-major values, [`PathSink::width`] per row
+    pub values: Vec<f64>,
+    /// [`hash_path_record`] of each row
+    pub path_hashes: Vec<u64>,
+}
+
+impl PathChunk {
+    /// Rows of `width` values
+    pub fn rows(&self, width: usize) -> std::slice::Chunks<'_, f64> {
+        self.values.chunks(width)
+    }
+}
+
+/// Destination of a streamed export
+pub trait PathSink: Send {
+    /// Values per row
+    fn width(&self) -> usize;
+
+    /// Write one chunk; chunks arrive in `path_id` order
+    fn write_chunk(&mut self, chunk: &PathChunk) -> io::Result<()>;
+
+    /// Flush after the last chunk, recording the run hash if the format
+    /// has a place for it
+    fn finish(&mut self, run_hash: u64) -> io::Result<()>;
+}
+
+/// CSV sink writing `path_id,<value columns>,path_hash` rows
+pub struct CsvPathSink {
+    writer: BufWriter<File>,
+    width: usize,
+}
+
+impl CsvPathSink {
+    /// Create `filename` with the header `path_id,<columns>,path_hash`
+    pub fn new(filename: &str, columns: &[&str]) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(filename)?);
+        writeln!(writer, "path_id,{},path_hash", columns.join(","))?;
+        Ok(CsvPathSink {
+            writer,
+            width: columns.len(),
+        })
+    }
+
+    /// Per-path `(s_t, payoff, delta)` rows in the current path schema
+    pub fn records(filename: &str) -> io::Result<Self> {
+        let columns = SchemaVersion::CURRENT.path_columns();
+        Self::new(filename, &columns[1..columns.len() - 1])
+    }
+
+    /// Full paths `s_0, ..., s_steps`
+    pub fn paths(filename: &str, steps: usize) -> io::Result<Self> {
+        let columns: Vec<String> = (0..=steps).map(|j| format!("s_{}", j)).collect();
+        let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+        Self::new(filename, &columns)
+    }
+}
+
+impl PathSink for CsvPathSink {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn write_chunk(&mut self, chunk: &PathChunk) -> io::Result<()> {
+        for ((path_id, row), path_hash) in (chunk.first_path_id..)
+            .zip(chunk.rows(self.width))
+            .zip(&chunk.path_hashes)
+        {
+            write!(self.writer, "{}", path_id)?;
+            for value in row {
+                write!(self.writer, ",{}", value)?;
+            }
+            writeln!(self.writer, ",{}", format_hash(*path_hash))?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, _run_hash: u64) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Simulate `paths` rows with `fill` and stream them into `sink`
+///
+/// `fill(path_id, row)` writes the [`PathSink::width`] values of one path
+/// and is called from the Rayon workers. Returns the run hash of the
+/// export.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` for a zero `chunk_paths`, `max_chunks_in_flight`
+/// or sink width, and the sink's first I/O error, after which no further
+/// chunks are simulated.
+pub fn stream_paths<S: PathSink>(
+    paths: usize,
+    config: &StreamConfig,
+    sink: &mut S,
+    fill: impl Fn(u64, &mut [f64]) + Sync,
+) -> io::Result<u64> {
+    let width = sink.width();
+    if config.chunk_paths == 0 || config.max_chunks_in_flight == 0 || width == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "chunk_paths, max_chunks_in_flight and the sink width must be positive",
+        ));
+    }
+    let chunk_paths = config.chunk_paths;
+    let n_chunks = (paths + chunk_paths - 1) / chunk_paths;
+    let make_chunk = |c: usize| {
+        let first = c * chunk_paths;
+        let rows = chunk_paths.min(paths - first);
+        let mut values = vec![0.0; rows * width];
+        let mut path_hashes = Vec::with_capacity(rows);
+        for (i, row) in (first as u64..).zip(values.chunks_mut(width)) {
+            fill(i, row);
+            path_hashes.push(hash_path_record(i, row));
+        }
+        PathChunk {
+            first_path_id: first as u64,
+            values,
+            path_hashes,
+        }
+    };
+
+    thread::scope(|scope| {
+        let (sender, receiver) =
+            mpsc::sync_channel::<(usize, PathChunk)>(config.max_chunks_in_flight);
+        let writer = scope.spawn(move || -> io::Result<u64> {
+            let mut hasher = RunHasher::new(paths as u64);
+            let mut pending = BTreeMap::new();
+            let mut next = 0;
+            for (index, chunk) in receiver {
+                pending.insert(index, chunk);
+                while let Some(chunk) = pending.remove(&next) {
+                    chunk.path_hashes.iter().for_each(|&h| hasher.push(h));
+                    sink.write_chunk(&chunk)?;
+                    next += 1;
+                }
+            }
+            let run_hash = hasher.finish();
+            sink.finish(run_hash)?;
+            Ok(run_hash)
+        });
+
+        // Windows of chunks keep the writer's reorder buffer bounded even
+        // when work stealing runs far ahead on one part of the range
+        for start in (0..n_chunks).step_by(config.max_chunks_in_flight) {
+            let end = (start + config.max_chunks_in_flight).min(n_chunks);
+            let sent = (start..end)
+                .into_par_iter()
+                .try_for_each_with(sender.clone(), |sender, c| sender.send((c, make_chunk(c))));
+            if sent.is_err() {
+                // The writer failed; its error is reported below
+                break;
+            }
+        }
+        drop(sender);
+        writer
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{read_paths_from_csv, write_paths_to_csv_versioned};
+
+    fn temp_file(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!(
+                "fast_sde_stream_{}_{}.csv",
+                name,
+                std::process::id()
+            ))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn record(i: u64) -> (f64, f64, f64) {
+        let s_t = 100.0 + (i as f64).sin() * 10.0;
+        (
+            s_t,
+            (s_t - 100.0).max(0.0),
+            if s_t > 100.0 { s_t / 100.0 } else { 0.0 },
+        )
+    }
+
+    #[test]
+    fn test_streamed_records_match_collected_export() {
+        let n = 1003;
+        let collected: Vec<_> = (0..n as u64).map(record).collect();
+        let (expected_file, streamed_file) = (temp_file("collected"), temp_file("streamed"));
+        let expected_hash =
+            write_paths_to_csv_versioned(&expected_file, &collected, SchemaVersion::CURRENT)
+                .unwrap();
+
+        let config = StreamConfig {
+            chunk_paths: 17,
+            max_chunks_in_flight: 3,
+        };
+        let mut sink = CsvPathSink::records(&streamed_file).unwrap();
+        let run_hash = stream_paths(n, &config, &mut sink, |i, row| {
+            let (s_t, payoff, delta) = record(i);
+            row.copy_from_slice(&[s_t, payoff, delta]);
+        })
+        .unwrap();
+        drop(sink);
+
+        let expected = std::fs::read_to_string(&expected_file).unwrap();
+        let streamed = std::fs::read_to_string(&streamed_file).unwrap();
+        let (version, records) = read_paths_from_csv(&streamed_file).unwrap();
+        std::fs::remove_file(&expected_file).unwrap();
+        std::fs::remove_file(&streamed_file).unwrap();
+
+        assert_eq!(run_hash, expected_hash);
+        assert_eq!(streamed, expected);
+        assert_eq!(version, SchemaVersion::CURRENT);
+        assert_eq!(records.len(), n);
+    }
+
+    struct FailingSink {
+        chunks: usize,
+    }
+
+    impl PathSink for FailingSink {
+        fn width(&self) -> usize {
+            1
+        }
+
+        fn write_chunk(&mut self, _chunk: &PathChunk) -> io::Result<()> {
+            self.chunks += 1;
+            Err(io::Error::new(io::ErrorKind::Other, "disk full"))
+        }
+
+        fn finish(&mut self, _run_hash: u64) -> io::Result<()> {
+            unreachable!("finish after a failed chunk")
+        }
+    }
+
+    #[test]
+    fn test_sink_error_stops_the_export() {
+        let config = StreamConfig {
+            chunk_paths: 10,
+            max_chunks_in_flight: 2,
+        };
+        let mut sink = FailingSink { chunks: 0 };
+        let err =
+            stream_paths(100_000, &config, &mut sink, |i, row| row[0] = i as f64).unwrap_err();
+        assert_eq!(err.to_string(), "disk full");
+        assert_eq!(sink.chunks, 1);
+
+        let bad = StreamConfig {
+            chunk_paths: 0,
+            ..config
+        };
+        let err = stream_paths(10, &bad, &mut sink, |_, _| {}).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
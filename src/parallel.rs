@@ -0,0 +1,370 @@
+// src/parallel.rs
+//! Optional Rayon parallelism
+//!
+//! With the `parallel` feature (on by default) [`prelude`] is Rayon's
+//! prelude. Without it, the same entry points (`into_par_iter`,
+//! `par_iter`, `par_chunks_mut`, ...) and combinators (`fold` with an
+//! identity closure, `reduce`, `try_reduce`, `with_min_len`, ...) run
+//! sequentially on the calling thread, so the engine builds for targets
+//! without threads such as `wasm32-unknown-unknown`.
+//!
+//! # Determinism
+//!
+//! Results that the engine documents as independent of the thread count
+//! (seeded per-path streams, [`McConfig::deterministic`]) are identical
+//! with and without the feature.
+//!
+//! # Thread Pools
+//!
+//! [`Parallelism`] selects where an engine call runs: Rayon's global pool,
+//! a dedicated pool of a given size, or a single worker. Dedicated pools
+//! are built on first use and shared by every call asking for the same
+//! size, so embedders running many pricers at once can bound the total
+//! thread count without paying for a pool per call.
+//!
+//! Cached pools live until the process exits. At most
+//! [`MAX_CACHED_POOLS`] sizes are cached; a call asking for another size
+//! once the cache is full builds a pool of its own and releases it when
+//! it returns.
+//!
+//! [`McConfig::deterministic`]: crate::mc::mc_engine::McConfig::deterministic
+
+#[cfg(feature = "parallel")]
+pub use rayon::prelude;
+
+/// Number of dedicated pool sizes kept alive between calls
+pub const MAX_CACHED_POOLS: usize = 8;
+
+/// Threads used by an engine call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Parallelism {
+    /// Rayon's global pool
+    #[default]
+    Auto,
+    /// A dedicated pool of `n` threads (at least 1)
+    Threads(usize),
+    /// One worker thread
+    Sequential,
+}
+
+impl Parallelism {
+    /// Run `f` on the pool selected by `self`
+    ///
+    /// Parallel iterators inside `f` use that pool; nested calls that select
+    /// the pool they already run on execute inline. Without the `parallel`
+    /// feature `f` runs on the calling thread. If a dedicated pool cannot be
+    /// built, `f` runs on the global pool.
+    pub fn install<R: Send>(self, f: impl FnOnce() -> R + Send) -> R {
+        #[cfg(feature = "parallel")]
+        {
+            let threads = match self {
+                Parallelism::Auto => return f(),
+                Parallelism::Threads(n) => n.max(1),
+                Parallelism::Sequential => 1,
+            };
+            match dedicated_pool(threads) {
+                Some(pool) => pool.install(f),
+                None => f(),
+            }
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            f()
+        }
+    }
+
+    /// Number of threads `install` runs on
+    pub fn threads(self) -> usize {
+        match self {
+            Parallelism::Auto => current_num_threads(),
+            Parallelism::Threads(n) if cfg!(feature = "parallel") => n.max(1),
+            Parallelism::Threads(_) | Parallelism::Sequential => 1,
+        }
+    }
+}
+
+/// Pool of `threads` threads: the cached one, a newly cached one while
+/// fewer than [`MAX_CACHED_POOLS`] sizes are cached, or else one owned by
+/// the caller alone
+#[cfg(feature = "parallel")]
+fn dedicated_pool(threads: usize) -> Option<std::sync::Arc<rayon::ThreadPool>> {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex, OnceLock};
+
+    static POOLS: OnceLock<Mutex<HashMap<usize, Arc<rayon::ThreadPool>>>> = OnceLock::new();
+    let mut pools = POOLS.get_or_init(Default::default).lock().ok()?;
+    if let Some(pool) = pools.get(&threads) {
+        return Some(pool.clone());
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(move |i| format!("fast-sde-{}x{}", threads, i))
+        .build()
+        .ok()?;
+    let pool = Arc::new(pool);
+    if pools.len() < MAX_CACHED_POOLS {
+        pools.insert(threads, pool.clone());
+    }
+    Some(pool)
+}
+
+/// Number of worker threads (1 without the `parallel` feature)
+#[cfg(feature = "parallel")]
+pub fn current_num_threads() -> usize {
+    rayon::current_num_threads()
+}
+
+/// Number of worker threads (1 without the `parallel` feature)
+#[cfg(not(feature = "parallel"))]
+pub fn current_num_threads() -> usize {
+    1
+}
+
+#[cfg(not(feature = "parallel"))]
+pub mod prelude {
+    //! Sequential stand-ins for the subset of Rayon's prelude used by the
+    //! crate
+
+    /// Sequential "parallel" iterator
+    ///
+    /// Adapters return `Seq` again so that Rayon-style `fold`/`reduce`
+    /// (which take identity closures) resolve to the inherent methods
+    /// rather than [`Iterator::fold`]/[`Iterator::reduce`].
+    pub struct Seq<I>(I);
+
+    impl<I: Iterator> Iterator for Seq<I> {
+        type Item = I::Item;
+
+        fn next(&mut self) -> Option<I::Item> {
+            self.0.next()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.0.size_hint()
+        }
+    }
+
+    impl<I: Iterator> Seq<I> {
+        pub fn map<B, F: FnMut(I::Item) -> B>(self, f: F) -> Seq<std::iter::Map<I, F>> {
+            Seq(self.0.map(f))
+        }
+
+        pub fn filter<P: FnMut(&I::Item) -> bool>(self, p: P) -> Seq<std::iter::Filter<I, P>> {
+            Seq(self.0.filter(p))
+        }
+
+        pub fn filter_map<B, F: FnMut(I::Item) -> Option<B>>(
+            self,
+            f: F,
+        ) -> Seq<std::iter::FilterMap<I, F>> {
+            Seq(self.0.filter_map(f))
+        }
+
+        pub fn flat_map_iter<U: IntoIterator, F: FnMut(I::Item) -> U>(
+            self,
+            f: F,
+        ) -> Seq<std::iter::FlatMap<I, U, F>> {
+            Seq(self.0.flat_map(f))
+        }
+
+        pub fn enumerate(self) -> Seq<std::iter::Enumerate<I>> {
+            Seq(self.0.enumerate())
+        }
+
+        pub fn zip<J: IntoIterator>(self, other: J) -> Seq<std::iter::Zip<I, J::IntoIter>> {
+            Seq(self.0.zip(other))
+        }
+
+        /// Rayon's `map_init`: `init` runs once, its value is reused for
+        /// every item
+        pub fn map_init<T, B>(
+            self,
+            init: impl FnOnce() -> T,
+            mut f: impl FnMut(&mut T, I::Item) -> B,
+        ) -> Seq<impl Iterator<Item = B>> {
+            let mut state = init();
+            Seq(self.0.map(move |item| f(&mut state, item)))
+        }
+
+        /// Minimum task length; meaningless sequentially
+        pub fn with_min_len(self, _min: usize) -> Self {
+            self
+        }
+
+        /// Rayon's `fold`: one accumulator for the whole (single) task
+        pub fn fold<T, ID, F>(self, identity: ID, op: F) -> Seq<std::iter::Once<T>>
+        where
+            ID: Fn() -> T,
+            F: Fn(T, I::Item) -> T,
+        {
+            Seq(std::iter::once(self.0.fold(identity(), op)))
+        }
+
+        /// Rayon's `try_for_each_with`: `op` sees the same `init` value for
+        /// every item and stops at the first error
+        pub fn try_for_each_with<T, E>(
+            mut self,
+            mut init: T,
+            op: impl Fn(&mut T, I::Item) -> Result<(), E>,
+        ) -> Result<(), E> {
+            self.0.try_for_each(|item| op(&mut init, item))
+        }
+
+        /// Rayon's `reduce`: `identity()` combined with every item
+        pub fn reduce<ID, OP>(self, identity: ID, op: OP) -> I::Item
+        where
+            ID: Fn() -> I::Item,
+            OP: Fn(I::Item, I::Item) -> I::Item,
+        {
+            self.0.fold(identity(), op)
+        }
+    }
+
+    impl<T, E, I: Iterator<Item = Result<T, E>>> Seq<I> {
+        /// Rayon's `try_reduce` over `Result` items, stopping at the first
+        /// error
+        pub fn try_reduce<ID, OP>(self, identity: ID, op: OP) -> Result<T, E>
+        where
+            ID: Fn() -> T,
+            OP: Fn(T, T) -> Result<T, E>,
+        {
+            let mut acc = identity();
+            for item in self.0 {
+                acc = op(acc, item?)?;
+            }
+            Ok(acc)
+        }
+    }
+
+    /// `into_par_iter` for anything iterable
+    pub trait IntoParallelIterator: IntoIterator + Sized {
+        fn into_par_iter(self) -> Seq<Self::IntoIter> {
+            Seq(self.into_iter())
+        }
+    }
+
+    impl<T: IntoIterator> IntoParallelIterator for T {}
+
+    /// `par_iter` for anything iterable by reference
+    pub trait IntoParallelRefIterator<'a> {
+        type Iter: Iterator;
+
+        fn par_iter(&'a self) -> Seq<Self::Iter>;
+    }
+
+    impl<'a, C: 'a + ?Sized> IntoParallelRefIterator<'a> for C
+    where
+        &'a C: IntoIterator,
+    {
+        type Iter = <&'a C as IntoIterator>::IntoIter;
+
+        fn par_iter(&'a self) -> Seq<Self::Iter> {
+            Seq(self.into_iter())
+        }
+    }
+
+    /// `par_iter_mut` for anything iterable by mutable reference
+    pub trait IntoParallelRefMutIterator<'a> {
+        type Iter: Iterator;
+
+        fn par_iter_mut(&'a mut self) -> Seq<Self::Iter>;
+    }
+
+    impl<'a, C: 'a + ?Sized> IntoParallelRefMutIterator<'a> for C
+    where
+        &'a mut C: IntoIterator,
+    {
+        type Iter = <&'a mut C as IntoIterator>::IntoIter;
+
+        fn par_iter_mut(&'a mut self) -> Seq<Self::Iter> {
+            Seq(self.into_iter())
+        }
+    }
+
+    /// `par_chunks` on slices
+    pub trait ParallelSlice<T> {
+        fn par_chunks(&self, size: usize) -> Seq<std::slice::Chunks<'_, T>>;
+    }
+
+    impl<T> ParallelSlice<T> for [T] {
+        fn par_chunks(&self, size: usize) -> Seq<std::slice::Chunks<'_, T>> {
+            Seq(self.chunks(size))
+        }
+    }
+
+    /// `par_chunks_mut` on slices
+    pub trait ParallelSliceMut<T> {
+        fn par_chunks_mut(&mut self, size: usize) -> Seq<std::slice::ChunksMut<'_, T>>;
+    }
+
+    impl<T> ParallelSliceMut<T> for [T] {
+        fn par_chunks_mut(&mut self, size: usize) -> Seq<std::slice::ChunksMut<'_, T>> {
+            Seq(self.chunks_mut(size))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prelude::*;
+
+    #[test]
+    fn test_fold_reduce_contract() {
+        let sum = (0..1000usize)
+            .into_par_iter()
+            .with_min_len(16)
+            .fold(|| 0, |acc, x| acc + x)
+            .reduce(|| 0, |a, b| a + b);
+        assert_eq!(sum, 999 * 1000 / 2);
+
+        let values = vec![1.0, 2.0, 3.0];
+        let squares: Vec<f64> = values.par_iter().map(|x| x * x).collect();
+        assert_eq!(squares, [1.0, 4.0, 9.0]);
+
+        let failed = (0..10)
+            .into_par_iter()
+            .map(|i| if i == 7 { Err(i) } else { Ok(i) })
+            .try_reduce(|| 0, |a, b| Ok(a + b));
+        assert_eq!(failed, Err(7));
+        assert!(super::current_num_threads() >= 1);
+    }
+
+    #[test]
+    fn test_parallelism_selects_pool_without_changing_results() {
+        use super::Parallelism;
+        use crate::mc::mc_engine::{mc_price_option_gbm, McConfig};
+
+        assert_eq!(
+            Parallelism::Sequential.install(super::current_num_threads),
+            1
+        );
+        // Sizes past the cache bound get a pool for the call alone
+        #[cfg(feature = "parallel")]
+        for n in 1..=super::MAX_CACHED_POOLS + 2 {
+            assert_eq!(
+                Parallelism::Threads(n).install(super::current_num_threads),
+                n
+            );
+        }
+
+        let cfg = McConfig {
+            paths: 20_000,
+            deterministic: true,
+            ..Default::default()
+        };
+        let auto = mc_price_option_gbm(&cfg).unwrap();
+        for parallelism in [Parallelism::Threads(2), Parallelism::Sequential] {
+            let cfg = McConfig {
+                parallelism,
+                ..cfg.clone()
+            };
+            assert_eq!(mc_price_option_gbm(&cfg).unwrap(), auto);
+        }
+        let invalid = McConfig {
+            parallelism: Parallelism::Threads(0),
+            ..cfg
+        };
+        assert!(mc_price_option_gbm(&invalid).is_err());
+    }
+}
@@ -0,0 +1 @@
+pub mod crank_nicolson;
@@ -0,0 +1,675 @@
+// src/python.rs
+//! Python bindings (PyO3)
+//!
+//! Built as the `fast_sde` extension module with maturin (`pip install .`
+//! or `maturin develop`, see `pyproject.toml`).
+//!
+//! # Python API
+//!
+//! ```text
+//! import fast_sde, numpy as np
+//!
+//! cfg = fast_sde.McConfig(paths=200_000, sigma=0.25,
+//!                         payoff={"EuropeanCall": {"k": 100.0}})
+//! price, std_error = fast_sde.mc_price_option_gbm(cfg)
+//! greeks = fast_sde.mc_greeks_gbm(cfg, ["delta", "vega"])    # dict
+//! chain = fast_sde.mc_price_chain(cfg, np.linspace(80, 120, 9),
+//!                                 np.array([0.25, 0.5, 1.0]))  # dict of 2-D arrays
+//! paths = fast_sde.gbm_paths(cfg)                              # (paths, steps + 1)
+//!
+//! heston = fast_sde.Heston(100.0, 0.04, 0.02, 1.5, 0.04, 0.5, -0.7)
+//! spot, variance = heston.paths(10_000, 100, 1.0)
+//! grid = heston.price_grid(strikes, maturities)                 # analytic calls
+//! fit = fast_sde.calibrate_heston(heston, strikes, maturities, prices, calls)
+//! ```
+//! Payoffs are given as dicts (or JSON strings) in the serialized form of
+//! [`Payoff`]. Library errors are raised as `fast_sde.SdeError`, a
+//! `ValueError` subclass. Simulations release the GIL.
+
+use crate::analytics::heston_analytic::{heston_call_price, heston_put_price};
+use crate::analytics::sabr_analytic::hagan_implied_vol;
+use crate::calibration::heston::HestonCalibrator;
+use crate::calibration::quotes::{OptionQuote, OptionType, VolQuote};
+use crate::calibration::sabr::SabrCalibrator;
+use crate::error::{validation::*, SdeError};
+use crate::mc::chain::mc_price_chain;
+use crate::mc::greeks_plan::mc_greeks_gbm;
+use crate::mc::heston_greeks::{HestonGreeks, HestonGreeksConfig};
+use crate::mc::mc_engine::{mc_price_option_gbm, GreeksConfig, McConfig};
+use crate::mc::payoffs::Payoff;
+use crate::models::gbm::Gbm;
+use crate::models::heston::{FellerPolicy, Heston, HestonParams, HestonScheme};
+use crate::models::sabr::{Sabr, SabrParams};
+use crate::parallel::prelude::*;
+use crate::rng::{self, SeedStrategy};
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyReadonlyArray1};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::de::DeserializeOwned;
+
+pyo3::create_exception!(fast_sde, SdeException, PyValueError);
+
+/// Two `(paths, steps + 1)` arrays: the asset and its volatility factor
+type PathPair<'py> = (Bound<'py, PyArray2<f64>>, Bound<'py, PyArray2<f64>>);
+
+impl From<SdeError> for PyErr {
+    fn from(err: SdeError) -> Self {
+        SdeException::new_err(err.to_string())
+    }
+}
+
+/// Python object as JSON, via the `json` module
+fn py_to_json(obj: &Bound<'_, PyAny>) -> PyResult<serde_json::Value> {
+    let text: String = PyModule::import(obj.py(), "json")?
+        .call_method1("dumps", (obj,))?
+        .extract()?;
+    serde_json::from_str(&text).map_err(|e| SdeException::new_err(e.to_string()))
+}
+
+fn json_to_py<'py>(py: Python<'py>, value: &serde_json::Value) -> PyResult<Bound<'py, PyAny>> {
+    PyModule::import(py, "json")?.call_method1("loads", (value.to_string(),))
+}
+
+fn from_json<T: DeserializeOwned>(what: &str, value: serde_json::Value) -> PyResult<T> {
+    serde_json::from_value(value).map_err(|e| SdeException::new_err(format!("{}: {}", what, e)))
+}
+
+/// Payoff from a dict in the serialized form of [`Payoff`], or a JSON string
+fn payoff_from_py(payoff: &Bound<'_, PyAny>) -> PyResult<Payoff> {
+    match payoff.extract::<String>() {
+        Ok(text) => {
+            serde_json::from_str(&text).map_err(|e| SdeException::new_err(format!("payoff: {}", e)))
+        }
+        Err(_) => from_json("payoff", py_to_json(payoff)?),
+    }
+}
+
+fn greek_from_name(name: &str) -> PyResult<GreeksConfig> {
+    match name {
+        "delta" => Ok(GreeksConfig::DELTA),
+        "gamma" => Ok(GreeksConfig::GAMMA),
+        "vega" => Ok(GreeksConfig::VEGA),
+        "rho" => Ok(GreeksConfig::RHO),
+        _ => Err(SdeException::new_err(format!("unknown Greek '{}'", name))),
+    }
+}
+
+fn scheme_from_name(name: &str) -> PyResult<HestonScheme> {
+    match name {
+        "euler" => Ok(HestonScheme::FullTruncationEuler),
+        "qe" => Ok(HestonScheme::AndersenQE),
+        "qe-m" => Ok(HestonScheme::AndersenQEM),
+        "alfonsi" => Ok(HestonScheme::Alfonsi),
+        _ => Err(SdeException::new_err(format!(
+            "unknown Heston scheme '{}' (expected euler, qe, qe-m or alfonsi)",
+            name
+        ))),
+    }
+}
+
+fn seed_strategy_from_name(name: &str) -> PyResult<SeedStrategy> {
+    match name {
+        "hashed" => Ok(SeedStrategy::Hashed),
+        "sequential" => Ok(SeedStrategy::Sequential),
+        _ => Err(SdeException::new_err(format!(
+            "unknown seed strategy '{}' (expected hashed or sequential)",
+            name
+        ))),
+    }
+}
+
+/// Monte Carlo configuration of the GBM engine
+///
+/// Keyword arguments are the serialized fields of [`McConfig`] (`paths`,
+/// `use_antithetic`, `payoff`, `greeks = "DELTA | VEGA"`, ...); omitted
+/// fields keep their defaults.
+#[pyclass(name = "McConfig", module = "fast_sde")]
+#[derive(Clone)]
+pub struct PyMcConfig {
+    pub config: McConfig,
+}
+
+#[pymethods]
+impl PyMcConfig {
+    #[new]
+    #[pyo3(signature = (**kwargs))]
+    fn new(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        PyMcConfig {
+            config: McConfig::default(),
+        }
+        .replace(kwargs)
+    }
+
+    /// Copy with the given fields replaced
+    #[pyo3(signature = (**kwargs))]
+    fn replace(&self, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let mut value =
+            serde_json::to_value(&self.config).map_err(|e| SdeException::new_err(e.to_string()))?;
+        if let (Some(kwargs), Some(fields)) = (kwargs, value.as_object_mut()) {
+            if let serde_json::Value::Object(updates) = py_to_json(kwargs.as_any())? {
+                for (field, update) in updates {
+                    if !fields.contains_key(&field) {
+                        return Err(SdeException::new_err(format!(
+                            "McConfig has no field '{}'",
+                            field
+                        )));
+                    }
+                    fields.insert(field, update);
+                }
+            }
+        }
+        Ok(PyMcConfig {
+            config: from_json("McConfig", value)?,
+        })
+    }
+
+    /// Fields as a dict
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let value =
+            serde_json::to_value(&self.config).map_err(|e| SdeException::new_err(e.to_string()))?;
+        json_to_py(py, &value)
+    }
+}
+
+/// `(price, std_error)` of `cfg.payoff` under GBM
+#[pyfunction(name = "mc_price_option_gbm")]
+fn py_mc_price_option_gbm(py: Python<'_>, cfg: &PyMcConfig) -> PyResult<(f64, f64)> {
+    let cfg = cfg.config.clone();
+    let (price, variance) = py.detach(|| mc_price_option_gbm(&cfg))?;
+    Ok((price, variance.sqrt()))
+}
+
+/// Requested Greeks (`"delta"`, `"gamma"`, `"vega"`, `"rho"`) as a dict
+#[pyfunction(name = "mc_greeks_gbm")]
+fn py_mc_greeks_gbm<'py>(
+    py: Python<'py>,
+    cfg: &PyMcConfig,
+    greeks: Vec<String>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let mut config = cfg.config.clone();
+    for name in &greeks {
+        config.greeks |= greek_from_name(name)?;
+    }
+    let report = py.detach(|| mc_greeks_gbm(&config))?;
+    let dict = PyDict::new(py);
+    let values = [
+        ("delta", report.delta),
+        ("gamma", report.gamma),
+        ("vega", report.vega),
+        ("rho", report.rho),
+    ];
+    for (name, value) in values {
+        if let Some(value) = value {
+            dict.set_item(name, value)?;
+        }
+    }
+    Ok(dict)
+}
+
+/// Strike × maturity grid as a dict of `(maturities, strikes)` arrays
+/// (`prices`, `std_errors`, `deltas`, `vegas`)
+#[pyfunction(name = "mc_price_chain")]
+fn py_mc_price_chain<'py>(
+    py: Python<'py>,
+    cfg: &PyMcConfig,
+    strikes: PyReadonlyArray1<'py, f64>,
+    maturities: PyReadonlyArray1<'py, f64>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let cfg = cfg.config.clone();
+    let strikes = strikes.as_array().to_vec();
+    let maturities = maturities.as_array().to_vec();
+    let chain = py.detach(|| mc_price_chain(&cfg, &strikes, &maturities))?;
+    let dict = PyDict::new(py);
+    dict.set_item("prices", chain.prices.into_pyarray(py))?;
+    dict.set_item("std_errors", chain.std_errors.into_pyarray(py))?;
+    dict.set_item("deltas", chain.deltas.into_pyarray(py))?;
+    dict.set_item("vegas", chain.vegas.into_pyarray(py))?;
+    Ok(dict)
+}
+
+/// Exact GBM paths of `cfg` as a `(paths, steps + 1)` array starting at `s0`
+///
+/// Path `i` draws from its `seed_strategy` stream; antithetic pairing is not applied.
+#[pyfunction(name = "gbm_paths")]
+fn py_gbm_paths<'py>(py: Python<'py>, cfg: &PyMcConfig) -> PyResult<Bound<'py, PyArray2<f64>>> {
+    let cfg = cfg.config.clone();
+    cfg.validate()?;
+    let paths = py.detach(|| {
+        cfg.parallelism.install(|| {
+            let model = Gbm::new(cfg.s0, cfg.r, cfg.sigma);
+            let dt = cfg.t / cfg.steps as f64;
+            let mut paths = Array2::zeros((cfg.paths, cfg.steps + 1));
+            paths
+                .axis_iter_mut(Axis(0))
+                .into_par_iter()
+                .enumerate()
+                .for_each(|(i, mut path)| {
+                    let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+                    let mut s = cfg.s0;
+                    path[0] = s;
+                    for j in 1..=cfg.steps {
+                        s = model.exact_step(s, dt, rng::get_normal_draw(&mut rng));
+                        path[j] = s;
+                    }
+                });
+            paths
+        })
+    });
+    Ok(paths.into_pyarray(py))
+}
+
+/// Heston model: Monte Carlo paths and Greeks, semi-analytic prices
+#[pyclass(name = "Heston", module = "fast_sde")]
+#[derive(Clone)]
+pub struct PyHeston {
+    pub params: HestonParams,
+    pub scheme: HestonScheme,
+    /// Derivation of the per-row streams of `paths` from its seed
+    pub seed_strategy: SeedStrategy,
+}
+
+#[pymethods]
+impl PyHeston {
+    /// Model with the full truncation Euler scheme
+    #[new]
+    fn new(s0: f64, v0: f64, r: f64, kappa: f64, theta: f64, xi: f64, rho: f64) -> PyResult<Self> {
+        let params = HestonParams {
+            s0,
+            v0,
+            r,
+            kappa,
+            theta,
+            xi,
+            rho,
+        };
+        Heston::new_with_policy(
+            params,
+            HestonScheme::FullTruncationEuler,
+            FellerPolicy::Ignore,
+        )?;
+        Ok(PyHeston {
+            params,
+            scheme: HestonScheme::FullTruncationEuler,
+            seed_strategy: SeedStrategy::Hashed,
+        })
+    }
+
+    /// Copy simulating with `scheme`: `"euler"` (full truncation), `"qe"`,
+    /// `"qe-m"` (martingale-corrected QE) or `"alfonsi"`
+    fn with_scheme(&self, scheme: &str) -> PyResult<Self> {
+        Ok(PyHeston {
+            scheme: scheme_from_name(scheme)?,
+            ..self.clone()
+        })
+    }
+
+    /// Copy drawing the rows of `paths` from `"hashed"` (default) or
+    /// `"sequential"` seed streams
+    fn with_seed_strategy(&self, seed_strategy: &str) -> PyResult<Self> {
+        Ok(PyHeston {
+            seed_strategy: seed_strategy_from_name(seed_strategy)?,
+            ..self.clone()
+        })
+    }
+
+    /// Parameters as a dict
+    #[getter]
+    fn params<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let p = &self.params;
+        let dict = PyDict::new(py);
+        for (name, value) in [
+            ("s0", p.s0),
+            ("v0", p.v0),
+            ("r", p.r),
+            ("kappa", p.kappa),
+            ("theta", p.theta),
+            ("xi", p.xi),
+            ("rho", p.rho),
+        ] {
+            dict.set_item(name, value)?;
+        }
+        Ok(dict)
+    }
+
+    /// `(spot, variance)` arrays of shape `(paths, steps + 1)`
+    ///
+    /// With `antithetic`, rows `2i` and `2i + 1` are an antithetic pair.
+    /// Row (pair) `i` draws from stream `i` of `seed` under the model's
+    /// seed strategy.
+    #[pyo3(signature = (paths, steps, t, seed = 12345, antithetic = false))]
+    fn paths<'py>(
+        &self,
+        py: Python<'py>,
+        paths: usize,
+        steps: usize,
+        t: f64,
+        seed: u64,
+        antithetic: bool,
+    ) -> PyResult<PathPair<'py>> {
+        validate_paths(paths)?;
+        validate_steps(steps)?;
+        validate_positive("t", t)?;
+        let model = Heston::new_with_policy(self.params, self.scheme, FellerPolicy::Ignore)?;
+        let rows = if antithetic { 2 } else { 1 };
+        let (spot, variance) = py.detach(|| -> PyResult<_> {
+            let dt = t / steps as f64;
+            let mut spot = Array2::zeros((paths, steps + 1));
+            let mut variance = Array2::zeros((paths, steps + 1));
+            spot.axis_chunks_iter_mut(Axis(0), rows)
+                .into_par_iter()
+                .zip(variance.axis_chunks_iter_mut(Axis(0), rows))
+                .enumerate()
+                .try_for_each(|(i, (mut s_rows, mut v_rows))| {
+                    let mut rng = self.seed_strategy.path_rng(seed, i as u64);
+                    let (mut s, mut v) = ([self.params.s0; 2], [self.params.v0; 2]);
+                    let n = s_rows.nrows();
+                    for j in 0..=steps {
+                        if j > 0 {
+                            if antithetic {
+                                model.step_antithetic(&mut s, &mut v, dt, &mut rng)
+                            } else {
+                                model.step(&mut s[0], &mut v[0], dt, &mut rng)
+                            }
+                            .map_err(|e| e.at_step(j - 1).at_path(i * rows))?;
+                        }
+                        s_rows.column_mut(j).assign(&ArrayView1::from(&s[..n]));
+                        v_rows.column_mut(j).assign(&ArrayView1::from(&v[..n]));
+                    }
+                    Ok::<_, SdeError>(())
+                })?;
+            Ok((spot, variance))
+        })?;
+        Ok((spot.into_pyarray(py), variance.into_pyarray(py)))
+    }
+
+    /// Semi-analytic European call price
+    fn call_price(&self, k: f64, t: f64) -> PyResult<f64> {
+        Ok(heston_call_price(&self.params, k, t)?)
+    }
+
+    /// Semi-analytic European put price
+    fn put_price(&self, k: f64, t: f64) -> PyResult<f64> {
+        Ok(heston_put_price(&self.params, k, t)?)
+    }
+
+    /// Semi-analytic call (or put) prices on a `(maturities, strikes)` grid
+    #[pyo3(signature = (strikes, maturities, call = true))]
+    fn price_grid<'py>(
+        &self,
+        py: Python<'py>,
+        strikes: PyReadonlyArray1<'py, f64>,
+        maturities: PyReadonlyArray1<'py, f64>,
+        call: bool,
+    ) -> PyResult<Bound<'py, PyArray2<f64>>> {
+        let strikes = strikes.as_array();
+        let maturities = maturities.as_array();
+        let pricer = if call {
+            heston_call_price
+        } else {
+            heston_put_price
+        };
+        let mut grid = Array2::zeros((maturities.len(), strikes.len()));
+        for ((j, m), price) in grid.indexed_iter_mut() {
+            *price = pricer(&self.params, strikes[m], maturities[j])?;
+        }
+        Ok(grid.into_pyarray(py))
+    }
+
+    /// Monte Carlo price and bump-and-revalue sensitivities as a dict
+    ///
+    /// Draws are antithetic.
+    #[pyo3(signature = (payoff, t, paths = 100_000, steps = 100, seed = 12345))]
+    fn greeks<'py>(
+        &self,
+        payoff: &Bound<'py, PyAny>,
+        t: f64,
+        paths: usize,
+        steps: usize,
+        seed: u64,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let py = payoff.py();
+        let cfg = HestonGreeksConfig {
+            paths,
+            steps,
+            t,
+            seed,
+            payoff: payoff_from_py(payoff)?,
+            ..Default::default()
+        };
+        let greeks = HestonGreeks::new(self.params, self.scheme)?;
+        let report = py.detach(|| greeks.compute(&cfg))?;
+        let dict = PyDict::new(py);
+        for (name, value) in [
+            ("price", report.price),
+            ("delta", report.delta),
+            ("gamma", report.gamma),
+            ("vega_v0", report.vega_v0),
+            ("dv_dkappa", report.dv_dkappa),
+            ("dv_dtheta", report.dv_dtheta),
+            ("dv_dxi", report.dv_dxi),
+            ("dv_drho", report.dv_drho),
+        ] {
+            dict.set_item(name, value)?;
+        }
+        Ok(dict)
+    }
+}
+
+/// SABR model (`β = 1` lognormal by default; `β < 1` absorbs the forward
+/// at zero): Monte Carlo paths and Hagan smile
+#[pyclass(name = "Sabr", module = "fast_sde")]
+#[derive(Clone)]
+pub struct PySabr {
+    pub params: SabrParams,
+    /// Derivation of the per-row streams of `paths` from its seed
+    pub seed_strategy: SeedStrategy,
+}
+
+#[pymethods]
+impl PySabr {
+    #[new]
+    #[pyo3(signature = (f0, alpha, rho, nu, v0 = 1.0, beta = 1.0))]
+    fn new(f0: f64, alpha: f64, rho: f64, nu: f64, v0: f64, beta: f64) -> PyResult<Self> {
+        let params = SabrParams {
+            f0,
+            alpha,
+            beta,
+            rho,
+            nu,
+            v0,
+        };
+        Sabr::new(params)?;
+        Ok(PySabr {
+            params,
+            seed_strategy: SeedStrategy::Hashed,
+        })
+    }
+
+    /// Copy drawing the rows of `paths` from `"hashed"` (default) or
+    /// `"sequential"` seed streams
+    fn with_seed_strategy(&self, seed_strategy: &str) -> PyResult<Self> {
+        Ok(PySabr {
+            seed_strategy: seed_strategy_from_name(seed_strategy)?,
+            ..self.clone()
+        })
+    }
+
+    /// `(forward, volatility)` arrays of shape `(paths, steps + 1)`
+    ///
+    /// With `antithetic`, rows `2i` and `2i + 1` are an antithetic pair.
+    /// Row (pair) `i` draws from stream `i` of `seed` under the model's
+    /// seed strategy.
+    #[pyo3(signature = (paths, steps, t, seed = 12345, antithetic = false))]
+    fn paths<'py>(
+        &self,
+        py: Python<'py>,
+        paths: usize,
+        steps: usize,
+        t: f64,
+        seed: u64,
+        antithetic: bool,
+    ) -> PyResult<PathPair<'py>> {
+        validate_paths(paths)?;
+        validate_steps(steps)?;
+        validate_positive("t", t)?;
+        let model = Sabr::new(self.params)?;
+        let rows = if antithetic { 2 } else { 1 };
+        let (forward, vol) = py.detach(|| {
+            let dt = t / steps as f64;
+            let mut forward = Array2::zeros((paths, steps + 1));
+            let mut vol = Array2::zeros((paths, steps + 1));
+            forward
+                .axis_chunks_iter_mut(Axis(0), rows)
+                .into_par_iter()
+                .zip(vol.axis_chunks_iter_mut(Axis(0), rows))
+                .enumerate()
+                .for_each(|(i, (mut f_rows, mut v_rows))| {
+                    let mut rng = self.seed_strategy.path_rng(seed, i as u64);
+                    let (mut f, mut v) = ([self.params.f0; 2], [self.params.v0; 2]);
+                    let n = f_rows.nrows();
+                    for j in 0..=steps {
+                        if j > 0 {
+                            if antithetic {
+                                model.step_antithetic(&mut f, &mut v, dt, &mut rng);
+                            } else {
+                                model.step(&mut f[0], &mut v[0], dt, &mut rng);
+                            }
+                        }
+                        f_rows.column_mut(j).assign(&ArrayView1::from(&f[..n]));
+                        v_rows.column_mut(j).assign(&ArrayView1::from(&v[..n]));
+                    }
+                });
+            (forward, vol)
+        });
+        Ok((forward.into_pyarray(py), vol.into_pyarray(py)))
+    }
+
+    /// Hagan implied volatilities at `strikes` for expiry `t`
+    fn implied_vols<'py>(
+        &self,
+        py: Python<'py>,
+        strikes: PyReadonlyArray1<'py, f64>,
+        t: f64,
+    ) -> Bound<'py, PyArray1<f64>> {
+        let p = &self.params;
+        strikes
+            .as_array()
+            .mapv(|k| hagan_implied_vol(p.f0, k, t, p.alpha * p.v0, p.beta, p.rho, p.nu))
+            .into_pyarray(py)
+    }
+}
+
+/// Fit Heston parameters to European option prices
+///
+/// `initial` supplies the market spot and rate (kept fixed) and the
+/// starting point; `calls[i]` selects a call (or put) quote. Returns a dict
+/// with the fitted `model` (a `Heston` with the scheme and seed strategy of
+/// `initial`), `rmse`, `iterations`, `converged` and the per-quote
+/// `model_prices`.
+#[pyfunction]
+fn calibrate_heston<'py>(
+    py: Python<'py>,
+    initial: &PyHeston,
+    strikes: PyReadonlyArray1<'py, f64>,
+    maturities: PyReadonlyArray1<'py, f64>,
+    prices: PyReadonlyArray1<'py, f64>,
+    calls: Vec<bool>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let (strikes, maturities, prices) =
+        (strikes.as_array(), maturities.as_array(), prices.as_array());
+    let n = strikes.len();
+    if maturities.len() != n || prices.len() != n || calls.len() != n {
+        return Err(SdeException::new_err(
+            "strikes, maturities, prices and calls must have the same length",
+        ));
+    }
+    let quotes = (0..n)
+        .map(|i| OptionQuote {
+            strike: strikes[i],
+            time_to_expiry: maturities[i],
+            market_price: prices[i],
+            option_type: if calls[i] {
+                OptionType::Call
+            } else {
+                OptionType::Put
+            },
+        })
+        .collect();
+    let calibration = py.detach(|| HestonCalibrator::new(quotes).calibrate(&initial.params))?;
+    let model = PyHeston {
+        params: calibration.params,
+        ..initial.clone()
+    };
+    let model_prices: Array1<f64> = calibration.fits.iter().map(|f| f.model_price).collect();
+    let dict = PyDict::new(py);
+    dict.set_item("model", Bound::new(py, model)?)?;
+    dict.set_item("rmse", calibration.rmse)?;
+    dict.set_item("iterations", calibration.iterations)?;
+    dict.set_item("converged", calibration.converged)?;
+    dict.set_item("model_prices", model_prices.into_pyarray(py))?;
+    Ok(dict)
+}
+
+/// Fit a SABR smile with fixed `beta` to the implied vols of one expiry
+///
+/// Returns a dict with `alpha`, `beta`, `rho`, `nu`, `rmse`, `iterations`,
+/// `converged` and the per-quote `model_vols`.
+#[pyfunction]
+#[pyo3(signature = (forward, t, strikes, vols, beta = 1.0))]
+fn calibrate_sabr<'py>(
+    py: Python<'py>,
+    forward: f64,
+    t: f64,
+    strikes: PyReadonlyArray1<'py, f64>,
+    vols: PyReadonlyArray1<'py, f64>,
+    beta: f64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let (strikes, vols) = (strikes.as_array(), vols.as_array());
+    if strikes.len() != vols.len() {
+        return Err(SdeException::new_err(
+            "strikes and vols must have the same length",
+        ));
+    }
+    let quotes: Vec<VolQuote> = strikes
+        .iter()
+        .zip(vols.iter())
+        .map(|(&strike, &implied_vol)| VolQuote {
+            strike,
+            time_to_expiry: t,
+            implied_vol,
+        })
+        .collect();
+    let smile = py.detach(|| SabrCalibrator::new(beta).calibrate_smile(forward, &quotes))?;
+    let model_vols: Array1<f64> = smile.fits.iter().map(|f| f.model_vol).collect();
+    let dict = PyDict::new(py);
+    for (name, value) in [
+        ("alpha", smile.params.alpha),
+        ("beta", smile.params.beta),
+        ("rho", smile.params.rho),
+        ("nu", smile.params.nu),
+        ("rmse", smile.rmse),
+    ] {
+        dict.set_item(name, value)?;
+    }
+    dict.set_item("iterations", smile.iterations)?;
+    dict.set_item("converged", smile.converged)?;
+    dict.set_item("model_vols", model_vols.into_pyarray(py))?;
+    Ok(dict)
+}
+
+#[pymodule]
+fn fast_sde(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("SdeError", m.py().get_type::<SdeException>())?;
+    m.add_class::<PyMcConfig>()?;
+    m.add_class::<PyHeston>()?;
+    m.add_class::<PySabr>()?;
+    m.add_function(wrap_pyfunction!(py_mc_price_option_gbm, m)?)?;
+    m.add_function(wrap_pyfunction!(py_mc_greeks_gbm, m)?)?;
+    m.add_function(wrap_pyfunction!(py_mc_price_chain, m)?)?;
+    m.add_function(wrap_pyfunction!(py_gbm_paths, m)?)?;
+    m.add_function(wrap_pyfunction!(calibrate_heston, m)?)?;
+    m.add_function(wrap_pyfunction!(calibrate_sabr, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,544 @@
+// src/risk/exposure.rs
+//! Counterparty Exposure Profiles and CVA
+//!
+//! # Exposure Simulation
+//!
+//! The underlying is simulated exactly under the risk-neutral measure on the
+//! schedule of exposure dates `t_1 < ... < t_m`. At each date the netting
+//! set is revalued in closed form with its remaining maturities:
+//! ```text
+//! V(t_k) = Σ_j q_j V_j(S(t_k), T_j - t_k)
+//! E(t_k) = max(V(t_k), 0)
+//! ```
+//! A position pays off at `t_k = T_j` and drops out of the netting set
+//! afterwards.
+//!
+//! # Profiles
+//!
+//! ```text
+//! EE(t_k)  = E[E(t_k)]
+//! DEE(t_k) = E[e^(-r t_k) E(t_k)]
+//! PFE_α(t_k) = α-quantile of E(t_k)
+//! ```
+//!
+//! # CVA
+//!
+//! With a piecewise-constant hazard curve, survival
+//! `Q(t) = exp(-∫₀ᵗ λ(u) du)` and recovery `R`:
+//! ```text
+//! CVA = (1 - R) Σ_k DEE(t_k) [Q(t_{k-1}) - Q(t_k)],   t_0 = 0
+//! ```
+//!
+//! # Scope
+//!
+//! [`mc_exposure_profile`] revalues analytically, so only European calls
+//! and puts and digital calls and puts are supported; other payoffs return
+//! `SdeError::UnsupportedOperation`. [`mc_exposure_profile_proxy`] values
+//! any netting set with least-squares regression proxies of the remaining
+//! cashflows on the spot, at the cost of a regression error.
+
+use crate::analytics::bs_analytic;
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::mc::currency::Currency;
+use crate::mc::mc_engine::McConfig;
+use crate::mc::path_generator::{brownian_path, gbm_path, grid_index};
+use crate::mc::payoffs::Payoff;
+use crate::mc::portfolio::{observation_grid, Portfolio};
+use crate::mc::regression_proxy::RegressionProxy;
+use crate::parallel::prelude::*;
+use crate::rng;
+
+/// Piecewise-constant default intensity
+///
+/// `rates[i]` applies on `(times[i - 1], times[i]]` with `times[-1] = 0`;
+/// the last rate extends beyond the last time.
+#[derive(Debug, Clone)]
+pub struct HazardCurve {
+    pub times: Vec<f64>,
+    pub rates: Vec<f64>,
+}
+
+impl HazardCurve {
+    /// Constant hazard rate
+    pub fn flat(rate: f64) -> Self {
+        HazardCurve {
+            times: vec![1.0],
+            rates: vec![rate],
+        }
+    }
+
+    /// Validate pillar times and rates
+    pub fn validate(&self) -> SdeResult<()> {
+        if self.times.is_empty() || self.times.len() != self.rates.len() {
+            return Err(SdeError::InvalidConfiguration {
+                field: "hazard_curve".to_string(),
+                reason: "needs one rate per pillar time and at least one pillar".to_string(),
+            });
+        }
+        let mut prev = 0.0;
+        for (&t, &rate) in self.times.iter().zip(&self.rates) {
+            validate_finite("hazard_time", t)?;
+            if t <= prev {
+                return Err(SdeError::InvalidParameters {
+                    parameter: "hazard_time".to_string(),
+                    value: t,
+                    constraint: "pillar times must be positive and strictly increasing".to_string(),
+                });
+            }
+            validate_finite("hazard_rate", rate)?;
+            validate_non_negative("hazard_rate", rate)?;
+            prev = t;
+        }
+        Ok(())
+    }
+
+    /// Survival probability `Q(t)`
+    pub fn survival(&self, t: f64) -> f64 {
+        let mut integral = 0.0;
+        let mut prev = 0.0;
+        for (i, (&pillar, &rate)) in self.times.iter().zip(&self.rates).enumerate() {
+            let end = if i + 1 == self.times.len() {
+                t
+            } else {
+                pillar.min(t)
+            };
+            if end > prev {
+                integral += rate * (end - prev);
+            }
+            prev = pillar;
+            if pillar >= t {
+                break;
+            }
+        }
+        (-integral).exp()
+    }
+
+    /// Time `t` with `Q(t) = q`, or infinity if the survival probability
+    /// never falls to `q`
+    ///
+    /// With `q` uniform on (0, 1) this samples a default time.
+    pub fn inverse_survival(&self, q: f64) -> f64 {
+        let mut remaining = -q.ln();
+        let mut prev = 0.0;
+        for (i, (&pillar, &rate)) in self.times.iter().zip(&self.rates).enumerate() {
+            let last = i + 1 == self.times.len();
+            let length = if last { f64::INFINITY } else { pillar - prev };
+            if rate > 0.0 && rate * length >= remaining {
+                return prev + remaining / rate;
+            }
+            remaining -= rate * length;
+            prev = pillar;
+        }
+        f64::INFINITY
+    }
+}
+
+/// Exposure profiles on the exposure date schedule
+#[derive(Debug, Clone)]
+pub struct ExposureProfile {
+    /// Currency of the netting set, in which exposures are reported
+    pub currency: Currency,
+    pub dates: Vec<f64>,
+    /// Expected exposure `EE(t_k)`
+    pub expected_exposure: Vec<f64>,
+    /// Discounted expected exposure `DEE(t_k)`
+    pub discounted_expected_exposure: Vec<f64>,
+    /// Potential future exposure at `pfe_confidence`
+    pub pfe: Vec<f64>,
+    pub pfe_confidence: f64,
+}
+
+impl ExposureProfile {
+    /// Unilateral CVA against a counterparty with `hazard` and `recovery`
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError` for an invalid hazard curve or a recovery rate
+    /// outside [0, 1].
+    pub fn cva(&self, hazard: &HazardCurve, recovery: f64) -> SdeResult<f64> {
+        hazard.validate()?;
+        validate_range("recovery", recovery, 0.0, 1.0)?;
+        let mut prev_survival = 1.0;
+        let mut cva = 0.0;
+        for (&t, &dee) in self.dates.iter().zip(&self.discounted_expected_exposure) {
+            let survival = hazard.survival(t);
+            cva += dee * (prev_survival - survival);
+            prev_survival = survival;
+        }
+        Ok((1.0 - recovery) * cva)
+    }
+}
+
+/// Simulate EE, discounted EE and PFE profiles of a netting set
+///
+/// Uses `cfg.s0`, `r`, `sigma`, `paths`, `seed` and `use_antithetic`;
+/// `cfg.payoff` and `cfg.t` are ignored.
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid configurations, empty or unordered dates,
+/// or positions that cannot be revalued analytically.
+pub fn mc_exposure_profile(
+    cfg: &McConfig,
+    portfolio: &Portfolio,
+    dates: &[f64],
+    pfe_confidence: f64,
+) -> SdeResult<ExposureProfile> {
+    cfg.validate()?;
+    cfg.parallelism.install(|| {
+        portfolio.validate()?;
+        validate_range("pfe_confidence", pfe_confidence, 0.0, 1.0)?;
+        validate_dates(dates)?;
+        for inst in &portfolio.instruments {
+            if !matches!(
+                inst.payoff,
+                Payoff::EuropeanCall { .. }
+                    | Payoff::EuropeanPut { .. }
+                    | Payoff::DigitalCall { .. }
+                    | Payoff::DigitalPut { .. }
+            ) {
+                return Err(SdeError::UnsupportedOperation {
+                    operation: "Exposure simulation".to_string(),
+                    context: "only European and digital calls and puts can be revalued".to_string(),
+                });
+            }
+        }
+        let rates = portfolio.conversion_rates()?;
+
+        let signs: &[f64] = if cfg.use_antithetic {
+            &[1.0, -1.0]
+        } else {
+            &[1.0]
+        };
+
+        // Exposure per (path, sign) and date
+        let exposures: Vec<Vec<f64>> = (0..cfg.paths)
+            .into_par_iter()
+            .flat_map_iter(|i| {
+                let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+                let draws: Vec<f64> = dates
+                    .iter()
+                    .map(|_| rng::get_normal_draw(&mut rng))
+                    .collect();
+                let rates = &rates;
+                signs.iter().map(move |&sign| {
+                    let (mut prev_t, mut w) = (0.0, 0.0);
+                    dates
+                        .iter()
+                        .zip(&draws)
+                        .map(|(&t, &z)| {
+                            w += (t - prev_t).sqrt() * sign * z;
+                            prev_t = t;
+                            let s = cfg.s0
+                                * ((cfg.r - 0.5 * cfg.sigma * cfg.sigma) * t + cfg.sigma * w).exp();
+                            netting_set_value(cfg, portfolio, rates, s, t).max(0.0)
+                        })
+                        .collect()
+                })
+            })
+            .collect();
+        profile_from_exposures(cfg, portfolio.currency, dates, &exposures, pfe_confidence)
+    })
+}
+
+/// Simulate EE, discounted EE and PFE profiles of a netting set, valued
+/// by regression proxies
+///
+/// At each date the remaining cashflows of every path, discounted to the
+/// date, are regressed on the spot with polynomials of degree `degree`
+/// (see [`RegressionProxy`]), so any payoff can be in the netting set.
+/// Cashflows paid on the date itself enter with their realised value.
+/// Uses `cfg.s0`, `r`, `sigma`, `paths`, `steps` (fixings of
+/// path-dependent payoffs), `seed` and `use_antithetic`; `cfg.payoff` and
+/// `cfg.t` are ignored.
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid configurations, empty or unordered dates,
+/// or fewer paths than basis terms.
+pub fn mc_exposure_profile_proxy(
+    cfg: &McConfig,
+    portfolio: &Portfolio,
+    dates: &[f64],
+    pfe_confidence: f64,
+    degree: usize,
+) -> SdeResult<ExposureProfile> {
+    cfg.validate()?;
+    cfg.parallelism.install(|| {
+        portfolio.validate()?;
+        validate_range("pfe_confidence", pfe_confidence, 0.0, 1.0)?;
+        validate_dates(dates)?;
+        let rates = portfolio.conversion_rates()?;
+
+        let (grid, observed) = observation_grid(portfolio, cfg.steps, dates);
+        let date_index: Vec<usize> = dates
+            .iter()
+            .map(|&t| grid_index(&grid, t).expect("every exposure date is on the grid"))
+            .collect();
+        let signs: &[f64] = if cfg.use_antithetic {
+            &[1.0, -1.0]
+        } else {
+            &[1.0]
+        };
+
+        // Per (path, sign): spot at each date and each instrument's cashflow
+        // discounted to time 0
+        let samples: Vec<(Vec<f64>, Vec<f64>)> = (0..cfg.paths)
+            .into_par_iter()
+            .flat_map_iter(|i| {
+                let mut rng = cfg.seed_strategy.path_rng(cfg.seed, i as u64);
+                let draws: Vec<f64> = grid
+                    .iter()
+                    .map(|_| rng::get_normal_draw(&mut rng))
+                    .collect();
+                let (grid, observed, date_index, rates) = (&grid, &observed, &date_index, &rates);
+                signs.iter().map(move |&sign| {
+                    let brownian = brownian_path(grid, &draws, sign);
+                    let spots = gbm_path(cfg.s0, cfg.r, cfg.sigma, grid, &brownian);
+                    let cashflows = portfolio
+                        .instruments
+                        .iter()
+                        .zip(observed)
+                        .zip(rates)
+                        .map(|((inst, obs), rate)| {
+                            let mut path = Vec::with_capacity(obs.len() + 1);
+                            path.push(cfg.s0);
+                            path.extend(obs.iter().map(|&g| spots[g]));
+                            let discount = (-cfg.r * inst.maturity).exp();
+                            rate * inst.quantity * discount * inst.payoff.calculate(&path)
+                        })
+                        .collect();
+                    (date_index.iter().map(|&g| spots[g]).collect(), cashflows)
+                })
+            })
+            .collect();
+
+        let mut exposures = vec![vec![0.0; dates.len()]; samples.len()];
+        for (k, &t) in dates.iter().enumerate() {
+            // A position still belongs to the netting set on its payment date
+            let live: Vec<usize> = (0..portfolio.instruments.len())
+                .filter(|&j| portfolio.instruments[j].maturity >= t - 1e-12)
+                .collect();
+            if live.is_empty() {
+                continue;
+            }
+            let growth = (cfg.r * t).exp();
+            // Cashflows paid on the date are known on the path; only later
+            // ones need the proxy
+            let (paying, later): (Vec<usize>, Vec<usize>) = live
+                .into_iter()
+                .partition(|&j| portfolio.instruments[j].maturity <= t + 1e-12);
+            let value_of = |cashflows: &[f64], set: &[usize]| {
+                growth * set.iter().map(|&j| cashflows[j]).sum::<f64>()
+            };
+            let states: Vec<f64> = samples.iter().map(|(spots, _)| spots[k]).collect();
+            let proxy = if later.is_empty() {
+                None
+            } else {
+                let targets: Vec<f64> = samples
+                    .iter()
+                    .map(|(_, cashflows)| value_of(cashflows, &later))
+                    .collect();
+                Some(RegressionProxy::fit(&states, &targets, 1, degree)?)
+            };
+            for ((row, &s), (_, cashflows)) in exposures.iter_mut().zip(&states).zip(&samples) {
+                let continuation = proxy.as_ref().map_or(0.0, |p| p.value(&[s]));
+                row[k] = (value_of(cashflows, &paying) + continuation).max(0.0);
+            }
+        }
+        profile_from_exposures(cfg, portfolio.currency, dates, &exposures, pfe_confidence)
+    })
+}
+
+/// Check that exposure dates are positive and strictly increasing
+fn validate_dates(dates: &[f64]) -> SdeResult<()> {
+    if dates.is_empty() {
+        return Err(SdeError::InvalidConfiguration {
+            field: "dates".to_string(),
+            reason: "must contain at least one exposure date".to_string(),
+        });
+    }
+    let mut prev = 0.0;
+    for &t in dates {
+        validate_finite("exposure_date", t)?;
+        if t <= prev {
+            return Err(SdeError::InvalidParameters {
+                parameter: "exposure_date".to_string(),
+                value: t,
+                constraint: "dates must be positive and strictly increasing".to_string(),
+            });
+        }
+        prev = t;
+    }
+    Ok(())
+}
+
+/// Profiles from the exposure of every sample (one row each) at every date
+fn profile_from_exposures(
+    cfg: &McConfig,
+    currency: Currency,
+    dates: &[f64],
+    exposures: &[Vec<f64>],
+    pfe_confidence: f64,
+) -> SdeResult<ExposureProfile> {
+    let n = exposures.len();
+    let pfe_index = ((pfe_confidence * n as f64).ceil() as usize).clamp(1, n) - 1;
+    let mut profile = ExposureProfile {
+        currency,
+        dates: dates.to_vec(),
+        expected_exposure: Vec::with_capacity(dates.len()),
+        discounted_expected_exposure: Vec::with_capacity(dates.len()),
+        pfe: Vec::with_capacity(dates.len()),
+        pfe_confidence,
+    };
+    for (k, &t) in dates.iter().enumerate() {
+        let mut column: Vec<f64> = exposures.iter().map(|row| row[k]).collect();
+        let ee = column.iter().sum::<f64>() / n as f64;
+        column.sort_by(f64::total_cmp);
+        profile.expected_exposure.push(ee);
+        profile
+            .discounted_expected_exposure
+            .push((-cfg.r * t).exp() * ee);
+        profile.pfe.push(column[pfe_index]);
+    }
+
+    if profile.expected_exposure.iter().any(|e| !e.is_finite()) {
+        return Err(SdeError::NumericalInstability {
+            method: "Exposure simulation".to_string(),
+            reason: "non-finite expected exposure".to_string(),
+        });
+    }
+
+    Ok(profile)
+}
+
+/// Mark-to-market of the netting set at spot `s` and time `t`, converted
+/// at `rates` into the portfolio currency
+fn netting_set_value(cfg: &McConfig, portfolio: &Portfolio, rates: &[f64], s: f64, t: f64) -> f64 {
+    portfolio
+        .instruments
+        .iter()
+        .zip(rates)
+        .map(|(inst, rate)| {
+            let tau = inst.maturity - t;
+            if tau < -1e-12 {
+                return 0.0;
+            }
+            let value = if tau <= 1e-12 {
+                inst.payoff.calculate(&[s])
+            } else {
+                let (r, sigma) = (cfg.r, cfg.sigma);
+                match inst.payoff {
+                    Payoff::EuropeanCall { k } => bs_analytic::bs_call_price(s, k, r, sigma, tau),
+                    Payoff::EuropeanPut { k } => bs_analytic::bs_put_price(s, k, r, sigma, tau),
+                    Payoff::DigitalCall { k } => {
+                        bs_analytic::bs_digital_call_price(s, k, r, sigma, tau)
+                    }
+                    Payoff::DigitalPut { k } => {
+                        (-r * tau).exp() - bs_analytic::bs_digital_call_price(s, k, r, sigma, tau)
+                    }
+                    _ => 0.0,
+                }
+            };
+            rate * inst.quantity * value
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mc::portfolio::mc_price_portfolio;
+
+    #[test]
+    fn test_long_call_exposure_and_cva() {
+        let cfg = McConfig {
+            paths: 20_000,
+            ..Default::default()
+        };
+        let mut book = Portfolio::default();
+        book.push(Payoff::EuropeanCall { k: 100.0 }, 1.0, 1.0);
+        let dates = [0.25, 0.5, 0.75, 1.0, 1.25];
+        let profile = mc_exposure_profile(&cfg, &book, &dates, 0.95).expect("Valid profile");
+
+        // A long option is never a liability, so DEE is the martingale V(0)
+        let v0 = bs_analytic::bs_call_price(cfg.s0, 100.0, cfg.r, cfg.sigma, 1.0);
+        for (k, &t) in dates.iter().enumerate().take(4) {
+            let dee = profile.discounted_expected_exposure[k];
+            assert!(
+                (dee - v0).abs() < 0.05 * v0,
+                "DEE({}) = {} vs {}",
+                t,
+                dee,
+                v0
+            );
+            assert!(profile.pfe[k] > profile.expected_exposure[k]);
+        }
+        assert_eq!(profile.expected_exposure[4], 0.0);
+
+        // Flat hazard: CVA = (1 - R) V(0) (1 - e^(-λT))
+        let hazard = HazardCurve::flat(0.02);
+        let cva = profile.cva(&hazard, 0.4).expect("Valid hazard curve");
+        let expected = 0.6 * v0 * (1.0 - (-0.02f64).exp());
+        assert!((cva - expected).abs() < 0.05 * expected);
+
+        let curve = HazardCurve {
+            times: vec![1.0, 2.0],
+            rates: vec![0.01, 0.03],
+        };
+        assert!((curve.survival(1.5) - (-0.025f64).exp()).abs() < 1e-12);
+        assert!((curve.survival(3.0) - (-0.07f64).exp()).abs() < 1e-12);
+
+        // An overflowed sample is reported, not a panic in the PFE sort
+        let overflowed = [vec![1.0, 2.0], vec![f64::NAN, 3.0]];
+        assert!(
+            profile_from_exposures(&cfg, book.currency, &[0.5, 1.0], &overflowed, 0.95).is_err()
+        );
+    }
+
+    #[test]
+    fn test_proxy_exposure_matches_analytic_and_values_asians() {
+        let cfg = McConfig {
+            paths: 20_000,
+            steps: 12,
+            ..Default::default()
+        };
+        // Long call against a short put: the netting set changes sign
+        let mut book = Portfolio::default();
+        book.push(Payoff::EuropeanCall { k: 100.0 }, 1.0, 1.0);
+        book.push(Payoff::EuropeanPut { k: 95.0 }, -1.0, 0.5);
+        let dates = [0.25, 0.5, 0.75, 1.0, 1.25];
+        let exact = mc_exposure_profile(&cfg, &book, &dates, 0.95).unwrap();
+        let proxy = mc_exposure_profile_proxy(&cfg, &book, &dates, 0.95, 4).unwrap();
+        for (k, &t) in dates.iter().enumerate().take(4) {
+            let (a, b) = (exact.expected_exposure[k], proxy.expected_exposure[k]);
+            assert!((a - b).abs() < 0.03 * a + 0.1, "EE({}) = {} vs {}", t, b, a);
+            let (a, b) = (exact.pfe[k], proxy.pfe[k]);
+            assert!(
+                (a - b).abs() < 0.05 * a + 0.2,
+                "PFE({}) = {} vs {}",
+                t,
+                b,
+                a
+            );
+        }
+        assert_eq!(proxy.expected_exposure[4], 0.0);
+
+        // Payoffs without a closed form: DEE of a long Asian stays at V(0)
+        let mut asian = Portfolio::default();
+        asian.push(Payoff::AsianCall { k: 100.0 }, 1.0, 1.0);
+        assert!(mc_exposure_profile(&cfg, &asian, &dates, 0.95).is_err());
+        let v0 = mc_price_portfolio(&cfg, &asian).unwrap().total.value;
+        let profile = mc_exposure_profile_proxy(&cfg, &asian, &dates, 0.95, 3).unwrap();
+        for (k, &t) in dates.iter().enumerate().take(4) {
+            let dee = profile.discounted_expected_exposure[k];
+            assert!(
+                (dee - v0).abs() < 0.05 * v0,
+                "DEE({}) = {} vs {}",
+                t,
+                dee,
+                v0
+            );
+        }
+    }
+}
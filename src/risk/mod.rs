@@ -0,0 +1,4 @@
+pub mod exposure;
+pub mod pnl_explain;
+pub mod scenarios;
+pub mod var;
@@ -0,0 +1,319 @@
+// src/risk/var.rs
+//! Value-at-Risk and Expected Shortfall
+//!
+//! # Full Revaluation
+//!
+//! For each market scenario at the horizon `h` the portfolio is repriced
+//! with its remaining maturities `T - h` and the shocked inputs, and the
+//! P&L is
+//! ```text
+//! P&L = V(S_h, σ_h, T - h) - V(S₀, σ, T)
+//! ```
+//! Every revaluation uses the same seed as the base value (common random
+//! numbers), so pricing noise largely cancels in the P&L.
+//!
+//! # Scenarios
+//!
+//! - **Monte Carlo**: spot evolves under GBM with real-world drift `μ`:
+//!   `S_h = S₀ exp((μ - σ²/2)h + σ√h Z)`, volatility unchanged
+//! - **Historical**: caller-supplied log returns and volatility changes,
+//!   e.g. observed over past `h`-day windows
+//!
+//! # Risk Measures
+//!
+//! With losses `L = -P&L` sorted ascending and confidence level `α`:
+//! ```text
+//! VaR_α = L_(⌈αn⌉)
+//! ES_α  = mean of L_(i) for i ≥ ⌈αn⌉
+//! ```
+//! Both are reported as positive numbers for losses.
+//!
+//! # Limitations
+//!
+//! Path-dependent positions are revalued as fresh contracts over their
+//! remaining life; fixings and barrier observations inside the horizon are
+//! not carried over.
+
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::mc::currency::Currency;
+use crate::mc::mc_engine::McConfig;
+use crate::mc::portfolio::{mc_portfolio_value, Portfolio};
+use crate::parallel::prelude::*;
+use crate::rng;
+
+/// Evaluation index of the scenario streams, apart from the pricing
+/// streams (evaluation 0) even when `RiskConfig::seed` equals the pricing
+/// seed
+const SCENARIO_EVALUATION: u64 = 1;
+
+/// Settings for a VaR/ES calculation
+#[derive(Debug, Clone)]
+pub struct RiskConfig {
+    /// Risk horizon in years (10 trading days by default)
+    pub horizon: f64,
+    /// Confidence levels in (0, 1), e.g. 0.99
+    pub confidence_levels: Vec<f64>,
+    /// Number of Monte Carlo market scenarios
+    pub scenarios: usize,
+    /// Seed for the market scenarios, keyed per scenario through
+    /// `McConfig::seed_strategy` on an evaluation index of their own (the
+    /// pricing seed is `McConfig::seed`)
+    pub seed: u64,
+    /// Real-world drift of the underlying for Monte Carlo scenarios
+    pub drift: f64,
+}
+
+impl RiskConfig {
+    /// Validate the risk settings
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_positive("horizon", self.horizon)?;
+        validate_finite("drift", self.drift)?;
+        validate_paths(self.scenarios)?;
+        if self.confidence_levels.is_empty() {
+            return Err(SdeError::InvalidConfiguration {
+                field: "confidence_levels".to_string(),
+                reason: "must contain at least one level".to_string(),
+            });
+        }
+        for &level in &self.confidence_levels {
+            validate_range("confidence_level", level, 0.0, 1.0)?;
+            if level == 0.0 || level == 1.0 {
+                return Err(SdeError::InvalidParameters {
+                    parameter: "confidence_level".to_string(),
+                    value: level,
+                    constraint: "must be strictly between 0 and 1".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        RiskConfig {
+            horizon: 10.0 / 252.0,
+            confidence_levels: vec![0.95, 0.99],
+            scenarios: 1_000,
+            seed: 2024,
+            drift: 0.0,
+        }
+    }
+}
+
+/// A historical market move over the risk horizon
+#[derive(Debug, Clone, Copy)]
+pub struct HistoricalScenario {
+    /// Log return of the underlying, `ln(S_h / S₀)`
+    pub spot_log_return: f64,
+    /// Absolute change in volatility
+    pub vol_change: f64,
+}
+
+/// VaR and ES at one confidence level
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RiskMeasure {
+    pub confidence: f64,
+    pub var: f64,
+    pub expected_shortfall: f64,
+}
+
+/// P&L distribution and risk measures for a portfolio
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RiskReport {
+    /// Reporting currency of the portfolio
+    pub currency: Currency,
+    /// Portfolio value today
+    pub base_value: f64,
+    /// P&L per scenario, in scenario order
+    pub pnl: Vec<f64>,
+    /// One entry per requested confidence level
+    pub measures: Vec<RiskMeasure>,
+}
+
+/// VaR and ES of a P&L sample at confidence level `confidence`
+///
+/// Returns `(var, expected_shortfall)` as positive loss amounts.
+///
+/// # Errors
+///
+/// Returns `SdeError` for a non-finite P&L or a confidence outside `[0, 1]`.
+pub fn var_and_expected_shortfall(pnl: &[f64], confidence: f64) -> SdeResult<(f64, f64)> {
+    validate_range("confidence", confidence, 0.0, 1.0)?;
+    for &p in pnl {
+        validate_finite("pnl", p)?;
+    }
+    if pnl.is_empty() {
+        return Ok((0.0, 0.0));
+    }
+    let mut losses: Vec<f64> = pnl.iter().map(|p| -p).collect();
+    losses.sort_by(f64::total_cmp);
+    let n = losses.len();
+    let idx = ((confidence * n as f64).ceil() as usize).clamp(1, n) - 1;
+    let tail = &losses[idx..];
+    Ok((losses[idx], tail.iter().sum::<f64>() / tail.len() as f64))
+}
+
+/// VaR and ES from Monte Carlo market scenarios with full revaluation
+///
+/// # Errors
+///
+/// Returns `SdeError` for invalid configurations, a horizon at or beyond
+/// any position's maturity, or pricing failures.
+pub fn mc_portfolio_risk(
+    cfg: &McConfig,
+    portfolio: &Portfolio,
+    risk: &RiskConfig,
+) -> SdeResult<RiskReport> {
+    risk.validate()?;
+    let h = risk.horizon;
+    let shocks: Vec<HistoricalScenario> = (0..risk.scenarios)
+        .map(|i| {
+            let mut rng = cfg
+                .seed_strategy
+                .stream(risk.seed, SCENARIO_EVALUATION, i as u64);
+            let z = rng::get_normal_draw(&mut rng);
+            HistoricalScenario {
+                spot_log_return: (risk.drift - 0.5 * cfg.sigma * cfg.sigma) * h
+                    + cfg.sigma * h.sqrt() * z,
+                vol_change: 0.0,
+            }
+        })
+        .collect();
+    revalue(cfg, portfolio, risk, &shocks)
+}
+
+/// VaR and ES from historical market moves with full revaluation
+///
+/// `risk.scenarios`, `risk.seed` and `risk.drift` are not used.
+///
+/// # Errors
+///
+/// Same as [`mc_portfolio_risk`], and for an empty scenario set or a
+/// scenario that makes volatility non-positive.
+pub fn historical_portfolio_risk(
+    cfg: &McConfig,
+    portfolio: &Portfolio,
+    scenarios: &[HistoricalScenario],
+    risk: &RiskConfig,
+) -> SdeResult<RiskReport> {
+    risk.validate()?;
+    if scenarios.is_empty() {
+        return Err(SdeError::InvalidConfiguration {
+            field: "scenarios".to_string(),
+            reason: "must contain at least one historical scenario".to_string(),
+        });
+    }
+    revalue(cfg, portfolio, risk, scenarios)
+}
+
+fn revalue(
+    cfg: &McConfig,
+    portfolio: &Portfolio,
+    risk: &RiskConfig,
+    scenarios: &[HistoricalScenario],
+) -> SdeResult<RiskReport> {
+    let h = risk.horizon;
+    if portfolio.instruments.iter().any(|inst| inst.maturity <= h) {
+        return Err(SdeError::InvalidConfiguration {
+            field: "horizon".to_string(),
+            reason: format!("horizon {} must be shorter than every maturity", h),
+        });
+    }
+
+    let base_value = mc_portfolio_value(cfg, portfolio)?;
+    let mut aged = portfolio.clone();
+    for inst in &mut aged.instruments {
+        inst.maturity -= h;
+    }
+
+    let pnl = cfg.parallelism.install(|| {
+        scenarios
+            .par_iter()
+            .map(|shock| {
+                let shocked = McConfig {
+                    s0: cfg.s0 * shock.spot_log_return.exp(),
+                    sigma: cfg.sigma + shock.vol_change,
+                    ..cfg.clone()
+                };
+                Ok(mc_portfolio_value(&shocked, &aged)? - base_value)
+            })
+            .collect::<SdeResult<Vec<f64>>>()
+    })?;
+
+    let measures = risk
+        .confidence_levels
+        .iter()
+        .map(|&confidence| {
+            let (var, expected_shortfall) = var_and_expected_shortfall(&pnl, confidence)?;
+            Ok(RiskMeasure {
+                confidence,
+                var,
+                expected_shortfall,
+            })
+        })
+        .collect::<SdeResult<Vec<RiskMeasure>>>()?;
+
+    Ok(RiskReport {
+        currency: portfolio.currency,
+        base_value,
+        pnl,
+        measures,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mc::payoffs::Payoff;
+
+    #[test]
+    fn test_var_es_on_known_sample() {
+        // Losses 1..=100: the 95% VaR is the 95th smallest loss
+        let pnl: Vec<f64> = (1..=100).map(|i| -(i as f64)).collect();
+        let (var, es) = var_and_expected_shortfall(&pnl, 0.95).unwrap();
+        assert_eq!(var, 95.0);
+        assert!((es - 97.5).abs() < 1e-12);
+        assert!(var_and_expected_shortfall(&[1.0, f64::NAN], 0.95).is_err());
+        assert!(var_and_expected_shortfall(&pnl, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_portfolio_risk_mc_and_historical() {
+        let cfg = McConfig {
+            paths: 5_000,
+            ..Default::default()
+        };
+        let mut book = Portfolio::default();
+        book.push(Payoff::EuropeanCall { k: 100.0 }, 10.0, 0.5);
+        let risk = RiskConfig {
+            scenarios: 200,
+            ..Default::default()
+        };
+
+        let report = mc_portfolio_risk(&cfg, &book, &risk).expect("Valid risk config");
+        assert_eq!(report.pnl.len(), 200);
+        for m in &report.measures {
+            assert!(m.var > 0.0 && m.expected_shortfall >= m.var);
+        }
+        assert!(report.measures[1].var >= report.measures[0].var);
+
+        // A long call loses most in the down move
+        let moves = [-0.1, 0.0, 0.1].map(|r| HistoricalScenario {
+            spot_log_return: r,
+            vol_change: 0.0,
+        });
+        let historical =
+            historical_portfolio_risk(&cfg, &book, &moves, &risk).expect("Valid scenarios");
+        assert!(historical.pnl[0] < historical.pnl[1] && historical.pnl[1] < historical.pnl[2]);
+        assert_eq!(historical.measures[1].var, -historical.pnl[0]);
+
+        let too_long = RiskConfig {
+            horizon: 1.0,
+            ..risk
+        };
+        assert!(mc_portfolio_risk(&cfg, &book, &too_long).is_err());
+    }
+}
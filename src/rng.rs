@@ -11,24 +11,41 @@
 //!
 //! # Counter-Based RNG
 //!
-//! Uses a counter-based approach similar to Philox/Threefry algorithms:
-//! - Each path gets a unique counter value
-//! - Deterministic mapping: (seed, counter) → random value
-//! - Perfect reproducibility across different thread counts
+//! Counter-based generators map `(key, counter)` → random value, so any
+//! draw of any path can be computed directly and results do not depend on
+//! thread counts:
+//! - [`CounterRng`]: a splitmix64 hash of `key + counter`; fast, with
+//!   per-path keys that make stream overlap unlikely
+//! - [`philox::Philox4x32`]: Philox4x32-10, where streams occupy disjoint
+//!   counter ranges and are independent by construction
 //!
-//! # Box-Muller Transform
+//! # Seed Strategies
 //!
-//! Converts uniform random variables to normal distributions:
-//! ```text
-//! Z₁ = √(-2ln(U₁)) * cos(2πU₂)
-//! Z₂ = √(-2ln(U₁)) * sin(2πU₂)
-//! ```
-//! where U₁, U₂ ~ Uniform(0,1) and Z₁, Z₂ ~ N(0,1).
+//! Pricers that draw from `StdRng` derive one stream per path (and per
+//! independent evaluation) from the run's seed through a [`SeedStrategy`].
+//! The default [`SeedStrategy::Hashed`] builds the full 256-bit ChaCha key
+//! of each stream from hashes of `(seed, evaluation, path)`, so distinct
+//! triples never share a stream. The legacy [`SeedStrategy::Sequential`]
+//! seeds path `i` with `seed + i`, which makes path `i + 1` of a run seeded
+//! with `s` the same stream as path `i` of a run seeded with `s + 1`.
+//!
+//! # Normal Draws
+//!
+//! Normals come from the ziggurat sampler of `rand_distr::StandardNormal`:
+//! the density is covered by 128 equal-area horizontal layers, a uniform
+//! picks a layer and a point in it, and the point is accepted outright
+//! about 98.8% of the time, costing one 64-bit draw and a multiply. The
+//! sampler keeps no state between calls, so each generator's normals
+//! depend only on its own stream.
+
+pub mod faure;
+pub mod halton;
+pub mod philox;
+pub mod sobol;
 
 use rand::rngs::StdRng;
-use rand::{Rng, SeedableRng};
+use rand::{Rng, RngCore, SeedableRng};
 use rand_distr::{Distribution, StandardNormal};
-use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Counter-based RNG for reproducible parallel simulations
 ///
@@ -57,47 +74,61 @@ impl CounterRng {
         Self { base_seed, counter }
     }
 
+    /// Independent stream for path `path` of a simulation seeded with `seed`
+    ///
+    /// The key is a splitmix64 hash of `(seed, path)`, so neighbouring paths
+    /// get unrelated starting points rather than overlapping counters.
+    pub fn for_path(seed: u64, path: u64) -> Self {
+        let key = splitmix64(seed ^ splitmix64(path.wrapping_add(0x9e3779b97f4a7c15)));
+        Self::new(key, 0)
+    }
+
     pub fn next_u64(&mut self) -> u64 {
         // Simple counter-based PRNG using splitmix64-like algorithm
         self.counter = self.counter.wrapping_add(1);
-        let mut z = self.base_seed.wrapping_add(self.counter);
-        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9u64);
-        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111ebu64);
-        z ^ (z >> 31)
+        splitmix64(self.base_seed.wrapping_add(self.counter))
     }
 
     pub fn uniform(&mut self) -> f64 {
         (self.next_u64() >> 11) as f64 * (1.0 / 9007199254740992.0) // 2^53
     }
 
+    /// Standard normal draw (ziggurat) from this stream
     pub fn normal(&mut self) -> f64 {
-        // Box-Muller transform
-        static mut SPARE: Option<f64> = None;
-        static SPARE_READY: AtomicU64 = AtomicU64::new(0);
-
-        unsafe {
-            if SPARE_READY.load(Ordering::Relaxed) == 1 {
-                SPARE_READY.store(0, Ordering::Relaxed);
-                return SPARE.unwrap();
-            }
-        }
+        StandardNormal.sample(self)
+    }
+}
 
-        let u1 = self.uniform();
-        let u2 = self.uniform();
+/// Lets `rand` distributions (e.g. the ziggurat `StandardNormal` behind
+/// [`get_normal_draw`]) draw from the counter stream
+impl RngCore for CounterRng {
+    fn next_u32(&mut self) -> u32 {
+        (CounterRng::next_u64(self) >> 32) as u32
+    }
 
-        let mag = (-2.0 * u1.ln()).sqrt();
-        let z1 = mag * (2.0 * std::f64::consts::PI * u2).cos();
-        let z2 = mag * (2.0 * std::f64::consts::PI * u2).sin();
+    fn next_u64(&mut self) -> u64 {
+        CounterRng::next_u64(self)
+    }
 
-        unsafe {
-            SPARE = Some(z2);
-            SPARE_READY.store(1, Ordering::Relaxed);
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = CounterRng::next_u64(self).to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
         }
+    }
 
-        z1
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
     }
 }
 
+fn splitmix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9u64);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111ebu64);
+    z ^ (z >> 31)
+}
+
 /// RNG factory for reproducible parallel simulations
 pub struct RngFactory {
     base_seed: u64,
@@ -110,7 +141,7 @@ impl RngFactory {
 
     /// Create a counter RNG for a specific path/thread
     pub fn create_counter_rng(&self, path_id: u64) -> CounterRng {
-        CounterRng::new(self.base_seed, path_id)
+        CounterRng::for_path(self.base_seed, path_id)
     }
 
     /// Create a standard RNG for a specific path/thread (backward compatibility)
@@ -119,6 +150,55 @@ impl RngFactory {
     }
 }
 
+/// Derivation of per-path `StdRng` streams from a run's seed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SeedStrategy {
+    /// Key the stream with hashes of `(seed, evaluation, path)`: distinct
+    /// triples give distinct ChaCha keys, so streams never overlap, within
+    /// a run or across runs with different seeds
+    #[default]
+    Hashed,
+    /// Seed the stream with `seed + evaluation + path`, as before the
+    /// strategies existed; streams of runs with nearby seeds coincide
+    Sequential,
+}
+
+impl SeedStrategy {
+    /// Stream of path `path` in a run seeded with `seed`
+    pub fn path_rng(self, seed: u64, path: u64) -> StdRng {
+        self.stream(seed, 0, path)
+    }
+
+    /// Stream of path `path` in evaluation `evaluation` of a run seeded
+    /// with `seed`
+    ///
+    /// Evaluations that should share draws (common random numbers) use the
+    /// same index; independent evaluations of one run use different ones.
+    pub fn stream(self, seed: u64, evaluation: u64, path: u64) -> StdRng {
+        match self {
+            SeedStrategy::Hashed => {
+                // splitmix64 is a bijection, so each key word determines
+                // its input and the key determines the triple
+                let words = [
+                    splitmix64(seed),
+                    splitmix64(evaluation ^ 0x6a09e667f3bcc908),
+                    splitmix64(path ^ 0xbb67ae8584caa73b),
+                    0x3c6ef372fe94f82b,
+                ];
+                let mut key = [0u8; 32];
+                for (chunk, word) in key.chunks_mut(8).zip(words) {
+                    chunk.copy_from_slice(&word.to_le_bytes());
+                }
+                StdRng::from_seed(key)
+            }
+            SeedStrategy::Sequential => {
+                StdRng::seed_from_u64(seed.wrapping_add(evaluation).wrapping_add(path))
+            }
+        }
+    }
+}
+
 // Backward compatibility functions
 pub fn seed_rng_from_u64(seed: u64) -> StdRng {
     StdRng::seed_from_u64(seed)
@@ -181,4 +261,89 @@ mod tests {
             variance
         );
     }
+
+    #[test]
+    fn test_normal_state_is_per_instance() {
+        let factory = RngFactory::new(7);
+        let alone: Vec<f64> = {
+            let mut rng = factory.create_counter_rng(3);
+            (0..64).map(|_| rng.normal()).collect()
+        };
+
+        // Interleaving with another generator must not change the stream
+        let mut rng = factory.create_counter_rng(3);
+        let mut other = factory.create_counter_rng(4);
+        let interleaved: Vec<f64> = (0..64)
+            .map(|_| {
+                other.normal();
+                rng.normal()
+            })
+            .collect();
+        assert_eq!(alone, interleaved);
+    }
+
+    #[test]
+    fn test_normals_independent_across_paths() {
+        let factory = RngFactory::new(2024);
+        let paths = 20_000;
+        let draws: Vec<[f64; 2]> = (0..paths as u64)
+            .map(|p| {
+                let mut rng = factory.create_counter_rng(p);
+                [rng.normal(), rng.normal()]
+            })
+            .collect();
+        let correlation = |pairs: &[(f64, f64)]| {
+            let n = pairs.len() as f64;
+            let (mx, my) = pairs
+                .iter()
+                .fold((0.0, 0.0), |(a, b), &(x, y)| (a + x / n, b + y / n));
+            let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+            for &(x, y) in pairs {
+                sxy += (x - mx) * (y - my);
+                sxx += (x - mx) * (x - mx);
+                syy += (y - my) * (y - my);
+            }
+            sxy / (sxx * syy).sqrt()
+        };
+        // Under independence the sample correlation is about N(0, 1/n)
+        let bound = 4.0 / (paths as f64).sqrt();
+
+        // Same draw index on neighbouring paths
+        let neighbours: Vec<(f64, f64)> = draws.windows(2).map(|w| (w[0][0], w[1][0])).collect();
+        assert!(correlation(&neighbours).abs() < bound);
+        // Next draw of a path against the first draw of the next path
+        let shifted: Vec<(f64, f64)> = draws.windows(2).map(|w| (w[0][1], w[1][0])).collect();
+        assert!(correlation(&shifted).abs() < bound);
+        // Consecutive draws within a path
+        let within: Vec<(f64, f64)> = draws.iter().map(|d| (d[0], d[1])).collect();
+        assert!(correlation(&within).abs() < bound);
+    }
+
+    #[test]
+    fn test_hashed_streams_do_not_overlap_across_seeds() {
+        let first = |strategy: SeedStrategy, seed, evaluation, path| {
+            strategy.stream(seed, evaluation, path).next_u64()
+        };
+        // Sequential: path 1 of seed 10 replays path 0 of seed 11
+        let sequential = SeedStrategy::Sequential;
+        assert_eq!(first(sequential, 10, 0, 1), first(sequential, 11, 0, 0));
+        assert_eq!(
+            first(sequential, 10, 0, 0),
+            sequential.path_rng(10, 0).next_u64()
+        );
+
+        let hashed = SeedStrategy::Hashed;
+        let mut seen = std::collections::HashSet::new();
+        for seed in 0..8 {
+            for evaluation in 0..4 {
+                for path in 0..64 {
+                    assert!(seen.insert(first(hashed, seed, evaluation, path)));
+                }
+            }
+        }
+        // Reproducible
+        let mut a = hashed.path_rng(3, 5);
+        let mut b = hashed.stream(3, 0, 5);
+        assert!((0..16).all(|_| a.next_u64() == b.next_u64()));
+    }
 }
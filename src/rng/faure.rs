@@ -0,0 +1,209 @@
+// src/rng/faure.rs
+//! Faure and Generalized Faure Sequences
+//!
+//! # Construction
+//!
+//! All dimensions share one prime base `b ≥ d`. With the base-`b` digits
+//! `a = (a_0, a_1, ...)` of the index, dimension `j` of the point has the
+//! digits `y = C_j a (mod b)`:
+//! ```text
+//! x_j(i) = Σ_r y_r b^{-(r+1)},   C_j = P^j,   (P^j)_{rc} = binom(c, r) j^{c-r}
+//! ```
+//! with `P` the upper-triangular Pascal matrix mod `b` (dimension 0 is the
+//! van der Corput sequence in base `b`). Every elementary box of volume
+//! `b^-m` holds exactly `b^k` of the first `b^{m+k}` points: the sequence
+//! is a `(0, d)`-sequence, the best equidistribution any base allows.
+//!
+//! # Generalized Faure
+//!
+//! [`Faure::scramble`] replaces `C_j` by `A_j P^j` with a random
+//! nonsingular lower-triangular `A_j` (Tezuka's generalized Faure
+//! sequence, a linear scramble in Matoušek's sense) and adds a random
+//! digital shift, which keeps the net property while making every point
+//! uniformly distributed for randomized QMC.
+//!
+//! # Dimension
+//!
+//! The base grows with the dimension, so a complete net needs `b^m ≥ d^m`
+//! points: Faure suits moderate dimensions, Halton with scrambling or
+//! Sobol higher ones.
+
+use super::halton::{digits, first_primes, BELOW_ONE};
+use crate::error::{SdeError, SdeResult};
+use rand::Rng;
+
+/// Faure point generator
+#[derive(Debug, Clone)]
+pub struct Faure {
+    base: u32,
+    digits: usize,
+    /// `P^j` per dimension, `digits × digits` row-major
+    pascal: Vec<Vec<u32>>,
+    /// Generator matrices in use: `P^j`, or `A_j P^j` once scrambled
+    generators: Vec<Vec<u32>>,
+    /// Digital shift per dimension and output digit
+    shifts: Vec<Vec<u32>>,
+}
+
+impl Faure {
+    /// Unscrambled generator of `dimension`-dimensional points in the
+    /// smallest prime base `b ≥ max(dimension, 2)`
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` for dimension 0.
+    pub fn new(dimension: usize) -> SdeResult<Self> {
+        if dimension == 0 {
+            return Err(SdeError::InvalidConfiguration {
+                field: "dimension".to_string(),
+                reason: "Faure sequence needs at least one dimension".to_string(),
+            });
+        }
+        let base = smallest_prime_at_least(dimension.max(2) as u32);
+        let k = digits(base);
+        let b = base as u64;
+
+        // Pascal triangle mod b: binomial[c][r] = binom(c, r)
+        let mut binomial = vec![vec![0u64; k]; k];
+        for c in 0..k {
+            binomial[c][0] = 1;
+            for r in 1..=c {
+                binomial[c][r] = (binomial[c - 1][r - 1] + binomial[c - 1][r]) % b;
+            }
+        }
+        let pascal: Vec<Vec<u32>> = (0..dimension as u64)
+            .map(|j| {
+                let mut power = vec![1u64; k];
+                for e in 1..k {
+                    power[e] = power[e - 1] * (j % b) % b;
+                }
+                let mut matrix = vec![0u32; k * k];
+                for r in 0..k {
+                    for c in r..k {
+                        matrix[r * k + c] = (binomial[c][r] * power[c - r] % b) as u32;
+                    }
+                }
+                matrix
+            })
+            .collect();
+        Ok(Faure {
+            base,
+            digits: k,
+            generators: pascal.clone(),
+            pascal,
+            shifts: vec![vec![0; k]; dimension],
+        })
+    }
+
+    /// Draw a new random lower-triangular scramble and digital shift for
+    /// every dimension from `rng`
+    pub fn scramble<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let (b, k) = (self.base, self.digits);
+        for ((pascal, generator), shift) in self
+            .pascal
+            .iter()
+            .zip(&mut self.generators)
+            .zip(&mut self.shifts)
+        {
+            let mut lower = vec![0u64; k * k];
+            for r in 0..k {
+                lower[r * k + r] = rng.gen_range(1..b) as u64;
+                for c in 0..r {
+                    lower[r * k + c] = rng.gen_range(0..b) as u64;
+                }
+            }
+            for r in 0..k {
+                for c in 0..k {
+                    let sum: u64 = (0..=r)
+                        .map(|m| lower[r * k + m] * pascal[m * k + c] as u64)
+                        .sum();
+                    generator[r * k + c] = (sum % b as u64) as u32;
+                }
+            }
+            shift.iter_mut().for_each(|s| *s = rng.gen_range(0..b));
+        }
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.generators.len()
+    }
+
+    /// The prime base shared by all dimensions
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    /// Point `index` in `[0, 1)^d`
+    ///
+    /// Only the lowest `f64`-resolvable number of base-`b` digits of
+    /// `index` are used (at least 2^53 distinct points).
+    pub fn point(&self, index: u64, out: &mut [f64]) {
+        let (b, k) = (self.base as u64, self.digits);
+        let mut a = vec![0u64; k];
+        let mut rest = index;
+        for digit in a.iter_mut() {
+            *digit = rest % b;
+            rest /= b;
+        }
+        let used = k - a.iter().rev().take_while(|&&d| d == 0).count();
+        let inv = 1.0 / b as f64;
+        for ((x, generator), shift) in out.iter_mut().zip(&self.generators).zip(&self.shifts) {
+            let mut scale = inv;
+            let mut value = 0.0;
+            for (r, &s) in shift.iter().enumerate() {
+                let row = &generator[r * k..r * k + used];
+                let y = row
+                    .iter()
+                    .zip(&a)
+                    .fold(s as u64, |acc, (&g, &d)| (acc + g as u64 * d) % b);
+                value += y as f64 * scale;
+                scale *= inv;
+            }
+            *x = value.min(BELOW_ONE);
+        }
+    }
+}
+
+fn smallest_prime_at_least(n: u32) -> u32 {
+    let mut primes = first_primes(1);
+    while *primes.last().unwrap() < n {
+        primes = first_primes(primes.len() + 1);
+    }
+    *primes.last().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_faure_points_and_net_property() {
+        let faure = Faure::new(3).unwrap();
+        assert_eq!(faure.base(), 3);
+        let mut x = [0.0; 3];
+        // Index 3 = 10₃: digits (0, 1)
+        faure.point(3, &mut x);
+        let expected = [1.0 / 9.0, 4.0 / 9.0, 7.0 / 9.0];
+        assert!(x.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-15));
+
+        // (0, 2, 3)-net: any two dimensions of the first b^2 points put one
+        // point in each b^-1 × b^-1 box, scrambled or not
+        let mut scrambled = Faure::new(5).unwrap();
+        scrambled.scramble(&mut rand::rngs::StdRng::seed_from_u64(9));
+        for generator in [Faure::new(5).unwrap(), scrambled] {
+            let b = generator.base() as usize;
+            let mut x = vec![0.0; 5];
+            for (j, l) in [(0, 1), (1, 4), (2, 3)] {
+                let mut hit = vec![false; b * b];
+                for i in 0..(b * b) as u64 {
+                    generator.point(i, &mut x);
+                    let cell = |u: f64| (u * b as f64) as usize;
+                    hit[cell(x[j]) * b + cell(x[l])] = true;
+                }
+                assert!(hit.iter().all(|&h| h), "dimensions {} and {}", j, l);
+            }
+        }
+        assert!(Faure::new(0).is_err());
+    }
+}
@@ -0,0 +1,203 @@
+// src/rng/halton.rs
+//! Halton Low-Discrepancy Sequence
+//!
+//! # Construction
+//!
+//! Dimension `j` of point `i` is the radical inverse of `i` in the `j`-th
+//! prime base `b_j`: with `i = Σ_k d_k b_j^k`,
+//! ```text
+//! x_j(i) = Σ_k d_k b_j^{-(k+1)}
+//! ```
+//! The first `b_j^m` points put exactly one point in each interval
+//! `[l/b_j^m, (l+1)/b_j^m)` of dimension `j`.
+//!
+//! # High Dimensions
+//!
+//! For large neighbouring primes the leading points of two dimensions lie
+//! on a few lines (e.g. bases 29 and 31), which spoils integration in
+//! dimensions above ten or so. Two remedies are offered:
+//! - scrambling ([`Halton::scramble`]): every digit goes through a random
+//!   permutation of `0..b_j` per dimension followed by a random digital
+//!   shift per digit, which breaks the correlations, keeps the
+//!   stratification and makes each point uniformly distributed
+//! - leaping ([`Halton::with_leap`], Kocis & Whiten): use points
+//!   `0, L, 2L, ...` for a leap `L` coprime to every base
+
+use crate::error::{SdeError, SdeResult};
+use rand::Rng;
+
+/// Halton point generator in any dimension
+#[derive(Debug, Clone)]
+pub struct Halton {
+    bases: Vec<u32>,
+    leap: u64,
+    /// Per dimension: digit permutation and per-digit shifts
+    scrambling: Option<Vec<(Vec<u32>, Vec<u32>)>>,
+}
+
+impl Halton {
+    /// Unscrambled generator of `dimension`-dimensional points on the first
+    /// `dimension` primes
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` for dimension 0.
+    pub fn new(dimension: usize) -> SdeResult<Self> {
+        if dimension == 0 {
+            return Err(SdeError::InvalidConfiguration {
+                field: "dimension".to_string(),
+                reason: "Halton sequence needs at least one dimension".to_string(),
+            });
+        }
+        Ok(Halton {
+            bases: first_primes(dimension),
+            leap: 1,
+            scrambling: None,
+        })
+    }
+
+    /// Use every `leap`-th point
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` unless `leap` is positive
+    /// and coprime to every base.
+    pub fn with_leap(mut self, leap: u64) -> SdeResult<Self> {
+        if let Some(&base) = self
+            .bases
+            .iter()
+            .find(|&&b| leap == 0 || leap % b as u64 == 0)
+        {
+            return Err(SdeError::InvalidConfiguration {
+                field: "leap".to_string(),
+                reason: format!("{} must be positive and coprime to base {}", leap, base),
+            });
+        }
+        self.leap = leap;
+        Ok(self)
+    }
+
+    /// Draw a new random digit permutation and digital shift for every
+    /// dimension from `rng`
+    pub fn scramble<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let scrambling = self
+            .bases
+            .iter()
+            .map(|&b| {
+                let mut permutation: Vec<u32> = (0..b).collect();
+                for j in (1..b as usize).rev() {
+                    permutation.swap(j, rng.gen_range(0..=j));
+                }
+                let shifts = (0..digits(b)).map(|_| rng.gen_range(0..b)).collect();
+                (permutation, shifts)
+            })
+            .collect();
+        self.scrambling = Some(scrambling);
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.bases.len()
+    }
+
+    /// Prime base of every dimension
+    pub fn bases(&self) -> &[u32] {
+        &self.bases
+    }
+
+    /// Point `index` (of the leaped sequence) in `[0, 1)^d`
+    pub fn point(&self, index: u64, out: &mut [f64]) {
+        let n = index as u128 * self.leap as u128;
+        for (j, (x, &b)) in out.iter_mut().zip(&self.bases).enumerate() {
+            let (b128, inv) = (b as u128, 1.0 / b as f64);
+            let mut rest = n;
+            let mut scale = inv;
+            let mut value = 0.0;
+            match &self.scrambling {
+                None => {
+                    while rest > 0 {
+                        value += (rest % b128) as f64 * scale;
+                        rest /= b128;
+                        scale *= inv;
+                    }
+                }
+                Some(scrambling) => {
+                    let (permutation, shifts) = &scrambling[j];
+                    for &shift in shifts {
+                        let digit = permutation[(rest % b128) as usize];
+                        value += ((digit + shift) % b) as f64 * scale;
+                        rest /= b128;
+                        scale *= inv;
+                    }
+                }
+            }
+            // Rounding of the digit sum must not reach 1
+            *x = value.min(BELOW_ONE);
+        }
+    }
+}
+
+/// Largest `f64` below 1
+pub(crate) const BELOW_ONE: f64 = 1.0 - f64::EPSILON / 2.0;
+
+/// Base-`b` digits resolved by an `f64` in `[0, 1)`
+pub(crate) fn digits(b: u32) -> usize {
+    (f64::MANTISSA_DIGITS as f64 / (b as f64).log2()).ceil() as usize
+}
+
+/// The first `n` primes
+pub(crate) fn first_primes(n: usize) -> Vec<u32> {
+    let mut primes: Vec<u32> = Vec::with_capacity(n);
+    let mut candidate = 2;
+    while primes.len() < n {
+        if primes
+            .iter()
+            .take_while(|&&p| p * p <= candidate)
+            .all(|&p| candidate % p != 0)
+        {
+            primes.push(candidate);
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_halton_points_leap_and_scrambling() {
+        let halton = Halton::new(3).unwrap();
+        assert_eq!(halton.bases(), &[2, 3, 5]);
+        let mut x = [0.0; 3];
+        halton.point(5, &mut x);
+        // 5 = 101₂ = 12₃ = 10₅
+        let expected = [0.625, 2.0 / 3.0 + 1.0 / 9.0, 0.04];
+        assert!(x.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-15));
+
+        let leaped = Halton::new(3).unwrap().with_leap(7).unwrap();
+        let mut y = [0.0; 3];
+        leaped.point(2, &mut y);
+        halton.point(14, &mut x);
+        assert_eq!(x, y);
+        assert!(Halton::new(3).unwrap().with_leap(10).is_err());
+
+        // Scrambled dimensions keep their stratification: the first b^m
+        // points fill every b^-m interval
+        let mut scrambled = Halton::new(12).unwrap();
+        scrambled.scramble(&mut rand::rngs::StdRng::seed_from_u64(3));
+        let mut x = vec![0.0; 12];
+        for (j, &b) in scrambled.bases().iter().enumerate() {
+            let cells = (b as usize).pow(if b < 10 { 3 } else { 1 });
+            let mut hit = vec![false; cells];
+            for i in 0..cells as u64 {
+                scrambled.point(i, &mut x);
+                assert!((0.0..1.0).contains(&x[j]));
+                hit[(x[j] * cells as f64) as usize] = true;
+            }
+            assert!(hit.iter().all(|&h| h), "base {}", b);
+        }
+        assert!(Halton::new(0).is_err());
+    }
+}
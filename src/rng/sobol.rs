@@ -0,0 +1,157 @@
+// src/rng/sobol.rs
+//! Sobol Low-Discrepancy Sequence
+//!
+//! # Construction
+//!
+//! Dimension `j` of point `i` is the XOR of direction numbers `v_{j,k}`
+//! over the set bits `k` of the Gray code of `i` (Antonov–Saleev):
+//! ```text
+//! x_j(i) = ⊕_{k : bit k of (i ⊕ (i >> 1)) set} v_{j,k}
+//! ```
+//! Dimension 1 is the van der Corput sequence; the others use the primitive
+//! polynomials and initial direction numbers of Joe & Kuo
+//! (`new-joe-kuo-6.21201`), extended by the recurrence
+//! ```text
+//! v_k = v_{k-s} ⊕ (v_{k-s} >> s) ⊕ ⊕_{l=1}^{s-1} a_l v_{k-l}
+//! ```
+//! for a polynomial of degree `s` with inner coefficients `a_l`.
+//!
+//! # Properties
+//!
+//! Every dimension of the first `2^m` points has exactly one point in each
+//! interval `[l/2^m, (l+1)/2^m)`. XOR-ing every point with the same random
+//! word (a digital shift) keeps this structure while making each point
+//! uniformly distributed, which is what randomized QMC needs.
+
+use crate::error::{SdeError, SdeResult};
+
+/// Highest supported dimension
+pub const MAX_DIMENSION: usize = 21;
+
+const BITS: usize = 32;
+
+/// `(degree s, coefficients a, initial m_1..m_s)` for dimensions 2, 3, ...
+const JOE_KUO: [(usize, u32, &[u32]); MAX_DIMENSION - 1] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+    (5, 4, &[1, 1, 5, 5, 5]),
+    (5, 7, &[1, 1, 7, 11, 19]),
+    (5, 11, &[1, 1, 5, 1, 1]),
+    (5, 13, &[1, 1, 1, 3, 11]),
+    (5, 14, &[1, 3, 5, 5, 31]),
+    (6, 1, &[1, 3, 3, 9, 7, 49]),
+    (6, 13, &[1, 1, 1, 15, 21, 21]),
+    (6, 16, &[1, 3, 1, 13, 27, 49]),
+    (6, 19, &[1, 1, 1, 15, 7, 5]),
+    (6, 22, &[1, 3, 1, 15, 13, 25]),
+    (6, 25, &[1, 1, 5, 5, 19, 61]),
+    (7, 1, &[1, 3, 7, 11, 23, 15, 103]),
+    (7, 4, &[1, 3, 7, 13, 13, 15, 69]),
+];
+
+/// Sobol point generator in up to [`MAX_DIMENSION`] dimensions
+#[derive(Debug, Clone)]
+pub struct Sobol {
+    directions: Vec<[u32; BITS]>,
+}
+
+impl Sobol {
+    /// Generator of `dimension`-dimensional points
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` unless
+    /// `1 <= dimension <= MAX_DIMENSION`.
+    pub fn new(dimension: usize) -> SdeResult<Self> {
+        if dimension == 0 || dimension > MAX_DIMENSION {
+            return Err(SdeError::InvalidConfiguration {
+                field: "dimension".to_string(),
+                reason: format!(
+                    "Sobol sequence supports 1 to {} dimensions, got {}",
+                    MAX_DIMENSION, dimension
+                ),
+            });
+        }
+        let mut directions = Vec::with_capacity(dimension);
+        let mut first = [0u32; BITS];
+        for (k, v) in first.iter_mut().enumerate() {
+            *v = 1 << (BITS - 1 - k);
+        }
+        directions.push(first);
+
+        for &(s, a, m) in JOE_KUO.iter().take(dimension - 1) {
+            let mut v = [0u32; BITS];
+            for k in 0..s {
+                v[k] = m[k] << (BITS - 1 - k);
+            }
+            for k in s..BITS {
+                v[k] = v[k - s] ^ (v[k - s] >> s);
+                for l in 1..s {
+                    if (a >> (s - 1 - l)) & 1 == 1 {
+                        v[k] ^= v[k - l];
+                    }
+                }
+            }
+            directions.push(v);
+        }
+        Ok(Sobol { directions })
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.directions.len()
+    }
+
+    /// Point `index` as 32-bit integers (divide by `2³²` for `[0, 1)`)
+    pub fn point(&self, index: u32, out: &mut [u32]) {
+        let gray = index ^ (index >> 1);
+        for (x, v) in out.iter_mut().zip(&self.directions) {
+            *x = (0..BITS)
+                .filter(|&k| (gray >> k) & 1 == 1)
+                .fold(0, |acc, k| acc ^ v[k]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sobol_points_and_stratification() {
+        let sobol = Sobol::new(MAX_DIMENSION).expect("Supported dimension");
+        let mut x = vec![0u32; MAX_DIMENSION];
+        let unit = |v: u32| v as f64 / 4_294_967_296.0;
+
+        // Leading points of the first two dimensions
+        let expected = [
+            (0.0, 0.0),
+            (0.5, 0.5),
+            (0.75, 0.25),
+            (0.25, 0.75),
+            (0.375, 0.375),
+            (0.875, 0.875),
+        ];
+        for (i, &(a, b)) in expected.iter().enumerate() {
+            sobol.point(i as u32, &mut x);
+            assert_eq!((unit(x[0]), unit(x[1])), (a, b));
+        }
+
+        // Each dimension of the first 2^m points fills every 2^-m interval
+        let m = 10;
+        for j in 0..MAX_DIMENSION {
+            let mut hit = vec![false; 1 << m];
+            for i in 0..1u32 << m {
+                sobol.point(i, &mut x);
+                hit[(x[j] >> (32 - m)) as usize] = true;
+            }
+            assert!(hit.iter().all(|&h| h), "dimension {}", j + 1);
+        }
+
+        assert!(Sobol::new(MAX_DIMENSION + 1).is_err());
+    }
+}
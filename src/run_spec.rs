@@ -0,0 +1,323 @@
+// src/run_spec.rs
+//! Run specifications for the `fast-sde` command-line pricer
+//!
+//! # Spec Files
+//!
+//! A [`RunSpec`] describes one pricing run in TOML or JSON:
+//! ```text
+//! t = 1.0
+//! greeks = ["delta", "vega"]
+//!
+//! [model]
+//! type = "gbm"            # or "heston" with s0, v0, r, kappa, theta, xi, rho
+//! s0 = 100.0
+//! r = 0.05
+//! sigma = 0.2
+//!
+//! [payoff]
+//! EuropeanCall = { k = 100.0 }
+//!
+//! [simulation]            # optional; defaults shown in SimulationSpec
+//! paths = 200000
+//! antithetic = true
+//! ```
+//! The payoff uses the serialized form of [`Payoff`].
+//!
+//! # Execution
+//!
+//! GBM runs use [`mc_price_option_gbm`] and the Greeks planner
+//! ([`mc_greeks_gbm`]); Heston runs use common-random-number bumping
+//! ([`HestonGreeks`]), which reports delta, gamma and vega with respect to
+//! `v0` but no rho.
+
+use crate::error::{SdeError, SdeResult};
+use crate::math_utils::Timer;
+use crate::mc::greeks_plan::mc_greeks_gbm;
+use crate::mc::heston_greeks::{HestonGreeks, HestonGreeksConfig, HestonSensitivityReport};
+use crate::mc::mc_engine::{mc_price_option_gbm, GreekMethod, GreeksConfig, McConfig};
+use crate::mc::path_failures::PathFailurePolicy;
+use crate::mc::payoffs::Payoff;
+use crate::models::heston::{HestonParams, HestonScheme};
+use crate::rng::SeedStrategy;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Model of a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ModelSpec {
+    /// Black-Scholes dynamics `dS = rS dt + σS dW`
+    Gbm { s0: f64, r: f64, sigma: f64 },
+    /// Heston dynamics; `scheme` defaults to full truncation Euler
+    Heston {
+        #[serde(flatten)]
+        params: HestonParams,
+        #[serde(default = "default_heston_scheme")]
+        scheme: HestonScheme,
+    },
+}
+
+fn default_heston_scheme() -> HestonScheme {
+    HestonScheme::FullTruncationEuler
+}
+
+/// Greek requested in a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Greek {
+    Delta,
+    Gamma,
+    Vega,
+    Rho,
+}
+
+impl Greek {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Greek::Delta => "delta",
+            Greek::Gamma => "gamma",
+            Greek::Vega => "vega",
+            Greek::Rho => "rho",
+        }
+    }
+}
+
+/// Monte Carlo settings of a run (defaults: 100 000 paths, one step,
+/// antithetic draws, no control variate, pathwise Greeks)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationSpec {
+    pub paths: usize,
+    pub steps: usize,
+    pub seed: u64,
+    /// Derivation of the per-path streams from `seed`
+    pub seed_strategy: SeedStrategy,
+    pub antithetic: bool,
+    /// GBM only
+    pub control_variate: bool,
+    /// GBM only
+    pub greek_method: GreekMethod,
+}
+
+impl Default for SimulationSpec {
+    fn default() -> Self {
+        SimulationSpec {
+            paths: 100_000,
+            steps: 1,
+            seed: 12345,
+            seed_strategy: SeedStrategy::Hashed,
+            antithetic: true,
+            control_variate: false,
+            greek_method: GreekMethod::Pathwise,
+        }
+    }
+}
+
+/// A complete pricing run
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RunSpec {
+    pub model: ModelSpec,
+    pub payoff: Payoff,
+    /// Maturity in years
+    pub t: f64,
+    #[serde(default)]
+    pub simulation: SimulationSpec,
+    #[serde(default)]
+    pub greeks: Vec<Greek>,
+}
+
+/// Encoding of a spec file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecFormat {
+    Toml,
+    Json,
+}
+
+impl SpecFormat {
+    /// Format from a `.toml` or `.json` file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "toml" => Some(SpecFormat::Toml),
+            "json" => Some(SpecFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of [`RunSpec::run`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunResult {
+    pub model: String,
+    pub price: f64,
+    /// Standard error reported by the engine (GBM only)
+    pub std_error: Option<f64>,
+    /// Requested Greeks, in request order
+    pub greeks: Vec<(Greek, f64)>,
+    pub elapsed_ms: f64,
+}
+
+impl RunSpec {
+    /// Parse a spec in `format`
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError::InvalidConfiguration` describing the parse error.
+    pub fn parse(text: &str, format: SpecFormat) -> SdeResult<Self> {
+        let parsed = match format {
+            SpecFormat::Toml => toml::from_str(text).map_err(|e| e.to_string()),
+            SpecFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+        };
+        parsed.map_err(|reason| SdeError::InvalidConfiguration {
+            field: "spec".to_string(),
+            reason,
+        })
+    }
+
+    /// Price the payoff and compute the requested Greeks
+    ///
+    /// # Errors
+    ///
+    /// Returns the engine's error for invalid settings, and
+    /// `SdeError::UnsupportedOperation` for rho under Heston.
+    pub fn run(&self) -> SdeResult<RunResult> {
+        let timer = Timer::new();
+        let sim = &self.simulation;
+        let (model, price, std_error, greeks) = match &self.model {
+            ModelSpec::Gbm { s0, r, sigma } => {
+                let mut cfg = McConfig {
+                    paths: sim.paths,
+                    steps: sim.steps,
+                    s0: *s0,
+                    r: *r,
+                    sigma: *sigma,
+                    t: self.t,
+                    use_antithetic: sim.antithetic,
+                    use_control_variate: sim.control_variate,
+                    seed: sim.seed,
+                    seed_strategy: sim.seed_strategy,
+                    payoff: self.payoff.clone(),
+                    greek_method: sim.greek_method,
+                    ..Default::default()
+                };
+                let (price, variance) = mc_price_option_gbm(&cfg)?;
+                cfg.greeks = self
+                    .greeks
+                    .iter()
+                    .fold(GreeksConfig::NONE, |flags, g| flags | greek_flag(*g));
+                let report = if self.greeks.is_empty() {
+                    Default::default()
+                } else {
+                    mc_greeks_gbm(&cfg)?
+                };
+                let greeks = self
+                    .greeks
+                    .iter()
+                    .map(|&g| {
+                        let value = match g {
+                            Greek::Delta => report.delta,
+                            Greek::Gamma => report.gamma,
+                            Greek::Vega => report.vega,
+                            Greek::Rho => report.rho,
+                        };
+                        (g, value.unwrap_or(f64::NAN))
+                    })
+                    .collect();
+                ("gbm", price, Some(variance.sqrt()), greeks)
+            }
+            ModelSpec::Heston { params, scheme } => {
+                let selectors = self
+                    .greeks
+                    .iter()
+                    .map(|&g| heston_selector(g).map(|select| (g, select)))
+                    .collect::<SdeResult<Vec<_>>>()?;
+                let cfg = HestonGreeksConfig {
+                    paths: sim.paths,
+                    steps: sim.steps,
+                    t: self.t,
+                    seed: sim.seed,
+                    seed_strategy: sim.seed_strategy,
+                    payoff: self.payoff.clone(),
+                    use_antithetic: sim.antithetic,
+                    relative_bump: 0.01,
+                    failure_policy: PathFailurePolicy::Fail,
+                };
+                let report = HestonGreeks::new(*params, *scheme)?.compute(&cfg)?;
+                let greeks = selectors
+                    .into_iter()
+                    .map(|(g, select)| (g, select(&report)))
+                    .collect();
+                ("heston", report.price, None, greeks)
+            }
+        };
+        Ok(RunResult {
+            model: model.to_string(),
+            price,
+            std_error,
+            greeks,
+            elapsed_ms: timer.elapsed_ms(),
+        })
+    }
+}
+
+impl RunResult {
+    /// `(quantity, value)` rows: price, standard error, Greeks, time
+    pub fn rows(&self) -> Vec<(String, f64)> {
+        let mut rows = vec![("price".to_string(), self.price)];
+        if let Some(std_error) = self.std_error {
+            rows.push(("std_error".to_string(), std_error));
+        }
+        rows.extend(self.greeks.iter().map(|(g, v)| (g.name().to_string(), *v)));
+        rows.push(("elapsed_ms".to_string(), self.elapsed_ms));
+        rows
+    }
+
+    /// Aligned two-column text table
+    pub fn to_table(&self) -> String {
+        let rows = self.rows();
+        let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        let mut table = format!("{:<width$}  {}\n", "model", self.model, width = width);
+        for (name, value) in rows {
+            table.push_str(&format!("{:<width$}  {:.6}\n", name, value, width = width));
+        }
+        table
+    }
+
+    /// `quantity,value` CSV with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("quantity,value\n");
+        for (name, value) in self.rows() {
+            csv.push_str(&format!("{},{}\n", name, value));
+        }
+        csv
+    }
+
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> SdeResult<String> {
+        serde_json::to_string_pretty(self).map_err(|e| SdeError::InvalidConfiguration {
+            field: "result".to_string(),
+            reason: e.to_string(),
+        })
+    }
+}
+
+/// Field of a Heston report holding `greek`
+fn heston_selector(greek: Greek) -> SdeResult<fn(&HestonSensitivityReport) -> f64> {
+    match greek {
+        Greek::Delta => Ok(|r| r.delta),
+        Greek::Gamma => Ok(|r| r.gamma),
+        Greek::Vega => Ok(|r| r.vega_v0),
+        Greek::Rho => Err(SdeError::UnsupportedOperation {
+            operation: "rho".to_string(),
+            context: "Heston runs bump model parameters, not the rate".to_string(),
+        }),
+    }
+}
+
+fn greek_flag(greek: Greek) -> GreeksConfig {
+    match greek {
+        Greek::Delta => GreeksConfig::DELTA,
+        Greek::Gamma => GreeksConfig::GAMMA,
+        Greek::Vega => GreeksConfig::VEGA,
+        Greek::Rho => GreeksConfig::RHO,
+    }
+}
@@ -0,0 +1,256 @@
+// src/solvers/adaptive.rs
+//! Adaptive Time Stepping with Local Error Control
+//!
+//! # Error Estimate
+//!
+//! Each trial step of size `h` is taken with the embedded Euler–Maruyama /
+//! Milstein pair. Both share `ΔW`; their difference
+//! ```text
+//! e_W = ½ b b' (ΔW² - h)
+//! ```
+//! estimates the local strong error of the lower-order solution, and the
+//! Heun-style drift defect
+//! ```text
+//! e_a = ½ (a(X̃_{n+1}) - a(X_n)) h
+//! ```
+//! that of the frozen drift. The step is accepted when
+//! ```text
+//! err = max(|e_W|, |e_a|) / (atol + rtol |X_n|) ≤ 1
+//! ```
+//! and the Milstein solution is kept. Either way the next trial size is
+//! `h · clamp(safety · err^{-1/2}, ½, 2)`, capped by `dt_max` and the end
+//! of the interval.
+//!
+//! # Brownian Consistency
+//!
+//! A rejected step must not discard its increment: redrawing would bias
+//! the path towards small increments. The solver keeps the not yet used
+//! part of the Brownian path as a queue of intervals `(h_k, ΔW_k)`.
+//! Consecutive intervals are merged by adding increments, and an interval
+//! is split at `τ < h` with the Brownian bridge
+//! ```text
+//! ΔW_[0,τ] = (τ/h) ΔW + √(τ(h - τ)/h) · Z
+//! ```
+//! Fresh increments are drawn only beyond the end of the queue, so the
+//! accepted path is an exact sample of a single Brownian motion whatever
+//! the sequence of rejections (Gaines & Lyons, 1997).
+//!
+//! # When to Use
+//!
+//! - Models whose coefficients vary rapidly in some regions of the state
+//!   space, where a uniform grid fine enough everywhere is wasteful
+//! - Pathwise quantities on a per-path grid; Monte Carlo estimators that
+//!   need a common time grid across paths should use a fixed scheme
+
+use crate::error::{validation::*, SdeError, SdeResult};
+use crate::models::model::SDEModel;
+use crate::rng;
+use rand::Rng;
+use std::collections::VecDeque;
+use std::f64;
+
+/// Tolerances and step size bounds of [`AdaptiveSolver`]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdaptiveConfig {
+    /// Relative local error tolerance (default 1e-3)
+    pub rtol: f64,
+    /// Absolute local error tolerance, positive so that the error scale
+    /// never vanishes (default 1e-6)
+    pub atol: f64,
+    /// First trial step (default 1e-2)
+    pub dt_initial: f64,
+    /// Steps at this size are accepted whatever their error (default 1e-8)
+    pub dt_min: f64,
+    /// Largest step (default 0.1)
+    pub dt_max: f64,
+    /// Safety factor of the step size update (default 0.9)
+    pub safety: f64,
+    /// Accepted plus rejected steps before giving up (default 1 000 000)
+    pub max_steps: usize,
+}
+
+impl Default for AdaptiveConfig {
+    fn default() -> Self {
+        AdaptiveConfig {
+            rtol: 1e-3,
+            atol: 1e-6,
+            dt_initial: 1e-2,
+            dt_min: 1e-8,
+            dt_max: 0.1,
+            safety: 0.9,
+            max_steps: 1_000_000,
+        }
+    }
+}
+
+impl AdaptiveConfig {
+    /// Validate tolerances and step bounds
+    pub fn validate(&self) -> SdeResult<()> {
+        validate_non_negative("rtol", self.rtol)?;
+        validate_positive("atol", self.atol)?;
+        validate_positive("dt_min", self.dt_min)?;
+        validate_range("dt_initial", self.dt_initial, self.dt_min, self.dt_max)?;
+        validate_range("safety", self.safety, f64::MIN_POSITIVE, 1.0)?;
+        if self.max_steps == 0 {
+            return Err(SdeError::InvalidConfiguration {
+                field: "max_steps".to_string(),
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Path produced by [`AdaptiveSolver::solve`]
+#[derive(Debug, Clone, Default)]
+pub struct AdaptivePath {
+    /// Accepted grid, from `t0` to `t_end`
+    pub times: Vec<f64>,
+    pub values: Vec<f64>,
+    /// Driving Brownian motion `W_t - W_{t0}` on the grid
+    pub brownian: Vec<f64>,
+    /// Trial steps rejected by the error test
+    pub rejected: usize,
+}
+
+impl AdaptivePath {
+    /// Accepted steps
+    pub fn accepted(&self) -> usize {
+        self.times.len().saturating_sub(1)
+    }
+
+    pub fn terminal(&self) -> f64 {
+        *self.values.last().unwrap_or(&f64::NAN)
+    }
+}
+
+/// Not yet used part of the Brownian path, as consecutive `(h, ΔW)`
+/// intervals
+#[derive(Default)]
+struct BrownianQueue {
+    intervals: VecDeque<(f64, f64)>,
+}
+
+impl BrownianQueue {
+    /// Increment over the next `h` of the path, consuming it
+    fn take<R: Rng + ?Sized>(&mut self, h: f64, rng: &mut R) -> f64 {
+        let mut remaining = h;
+        let mut dw = 0.0;
+        while let Some((len, inc)) = self.intervals.pop_front() {
+            if len <= remaining * (1.0 + 1e-12) {
+                dw += inc;
+                remaining -= len;
+                if remaining <= 1e-15 * h {
+                    return dw;
+                }
+            } else {
+                // Brownian bridge split at `remaining`
+                let tail = len - remaining;
+                let head = inc * remaining / len
+                    + (remaining * tail / len).sqrt() * rng::get_normal_draw(rng);
+                self.intervals.push_front((tail, inc - head));
+                return dw + head;
+            }
+        }
+        dw + remaining.sqrt() * rng::get_normal_draw(rng)
+    }
+
+    /// Put back a rejected increment at the front of the path
+    fn put_back(&mut self, h: f64, dw: f64) {
+        self.intervals.push_front((h, dw));
+    }
+}
+
+/// Adaptive Milstein solver with an embedded Euler–Maruyama error estimate
+#[derive(Default)]
+pub struct AdaptiveSolver;
+
+impl AdaptiveSolver {
+    pub fn new() -> Self {
+        AdaptiveSolver {}
+    }
+
+    /// Integrate from `(t0, s0)` to `t_end` with per-step error control
+    ///
+    /// # Errors
+    ///
+    /// Returns `SdeError` for an invalid configuration or interval, a
+    /// non-finite state, or more than `cfg.max_steps` trial steps.
+    pub fn solve<M: SDEModel, R: Rng + ?Sized>(
+        model: &M,
+        s0: f64,
+        t0: f64,
+        t_end: f64,
+        cfg: &AdaptiveConfig,
+        rng: &mut R,
+    ) -> SdeResult<AdaptivePath> {
+        cfg.validate()?;
+        validate_finite("s0", s0)?;
+        validate_finite("t0", t0)?;
+        validate_finite("t_end", t_end)?;
+        if t_end <= t0 {
+            return Err(SdeError::InvalidConfiguration {
+                field: "t_end".to_string(),
+                reason: format!("must exceed t0 = {}", t0),
+            });
+        }
+
+        let mut path = AdaptivePath {
+            times: vec![t0],
+            values: vec![s0],
+            brownian: vec![0.0],
+            rejected: 0,
+        };
+        let mut queue = BrownianQueue::default();
+        let (mut t, mut x, mut w) = (t0, s0, 0.0);
+        let mut h = cfg.dt_initial;
+        let span = t_end - t0;
+
+        for _ in 0..cfg.max_steps {
+            let last = t + h >= t_end - 1e-12 * span;
+            if last {
+                h = t_end - t;
+            }
+            let dw = queue.take(h, rng);
+
+            let a = model.drift(x, t);
+            let b = model.diffusion(x, t);
+            let euler = x + a * h + b * dw;
+            let correction = 0.5 * b * model.diffusion_derivative(x, t) * (dw * dw - h);
+            let drift_defect = 0.5 * (model.drift(euler, t + h) - a) * h;
+            let scale = cfg.atol + cfg.rtol * x.abs();
+            let err = correction.abs().max(drift_defect.abs()) / scale;
+
+            if err <= 1.0 || h <= cfg.dt_min {
+                x = euler + correction;
+                if !x.is_finite() {
+                    return Err(SdeError::NumericalInstability {
+                        method: "Adaptive Milstein".to_string(),
+                        reason: format!("non-finite state at t = {}", t + h),
+                    });
+                }
+                t = if last { t_end } else { t + h };
+                w += dw;
+                path.times.push(t);
+                path.values.push(x);
+                path.brownian.push(w);
+                if last {
+                    return Ok(path);
+                }
+            } else {
+                queue.put_back(h, dw);
+                path.rejected += 1;
+            }
+            let factor = (cfg.safety / err.sqrt()).clamp(0.5, 2.0);
+            h = (h * factor).clamp(cfg.dt_min, cfg.dt_max);
+        }
+        Err(SdeError::NumericalInstability {
+            method: "Adaptive Milstein".to_string(),
+            reason: format!(
+                "reached t = {} of {} after max_steps = {}",
+                t, t_end, cfg.max_steps
+            ),
+        })
+    }
+}
@@ -36,6 +36,7 @@ use rand::Rng;
 use std::f64;
 
 /// Euler-Maruyama numerical scheme for SDE integration
+#[derive(Default)]
 pub struct EulerMaruyama;
 
 impl EulerMaruyama {
@@ -65,9 +66,15 @@ impl EulerMaruyama {
         dt: f64,
         rng: &mut R,
     ) {
-        let normal_draw = rng::get_normal_draw(rng);
+        let dw = dt.sqrt() * rng::get_normal_draw(rng);
+        Self::step_with_dw(model, s, t, dt, dw);
+    }
+
+    /// Single Euler-Maruyama step driven by a given Brownian increment
+    /// `dw ~ N(0, Δt)`
+    pub fn step_with_dw<M: SDEModel>(model: &M, s: &mut f64, t: f64, dt: f64, dw: f64) {
         let drift_term = model.drift(*s, t) * dt;
-        let diffusion_term = model.diffusion(*s, t) * dt.sqrt() * normal_draw;
+        let diffusion_term = model.diffusion(*s, t) * dw;
         *s += drift_term + diffusion_term;
     }
 }
@@ -35,6 +35,7 @@ use rand::Rng;
 use std::f64;
 
 /// Milstein numerical scheme for SDE integration
+#[derive(Default)]
 pub struct Milstein;
 
 impl Milstein {
@@ -63,13 +64,17 @@ impl Milstein {
         dt: f64,
         rng: &mut R,
     ) {
-        let normal_draw = rng::get_normal_draw(rng);
+        let dw = dt.sqrt() * rng::get_normal_draw(rng);
+        Self::step_with_dw(model, s, t, dt, dw);
+    }
+
+    /// Single Milstein step driven by a given Brownian increment
+    /// `dw ~ N(0, Δt)`
+    pub fn step_with_dw<M: SDEModel>(model: &M, s: &mut f64, t: f64, dt: f64, dw: f64) {
         let drift_val = model.drift(*s, t);
         let diffusion_val = model.diffusion(*s, t);
         let diffusion_derivative_val = model.diffusion_derivative(*s, t);
 
-        let dw = dt.sqrt() * normal_draw;
-
         // Milstein scheme: Euler + Itô correction
         *s += drift_val * dt
             + diffusion_val * dw
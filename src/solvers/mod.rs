@@ -1,4 +1,7 @@
 // src/solvers/mod.rs
+pub mod adaptive;
 pub mod euler_maruyama;
 pub mod milstein;
+pub mod ninomiya_victoir;
 pub mod srk;
+pub mod wagner_platen;
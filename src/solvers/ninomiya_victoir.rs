@@ -0,0 +1,96 @@
+// src/solvers/ninomiya_victoir.rs
+//! Ninomiya–Victoir Scheme: Weak Order 2 by Splitting
+//!
+//! # Mathematical Framework
+//!
+//! Write the scalar SDE in Stratonovich form, with the vector fields
+//! ```text
+//! V₀(x, t) = a(x, t) - ½ b(x, t) b'(x, t)     (Stratonovich drift)
+//! V₁(x, t) = b(x, t)
+//! ```
+//! and let `exp(s V)` denote the flow of the ODE `dx/du = V(x)` after
+//! "time" `s`. The Ninomiya–Victoir step for a single Brownian motion is the
+//! symmetric (Strang) splitting
+//! ```text
+//! X_{n+1} = exp(½Δt V₀) ∘ exp(ΔW V₁) ∘ exp(½Δt V₀) (X_n)
+//! ```
+//! which matches the Itô–Taylor expansion of `E[f(X_{t+Δt})]` up to
+//! `O(Δt³)` per step. With several Brownian motions the order of the `V_k`
+//! flows is randomised; with one it is not needed.
+//!
+//! # Flows
+//!
+//! Each flow is integrated with one classical Runge–Kutta (RK4) step. The
+//! local RK4 error is `O(|s|⁵)`, i.e. `O(Δt^{5/2})` for the noise flow,
+//! and its leading odd-power term averages out over `±ΔW`, so the weak
+//! order of the scheme is preserved. The noise flow is evaluated at the
+//! midpoint time `t + Δt/2`; the drift flows carry time along.
+//!
+//! # Convergence Properties
+//!
+//! - **Weak convergence**: Order 2.0 (vs 1.0 for Euler-Maruyama)
+//! - **Cost**: Three RK4 flows per step, and `b'` must be available
+//!
+//! # When to Use
+//!
+//! - Smooth payoffs, where halving the weak error needs `√2` times the
+//!   steps instead of twice as many
+//! - Not as input to Richardson extrapolation over steps, which assumes
+//!   a leading `O(Δt)` error
+
+use crate::models::model::SDEModel;
+use crate::rng;
+use rand::Rng;
+use std::f64;
+
+/// Ninomiya–Victoir weak second-order scheme
+#[derive(Default)]
+pub struct NinomiyaVictoir;
+
+impl NinomiyaVictoir {
+    pub fn new() -> Self {
+        NinomiyaVictoir {}
+    }
+
+    /// Single Ninomiya–Victoir step
+    ///
+    /// # Algorithm
+    ///
+    /// 1. Generate normal draw: Z ~ N(0,1), ΔW = √Δt * Z
+    /// 2. Follow the Stratonovich drift for Δt/2
+    /// 3. Follow the diffusion for ΔW
+    /// 4. Follow the Stratonovich drift for Δt/2
+    pub fn step<M: SDEModel, R: Rng + ?Sized>(
+        model: &M,
+        s: &mut f64,
+        t: f64,
+        dt: f64,
+        rng: &mut R,
+    ) {
+        let dw = dt.sqrt() * rng::get_normal_draw(rng);
+        Self::step_with_dw(model, s, t, dt, dw);
+    }
+
+    /// Single Ninomiya–Victoir step driven by a given Brownian increment
+    /// `dw ~ N(0, Δt)`
+    pub fn step_with_dw<M: SDEModel>(model: &M, s: &mut f64, t: f64, dt: f64, dw: f64) {
+        let half = 0.5 * dt;
+        let mid = t + half;
+        let stratonovich_drift = |x: f64, u: f64| {
+            model.drift(x, u) - 0.5 * model.diffusion(x, u) * model.diffusion_derivative(x, u)
+        };
+
+        let x = rk4_flow(*s, t, half, stratonovich_drift);
+        let x = rk4_flow(x, mid, dw, |x, _| model.diffusion(x, mid));
+        *s = rk4_flow(x, mid, half, stratonovich_drift);
+    }
+}
+
+/// One RK4 step of `dx/du = field(x, t + u)` over `u ∈ [0, h]`
+fn rk4_flow(x: f64, t: f64, h: f64, field: impl Fn(f64, f64) -> f64) -> f64 {
+    let k1 = field(x, t);
+    let k2 = field(x + 0.5 * h * k1, t + 0.5 * h);
+    let k3 = field(x + 0.5 * h * k2, t + 0.5 * h);
+    let k4 = field(x + h * k3, t + h);
+    x + h / 6.0 * (k1 + 2.0 * k2 + 2.0 * k3 + k4)
+}
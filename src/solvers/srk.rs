@@ -20,6 +20,11 @@
 //!    X_{n+1} = X_n + ½[a(X_n, t_n) + a(X*, t_{n+1})] Δt + ½[b(X_n, t_n) + b(X*, t_{n+1})] ΔW_n
 //!    ```
 //!
+//! The corrector evaluates the diffusion at both ends of the step, so the
+//! scheme converges to the *Stratonovich* solution. For multiplicative
+//! noise, integrate an Itô SDE by passing the corrected drift
+//! `a - ½ b ∂b/∂x` (e.g. `(r - σ²/2)S` for GBM).
+//!
 //! # Convergence Properties
 //!
 //! - **Strong convergence**: Order 1.0
@@ -38,6 +43,7 @@ use rand::Rng;
 use std::f64;
 
 /// Stochastic Runge-Kutta numerical scheme
+#[derive(Default)]
 pub struct Srk;
 
 impl Srk {
@@ -66,7 +72,11 @@ impl Srk {
         rng: &mut R,
     ) {
         let dw = dt.sqrt() * rng::get_normal_draw(rng);
+        Self::step_with_dw(model, s, t, dt, dw);
+    }
 
+    /// Single SRK step driven by a given Brownian increment `dw ~ N(0, Δt)`
+    pub fn step_with_dw<M: SDEModel>(model: &M, s: &mut f64, t: f64, dt: f64, dw: f64) {
         // Predictor step: Euler-Maruyama to get provisional value
         let s_star = *s + model.drift(*s, t) * dt + model.diffusion(*s, t) * dw;
 
@@ -0,0 +1,120 @@
+// src/solvers/wagner_platen.rs
+//! Wagner–Platen Scheme: Strong Order 1.5 Itô–Taylor Expansion
+//!
+//! # Mathematical Framework
+//!
+//! For the scalar SDE `dX = a(X, t) dt + b(X, t) dW`, the order 1.5 strong
+//! Taylor scheme keeps every Itô–Taylor term with a multiple integral of
+//! order up to 1.5:
+//! ```text
+//! X_{n+1} = X_n + aΔt + bΔW + ½bb'(ΔW² - Δt)
+//!         + a'b ΔZ + ½(aa' + ½b²a'' + ∂a/∂t) Δt²
+//!         + (ab' + ½b²b'' + ∂b/∂t)(ΔW Δt - ΔZ)
+//!         + ½b(bb'' + b'²)(⅓ΔW² - Δt) ΔW
+//! ```
+//! where `'` is `∂/∂x` and `ΔZ = ∫∫ dW ds` is the double integral of the
+//! Brownian motion over the step. `(ΔW, ΔZ)` is Gaussian with
+//! ```text
+//! Var(ΔW) = Δt,   Cov(ΔW, ΔZ) = ½Δt²,   Var(ΔZ) = ⅓Δt³
+//! ```
+//! and is sampled from two independent normals `U₁, U₂` as
+//! `ΔW = U₁√Δt`, `ΔZ = ½Δt^{3/2}(U₁ + U₂/√3)`.
+//!
+//! # Derivatives
+//!
+//! The scheme needs `a'`, `a''` and `b''`, taken from
+//! [`SDEModel::drift_derivative`], [`SDEModel::drift_second_derivative`] and
+//! [`SDEModel::diffusion_second_derivative`]. Models without closed forms
+//! fall back to central finite differences, which keep the order as long as
+//! the coefficients are smooth. Time derivatives are always differenced and
+//! vanish for autonomous models.
+//!
+//! # Convergence Properties
+//!
+//! - **Strong convergence**: Order 1.5 (vs 1.0 for Milstein)
+//! - **Weak convergence**: Order 2.0
+//! - **Cost**: Two normal draws and eight coefficient evaluations per step
+//!
+//! # When to Use
+//!
+//! - Pathwise quantities: hitting times, barrier monitoring, pathwise
+//!   sensitivities, multilevel estimators with fast level-variance decay
+//! - Coarse grids where Milstein's path error is still too large
+
+use crate::models::model::SDEModel;
+use crate::rng;
+use rand::Rng;
+use std::f64;
+
+/// Wagner–Platen strong order 1.5 Taylor scheme
+#[derive(Default)]
+pub struct WagnerPlaten;
+
+impl WagnerPlaten {
+    pub fn new() -> Self {
+        WagnerPlaten {}
+    }
+
+    /// Single Wagner–Platen step
+    ///
+    /// # Algorithm
+    ///
+    /// 1. Generate two normal draws: U₁, U₂ ~ N(0,1)
+    /// 2. Form ΔW = √Δt U₁ and ΔZ = ½Δt^{3/2} (U₁ + U₂/√3)
+    /// 3. Apply the order 1.5 Taylor update
+    pub fn step<M: SDEModel, R: Rng + ?Sized>(
+        model: &M,
+        s: &mut f64,
+        t: f64,
+        dt: f64,
+        rng: &mut R,
+    ) {
+        let u1 = rng::get_normal_draw(rng);
+        let u2 = rng::get_normal_draw(rng);
+        let (dw, dz) = Self::increments(dt, u1, u2);
+        Self::step_with_increments(model, s, t, dt, dw, dz);
+    }
+
+    /// Brownian increment `ΔW` and double integral `ΔZ` from two independent
+    /// standard normals
+    pub fn increments(dt: f64, u1: f64, u2: f64) -> (f64, f64) {
+        let sqrt_dt = dt.sqrt();
+        let dw = sqrt_dt * u1;
+        let dz = 0.5 * dt * sqrt_dt * (u1 + u2 / 3.0_f64.sqrt());
+        (dw, dz)
+    }
+
+    /// Single Wagner–Platen step driven by given increments `(ΔW, ΔZ)`
+    ///
+    /// Both must come from the same Brownian path over `[t, t + dt]`; see
+    /// [`WagnerPlaten::increments`].
+    pub fn step_with_increments<M: SDEModel>(
+        model: &M,
+        s: &mut f64,
+        t: f64,
+        dt: f64,
+        dw: f64,
+        dz: f64,
+    ) {
+        let x = *s;
+        let a = model.drift(x, t);
+        let b = model.diffusion(x, t);
+        let a_x = model.drift_derivative(x, t);
+        let a_xx = model.drift_second_derivative(x, t);
+        let b_x = model.diffusion_derivative(x, t);
+        let b_xx = model.diffusion_second_derivative(x, t);
+
+        let h = 6e-6 * t.abs().max(1.0);
+        let a_t = (model.drift(x, t + h) - model.drift(x, t - h)) / (2.0 * h);
+        let b_t = (model.diffusion(x, t + h) - model.diffusion(x, t - h)) / (2.0 * h);
+
+        *s = x
+            + a * dt
+            + b * dw
+            + 0.5 * b * b_x * (dw * dw - dt)
+            + a_x * b * dz
+            + 0.5 * (a * a_x + 0.5 * b * b * a_xx + a_t) * dt * dt
+            + (a * b_x + 0.5 * b * b * b_xx + b_t) * (dw * dt - dz)
+            + 0.5 * b * (b * b_xx + b_x * b_x) * (dw * dw / 3.0 - dt) * dw;
+    }
+}
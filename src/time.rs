@@ -0,0 +1,187 @@
+// src/time.rs
+//! Dates, Day Counts and Fixing Schedules
+//!
+//! # Year Fractions
+//!
+//! The engine measures time in years. Contracts are specified by dates,
+//! and [`DayCount`] turns a pair of dates into the year fraction between
+//! them:
+//! ```text
+//! ACT/365F   τ = days(d₁, d₂) / 365
+//! ACT/360    τ = days(d₁, d₂) / 360
+//! 30/360     τ = (360 (y₂ - y₁) + 30 (m₂ - m₁) + (D₂ - D₁)) / 360
+//!            D₁ = min(d₁, 30),  D₂ = min(d₂, 30) if D₁ = 30 else d₂
+//! ```
+//! where 30/360 is the US bond basis. ACT/365F is the convention of
+//! [`market_data`](crate::market_data) and the default here.
+//!
+//! # Schedules
+//!
+//! [`fixing_schedule`] rolls back from the final date in whole months, so
+//! a short stub falls at the start and month-end dates stay on the last
+//! day of the month. [`McConfigBuilder::expiry`] and
+//! [`McConfigBuilder::fixing_dates`] set the maturity and the observation
+//! times of a configuration from dates.
+//!
+//! [`McConfigBuilder::expiry`]: crate::mc::config_builder::McConfigBuilder::expiry
+//! [`McConfigBuilder::fixing_dates`]: crate::mc::config_builder::McConfigBuilder::fixing_dates
+
+use crate::error::{SdeError, SdeResult};
+use chrono::{Datelike, Months};
+
+/// Calendar date of expiries and fixings
+pub use chrono::NaiveDate as Date;
+
+/// Day-count convention of year fractions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DayCount {
+    /// Actual days over 365
+    #[default]
+    Act365Fixed,
+    /// Actual days over 360
+    Act360,
+    /// US bond basis 30/360
+    Thirty360,
+}
+
+impl DayCount {
+    /// Days from `start` to `end` under the convention (negative if `end`
+    /// is earlier)
+    pub fn days(&self, start: Date, end: Date) -> i64 {
+        match self {
+            DayCount::Act365Fixed | DayCount::Act360 => (end - start).num_days(),
+            DayCount::Thirty360 => {
+                let d1 = start.day().min(30) as i64;
+                let d2 = if d1 == 30 {
+                    end.day().min(30)
+                } else {
+                    end.day()
+                } as i64;
+                360 * (end.year() - start.year()) as i64
+                    + 30 * (end.month() as i64 - start.month() as i64)
+                    + (d2 - d1)
+            }
+        }
+    }
+
+    /// Year fraction from `start` to `end`
+    pub fn year_fraction(&self, start: Date, end: Date) -> f64 {
+        let basis = match self {
+            DayCount::Act365Fixed => 365.0,
+            DayCount::Act360 | DayCount::Thirty360 => 360.0,
+        };
+        self.days(start, end) as f64 / basis
+    }
+
+    /// Year fractions from `valuation` to each of `dates`
+    pub fn year_fractions(&self, valuation: Date, dates: &[Date]) -> Vec<f64> {
+        dates
+            .iter()
+            .map(|&date| self.year_fraction(valuation, date))
+            .collect()
+    }
+}
+
+/// Dates every `months` months from `end` back to, but excluding, `start`,
+/// in increasing order and ending at `end`
+///
+/// Each date is `end` minus a whole number of periods, clamped to the end
+/// of shorter months, so there is no drift after a short month.
+///
+/// # Errors
+///
+/// Returns `SdeError` if `months` is zero or `end` is not after `start`.
+pub fn fixing_schedule(start: Date, end: Date, months: u32) -> SdeResult<Vec<Date>> {
+    if months == 0 {
+        return Err(SdeError::InvalidConfiguration {
+            field: "months".to_string(),
+            reason: "must be at least 1".to_string(),
+        });
+    }
+    if end <= start {
+        return Err(SdeError::InvalidConfiguration {
+            field: "end".to_string(),
+            reason: format!("{} must be after start {}", end, start),
+        });
+    }
+    let mut dates: Vec<Date> = (0..)
+        .map_while(|k: u32| {
+            k.checked_mul(months)
+                .and_then(|m| end.checked_sub_months(Months::new(m)))
+                .filter(|&date| date > start)
+        })
+        .collect();
+    dates.reverse();
+    Ok(dates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> Date {
+        Date::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_day_counts_and_schedule() {
+        let start = date(2024, 1, 31);
+        let end = date(2024, 7, 31);
+        assert_eq!(DayCount::Act365Fixed.days(start, end), 182);
+        assert!((DayCount::Act365Fixed.year_fraction(start, end) - 182.0 / 365.0).abs() < 1e-15);
+        assert!((DayCount::Act360.year_fraction(start, end) - 182.0 / 360.0).abs() < 1e-15);
+        // 30/360: both month ends count as the 30th
+        assert_eq!(DayCount::Thirty360.days(start, end), 180);
+        assert_eq!(DayCount::Thirty360.year_fraction(start, end), 0.5);
+        assert_eq!(
+            DayCount::Thirty360.days(date(2024, 2, 29), date(2024, 3, 31)),
+            32
+        );
+        assert_eq!(DayCount::Act365Fixed.days(end, start), -182);
+
+        // Rolled back from the end date with month-end clamping and a short
+        // first period
+        let schedule = fixing_schedule(date(2024, 1, 15), date(2024, 8, 31), 2).unwrap();
+        assert_eq!(
+            schedule,
+            [
+                date(2024, 2, 29),
+                date(2024, 4, 30),
+                date(2024, 6, 30),
+                date(2024, 8, 31)
+            ]
+        );
+        let times = DayCount::Act365Fixed.year_fractions(date(2024, 1, 15), &schedule);
+        assert!(times.windows(2).all(|w| w[0] < w[1]));
+        assert!(fixing_schedule(end, start, 1).is_err());
+        assert!(fixing_schedule(start, end, 0).is_err());
+    }
+
+    #[test]
+    fn test_dates_configure_mc() {
+        use crate::mc::mc_engine::McConfig;
+
+        let valuation = date(2024, 3, 15);
+        let cfg = McConfig::builder()
+            .expiry(valuation, date(2025, 3, 15), DayCount::Act365Fixed)
+            .build()
+            .unwrap();
+        assert_eq!(cfg.t, 1.0);
+
+        let fixings = fixing_schedule(valuation, date(2025, 3, 15), 3).unwrap();
+        let cfg = McConfig::builder()
+            .fixing_dates(valuation, &fixings, DayCount::Thirty360)
+            .build()
+            .unwrap();
+        assert_eq!(cfg.observation_times, Some(vec![0.25, 0.5, 0.75, 1.0]));
+        assert_eq!(cfg.t, 1.0);
+
+        // Expired fixings fail validation
+        let past = [date(2024, 1, 2), valuation];
+        assert!(McConfig::builder()
+            .fixing_dates(valuation, &past, DayCount::Act360)
+            .build()
+            .is_err());
+    }
+}
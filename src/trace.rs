@@ -0,0 +1,58 @@
+// src/trace.rs
+//! Optional `tracing` instrumentation
+//!
+//! With the `tracing` feature the engine and the calibrations report
+//! through the `tracing` crate; install any subscriber to collect them.
+//! Without it the macros below expand to nothing.
+//!
+//! # Spans
+//!
+//! ```text
+//! mc_price_option_gbm{paths, steps, antithetic, control_variate}
+//!   simulate                    first pass: payoff and control moments
+//!   control_variate{controls}   second pass with the fitted coefficients
+//! mc_price_with_progress{paths, chunk_paths}
+//! calibrate_heston{quotes}, calibrate_merton{quotes},
+//! calibrate_sabr_smile{quotes, expiry}
+//!   differential_evolution{population, generations}
+//!   levenberg_marquardt{parameters}
+//! ```
+//!
+//! # Events
+//!
+//! - `DEBUG` per optimizer iteration (objective, damping or population
+//!   spread) and per progress chunk
+//! - `WARN` when Heston parameters violate the Feller condition
+
+/// Enter an `INFO` span until the returned guard is dropped
+macro_rules! span {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        let guard = tracing::info_span!($($arg)*).entered();
+        #[cfg(not(feature = "tracing"))]
+        let guard = $crate::trace::NoSpan;
+        guard
+    }};
+}
+
+/// Emit a `DEBUG` event
+macro_rules! debug_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+/// Emit a `WARN` event
+macro_rules! warn_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::warn!($($arg)*);
+    };
+}
+
+pub(crate) use {debug_event, span, warn_event};
+
+/// Guard returned by [`span!`] without the `tracing` feature
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
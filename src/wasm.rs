@@ -0,0 +1,90 @@
+// src/wasm.rs
+//! WebAssembly bindings (wasm-bindgen)
+//!
+//! Browser-side pricing with the sequential engine. Build with
+//! ```text
+//! wasm-pack build --target web -- --no-default-features --features wasm
+//! ```
+//! Structured inputs and outputs are JSON strings in the serialized forms
+//! of the library types, so partial configs fall back to their defaults:
+//! ```text
+//! import init, { priceGbm } from "./pkg/fast_sde.js";
+//! await init();
+//! const { price, std_error } = JSON.parse(priceGbm(JSON.stringify({
+//!     paths: 20000, sigma: 0.25, payoff: { EuropeanCall: { k: 100 } },
+//! })));
+//! ```
+//! Errors are thrown as JavaScript `Error`s carrying the `SdeError` message.
+
+use crate::analytics::bs_analytic::{bs_call_implied_vol, bs_call_price, bs_put_price};
+use crate::analytics::heston_analytic::{heston_call_price, heston_put_price};
+use crate::mc::chain::mc_price_chain;
+use crate::mc::greeks_plan::mc_greeks_gbm;
+use crate::mc::mc_engine::{mc_price_option_gbm, McConfig};
+use crate::models::heston::HestonParams;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+fn parse<T: DeserializeOwned>(what: &str, json: &str) -> Result<T, JsError> {
+    serde_json::from_str(json).map_err(|e| JsError::new(&format!("{}: {}", what, e)))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, JsError> {
+    serde_json::to_string(value).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// `{"price", "std_error"}` of the payoff of a JSON [`McConfig`]
+#[wasm_bindgen(js_name = priceGbm)]
+pub fn price_gbm(config: &str) -> Result<String, JsError> {
+    let cfg: McConfig = parse("config", config)?;
+    let (price, variance) = mc_price_option_gbm(&cfg)?;
+    to_json(&serde_json::json!({ "price": price, "std_error": variance.sqrt() }))
+}
+
+/// [`GreeksReport`](crate::mc::greeks_plan::GreeksReport) JSON for the
+/// Greeks requested in the config's `greeks` field (e.g. `"DELTA | VEGA"`)
+#[wasm_bindgen(js_name = greeksGbm)]
+pub fn greeks_gbm(config: &str) -> Result<String, JsError> {
+    let cfg: McConfig = parse("config", config)?;
+    to_json(&mc_greeks_gbm(&cfg)?)
+}
+
+/// [`ChainResult`](crate::mc::chain::ChainResult) JSON for a strike ×
+/// maturity grid
+#[wasm_bindgen(js_name = priceChain)]
+pub fn price_chain(config: &str, strikes: &[f64], maturities: &[f64]) -> Result<String, JsError> {
+    let cfg: McConfig = parse("config", config)?;
+    to_json(&mc_price_chain(&cfg, strikes, maturities)?)
+}
+
+/// Semi-analytic Heston price of a European call (or put) for JSON
+/// [`HestonParams`]
+#[wasm_bindgen(js_name = hestonPrice)]
+pub fn heston_price(params: &str, k: f64, t: f64, call: bool) -> Result<f64, JsError> {
+    let params: HestonParams = parse("params", params)?;
+    let price = if call {
+        heston_call_price(&params, k, t)?
+    } else {
+        heston_put_price(&params, k, t)?
+    };
+    Ok(price)
+}
+
+/// Black-Scholes European call price
+#[wasm_bindgen(js_name = bsCallPrice)]
+pub fn bs_call(s: f64, k: f64, r: f64, sigma: f64, t: f64) -> f64 {
+    bs_call_price(s, k, r, sigma, t)
+}
+
+/// Black-Scholes European put price
+#[wasm_bindgen(js_name = bsPutPrice)]
+pub fn bs_put(s: f64, k: f64, r: f64, sigma: f64, t: f64) -> f64 {
+    bs_put_price(s, k, r, sigma, t)
+}
+
+/// Black-Scholes implied volatility of a European call price
+#[wasm_bindgen(js_name = bsCallImpliedVol)]
+pub fn bs_implied_vol(price: f64, s: f64, k: f64, r: f64, t: f64) -> Result<f64, JsError> {
+    Ok(bs_call_implied_vol(price, s, k, r, t)?)
+}
@@ -0,0 +1,79 @@
+// tests/examples_test.rs
+use fast_sde::analytics::bs_analytic;
+use fast_sde::examples::heston_calibration::{
+    run_heston_grid_calibration, CalibrationInputs, HestonGrid,
+};
+use fast_sde::examples::vanilla::{
+    run_exotic_price_report, run_vanilla_vs_analytic_report, VanillaReportInputs,
+};
+use fast_sde::models::heston::HestonScheme;
+
+#[test]
+fn test_vanilla_vs_analytic_report() {
+    let inputs = VanillaReportInputs {
+        paths: 50_000,
+        steps: 1,
+        ..Default::default()
+    };
+    let report = run_vanilla_vs_analytic_report(&inputs).expect("Valid inputs");
+    assert!(report.price.rel_error < 0.02, "{:?}", report.price);
+    assert!(report.delta.abs_error < 0.01, "{:?}", report.delta);
+    assert!(report.vega.rel_error < 0.03, "{:?}", report.vega);
+    assert!(report.rho.rel_error < 0.03, "{:?}", report.rho);
+    assert!(report.gamma.rel_error < 0.1, "{:?}", report.gamma);
+    assert_eq!(report.summary_rows().len(), 25);
+
+    let bad = VanillaReportInputs {
+        sigma: -0.2,
+        ..inputs
+    };
+    assert!(run_vanilla_vs_analytic_report(&bad).is_err());
+}
+
+#[test]
+fn test_exotic_price_report_is_ordered() {
+    let inputs = VanillaReportInputs {
+        paths: 10_000,
+        steps: 50,
+        ..Default::default()
+    };
+    let report = run_exotic_price_report(&inputs).expect("Valid inputs");
+    let vanilla = bs_analytic::bs_call_price(100.0, 100.0, 0.01, 0.2, 1.0);
+    // Averaging and knock-outs both cheapen the call
+    assert!(report.asian_call.price < vanilla);
+    assert!(report.barrier_call_up_and_out.price < vanilla);
+    assert!(report.barrier_call_up_and_out.price > 0.0);
+    assert!(report.barrier_put_up_and_out.price > 0.0);
+    assert!(report.asian_call.std_error > 0.0);
+}
+
+#[test]
+fn test_heston_grid_calibration_recovers_grid_point() {
+    // Quotes generated by the model itself are refit exactly
+    let mut inputs = CalibrationInputs {
+        paths: 2_000,
+        steps: 20,
+        scheme: HestonScheme::FullTruncationEuler,
+        ..Default::default()
+    };
+    let grid = HestonGrid {
+        kappa: vec![1.0, 3.0],
+        theta: vec![0.02, 0.04, 0.08],
+        xi: vec![0.2, 0.5],
+        rho: vec![-0.5],
+    };
+    let truth = run_heston_grid_calibration(&inputs, &grid).expect("Valid inputs");
+    for (point, fit) in inputs.market_data.iter_mut().zip(&truth.fits) {
+        point.market_price = fit.model_price;
+    }
+    let refit = run_heston_grid_calibration(&inputs, &grid).expect("Valid inputs");
+    assert_eq!(refit.params.kappa, truth.params.kappa);
+    assert_eq!(refit.params.theta, truth.params.theta);
+    assert!(refit.sse < 1e-20);
+    // Most ξ = 0.5 points violate the Feller condition
+    assert_eq!(refit.evaluated + refit.skipped, 12);
+    assert!(refit.skipped > 0);
+
+    inputs.market_data.clear();
+    assert!(run_heston_grid_calibration(&inputs, &grid).is_err());
+}
@@ -1,11 +1,16 @@
 // tests/greeks_test.rs
-use fast_sde::analytics::bs_analytic;
+use fast_sde::analytics::{asian_analytic, bs_analytic};
+use fast_sde::mc::barrier_smoothing::mc_smoothed_barrier_greeks_gbm;
 use fast_sde::mc::mc_engine::{
-    mc_delta_european_call_gbm_pathwise, mc_gamma_european_call_gbm_finite_diff,
-    mc_gamma_european_call_gbm_finite_diff_batched, mc_rho_european_call_gbm_pathwise,
-    mc_vega_european_call_gbm_pathwise, GreeksConfig, McConfig,
+    mc_delta_european_call_gbm_pathwise, mc_delta_gbm, mc_gamma_european_call_gbm_finite_diff,
+    mc_gamma_european_call_gbm_finite_diff_batched, mc_gamma_gbm, mc_price_option_gbm,
+    mc_rho_european_call_gbm_pathwise, mc_vega_european_call_gbm_pathwise, GreekMethod,
+    GreeksConfig, McConfig,
 };
-use fast_sde::mc::payoffs::Payoff;
+use fast_sde::mc::payoffs::{Payoff, PayoffSmoothing};
+use fast_sde::mc::vibrato::mc_vibrato_greeks_gbm;
+use fast_sde::mc::what_if::{evaluate_path, ScenarioContext};
+use fast_sde::rng;
 
 #[test]
 fn test_mc_delta_pathwise_vs_analytic() {
@@ -79,7 +84,7 @@ fn test_bs_call_vega_analytic() {
     let t = 1.0;
 
     let analytic_vega = bs_analytic::bs_call_vega(s0, k, r, sigma, t);
-    let expected_vega = 37.524034691693792;
+    let expected_vega = 37.52403469169379;
 
     let abs_error = (analytic_vega - expected_vega).abs();
     let rel_error = abs_error / expected_vega;
@@ -427,3 +432,241 @@ fn test_mc_vega_rho_comprehensive_ci() {
         rho_ci_95_hi
     );
 }
+
+#[test]
+fn test_vibrato_digital_gamma_vs_analytic() {
+    let s0 = 100.0;
+    let k = 105.0;
+    let r = 0.05;
+    let sigma = 0.20;
+    let t = 1.0;
+
+    let cfg = McConfig {
+        paths: 200_000,
+        seed: 42,
+        s0,
+        r,
+        sigma,
+        t,
+        payoff: Payoff::DigitalCall { k },
+        use_control_variate: false,
+        greek_method: GreekMethod::Vibrato,
+        ..Default::default()
+    };
+
+    let vibrato = mc_vibrato_greeks_gbm(&cfg).expect("Valid configuration");
+    let analytic_delta = bs_analytic::bs_digital_call_delta(s0, k, r, sigma, t);
+    let analytic_gamma = bs_analytic::bs_digital_call_gamma(s0, k, r, sigma, t);
+
+    println!("\n=== Vibrato Digital Greeks ===");
+    println!(
+        "Delta: {} ± {} (analytic {})",
+        vibrato.delta, vibrato.delta_std_error, analytic_delta
+    );
+    println!(
+        "Gamma: {} ± {} (analytic {})",
+        vibrato.gamma, vibrato.gamma_std_error, analytic_gamma
+    );
+
+    assert!(
+        (vibrato.delta - analytic_delta).abs() < 4.0 * vibrato.delta_std_error,
+        "Vibrato Delta outside 4 standard errors"
+    );
+    assert!(
+        (vibrato.gamma - analytic_gamma).abs() < 4.0 * vibrato.gamma_std_error,
+        "Vibrato Gamma outside 4 standard errors"
+    );
+
+    // The dispatching entry point must agree with the direct call
+    let gamma = mc_gamma_gbm(&cfg).expect("Valid configuration");
+    assert_eq!(gamma, vibrato.gamma);
+
+    // Pathwise cannot differentiate a digital and must say so
+    let pathwise_cfg = McConfig {
+        greek_method: GreekMethod::Pathwise,
+        ..cfg
+    };
+    assert!(mc_gamma_gbm(&pathwise_cfg).is_err());
+}
+
+#[test]
+fn test_vibrato_asian_greeks_vs_curran() {
+    let s0 = 100.0;
+    let k = 100.0;
+    let r = 0.05;
+    let sigma = 0.20;
+    let t = 1.0;
+    let steps = 12;
+
+    let cfg = McConfig {
+        paths: 50_000,
+        steps,
+        seed: 7,
+        s0,
+        r,
+        sigma,
+        t,
+        payoff: Payoff::AsianCall { k },
+        use_control_variate: false,
+        greek_method: GreekMethod::Vibrato,
+        ..Default::default()
+    };
+
+    let vibrato = mc_vibrato_greeks_gbm(&cfg).expect("Valid configuration");
+    let curran_delta = asian_analytic::curran_asian_call_delta(s0, k, r, sigma, t, steps);
+    let curran_gamma = asian_analytic::curran_asian_call_gamma(s0, k, r, sigma, t, steps);
+    let tw_delta = asian_analytic::tw_asian_call_delta(s0, k, r, sigma, t, steps);
+
+    println!("\n=== Vibrato Asian Greeks ===");
+    println!(
+        "Delta: {} ± {} (Curran {}, Turnbull-Wakeman {})",
+        vibrato.delta, vibrato.delta_std_error, curran_delta, tw_delta
+    );
+    println!(
+        "Gamma: {} ± {} (Curran {})",
+        vibrato.gamma, vibrato.gamma_std_error, curran_gamma
+    );
+
+    // Curran's approximation error is far below the Monte Carlo error here
+    assert!(
+        (vibrato.delta - curran_delta).abs() < 4.0 * vibrato.delta_std_error,
+        "Vibrato Asian Delta outside 4 standard errors of Curran"
+    );
+    assert!(
+        (vibrato.gamma - curran_gamma).abs() < 4.0 * vibrato.gamma_std_error,
+        "Vibrato Asian Gamma outside 4 standard errors of Curran"
+    );
+
+    // Pathwise Vega averaged over simulated paths (the Asian payoff is Lipschitz)
+    let ctx = ScenarioContext { r, sigma, t };
+    let dt = t / steps as f64;
+    let vegas: Vec<f64> = (0..cfg.paths)
+        .map(|i| {
+            let mut rng = rng::seed_rng_from_u64(cfg.seed + i as u64);
+            let mut path = vec![s0];
+            for _ in 0..steps {
+                let z = rng::get_normal_draw(&mut rng);
+                let last = *path.last().unwrap();
+                path.push(last * ((r - 0.5 * sigma * sigma) * dt + sigma * dt.sqrt() * z).exp());
+            }
+            evaluate_path(&cfg.payoff, &path, &ctx).unwrap().vega
+        })
+        .collect();
+    let n = vegas.len() as f64;
+    let mc_vega = vegas.iter().sum::<f64>() / n;
+    let vega_se = (vegas.iter().map(|v| (v - mc_vega).powi(2)).sum::<f64>() / (n - 1.0) / n).sqrt();
+    let curran_vega = asian_analytic::curran_asian_call_vega(s0, k, r, sigma, t, steps);
+    println!("Vega: {} ± {} (Curran {})", mc_vega, vega_se, curran_vega);
+    assert!(
+        (mc_vega - curran_vega).abs() < 4.0 * vega_se,
+        "Pathwise Asian Vega outside 4 standard errors of Curran"
+    );
+}
+
+#[test]
+fn test_smoothed_barrier_greeks_vs_continuous_analytic() {
+    let s0 = 100.0;
+    let k = 100.0;
+    let h = 130.0;
+    let r = 0.05;
+    let sigma = 0.20;
+    let t = 1.0;
+
+    let cfg = McConfig {
+        paths: 50_000,
+        steps: 20,
+        seed: 11,
+        s0,
+        r,
+        sigma,
+        t,
+        payoff: Payoff::BarrierCallUpAndOut { k, h },
+        use_control_variate: false,
+        smoothing: PayoffSmoothing::BrownianBridge,
+        ..Default::default()
+    };
+
+    let smoothed = mc_smoothed_barrier_greeks_gbm(&cfg).expect("Valid configuration");
+    let price = |s: f64, vol: f64| bs_analytic::bs_up_and_out_call_price(s, k, h, r, vol, t);
+    let analytic_price = price(s0, sigma);
+    let analytic_delta = (price(s0 + 1e-3, sigma) - price(s0 - 1e-3, sigma)) / 2e-3;
+    let analytic_vega = (price(s0, sigma + 1e-5) - price(s0, sigma - 1e-5)) / 2e-5;
+
+    println!("\n=== Smoothed Up-and-Out Call ===");
+    println!(
+        "Price: {} (continuous analytic {})",
+        smoothed.price, analytic_price
+    );
+    println!(
+        "Delta: {} ± {} (analytic {})",
+        smoothed.delta, smoothed.delta_std_error, analytic_delta
+    );
+    println!(
+        "Vega: {} ± {} (analytic {})",
+        smoothed.vega, smoothed.vega_std_error, analytic_vega
+    );
+
+    assert!(
+        (smoothed.delta - analytic_delta).abs() < 4.0 * smoothed.delta_std_error,
+        "Smoothed barrier Delta outside 4 standard errors"
+    );
+    assert!(
+        (smoothed.vega - analytic_vega).abs() < 4.0 * smoothed.vega_std_error,
+        "Smoothed barrier Vega outside 4 standard errors"
+    );
+
+    // Pathwise Delta dispatches to the smoothed estimator, and is refused
+    // on the raw indicator
+    assert_eq!(mc_delta_gbm(&cfg).unwrap(), smoothed.delta);
+    let unsmoothed = McConfig {
+        smoothing: PayoffSmoothing::None,
+        ..cfg
+    };
+    assert!(mc_delta_gbm(&unsmoothed).is_err());
+}
+
+#[test]
+fn test_vibrato_asian_greeks_vs_finite_difference() {
+    let cfg = McConfig {
+        paths: 50_000,
+        steps: 12,
+        seed: 11,
+        payoff: Payoff::AsianCall { k: 100.0 },
+        use_control_variate: false,
+        greek_method: GreekMethod::Vibrato,
+        ..Default::default()
+    };
+    let vibrato = mc_vibrato_greeks_gbm(&cfg).expect("Valid configuration");
+
+    // Central differences with common random numbers; the Asian payoff is
+    // Lipschitz, so the bumped prices share almost all of their noise
+    let h = 2.0;
+    let price_at = |s0: f64| {
+        mc_price_option_gbm(&McConfig { s0, ..cfg.clone() })
+            .expect("Valid configuration")
+            .0
+    };
+    let (up, mid, down) = (price_at(cfg.s0 + h), price_at(cfg.s0), price_at(cfg.s0 - h));
+    let fd_delta = (up - down) / (2.0 * h);
+    let fd_gamma = (up - 2.0 * mid + down) / (h * h);
+
+    println!("\n=== Vibrato Asian Greeks vs Finite Differences ===");
+    println!(
+        "Delta: {} ± {} (FD {})",
+        vibrato.delta, vibrato.delta_std_error, fd_delta
+    );
+    println!(
+        "Gamma: {} ± {} (FD {})",
+        vibrato.gamma, vibrato.gamma_std_error, fd_gamma
+    );
+
+    // Without the initial-fixing term Delta is low by about P(A > K)/13
+    assert!(
+        (vibrato.delta - fd_delta).abs() < 4.0 * vibrato.delta_std_error + 2e-3,
+        "Vibrato Asian Delta disagrees with finite differences"
+    );
+    assert!(
+        (vibrato.gamma - fd_gamma).abs() < 4.0 * vibrato.gamma_std_error + 2e-3,
+        "Vibrato Asian Gamma disagrees with finite differences"
+    );
+}
@@ -1,7 +1,13 @@
 // tests/integration_test.rs
 use fast_sde::analytics::bs_analytic;
 use fast_sde::mc::mc_engine::{mc_price_option_gbm, McConfig};
-use fast_sde::mc::payoffs::Payoff;
+use fast_sde::mc::normal_source::{
+    MomentMatched, NormalSource, PseudoRandom, SamplerKind, SobolSource, Stratified,
+};
+use fast_sde::mc::path_construction::PathConstruction;
+use fast_sde::mc::payoffs::{AutocallObservation, BarrierShift, Payoff};
+use fast_sde::rng::SeedStrategy;
+use std::sync::Arc;
 
 #[test]
 fn test_bs_mc_vs_analytic() {
@@ -130,3 +136,523 @@ fn test_asian_option_cv() {
         vrf
     );
 }
+
+#[test]
+fn test_sparse_observation_matches_uniform_grid() {
+    let k = 100.0;
+    let monthly: Vec<f64> = (1..=12).map(|m| m as f64 / 12.0).collect();
+
+    let uniform = McConfig {
+        paths: 20_000,
+        steps: 12,
+        seed: 3,
+        payoff: Payoff::AsianCall { k },
+        use_control_variate: false,
+        ..Default::default()
+    };
+    let sparse = McConfig {
+        steps: 1,
+        observation_times: Some(monthly),
+        ..uniform.clone()
+    };
+
+    // Same fixing dates and draws: the sparse path is the uniform path
+    let (uniform_price, _) = mc_price_option_gbm(&uniform).expect("Valid configuration");
+    let (sparse_price, _) = mc_price_option_gbm(&sparse).expect("Valid configuration");
+    assert!((uniform_price - sparse_price).abs() < 1e-10);
+
+    // Uneven dates still sample the terminal distribution exactly
+    let european = McConfig {
+        paths: 100_000,
+        payoff: Payoff::EuropeanCall { k },
+        observation_times: Some(vec![0.1, 0.55, 1.0]),
+        ..sparse.clone()
+    };
+    let (price, _) = mc_price_option_gbm(&european).expect("Valid configuration");
+    let analytic = bs_analytic::bs_call_price(100.0, k, 0.01, 0.2, 1.0);
+    // About 4 standard errors of a 100k-path European call price
+    assert!(
+        (price - analytic).abs() < 0.15,
+        "Sparse European price {} vs analytic {}",
+        price,
+        analytic
+    );
+
+    let bad = McConfig {
+        observation_times: Some(vec![0.5, 0.25, 1.0]),
+        ..sparse
+    };
+    assert!(mc_price_option_gbm(&bad).is_err());
+}
+
+#[test]
+fn test_barrier_shift_continuity_correction() {
+    let (s0, k, h, r, sigma, t) = (100.0, 100.0, 130.0, 0.05, 0.2, 1.0);
+    let cfg = McConfig {
+        paths: 100_000,
+        steps: 50,
+        seed: 21,
+        s0,
+        r,
+        sigma,
+        t,
+        payoff: Payoff::BarrierCallUpAndOut { k, h },
+        use_control_variate: false,
+        ..Default::default()
+    };
+    let continuous = bs_analytic::bs_up_and_out_call_price(s0, k, h, r, sigma, t);
+
+    let (discrete, _) = mc_price_option_gbm(&cfg).expect("Valid configuration");
+    let corrected_cfg = McConfig {
+        barrier_shift: Some(BarrierShift::ContinuityCorrection),
+        ..cfg.clone()
+    };
+    let (corrected, _) = mc_price_option_gbm(&corrected_cfg).expect("Valid configuration");
+
+    println!("\nDiscrete barrier: {}", discrete);
+    println!("BGK-shifted barrier: {}", corrected);
+    println!("Continuous analytic: {}", continuous);
+
+    // Discrete monitoring overprices the knock-out; the shift removes most of it
+    assert!(discrete > continuous + 0.2);
+    assert!(
+        (corrected - continuous).abs() < 0.1,
+        "Corrected price {} too far from continuous {}",
+        corrected,
+        continuous
+    );
+
+    // Moving an up-and-out barrier up makes the option more valuable
+    let widened = McConfig {
+        barrier_shift: Some(BarrierShift::Absolute(5.0)),
+        ..cfg.clone()
+    };
+    assert!(mc_price_option_gbm(&widened).unwrap().0 > discrete);
+
+    let invalid = McConfig {
+        barrier_shift: Some(BarrierShift::Relative(-1.5)),
+        ..cfg
+    };
+    assert!(mc_price_option_gbm(&invalid).is_err());
+}
+
+#[test]
+fn test_early_termination_of_knocked_out_paths() {
+    let cfg = McConfig {
+        paths: 50_000,
+        steps: 100,
+        seed: 8,
+        payoff: Payoff::BarrierCallUpAndOut { k: 100.0, h: 115.0 },
+        use_antithetic: false,
+        use_control_variate: false,
+        ..Default::default()
+    };
+    let early = McConfig {
+        early_termination: true,
+        ..cfg.clone()
+    };
+
+    // Without antithetics the truncated paths give exactly the same sample
+    let (full, _) = mc_price_option_gbm(&cfg).expect("Valid configuration");
+    let (truncated, _) = mc_price_option_gbm(&early).expect("Valid configuration");
+    assert_eq!(full, truncated);
+
+    // With antithetics the partner paths differ but the estimate agrees
+    let (full, _) = mc_price_option_gbm(&McConfig {
+        use_antithetic: true,
+        ..cfg
+    })
+    .expect("Valid configuration");
+    let (truncated, _) = mc_price_option_gbm(&McConfig {
+        use_antithetic: true,
+        ..early
+    })
+    .expect("Valid configuration");
+    println!("\nFull paths: {}, early termination: {}", full, truncated);
+    assert!((full - truncated).abs() < 0.04);
+}
+
+#[test]
+fn test_deterministic_mode_is_independent_of_thread_count() {
+    let cfg = McConfig {
+        paths: 20_000,
+        steps: 12,
+        seed: 11,
+        payoff: Payoff::AsianCall { k: 100.0 },
+        deterministic: true,
+        ..Default::default()
+    };
+    let run_on = |threads: usize| {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .expect("Thread pool")
+            .install(|| mc_price_option_gbm(&cfg).expect("Valid configuration"))
+    };
+
+    // Bitwise identical whatever the scheduling
+    let single = run_on(1);
+    for threads in [2, 3, 8] {
+        let (price, variance) = run_on(threads);
+        assert_eq!(price.to_bits(), single.0.to_bits());
+        assert_eq!(variance.to_bits(), single.1.to_bits());
+    }
+
+    // Counter streams give a different sample of the same price
+    let (default_streams, _) = mc_price_option_gbm(&McConfig {
+        deterministic: false,
+        ..cfg.clone()
+    })
+    .expect("Valid configuration");
+    assert_ne!(default_streams, single.0);
+    assert!((default_streams - single.0).abs() < 0.05);
+}
+
+#[test]
+fn test_normal_sources_price_european_call() {
+    let (s0, k, r, sigma, t) = (100.0, 105.0, 0.03, 0.2, 1.0);
+    let bs = bs_analytic::bs_call_price(s0, k, r, sigma, t);
+    let base = McConfig {
+        s0,
+        r,
+        sigma,
+        t,
+        paths: 8_000,
+        steps: 16,
+        seed: 3,
+        use_antithetic: false,
+        use_control_variate: false,
+        payoff: Payoff::EuropeanCall { k },
+        ..Default::default()
+    };
+    // 8_000 blocks of 16 samples each; the standard error is about 0.02
+    let sources: Vec<Arc<dyn NormalSource>> = vec![
+        Arc::new(SobolSource {
+            seed: 3,
+            points: 16,
+            strategy: SeedStrategy::Hashed,
+        }),
+        Arc::new(Stratified {
+            seed: 3,
+            samples: 16,
+            strategy: SeedStrategy::Hashed,
+        }),
+        Arc::new(MomentMatched {
+            inner: PseudoRandom {
+                seed: 3,
+                counter_based: false,
+                strategy: SeedStrategy::Hashed,
+            },
+            blocks: 16,
+        }),
+        SamplerKind::Halton.source(3, SeedStrategy::Hashed, 16),
+        // Base 17 for 16 dimensions: one complete net per block
+        SamplerKind::Faure.source(3, SeedStrategy::Hashed, 17),
+    ];
+    for source in sources {
+        let cfg = McConfig {
+            normal_source: Some(source),
+            ..base.clone()
+        };
+        let (price, _) = mc_price_option_gbm(&cfg).expect("Valid configuration");
+        assert!((price - bs).abs() < 0.1, "{} vs {}", price, bs);
+        let (again, _) = mc_price_option_gbm(&cfg).expect("Valid configuration");
+        assert_eq!(price, again);
+    }
+
+    // Bridge and PCA construction of the Sobol paths price the same option
+    for construction in [PathConstruction::BrownianBridge, PathConstruction::Pca] {
+        let cfg = McConfig {
+            normal_source: Some(SamplerKind::Sobol.source(3, SeedStrategy::Hashed, 16)),
+            path_construction: construction,
+            ..base.clone()
+        };
+        let (price, _) = mc_price_option_gbm(&cfg).expect("Valid configuration");
+        assert!(
+            (price - bs).abs() < 0.1,
+            "{:?}: {} vs {}",
+            construction,
+            price,
+            bs
+        );
+    }
+
+    // Sobol points beyond the table are rejected up front
+    let too_many_steps = McConfig {
+        steps: 64,
+        normal_source: Some(Arc::new(SobolSource {
+            seed: 3,
+            points: 16,
+            strategy: SeedStrategy::Hashed,
+        })),
+        ..base
+    };
+    assert!(mc_price_option_gbm(&too_many_steps).is_err());
+    assert!(SamplerKind::Halton
+        .source(3, SeedStrategy::Hashed, 16)
+        .validate(64)
+        .is_ok());
+}
+
+#[test]
+fn test_autocallable_limits_match_closed_forms() {
+    let (s0, r, sigma, t, steps) = (100.0, 0.03, 0.25, 2.0, 8);
+    let notional = 1000.0;
+    let schedule = |barrier: f64| -> Vec<AutocallObservation> {
+        (1..=4)
+            .map(|i| {
+                let date = 0.5 * i as f64;
+                AutocallObservation::on_uniform_grid(date, t, steps, r, barrier, 0.05 * i as f64)
+            })
+            .collect()
+    };
+    let price = |observations: Vec<AutocallObservation>, knock_in: f64| {
+        let cfg = McConfig {
+            paths: 20_000,
+            steps,
+            s0,
+            r,
+            sigma,
+            t,
+            use_control_variate: false,
+            payoff: Payoff::Autocallable {
+                observations,
+                notional,
+                knock_in,
+                put_strike: 100.0,
+            },
+            ..Default::default()
+        };
+        mc_price_option_gbm(&cfg).expect("Valid autocallable")
+    };
+
+    // Called on the first date for sure: N (1 + c₁) e^(-r t₁)
+    let (called, _) = price(schedule(1e-9), 70.0);
+    let expected = notional * 1.05 * (-r * 0.5).exp();
+    assert!((called - expected).abs() < 1e-9 * expected);
+
+    // Never called, never knocked in: a zero-coupon bond
+    let (bond, _) = price(schedule(1e9), 0.0);
+    assert!((bond - notional * (-r * t).exp()).abs() < 1e-9 * notional);
+
+    // Never called, always knocked in: N/K · E[min(S_T, K)] = N/K (S₀ - C(K))
+    let (put_leg, _) = price(schedule(1e9), 1e9);
+    let expected = notional / 100.0 * (s0 - bs_analytic::bs_call_price(s0, 100.0, r, sigma, t));
+    assert!(
+        (put_leg - expected).abs() < 0.005 * expected,
+        "{} vs {}",
+        put_leg,
+        expected
+    );
+
+    // A realistic note sits between the knocked-in note and the bond plus coupons
+    let (note, _) = price(schedule(100.0), 70.0);
+    assert!(note > put_leg && note < notional * 1.2);
+
+    let mut bad = schedule(100.0);
+    bad[1].index = bad[0].index;
+    let cfg = McConfig {
+        steps,
+        payoff: Payoff::Autocallable {
+            observations: bad,
+            notional,
+            knock_in: 70.0,
+            put_strike: 100.0,
+        },
+        ..Default::default()
+    };
+    assert!(mc_price_option_gbm(&cfg).is_err());
+}
+
+#[test]
+fn test_forward_start_call_vs_rubinstein() {
+    let (s0, r, sigma, t) = (100.0, 0.02, 0.3, 1.0);
+    for alpha in [0.9, 1.0, 1.1] {
+        let cfg = McConfig {
+            paths: 50_000,
+            steps: 4,
+            s0,
+            r,
+            sigma,
+            t,
+            use_control_variate: false,
+            payoff: Payoff::ForwardStartCall {
+                start_index: 1,
+                alpha,
+            },
+            ..Default::default()
+        };
+        let (price, variance) = mc_price_option_gbm(&cfg).expect("Valid forward start");
+        let analytic = bs_analytic::bs_forward_start_call_price(s0, alpha, r, sigma, 0.25, t);
+        assert!(
+            (price - analytic).abs() < 4.0 * variance.sqrt(),
+            "alpha {}: {} vs {} ± {}",
+            alpha,
+            price,
+            analytic,
+            variance.sqrt()
+        );
+    }
+
+    let cfg = McConfig {
+        steps: 4,
+        payoff: Payoff::ForwardStartPut {
+            start_index: 4,
+            alpha: 1.0,
+        },
+        ..Default::default()
+    };
+    assert!(mc_price_option_gbm(&cfg).is_err());
+}
+
+#[test]
+fn test_power_and_chooser_vs_closed_forms() {
+    let (s0, r, sigma, t) = (100.0, 0.03, 0.25, 1.0);
+    let base = McConfig {
+        paths: 50_000,
+        steps: 4,
+        s0,
+        r,
+        sigma,
+        t,
+        use_control_variate: false,
+        ..Default::default()
+    };
+
+    for (k, p) in [(10_000.0, 2.0), (10.0, 0.5)] {
+        let cfg = McConfig {
+            payoff: Payoff::PowerCall { k, p },
+            ..base.clone()
+        };
+        let (price, _) = mc_price_option_gbm(&cfg).expect("Valid power call");
+        let analytic = bs_analytic::bs_power_call_price(s0, k, p, r, sigma, t);
+        assert!(
+            (price - analytic).abs() < 0.01 * analytic,
+            "p = {}: {} vs {}",
+            p,
+            price,
+            analytic
+        );
+    }
+
+    let cfg = McConfig {
+        payoff: Payoff::chooser(100.0, 0.5, t, 4, r),
+        ..base.clone()
+    };
+    let (price, _) = mc_price_option_gbm(&cfg).expect("Valid chooser");
+    let analytic = bs_analytic::bs_chooser_price(s0, 100.0, r, sigma, 0.5, t);
+    assert!(
+        (price - analytic).abs() < 0.01 * analytic,
+        "{} vs {}",
+        price,
+        analytic
+    );
+    // Worth more than either the call or the put alone
+    assert!(analytic > bs_analytic::bs_call_price(s0, 100.0, r, sigma, t));
+
+    let late = McConfig {
+        payoff: Payoff::chooser(100.0, 1.0, t, 4, r),
+        ..base
+    };
+    assert!(mc_price_option_gbm(&late).is_err());
+}
+
+#[test]
+fn test_terminal_payoff_fast_path_matches_full_path() {
+    let base = McConfig {
+        paths: 20_000,
+        steps: 16,
+        use_control_variate: false,
+        deterministic: true,
+        ..Default::default()
+    };
+    let call = Payoff::EuropeanCall { k: 105.0 };
+    assert!(!call.requires_full_path());
+    assert_eq!(call.calculate_terminal(110.0), Some(5.0));
+
+    // A forward-start call struck at α S_0 is a European call, but needs
+    // the stored path
+    let forward_start = Payoff::ForwardStartCall {
+        start_index: 0,
+        alpha: 1.05,
+    };
+    assert!(forward_start.requires_full_path());
+    assert_eq!(forward_start.calculate_terminal(110.0), None);
+
+    let price = |payoff: &Payoff| {
+        mc_price_option_gbm(&McConfig {
+            payoff: payoff.clone(),
+            ..base.clone()
+        })
+        .expect("Valid configuration")
+    };
+    let (fast, fast_var) = price(&call);
+    let (full, full_var) = price(&forward_start);
+    assert!((fast - full).abs() < 1e-10, "{} vs {}", fast, full);
+    assert!((fast_var - full_var).abs() < 1e-12);
+}
+
+#[test]
+fn test_incremental_payoffs_match_stored_paths() {
+    let path: Vec<f64> = (0..=200)
+        .map(|i| 100.0 * (0.1 * (i as f64 * 0.37).sin() + 0.0005 * i as f64).exp())
+        .collect();
+    let observations = [(40, 112.0, 0.05), (80, 108.0, 0.10), (200, 100.0, 0.15)].map(
+        |(index, autocall_barrier, coupon)| AutocallObservation {
+            index,
+            autocall_barrier,
+            coupon,
+            carry_to_maturity: 1.0,
+        },
+    );
+    let payoffs = [
+        Payoff::EuropeanCall { k: 100.0 },
+        Payoff::DigitalPut { k: 105.0 },
+        Payoff::AsianCall { k: 98.0 },
+        Payoff::BarrierCallUpAndOut { k: 95.0, h: 130.0 },
+        Payoff::BarrierPutUpAndOut { k: 105.0, h: 109.0 },
+        Payoff::ForwardStartCall {
+            start_index: 50,
+            alpha: 0.95,
+        },
+        Payoff::chooser(100.0, 0.5, 1.0, 200, 0.03),
+        Payoff::Autocallable {
+            observations: observations.to_vec(),
+            notional: 100.0,
+            knock_in: 92.0,
+            put_strike: 100.0,
+        },
+    ];
+    for payoff in &payoffs {
+        let mut state = payoff.start();
+        path.iter().for_each(|&price| state.observe(price));
+        assert_eq!(
+            state.value().to_bits(),
+            payoff.calculate(&path).to_bits(),
+            "{:?}",
+            payoff
+        );
+    }
+
+    // The engine streams path-dependent payoffs; stopping knocked-out paths
+    // early leaves the price unchanged
+    let cfg = McConfig {
+        paths: 5_000,
+        steps: 50,
+        use_control_variate: false,
+        payoff: Payoff::BarrierCallUpAndOut { k: 100.0, h: 130.0 },
+        ..Default::default()
+    };
+    let (streamed, _) = mc_price_option_gbm(&cfg).expect("Valid configuration");
+    let (stored, _) = mc_price_option_gbm(&McConfig {
+        early_termination: true,
+        ..cfg.clone()
+    })
+    .expect("Valid configuration");
+    assert!(
+        (streamed - stored).abs() < 1e-10,
+        "{} vs {}",
+        streamed,
+        stored
+    );
+}
@@ -0,0 +1,63 @@
+// tests/run_spec_test.rs
+use fast_sde::analytics::bs_analytic::bs_call_price;
+use fast_sde::error::SdeError;
+use fast_sde::run_spec::{Greek, RunSpec, SpecFormat};
+
+const GBM_SPEC: &str = r#"
+t = 1.0
+greeks = ["delta", "vega"]
+
+[model]
+type = "gbm"
+s0 = 100.0
+r = 0.05
+sigma = 0.2
+
+[payoff]
+EuropeanCall = { k = 100.0 }
+
+[simulation]
+paths = 50000
+# The draws this test's tolerances were set on
+seed_strategy = "Sequential"
+"#;
+
+#[test]
+fn test_gbm_toml_spec_runs() {
+    let spec = RunSpec::parse(GBM_SPEC, SpecFormat::Toml).expect("Valid spec");
+    assert_eq!(spec.simulation.steps, 1);
+    let result = spec.run().expect("Run succeeds");
+
+    let analytic = bs_call_price(100.0, 100.0, 0.05, 0.2, 1.0);
+    let std_error = result.std_error.expect("GBM reports a standard error");
+    assert!((result.price - analytic).abs() < 4.0 * std_error + 1e-3);
+    assert_eq!(result.greeks.len(), 2);
+    assert_eq!(result.greeks[0].0, Greek::Delta);
+    assert!((result.greeks[0].1 - 0.6368).abs() < 0.02);
+
+    let names: Vec<String> = result.rows().into_iter().map(|(n, _)| n).collect();
+    assert_eq!(names, ["price", "std_error", "delta", "vega", "elapsed_ms"]);
+    assert!(result.to_csv().starts_with("quantity,value\nprice,"));
+}
+
+#[test]
+fn test_heston_json_spec_rejects_rho() {
+    let spec = r#"{
+        "t": 0.5,
+        "model": { "type": "heston", "s0": 100.0, "v0": 0.04, "r": 0.02,
+                   "kappa": 1.5, "theta": 0.04, "xi": 0.5, "rho": -0.7 },
+        "payoff": { "EuropeanPut": { "k": 100.0 } },
+        "simulation": { "paths": 2000, "steps": 10 },
+        "greeks": ["delta", "rho"]
+    }"#;
+    let spec = RunSpec::parse(spec, SpecFormat::Json).expect("Valid spec");
+    assert!(matches!(
+        spec.run(),
+        Err(SdeError::UnsupportedOperation { .. })
+    ));
+
+    assert!(matches!(
+        RunSpec::parse("t = \"soon\"", SpecFormat::Toml),
+        Err(SdeError::InvalidConfiguration { .. })
+    ));
+}
@@ -0,0 +1,71 @@
+// tests/serde_test.rs
+use fast_sde::mc::chain::mc_price_chain;
+use fast_sde::mc::mc_engine::{mc_price_option_gbm, GreeksConfig, McConfig};
+use fast_sde::mc::payoff_stats::{mc_price_with_payoff_stats, PriceWithStats};
+use fast_sde::mc::payoffs::{BarrierShift, Payoff};
+use fast_sde::models::heston::HestonParams;
+
+#[test]
+fn test_config_from_partial_json_spec() {
+    let spec = r#"{
+        "paths": 20000,
+        "steps": 8,
+        "sigma": 0.25,
+        "payoff": { "BarrierCallUpAndOut": { "k": 100.0, "h": 130.0 } },
+        "greeks": "DELTA | VEGA",
+        "barrier_shift": "ContinuityCorrection"
+    }"#;
+    let cfg: McConfig = serde_json::from_str(spec).expect("Valid spec");
+    assert_eq!(cfg.paths, 20_000);
+    assert_eq!(cfg.greeks, GreeksConfig::DELTA | GreeksConfig::VEGA);
+    assert_eq!(cfg.barrier_shift, Some(BarrierShift::ContinuityCorrection));
+    // Omitted fields take their defaults
+    assert_eq!(cfg.seed, McConfig::default().seed);
+    assert!(cfg.normal_source.is_none());
+
+    // A serialized config prices identically after a round trip
+    let json = serde_json::to_string(&cfg).expect("Serializable");
+    let back: McConfig = serde_json::from_str(&json).expect("Round trip");
+    assert!(matches!(
+        back.payoff,
+        Payoff::BarrierCallUpAndOut { k, h } if k == 100.0 && h == 130.0
+    ));
+    let price = mc_price_option_gbm(&cfg).expect("Valid configuration");
+    assert_eq!(
+        mc_price_option_gbm(&back).expect("Valid configuration"),
+        price
+    );
+
+    assert!(serde_json::from_str::<McConfig>(r#"{"payoff": {"Straddle": {}}}"#).is_err());
+}
+
+#[test]
+fn test_params_and_results_round_trip() {
+    let params = HestonParams {
+        s0: 100.0,
+        v0: 0.04,
+        r: 0.02,
+        kappa: 1.5,
+        theta: 0.04,
+        xi: 0.5,
+        rho: -0.7,
+    };
+    let json = serde_json::to_string(&params).expect("Serializable");
+    let back: HestonParams = serde_json::from_str(&json).expect("Round trip");
+    assert_eq!((back.kappa, back.rho), (params.kappa, params.rho));
+
+    let cfg = McConfig {
+        paths: 10_000,
+        ..Default::default()
+    };
+    let stats = mc_price_with_payoff_stats(&cfg, &[0.05, 0.95]).expect("Valid configuration");
+    let json = serde_json::to_string(&stats).expect("Serializable");
+    let back: PriceWithStats = serde_json::from_str(&json).expect("Round trip");
+    assert_eq!(back.price, stats.price);
+    assert_eq!(back.summary.quantiles, stats.summary.quantiles);
+
+    let chain = mc_price_chain(&cfg, &[90.0, 110.0], &[0.5, 1.0]).expect("Valid chain");
+    let value = serde_json::to_value(&chain).expect("Serializable");
+    assert_eq!(value["strikes"], serde_json::json!([90.0, 110.0]));
+    assert!(value["prices"].is_object());
+}
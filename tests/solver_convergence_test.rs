@@ -1,9 +1,15 @@
 // tests/solver_convergence_test.rs
+use fast_sde::mc::mc_engine::{mc_price_option_gbm, McConfig, Scheme};
+use fast_sde::mc::payoffs::Payoff;
 use fast_sde::models::gbm::Gbm;
 use fast_sde::models::model::SDEModel;
 use fast_sde::models::ou_process::OuProcess;
 use fast_sde::rng;
-use fast_sde::solvers::{euler_maruyama::EulerMaruyama, milstein::Milstein, srk::Srk};
+use fast_sde::solvers::adaptive::{AdaptiveConfig, AdaptiveSolver};
+use fast_sde::solvers::{
+    euler_maruyama::EulerMaruyama, milstein::Milstein, ninomiya_victoir::NinomiyaVictoir, srk::Srk,
+    wagner_platen::WagnerPlaten,
+};
 use std::f64;
 
 // Exact solution for Ornstein-Uhlenbeck process (mean)
@@ -101,8 +107,8 @@ fn test_euler_maruyama_gbm_strong_convergence() {
             // Simulate numerical path using the provided normal draws (dW_n = Z_n * sqrt(dt))
             let mut s_numerical = s0;
             let mut t_current_numerical = 0.0;
-            for k in 0..*num_steps {
-                let dw = normal_draws[k] * dt.sqrt();
+            for &z in &normal_draws {
+                let dw = z * dt.sqrt();
                 gbm_process.step_with_dw(&mut s_numerical, t_current_numerical, dt, dw);
                 t_current_numerical += dt;
             }
@@ -238,3 +244,346 @@ fn test_srk_ou_convergence() {
         errors.last().unwrap()
     );
 }
+
+#[test]
+fn test_ninomiya_victoir_ou_convergence() {
+    let ou_process = OuProcess::new(0.5, 0.1, 0.2);
+    let s0 = 100.0;
+    let t_end = 1.0;
+    let num_paths = 100_000;
+
+    for num_steps in &[2, 5, 10] {
+        let dt = t_end / *num_steps as f64;
+        let mut sum_s_final = 0.0;
+
+        for i in 0..num_paths {
+            let mut rng = rng::seed_rng_from_u64(42 + i as u64);
+            let mut s_current = s0;
+            let mut t_current = 0.0;
+
+            for _ in 0..*num_steps {
+                NinomiyaVictoir::step(&ou_process, &mut s_current, t_current, dt, &mut rng);
+                t_current += dt;
+            }
+            sum_s_final += s_current;
+        }
+        let simulated_mean = sum_s_final / num_paths as f64;
+        let exact_s_t_mean = ou_exact_solution_mean(s0, ou_process.theta, ou_process.mu, t_end);
+        // Already accurate with a handful of steps, unlike Euler at 80
+        assert!(
+            (simulated_mean - exact_s_t_mean).abs() < 0.01,
+            "Ninomiya-Victoir mean error too high with {} steps",
+            num_steps
+        );
+    }
+}
+
+/// `dX = (1 - X) dt + 0.4 √(1 + X²) dW`: nonlinear diffusion, so no
+/// scheme is exact
+struct NonlinearDiffusion;
+
+impl SDEModel for NonlinearDiffusion {
+    fn drift(&self, s: f64, _t: f64) -> f64 {
+        1.0 - s
+    }
+
+    fn diffusion(&self, s: f64, _t: f64) -> f64 {
+        0.4 * (1.0 + s * s).sqrt()
+    }
+
+    fn diffusion_derivative(&self, s: f64, _t: f64) -> f64 {
+        0.4 * s / (1.0 + s * s).sqrt()
+    }
+
+    fn step_with_dw(&self, s_current: &mut f64, t_current: f64, dt: f64, dw: f64) {
+        EulerMaruyama::step_with_dw(self, s_current, t_current, dt, dw);
+    }
+}
+
+/// Weak errors of `E[X_T²]` against a 64-step Ninomiya–Victoir reference,
+/// all grids driven by the same Brownian paths
+fn weak_errors(
+    step: fn(&NonlinearDiffusion, &mut f64, f64, f64, f64),
+    grids: &[usize],
+) -> Vec<f64> {
+    const FINE: usize = 64;
+    let num_paths = 20_000;
+    let model = NonlinearDiffusion;
+    let mut sums = vec![0.0; grids.len()];
+    for i in 0..num_paths {
+        let mut rng = rng::seed_rng_from_u64(42 + i as u64);
+        let fine_dw: Vec<f64> = (0..FINE)
+            .map(|_| rng::get_normal_draw(&mut rng) / (FINE as f64).sqrt())
+            .collect();
+        let terminal = |steps: usize, step: fn(&NonlinearDiffusion, &mut f64, f64, f64, f64)| {
+            let dt = 1.0 / steps as f64;
+            let mut x = 0.5;
+            for (n, dws) in fine_dw.chunks(FINE / steps).enumerate() {
+                step(&model, &mut x, n as f64 * dt, dt, dws.iter().sum());
+            }
+            x * x
+        };
+        let reference = terminal(FINE, NinomiyaVictoir::step_with_dw);
+        for (sum, &steps) in sums.iter_mut().zip(grids) {
+            *sum += terminal(steps, step) - reference;
+        }
+    }
+    sums.iter().map(|s| (s / num_paths as f64).abs()).collect()
+}
+
+#[test]
+fn test_ninomiya_victoir_weak_order_two() {
+    let nv = weak_errors(NinomiyaVictoir::step_with_dw, &[2, 4, 8]);
+    let euler = weak_errors(EulerMaruyama::step_with_dw, &[2, 4, 8, 16]);
+
+    // Second order: halving the step cuts the error about fourfold
+    for i in 0..nv.len() - 1 {
+        assert!(
+            nv[i] > 2.5 * nv[i + 1],
+            "Ninomiya-Victoir errors {:?} not second order",
+            nv
+        );
+    }
+    // Four steps beat Euler with sixteen
+    assert!(
+        nv[1] < euler[3],
+        "Ninomiya-Victoir {:?} vs Euler {:?}",
+        nv,
+        euler
+    );
+}
+
+#[test]
+fn test_engine_schemes_converge_to_exact() {
+    // E[S_T²] = S₀² exp((2r + σ²)T); the exact scheme has no step bias
+    let base = McConfig {
+        paths: 50_000,
+        sigma: 0.5,
+        payoff: Payoff::PowerCall { k: 0.0, p: 2.0 },
+        ..Default::default()
+    };
+    let exact_moment = 100.0_f64.powi(2) * (2.0 * base.r + base.sigma * base.sigma).exp();
+    let (exact_price, _) = mc_price_option_gbm(&base).unwrap();
+    let discounted = (-base.r * base.t).exp() * exact_moment;
+    assert!((exact_price - discounted).abs() / discounted < 0.02);
+
+    for scheme in [
+        Scheme::EulerMaruyama,
+        Scheme::Milstein,
+        Scheme::Srk,
+        Scheme::NinomiyaVictoir,
+    ] {
+        // Common random numbers: the gap to the exact scheme is the bias
+        let errors: Vec<f64> = [1, 4, 16]
+            .iter()
+            .map(|&steps| {
+                let exact = McConfig {
+                    steps,
+                    ..base.clone()
+                };
+                let biased = McConfig {
+                    scheme,
+                    ..exact.clone()
+                };
+                let (price, _) = mc_price_option_gbm(&biased).unwrap();
+                let (reference, _) = mc_price_option_gbm(&exact).unwrap();
+                (price - reference).abs()
+            })
+            .collect();
+        assert!(
+            errors[0] > 2.0 * errors[1] && errors[1] > 2.0 * errors[2],
+            "{:?} bias {:?} should shrink with the step size",
+            scheme,
+            errors
+        );
+    }
+
+    // The control variate would cancel the bias, so biased schemes skip it
+    let euler = McConfig {
+        paths: 20_000,
+        scheme: Scheme::EulerMaruyama,
+        ..Default::default()
+    };
+    let plain = McConfig {
+        use_control_variate: false,
+        ..euler.clone()
+    };
+    let (with_cv, _) = mc_price_option_gbm(&euler).unwrap();
+    let (without_cv, _) = mc_price_option_gbm(&plain).unwrap();
+    assert!((with_cv - without_cv).abs() < 1e-9);
+}
+
+#[test]
+fn test_wagner_platen_gbm_strong_convergence() {
+    let s0 = 100.0;
+    let gbm_process = Gbm::new(s0, 0.05, 0.2);
+    let num_paths = 1_000;
+
+    let mut rms_errors = Vec::new();
+    for num_steps in [10, 20, 40, 80, 160] {
+        let dt = 1.0 / num_steps as f64;
+        let mut sum_sq_diff = 0.0;
+        for i in 0..num_paths {
+            let mut rng = rng::seed_rng_from_u64(42 + i as u64);
+            let mut normal_draws = Vec::with_capacity(num_steps);
+            let mut s_numerical = s0;
+            for n in 0..num_steps {
+                let u1 = rng::get_normal_draw(&mut rng);
+                let u2 = rng::get_normal_draw(&mut rng);
+                let (dw, dz) = WagnerPlaten::increments(dt, u1, u2);
+                WagnerPlaten::step_with_increments(
+                    &gbm_process,
+                    &mut s_numerical,
+                    n as f64 * dt,
+                    dt,
+                    dw,
+                    dz,
+                );
+                normal_draws.push(u1);
+            }
+            let exact_path = gbm_exact_solution_path(
+                s0,
+                gbm_process.mu,
+                gbm_process.sigma,
+                1.0,
+                dt,
+                &normal_draws,
+            );
+            sum_sq_diff += (s_numerical - exact_path.last().unwrap()).powi(2);
+        }
+        rms_errors.push((sum_sq_diff / num_paths as f64).sqrt());
+    }
+
+    println!(
+        "\nWagner-Platen GBM Strong Convergence RMSEs: {:?}",
+        rms_errors
+    );
+
+    // Strong order 1.5: halving dt divides the error by 2^{3/2} ≈ 2.83
+    for i in 0..(rms_errors.len() - 1) {
+        let ratio = rms_errors[i] / rms_errors[i + 1];
+        assert!(
+            ratio > 2.4 && ratio < 3.3,
+            "Strong convergence ratio not as expected at step {}: {}",
+            i,
+            ratio
+        );
+    }
+}
+
+#[test]
+fn test_wagner_platen_nonlinear_strong_order() {
+    // Finite-difference derivatives of NonlinearDiffusion, and a ΔZ that
+    // does not cancel, against a 256-step Wagner–Platen reference
+    const FINE: usize = 256;
+    let model = NonlinearDiffusion;
+    let grids = [4, 8, 16, 32];
+    let num_paths = 2_000;
+    let h = 1.0 / FINE as f64;
+
+    let mut wp_sq = vec![0.0; grids.len()];
+    let mut milstein_sq = vec![0.0; grids.len()];
+    for i in 0..num_paths {
+        let mut rng = rng::seed_rng_from_u64(42 + i as u64);
+        let fine: Vec<(f64, f64)> = (0..FINE)
+            .map(|_| {
+                let u1 = rng::get_normal_draw(&mut rng);
+                let u2 = rng::get_normal_draw(&mut rng);
+                WagnerPlaten::increments(h, u1, u2)
+            })
+            .collect();
+        let terminal = |steps: usize, milstein: bool| {
+            let dt = 1.0 / steps as f64;
+            let mut x = 0.5;
+            for (n, chunk) in fine.chunks(FINE / steps).enumerate() {
+                // ΔZ over the coarse step: ∫(W_s - W_t) ds piecewise
+                let (mut dw, mut dz) = (0.0, 0.0);
+                for &(dw_k, dz_k) in chunk {
+                    dz += dz_k + dw * h;
+                    dw += dw_k;
+                }
+                let t = n as f64 * dt;
+                if milstein {
+                    Milstein::step_with_dw(&model, &mut x, t, dt, dw);
+                } else {
+                    WagnerPlaten::step_with_increments(&model, &mut x, t, dt, dw, dz);
+                }
+            }
+            x
+        };
+        let reference = terminal(FINE, false);
+        for (k, &steps) in grids.iter().enumerate() {
+            wp_sq[k] += (terminal(steps, false) - reference).powi(2);
+            milstein_sq[k] += (terminal(steps, true) - reference).powi(2);
+        }
+    }
+    let rms =
+        |sq: Vec<f64>| -> Vec<f64> { sq.iter().map(|s| (s / num_paths as f64).sqrt()).collect() };
+    let (wp, milstein) = (rms(wp_sq), rms(milstein_sq));
+    println!(
+        "\nWagner-Platen nonlinear RMSEs: {:?}, Milstein: {:?}",
+        wp, milstein
+    );
+
+    for k in 0..grids.len() {
+        assert!(wp[k] < milstein[k], "Wagner-Platen should beat Milstein");
+    }
+    for k in 0..grids.len() - 1 {
+        let ratio = wp[k] / wp[k + 1];
+        assert!(ratio > 2.3, "Order 1.5 ratio too small at {}: {}", k, ratio);
+    }
+}
+
+#[test]
+fn test_adaptive_solver_tolerance_and_brownian_consistency() {
+    let gbm_process = Gbm::new(100.0, 0.05, 0.4);
+    let num_paths = 4_000;
+
+    let mut rms_errors = Vec::new();
+    for rtol in [1e-2, 1e-3, 1e-4] {
+        let cfg = AdaptiveConfig {
+            rtol,
+            dt_initial: 0.1,
+            ..Default::default()
+        };
+        let (mut sum_sq, mut sum_w, mut sum_w2) = (0.0, 0.0, 0.0);
+        let mut rejected = 0;
+        for i in 0..num_paths {
+            let mut rng = rng::seed_rng_from_u64(42 + i as u64);
+            let path = AdaptiveSolver::solve(&gbm_process, 100.0, 0.0, 1.0, &cfg, &mut rng)
+                .expect("Valid configuration");
+            assert_eq!(*path.times.last().unwrap(), 1.0);
+            let w = *path.brownian.last().unwrap();
+            let exact = 100.0 * ((0.05 - 0.5 * 0.16) + 0.4 * w).exp();
+            sum_sq += (path.terminal() - exact).powi(2);
+            sum_w += w;
+            sum_w2 += w * w;
+            rejected += path.rejected;
+        }
+        rms_errors.push((sum_sq / num_paths as f64).sqrt());
+
+        // Rejections split increments with the Brownian bridge instead of
+        // redrawing them, so W_1 stays N(0, 1)
+        let mean = sum_w / num_paths as f64;
+        let variance = sum_w2 / num_paths as f64 - mean * mean;
+        assert!(rejected > 0, "rtol = {} should reject some steps", rtol);
+        assert!(mean.abs() < 0.06, "E[W_1] = {}", mean);
+        assert!((variance - 1.0).abs() < 0.08, "Var[W_1] = {}", variance);
+    }
+
+    println!("\nAdaptive Milstein GBM RMSEs by rtol: {:?}", rms_errors);
+    for i in 0..(rms_errors.len() - 1) {
+        assert!(
+            rms_errors[i] > 2.0 * rms_errors[i + 1],
+            "Tighter tolerance should reduce the error: {:?}",
+            rms_errors
+        );
+    }
+
+    let mut rng = rng::seed_rng_from_u64(42);
+    let bad = AdaptiveConfig {
+        dt_initial: 1.0,
+        ..Default::default()
+    };
+    assert!(AdaptiveSolver::solve(&gbm_process, 100.0, 0.0, 1.0, &bad, &mut rng).is_err());
+}
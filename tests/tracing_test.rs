@@ -0,0 +1,128 @@
+// tests/tracing_test.rs
+use fast_sde::analytics::sabr_analytic::hagan_implied_vol;
+use fast_sde::calibration::quotes::VolQuote;
+use fast_sde::calibration::sabr::SabrCalibrator;
+use fast_sde::mc::mc_engine::{mc_price_option_gbm, McConfig};
+use fast_sde::models::heston::{Heston, HestonParams, HestonScheme};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::{span, Event, Level, Metadata, Subscriber};
+
+/// Subscriber recording span names and event messages
+#[derive(Default)]
+struct Recorder {
+    spans: Mutex<Vec<String>>,
+    events: Mutex<Vec<(Level, String)>>,
+    next_id: AtomicU64,
+}
+
+struct Message(String);
+
+impl Visit for Message {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        self.spans
+            .lock()
+            .unwrap()
+            .push(span.metadata().name().to_string());
+        span::Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut message = Message(String::new());
+        event.record(&mut message);
+        self.events
+            .lock()
+            .unwrap()
+            .push((*event.metadata().level(), message.0));
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+fn record<T>(run: impl FnOnce() -> T) -> (T, Arc<Recorder>) {
+    let recorder = Arc::new(Recorder::default());
+    let result = tracing::subscriber::with_default(recorder.clone(), run);
+    (result, recorder)
+}
+
+#[test]
+fn test_pricing_emits_stage_spans() {
+    let cfg = McConfig {
+        paths: 10_000,
+        ..Default::default()
+    };
+    let (price, recorder) = record(|| mc_price_option_gbm(&cfg));
+    assert!(price.is_ok());
+    let spans = recorder.spans.lock().unwrap();
+    assert_eq!(
+        *spans,
+        ["mc_price_option_gbm", "simulate", "control_variate"]
+    );
+    let events = recorder.events.lock().unwrap();
+    assert!(events.contains(&(Level::DEBUG, "priced".to_string())));
+}
+
+#[test]
+fn test_feller_violation_is_a_warn_event() {
+    let params = HestonParams {
+        s0: 100.0,
+        v0: 0.04,
+        r: 0.01,
+        kappa: 0.5,
+        theta: 0.04,
+        xi: 0.8,
+        rho: -0.7,
+    };
+    let (heston, recorder) =
+        record(|| Heston::new_with_scheme(params, HestonScheme::FullTruncationEuler));
+    assert!(heston.is_ok());
+    let events = recorder.events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0, Level::WARN);
+    assert!(events[0].1.contains("Feller"));
+}
+
+#[test]
+fn test_calibration_reports_iterations() {
+    let (forward, t) = (100.0, 1.0);
+    let quotes: Vec<VolQuote> = [80.0, 90.0, 100.0, 110.0, 120.0]
+        .iter()
+        .map(|&strike| VolQuote {
+            strike,
+            time_to_expiry: t,
+            implied_vol: hagan_implied_vol(forward, strike, t, 0.2, 1.0, -0.3, 0.6),
+        })
+        .collect();
+    let (smile, recorder) = record(|| SabrCalibrator::new(1.0).calibrate_smile(forward, &quotes));
+    assert!(smile.is_ok());
+    let spans = recorder.spans.lock().unwrap();
+    assert_eq!(*spans, ["calibrate_sabr_smile", "levenberg_marquardt"]);
+    let events = recorder.events.lock().unwrap();
+    let iterations = events
+        .iter()
+        .filter(|(level, message)| {
+            *level == Level::DEBUG && message == "levenberg_marquardt iteration"
+        })
+        .count();
+    assert!(iterations >= 1);
+}